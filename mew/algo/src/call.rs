@@ -0,0 +1,282 @@
+//! Running CALL statements.
+
+use std::collections::HashMap;
+
+use mew_core::{EdgeTypeId, NodeId, Value};
+use mew_graph::Graph;
+use mew_mutation::MutationExecutor;
+use mew_parser::{AttrAssignment, CallStmt, Expr, Literal, LiteralKind, SetStmt, Target};
+use mew_pattern::{Bindings, Evaluator};
+use mew_registry::{Procedure, Registry, NODE_COLUMN};
+
+use crate::error::{AlgoError, AlgoResult};
+use crate::procedure::run;
+use crate::view::GraphView;
+
+/// What a CALL computed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallOutput {
+    /// The YIELD columns, under their aliases.
+    pub columns: Vec<String>,
+    /// One row per node, in node ID order.
+    pub rows: Vec<Vec<Value>>,
+    /// The value of every node, for writing back.
+    pub results: Vec<(NodeId, Value)>,
+}
+
+/// Run the procedure of a CALL statement. Options are evaluated with
+/// `bindings`. The graph is not changed: see [`write_results`].
+pub fn call(
+    registry: &Registry,
+    graph: &Graph,
+    stmt: &CallStmt,
+    bindings: &Bindings,
+) -> AlgoResult<CallOutput> {
+    let procedure = Procedure::from_name(&stmt.procedure)
+        .ok_or_else(|| AlgoError::unknown_procedure(&stmt.procedure))?;
+
+    let edge_types = stmt
+        .edge_types
+        .iter()
+        .map(|name| {
+            let def = registry
+                .get_edge_type_by_name(name)
+                .ok_or_else(|| AlgoError::unknown_edge_type(name))?;
+            Ok((def.id, def.symmetric))
+        })
+        .collect::<AlgoResult<Vec<(EdgeTypeId, bool)>>>()?;
+
+    let evaluator = Evaluator::new(registry);
+    let mut options = HashMap::new();
+    for option in &stmt.options {
+        if !procedure
+            .options()
+            .iter()
+            .any(|(name, _)| *name == option.name)
+        {
+            return Err(AlgoError::invalid_option(
+                procedure.name(),
+                &option.name,
+                "no such option",
+            ));
+        }
+        let value = evaluator
+            .eval(&option.value, bindings, graph)
+            .map_err(|e| {
+                AlgoError::invalid_option(procedure.name(), &option.name, e.to_string())
+            })?;
+        options.insert(option.name.clone(), value);
+    }
+
+    let view = GraphView::new(graph, &edge_types);
+    let values = run(procedure, &view, &options)?;
+    let results: Vec<(NodeId, Value)> = (0..view.len()).map(|i| view.node(i)).zip(values).collect();
+
+    // The columns to return, as positions in (node, value)
+    let (value_column, _) = procedure.value_column();
+    let selected: Vec<(String, usize)> = if stmt.yields.is_empty() {
+        if stmt.write.is_some() {
+            Vec::new()
+        } else {
+            vec![(NODE_COLUMN.to_string(), 0), (value_column.to_string(), 1)]
+        }
+    } else {
+        stmt.yields
+            .iter()
+            .map(|item| {
+                let position = match item.column.as_str() {
+                    NODE_COLUMN => 0,
+                    column if column == value_column => 1,
+                    column => return Err(AlgoError::unknown_column(procedure.name(), column)),
+                };
+                Ok((
+                    item.alias.clone().unwrap_or_else(|| item.column.clone()),
+                    position,
+                ))
+            })
+            .collect::<AlgoResult<_>>()?
+    };
+
+    let rows = if selected.is_empty() {
+        Vec::new()
+    } else {
+        results
+            .iter()
+            .map(|(node, value)| {
+                let row = [Value::NodeRef(*node), value.clone()];
+                selected.iter().map(|(_, i)| row[*i].clone()).collect()
+            })
+            .collect()
+    };
+
+    Ok(CallOutput {
+        columns: selected.into_iter().map(|(name, _)| name).collect(),
+        rows,
+        results,
+    })
+}
+
+/// Store the results of a CALL as a node attribute, with the checks of
+/// SET. Returns the number of nodes written.
+pub fn write_results(
+    registry: &Registry,
+    graph: &mut Graph,
+    attr: &str,
+    results: &[(NodeId, Value)],
+) -> AlgoResult<usize> {
+    let bindings = Bindings::new();
+    let mut executor = MutationExecutor::new(registry, graph);
+    for (node, value) in results {
+        let kind = match value {
+            Value::Int(i) => LiteralKind::Int(*i),
+            Value::Float(f) => LiteralKind::Float(*f),
+            _ => LiteralKind::Null,
+        };
+        let stmt = SetStmt {
+            target: Target::Var(NODE_COLUMN.to_string()),
+            assignments: vec![AttrAssignment {
+                name: attr.to_string(),
+                value: Expr::Literal(Literal {
+                    kind,
+                    span: Default::default(),
+                }),
+                span: Default::default(),
+            }],
+            returning: None,
+            span: Default::default(),
+        };
+        executor.execute_set(&stmt, vec![*node], &bindings)?;
+    }
+    Ok(results.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mew_parser::{parse_stmt, Stmt};
+    use mew_registry::{AttrDef, RegistryBuilder};
+
+    fn test_registry() -> Registry {
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Person")
+            .attr(AttrDef::new("name", "String"))
+            .attr(AttrDef::new("rank", "Float"))
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("follows")
+            .param("a", "Person")
+            .param("b", "Person")
+            .done()
+            .unwrap();
+        builder.build().unwrap()
+    }
+
+    /// Ada and Bob follow Cy, who follows Ada.
+    fn test_graph(registry: &Registry) -> (Graph, Vec<NodeId>) {
+        let mut graph = Graph::new();
+        let mut executor = MutationExecutor::new(registry, &mut graph);
+        let people: Vec<NodeId> = ["Ada", "Bob", "Cy"]
+            .iter()
+            .map(|name| {
+                executor
+                    .create_node(
+                        "Person",
+                        vec![("name".into(), Value::String(name.to_string()))],
+                    )
+                    .unwrap()
+            })
+            .collect();
+        for (a, b) in [(0, 2), (1, 2), (2, 0)] {
+            executor
+                .create_edge(
+                    "follows",
+                    vec![people[a].into(), people[b].into()],
+                    vec![],
+                    false,
+                )
+                .unwrap();
+        }
+        (graph, people)
+    }
+
+    fn parse_call(input: &str) -> CallStmt {
+        match parse_stmt(input).unwrap() {
+            Stmt::Call(call) => call,
+            _ => panic!("Expected CALL statement"),
+        }
+    }
+
+    #[test]
+    fn test_call_yields_renamed_columns() {
+        // GIVEN
+        let registry = test_registry();
+        let (graph, people) = test_graph(&registry);
+        let stmt = parse_call("CALL algo.pagerank(follows) YIELD score AS rank, node");
+
+        // WHEN
+        let output = call(&registry, &graph, &stmt, &Bindings::new()).unwrap();
+
+        // THEN
+        assert_eq!(output.columns, vec!["rank", "node"]);
+        assert_eq!(output.rows.len(), 3);
+        assert_eq!(output.rows[2][1], Value::NodeRef(people[2]));
+        let rank = |row: &Vec<Value>| match row[0] {
+            Value::Float(f) => f,
+            _ => panic!("Expected a Float score"),
+        };
+        assert!(rank(&output.rows[2]) > rank(&output.rows[0]));
+        assert!(rank(&output.rows[0]) > rank(&output.rows[1]));
+    }
+
+    #[test]
+    fn test_call_rejects_unknown_names() {
+        let registry = test_registry();
+        let (graph, _) = test_graph(&registry);
+        let run = |input: &str| call(&registry, &graph, &parse_call(input), &Bindings::new());
+
+        assert!(matches!(
+            run("CALL algo.nope(follows)"),
+            Err(AlgoError::UnknownProcedure { .. })
+        ));
+        assert!(matches!(
+            run("CALL algo.pagerank(likes)"),
+            Err(AlgoError::UnknownEdgeType { .. })
+        ));
+        assert!(matches!(
+            run("CALL algo.wcc(follows, damping = 0.5)"),
+            Err(AlgoError::InvalidOption { .. })
+        ));
+        assert!(matches!(
+            run("CALL algo.wcc(follows) YIELD score"),
+            Err(AlgoError::UnknownColumn { .. })
+        ));
+    }
+
+    #[test]
+    fn test_write_results_sets_attributes() {
+        // GIVEN
+        let registry = test_registry();
+        let (mut graph, people) = test_graph(&registry);
+        let output = call(
+            &registry,
+            &graph,
+            &parse_call("CALL algo.pagerank(follows) WRITE rank"),
+            &Bindings::new(),
+        )
+        .unwrap();
+        assert!(output.rows.is_empty());
+
+        // WHEN
+        let written = write_results(&registry, &mut graph, "rank", &output.results).unwrap();
+
+        // THEN
+        assert_eq!(written, 3);
+        let rank = graph.get_node(people[2]).unwrap().get_attr("rank");
+        assert_eq!(rank, Some(&output.results[2].1));
+
+        // AND attributes the type does not have are rejected
+        assert!(write_results(&registry, &mut graph, "score", &output.results).is_err());
+    }
+}
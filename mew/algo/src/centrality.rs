@@ -0,0 +1,113 @@
+//! Degree and betweenness centrality.
+
+use std::collections::VecDeque;
+
+use crate::view::GraphView;
+
+/// Which arcs a degree counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Arcs leaving the node
+    Out,
+    /// Arcs entering the node
+    In,
+    /// Neighbors either way, each counted once
+    Both,
+}
+
+impl Direction {
+    /// The direction named `out`, `in` or `both`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "out" | "outgoing" => Some(Self::Out),
+            "in" | "incoming" => Some(Self::In),
+            "both" | "any" => Some(Self::Both),
+            _ => None,
+        }
+    }
+}
+
+/// The degree of every node.
+pub fn degree_centrality(view: &GraphView, direction: Direction) -> Vec<usize> {
+    (0..view.len())
+        .map(|i| match direction {
+            Direction::Out => view.out(i).len(),
+            Direction::In => view.inc(i).len(),
+            Direction::Both => view.neighbors(i).len(),
+        })
+        .collect()
+}
+
+/// The betweenness of every node: over all pairs of other nodes, the share
+/// of shortest directed paths between them that pass through it
+/// (Brandes' algorithm, unnormalized).
+pub fn betweenness_centrality(view: &GraphView) -> Vec<f64> {
+    let n = view.len();
+    let mut centrality = vec![0.0; n];
+
+    for source in 0..n {
+        let mut visited = Vec::with_capacity(n);
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0; n];
+        let mut distance = vec![usize::MAX; n];
+        paths[source] = 1.0;
+        distance[source] = 0;
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(i) = queue.pop_front() {
+            visited.push(i);
+            for &j in view.out(i) {
+                if distance[j] == usize::MAX {
+                    distance[j] = distance[i] + 1;
+                    queue.push_back(j);
+                }
+                if distance[j] == distance[i] + 1 {
+                    paths[j] += paths[i];
+                    predecessors[j].push(i);
+                }
+            }
+        }
+
+        let mut dependency = vec![0.0; n];
+        for &j in visited.iter().rev() {
+            for &i in &predecessors[j] {
+                dependency[i] += paths[i] / paths[j] * (1.0 + dependency[j]);
+            }
+            if j != source {
+                centrality[j] += dependency[j];
+            }
+        }
+    }
+    centrality
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::tests::view;
+
+    #[test]
+    fn test_degree_centrality() {
+        // GIVEN arcs 1->2, 2->1 and 1->3
+        let view = view(3, &[(1, 2), (2, 1), (1, 3)]);
+
+        // THEN
+        assert_eq!(degree_centrality(&view, Direction::Out), vec![2, 1, 0]);
+        assert_eq!(degree_centrality(&view, Direction::In), vec![1, 1, 1]);
+        assert_eq!(degree_centrality(&view, Direction::Both), vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn test_betweenness_splits_between_shortest_paths() {
+        // GIVEN two shortest paths from 1 to 4, through 2 and through 3,
+        // and 4 -> 5
+        let view = view(5, &[(1, 2), (1, 3), (2, 4), (3, 4), (4, 5)]);
+
+        // WHEN
+        let centrality = betweenness_centrality(&view);
+
+        // THEN 2 and 3 each carry half of the paths from 1 to 4 and to 5,
+        // and 4 carries every path to 5 but its own
+        assert_eq!(centrality, vec![0.0, 1.0, 1.0, 3.0, 0.0]);
+    }
+}
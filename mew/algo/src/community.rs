@@ -0,0 +1,63 @@
+//! Community detection by label propagation.
+
+use std::collections::BTreeMap;
+
+use crate::view::GraphView;
+
+/// Rounds of label propagation run at most.
+pub const DEFAULT_PROPAGATION_ITERATIONS: usize = 10;
+
+/// The community of every node, labeled by the index of its first member.
+///
+/// Every node starts in a community of its own. Each round, all nodes at
+/// once adopt the label most common among themselves and their neighbors,
+/// ignoring direction, with ties going to the lowest label. Rounds stop
+/// when no label changes, so the result is deterministic.
+pub fn label_propagation(view: &GraphView, iterations: usize) -> Vec<usize> {
+    let n = view.len();
+    let neighbors: Vec<Vec<usize>> = (0..n).map(|i| view.neighbors(i)).collect();
+    let mut labels: Vec<usize> = (0..n).collect();
+
+    for _ in 0..iterations {
+        let next: Vec<usize> = (0..n)
+            .map(|i| {
+                let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+                for &j in neighbors[i].iter().chain([&i]) {
+                    *counts.entry(labels[j]).or_default() += 1;
+                }
+                let best = *counts.values().max().unwrap();
+                // The first maximum in label order
+                counts.into_iter().find(|&(_, c)| c == best).unwrap().0
+            })
+            .collect();
+        if next == labels {
+            break;
+        }
+        labels = next;
+    }
+
+    // Relabel each community by its first member
+    let mut first: BTreeMap<usize, usize> = BTreeMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        first.entry(*label).or_insert(i);
+    }
+    labels.iter().map(|label| first[label]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::tests::view;
+
+    #[test]
+    fn test_label_propagation_finds_cliques() {
+        // GIVEN two triangles joined by the arc 3 -> 4
+        let view = view(6, &[(1, 2), (2, 3), (3, 1), (4, 5), (5, 6), (6, 4), (3, 4)]);
+
+        // WHEN
+        let communities = label_propagation(&view, DEFAULT_PROPAGATION_ITERATIONS);
+
+        // THEN
+        assert_eq!(communities, vec![0, 0, 0, 3, 3, 3]);
+    }
+}
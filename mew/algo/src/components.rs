@@ -0,0 +1,113 @@
+//! Connected and strongly connected components.
+//!
+//! A component is labeled by the index of its first node, which is also
+//! its node with the lowest ID.
+
+use crate::view::GraphView;
+
+/// The weakly connected component of every node, ignoring direction.
+pub fn connected_components(view: &GraphView) -> Vec<usize> {
+    let n = view.len();
+    let mut labels = vec![usize::MAX; n];
+    for start in 0..n {
+        if labels[start] != usize::MAX {
+            continue;
+        }
+        labels[start] = start;
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            for &j in view.out(i).iter().chain(view.inc(i)) {
+                if labels[j] == usize::MAX {
+                    labels[j] = start;
+                    stack.push(j);
+                }
+            }
+        }
+    }
+    labels
+}
+
+/// The strongly connected component of every node (Tarjan's algorithm,
+/// without recursion).
+pub fn strongly_connected_components(view: &GraphView) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+    let n = view.len();
+    let mut order = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut labels = vec![UNVISITED; n];
+    let mut counter = 0;
+
+    for root in 0..n {
+        if order[root] != UNVISITED {
+            continue;
+        }
+        // Each frame is a node and the position of its next successor
+        let mut frames = vec![(root, 0)];
+        order[root] = counter;
+        low[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&mut (i, ref mut next)) = frames.last_mut() {
+            if let Some(&j) = view.out(i).get(*next) {
+                *next += 1;
+                if order[j] == UNVISITED {
+                    order[j] = counter;
+                    low[j] = counter;
+                    counter += 1;
+                    stack.push(j);
+                    on_stack[j] = true;
+                    frames.push((j, 0));
+                } else if on_stack[j] {
+                    low[i] = low[i].min(order[j]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[i]);
+            }
+            if low[i] == order[i] {
+                let mut members = Vec::new();
+                while let Some(j) = stack.pop() {
+                    on_stack[j] = false;
+                    members.push(j);
+                    if j == i {
+                        break;
+                    }
+                }
+                let label = *members.iter().min().unwrap();
+                for j in members {
+                    labels[j] = label;
+                }
+            }
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::tests::view;
+
+    #[test]
+    fn test_components() {
+        // GIVEN a cycle 1-2-3, an arc 3->4, and a separate arc 6->5
+        let view = view(6, &[(1, 2), (2, 3), (3, 1), (3, 4), (6, 5)]);
+
+        // WHEN
+        let weak = connected_components(&view);
+        let strong = strongly_connected_components(&view);
+
+        // THEN direction is ignored for connected components
+        assert_eq!(weak, vec![0, 0, 0, 0, 4, 4]);
+
+        // AND only the cycle is strongly connected
+        assert_eq!(strong, vec![0, 0, 0, 3, 4, 5]);
+    }
+}
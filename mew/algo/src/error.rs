@@ -0,0 +1,67 @@
+//! Algorithm error types.
+
+use mew_mutation::MutationError;
+use thiserror::Error;
+
+/// Errors of a CALL.
+#[derive(Debug, Error)]
+pub enum AlgoError {
+    /// No procedure has the name.
+    #[error("unknown procedure '{name}'")]
+    UnknownProcedure { name: String },
+
+    /// An argument names no edge type.
+    #[error("unknown edge type '{name}'")]
+    UnknownEdgeType { name: String },
+
+    /// An option is not one of the procedure's, or its value is invalid.
+    #[error("invalid option '{option}' of {procedure}: {message}")]
+    InvalidOption {
+        procedure: &'static str,
+        option: String,
+        message: String,
+    },
+
+    /// YIELD names a column the procedure does not return.
+    #[error("{procedure} yields no column '{column}'")]
+    UnknownColumn {
+        procedure: &'static str,
+        column: String,
+    },
+
+    /// Writing the results back failed.
+    #[error("cannot write results: {0}")]
+    Write(#[from] MutationError),
+}
+
+impl AlgoError {
+    pub fn unknown_procedure(name: impl Into<String>) -> Self {
+        Self::UnknownProcedure { name: name.into() }
+    }
+
+    pub fn unknown_edge_type(name: impl Into<String>) -> Self {
+        Self::UnknownEdgeType { name: name.into() }
+    }
+
+    pub fn invalid_option(
+        procedure: &'static str,
+        option: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::InvalidOption {
+            procedure,
+            option: option.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn unknown_column(procedure: &'static str, column: impl Into<String>) -> Self {
+        Self::UnknownColumn {
+            procedure,
+            column: column.into(),
+        }
+    }
+}
+
+/// Result type for algorithm operations.
+pub type AlgoResult<T> = Result<T, AlgoError>;
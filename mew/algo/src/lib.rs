@@ -0,0 +1,33 @@
+//! MEW Algo
+//!
+//! Graph algorithms callable from statements:
+//! `CALL algo.pagerank(follows) YIELD node, score`.
+//!
+//! Responsibilities:
+//! - Project the edges of chosen types into a plain directed graph
+//! - PageRank, connected and strongly connected components, degree and
+//!   betweenness centrality, triangle counting and label propagation
+//! - Run the procedures whose signatures the registry declares, check
+//!   their options and select their YIELD columns
+//! - Write results back as node attributes, with the checks of SET
+
+mod call;
+mod centrality;
+mod community;
+mod components;
+mod error;
+mod pagerank;
+mod procedure;
+mod triangles;
+mod view;
+
+pub use call::{call, write_results, CallOutput};
+pub use centrality::{betweenness_centrality, degree_centrality, Direction};
+pub use community::{label_propagation, DEFAULT_PROPAGATION_ITERATIONS};
+pub use components::{connected_components, strongly_connected_components};
+pub use error::{AlgoError, AlgoResult};
+pub use mew_registry::{Procedure, NODE_COLUMN};
+pub use pagerank::{pagerank, DEFAULT_DAMPING, DEFAULT_PAGERANK_ITERATIONS};
+pub use procedure::run;
+pub use triangles::triangle_count;
+pub use view::GraphView;
@@ -0,0 +1,152 @@
+//! The algorithms behind the procedures CALL can run.
+
+use std::collections::HashMap;
+
+use mew_core::Value;
+use mew_registry::Procedure;
+
+use crate::centrality::{betweenness_centrality, degree_centrality, Direction};
+use crate::community::{label_propagation, DEFAULT_PROPAGATION_ITERATIONS};
+use crate::components::{connected_components, strongly_connected_components};
+use crate::error::{AlgoError, AlgoResult};
+use crate::pagerank::{pagerank, DEFAULT_DAMPING, DEFAULT_PAGERANK_ITERATIONS};
+use crate::triangles::triangle_count;
+use crate::view::GraphView;
+
+/// Run a procedure, returning the value of every node of `view` by index.
+/// Components and communities are identified by the ID of their member
+/// with the lowest ID.
+pub fn run(
+    procedure: Procedure,
+    view: &GraphView,
+    options: &HashMap<String, Value>,
+) -> AlgoResult<Vec<Value>> {
+    let options = Options {
+        procedure,
+        values: options,
+    };
+    let index_ids = |labels: Vec<usize>| {
+        labels
+            .into_iter()
+            .map(|i| Value::Int(view.node(i).raw() as i64))
+            .collect()
+    };
+    let ints = |counts: Vec<usize>| counts.into_iter().map(|c| Value::Int(c as i64)).collect();
+    let floats = |scores: Vec<f64>| scores.into_iter().map(Value::Float).collect();
+
+    Ok(match procedure {
+        Procedure::PageRank => {
+            let damping = options.float("damping", DEFAULT_DAMPING)?;
+            if !(0.0..1.0).contains(&damping) {
+                return Err(options.invalid("damping", "must be at least 0 and below 1"));
+            }
+            let iterations = options.count("iterations", DEFAULT_PAGERANK_ITERATIONS)?;
+            floats(pagerank(view, damping, iterations))
+        }
+        Procedure::ConnectedComponents => index_ids(connected_components(view)),
+        Procedure::StronglyConnectedComponents => index_ids(strongly_connected_components(view)),
+        Procedure::Degree => {
+            let direction = match options.values.get("direction") {
+                None => Direction::Both,
+                Some(Value::String(name)) => Direction::from_name(name).ok_or_else(|| {
+                    options.invalid("direction", "expected \"out\", \"in\" or \"both\"")
+                })?,
+                Some(other) => {
+                    return Err(options.invalid(
+                        "direction",
+                        format!("expected a String, got {}", other.type_name()),
+                    ))
+                }
+            };
+            ints(degree_centrality(view, direction))
+        }
+        Procedure::Betweenness => floats(betweenness_centrality(view)),
+        Procedure::TriangleCount => ints(triangle_count(view)),
+        Procedure::LabelPropagation => {
+            let iterations = options.count("iterations", DEFAULT_PROPAGATION_ITERATIONS)?;
+            index_ids(label_propagation(view, iterations))
+        }
+    })
+}
+
+/// The option values of one run.
+struct Options<'a> {
+    procedure: Procedure,
+    values: &'a HashMap<String, Value>,
+}
+
+impl Options<'_> {
+    fn invalid(&self, option: &str, message: impl Into<String>) -> AlgoError {
+        AlgoError::invalid_option(self.procedure.name(), option, message)
+    }
+
+    fn float(&self, name: &str, default: f64) -> AlgoResult<f64> {
+        match self.values.get(name) {
+            None => Ok(default),
+            Some(Value::Float(f)) => Ok(*f),
+            Some(Value::Int(i)) => Ok(*i as f64),
+            Some(other) => {
+                Err(self.invalid(name, format!("expected a Float, got {}", other.type_name())))
+            }
+        }
+    }
+
+    fn count(&self, name: &str, default: usize) -> AlgoResult<usize> {
+        match self.values.get(name) {
+            None => Ok(default),
+            Some(Value::Int(i)) if *i > 0 => Ok(*i as usize),
+            Some(other) => {
+                Err(self.invalid(name, format!("expected a positive Int, got {}", other)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::tests::view;
+
+    #[test]
+    fn test_run_labels_components_by_node_id() {
+        // GIVEN
+        let view = view(3, &[(2, 3)]);
+
+        // WHEN
+        let values = run(Procedure::ConnectedComponents, &view, &HashMap::new()).unwrap();
+
+        // THEN
+        assert_eq!(values, vec![Value::Int(1), Value::Int(2), Value::Int(2)]);
+    }
+
+    #[test]
+    fn test_run_checks_options() {
+        // GIVEN
+        let view = view(2, &[(1, 2)]);
+        let options = |name: &str, value| HashMap::from([(name.to_string(), value)]);
+
+        // THEN
+        let err = run(
+            Procedure::PageRank,
+            &view,
+            &options("damping", Value::Float(1.5)),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("must be at least 0"));
+        assert!(run(
+            Procedure::Degree,
+            &view,
+            &options("direction", Value::String("up".into()))
+        )
+        .is_err());
+        assert_eq!(
+            run(
+                Procedure::Degree,
+                &view,
+                &options("direction", Value::String("out".into()))
+            )
+            .unwrap(),
+            vec![Value::Int(1), Value::Int(0)]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Triangle counting.
+
+use crate::view::GraphView;
+
+/// The number of triangles every node is part of, ignoring direction.
+pub fn triangle_count(view: &GraphView) -> Vec<usize> {
+    let neighbors: Vec<Vec<usize>> = (0..view.len()).map(|i| view.neighbors(i)).collect();
+    let mut counts = vec![0; view.len()];
+
+    // Each triangle u < v < w is found once, from u
+    for (u, adjacent) in neighbors.iter().enumerate() {
+        for &v in adjacent.iter().filter(|&&v| v > u) {
+            for &w in neighbors[v].iter().filter(|&&w| w > v) {
+                if adjacent.binary_search(&w).is_ok() {
+                    counts[u] += 1;
+                    counts[v] += 1;
+                    counts[w] += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::tests::view;
+
+    #[test]
+    fn test_triangle_count() {
+        // GIVEN triangles 1-2-3 and 2-3-4 (in any direction) and a tail 4-5
+        let view = view(5, &[(1, 2), (3, 2), (1, 3), (2, 4), (4, 3), (4, 5)]);
+
+        // THEN
+        assert_eq!(triangle_count(&view), vec![1, 2, 2, 1, 0]);
+    }
+}
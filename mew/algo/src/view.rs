@@ -0,0 +1,187 @@
+//! The graph an algorithm runs over.
+//!
+//! Algorithms work on a plain directed graph: the nodes are the endpoints of
+//! the selected edges, numbered densely in ID order, and every edge becomes
+//! an arc from its first target to each of its other node targets. Parallel
+//! arcs and self loops are dropped, as are targets that are edges. An edge
+//! of a symmetric type becomes an arc each way.
+
+use std::collections::{BTreeSet, HashMap};
+
+use mew_core::{EdgeId, EdgeTypeId, EntityId, NodeId};
+use mew_graph::Graph;
+
+/// A directed simple graph over dense node indexes.
+#[derive(Debug, Clone, Default)]
+pub struct GraphView {
+    nodes: Vec<NodeId>,
+    index: HashMap<NodeId, usize>,
+    out: Vec<Vec<usize>>,
+    inc: Vec<Vec<usize>>,
+}
+
+impl GraphView {
+    /// The view of the edges of the given types, each with whether the type
+    /// is symmetric. No types selects every edge, none of them symmetric.
+    pub fn new(graph: &Graph, edge_types: &[(EdgeTypeId, bool)]) -> Self {
+        let mut arcs = BTreeSet::new();
+        let mut add_edge = |id: EdgeId, symmetric: bool| {
+            let Some(edge) = graph.get_edge(id) else {
+                return;
+            };
+            let mut targets = edge.targets.iter().filter_map(|t| match t {
+                EntityId::Node(id) => Some(*id),
+                EntityId::Edge(_) => None,
+            });
+            let Some(source) = targets.next() else {
+                return;
+            };
+            for target in targets.filter(|t| *t != source) {
+                arcs.insert((source, target));
+                if symmetric {
+                    arcs.insert((target, source));
+                }
+            }
+        };
+        if edge_types.is_empty() {
+            for id in graph.all_edge_ids() {
+                add_edge(id, false);
+            }
+        }
+        for &(type_id, symmetric) in edge_types {
+            for id in graph.edges_by_type(type_id) {
+                add_edge(id, symmetric);
+            }
+        }
+
+        let nodes: Vec<NodeId> = arcs
+            .iter()
+            .flat_map(|&(a, b)| [a, b])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        Self::from_arcs(nodes, arcs)
+    }
+
+    /// A view of the given nodes and arcs, which must be between them.
+    fn from_arcs(nodes: Vec<NodeId>, arcs: BTreeSet<(NodeId, NodeId)>) -> Self {
+        let index: HashMap<NodeId, usize> =
+            nodes.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+        let mut out = vec![Vec::new(); nodes.len()];
+        let mut inc = vec![Vec::new(); nodes.len()];
+        for (a, b) in arcs {
+            let (a, b) = (index[&a], index[&b]);
+            out[a].push(b);
+            inc[b].push(a);
+        }
+        Self {
+            nodes,
+            index,
+            out,
+            inc,
+        }
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the view has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The node at an index.
+    pub fn node(&self, index: usize) -> NodeId {
+        self.nodes[index]
+    }
+
+    /// The index of a node, if it is in the view.
+    pub fn index_of(&self, node: NodeId) -> Option<usize> {
+        self.index.get(&node).copied()
+    }
+
+    /// Successors of a node, in index order.
+    pub fn out(&self, index: usize) -> &[usize] {
+        &self.out[index]
+    }
+
+    /// Predecessors of a node, in index order.
+    pub fn inc(&self, index: usize) -> &[usize] {
+        &self.inc[index]
+    }
+
+    /// Neighbors of a node ignoring direction, in index order, without
+    /// duplicates.
+    pub fn neighbors(&self, index: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> = self.out[index]
+            .iter()
+            .chain(&self.inc[index])
+            .copied()
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A view with nodes 1..=n and the given arcs.
+    pub(crate) fn view(n: u64, arcs: &[(u64, u64)]) -> GraphView {
+        GraphView::from_arcs(
+            (1..=n).map(NodeId::new).collect(),
+            arcs.iter()
+                .map(|&(a, b)| (NodeId::new(a), NodeId::new(b)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_view_of_typed_edges() {
+        // GIVEN a knows edge each way, a duplicate, a ternary edge, a self
+        // loop and an edge of another type
+        let mut graph = Graph::new();
+        let knows = EdgeTypeId::new(1);
+        let meets = EdgeTypeId::new(2);
+        let n: Vec<NodeId> = (0..4)
+            .map(|_| graph.create_node(mew_core::TypeId::new(1), Default::default()))
+            .collect();
+        let mut link = |type_id, targets: &[NodeId]| {
+            graph
+                .create_edge(
+                    type_id,
+                    targets.iter().map(|t| (*t).into()).collect(),
+                    Default::default(),
+                )
+                .unwrap();
+        };
+        link(knows, &[n[0], n[1]]);
+        link(knows, &[n[0], n[1]]);
+        link(knows, &[n[1], n[2], n[0]]);
+        link(knows, &[n[2], n[2]]);
+        link(meets, &[n[2], n[3]]);
+
+        // WHEN
+        let view = GraphView::new(&graph, &[(knows, false)]);
+
+        // THEN the nodes are the endpoints, and arcs are neither repeated
+        // nor loops
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.index_of(n[3]), None);
+        assert_eq!(view.out(0), &[1]);
+        assert_eq!(view.out(1), &[0, 2]);
+        assert_eq!(view.out(2), &[] as &[usize]);
+        assert_eq!(view.neighbors(0), vec![1]);
+
+        // WHEN the type is symmetric
+        let view = GraphView::new(&graph, &[(meets, true)]);
+
+        // THEN the arc goes both ways
+        assert_eq!(view.out(0), &[1]);
+        assert_eq!(view.inc(0), &[1]);
+    }
+}
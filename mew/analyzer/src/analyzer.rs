@@ -2,10 +2,11 @@
 
 use crate::{AnalyzerError, AnalyzerResult, BinaryOpType, Scope, Type, UnaryOpType, VarBinding};
 use mew_parser::{
-    AttrAssignment, EdgePattern, Expr, FnCall, Literal, LiteralKind, MatchStmt, NodePattern,
-    PatternElem, Projection, Span, Stmt,
+    AttrAssignment, ConflictAction, EdgePattern, Expr, FnCall, ImportColumn, Literal, LiteralKind,
+    MatchStmt, NodePattern, PatternElem, Projection, Span, Stmt, Subquery, SubqueryKind, UnionStmt,
+    WithStage,
 };
-use mew_registry::Registry;
+use mew_registry::{Procedure, Registry, NODE_COLUMN};
 
 /// The Analyzer performs name resolution and type checking.
 pub struct Analyzer<'r> {
@@ -15,6 +16,8 @@ pub struct Analyzer<'r> {
     scope: Scope,
     /// Accumulated errors (for error recovery).
     errors: Vec<AnalyzerError>,
+    /// Types of the RETURN columns of the last analyzed MATCH.
+    columns: Vec<Type>,
 }
 
 impl<'r> Analyzer<'r> {
@@ -24,13 +27,21 @@ impl<'r> Analyzer<'r> {
             registry,
             scope: Scope::new(),
             errors: Vec::new(),
+            columns: Vec::new(),
         }
     }
 
+    /// Bind a variable for every statement analyzed after, as the REPL
+    /// does for the nodes and edges earlier statements bound.
+    pub fn bind(&mut self, name: impl Into<String>, ty: Type) {
+        self.scope.define(VarBinding::new(name, ty));
+    }
+
     /// Analyze a statement.
     pub fn analyze_stmt(&mut self, stmt: &Stmt) -> AnalyzerResult<Type> {
         match stmt {
             Stmt::Match(m) => self.analyze_match(m),
+            Stmt::Union(u) => self.analyze_union(u),
             Stmt::MatchMutate(mm) => self.analyze_match_mutate(mm),
             Stmt::MatchWalk(mw) => self.analyze_match_walk(mw),
             Stmt::Spawn(s) => self.analyze_spawn(s),
@@ -51,7 +62,194 @@ impl<'r> Analyzer<'r> {
                 self.analyze_stmt(&p.statement)?;
                 Ok(Type::Any) // Returns execution metrics
             }
+            Stmt::Watch(w) => self.analyze_match(&w.to_match()),
+            Stmt::Ack(a) => self.analyze_string_arg(&a.delivery_id),
+            Stmt::Nack(n) => self.analyze_string_arg(&n.delivery_id),
+            Stmt::Import(i) => self.analyze_import(i),
+            Stmt::Dump(d) => self.analyze_string_arg(&d.path),
+            Stmt::Call(c) => self.analyze_call(c),
+            Stmt::Unwind(u) => self.analyze_unwind(u),
+        }
+    }
+
+    /// Analyze a statement argument that must be a string: the delivery ID
+    /// of ACK/NACK, the path of DUMP.
+    fn analyze_string_arg(&mut self, expr: &Expr) -> AnalyzerResult<Type> {
+        let id_type = self.analyze_expr(expr)?;
+        if id_type != Type::String && id_type != Type::Any {
+            return Err(AnalyzerError::type_mismatch(
+                &Type::String,
+                &id_type,
+                expr.span(),
+            ));
         }
+        Ok(Type::Null)
+    }
+
+    /// Analyze a CALL statement: the procedure, its edge types and option
+    /// types, the YIELD columns and the attribute results are written to.
+    /// The YIELD columns become the result columns.
+    fn analyze_call(&mut self, stmt: &mew_parser::CallStmt) -> AnalyzerResult<Type> {
+        let procedure = Procedure::from_name(&stmt.procedure)
+            .ok_or_else(|| AnalyzerError::unknown_procedure(&stmt.procedure, stmt.span))?;
+        let (value_column, value_type) = procedure.value_column();
+        let value_type = self.type_name_to_type(value_type);
+
+        let mut edge_types = Vec::new();
+        for name in &stmt.edge_types {
+            let edge_type = self
+                .registry
+                .get_edge_type_by_name(name)
+                .ok_or_else(|| AnalyzerError::unknown_edge_type(name, stmt.span))?;
+            edge_types.push(edge_type);
+        }
+
+        for option in &stmt.options {
+            let (_, expected) = procedure
+                .options()
+                .iter()
+                .find(|(name, _)| *name == option.name)
+                .ok_or_else(|| {
+                    AnalyzerError::unknown_procedure_name(
+                        procedure.name(),
+                        "option",
+                        &option.name,
+                        option.span,
+                    )
+                })?;
+            let expected = self.type_name_to_type(expected);
+            let actual = self.analyze_expr(&option.value)?;
+            let compatible = actual == expected
+                || actual == Type::Any
+                || (expected == Type::Float && actual == Type::Int);
+            if !compatible {
+                return Err(AnalyzerError::type_mismatch(
+                    &expected,
+                    &actual,
+                    option.value.span(),
+                ));
+            }
+        }
+
+        self.columns.clear();
+        if stmt.yields.is_empty() && stmt.write.is_none() {
+            self.columns = vec![Type::AnyNodeRef, value_type.clone()];
+        }
+        for item in &stmt.yields {
+            let column_type = match item.column.as_str() {
+                NODE_COLUMN => Type::AnyNodeRef,
+                column if column == value_column => value_type.clone(),
+                column => {
+                    return Err(AnalyzerError::unknown_procedure_name(
+                        procedure.name(),
+                        "column",
+                        column,
+                        item.span,
+                    ))
+                }
+            };
+            self.columns.push(column_type);
+        }
+
+        // The attribute must take the value on every node type the edges join
+        if let Some(attr) = &stmt.write {
+            for param in edge_types.iter().flat_map(|e| &e.params) {
+                let Some(type_id) = self.registry.get_type_id(&param.type_constraint) else {
+                    continue;
+                };
+                let attr_def = self.registry.get_type_attr(type_id, attr).ok_or_else(|| {
+                    AnalyzerError::unknown_attribute(attr, &param.type_constraint, stmt.span)
+                })?;
+                let attr_type = self.type_name_to_type(&attr_def.type_name);
+                let compatible = attr_type == value_type
+                    || attr_type == Type::Any
+                    || (attr_type == Type::Float && value_type == Type::Int);
+                if !compatible {
+                    return Err(AnalyzerError::type_mismatch(
+                        &attr_type,
+                        &value_type,
+                        stmt.span,
+                    ));
+                }
+            }
+        }
+        Ok(Type::Null)
+    }
+
+    /// Analyze an IMPORT statement: the target, the attributes and endpoint
+    /// keys its columns name, and the source and batch expressions.
+    fn analyze_import(&mut self, stmt: &mew_parser::ImportStmt) -> AnalyzerResult<Type> {
+        let source_type = self.analyze_expr(&stmt.source)?;
+        if source_type != Type::String && source_type != Type::Any {
+            return Err(AnalyzerError::type_mismatch(
+                &Type::String,
+                &source_type,
+                stmt.source.span(),
+            ));
+        }
+        if let Some(batch) = &stmt.options.batch {
+            let batch_type = self.analyze_expr(batch)?;
+            if batch_type != Type::Int && batch_type != Type::Any {
+                return Err(AnalyzerError::type_mismatch(
+                    &Type::Int,
+                    &batch_type,
+                    batch.span(),
+                ));
+            }
+        }
+
+        let endpoints: Vec<_> = stmt
+            .columns
+            .iter()
+            .filter_map(|c| match c {
+                ImportColumn::Endpoint { type_name, key, .. } => Some((type_name, key)),
+                ImportColumn::Attr { .. } => None,
+            })
+            .collect();
+        for (type_name, key) in &endpoints {
+            let type_id = self
+                .registry
+                .get_type_id(type_name)
+                .ok_or_else(|| AnalyzerError::unknown_type(*type_name, stmt.span))?;
+            if !self.registry.type_has_attr(type_id, key) {
+                return Err(AnalyzerError::unknown_attribute(
+                    *key, *type_name, stmt.span,
+                ));
+            }
+        }
+
+        let node_type = self.registry.get_type_id(&stmt.target);
+        let edge_def = self.registry.get_edge_type_by_name(&stmt.target);
+        match (node_type, edge_def) {
+            (None, None) => return Err(AnalyzerError::unknown_type(&stmt.target, stmt.span)),
+            (None, Some(edge_def)) if edge_def.arity() != endpoints.len() => {
+                return Err(AnalyzerError::WrongTargetCount {
+                    edge: stmt.target.clone(),
+                    expected: edge_def.arity(),
+                    actual: endpoints.len(),
+                    line: stmt.span.line,
+                    column: stmt.span.column,
+                });
+            }
+            _ => {}
+        }
+        for column in &stmt.columns {
+            if let ImportColumn::Attr { attr, .. } = column {
+                let known = match node_type {
+                    Some(type_id) => self.registry.type_has_attr(type_id, attr),
+                    None => edge_def.is_some_and(|e| e.attributes.contains_key(attr)),
+                };
+                if !known {
+                    return Err(AnalyzerError::unknown_attribute(
+                        attr,
+                        &stmt.target,
+                        stmt.span,
+                    ));
+                }
+            }
+        }
+
+        Ok(Type::Null)
     }
 
     /// Analyze a MATCH...mutation compound statement.
@@ -69,8 +267,35 @@ impl<'r> Analyzer<'r> {
             self.analyze_expr(where_expr)?;
         }
 
-        // Analyze each mutation
-        for mutation in &stmt.mutations {
+        self.analyze_mutation_actions(&stmt.mutations)?;
+
+        // Pop scope
+        self.scope.pop();
+
+        // Returns a count of affected entities
+        Ok(Type::Int)
+    }
+
+    /// Analyze an UNWIND statement: the list, then the mutations with the
+    /// element variable in scope.
+    fn analyze_unwind(&mut self, stmt: &mew_parser::UnwindStmt) -> AnalyzerResult<Type> {
+        let element = self.analyze_expr(&stmt.list)?.element();
+
+        self.scope.push();
+        self.scope
+            .define(VarBinding::new(stmt.var.clone(), element));
+        self.analyze_mutation_actions(&stmt.mutations)?;
+        self.scope.pop();
+
+        Ok(Type::Int)
+    }
+
+    /// Analyze the mutations of a compound statement, in order.
+    fn analyze_mutation_actions(
+        &mut self,
+        mutations: &[mew_parser::MutationAction],
+    ) -> AnalyzerResult<()> {
+        for mutation in mutations {
             match mutation {
                 mew_parser::MutationAction::Spawn(s) => {
                     self.analyze_spawn(s)?;
@@ -89,12 +314,7 @@ impl<'r> Analyzer<'r> {
                 }
             }
         }
-
-        // Pop scope
-        self.scope.pop();
-
-        // Returns a count of affected entities
-        Ok(Type::Int)
+        Ok(())
     }
 
     /// Analyze a MATCH...WALK compound statement.
@@ -162,16 +382,36 @@ impl<'r> Analyzer<'r> {
             }
         }
 
-        // Analyze RETURN clause and bind aliases
-        for proj in &stmt.return_clause.projections {
-            let proj_type = self.analyze_projection(proj)?;
-            // Bind alias if present (so it can be used in ORDER BY)
-            if let Some(alias) = &proj.alias {
-                let binding = VarBinding::new(alias, proj_type);
-                self.scope.define(binding);
+        // Analyze NEAREST: the attribute must hold vectors
+        if let Some(nearest) = &stmt.nearest {
+            self.analyze_expr(&nearest.query)?;
+            let attr_expr = Expr::AttrAccess(
+                Box::new(Expr::Var(nearest.var.clone(), nearest.span)),
+                nearest.attr.clone(),
+                nearest.span,
+            );
+            let attr_type = self.analyze_expr(&attr_expr)?;
+            if attr_type != Type::Vector && attr_type != Type::Any {
+                return Err(AnalyzerError::type_mismatch(
+                    &Type::Vector,
+                    &attr_type,
+                    nearest.span,
+                ));
             }
         }
 
+        // Analyze WITH stages; each replaces the scope with its projections
+        for stage in &stmt.stages {
+            self.analyze_with_stage(stage)?;
+        }
+
+        // Analyze RETURN clause and bind aliases (for GROUP BY, HAVING and ORDER BY)
+        let columns = self.analyze_grouped_projections(
+            &stmt.return_clause.projections,
+            stmt.group_by.as_deref(),
+            stmt.having.as_ref(),
+        )?;
+
         // Analyze ORDER BY if present
         if let Some(order_terms) = &stmt.order_by {
             for term in order_terms {
@@ -181,11 +421,169 @@ impl<'r> Analyzer<'r> {
 
         // Pop the pattern scope
         self.scope.pop();
+        self.columns = columns;
 
         // MATCH returns a result set type (simplified as Any for now)
         Ok(Type::Any)
     }
 
+    /// Analyze a WITH stage. Afterwards only the names it projects are in scope.
+    /// Analyze a UNION: every branch must return as many columns as the
+    /// first, each of a type compatible with the first branch's column.
+    /// The combined column types become the result columns.
+    fn analyze_union(&mut self, stmt: &UnionStmt) -> AnalyzerResult<Type> {
+        let mut columns: Option<Vec<Type>> = None;
+        for branch in &stmt.branches {
+            self.analyze_match(branch)?;
+            let branch_columns = std::mem::take(&mut self.columns);
+            let Some(merged) = columns.as_mut() else {
+                columns = Some(branch_columns);
+                continue;
+            };
+            if merged.len() != branch_columns.len() {
+                return Err(AnalyzerError::invalid_union(
+                    format!(
+                        "UNION branches return different numbers of columns: {} and {}",
+                        merged.len(),
+                        branch_columns.len()
+                    ),
+                    branch.span,
+                ));
+            }
+            for (i, (left, right)) in merged.iter_mut().zip(&branch_columns).enumerate() {
+                *left = left.common_type(right).ok_or_else(|| {
+                    AnalyzerError::invalid_union(
+                        format!(
+                            "UNION column {} has incompatible types {} and {}",
+                            i + 1,
+                            left.name(),
+                            right.name()
+                        ),
+                        branch.return_clause.projections[i].span,
+                    )
+                })?;
+            }
+        }
+        self.columns = columns.unwrap_or_default();
+        Ok(Type::Any)
+    }
+
+    fn analyze_with_stage(&mut self, stage: &WithStage) -> AnalyzerResult<()> {
+        for proj in &stage.projections {
+            if proj.alias.is_none() && !matches!(proj.expr, Expr::Var(..)) {
+                return Err(AnalyzerError::invalid_projection(
+                    "Expression in WITH must be named with AS",
+                    proj.span,
+                ));
+            }
+        }
+        let types = self.analyze_grouped_projections(
+            &stage.projections,
+            stage.group_by.as_deref(),
+            stage.having.as_ref(),
+        )?;
+
+        self.scope.pop();
+        self.scope.push();
+        for (proj, ty) in stage.projections.iter().zip(types) {
+            let name = match (&proj.alias, &proj.expr) {
+                (Some(alias), _) => alias.clone(),
+                (None, Expr::Var(name, _)) => name.clone(),
+                (None, _) => unreachable!("checked above"),
+            };
+            self.scope.define(VarBinding::new(name, ty));
+        }
+
+        if let Some(order_terms) = &stage.order_by {
+            for term in order_terms {
+                self.analyze_expr(&term.expr)?;
+            }
+        }
+        for elem in &stage.pattern {
+            self.analyze_pattern_elem(elem)?;
+        }
+        if let Some(where_expr) = &stage.where_clause {
+            self.check_bool(where_expr)?;
+        }
+        Ok(())
+    }
+
+    /// Analyze the projections of a RETURN or WITH with its GROUP BY and
+    /// HAVING, defining the aliases in the current scope. Returns the
+    /// projection types.
+    fn analyze_grouped_projections(
+        &mut self,
+        projections: &[Projection],
+        group_by: Option<&[Expr]>,
+        having: Option<&Expr>,
+    ) -> AnalyzerResult<Vec<Type>> {
+        let mut types = Vec::with_capacity(projections.len());
+        for proj in projections {
+            let proj_type = self.analyze_projection(proj)?;
+            if let Some(alias) = &proj.alias {
+                let binding = VarBinding::new(alias, proj_type.clone());
+                self.scope.define(binding);
+            }
+            types.push(proj_type);
+        }
+
+        if let Some(keys) = group_by {
+            for key in keys {
+                self.analyze_expr(key)?;
+            }
+            for proj in projections {
+                Self::check_grouped(proj, projections, keys)?;
+            }
+        }
+        if let Some(having) = having {
+            self.check_bool(having)?;
+        }
+        Ok(types)
+    }
+
+    /// Check that a projection outside any aggregate uses only grouped values:
+    /// it is a GROUP BY key itself, or its variables are keys.
+    fn check_grouped(
+        proj: &Projection,
+        projections: &[Projection],
+        keys: &[Expr],
+    ) -> AnalyzerResult<()> {
+        if contains_aggregate(&proj.expr) {
+            return Ok(());
+        }
+        let is_key = |expr: &Expr| {
+            keys.iter().any(|key| {
+                same_expr(key, expr)
+                    || matches!(key, Expr::Var(name, _) if projections.iter().any(|p| {
+                        p.alias.as_deref() == Some(name.as_str()) && same_expr(&p.expr, expr)
+                    }))
+            })
+        };
+        if is_key(&proj.expr) {
+            return Ok(());
+        }
+        let mut vars = Vec::new();
+        collect_vars(&proj.expr, &mut vars);
+        for (name, span) in vars {
+            if !is_key(&Expr::Var(name.clone(), span)) {
+                return Err(AnalyzerError::invalid_projection(
+                    format!("'{name}' is neither grouped nor aggregated"),
+                    proj.span,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Analyze a condition that must be boolean.
+    fn check_bool(&mut self, expr: &Expr) -> AnalyzerResult<()> {
+        let ty = self.analyze_expr(expr)?;
+        if ty != Type::Bool && ty != Type::Any {
+            return Err(AnalyzerError::type_mismatch(&Type::Bool, &ty, expr.span()));
+        }
+        Ok(())
+    }
+
     /// Analyze a pattern element.
     fn analyze_pattern_elem(&mut self, elem: &PatternElem) -> AnalyzerResult<()> {
         match elem {
@@ -241,6 +639,9 @@ impl<'r> Analyzer<'r> {
         // by the edge pattern itself rather than requiring prior node pattern declarations.
         // This enables patterns like: MATCH follows+(u, u) RETURN u.username
         let is_transitive = pattern.transitive.is_some();
+        if is_transitive {
+            self.check_transitive_pattern(pattern)?;
+        }
 
         // Check that target variables exist (skip "_" wildcard)
         // For transitive patterns, implicitly bind undefined variables based on edge param types
@@ -282,6 +683,32 @@ impl<'r> Analyzer<'r> {
         Ok(())
     }
 
+    /// Check that a transitive edge pattern is over a binary edge and that its
+    /// depth bounds are positive and in order.
+    fn check_transitive_pattern(&self, pattern: &EdgePattern) -> AnalyzerResult<()> {
+        if pattern.targets.len() != 2 {
+            return Err(AnalyzerError::invalid_transitive_pattern(
+                "Transitive patterns require binary edges",
+                pattern.span,
+            ));
+        }
+        if let Some(max) = pattern.max_depth {
+            if max <= 0 {
+                return Err(AnalyzerError::invalid_transitive_pattern(
+                    "Depth limit must be positive",
+                    pattern.span,
+                ));
+            }
+            if let Some(min) = pattern.min_depth.filter(|min| *min > max) {
+                return Err(AnalyzerError::invalid_transitive_pattern(
+                    format!("Depth range {}..{} is empty", min, max),
+                    pattern.span,
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Analyze a projection.
     fn analyze_projection(&mut self, proj: &Projection) -> AnalyzerResult<Type> {
         self.analyze_expr(&proj.expr)
@@ -324,6 +751,44 @@ impl<'r> Analyzer<'r> {
                 }
             }
 
+            // ON CONFLICT names unique attributes the item sets; DO UPDATE
+            // sees the variable bound to the existing node
+            if let Some(on_conflict) = &item.on_conflict {
+                for attr_name in &on_conflict.attrs {
+                    let attr_def = self
+                        .registry
+                        .get_all_type_attrs(type_id)
+                        .into_iter()
+                        .find(|attr_def| &attr_def.name == attr_name)
+                        .ok_or_else(|| {
+                            AnalyzerError::unknown_attribute(
+                                attr_name,
+                                &item.type_name,
+                                on_conflict.span,
+                            )
+                        })?;
+                    if !attr_def.unique {
+                        return Err(AnalyzerError::invalid_conflict_target(
+                            attr_name,
+                            "the attribute is not unique",
+                            on_conflict.span,
+                        ));
+                    }
+                    if !item.attrs.iter().any(|attr| &attr.name == attr_name) {
+                        return Err(AnalyzerError::invalid_conflict_target(
+                            attr_name,
+                            "the SPAWN does not set it",
+                            on_conflict.span,
+                        ));
+                    }
+                }
+                if let ConflictAction::Update(assignments) = &on_conflict.action {
+                    for attr in assignments {
+                        self.analyze_attr_assignment(attr, &item.type_name)?;
+                    }
+                }
+            }
+
             last_type_id = Some(type_id);
         }
 
@@ -458,6 +923,10 @@ impl<'r> Analyzer<'r> {
             }
         }
 
+        if let Some(shortest) = &stmt.shortest {
+            self.analyze_shortest(shortest, &stmt.follow)?;
+        }
+
         // Analyze UNTIL condition if present
         if let Some(until_expr) = &stmt.until {
             let until_type = self.analyze_expr(until_expr)?;
@@ -474,6 +943,48 @@ impl<'r> Analyzer<'r> {
         Ok(Type::Any)
     }
 
+    /// Analyze a SHORTEST clause: the target must be a node and the weight
+    /// a numeric attribute of every followed edge type.
+    fn analyze_shortest(
+        &mut self,
+        shortest: &mew_parser::ShortestClause,
+        follow: &[mew_parser::FollowClause],
+    ) -> AnalyzerResult<()> {
+        let to_type = self.analyze_expr(&shortest.to)?;
+        if !to_type.is_ref() && to_type != Type::Any {
+            return Err(AnalyzerError::type_mismatch(
+                &Type::AnyNodeRef,
+                &to_type,
+                shortest.to.span(),
+            ));
+        }
+
+        let Some(weight) = &shortest.weight else {
+            return Ok(());
+        };
+        for edge_type_name in follow.iter().flat_map(|f| &f.edge_types) {
+            let Some(edge_type) = self
+                .registry
+                .get_edge_type_id(edge_type_name)
+                .and_then(|id| self.registry.get_edge_type(id))
+            else {
+                continue;
+            };
+            let attr_def = edge_type.attributes.get(weight).ok_or_else(|| {
+                AnalyzerError::unknown_attribute(weight, edge_type_name, shortest.span)
+            })?;
+            let weight_type = self.type_name_to_type(&attr_def.type_name);
+            if !matches!(weight_type, Type::Int | Type::Float | Type::Duration) {
+                return Err(AnalyzerError::type_mismatch(
+                    &Type::Float,
+                    &weight_type,
+                    shortest.span,
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Analyze a target.
     fn analyze_target(&mut self, target: &mew_parser::Target, span: Span) -> AnalyzerResult<Type> {
         match target {
@@ -532,12 +1043,51 @@ impl<'r> Analyzer<'r> {
             Expr::NotExists(pattern, where_clause, span) => {
                 self.analyze_exists(pattern, where_clause.as_deref(), *span)
             }
+            Expr::Subquery(sq) => self.analyze_subquery(sq),
+            Expr::Quantifier(q) => {
+                let element = self.analyze_expr(&q.list)?.element();
+                // The element variable is scoped to the predicate
+                self.scope.push();
+                self.scope.define(VarBinding::new(&q.var, element));
+                let result = self.check_bool(&q.predicate);
+                self.scope.pop();
+                result?;
+                Ok(Type::Bool)
+            }
+            Expr::Index(base, index, span) => {
+                let base_type = self.analyze_expr(base)?;
+                let index_type = self.analyze_expr(index)?;
+                match (&base_type, &index_type) {
+                    (Type::List(element), Type::Int | Type::Any | Type::Null) => {
+                        Ok((**element).clone())
+                    }
+                    (Type::Map, Type::String | Type::Any | Type::Null)
+                    | (Type::Any | Type::Null, _) => Ok(Type::Any),
+                    _ => Err(AnalyzerError::invalid_operator(
+                        "[]",
+                        &base_type,
+                        &index_type,
+                        *span,
+                    )),
+                }
+            }
             Expr::List(elements, _) => {
-                // Analyze all elements but return a generic list type
+                // Elements of mixed types make a list of Any
+                let mut element = Type::Null;
                 for elem in elements {
-                    self.analyze_expr(elem)?;
+                    let elem_type = self.analyze_expr(elem)?;
+                    element = element.common_type(&elem_type).unwrap_or(Type::Any);
+                }
+                Ok(Type::list(match element {
+                    Type::Null => Type::Any,
+                    element => element,
+                }))
+            }
+            Expr::Map(entries, _) => {
+                for (_, value) in entries {
+                    self.analyze_expr(value)?;
                 }
-                Ok(Type::Any) // List type - could be refined to Type::List in future
+                Ok(Type::Map)
             }
             Expr::TypeCheck(base, _type_name, _) => {
                 // Type check: expr:Type - returns bool
@@ -557,6 +1107,8 @@ impl<'r> Analyzer<'r> {
             LiteralKind::String(_) => Type::String,
             LiteralKind::Duration(_) => Type::Duration,
             LiteralKind::Timestamp(_) => Type::Timestamp,
+            LiteralKind::Bytes(_) => Type::Bytes,
+            LiteralKind::Hash(_) => Type::Hash,
         })
     }
 
@@ -617,6 +1169,8 @@ impl<'r> Analyzer<'r> {
                 }
             }
             Type::AnyEdgeRef => Ok(Type::Any),
+            // Map keys are not part of the schema, so nested values are untyped
+            Type::Map => Ok(Type::Any),
             Type::Any => Ok(Type::Any),
             _ => Err(AnalyzerError::cannot_access_attribute(&base_type, span)),
         }
@@ -631,6 +1185,16 @@ impl<'r> Analyzer<'r> {
             "String" | "string" => Type::String,
             "Timestamp" | "timestamp" => Type::Timestamp,
             "Duration" | "duration" => Type::Duration,
+            "Map" | "map" => Type::Map,
+            "Bytes" | "bytes" => Type::Bytes,
+            "Hash" | "hash" => Type::Hash,
+            name if name.starts_with("Vector") => Type::Vector,
+            "Point" | "point" => Type::Point,
+            // Json may hold a map, a list or a scalar
+            "Json" | "json" => Type::Any,
+            name if name.starts_with('[') && name.ends_with(']') => {
+                Type::list(self.type_name_to_type(&name[1..name.len() - 1]))
+            }
             _ => {
                 // Check if it's a node type
                 if let Some(type_id) = self.registry.get_type_id(name) {
@@ -645,6 +1209,7 @@ impl<'r> Analyzer<'r> {
     }
 
     /// Analyze a binary operation.
+    #[allow(clippy::collapsible_match)]
     fn analyze_binary_op(
         &mut self,
         op: mew_parser::BinaryOp,
@@ -714,60 +1279,68 @@ impl<'r> Analyzer<'r> {
 
     /// Analyze a function call.
     fn analyze_fn_call(&mut self, fc: &FnCall) -> AnalyzerResult<Type> {
-        // Check if this is a pattern-based aggregation (e.g., count(a: Author, written_by(b, a)))
-        // Pattern-based aggregations use TypeCheck expressions to declare scoped variables
-        let is_aggregate = matches!(
-            fc.name.to_lowercase().as_str(),
-            "count" | "sum" | "avg" | "min" | "max" | "collect"
-        );
-
-        if is_aggregate && !fc.args.is_empty() {
-            // Check if first arg looks like a pattern declaration (TypeCheck = var:Type)
-            if let Some(Expr::TypeCheck(base_expr, type_name, _)) = fc.args.first() {
-                if let Expr::Var(var_name, _) = base_expr.as_ref() {
-                    // This looks like pattern syntax: count(a: Author, ...)
-                    // Push a new scope and register the variable
-                    self.scope.push();
-
-                    // Look up the type to get the TypeId
-                    if let Some(type_id) = self.registry.get_type_id(type_name) {
-                        let binding = VarBinding::new(var_name, Type::NodeRef(type_id));
-                        self.scope.define(binding);
-                    } else {
-                        // Unknown type - still register with Any type to allow analysis to continue
-                        let binding = VarBinding::new(var_name, Type::Any);
-                        self.scope.define(binding);
-                    }
-
-                    // Analyze remaining arguments with the scoped variable
-                    for arg in fc.args.iter().skip(1) {
-                        self.analyze_expr(arg)?;
-                    }
-
-                    // Analyze filter if present
-                    if let Some(filter) = &fc.filter {
-                        self.analyze_expr(filter)?;
-                    }
-
-                    // Pop the scope
-                    self.scope.pop();
-
-                    // Return the appropriate type for the aggregate
-                    return Ok(match fc.name.to_lowercase().as_str() {
-                        "count" => Type::Int,
-                        "sum" | "avg" | "min" | "max" => Type::Float,
-                        "collect" => Type::Any,
-                        _ => Type::Any,
-                    });
-                }
+        // Pattern-based aggregation (e.g., count(a: Author, written_by(b, a))): the
+        // first argument declares a variable scoped to the call
+        if let Some((var_name, type_name)) = fc.pattern_var() {
+            let name = fc.name.to_lowercase();
+            if name != "count" && name != "collect" {
+                return Err(AnalyzerError::invalid_aggregate(
+                    format!(
+                        "{} over a pattern is not supported; use COUNT or COLLECT",
+                        name.to_uppercase()
+                    ),
+                    fc.span,
+                ));
             }
+
+            // Unknown types still register with Any to allow analysis to continue
+            let value_type = match self.registry.get_type_id(type_name) {
+                Some(type_id) => Type::NodeRef(type_id),
+                None => Type::Any,
+            };
+            self.scope.push();
+            self.scope
+                .define(VarBinding::new(var_name, value_type.clone()));
+
+            // Analyze remaining arguments and the filter with the scoped variable
+            let result = fc
+                .args
+                .iter()
+                .skip(1)
+                .chain(fc.filter.as_deref())
+                .try_for_each(|arg| self.analyze_expr(arg).map(drop));
+            self.scope.pop();
+            result?;
+
+            return Ok(aggregate_type(&name, &value_type));
         }
 
         // Standard function call analysis
+        let mut arg_types = Vec::with_capacity(fc.args.len());
         for arg in &fc.args {
-            self.analyze_expr(arg)?;
+            arg_types.push(self.analyze_expr(arg)?);
         }
 
+        // Temporal and string functions take typed arguments
+        let expected: &[Type] = match fc.name.to_lowercase().as_str() {
+            "year" | "month" | "day" | "hour" | "minute" | "second" | "millisecond"
+            | "day_of_week" | "format_timestamp" => &[Type::Timestamp],
+            "timestamp" => &[Type::String],
+            "split" | "string_agg" => &[Type::String, Type::String],
+            "date_trunc" => &[Type::String, Type::Timestamp],
+            "to_milliseconds" | "to_seconds" | "to_minutes" | "to_hours" | "to_days" => {
+                &[Type::Duration]
+            }
+            _ => &[],
+        };
+        for ((arg, actual), expected) in fc.args.iter().zip(&arg_types).zip(expected) {
+            if actual != expected && !matches!(actual, Type::Any | Type::Null | Type::Unknown) {
+                return Err(AnalyzerError::type_mismatch(expected, actual, arg.span()));
+            }
+        }
+
+        self.check_statistical_aggregate(fc, &arg_types)?;
+
         // Analyze filter if present
         if let Some(filter) = &fc.filter {
             self.analyze_expr(filter)?;
@@ -775,10 +1348,39 @@ impl<'r> Analyzer<'r> {
 
         // Return type depends on function - simplified for now
         Ok(match fc.name.to_lowercase().as_str() {
-            "count" => Type::Int,
-            "sum" | "avg" | "min" | "max" => Type::Float,
+            // Scalar MIN and MAX pick one of their arguments
+            "min" | "max" if arg_types.len() > 1 => arg_types
+                .iter()
+                .try_fold(Type::Null, |acc, t| acc.common_type(t))
+                .unwrap_or(Type::Any),
+            name @ ("count" | "sum" | "avg" | "min" | "max" | "collect") => {
+                aggregate_type(name, arg_types.first().unwrap_or(&Type::Any))
+            }
+            "split" => Type::list(Type::String),
+            // A slice of a list has the list's type
+            "slice" => match arg_types.first() {
+                Some(list @ Type::List(_)) => list.clone(),
+                _ => Type::Any,
+            },
             "concat" | "upper" | "lower" | "trim" => Type::String,
-            "now" => Type::Timestamp,
+            "now" | "wall_time" | "timestamp" | "date_trunc" => Type::Timestamp,
+            "year" | "month" | "day" | "hour" | "minute" | "second" | "millisecond"
+            | "day_of_week" => Type::Int,
+            "format_timestamp" => Type::String,
+            "size" => Type::Int,
+            "join" | "string_agg" => Type::String,
+            "stddev" | "variance" | "median" | "percentile_cont" => Type::Float,
+            // A discrete percentile is one of the input values
+            "percentile_disc" => arg_types.first().cloned().unwrap_or(Type::Any),
+            "to_milliseconds" | "to_seconds" | "to_minutes" | "to_hours" | "to_days" => Type::Int,
+            "hash" => Type::Hash,
+            "vector" => Type::Vector,
+            "cosine_similarity" | "l2_distance" => Type::Float,
+            "point" => Type::Point,
+            "distance" => Type::Float,
+            "within_box" | "within_radius" => Type::Bool,
+            "search" => Type::Bool,
+            "search_score" => Type::Float,
             "coalesce" => {
                 // Return type of first non-null argument
                 if let Some(arg) = fc.args.first() {
@@ -791,6 +1393,64 @@ impl<'r> Analyzer<'r> {
         })
     }
 
+    /// Check the arguments of the statistical aggregates and STRING_AGG: the
+    /// number of arguments, numeric values, and a percentile fraction in [0, 1].
+    fn check_statistical_aggregate(&self, fc: &FnCall, arg_types: &[Type]) -> AnalyzerResult<()> {
+        let name = fc.name.to_lowercase();
+        let (arity, numeric_value) = match name.as_str() {
+            "stddev" | "variance" | "median" => (1, true),
+            "percentile_cont" => (2, true),
+            "percentile_disc" => (2, false),
+            "string_agg" => (2, false),
+            _ => return Ok(()),
+        };
+        if fc.args.len() != arity {
+            return Err(AnalyzerError::invalid_aggregate(
+                format!(
+                    "{} expects {} argument{}, got {}",
+                    name.to_uppercase(),
+                    arity,
+                    if arity == 1 { "" } else { "s" },
+                    fc.args.len()
+                ),
+                fc.span,
+            ));
+        }
+        let is_numeric =
+            |t: &Type| t.is_numeric() || matches!(t, Type::Any | Type::Null | Type::Unknown);
+        if numeric_value && !is_numeric(&arg_types[0]) {
+            return Err(AnalyzerError::type_mismatch(
+                &Type::Float,
+                &arg_types[0],
+                fc.args[0].span(),
+            ));
+        }
+        if name.starts_with("percentile") {
+            if !is_numeric(&arg_types[1]) {
+                return Err(AnalyzerError::type_mismatch(
+                    &Type::Float,
+                    &arg_types[1],
+                    fc.args[1].span(),
+                ));
+            }
+            let fraction = match &fc.args[1] {
+                Expr::Literal(lit) => match lit.kind {
+                    LiteralKind::Int(i) => Some(i as f64),
+                    LiteralKind::Float(f) => Some(f),
+                    _ => None,
+                },
+                _ => None,
+            };
+            if fraction.is_some_and(|f| !(0.0..=1.0).contains(&f)) {
+                return Err(AnalyzerError::invalid_aggregate(
+                    format!("{} fraction must be between 0 and 1", name.to_uppercase()),
+                    fc.args[1].span(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Analyze an EXISTS/NOT EXISTS expression.
     fn analyze_exists(
         &mut self,
@@ -824,6 +1484,29 @@ impl<'r> Analyzer<'r> {
         Ok(Type::Bool)
     }
 
+    /// Analyze a COUNT, COLLECT or scalar subquery. Its pattern variables are
+    /// scoped to the subquery.
+    fn analyze_subquery(&mut self, sq: &Subquery) -> AnalyzerResult<Type> {
+        self.scope.push();
+        for elem in &sq.pattern {
+            self.analyze_pattern_elem(elem)?;
+        }
+        if let Some(where_expr) = &sq.where_clause {
+            self.check_bool(where_expr)?;
+        }
+        let projection_type = match &sq.projection {
+            Some(expr) => self.analyze_expr(expr)?,
+            None => Type::Any,
+        };
+        self.scope.pop();
+
+        Ok(match sq.kind {
+            SubqueryKind::Count => Type::Int,
+            SubqueryKind::Collect => Type::list(projection_type),
+            SubqueryKind::Scalar => projection_type,
+        })
+    }
+
     /// Get accumulated errors.
     pub fn errors(&self) -> &[AnalyzerError] {
         &self.errors
@@ -834,10 +1517,108 @@ impl<'r> Analyzer<'r> {
         !self.errors.is_empty()
     }
 
+    /// Types of the RETURN columns of the last analyzed MATCH or WATCH.
+    pub fn column_types(&self) -> &[Type] {
+        &self.columns
+    }
+
     /// Clear scope and errors for fresh analysis.
     pub fn reset(&mut self) {
         self.scope = Scope::new();
         self.errors.clear();
+        self.columns.clear();
+    }
+}
+
+/// Whether `fc` is an aggregate call (min and max take one argument as
+/// aggregates). Aggregates over a pattern are evaluated for each row.
+fn is_aggregate_call(fc: &FnCall) -> bool {
+    if fc.pattern_var().is_some() {
+        return false;
+    }
+    match fc.name.to_lowercase().as_str() {
+        "count" | "sum" | "avg" | "collect" | "stddev" | "variance" | "median"
+        | "percentile_cont" | "percentile_disc" | "string_agg" => true,
+        "min" | "max" => fc.args.len() == 1,
+        _ => false,
+    }
+}
+
+/// The result type of COUNT, SUM, AVG, MIN, MAX or COLLECT over values of
+/// `value_type`.
+fn aggregate_type(name: &str, value_type: &Type) -> Type {
+    match (name, value_type) {
+        ("count", _) => Type::Int,
+        ("avg", _) => Type::Float,
+        // Integers sum to an integer; a mix of numbers may sum to either
+        ("sum", Type::Int | Type::Float) => value_type.clone(),
+        ("sum", _) => Type::Any,
+        ("min" | "max", _) => value_type.clone(),
+        ("collect", _) => Type::list(value_type.clone()),
+        _ => Type::Any,
+    }
+}
+
+/// Whether `expr` contains an aggregate call.
+fn contains_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::FnCall(fc) => is_aggregate_call(fc) || fc.args.iter().any(contains_aggregate),
+        Expr::AttrAccess(base, _, _) | Expr::UnaryOp(_, base, _) | Expr::TypeCheck(base, _, _) => {
+            contains_aggregate(base)
+        }
+        Expr::BinaryOp(_, left, right, _) | Expr::Index(left, right, _) => {
+            contains_aggregate(left) || contains_aggregate(right)
+        }
+        Expr::Quantifier(q) => contains_aggregate(&q.list) || contains_aggregate(&q.predicate),
+        Expr::List(items, _) => items.iter().any(contains_aggregate),
+        Expr::Map(entries, _) => entries.iter().any(|(_, value)| contains_aggregate(value)),
+        _ => false,
+    }
+}
+
+/// Collect the variables `expr` reads.
+fn collect_vars(expr: &Expr, vars: &mut Vec<(String, Span)>) {
+    match expr {
+        Expr::Var(name, span) => vars.push((name.clone(), *span)),
+        Expr::FnCall(fc) => fc.args.iter().for_each(|arg| collect_vars(arg, vars)),
+        Expr::AttrAccess(base, _, _) | Expr::UnaryOp(_, base, _) | Expr::TypeCheck(base, _, _) => {
+            collect_vars(base, vars)
+        }
+        Expr::BinaryOp(_, left, right, _) | Expr::Index(left, right, _) => {
+            collect_vars(left, vars);
+            collect_vars(right, vars);
+        }
+        Expr::Quantifier(q) => {
+            collect_vars(&q.list, vars);
+            let mut inner = Vec::new();
+            collect_vars(&q.predicate, &mut inner);
+            vars.extend(inner.into_iter().filter(|(name, _)| *name != q.var));
+        }
+        Expr::List(items, _) => items.iter().for_each(|item| collect_vars(item, vars)),
+        Expr::Map(entries, _) => entries
+            .iter()
+            .for_each(|(_, value)| collect_vars(value, vars)),
+        _ => {}
+    }
+}
+
+/// Structural equality of expressions, ignoring spans.
+fn same_expr(a: &Expr, b: &Expr) -> bool {
+    match (a, b) {
+        (Expr::Literal(a), Expr::Literal(b)) => a.kind == b.kind,
+        (Expr::Var(a, _), Expr::Var(b, _)) | (Expr::Param(a, _), Expr::Param(b, _)) => a == b,
+        (Expr::AttrAccess(a, x, _), Expr::AttrAccess(b, y, _)) => x == y && same_expr(a, b),
+        (Expr::BinaryOp(op_a, la, ra, _), Expr::BinaryOp(op_b, lb, rb, _)) => {
+            op_a == op_b && same_expr(la, lb) && same_expr(ra, rb)
+        }
+        (Expr::UnaryOp(op_a, a, _), Expr::UnaryOp(op_b, b, _)) => op_a == op_b && same_expr(a, b),
+        (Expr::Index(la, ia, _), Expr::Index(lb, ib, _)) => same_expr(la, lb) && same_expr(ia, ib),
+        (Expr::FnCall(a), Expr::FnCall(b)) => {
+            a.name.eq_ignore_ascii_case(&b.name)
+                && a.args.len() == b.args.len()
+                && a.args.iter().zip(&b.args).all(|(x, y)| same_expr(x, y))
+        }
+        _ => false,
     }
 }
 
@@ -1113,4 +1894,408 @@ mod tests {
         // THEN
         assert_eq!(result.unwrap(), Type::Int);
     }
+
+    #[test]
+    fn test_analyze_import() {
+        // GIVEN
+        let registry = test_registry();
+        let analyze = |input: &str| {
+            let stmt = mew_parser::parse_stmt(input).unwrap();
+            Analyzer::new(&registry)
+                .analyze_stmt(&stmt)
+                .map_err(|e| e.to_string())
+        };
+
+        // WHEN / THEN
+        assert!(analyze(r#"IMPORT Person(full AS name, age) FROM "p.csv""#).is_ok());
+        assert!(analyze(r#"IMPORT Knows(a: Person.name, b: Person.name) FROM $f"#).is_ok());
+        assert!(analyze(r#"IMPORT Robot FROM "r.csv""#)
+            .unwrap_err()
+            .contains("Unknown type 'Robot'"));
+        assert!(analyze(r#"IMPORT Person(nick) FROM "p.csv""#)
+            .unwrap_err()
+            .contains("Unknown attribute 'nick'"));
+        assert!(analyze(r#"IMPORT Knows(a: Person.name) FROM "k.csv""#)
+            .unwrap_err()
+            .contains("expects 2 targets, got 1"));
+        assert!(analyze(r#"IMPORT Knows(a: Person.nick, b: Person.name) FROM "k.csv""#).is_err());
+        assert!(analyze("IMPORT Person FROM 42").is_err());
+    }
+
+    #[test]
+    fn test_analyze_walk_shortest_weight() {
+        // GIVEN a road edge with a numeric and a string attribute
+        let mut builder = RegistryBuilder::new();
+        builder.add_type("City").done().unwrap();
+        builder
+            .add_edge_type("road")
+            .param("from", "City")
+            .param("to", "City")
+            .attr(AttrDef::new("km", "Float"))
+            .attr(AttrDef::new("name", "String"))
+            .done()
+            .unwrap();
+        let registry = builder.build().unwrap();
+        let analyze = |weight: &str| {
+            let input = format!(
+                "WALK FROM #a FOLLOW road SHORTEST PATH TO #b {} RETURN PATH",
+                weight
+            );
+            let stmt = mew_parser::parse_stmt(&input).unwrap();
+            Analyzer::new(&registry)
+                .analyze_stmt(&stmt)
+                .map_err(|e| e.to_string())
+        };
+
+        // WHEN / THEN
+        assert!(analyze("").is_ok());
+        assert!(analyze("WEIGHT km").is_ok());
+        assert!(analyze("WEIGHT name")
+            .unwrap_err()
+            .contains("Type mismatch"));
+        assert!(analyze("WEIGHT toll")
+            .unwrap_err()
+            .contains("Unknown attribute 'toll'"));
+    }
+
+    #[test]
+    fn test_analyze_transitive_pattern() {
+        // GIVEN the test registry plus a ternary edge
+        let mut builder = RegistryBuilder::new();
+        builder.add_type("Person").done().unwrap();
+        builder
+            .add_edge_type("Knows")
+            .param("from", "Person")
+            .param("to", "Person")
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("Introduced")
+            .param("by", "Person")
+            .param("a", "Person")
+            .param("b", "Person")
+            .done()
+            .unwrap();
+        let registry = builder.build().unwrap();
+        let analyze = |input: &str| {
+            let stmt = mew_parser::parse_stmt(input).unwrap();
+            Analyzer::new(&registry)
+                .analyze_stmt(&stmt)
+                .map_err(|e| e.to_string())
+        };
+
+        // WHEN / THEN undeclared ends are bound implicitly
+        assert!(analyze("MATCH Knows+(u, u) RETURN u").is_ok());
+        assert!(analyze("MATCH a: Person, Knows*(a, b) [depth: 1..3] RETURN b").is_ok());
+        // AND depth bounds must be positive and in order
+        assert!(analyze("MATCH Knows+(a, b) [depth: 0] RETURN b")
+            .unwrap_err()
+            .contains("Depth limit must be positive"));
+        assert!(analyze("MATCH Knows+(a, b) [depth: 3..2] RETURN b")
+            .unwrap_err()
+            .contains("Depth range 3..2 is empty"));
+        // AND the edge must be binary
+        assert!(analyze("MATCH Introduced+(a, b, c) RETURN a")
+            .unwrap_err()
+            .contains("Transitive patterns require binary edges"));
+    }
+
+    #[test]
+    fn test_analyze_call() {
+        // GIVEN cities with a Float and a String attribute
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("City")
+            .attr(AttrDef::new("rank", "Float"))
+            .attr(AttrDef::new("name", "String"))
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("road")
+            .param("from", "City")
+            .param("to", "City")
+            .done()
+            .unwrap();
+        let registry = builder.build().unwrap();
+        let analyze = |input: &str| {
+            let stmt = mew_parser::parse_stmt(input).unwrap();
+            let mut analyzer = Analyzer::new(&registry);
+            analyzer
+                .analyze_stmt(&stmt)
+                .map(|_| analyzer.column_types().to_vec())
+                .map_err(|e| e.to_string())
+        };
+
+        // WHEN / THEN the YIELD columns are typed
+        assert_eq!(
+            analyze("CALL algo.pagerank(road, iterations = 5) YIELD score, node").unwrap(),
+            vec![Type::Float, Type::AnyNodeRef]
+        );
+        assert_eq!(
+            analyze("CALL algo.scc(road)").unwrap(),
+            vec![Type::AnyNodeRef, Type::Int]
+        );
+        assert!(analyze("CALL algo.degree(road) WRITE rank").is_ok());
+
+        // AND names and types are checked
+        assert!(analyze("CALL algo.rank(road)")
+            .unwrap_err()
+            .contains("Unknown procedure"));
+        assert!(analyze("CALL algo.pagerank(rail)")
+            .unwrap_err()
+            .contains("Unknown edge type"));
+        assert!(analyze("CALL algo.pagerank(road, damping = \"high\")")
+            .unwrap_err()
+            .contains("Type mismatch"));
+        assert!(analyze("CALL algo.wcc(road) YIELD score")
+            .unwrap_err()
+            .contains("has no column 'score'"));
+        assert!(analyze("CALL algo.pagerank(road) WRITE name")
+            .unwrap_err()
+            .contains("Type mismatch"));
+        assert!(analyze("CALL algo.pagerank(road) WRITE size")
+            .unwrap_err()
+            .contains("Unknown attribute 'size'"));
+    }
+
+    #[test]
+    fn test_statistical_aggregates_are_type_checked() {
+        // GIVEN
+        let registry = test_registry();
+        let analyze = |input: &str| {
+            let stmt = mew_parser::parse_stmt(input).unwrap();
+            let mut analyzer = Analyzer::new(&registry);
+            analyzer
+                .analyze_stmt(&stmt)
+                .map(|_| analyzer.column_types().to_vec())
+                .map_err(|e| e.to_string())
+        };
+
+        // WHEN / THEN numeric aggregates are Float, a discrete percentile keeps its input type
+        assert_eq!(
+            analyze(
+                "MATCH p: Person RETURN stddev(p.age), median(p.age), \
+                 percentile_disc(p.age, 0.9), string_agg(DISTINCT p.name, \", \")"
+            )
+            .unwrap(),
+            vec![Type::Float, Type::Float, Type::Int, Type::String]
+        );
+
+        // AND arguments are checked
+        assert!(analyze("MATCH p: Person RETURN variance(p.name)")
+            .unwrap_err()
+            .contains("Type mismatch"));
+        assert!(analyze("MATCH p: Person RETURN string_agg(p.age, \",\")")
+            .unwrap_err()
+            .contains("Type mismatch"));
+        assert!(analyze("MATCH p: Person RETURN percentile_cont(p.age)")
+            .unwrap_err()
+            .contains("expects 2 arguments"));
+        assert!(analyze("MATCH p: Person RETURN percentile_cont(p.age, 2)")
+            .unwrap_err()
+            .contains("between 0 and 1"));
+    }
+
+    #[test]
+    fn test_match_records_column_types() {
+        // GIVEN
+        let registry = test_registry();
+        let mut analyzer = Analyzer::new(&registry);
+        let stmt = mew_parser::parse_stmt(
+            "MATCH p: Person RETURN p, p.name, p.age + 1 AS next, count(p), $x",
+        )
+        .unwrap();
+
+        // WHEN
+        analyzer.analyze_stmt(&stmt).unwrap();
+
+        // THEN
+        let person = registry.get_type_id("Person").unwrap();
+        assert_eq!(
+            analyzer.column_types(),
+            &[
+                Type::NodeRef(person),
+                Type::String,
+                Type::Int,
+                Type::Int,
+                Type::Any
+            ]
+        );
+        let names: Vec<_> = analyzer
+            .column_types()
+            .iter()
+            .map(|t| t.describe(&registry))
+            .collect();
+        assert_eq!(names, vec!["Person", "String", "Int", "Int", "Any"]);
+    }
+
+    #[test]
+    fn test_aggregate_types_follow_their_argument() {
+        // GIVEN aggregates over Int, String and node values
+        let registry = test_registry();
+        let mut analyzer = Analyzer::new(&registry);
+        let stmt = mew_parser::parse_stmt(
+            "MATCH p: Person RETURN sum(p.age), avg(p.age), min(p.name), max(p.age), \
+             collect(p.name), collect(t: Task, Assigned(p, t))",
+        )
+        .unwrap();
+
+        // WHEN
+        analyzer.analyze_stmt(&stmt).unwrap();
+
+        // THEN
+        let names: Vec<_> = analyzer
+            .column_types()
+            .iter()
+            .map(|t| t.describe(&registry))
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Int", "Float", "String", "Int", "[String]", "[Task]"]
+        );
+    }
+
+    #[test]
+    fn test_analyze_with_stages_and_grouping() {
+        // GIVEN the test registry
+        let registry = test_registry();
+        let analyze = |input: &str| {
+            let stmt = mew_parser::parse_stmt(input).unwrap();
+            Analyzer::new(&registry)
+                .analyze_stmt(&stmt)
+                .map_err(|e| e.to_string())
+        };
+
+        // WHEN / THEN a WITH stage passes on only what it projects
+        assert!(analyze("MATCH t: Task WITH t, t.title AS title RETURN t, title").is_ok());
+        assert!(analyze("MATCH t: Task WITH t.title AS title RETURN t")
+            .unwrap_err()
+            .contains("Undefined variable 't'"));
+        // AND its expressions must be named
+        assert!(analyze("MATCH t: Task WITH t.title RETURN t")
+            .unwrap_err()
+            .contains("must be named with AS"));
+        // AND HAVING must be boolean
+        assert!(
+            analyze("MATCH t: Task RETURN t.title, count(t) AS n HAVING n")
+                .unwrap_err()
+                .contains("Type mismatch")
+        );
+        // AND with GROUP BY every other projection is grouped or aggregated
+        assert!(analyze(
+            "MATCH t: Task RETURN t.title AS title, count(t) AS n GROUP BY title HAVING n > 1"
+        )
+        .is_ok());
+        assert!(
+            analyze("MATCH p: Person RETURN p.name, p.age, count(p) GROUP BY p.name")
+                .unwrap_err()
+                .contains("'p' is neither grouped nor aggregated")
+        );
+    }
+
+    #[test]
+    fn test_analyze_spawn_on_conflict() {
+        // GIVEN a type with a unique email
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Account")
+            .attr(AttrDef::new("email", "String").unique())
+            .attr(AttrDef::new("name", "String"))
+            .done()
+            .unwrap();
+        let registry = builder.build().unwrap();
+        let analyze = |input: &str| {
+            let stmt = mew_parser::parse_stmt(input).unwrap();
+            Analyzer::new(&registry)
+                .analyze_stmt(&stmt)
+                .map_err(|e| e.to_string())
+        };
+
+        // WHEN / THEN DO UPDATE can read the existing node through the variable
+        assert!(analyze(
+            r#"SPAWN a: Account { email = "x", name = "X" }
+               ON CONFLICT (email) DO UPDATE SET name = a.name"#
+        )
+        .is_ok());
+        // AND the target must be a unique attribute
+        assert!(
+            analyze(r#"SPAWN a: Account { name = "X" } ON CONFLICT (name) DO NOTHING"#)
+                .unwrap_err()
+                .contains("not unique")
+        );
+        // AND set by the SPAWN
+        assert!(
+            analyze(r#"SPAWN a: Account { name = "X" } ON CONFLICT (email) DO NOTHING"#)
+                .unwrap_err()
+                .contains("does not set it")
+        );
+    }
+
+    #[test]
+    fn test_analyze_union() {
+        // GIVEN the test registry
+        let registry = test_registry();
+        let mut analyzer = Analyzer::new(&registry);
+
+        // WHEN the branches return compatible columns
+        let stmt = mew_parser::parse_stmt(
+            "MATCH p: Person RETURN p, p.age AS n UNION MATCH t: Task RETURN t, 1.5 AS n",
+        )
+        .unwrap();
+        analyzer.analyze_stmt(&stmt).unwrap();
+
+        // THEN the columns take the type that holds both
+        assert_eq!(analyzer.column_types(), &[Type::AnyNodeRef, Type::Float]);
+
+        // AND the branches must agree on the number of columns
+        let analyze = |input: &str| {
+            let stmt = mew_parser::parse_stmt(input).unwrap();
+            Analyzer::new(&registry)
+                .analyze_stmt(&stmt)
+                .map_err(|e| e.to_string())
+        };
+        assert!(
+            analyze("MATCH p: Person RETURN p.name UNION MATCH t: Task RETURN t.title, t")
+                .unwrap_err()
+                .contains("different numbers of columns: 1 and 2")
+        );
+        // AND on the kind of each column
+        assert!(
+            analyze("MATCH p: Person RETURN p.age UNION ALL MATCH t: Task RETURN t.title")
+                .unwrap_err()
+                .contains("UNION column 1 has incompatible types Int and String")
+        );
+    }
+
+    #[test]
+    fn test_analyze_subqueries() {
+        // GIVEN the test registry
+        let registry = test_registry();
+        let analyze = |input: &str| {
+            let stmt = mew_parser::parse_stmt(input).unwrap();
+            Analyzer::new(&registry)
+                .analyze_stmt(&stmt)
+                .map_err(|e| e.to_string())
+        };
+
+        // WHEN / THEN subqueries see the outer variables and have typed results
+        assert!(
+            analyze("MATCH p: Person WHERE COUNT { q: Person, Knows(p, q) } > 2 RETURN p").is_ok()
+        );
+        assert!(analyze(
+            "MATCH p: Person RETURN { MATCH q: Person, Knows(p, q) RETURN q.age } + 1"
+        )
+        .is_ok());
+        assert!(
+            analyze("MATCH p: Person WHERE COUNT { q: Person, Knows(p, q) } RETURN p")
+                .unwrap_err()
+                .contains("Type mismatch")
+        );
+        // AND their own variables stay inside
+        assert!(
+            analyze("MATCH p: Person WHERE COUNT { q: Person, Knows(p, q) } > 2 RETURN q")
+                .unwrap_err()
+                .contains("Undefined variable 'q'")
+        );
+    }
 }
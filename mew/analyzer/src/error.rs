@@ -97,6 +97,65 @@ pub enum AnalyzerError {
         line: usize,
         column: usize,
     },
+
+    /// Transitive edge pattern on a non-binary edge or with bad depth bounds.
+    #[error("{message} at line {line}, column {column}")]
+    InvalidTransitivePattern {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// Projection that a WITH stage cannot name, or that GROUP BY does not cover.
+    #[error("{message} at line {line}, column {column}")]
+    InvalidProjection {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// UNION branches whose columns do not line up.
+    #[error("{message} at line {line}, column {column}")]
+    InvalidUnion {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// Aggregate call with the wrong arguments.
+    #[error("{message} at line {line}, column {column}")]
+    InvalidAggregate {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// ON CONFLICT on an attribute that is not unique or not spawned.
+    #[error("Invalid ON CONFLICT attribute '{attr}' at line {line}, column {column}: {reason}")]
+    InvalidConflictTarget {
+        attr: String,
+        reason: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// CALL of an unknown procedure.
+    #[error("Unknown procedure '{name}' at line {line}, column {column}")]
+    UnknownProcedure {
+        name: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// CALL with an option or YIELD column the procedure does not have.
+    #[error("{procedure} has no {what} '{name}' at line {line}, column {column}")]
+    UnknownProcedureName {
+        procedure: String,
+        what: &'static str,
+        name: String,
+        line: usize,
+        column: usize,
+    },
 }
 
 impl AnalyzerError {
@@ -173,6 +232,74 @@ impl AnalyzerError {
         }
     }
 
+    pub fn invalid_transitive_pattern(message: impl Into<String>, span: Span) -> Self {
+        Self::InvalidTransitivePattern {
+            message: message.into(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+
+    pub fn invalid_projection(message: impl Into<String>, span: Span) -> Self {
+        Self::InvalidProjection {
+            message: message.into(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+
+    pub fn invalid_union(message: impl Into<String>, span: Span) -> Self {
+        Self::InvalidUnion {
+            message: message.into(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+
+    pub fn invalid_aggregate(message: impl Into<String>, span: Span) -> Self {
+        Self::InvalidAggregate {
+            message: message.into(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+
+    pub fn invalid_conflict_target(
+        attr: impl Into<String>,
+        reason: impl Into<String>,
+        span: Span,
+    ) -> Self {
+        Self::InvalidConflictTarget {
+            attr: attr.into(),
+            reason: reason.into(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+
+    pub fn unknown_procedure(name: impl Into<String>, span: Span) -> Self {
+        Self::UnknownProcedure {
+            name: name.into(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+
+    pub fn unknown_procedure_name(
+        procedure: impl Into<String>,
+        what: &'static str,
+        name: impl Into<String>,
+        span: Span,
+    ) -> Self {
+        Self::UnknownProcedureName {
+            procedure: procedure.into(),
+            what,
+            name: name.into(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+
     pub fn cannot_access_attribute(actual: &Type, span: Span) -> Self {
         Self::CannotAccessAttribute {
             actual: actual.name().to_string(),
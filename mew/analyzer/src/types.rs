@@ -1,6 +1,7 @@
 //! Type system for the analyzer.
 
 use mew_core::{EdgeTypeId, TypeId};
+use mew_registry::Registry;
 
 /// The type of a value in MEW.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,6 +20,18 @@ pub enum Type {
     Timestamp,
     /// Duration (milliseconds).
     Duration,
+    /// Structured map with string keys.
+    Map,
+    /// Raw byte sequence.
+    Bytes,
+    /// 32-byte content hash.
+    Hash,
+    /// Dense embedding vector (dimension is checked at mutation time).
+    Vector,
+    /// 2D or 3D spatial point.
+    Point,
+    /// List whose elements have the given type.
+    List(Box<Type>),
     /// Reference to a node of a specific type.
     NodeRef(TypeId),
     /// Reference to an edge of a specific type.
@@ -64,10 +77,44 @@ impl Type {
             // Edge refs can be compared
             (Type::EdgeRef(_), Type::EdgeRef(_)) => true,
             (Type::EdgeRef(_), Type::AnyEdgeRef) | (Type::AnyEdgeRef, Type::EdgeRef(_)) => true,
+            // Lists compare element-wise
+            (Type::List(a), Type::List(b)) => a.can_eq(b),
             _ => false,
         }
     }
 
+    /// The type of a column holding values of both types, as in the
+    /// columns of UNION branches, or `None` when they do not mix.
+    pub fn common_type(&self, other: &Type) -> Option<Type> {
+        match (self, other) {
+            (a, b) if a == b => Some(a.clone()),
+            (Type::Null, t) | (t, Type::Null) => Some(t.clone()),
+            (Type::Any, _) | (_, Type::Any) => Some(Type::Any),
+            (Type::Int, Type::Float) | (Type::Float, Type::Int) => Some(Type::Float),
+            (Type::NodeRef(_) | Type::AnyNodeRef, Type::NodeRef(_) | Type::AnyNodeRef) => {
+                Some(Type::AnyNodeRef)
+            }
+            (Type::EdgeRef(_) | Type::AnyEdgeRef, Type::EdgeRef(_) | Type::AnyEdgeRef) => {
+                Some(Type::AnyEdgeRef)
+            }
+            (Type::List(a), Type::List(b)) => a.common_type(b).map(Type::list),
+            _ => None,
+        }
+    }
+
+    /// A list of `element`.
+    pub fn list(element: Type) -> Type {
+        Type::List(Box::new(element))
+    }
+
+    /// The element type of a list, or `Any` for values not known to be lists.
+    pub fn element(&self) -> Type {
+        match self {
+            Type::List(element) => (**element).clone(),
+            _ => Type::Any,
+        }
+    }
+
     /// Check if this type can be ordered (compared with <, >, etc).
     pub fn can_order(&self, other: &Type) -> bool {
         matches!(
@@ -92,7 +139,9 @@ impl Type {
                 (Type::Float, Type::Float) => Some(Type::Float),
                 (Type::Int, Type::Float) | (Type::Float, Type::Int) => Some(Type::Float),
                 // Timestamp + Duration = Timestamp
-                (Type::Timestamp, Type::Duration) => Some(Type::Timestamp),
+                (Type::Timestamp, Type::Duration) | (Type::Duration, Type::Timestamp) => {
+                    Some(Type::Timestamp)
+                }
                 // Duration + Duration = Duration
                 (Type::Duration, Type::Duration) => Some(Type::Duration),
                 (Type::Any, _) | (_, Type::Any) => Some(Type::Any),
@@ -118,6 +167,10 @@ impl Type {
                     (Type::Int, Type::Float) | (Type::Float, Type::Int) => Some(Type::Float),
                     // Duration * Int = Duration, Duration / Int = Duration
                     (Type::Duration, Type::Int) => Some(Type::Duration),
+                    // Int * Duration = Duration
+                    (Type::Int, Type::Duration) if op == BinaryOpType::Mul => Some(Type::Duration),
+                    // Duration / Duration = Int (how many times one fits in the other)
+                    (Type::Duration, Type::Duration) if op == BinaryOpType::Div => Some(Type::Int),
                     (Type::Any, _) | (_, Type::Any) => Some(Type::Any),
                     _ => None,
                 }
@@ -164,6 +217,7 @@ impl Type {
             UnaryOpType::Neg => match self {
                 Type::Int => Some(Type::Int),
                 Type::Float => Some(Type::Float),
+                Type::Duration => Some(Type::Duration),
                 Type::Any => Some(Type::Any),
                 _ => None,
             },
@@ -185,6 +239,12 @@ impl Type {
             Type::String => "String",
             Type::Timestamp => "Timestamp",
             Type::Duration => "Duration",
+            Type::Map => "Map",
+            Type::Bytes => "Bytes",
+            Type::Hash => "Hash",
+            Type::Vector => "Vector",
+            Type::Point => "Point",
+            Type::List(_) => "List",
             Type::NodeRef(_) => "NodeRef",
             Type::EdgeRef(_) => "EdgeRef",
             Type::AnyNodeRef => "NodeRef",
@@ -193,6 +253,25 @@ impl Type {
             Type::Unknown => "Unknown",
         }
     }
+
+    /// Describe this type for result metadata.
+    ///
+    /// Like [`Type::name`], except references to a known node or edge type
+    /// use that type's name, lists name their element type as `[T]` and
+    /// unresolved types are reported as `Any`.
+    pub fn describe(&self, registry: &Registry) -> String {
+        let declared = match self {
+            Type::List(element) => Some(format!("[{}]", element.describe(registry))),
+            Type::NodeRef(id) => registry.get_type(*id).map(|t| t.name.clone()),
+            Type::EdgeRef(id) => registry.get_edge_type(*id).map(|e| e.name.clone()),
+            _ => None,
+        };
+        match (declared, self) {
+            (Some(name), _) => name,
+            (None, Type::Unknown) => Type::Any.name().to_string(),
+            (None, _) => self.name().to_string(),
+        }
+    }
 }
 
 /// Type for binary operators (for type checking).
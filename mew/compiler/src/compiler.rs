@@ -44,6 +44,7 @@ impl Compiler {
     }
 
     /// Compile ontology source into a Registry.
+    #[allow(clippy::collapsible_match)]
     pub fn compile(&mut self, source: &str) -> CompileResult<Registry> {
         // Parse the ontology
         let defs = parse_ontology(source)?;
@@ -96,21 +97,29 @@ impl Compiler {
                             return Err(CompileError::unknown_type(&primary_type, c.span));
                         }
                         // For edge-only constraints, register them on the edge type
-                        builder
+                        let mut constraint_builder = builder
                             .add_constraint(&c.name, format!("{:?}", c.condition))
-                            .for_edge_type(edge_name)
-                            .done()?;
+                            .source(&source[c.span.start..c.span.end])
+                            .for_edge_type(edge_name);
+                        if c.modifiers.soft {
+                            constraint_builder = constraint_builder.soft();
+                        }
+                        constraint_builder.done()?;
                     } else {
                         // Node type constraint
                         if !self.type_names.contains(&primary_type) {
                             return Err(CompileError::unknown_type(&primary_type, c.span));
                         }
-                        // Note: Constraints are stored as strings in the registry
-                        // The actual constraint checking is done at runtime
-                        builder
+                        // Note: Constraints are stored as strings in the registry,
+                        // with their source parsed again for checking at runtime
+                        let mut constraint_builder = builder
                             .add_constraint(&c.name, format!("{:?}", c.condition))
-                            .for_type(&primary_type)
-                            .done()?;
+                            .source(&source[c.span.start..c.span.end])
+                            .for_type(&primary_type);
+                        if c.modifiers.soft {
+                            constraint_builder = constraint_builder.soft();
+                        }
+                        constraint_builder.done()?;
                     }
                 }
                 OntologyDef::Rule(r) => {
@@ -171,7 +180,10 @@ impl Compiler {
                 .done()?;
         }
 
-        builder.build().map_err(CompileError::from)
+        builder
+            .build()
+            .map(|registry| registry.with_source(source))
+            .map_err(CompileError::from)
     }
 
     /// Add a node type to the registry builder.
@@ -250,6 +262,18 @@ impl Compiler {
                         // Readonly is enforced at SET time by the mutation executor
                         // No constraint needed - it's a mutation-time check
                     }
+                    AttrModifier::Searchable => {
+                        if resolved_type != "String" {
+                            return Err(CompileError::validation(
+                                format!(
+                                    "Attribute '{}' is [searchable] but has type {}, not String",
+                                    attr_def.name, resolved_type
+                                ),
+                                attr_def.span,
+                            ));
+                        }
+                        attr = attr.searchable();
+                    }
                     AttrModifier::Default(expr) => {
                         // For now, only handle simple literals
                         if let Some(value) = expr_to_value(expr) {
@@ -344,6 +368,12 @@ impl Compiler {
 
     /// Resolve a type name, expanding type aliases to their base types.
     fn resolve_type_name(&self, type_name: &str) -> String {
+        if let Some(elem) = type_name
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            return format!("[{}]", self.resolve_type_name(elem));
+        }
         if let Some(alias) = self.type_aliases.get(type_name) {
             // Recursively resolve in case of chained aliases
             self.resolve_type_name(&alias.base_type)
@@ -404,6 +434,15 @@ impl Compiler {
                     AttrModifier::Readonly => {
                         attr = attr.readonly();
                     }
+                    AttrModifier::Searchable => {
+                        return Err(CompileError::validation(
+                            format!(
+                                "Edge attribute '{}' cannot be [searchable]: only node attributes are indexed",
+                                attr_def.name
+                            ),
+                            attr_def.span,
+                        ));
+                    }
                     AttrModifier::Default(expr) => {
                         if let Some(value) = expr_to_value(expr) {
                             attr = attr.with_default(value);
@@ -544,7 +583,19 @@ fn expr_to_value(expr: &mew_parser::Expr) -> Option<Value> {
             mew_parser::LiteralKind::String(s) => Some(Value::String(s.clone())),
             mew_parser::LiteralKind::Duration(ms) => Some(Value::Duration(*ms)),
             mew_parser::LiteralKind::Timestamp(ms) => Some(Value::Timestamp(*ms)),
+            mew_parser::LiteralKind::Bytes(b) => Some(Value::Bytes(b.clone())),
+            mew_parser::LiteralKind::Hash(h) => Some(Value::Hash(*h)),
         },
+        mew_parser::Expr::List(items, _) => items
+            .iter()
+            .map(expr_to_value)
+            .collect::<Option<Vec<_>>>()
+            .map(Value::List),
+        mew_parser::Expr::Map(entries, _) => entries
+            .iter()
+            .map(|(k, v)| expr_to_value(v).map(|v| (k.clone(), v)))
+            .collect::<Option<_>>()
+            .map(Value::Map),
         _ => None,
     }
 }
@@ -581,6 +632,51 @@ mod tests {
         assert_eq!(task_type.unwrap().name, "Task");
     }
 
+    #[test]
+    fn test_compile_map_attribute_with_default() {
+        // GIVEN
+        let source = r#"
+            node Service {
+                meta: Map = {region: "eu", replicas: 3},
+                payload: Json
+            }
+        "#;
+
+        // WHEN
+        let registry = compile(source).unwrap();
+
+        // THEN
+        let service = registry.get_type_by_name("Service").unwrap();
+        let meta = service.get_attr("meta").unwrap();
+        assert_eq!(meta.type_name, "Map");
+        let default = meta.default.as_ref().unwrap();
+        assert_eq!(default.get_key("region"), Some(&Value::from("eu")));
+        assert_eq!(default.get_key("replicas"), Some(&Value::Int(3)));
+        assert_eq!(service.get_attr("payload").unwrap().type_name, "Json");
+    }
+
+    #[test]
+    fn test_compile_list_attribute_types() {
+        // GIVEN
+        let source = r#"
+            type Label = String [length: 1..20]
+            node Bookmark {
+                tags: [String] = ["unread"],
+                labels: [Label]
+            }
+        "#;
+
+        // WHEN
+        let registry = compile(source).unwrap();
+
+        // THEN
+        let bookmark = registry.get_type_by_name("Bookmark").unwrap();
+        let tags = bookmark.get_attr("tags").unwrap();
+        assert_eq!(tags.type_name, "[String]");
+        assert_eq!(tags.default, Some(Value::List(vec![Value::from("unread")])));
+        assert_eq!(bookmark.get_attr("labels").unwrap().type_name, "[String]");
+    }
+
     #[test]
     fn test_compile_node_with_required_modifier() {
         // GIVEN
@@ -617,6 +713,25 @@ mod tests {
         assert!(attr.unique);
     }
 
+    #[test]
+    fn test_compile_node_with_searchable_modifier() {
+        // GIVEN a searchable string and a searchable integer
+        let source = r#"
+            node Bookmark {
+                body: String [searchable]
+            }
+        "#;
+        let invalid = "node Bookmark { stars: Int [searchable] }";
+
+        // WHEN
+        let registry = compile(source).unwrap();
+
+        // THEN only the string attribute can be indexed
+        let bookmark_type = registry.get_type_by_name("Bookmark").unwrap();
+        assert!(bookmark_type.get_attr("body").unwrap().searchable);
+        assert!(compile(invalid).is_err());
+    }
+
     #[test]
     fn test_compile_simple_edge_type() {
         // GIVEN
@@ -727,6 +842,10 @@ mod tests {
         let task_id = registry.get_type_id("Task").unwrap();
         let constraints = registry.get_constraints_for_type(task_id);
         assert!(!constraints.is_empty());
+        assert_eq!(
+            constraints[0].source.as_deref(),
+            Some("constraint priority_positive: t: Task => t.priority >= 0")
+        );
     }
 
     #[test]
@@ -1,21 +1,34 @@
 //! Constraint checking.
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use mew_core::{EdgeId, NodeId, Value};
 use mew_graph::Graph;
-use mew_pattern::{Evaluator, Matcher};
+use mew_parser::{parse_ontology, ConstraintDef as ConstraintDecl, OntologyDef, PatternElem};
+use mew_pattern::{Binding, Bindings, CompiledPattern, Evaluator, Matcher};
 use mew_registry::{ConstraintDef, Registry};
 
-use crate::error::ConstraintResult;
+use crate::error::{ConstraintError, ConstraintResult};
+use crate::touched::Touched;
 use crate::violation::{Violation, ViolationSeverity, Violations};
 
+/// A declared constraint, parsed from its ontology source.
+struct Declared {
+    decl: ConstraintDecl,
+    /// What its pattern and condition read.
+    reads: Touched,
+}
+
 /// Constraint checker.
 pub struct ConstraintChecker<'r, 'g> {
     registry: &'r Registry,
     graph: &'g Graph,
-    #[allow(dead_code)]
     matcher: Matcher<'r, 'g>,
-    #[allow(dead_code)]
     evaluator: Evaluator<'r>,
+    /// Declared constraints parsed so far, by constraint ID.
+    declared: RefCell<HashMap<u32, Rc<Declared>>>,
 }
 
 impl<'r, 'g> ConstraintChecker<'r, 'g> {
@@ -26,6 +39,7 @@ impl<'r, 'g> ConstraintChecker<'r, 'g> {
             graph,
             matcher: Matcher::new(registry, graph),
             evaluator: Evaluator::new(registry),
+            declared: RefCell::new(HashMap::new()),
         }
     }
 
@@ -155,6 +169,30 @@ impl<'r, 'g> ConstraintChecker<'r, 'g> {
         Ok(violations)
     }
 
+    /// Check the declared constraints a statement's writes can break, over
+    /// every match of their patterns.
+    pub fn check_touched(&self, touched: &Touched) -> ConstraintResult<Violations> {
+        let mut violations = Violations::new();
+        if touched.is_empty() {
+            return Ok(violations);
+        }
+
+        for constraint in self.registry.all_constraints() {
+            if constraint.deferred || constraint.source.is_none() {
+                continue;
+            }
+            let declared = self.declared(constraint)?;
+            if !touched.overlaps(&declared.reads, self.registry) {
+                continue;
+            }
+            if let Some(violation) = self.check_declared(constraint, None)? {
+                violations.push(violation);
+            }
+        }
+
+        Ok(violations)
+    }
+
     // ========== Internal checking methods ==========
 
     /// Check a single constraint.
@@ -185,11 +223,99 @@ impl<'r, 'g> ConstraintChecker<'r, 'g> {
             return self.check_no_self(constraint, edge_id);
         }
 
-        // For other conditions, we'd need to parse and evaluate them
-        // For now, return no violation (constraint passes)
+        // Declared constraints are evaluated from their source
+        if constraint.source.is_some() {
+            return self.check_declared(constraint, node_id);
+        }
+
+        Ok(None)
+    }
+
+    /// Check a declared constraint: its condition must hold for every match
+    /// of its pattern. With a node, only the matches whose first node
+    /// variable is that node are checked.
+    fn check_declared(
+        &self,
+        constraint: &ConstraintDef,
+        node_id: Option<NodeId>,
+    ) -> ConstraintResult<Option<Violation>> {
+        let declared = self.declared(constraint)?;
+        let pattern = &declared.decl.pattern;
+        let anchor = pattern.elements.iter().find_map(|elem| match elem {
+            PatternElem::Node(node) => Some(node.var.clone()),
+            PatternElem::Edge(_) => None,
+        });
+
+        // The anchor is bound up front instead of scanned
+        let mut initial = Bindings::new();
+        let mut elements = pattern.elements.clone();
+        let mut prebound = Vec::new();
+        if let (Some(node_id), Some(var)) = (node_id, &anchor) {
+            elements.retain(|elem| !matches!(elem, PatternElem::Node(node) if &node.var == var));
+            initial.insert(var.clone(), Binding::Node(node_id));
+            prebound.push(var.clone());
+        }
+        let mut compiled =
+            CompiledPattern::compile_with_prebound(&elements, self.registry, &prebound)?;
+        if let Some(where_clause) = &pattern.where_clause {
+            compiled = compiled.with_filter(where_clause.clone());
+        }
+
+        for row in self.matcher.find_all_with_initial(&compiled, initial)? {
+            if self
+                .evaluator
+                .eval_bool(&declared.decl.condition, &row, self.graph)?
+            {
+                continue;
+            }
+            let severity = if constraint.hard {
+                ViolationSeverity::Error
+            } else {
+                ViolationSeverity::Warning
+            };
+            let message = declared
+                .decl
+                .modifiers
+                .message
+                .clone()
+                .unwrap_or_else(|| "Constraint condition does not hold".to_string());
+            let mut violation = Violation::new(&constraint.name, severity, message);
+            if let Some(node) = anchor.as_ref().and_then(|var| row.get(var)?.as_node()) {
+                violation = violation.with_node(node);
+            }
+            return Ok(Some(violation));
+        }
+
         Ok(None)
     }
 
+    /// Parse a declared constraint's source, once per checker.
+    fn declared(&self, constraint: &ConstraintDef) -> ConstraintResult<Rc<Declared>> {
+        if let Some(declared) = self.declared.borrow().get(&constraint.id) {
+            return Ok(Rc::clone(declared));
+        }
+
+        let source = constraint.source.as_deref().unwrap_or_default();
+        let decl = parse_ontology(source)
+            .map_err(|e| ConstraintError::internal(e.to_string()))?
+            .into_iter()
+            .find_map(|def| match def {
+                OntologyDef::Constraint(decl) => Some(decl),
+                _ => None,
+            })
+            .ok_or_else(|| ConstraintError::unknown_constraint(&constraint.name))?;
+        let reads = Touched::read_by(
+            &decl.pattern.elements,
+            decl.pattern.where_clause.as_ref(),
+            &decl.condition,
+        );
+        let declared = Rc::new(Declared { decl, reads });
+        self.declared
+            .borrow_mut()
+            .insert(constraint.id, Rc::clone(&declared));
+        Ok(declared)
+    }
+
     /// Check required attribute constraint.
     fn check_required(
         &self,
@@ -384,6 +510,43 @@ mod tests {
         assert!(violations.is_empty());
     }
 
+    #[test]
+    fn test_check_declared_condition() {
+        // GIVEN a declared constraint on priority, one task keeping it and one breaking it
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Task")
+            .attr(AttrDef::new("priority", "Int"))
+            .done()
+            .unwrap();
+        builder
+            .add_constraint("priority_positive", "t.priority >= 0")
+            .source("constraint priority_positive: t: Task => t.priority >= 0")
+            .for_type("Task")
+            .done()
+            .unwrap();
+        let registry = builder.build().unwrap();
+        let mut graph = Graph::new();
+        let task_type_id = registry.get_type_id("Task").unwrap();
+        let good = graph.create_node(task_type_id, attrs! { "priority" => 1 });
+        let bad = graph.create_node(task_type_id, attrs! { "priority" => -1 });
+        let checker = ConstraintChecker::new(&registry, &graph);
+
+        // WHEN the tasks are checked, and a write of an unrelated attribute
+        let on_good = checker.check_node_immediate(good).unwrap();
+        let on_bad = checker.check_node_immediate(bad).unwrap();
+        let mut unrelated = Touched::default();
+        unrelated.attrs.insert("title".to_string());
+        let mut priority = Touched::default();
+        priority.attrs.insert("priority".to_string());
+
+        // THEN only the breaking task violates it, and only writes it reads re-check it
+        assert!(on_good.is_empty());
+        assert_eq!(on_bad.all()[0].node_id, Some(bad));
+        assert!(checker.check_touched(&unrelated).unwrap().is_empty());
+        assert_eq!(checker.check_touched(&priority).unwrap().all().len(), 1);
+    }
+
     #[test]
     fn test_violations_collection() {
         // GIVEN
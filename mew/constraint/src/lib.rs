@@ -5,13 +5,16 @@
 //! Responsibilities:
 //! - Check immediate constraints after mutations
 //! - Check deferred constraints at commit
+//! - Re-check declared constraints when a statement writes what they read
 //! - Distinguish hard (abort) vs soft (warn) constraints
 //! - Produce meaningful violation messages
 
 mod checker;
 mod error;
+mod touched;
 mod violation;
 
 pub use checker::ConstraintChecker;
 pub use error::{ConstraintError, ConstraintResult};
+pub use touched::Touched;
 pub use violation::{Violation, ViolationSeverity};
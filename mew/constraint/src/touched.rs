@@ -0,0 +1,245 @@
+//! What a statement can change, and what a declared constraint reads.
+//!
+//! A declared constraint only needs checking after a statement that changes
+//! something it reads: a node type it matches (directly or inside a
+//! subquery), an edge type it follows or an attribute it compares.
+
+use std::collections::HashSet;
+
+use mew_parser::{
+    Expr, MutationAction, PatternElem, SpawnStmt, Stmt, Target, TargetRef, UnlinkStmt,
+};
+use mew_registry::Registry;
+
+/// Node types, edge types and attributes a statement writes, or a
+/// constraint reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Touched {
+    /// The statement can change anything (KILL, IMPORT, ...).
+    pub everything: bool,
+    pub types: HashSet<String>,
+    pub edge_types: HashSet<String>,
+    pub attrs: HashSet<String>,
+}
+
+impl Touched {
+    /// What a statement can write. Queries write nothing.
+    pub fn of(stmt: &Stmt) -> Self {
+        let mut touched = Self::default();
+        match stmt {
+            Stmt::Spawn(spawn) => touched.spawn(spawn),
+            Stmt::Link(link) => touched.link(link),
+            Stmt::Unlink(unlink) => touched.unlink(unlink),
+            Stmt::Set(set) => touched
+                .attrs
+                .extend(set.assignments.iter().map(|a| a.name.clone())),
+            Stmt::MatchMutate(stmt) => touched.actions(&stmt.mutations),
+            Stmt::Unwind(stmt) => touched.actions(&stmt.mutations),
+            Stmt::Profile(profile) => touched = Self::of(&profile.statement),
+            Stmt::Call(call) => touched.attrs.extend(call.write.iter().cloned()),
+            Stmt::Kill(_) | Stmt::Import(_) | Stmt::Ack(_) | Stmt::Nack(_) => {
+                touched.everything = true
+            }
+            Stmt::Match(_)
+            | Stmt::Union(_)
+            | Stmt::MatchWalk(_)
+            | Stmt::Walk(_)
+            | Stmt::Inspect(_)
+            | Stmt::Txn(_)
+            | Stmt::Explain(_)
+            | Stmt::Watch(_)
+            | Stmt::Dump(_) => {}
+        }
+        touched
+    }
+
+    /// What a constraint's pattern, WHERE clause and condition read.
+    pub fn read_by(pattern: &[PatternElem], where_clause: Option<&Expr>, condition: &Expr) -> Self {
+        let mut touched = Self::default();
+        touched.pattern(pattern);
+        if let Some(expr) = where_clause {
+            touched.expr(expr);
+        }
+        touched.expr(condition);
+        touched
+    }
+
+    /// Whether nothing is written.
+    pub fn is_empty(&self) -> bool {
+        !self.everything
+            && self.types.is_empty()
+            && self.edge_types.is_empty()
+            && self.attrs.is_empty()
+    }
+
+    /// Add what another statement writes.
+    pub fn merge(&mut self, other: Touched) {
+        self.everything |= other.everything;
+        self.types.extend(other.types);
+        self.edge_types.extend(other.edge_types);
+        self.attrs.extend(other.attrs);
+    }
+
+    /// Whether writing `self` can change what `reads` read. A node of a
+    /// subtype is also a node of the types it extends.
+    pub fn overlaps(&self, reads: &Touched, registry: &Registry) -> bool {
+        let extends = |written: &String, read: &String| {
+            written == read
+                || match (registry.get_type_id(written), registry.get_type_id(read)) {
+                    (Some(sub), Some(super_type)) => registry.is_subtype(sub, super_type),
+                    _ => false,
+                }
+        };
+        self.everything
+            || self.edge_types.iter().any(|e| reads.edge_types.contains(e))
+            || self.attrs.iter().any(|a| reads.attrs.contains(a))
+            || self
+                .types
+                .iter()
+                .any(|t| reads.types.iter().any(|r| extends(t, r)))
+    }
+
+    fn actions(&mut self, actions: &[MutationAction]) {
+        for action in actions {
+            match action {
+                MutationAction::Spawn(spawn) => self.spawn(spawn),
+                MutationAction::Link(link) => self.link(link),
+                MutationAction::Set(set) => self
+                    .attrs
+                    .extend(set.assignments.iter().map(|a| a.name.clone())),
+                MutationAction::Kill(_) => self.everything = true,
+                MutationAction::Unlink(unlink) => self.unlink(unlink),
+            }
+        }
+    }
+
+    fn spawn(&mut self, spawn: &SpawnStmt) {
+        self.types
+            .extend(spawn.items.iter().map(|item| item.type_name.clone()));
+    }
+
+    fn link(&mut self, link: &mew_parser::LinkStmt) {
+        self.edge_types.insert(link.edge_type.clone());
+        for target in &link.targets {
+            if let TargetRef::InlineSpawn(spawn) = target {
+                self.spawn(spawn);
+            }
+        }
+    }
+
+    fn unlink(&mut self, unlink: &UnlinkStmt) {
+        match &unlink.target {
+            Target::EdgePattern { edge_type, .. } => {
+                self.edge_types.insert(edge_type.clone());
+            }
+            // The edge's type is only known once the target is resolved
+            Target::Var(_) | Target::Id(_) | Target::Pattern(_) => self.everything = true,
+        }
+    }
+
+    fn pattern(&mut self, pattern: &[PatternElem]) {
+        for elem in pattern {
+            match elem {
+                PatternElem::Node(node) => {
+                    self.types.insert(node.type_name.clone());
+                }
+                PatternElem::Edge(edge) => {
+                    self.edge_types.insert(edge.edge_type.clone());
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(_) | Expr::Var(..) | Expr::IdRef(..) | Expr::Param(..) => {}
+            Expr::AttrAccess(base, attr, _) => {
+                self.attrs.insert(attr.clone());
+                self.expr(base);
+            }
+            Expr::BinaryOp(_, left, right, _) | Expr::Index(left, right, _) => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::UnaryOp(_, operand, _) => self.expr(operand),
+            Expr::FnCall(call) => {
+                // An edge type used as a predicate: `friend_of(a, b)`
+                self.edge_types.insert(call.name.clone());
+                call.args.iter().for_each(|arg| self.expr(arg));
+                if let Some(filter) = &call.filter {
+                    self.expr(filter);
+                }
+            }
+            Expr::Exists(pattern, where_clause, _) | Expr::NotExists(pattern, where_clause, _) => {
+                self.pattern(pattern);
+                if let Some(expr) = where_clause {
+                    self.expr(expr);
+                }
+            }
+            Expr::Subquery(sq) => {
+                self.pattern(&sq.pattern);
+                for expr in sq.where_clause.iter().chain(&sq.projection) {
+                    self.expr(expr);
+                }
+            }
+            Expr::Quantifier(q) => {
+                self.expr(&q.list);
+                self.expr(&q.predicate);
+            }
+            Expr::List(items, _) => items.iter().for_each(|item| self.expr(item)),
+            Expr::Map(entries, _) => entries.iter().for_each(|(_, value)| self.expr(value)),
+            Expr::TypeCheck(base, type_name, _) => {
+                self.types.insert(type_name.clone());
+                self.expr(base);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mew_parser::{parse_ontology, parse_stmt, OntologyDef};
+
+    fn reads(source: &str) -> Touched {
+        match &parse_ontology(source).unwrap()[0] {
+            OntologyDef::Constraint(c) => Touched::read_by(
+                &c.pattern.elements,
+                c.pattern.where_clause.as_ref(),
+                &c.condition,
+            ),
+            other => panic!("expected a constraint, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_constraint_reads_inside_subqueries() {
+        // GIVEN a constraint counting matches of a subquery
+        let source = "constraint max_open: p: Project => \
+                      COUNT { t: Task, belongs_to(t, p) WHERE t.open = true } <= 2";
+
+        // WHEN its reads are collected
+        let reads = reads(source);
+
+        // THEN the subquery's types, edge types and attributes are included
+        assert!(reads.types.contains("Project") && reads.types.contains("Task"));
+        assert!(reads.edge_types.contains("belongs_to"));
+        assert!(reads.attrs.contains("open"));
+    }
+
+    #[test]
+    fn test_statement_writes() {
+        // GIVEN a LINK, a SET, an UNLINK by variable and a query
+        let link = parse_stmt("LINK belongs_to(t, p)").unwrap();
+        let set = parse_stmt("MATCH t: Task SET t.open = false").unwrap();
+        let unlink = parse_stmt("UNLINK e").unwrap();
+        let query = parse_stmt("MATCH t: Task RETURN t").unwrap();
+
+        // WHEN their writes are collected
+        // THEN each names what it can change
+        assert!(Touched::of(&link).edge_types.contains("belongs_to"));
+        assert!(Touched::of(&set).attrs.contains("open"));
+        assert!(Touched::of(&unlink).everything);
+        assert!(Touched::of(&query).is_empty());
+    }
+}
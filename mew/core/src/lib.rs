@@ -4,7 +4,11 @@
 //! - Identity types (NodeId, EdgeId, EntityId)
 //! - Type identifiers (TypeId, EdgeTypeId, AttrId)
 //! - Value types (the Value enum with all scalar and reference types)
+//! - Vector similarity metrics
+//! - Spatial points
 //! - Entity structures (Node, Edge)
+//! - Calendar helpers for timestamps
+//! - Full-text tokenizing and relevance
 //! - Common error types
 //! - Shared error messages
 
@@ -12,9 +16,15 @@ mod entity;
 mod error;
 mod id;
 pub mod messages;
+mod spatial;
+pub mod text;
+pub mod time;
 mod value;
+mod vector;
 
 pub use entity::*;
 pub use error::*;
 pub use id::*;
+pub use spatial::*;
 pub use value::*;
+pub use vector::*;
@@ -15,6 +15,9 @@ pub const ERR_SET_REQUIRES_NODE: &str = "SET requires a node target";
 /// Error: KILL requires a node target.
 pub const ERR_KILL_REQUIRES_NODE: &str = "KILL requires a node target";
 
+/// Error: UNWIND requires a list.
+pub const ERR_UNWIND_REQUIRES_LIST: &str = "UNWIND requires a list";
+
 /// Error: UNLINK requires an edge target.
 pub const ERR_UNLINK_REQUIRES_EDGE: &str = "UNLINK requires an edge target";
 
@@ -0,0 +1,105 @@
+//! Spatial points.
+//!
+//! Points are 2D or 3D Euclidean coordinates stored in `Value::Point`.
+//! Points of different dimensionality are not comparable: every spatial
+//! predicate between them is undefined (None).
+
+use std::fmt;
+
+/// A 2D or 3D point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    /// Third coordinate, present for 3D points.
+    pub z: Option<f64>,
+}
+
+impl Point {
+    /// Create a 2D point.
+    pub fn new_2d(x: f64, y: f64) -> Self {
+        Self { x, y, z: None }
+    }
+
+    /// Create a 3D point.
+    pub fn new_3d(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z: Some(z) }
+    }
+
+    /// Number of dimensions (2 or 3).
+    pub fn dims(&self) -> usize {
+        if self.z.is_some() {
+            3
+        } else {
+            2
+        }
+    }
+
+    /// Coordinates padded to three dimensions (2D points lie on z = 0).
+    pub fn coords(&self) -> [f64; 3] {
+        [self.x, self.y, self.z.unwrap_or(0.0)]
+    }
+
+    /// Euclidean distance. None if the dimensions differ.
+    pub fn distance(&self, other: &Point) -> Option<f64> {
+        if self.dims() != other.dims() {
+            return None;
+        }
+        let (a, b) = (self.coords(), other.coords());
+        let sum: f64 = a.iter().zip(&b).map(|(p, q)| (p - q) * (p - q)).sum();
+        Some(sum.sqrt())
+    }
+
+    /// Check whether this point lies in the box spanned by two opposite corners
+    /// (inclusive). None if the dimensions differ.
+    pub fn within_box(&self, corner_a: &Point, corner_b: &Point) -> Option<bool> {
+        if self.dims() != corner_a.dims() || self.dims() != corner_b.dims() {
+            return None;
+        }
+        let (p, a, b) = (self.coords(), corner_a.coords(), corner_b.coords());
+        Some((0..3).all(|i| p[i] >= a[i].min(b[i]) && p[i] <= a[i].max(b[i])))
+    }
+
+    /// Check whether this point lies within `radius` of `center` (inclusive).
+    /// None if the dimensions differ.
+    pub fn within_radius(&self, center: &Point, radius: f64) -> Option<bool> {
+        self.distance(center).map(|d| d <= radius)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.z {
+            Some(z) => write!(f, "point({}, {}, {})", self.x, self.y, z),
+            None => write!(f, "point({}, {})", self.x, self.y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_predicates() {
+        // GIVEN
+        let origin = Point::new_2d(0.0, 0.0);
+        let p = Point::new_2d(3.0, 4.0);
+        let p3 = Point::new_3d(3.0, 4.0, 0.0);
+
+        // THEN
+        assert_eq!(origin.distance(&p), Some(5.0));
+        assert_eq!(p.within_radius(&origin, 5.0), Some(true));
+        assert_eq!(p.within_radius(&origin, 4.9), Some(false));
+        assert_eq!(
+            p.within_box(&Point::new_2d(5.0, 5.0), &Point::new_2d(0.0, 0.0)),
+            Some(true)
+        );
+        assert_eq!(
+            p.within_box(&Point::new_2d(0.0, 0.0), &Point::new_2d(2.0, 5.0)),
+            Some(false)
+        );
+        assert_eq!(origin.distance(&p3), None);
+        assert_eq!(p3.to_string(), "point(3, 4, 0)");
+    }
+}
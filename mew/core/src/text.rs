@@ -0,0 +1,80 @@
+//! Full-text matching.
+//!
+//! Text is split into lowercase words: maximal runs of alphanumeric
+//! characters. A query matches a text when every query word occurs in it;
+//! its relevance is the share of the text's words that are query words, so
+//! more occurrences and shorter texts rank higher. The `search()` functions
+//! and the graph's text index both use these rules, so a search answered
+//! from the index scores exactly as one evaluated row by row.
+
+/// Split text into lowercase words, in order, keeping repeats.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// The distinct words of a query, in order of first occurrence.
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms = tokenize(query);
+    let mut seen = std::collections::HashSet::new();
+    terms.retain(|term| seen.insert(term.clone()));
+    terms
+}
+
+/// Relevance of a text of `len` words, given how often each query term
+/// occurs in it. None unless every term occurs; a query without words
+/// matches nothing.
+pub fn relevance(terms: &[String], len: usize, count: impl Fn(&str) -> usize) -> Option<f64> {
+    if terms.is_empty() {
+        return None;
+    }
+    let mut hits = 0;
+    for term in terms {
+        match count(term) {
+            0 => return None,
+            n => hits += n,
+        }
+    }
+    Some(hits as f64 / len as f64)
+}
+
+/// Relevance of `text` to `query`, or None when it does not match.
+pub fn search(text: &str, query: &str) -> Option<f64> {
+    let words = tokenize(text);
+    relevance(&query_terms(query), words.len(), |term| {
+        words.iter().filter(|word| *word == term).count()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_lowercases_words() {
+        // GIVEN text with punctuation, case and non-ASCII letters
+        // THEN it splits into lowercase alphanumeric words
+        assert_eq!(
+            tokenize("Rust, Graph-DBs & Café 2024!"),
+            vec!["rust", "graph", "dbs", "café", "2024"]
+        );
+        assert!(tokenize(" -- ").is_empty());
+    }
+
+    #[test]
+    fn test_search_requires_every_term() {
+        // GIVEN a two-word query
+        let query = "rust GRAPH";
+
+        // THEN texts with both words match, shorter and denser ones scoring higher
+        assert_eq!(search("Rust graph", query), Some(1.0));
+        assert_eq!(search("A graph store in Rust", query), Some(0.4));
+        assert_eq!(search("rust rust graph tips", query), Some(0.75));
+
+        // AND a missing word or an empty query matches nothing
+        assert_eq!(search("Rust tips", query), None);
+        assert_eq!(search("Rust graph", "  "), None);
+    }
+}
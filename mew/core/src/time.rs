@@ -0,0 +1,247 @@
+//! Calendar helpers for timestamps (milliseconds since the Unix epoch, UTC).
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Convert days since Unix epoch (1970-01-01) to (year, month, day).
+pub fn days_to_date(days: i32) -> (i32, u32, u32) {
+    // Algorithm from https://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u32; // day of era [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // year of era [0, 399]
+    let y = yoe as i32 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // day of year [0, 365]
+    let mp = (5 * doy + 2) / 153; // month offset [0, 11]
+    let d = doy - (153 * mp + 2) / 5 + 1; // day [1, 31]
+    let m = if mp < 10 { mp + 3 } else { mp - 9 }; // month [1, 12]
+    let y = if m <= 2 { y + 1 } else { y };
+    (y, m, d)
+}
+
+/// Convert a (year, month, day) date to days since Unix epoch.
+pub fn date_to_days(year: i32, month: u32, day: u32) -> i64 {
+    // Inverse of `days_to_date`, from the same source
+    let y = i64::from(if month <= 2 { year - 1 } else { year });
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400; // year of era [0, 399]
+    let mp = (i64::from(month) + 9) % 12; // month offset [0, 11], March = 0
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1; // day of year [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // day of era [0, 146096]
+    era * 146097 + doe - 719468
+}
+
+/// Number of days in a month of a year.
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Split a timestamp into its UTC date and time of day:
+/// (year, month, day, hour, minute, second, millisecond).
+pub fn components(ms: i64) -> (i32, u32, u32, u32, u32, u32, u32) {
+    let (year, month, day) = days_to_date(ms.div_euclid(MS_PER_DAY) as i32);
+    let ms_in_day = ms.rem_euclid(MS_PER_DAY) as u32;
+    let seconds = ms_in_day / 1000;
+    (
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+        ms_in_day % 1000,
+    )
+}
+
+/// Day of the week of a timestamp, from 0 (Sunday) to 6 (Saturday).
+pub fn day_of_week(ms: i64) -> u32 {
+    // 1970-01-01 was a Thursday
+    (ms.div_euclid(MS_PER_DAY) + 4).rem_euclid(7) as u32
+}
+
+/// Truncate a timestamp to the start of its `unit`: `"second"`, `"minute"`,
+/// `"hour"`, `"day"`, `"week"` (starting Monday), `"month"`, `"quarter"` or
+/// `"year"`. None for any other unit.
+pub fn truncate(ms: i64, unit: &str) -> Option<i64> {
+    let floor = |step: i64| ms - ms.rem_euclid(step);
+    let days = ms.div_euclid(MS_PER_DAY);
+    let (year, month, _) = days_to_date(days as i32);
+    let truncated = match unit.to_ascii_lowercase().as_str() {
+        "second" => floor(1000),
+        "minute" => floor(60_000),
+        "hour" => floor(3_600_000),
+        "day" => floor(MS_PER_DAY),
+        "week" => (days - (days + 3).rem_euclid(7)) * MS_PER_DAY,
+        "month" => date_to_days(year, month, 1) * MS_PER_DAY,
+        "quarter" => date_to_days(year, month - (month - 1) % 3, 1) * MS_PER_DAY,
+        "year" => date_to_days(year, 1, 1) * MS_PER_DAY,
+        _ => return None,
+    };
+    Some(truncated)
+}
+
+/// Parse an ISO 8601 timestamp: a date, optionally followed by `T` (or a
+/// space) and a time of day with optional seconds and fraction, then `Z` or
+/// an offset such as `+05:30`. Without an offset the time is UTC.
+/// None when the string is not such a timestamp or names no real date.
+pub fn parse_timestamp(s: &str) -> Option<i64> {
+    fn number<T: std::str::FromStr>(digits: &str, len: usize) -> Option<T> {
+        if digits.len() != len || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    }
+
+    let s = s.trim();
+    let (date, time) = match s.find(['T', 't', ' ']) {
+        Some(pos) => (&s[..pos], Some(&s[pos + 1..])),
+        None => (s, None),
+    };
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i32 = number(date_parts.next()?, 4)?;
+    let month: u32 = number(date_parts.next()?, 2)?;
+    let day: u32 = number(date_parts.next()?, 2)?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let mut ms = date_to_days(year, month, day) * MS_PER_DAY;
+
+    let Some(time) = time else {
+        return Some(ms);
+    };
+    let (clock, offset_ms) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else if let Some(pos) = time.rfind(['+', '-']) {
+        let sign = if time[pos..].starts_with('-') { -1 } else { 1 };
+        let offset = time[pos + 1..].replace(':', "");
+        let hours: i64 = number(offset.get(..2)?, 2)?;
+        let minutes: i64 = number(offset.get(2..)?, 2)?;
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+        (&time[..pos], sign * (hours * 3_600_000 + minutes * 60_000))
+    } else {
+        (time, 0)
+    };
+
+    let (clock, fraction) = match clock.split_once('.') {
+        Some((clock, fraction)) => (clock, Some(fraction)),
+        None => (clock, None),
+    };
+    let mut clock_parts = clock.split(':');
+    let hour: i64 = number(clock_parts.next()?, 2)?;
+    let minute: i64 = number(clock_parts.next()?, 2)?;
+    let second: i64 = match clock_parts.next() {
+        Some(second) => number(second, 2)?,
+        None if fraction.is_none() => 0,
+        None => return None,
+    };
+    if clock_parts.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let millis = match fraction {
+        // Digits beyond milliseconds are truncated
+        Some(fraction) => {
+            if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            format!("{:0<3}", &fraction[..fraction.len().min(3)])
+                .parse::<i64>()
+                .ok()?
+        }
+        None => 0,
+    };
+
+    ms += hour * 3_600_000 + minute * 60_000 + second * 1000 + millis - offset_ms;
+    Some(ms)
+}
+
+/// Format a timestamp as ISO 8601 in UTC with milliseconds, as accepted by
+/// timestamp literals: `2024-01-15T10:30:00.500Z`.
+pub fn format_timestamp(ms: i64) -> String {
+    let (year, month, day, hour, minute, second, millis) = components(ms);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hour, minute, second, millis
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_to_date() {
+        assert_eq!(days_to_date(0), (1970, 1, 1));
+        assert_eq!(days_to_date(19737), (2024, 1, 15));
+        assert_eq!(days_to_date(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_timestamp(1_705_314_600_500),
+            "2024-01-15T10:30:00.500Z"
+        );
+        assert_eq!(format_timestamp(-1), "1969-12-31T23:59:59.999Z");
+    }
+
+    #[test]
+    fn test_date_to_days() {
+        assert_eq!(date_to_days(1970, 1, 1), 0);
+        assert_eq!(date_to_days(2024, 1, 15), 19737);
+        assert_eq!(date_to_days(1969, 12, 31), -1);
+        assert_eq!(date_to_days(2000, 3, 1), date_to_days(2000, 2, 29) + 1);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("2024-01-15"), Some(1_705_276_800_000));
+        assert_eq!(
+            parse_timestamp("2024-01-15T10:30:00.500Z"),
+            Some(1_705_314_600_500)
+        );
+        assert_eq!(parse_timestamp("2024-01-15T10:30"), Some(1_705_314_600_000));
+        assert_eq!(
+            parse_timestamp("2024-01-15T16:00:00+05:30"),
+            Some(1_705_314_600_000)
+        );
+        assert_eq!(
+            parse_timestamp("2024-01-15 02:30:00.5-0800"),
+            Some(1_705_314_600_500)
+        );
+        assert_eq!(parse_timestamp("2023-02-29"), None);
+        assert_eq!(parse_timestamp("2024-01-15T24:00:00Z"), None);
+        assert_eq!(parse_timestamp("15/01/2024"), None);
+        assert_eq!(parse_timestamp("2024-01-15T10:30:00.Z"), None);
+    }
+
+    #[test]
+    fn test_truncate() {
+        // Wednesday 2024-05-15T10:30:45.250Z
+        let ms = parse_timestamp("2024-05-15T10:30:45.250Z").unwrap();
+        let at = |s: &str| parse_timestamp(s);
+        assert_eq!(truncate(ms, "second"), at("2024-05-15T10:30:45Z"));
+        assert_eq!(truncate(ms, "hour"), at("2024-05-15T10:00:00Z"));
+        assert_eq!(truncate(ms, "DAY"), at("2024-05-15"));
+        assert_eq!(truncate(ms, "week"), at("2024-05-13"));
+        assert_eq!(truncate(ms, "month"), at("2024-05-01"));
+        assert_eq!(truncate(ms, "quarter"), at("2024-04-01"));
+        assert_eq!(truncate(ms, "year"), at("2024-01-01"));
+        assert_eq!(truncate(-1, "day"), at("1969-12-31"));
+        assert_eq!(truncate(ms, "fortnight"), None);
+    }
+
+    #[test]
+    fn test_day_of_week() {
+        assert_eq!(day_of_week(0), 4);
+        assert_eq!(day_of_week(parse_timestamp("2024-05-12").unwrap()), 0);
+        assert_eq!(day_of_week(-1), 3);
+    }
+}
@@ -1,10 +1,13 @@
 //! Value types for MEW attributes.
 //!
 //! Values are the atomic data stored in node and edge attributes.
-//! MEW supports scalar types (String, Int, Float, Bool, Timestamp, Duration)
-//! and reference types (NodeRef, EdgeRef).
+//! MEW supports scalar types (String, Int, Float, Bool, Timestamp, Duration),
+//! binary types (Bytes, Hash), embeddings (Vector), spatial points (Point),
+//! reference types
+//! (NodeRef, EdgeRef) and structured types (List, Map).
 
-use crate::{EdgeId, NodeId};
+use crate::{EdgeId, NodeId, Point};
+use std::collections::BTreeMap;
 use std::fmt;
 
 /// A value that can be stored in an attribute.
@@ -24,12 +27,22 @@ pub enum Value {
     Timestamp(i64),
     /// Duration in milliseconds.
     Duration(i64),
+    /// Opaque binary data.
+    Bytes(Vec<u8>),
+    /// Content hash (32 bytes, SHA-256).
+    Hash([u8; 32]),
+    /// Dense embedding vector.
+    Vector(Vec<f32>),
+    /// 2D or 3D spatial point.
+    Point(Point),
     /// Reference to a node.
     NodeRef(NodeId),
     /// Reference to an edge.
     EdgeRef(EdgeId),
     /// List of values.
     List(Vec<Value>),
+    /// Structured record with string keys, kept in key order.
+    Map(BTreeMap<String, Value>),
 }
 
 impl Value {
@@ -68,6 +81,16 @@ impl Value {
         matches!(self, Value::Duration(_))
     }
 
+    /// Returns true if this is a bytes value.
+    pub fn is_bytes(&self) -> bool {
+        matches!(self, Value::Bytes(_))
+    }
+
+    /// Returns true if this is a hash value.
+    pub fn is_hash(&self) -> bool {
+        matches!(self, Value::Hash(_))
+    }
+
     /// Returns true if this is a node reference.
     pub fn is_node_ref(&self) -> bool {
         matches!(self, Value::NodeRef(_))
@@ -78,6 +101,11 @@ impl Value {
         matches!(self, Value::EdgeRef(_))
     }
 
+    /// Returns true if this is a map value.
+    pub fn is_map(&self) -> bool {
+        matches!(self, Value::Map(_))
+    }
+
     /// Get as boolean if this is a Bool value.
     pub fn as_bool(&self) -> Option<bool> {
         match self {
@@ -126,6 +154,79 @@ impl Value {
         }
     }
 
+    /// Get as byte slice if this is a Bytes value.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Get as hash digest if this is a Hash value.
+    pub fn as_hash(&self) -> Option<&[u8; 32]> {
+        match self {
+            Value::Hash(h) => Some(h),
+            _ => None,
+        }
+    }
+
+    /// Get as vector components if this is a Vector value.
+    pub fn as_vector(&self) -> Option<&[f32]> {
+        match self {
+            Value::Vector(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Convert a Vector, or a List of numbers, to vector components.
+    /// Returns None for any other value.
+    pub fn to_vector(&self) -> Option<Vec<f32>> {
+        match self {
+            Value::Vector(v) => Some(v.clone()),
+            Value::List(items) => items
+                .iter()
+                .map(|item| match item {
+                    Value::Int(i) => Some(*i as f32),
+                    Value::Float(f) => Some(*f as f32),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+
+    /// Get as point if this is a Point value.
+    pub fn as_point(&self) -> Option<&Point> {
+        match self {
+            Value::Point(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Convert a Point, or a List of 2 or 3 numbers, to a point.
+    /// Returns None for any other value.
+    pub fn to_point(&self) -> Option<Point> {
+        match self {
+            Value::Point(p) => Some(*p),
+            Value::List(items) => {
+                let coords: Option<Vec<f64>> = items
+                    .iter()
+                    .map(|item| match item {
+                        Value::Int(i) => Some(*i as f64),
+                        Value::Float(f) => Some(*f),
+                        _ => None,
+                    })
+                    .collect();
+                match coords?.as_slice() {
+                    [x, y] => Some(Point::new_2d(*x, *y)),
+                    [x, y, z] => Some(Point::new_3d(*x, *y, *z)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// Get as node ID if this is a NodeRef value.
     pub fn as_node_ref(&self) -> Option<NodeId> {
         match self {
@@ -142,6 +243,33 @@ impl Value {
         }
     }
 
+    /// Get as map reference if this is a Map value.
+    pub fn as_map(&self) -> Option<&BTreeMap<String, Value>> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Look up a key in a Map value. Returns None for missing keys and non-maps.
+    pub fn get_key(&self, key: &str) -> Option<&Value> {
+        self.as_map().and_then(|m| m.get(key))
+    }
+
+    /// Check whether this map contains every entry of `other` (recursively for nested maps).
+    /// Returns false if either value is not a map.
+    pub fn contains_map(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Map(outer), Value::Map(inner)) => inner.iter().all(|(k, v)| {
+                outer.get(k).is_some_and(|ov| match (ov, v) {
+                    (Value::Map(_), Value::Map(_)) => ov.contains_map(v),
+                    _ => ov == v,
+                })
+            }),
+            _ => false,
+        }
+    }
+
     /// Returns the type name of this value.
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -152,9 +280,14 @@ impl Value {
             Value::String(_) => "String",
             Value::Timestamp(_) => "Timestamp",
             Value::Duration(_) => "Duration",
+            Value::Bytes(_) => "Bytes",
+            Value::Hash(_) => "Hash",
+            Value::Vector(_) => "Vector",
+            Value::Point(_) => "Point",
             Value::NodeRef(_) => "NodeRef",
             Value::EdgeRef(_) => "EdgeRef",
             Value::List(_) => "List",
+            Value::Map(_) => "Map",
         }
     }
 
@@ -172,6 +305,8 @@ impl Value {
             (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
             (Value::Timestamp(a), Value::Timestamp(b)) => a.cmp(b),
             (Value::Duration(a), Value::Duration(b)) => a.cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            (Value::Hash(a), Value::Hash(b)) => a.cmp(b),
             _ => Ordering::Equal,
         }
     }
@@ -211,6 +346,19 @@ impl fmt::Display for Value {
             Value::String(s) => write!(f, "\"{}\"", s),
             Value::Timestamp(t) => write!(f, "ts:{}", t),
             Value::Duration(d) => write!(f, "dur:{}", d),
+            Value::Bytes(b) => write!(f, "0x{}", to_hex(b)),
+            Value::Hash(h) => write!(f, "0h{}", to_hex(h)),
+            Value::Vector(v) => {
+                write!(f, "<")?;
+                for (i, x) in v.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", x)?;
+                }
+                write!(f, ">")
+            }
+            Value::Point(p) => write!(f, "{}", p),
             Value::NodeRef(id) => write!(f, "#{}", id),
             Value::EdgeRef(id) => write!(f, "#{}", id),
             Value::List(items) => {
@@ -223,10 +371,36 @@ impl fmt::Display for Value {
                 }
                 write!(f, "]")
             }
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
         }
     }
 }
 
+/// Encode bytes as lowercase hex.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decode a hex string (case-insensitive). Returns None on odd length or non-hex input.
+pub fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
 // Convenient From implementations
 impl From<bool> for Value {
     fn from(b: bool) -> Self {
@@ -276,6 +450,30 @@ impl From<EdgeId> for Value {
     }
 }
 
+impl From<Vec<u8>> for Value {
+    fn from(b: Vec<u8>) -> Self {
+        Value::Bytes(b)
+    }
+}
+
+impl From<Vec<f32>> for Value {
+    fn from(v: Vec<f32>) -> Self {
+        Value::Vector(v)
+    }
+}
+
+impl From<Point> for Value {
+    fn from(p: Point) -> Self {
+        Value::Point(p)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(m: BTreeMap<String, Value>) -> Self {
+        Value::Map(m)
+    }
+}
+
 /// Type alias for attribute storage.
 pub type Attributes = std::collections::HashMap<String, Value>;
 
@@ -335,4 +533,68 @@ mod tests {
         assert_eq!(attrs.get("age"), Some(&Value::Int(30)));
         assert_eq!(attrs.get("active"), Some(&Value::Bool(true)));
     }
+
+    #[test]
+    fn test_map_value() {
+        // GIVEN
+        let mut inner = BTreeMap::new();
+        inner.insert("region".to_string(), Value::from("eu-west"));
+        let mut outer = BTreeMap::new();
+        outer.insert("zone".to_string(), Value::Int(2));
+        outer.insert("meta".to_string(), Value::Map(inner.clone()));
+        let map = Value::Map(outer);
+
+        // THEN
+        assert!(map.is_map());
+        assert_eq!(map.type_name(), "Map");
+        assert_eq!(map.get_key("zone"), Some(&Value::Int(2)));
+        assert_eq!(map.get_key("missing"), None);
+        assert!(map.contains_map(&Value::Map(BTreeMap::new())));
+        let mut probe = BTreeMap::new();
+        probe.insert("meta".to_string(), Value::Map(inner));
+        assert!(map.contains_map(&Value::Map(probe)));
+        assert_eq!(map.to_string(), "{meta: {region: \"eu-west\"}, zone: 2}");
+    }
+
+    #[test]
+    fn test_binary_values() {
+        // GIVEN
+        let bytes = Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef]);
+        let hash = Value::Hash([0xab; 32]);
+
+        // THEN
+        assert!(bytes.is_bytes());
+        assert!(hash.is_hash());
+        assert_eq!(bytes.to_string(), "0xdeadbeef");
+        assert_eq!(hash.to_string(), format!("0h{}", "ab".repeat(32)));
+        assert_eq!(from_hex("DEADbeef"), Some(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+
+    #[test]
+    fn test_vector_value() {
+        // GIVEN
+        let vector = Value::Vector(vec![0.5, 1.0]);
+        let list = Value::List(vec![Value::Int(1), Value::Float(0.5)]);
+
+        // THEN
+        assert_eq!(vector.type_name(), "Vector");
+        assert_eq!(vector.to_string(), "<0.5, 1>");
+        assert_eq!(list.to_vector(), Some(vec![1.0, 0.5]));
+        assert_eq!(Value::List(vec![Value::from("x")]).to_vector(), None);
+    }
+
+    #[test]
+    fn test_point_value() {
+        // GIVEN
+        let point = Value::Point(Point::new_2d(1.0, 2.5));
+        let list = Value::List(vec![Value::Int(1), Value::Float(2.0), Value::Int(3)]);
+
+        // THEN
+        assert_eq!(point.type_name(), "Point");
+        assert_eq!(point.to_string(), "point(1, 2.5)");
+        assert_eq!(list.to_point(), Some(Point::new_3d(1.0, 2.0, 3.0)));
+        assert_eq!(Value::List(vec![Value::Int(1)]).to_point(), None);
+    }
 }
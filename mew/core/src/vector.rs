@@ -0,0 +1,103 @@
+//! Vector similarity metrics.
+//!
+//! Vectors are dense `f32` embeddings stored in `Value::Vector`. Scores are
+//! computed in `f64` so that rankings are stable across metrics.
+
+/// A similarity metric for nearest-neighbour search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VectorMetric {
+    /// Cosine similarity (higher is closer).
+    #[default]
+    Cosine,
+    /// Euclidean distance (lower is closer).
+    L2,
+}
+
+impl VectorMetric {
+    /// Score two vectors under this metric. Returns None if the dimensions
+    /// differ or the score is undefined (e.g. cosine of a zero vector).
+    pub fn score(&self, a: &[f32], b: &[f32]) -> Option<f64> {
+        match self {
+            VectorMetric::Cosine => cosine_similarity(a, b),
+            VectorMetric::L2 => l2_distance(a, b),
+        }
+    }
+
+    /// Returns true if higher scores mean closer vectors.
+    pub fn higher_is_closer(&self) -> bool {
+        matches!(self, VectorMetric::Cosine)
+    }
+
+    /// Order two scores so that the closer one comes first.
+    pub fn cmp_scores(&self, a: f64, b: f64) -> std::cmp::Ordering {
+        let ord = a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal);
+        if self.higher_is_closer() {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+}
+
+/// Cosine similarity in [-1, 1]. None for mismatched dimensions or zero vectors.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f64> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let mut dot = 0.0f64;
+    let mut norm_a = 0.0f64;
+    let mut norm_b = 0.0f64;
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+    Some(dot / (norm_a.sqrt() * norm_b.sqrt()))
+}
+
+/// Euclidean distance. None for mismatched dimensions.
+pub fn l2_distance(a: &[f32], b: &[f32]) -> Option<f64> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let sum: f64 = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| {
+            let d = *x as f64 - *y as f64;
+            d * d
+        })
+        .sum();
+    Some(sum.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        // GIVEN
+        let a = [1.0, 0.0];
+        let b = [0.0, 1.0];
+
+        // THEN
+        assert_eq!(cosine_similarity(&a, &a), Some(1.0));
+        assert_eq!(cosine_similarity(&a, &b), Some(0.0));
+        assert_eq!(cosine_similarity(&a, &[0.0, 0.0]), None);
+        assert_eq!(l2_distance(&[0.0, 0.0], &[3.0, 4.0]), Some(5.0));
+        assert_eq!(l2_distance(&a, &[1.0]), None);
+        assert_eq!(
+            VectorMetric::Cosine.cmp_scores(0.9, 0.1),
+            std::cmp::Ordering::Less
+        );
+        assert_eq!(
+            VectorMetric::L2.cmp_scores(0.9, 0.1),
+            std::cmp::Ordering::Greater
+        );
+    }
+}
@@ -1,42 +1,112 @@
 //! Core graph storage implementation.
 
-use crate::index::{AdjacencyIndex, AttributeIndex, EdgeTypeIndex, HigherOrderIndex, TypeIndex};
+use crate::index::{
+    AdjacencyIndex, ApproximateConfig, AttrValue, AttributeIndex, EdgeTypeIndex, HigherOrderIndex,
+    SpatialIndex, TextIndex, TypeIndex, VectorIndex,
+};
+use im::HashMap;
 use mew_core::{
-    Attributes, Edge, EdgeId, EdgeTypeId, EntityId, GraphError, GraphResult, Node, NodeId, TypeId,
-    Value,
+    Attributes, Edge, EdgeId, EdgeTypeId, EntityId, GraphError, GraphResult, Node, NodeId, Point,
+    TypeId, Value, VectorMetric,
 };
-use std::collections::HashMap;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 /// ID allocator for nodes and edges.
-#[derive(Debug, Default)]
+///
+/// A shared allocator keeps handing out unique IDs across every clone of the
+/// graph, so copies that diverge and are later merged never collide.
+#[derive(Debug)]
 struct IdAllocator {
-    next_node_id: u64,
-    next_edge_id: u64,
+    next_node_id: Arc<AtomicU64>,
+    next_edge_id: Arc<AtomicU64>,
+    shared: bool,
 }
 
 impl IdAllocator {
     fn new() -> Self {
         Self {
-            next_node_id: 1,
-            next_edge_id: 1,
+            next_node_id: Arc::new(AtomicU64::new(1)),
+            next_edge_id: Arc::new(AtomicU64::new(1)),
+            shared: false,
         }
     }
 
     fn alloc_node_id(&mut self) -> NodeId {
-        let id = NodeId::new(self.next_node_id);
-        self.next_node_id += 1;
-        id
+        NodeId::new(self.next_node_id.fetch_add(1, Ordering::Relaxed))
     }
 
     fn alloc_edge_id(&mut self) -> EdgeId {
-        let id = EdgeId::new(self.next_edge_id);
-        self.next_edge_id += 1;
-        id
+        EdgeId::new(self.next_edge_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Make sure `id` is never handed out again.
+    fn reserve_node_id(&mut self, id: NodeId) {
+        self.next_node_id.fetch_max(id.0 + 1, Ordering::Relaxed);
+    }
+
+    /// Make sure `id` is never handed out again.
+    fn reserve_edge_id(&mut self, id: EdgeId) {
+        self.next_edge_id.fetch_max(id.0 + 1, Ordering::Relaxed);
+    }
+}
+
+impl Clone for IdAllocator {
+    fn clone(&self) -> Self {
+        if self.shared {
+            return Self {
+                next_node_id: Arc::clone(&self.next_node_id),
+                next_edge_id: Arc::clone(&self.next_edge_id),
+                shared: true,
+            };
+        }
+        Self {
+            next_node_id: Arc::new(AtomicU64::new(self.next_node_id.load(Ordering::Relaxed))),
+            next_edge_id: Arc::new(AtomicU64::new(self.next_edge_id.load(Ordering::Relaxed))),
+            shared: false,
+        }
     }
 }
 
+/// IDs of the entities written (created, modified or deleted) while change
+/// tracking is on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSet {
+    pub nodes: BTreeSet<NodeId>,
+    pub edges: BTreeSet<EdgeId>,
+}
+
+impl ChangeSet {
+    /// Check if nothing was written.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+
+    /// Check if both change sets wrote a common entity.
+    pub fn overlaps(&self, other: &ChangeSet) -> bool {
+        !self.nodes.is_disjoint(&other.nodes) || !self.edges.is_disjoint(&other.edges)
+    }
+}
+
+/// Before-image of one graph write, enough to undo it.
+#[derive(Debug, Clone)]
+pub enum UndoRecord {
+    /// A node was created.
+    NodeCreated(NodeId),
+    /// A node was modified or deleted; holds its previous state.
+    NodeChanged(Node),
+    /// An edge was created.
+    EdgeCreated(EdgeId),
+    /// An edge was modified or deleted; holds its previous state.
+    EdgeChanged(Edge),
+}
+
 /// The in-memory graph storage.
-#[derive(Debug)]
+///
+/// Entities and indexes live in persistent maps: a clone shares them with
+/// the original and each side copies only the parts it later writes.
+#[derive(Debug, Clone)]
 pub struct Graph {
     /// Node storage
     nodes: HashMap<NodeId, Node>,
@@ -50,10 +120,20 @@ pub struct Graph {
     edge_type_index: EdgeTypeIndex,
     /// Attribute index
     attr_index: AttributeIndex,
+    /// Vector index
+    vector_index: VectorIndex,
+    /// Spatial index
+    spatial_index: SpatialIndex,
+    /// Full-text index
+    text_index: TextIndex,
     /// Adjacency index
     adj_index: AdjacencyIndex,
     /// Higher-order index
     ho_index: HigherOrderIndex,
+    /// Written entities, when change tracking is on
+    changes: Option<ChangeSet>,
+    /// Before-images of writes, when the undo journal is on
+    undo: Option<Vec<UndoRecord>>,
 }
 
 impl Default for Graph {
@@ -72,8 +152,13 @@ impl Graph {
             type_index: TypeIndex::new(),
             edge_type_index: EdgeTypeIndex::new(),
             attr_index: AttributeIndex::new(),
+            vector_index: VectorIndex::new(),
+            spatial_index: SpatialIndex::new(),
+            text_index: TextIndex::new(),
             adj_index: AdjacencyIndex::new(),
             ho_index: HigherOrderIndex::new(),
+            changes: None,
+            undo: None,
         }
     }
 
@@ -82,12 +167,16 @@ impl Graph {
     /// Create a new node with the given type and attributes.
     pub fn create_node(&mut self, type_id: TypeId, attributes: Attributes) -> NodeId {
         let id = self.id_alloc.alloc_node_id();
+        self.record_node(id);
         let node = Node::new(id, type_id, attributes);
 
         // Update indexes
         self.type_index.insert(type_id, id);
         for (attr_name, value) in &node.attributes {
             self.attr_index.insert(type_id, attr_name, value, id);
+            self.vector_index.insert(type_id, attr_name, value, id);
+            self.spatial_index.insert(type_id, attr_name, value, id);
+            self.text_index.insert(type_id, attr_name, value, id);
         }
 
         self.nodes.insert(id, node);
@@ -101,6 +190,9 @@ impl Graph {
 
     /// Get a mutable reference to a node by ID.
     pub fn get_node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        if self.nodes.contains_key(&id) {
+            self.record_node(id);
+        }
         self.nodes.get_mut(&id)
     }
 
@@ -118,12 +210,16 @@ impl Graph {
         }
 
         // Now remove the node
+        self.record_node(id);
         let node = self.nodes.remove(&id).unwrap();
 
         // Update indexes
         self.type_index.remove(type_id, id);
         for (attr_name, value) in &node.attributes {
             self.attr_index.remove(type_id, attr_name, value, id);
+            self.vector_index.remove(type_id, attr_name, value, id);
+            self.spatial_index.remove(type_id, attr_name, value, id);
+            self.text_index.remove(type_id, attr_name, value, id);
         }
 
         Ok(())
@@ -131,6 +227,9 @@ impl Graph {
 
     /// Set an attribute on a node.
     pub fn set_node_attr(&mut self, id: NodeId, attr_name: &str, value: Value) -> GraphResult<()> {
+        if self.nodes.contains_key(&id) {
+            self.record_node(id);
+        }
         let node = self
             .nodes
             .get_mut(&id)
@@ -140,10 +239,16 @@ impl Graph {
         // Remove old value from index
         if let Some(old_value) = node.attributes.get(attr_name) {
             self.attr_index.remove(type_id, attr_name, old_value, id);
+            self.vector_index.remove(type_id, attr_name, old_value, id);
+            self.spatial_index.remove(type_id, attr_name, old_value, id);
+            self.text_index.remove(type_id, attr_name, old_value, id);
         }
 
         // Add new value to index
         self.attr_index.insert(type_id, attr_name, &value, id);
+        self.vector_index.insert(type_id, attr_name, &value, id);
+        self.spatial_index.insert(type_id, attr_name, &value, id);
+        self.text_index.insert(type_id, attr_name, &value, id);
 
         // Update node
         node.set_attr(attr_name.to_string(), value);
@@ -177,6 +282,7 @@ impl Graph {
         }
 
         let id = self.id_alloc.alloc_edge_id();
+        self.record_edge(id);
         let edge = Edge::new(id, type_id, targets.clone(), attributes);
 
         // Update indexes
@@ -201,6 +307,9 @@ impl Graph {
 
     /// Get a mutable reference to an edge by ID.
     pub fn get_edge_mut(&mut self, id: EdgeId) -> Option<&mut Edge> {
+        if self.edges.contains_key(&id) {
+            self.record_edge(id);
+        }
         self.edges.get_mut(&id)
     }
 
@@ -219,6 +328,7 @@ impl Graph {
         }
 
         // Now remove the edge
+        self.record_edge(id);
         self.edges.remove(&id);
 
         // Update indexes
@@ -237,6 +347,9 @@ impl Graph {
 
     /// Set an attribute on an edge.
     pub fn set_edge_attr(&mut self, id: EdgeId, attr_name: &str, value: Value) -> GraphResult<()> {
+        if self.edges.contains_key(&id) {
+            self.record_edge(id);
+        }
         let edge = self
             .edges
             .get_mut(&id)
@@ -255,6 +368,7 @@ impl Graph {
     }
 
     /// Find nodes by attribute value (exact match).
+    /// A list attribute matches each of its elements.
     pub fn nodes_by_attr(
         &self,
         type_id: TypeId,
@@ -264,6 +378,12 @@ impl Graph {
         self.attr_index.find_exact(type_id, attr_name, value)
     }
 
+    /// Check whether a value can be looked up through the attribute index.
+    /// Values that are not indexed (e.g. Float) must be found by scanning.
+    pub fn is_attr_indexable(value: &Value) -> bool {
+        AttrValue::from_value(value).is_some()
+    }
+
     /// Find nodes by attribute range (integers only).
     pub fn nodes_by_attr_range(
         &self,
@@ -275,6 +395,89 @@ impl Graph {
         self.attr_index.find_range(type_id, attr_name, min, max)
     }
 
+    /// Find the `k` nodes of a type whose vector attribute is closest to `query`.
+    /// Results are ordered closest first and carry their metric score.
+    pub fn nearest_nodes(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        query: &[f32],
+        k: usize,
+        metric: VectorMetric,
+    ) -> Vec<(NodeId, f64)> {
+        self.vector_index
+            .nearest(type_id, attr_name, query, k, metric)
+    }
+
+    /// Serve nearest-neighbour lookups on a vector attribute from an approximate
+    /// (LSH) index instead of an exact scan. Existing vectors are indexed immediately.
+    pub fn enable_approximate_vector_index(
+        &mut self,
+        type_id: TypeId,
+        attr_name: &str,
+        config: ApproximateConfig,
+    ) {
+        self.vector_index
+            .enable_approximate(type_id, attr_name, config);
+    }
+
+    /// Find nodes of a type whose point attribute lies in the box spanned by
+    /// two opposite corners, in node ID order.
+    pub fn nodes_within_box(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        corner_a: &Point,
+        corner_b: &Point,
+    ) -> Vec<NodeId> {
+        self.spatial_index
+            .within_box(type_id, attr_name, corner_a, corner_b)
+    }
+
+    /// Find nodes of a type whose point attribute lies within `radius` of
+    /// `center`, in node ID order.
+    pub fn nodes_within_radius(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        center: &Point,
+        radius: f64,
+    ) -> Vec<NodeId> {
+        self.spatial_index
+            .within_radius(type_id, attr_name, center, radius)
+    }
+
+    /// Maintain a full-text index over a string attribute of a type,
+    /// indexing the values already stored. Without one, text searches on
+    /// the attribute must scan.
+    pub fn enable_text_index(&mut self, type_id: TypeId, attr_name: &str) {
+        if self.text_index.is_enabled(type_id, attr_name) {
+            return;
+        }
+        self.text_index.enable(type_id, attr_name);
+        for id in self.type_index.get(type_id) {
+            if let Some(value) = self
+                .nodes
+                .get(&id)
+                .and_then(|n| n.attributes.get(attr_name))
+            {
+                self.text_index.insert(type_id, attr_name, value, id);
+            }
+        }
+    }
+
+    /// Find nodes of a type whose string attribute matches a text query,
+    /// with their relevance, in node ID order. None when the attribute has
+    /// no text index.
+    pub fn search_nodes(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        query: &str,
+    ) -> Option<Vec<(NodeId, f64)>> {
+        self.text_index.search(type_id, attr_name, query)
+    }
+
     /// Find edges by type.
     pub fn edges_by_type(&self, type_id: EdgeTypeId) -> impl Iterator<Item = EdgeId> + '_ {
         self.edge_type_index.get(type_id)
@@ -324,6 +527,234 @@ impl Graph {
     pub fn all_edge_ids(&self) -> impl Iterator<Item = EdgeId> + '_ {
         self.edges.keys().copied()
     }
+
+    // ==================== Change Tracking ====================
+
+    /// Share the ID allocator with every future clone of this graph.
+    pub fn share_ids(&mut self) {
+        self.id_alloc.shared = true;
+    }
+
+    /// Start recording which entities are written.
+    pub fn track_changes(&mut self) {
+        self.changes.get_or_insert_with(ChangeSet::default);
+    }
+
+    /// Entities written since tracking started or the last `take_changes`.
+    /// None if tracking is off.
+    pub fn changes(&self) -> Option<&ChangeSet> {
+        self.changes.as_ref()
+    }
+
+    /// Take the recorded changes, leaving tracking on.
+    pub fn take_changes(&mut self) -> ChangeSet {
+        self.changes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Note that a node is about to be written.
+    fn record_node(&mut self, id: NodeId) {
+        if let Some(changes) = &mut self.changes {
+            changes.nodes.insert(id);
+        }
+        if let Some(undo) = &mut self.undo {
+            undo.push(match self.nodes.get(&id) {
+                Some(node) => UndoRecord::NodeChanged(node.clone()),
+                None => UndoRecord::NodeCreated(id),
+            });
+        }
+    }
+
+    /// Note that an edge is about to be written.
+    fn record_edge(&mut self, id: EdgeId) {
+        if let Some(changes) = &mut self.changes {
+            changes.edges.insert(id);
+        }
+        if let Some(undo) = &mut self.undo {
+            undo.push(match self.edges.get(&id) {
+                Some(edge) => UndoRecord::EdgeChanged(edge.clone()),
+                None => UndoRecord::EdgeCreated(id),
+            });
+        }
+    }
+
+    // ==================== Undo Journal ====================
+
+    /// Start journaling the before-image of every write.
+    pub fn start_undo_log(&mut self) {
+        self.undo.get_or_insert_with(Vec::new);
+    }
+
+    /// Stop journaling and drop the journal.
+    pub fn stop_undo_log(&mut self) {
+        self.undo = None;
+    }
+
+    /// Number of journaled writes; a mark to pass to `undo_to`.
+    pub fn undo_mark(&self) -> usize {
+        self.undo.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Forget journaled writes after `mark` without undoing them.
+    pub fn truncate_undo(&mut self, mark: usize) {
+        if let Some(undo) = &mut self.undo {
+            undo.truncate(mark);
+        }
+    }
+
+    /// Undo every journaled write after `mark`, newest first.
+    ///
+    /// Deleted entities come back with their original IDs and attributes;
+    /// cascaded deletions are journaled individually, so incident edges are
+    /// restored after their endpoints.
+    pub fn undo_to(&mut self, mark: usize) -> GraphResult<()> {
+        let Some(undo) = &mut self.undo else {
+            return Ok(());
+        };
+        let records = undo.split_off(mark.min(undo.len()));
+
+        // Reverting must not journal itself
+        let journal = self.undo.take();
+        let result = records
+            .into_iter()
+            .rev()
+            .try_for_each(|record| self.revert(record));
+        self.undo = journal;
+        result
+    }
+
+    fn revert(&mut self, record: UndoRecord) -> GraphResult<()> {
+        match record {
+            UndoRecord::NodeCreated(id) => {
+                if self.nodes.contains_key(&id) {
+                    self.delete_node(id)?;
+                }
+            }
+            UndoRecord::NodeChanged(node) => self.put_node(node),
+            UndoRecord::EdgeCreated(id) => {
+                if self.edges.contains_key(&id) {
+                    self.delete_edge(id)?;
+                }
+            }
+            UndoRecord::EdgeChanged(edge) => self.put_edge(edge)?,
+        }
+        Ok(())
+    }
+
+    /// Insert a node under its own ID, replacing any node with that ID.
+    pub fn put_node(&mut self, node: Node) {
+        let id = node.id;
+        self.id_alloc.reserve_node_id(id);
+        self.record_node(id);
+
+        if let Some(old) = self.nodes.remove(&id) {
+            self.type_index.remove(old.type_id, id);
+            for (attr_name, value) in &old.attributes {
+                self.attr_index.remove(old.type_id, attr_name, value, id);
+                self.vector_index.remove(old.type_id, attr_name, value, id);
+                self.spatial_index.remove(old.type_id, attr_name, value, id);
+                self.text_index.remove(old.type_id, attr_name, value, id);
+            }
+        }
+
+        self.type_index.insert(node.type_id, id);
+        for (attr_name, value) in &node.attributes {
+            self.attr_index.insert(node.type_id, attr_name, value, id);
+            self.vector_index.insert(node.type_id, attr_name, value, id);
+            self.spatial_index
+                .insert(node.type_id, attr_name, value, id);
+            self.text_index.insert(node.type_id, attr_name, value, id);
+        }
+
+        self.nodes.insert(id, node);
+    }
+
+    /// Insert an edge under its own ID, replacing any edge with that ID.
+    pub fn put_edge(&mut self, edge: Edge) -> GraphResult<()> {
+        for target in &edge.targets {
+            match target {
+                EntityId::Node(node_id) if !self.nodes.contains_key(node_id) => {
+                    return Err(GraphError::NodeNotFound(*node_id));
+                }
+                EntityId::Edge(edge_id) if !self.edges.contains_key(edge_id) => {
+                    return Err(GraphError::EdgeNotFound(*edge_id));
+                }
+                _ => {}
+            }
+        }
+
+        let id = edge.id;
+        self.id_alloc.reserve_edge_id(id);
+        self.record_edge(id);
+
+        if let Some(old) = self.edges.remove(&id) {
+            self.edge_type_index.remove(old.type_id, id);
+            self.adj_index.remove(id, old.type_id, &old.targets);
+            for target in &old.targets {
+                if let EntityId::Edge(target_edge_id) = target {
+                    self.ho_index.remove(*target_edge_id, id);
+                }
+            }
+        }
+
+        self.edge_type_index.insert(edge.type_id, id);
+        self.adj_index.insert(id, edge.type_id, &edge.targets);
+        for target in &edge.targets {
+            if let EntityId::Edge(target_edge_id) = target {
+                self.ho_index.insert(*target_edge_id, id);
+            }
+        }
+
+        self.edges.insert(id, edge);
+        Ok(())
+    }
+
+    /// Replay the entities named in `changes` from `source` onto this graph.
+    ///
+    /// Entities that exist in `source` are written as they are there; the
+    /// others are deleted. Fails if an edge would point at an entity this
+    /// graph no longer has, or if a deletion would take edges with it that
+    /// `source` never saw.
+    pub fn apply_changes(&mut self, source: &Graph, changes: &ChangeSet) -> GraphResult<()> {
+        for &id in &changes.nodes {
+            if let Some(node) = source.get_node(id) {
+                self.put_node(node.clone());
+            }
+        }
+        // Ascending IDs put edges before the higher-order edges about them
+        for &id in &changes.edges {
+            if let Some(edge) = source.get_edge(id) {
+                self.put_edge(edge.clone())?;
+            }
+        }
+
+        for &id in changes.edges.iter().rev() {
+            if source.get_edge(id).is_some() || !self.edges.contains_key(&id) {
+                continue;
+            }
+            if self
+                .ho_index
+                .edges_about(id)
+                .any(|e| !changes.edges.contains(&e))
+            {
+                return Err(GraphError::EdgeHasHigherOrder(id));
+            }
+            self.delete_edge(id)?;
+        }
+        for &id in &changes.nodes {
+            if source.get_node(id).is_some() || !self.nodes.contains_key(&id) {
+                continue;
+            }
+            if self.adj_index.edges_involving(id).next().is_some() {
+                return Err(GraphError::NodeHasEdges(id));
+            }
+            self.delete_node(id)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -578,6 +1009,50 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    // ========== TEST: find_nodes_by_hash_attribute ==========
+    #[test]
+    fn test_find_nodes_by_hash_attribute() {
+        // GIVEN graph with content nodes keyed by hash
+        let mut graph = Graph::new();
+        let id_a = graph.create_node(TypeId::new(1), attrs! { "hash" => Value::Hash([1; 32]) });
+        let _id_b = graph.create_node(TypeId::new(1), attrs! { "hash" => Value::Hash([2; 32]) });
+
+        // WHEN find_by_attr(type=1, attr="hash", value=[1; 32])
+        let result: Vec<NodeId> = graph
+            .nodes_by_attr(TypeId::new(1), "hash", &Value::Hash([1; 32]))
+            .collect();
+
+        // THEN returns [A] and the value is reported as indexable
+        assert_eq!(result, vec![id_a]);
+        assert!(Graph::is_attr_indexable(&Value::Hash([1; 32])));
+        assert!(!Graph::is_attr_indexable(&Value::Float(1.0)));
+    }
+
+    // ========== TEST: find_nodes_by_list_attribute_element ==========
+    #[test]
+    fn test_find_nodes_by_list_attribute_element() {
+        // GIVEN graph with nodes carrying tag lists
+        let mut graph = Graph::new();
+        let tags = |items: &[&str]| Value::List(items.iter().map(|t| Value::from(*t)).collect());
+        let id_a = graph.create_node(TypeId::new(1), attrs! { "tags" => tags(&["rust", "db"]) });
+        let id_b = graph.create_node(TypeId::new(1), attrs! { "tags" => tags(&["go"]) });
+        let rust = Value::String("rust".into());
+
+        // WHEN find_by_attr(type=1, attr="tags", value="rust")
+        let result: Vec<NodeId> = graph.nodes_by_attr(TypeId::new(1), "tags", &rust).collect();
+
+        // THEN returns [A]
+        assert_eq!(result, vec![id_a]);
+
+        // WHEN B's tags are replaced and A's are cleared
+        graph.set_node_attr(id_b, "tags", tags(&["rust"])).unwrap();
+        graph.set_node_attr(id_a, "tags", tags(&[])).unwrap();
+
+        // THEN the index follows the writes
+        let result: Vec<NodeId> = graph.nodes_by_attr(TypeId::new(1), "tags", &rust).collect();
+        assert_eq!(result, vec![id_b]);
+    }
+
     // ========== TEST: find_nodes_by_attribute_range ==========
     #[test]
     fn test_find_nodes_by_attribute_range() {
@@ -596,6 +1071,206 @@ mod tests {
         assert_eq!(result, vec![id_b]);
     }
 
+    // ========== TEST: nearest_nodes_by_vector ==========
+    #[test]
+    fn test_nearest_nodes_by_vector() {
+        // GIVEN graph with three embedded nodes
+        let mut graph = Graph::new();
+        let t = TypeId::new(1);
+        let east = graph.create_node(t, attrs! { "emb" => Value::Vector(vec![1.0, 0.0]) });
+        let north = graph.create_node(t, attrs! { "emb" => Value::Vector(vec![0.0, 1.0]) });
+        let west = graph.create_node(t, attrs! { "emb" => Value::Vector(vec![-1.0, 0.1]) });
+
+        // WHEN querying the 2 nearest to a north-east vector
+        let cosine = graph.nearest_nodes(t, "emb", &[0.6, 0.8], 2, VectorMetric::Cosine);
+        let l2 = graph.nearest_nodes(t, "emb", &[-0.9, 0.0], 1, VectorMetric::L2);
+
+        // THEN results are closest first
+        let ids: Vec<NodeId> = cosine.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![north, east]);
+        assert_eq!(l2[0].0, west);
+
+        // WHEN the node moves and the index is updated
+        graph
+            .set_node_attr(north, "emb", Value::Vector(vec![0.0, -1.0]))
+            .unwrap();
+        let cosine = graph.nearest_nodes(t, "emb", &[0.6, 0.8], 1, VectorMetric::Cosine);
+
+        // THEN the old position is forgotten
+        assert_eq!(cosine[0].0, east);
+    }
+
+    // ========== TEST: approximate_vector_index ==========
+    #[test]
+    fn test_approximate_vector_index() {
+        // GIVEN 200 nodes on the unit circle and an approximate index
+        let mut graph = Graph::new();
+        let t = TypeId::new(1);
+        let ids: Vec<NodeId> = (0..200)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / 200.0;
+                graph.create_node(
+                    t,
+                    attrs! { "emb" => Value::Vector(vec![angle.cos(), angle.sin(), 0.5]) },
+                )
+            })
+            .collect();
+        graph.enable_approximate_vector_index(t, "emb", ApproximateConfig::default());
+
+        // WHEN querying near the position of node 50
+        let angle = 50.0 * std::f32::consts::TAU / 200.0;
+        let result = graph.nearest_nodes(
+            t,
+            "emb",
+            &[angle.cos(), angle.sin(), 0.5],
+            3,
+            VectorMetric::Cosine,
+        );
+
+        // THEN the exact neighbour is found and results stay ranked
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].0, ids[50]);
+        assert!(result[0].1 >= result[1].1 && result[1].1 >= result[2].1);
+    }
+
+    // ========== TEST: spatial_index_box_and_radius ==========
+    #[test]
+    fn test_spatial_index_box_and_radius() {
+        // GIVEN a 30x30 grid of points, enough to split the R-tree several levels deep
+        let mut graph = Graph::new();
+        let t = TypeId::new(1);
+        let mut grid = Vec::new();
+        for x in 0..30 {
+            for y in 0..30 {
+                let p = Point::new_2d(x as f64, y as f64);
+                grid.push((graph.create_node(t, attrs! { "loc" => Value::Point(p) }), p));
+            }
+        }
+        let elevated = graph.create_node(
+            t,
+            attrs! { "loc" => Value::Point(Point::new_3d(5.0, 5.0, 0.0)) },
+        );
+        let brute = |grid: &[(NodeId, Point)], test: &dyn Fn(&Point) -> bool| {
+            let mut ids: Vec<NodeId> = grid
+                .iter()
+                .filter(|(_, p)| test(p))
+                .map(|(id, _)| *id)
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        // WHEN querying a box and a radius
+        let (a, b) = (Point::new_2d(12.5, 3.0), Point::new_2d(4.0, 7.0));
+        let center = Point::new_2d(10.0, 10.0);
+        let in_box = graph.nodes_within_box(t, "loc", &a, &b);
+        let in_radius = graph.nodes_within_radius(t, "loc", &center, 3.5);
+
+        // THEN results match a full scan and the 3D point is never matched
+        assert_eq!(in_box.len(), 45);
+        assert_eq!(
+            in_box,
+            brute(&grid, &|p| p.within_box(&a, &b) == Some(true))
+        );
+        assert_eq!(
+            in_radius,
+            brute(&grid, &|p| p.within_radius(&center, 3.5) == Some(true))
+        );
+        assert!(!in_box.contains(&elevated));
+
+        // WHEN half the points are deleted and one is moved
+        for (id, _) in grid.iter().step_by(2) {
+            graph.delete_node(*id).unwrap();
+        }
+        let remaining: Vec<(NodeId, Point)> = grid.iter().skip(1).step_by(2).copied().collect();
+        let (moved, _) = remaining[0];
+        graph
+            .set_node_attr(moved, "loc", Value::Point(Point::new_2d(10.0, 11.0)))
+            .unwrap();
+
+        // THEN the index reflects the changes
+        let in_radius = graph.nodes_within_radius(t, "loc", &center, 3.5);
+        let mut expected = brute(&remaining[1..], &|p| {
+            p.within_radius(&center, 3.5) == Some(true)
+        });
+        expected.push(moved);
+        expected.sort();
+        assert_eq!(in_radius, expected);
+    }
+
+    // ========== TEST: clone_is_isolated_from_later_writes ==========
+    #[test]
+    fn test_clone_is_isolated_from_later_writes() {
+        // GIVEN a graph with enough points to split the R-tree, and a copy of it
+        let mut graph = Graph::new();
+        let t = TypeId::new(1);
+        let mut ids = Vec::new();
+        for x in 0..20 {
+            for y in 0..20 {
+                let p = Point::new_2d(x as f64, y as f64);
+                ids.push(
+                    graph.create_node(t, attrs! { "loc" => Value::Point(p), "n" => x * 20 + y }),
+                );
+            }
+        }
+        let snapshot = graph.clone();
+        let center = Point::new_2d(5.0, 5.0);
+        let before = snapshot.nodes_within_radius(t, "loc", &center, 2.0);
+
+        // WHEN the original deletes, moves and updates nodes
+        graph.delete_node(ids[5 * 20 + 5]).unwrap();
+        graph
+            .set_node_attr(ids[0], "loc", Value::Point(Point::new_2d(5.0, 6.0)))
+            .unwrap();
+        graph.set_node_attr(ids[1], "n", Value::Int(-1)).unwrap();
+
+        // THEN the copy still sees the old data and the old index entries
+        assert_eq!(snapshot.node_count(), 400);
+        assert_eq!(snapshot.nodes_within_radius(t, "loc", &center, 2.0), before);
+        assert_eq!(
+            snapshot.get_node(ids[1]).unwrap().get_attr("n"),
+            Some(&Value::Int(1))
+        );
+        assert_eq!(snapshot.nodes_by_attr(t, "n", &Value::Int(-1)).count(), 0);
+        assert_eq!(graph.node_count(), 399);
+        assert_ne!(graph.nodes_within_radius(t, "loc", &center, 2.0), before);
+        assert_eq!(graph.nodes_by_attr(t, "n", &Value::Int(-1)).count(), 1);
+    }
+
+    // ========== TEST: text_index_follows_writes ==========
+    #[test]
+    fn test_text_index_follows_writes() {
+        // GIVEN a node stored before its attribute was indexed, and one after
+        let mut graph = Graph::new();
+        let t = TypeId::new(1);
+        let old = graph.create_node(t, attrs! { "body" => "Rust graph databases" });
+        assert_eq!(graph.search_nodes(t, "body", "rust"), None);
+        graph.enable_text_index(t, "body");
+        let new = graph.create_node(t, attrs! { "body" => "graph theory" });
+
+        // WHEN searching
+        // THEN both are found, each with its relevance
+        assert_eq!(
+            graph.search_nodes(t, "body", "Graph"),
+            Some(vec![(old, 1.0 / 3.0), (new, 0.5)])
+        );
+        assert_eq!(
+            graph.search_nodes(t, "body", "rust graph"),
+            Some(vec![(old, 2.0 / 3.0)])
+        );
+
+        // AND updates and deletions leave the index in step
+        graph
+            .set_node_attr(old, "body", Value::String("Python".into()))
+            .unwrap();
+        graph.delete_node(new).unwrap();
+        assert_eq!(graph.search_nodes(t, "body", "graph"), Some(vec![]));
+        assert_eq!(
+            graph.search_nodes(t, "body", "python"),
+            Some(vec![(old, 1.0)])
+        );
+    }
+
     // ========== TEST: find_edges_from_node ==========
     #[test]
     fn test_find_edges_from_node() {
@@ -702,4 +1377,63 @@ mod tests {
         expected.sort();
         assert_eq!(result, expected);
     }
+
+    // ========== TEST: apply_changes_merges_diverged_copies ==========
+    #[test]
+    fn test_apply_changes_merges_diverged_copies() {
+        // GIVEN a base graph with two nodes and two tracked copies of it
+        let mut base = Graph::new();
+        base.share_ids();
+        base.track_changes();
+        let alice = base.create_node(TypeId::new(1), attrs! { "name" => "Alice" });
+        let bob = base.create_node(TypeId::new(1), attrs! { "name" => "Bob" });
+        base.take_changes();
+        let mut left = base.clone();
+        let mut right = base.clone();
+
+        // WHEN each copy writes independently
+        let carol = left.create_node(TypeId::new(1), attrs! { "name" => "Carol" });
+        left.set_node_attr(alice, "name", Value::String("Alicia".into()))
+            .unwrap();
+        let dave = right.create_node(TypeId::new(1), attrs! { "name" => "Dave" });
+        right
+            .create_edge(
+                EdgeTypeId::new(1),
+                vec![EntityId::Node(bob), EntityId::Node(dave)],
+                attrs! {},
+            )
+            .unwrap();
+        let left_changes = left.take_changes();
+        let right_changes = right.take_changes();
+        let mut merged = base.clone();
+        merged.apply_changes(&left, &left_changes).unwrap();
+        merged.apply_changes(&right, &right_changes).unwrap();
+
+        // THEN IDs never collide and both write sets land
+        assert_ne!(carol, dave);
+        assert!(!left_changes.overlaps(&right_changes));
+        assert_eq!(merged.node_count(), 4);
+        assert_eq!(merged.edge_count(), 1);
+        assert_eq!(
+            merged.get_node(alice).unwrap().get_attr("name"),
+            Some(&Value::String("Alicia".into()))
+        );
+        assert_eq!(
+            merged
+                .nodes_by_attr(TypeId::new(1), "name", &Value::String("Dave".into()))
+                .count(),
+            1
+        );
+
+        // WHEN a copy that never saw the new edge deletes its endpoint
+        let mut stale = base.clone();
+        stale.delete_node(bob).unwrap();
+        let stale_changes = stale.take_changes();
+
+        // THEN the deletion is refused
+        assert!(matches!(
+            merged.apply_changes(&stale, &stale_changes),
+            Err(GraphError::NodeHasEdges(id)) if id == bob
+        ));
+    }
 }
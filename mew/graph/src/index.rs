@@ -1,10 +1,11 @@
 //! Indexes for efficient graph lookups.
 
-use mew_core::{EdgeId, EdgeTypeId, EntityId, NodeId, TypeId, Value};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use im::{HashMap, HashSet, OrdMap};
+use mew_core::{text, EdgeId, EdgeTypeId, EntityId, NodeId, Point, TypeId, Value, VectorMetric};
+use std::sync::Arc;
 
 /// Type index: TypeId -> Set<NodeId>
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct TypeIndex {
     index: HashMap<TypeId, HashSet<NodeId>>,
 }
@@ -36,7 +37,7 @@ impl TypeIndex {
 }
 
 /// Edge type index: EdgeTypeId -> Set<EdgeId>
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct EdgeTypeIndex {
     index: HashMap<EdgeTypeId, HashSet<EdgeId>>,
 }
@@ -83,6 +84,8 @@ pub enum AttrValue {
     Bool(bool),
     Int(i64),
     String(String),
+    Bytes(Vec<u8>),
+    Hash([u8; 32]),
 }
 
 impl AttrValue {
@@ -92,6 +95,8 @@ impl AttrValue {
             Value::Bool(b) => Some(AttrValue::Bool(*b)),
             Value::Int(i) => Some(AttrValue::Int(*i)),
             Value::String(s) => Some(AttrValue::String(s.clone())),
+            Value::Bytes(b) => Some(AttrValue::Bytes(b.clone())),
+            Value::Hash(h) => Some(AttrValue::Hash(*h)),
             // Float, Timestamp, Duration, NodeRef, EdgeRef are not indexed for exact match
             _ => None,
         }
@@ -99,12 +104,12 @@ impl AttrValue {
 }
 
 /// Attribute index: (TypeId, attr_name, value) -> Set<NodeId>
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct AttributeIndex {
     /// Exact match index
     exact: HashMap<AttrKey, HashSet<NodeId>>,
-    /// Range index for integers: (TypeId, attr_name) -> BTreeMap<i64, Set<NodeId>>
-    range: HashMap<(TypeId, String), BTreeMap<i64, HashSet<NodeId>>>,
+    /// Range index for integers: (TypeId, attr_name) -> OrdMap<i64, Set<NodeId>>
+    range: HashMap<(TypeId, String), OrdMap<i64, HashSet<NodeId>>>,
 }
 
 impl AttributeIndex {
@@ -113,6 +118,14 @@ impl AttributeIndex {
     }
 
     pub fn insert(&mut self, type_id: TypeId, attr_name: &str, value: &Value, node_id: NodeId) {
+        // A list attribute is indexed under each of its elements
+        if let Value::List(items) = value {
+            for item in items {
+                self.insert(type_id, attr_name, item, node_id);
+            }
+            return;
+        }
+
         // Add to exact index
         if let Some(attr_value) = AttrValue::from_value(value) {
             let key = AttrKey {
@@ -135,6 +148,13 @@ impl AttributeIndex {
     }
 
     pub fn remove(&mut self, type_id: TypeId, attr_name: &str, value: &Value, node_id: NodeId) {
+        if let Value::List(items) = value {
+            for item in items {
+                self.remove(type_id, attr_name, item, node_id);
+            }
+            return;
+        }
+
         // Remove from exact index
         if let Some(attr_value) = AttrValue::from_value(value) {
             let key = AttrKey {
@@ -206,7 +226,7 @@ impl AttributeIndex {
 }
 
 /// Adjacency index: NodeId -> { outbound: Map<EdgeTypeId, Set<EdgeId>>, inbound: ... }
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct AdjacencyIndex {
     /// Edges where the node is at position 0 (outbound for binary edges)
     outbound: HashMap<NodeId, HashMap<EdgeTypeId, HashSet<EdgeId>>>,
@@ -340,7 +360,7 @@ impl AdjacencyIndex {
 }
 
 /// Higher-order index: EdgeId -> Set<EdgeId> (edges that target this edge)
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct HigherOrderIndex {
     index: HashMap<EdgeId, HashSet<EdgeId>>,
 }
@@ -383,3 +403,674 @@ impl HigherOrderIndex {
             .unwrap_or(false)
     }
 }
+
+/// Parameters for an approximate vector index (random-hyperplane LSH).
+///
+/// Each table hashes a vector to a `bits`-wide signature; a query probes its
+/// own bucket and every bucket one bit away, then re-ranks the candidates
+/// exactly. More tables raise recall, more bits shrink buckets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApproximateConfig {
+    pub tables: usize,
+    pub bits: usize,
+    pub seed: u64,
+}
+
+impl Default for ApproximateConfig {
+    fn default() -> Self {
+        Self {
+            tables: 4,
+            bits: 8,
+            seed: 0x5eed,
+        }
+    }
+}
+
+/// Vector index: (TypeId, attr_name) -> embeddings, for nearest-neighbour search.
+#[derive(Debug, Clone, Default)]
+pub struct VectorIndex {
+    spaces: HashMap<(TypeId, String), VectorSpace>,
+}
+
+/// All vectors stored under one (type, attribute) pair.
+#[derive(Debug, Clone, Default)]
+struct VectorSpace {
+    vectors: HashMap<NodeId, Vec<f32>>,
+    lsh: Option<LshTables>,
+}
+
+impl VectorIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, type_id: TypeId, attr_name: &str, value: &Value, node_id: NodeId) {
+        if let Value::Vector(v) = value {
+            let space = self
+                .spaces
+                .entry((type_id, attr_name.to_string()))
+                .or_default();
+            if let Some(lsh) = &mut space.lsh {
+                lsh.insert(node_id, v);
+            }
+            space.vectors.insert(node_id, v.clone());
+        }
+    }
+
+    pub fn remove(&mut self, type_id: TypeId, attr_name: &str, value: &Value, node_id: NodeId) {
+        if let Value::Vector(v) = value {
+            let key = (type_id, attr_name.to_string());
+            if let Some(space) = self.spaces.get_mut(&key) {
+                if let Some(lsh) = &mut space.lsh {
+                    lsh.remove(node_id, v);
+                }
+                space.vectors.remove(&node_id);
+                if space.vectors.is_empty() && space.lsh.is_none() {
+                    self.spaces.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Build an approximate index over an attribute, including vectors already stored.
+    pub fn enable_approximate(
+        &mut self,
+        type_id: TypeId,
+        attr_name: &str,
+        config: ApproximateConfig,
+    ) {
+        let space = self
+            .spaces
+            .entry((type_id, attr_name.to_string()))
+            .or_default();
+        let mut lsh = LshTables::new(config);
+        for (node_id, v) in &space.vectors {
+            lsh.insert(*node_id, v);
+        }
+        space.lsh = Some(lsh);
+    }
+
+    /// The `k` closest vectors to `query`, closest first. Ties break on node ID.
+    pub fn nearest(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        query: &[f32],
+        k: usize,
+        metric: VectorMetric,
+    ) -> Vec<(NodeId, f64)> {
+        let Some(space) = self.spaces.get(&(type_id, attr_name.to_string())) else {
+            return Vec::new();
+        };
+
+        // Approximate search scores only the probed candidates; fall back to an
+        // exact scan when the buckets are too sparse to fill k results.
+        let candidates: Option<HashSet<NodeId>> = space
+            .lsh
+            .as_ref()
+            .and_then(|lsh| lsh.candidates(query))
+            .filter(|c| c.len() >= k);
+
+        let mut scored: Vec<(NodeId, f64)> = match candidates {
+            Some(ids) => ids
+                .into_iter()
+                .filter_map(|id| {
+                    let v = space.vectors.get(&id)?;
+                    metric.score(v, query).map(|s| (id, s))
+                })
+                .collect(),
+            None => space
+                .vectors
+                .iter()
+                .filter_map(|(id, v)| metric.score(v, query).map(|s| (*id, s)))
+                .collect(),
+        };
+
+        scored.sort_by(|(a_id, a), (b_id, b)| metric.cmp_scores(*a, *b).then(a_id.cmp(b_id)));
+        scored.truncate(k);
+        scored
+    }
+}
+
+/// Random-hyperplane LSH tables. Hyperplanes are drawn once the first
+/// vector fixes the dimension.
+#[derive(Debug, Clone)]
+struct LshTables {
+    config: ApproximateConfig,
+    /// planes[table][bit] is a hyperplane normal
+    planes: Vec<Vec<Vec<f32>>>,
+    /// buckets[table]: signature -> nodes
+    buckets: Vec<HashMap<u64, HashSet<NodeId>>>,
+}
+
+impl LshTables {
+    fn new(config: ApproximateConfig) -> Self {
+        Self {
+            config,
+            planes: Vec::new(),
+            buckets: (0..config.tables).map(|_| HashMap::new()).collect(),
+        }
+    }
+
+    fn dim(&self) -> Option<usize> {
+        self.planes.first().and_then(|t| t.first()).map(|p| p.len())
+    }
+
+    fn ensure_planes(&mut self, dim: usize) {
+        if self.dim().is_some() {
+            return;
+        }
+        // splitmix64: deterministic, so a rebuilt index hashes identically
+        let mut state = self.config.seed;
+        let mut next = || {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            (z >> 11) as f32 / (1u64 << 53) as f32 * 2.0 - 1.0
+        };
+        self.planes = (0..self.config.tables)
+            .map(|_| {
+                (0..self.config.bits)
+                    .map(|_| (0..dim).map(|_| next()).collect())
+                    .collect()
+            })
+            .collect();
+    }
+
+    fn signature(&self, table: usize, v: &[f32]) -> u64 {
+        self.planes[table]
+            .iter()
+            .enumerate()
+            .fold(0u64, |sig, (bit, plane)| {
+                let dot: f32 = plane.iter().zip(v).map(|(p, x)| p * x).sum();
+                if dot >= 0.0 {
+                    sig | (1 << bit)
+                } else {
+                    sig
+                }
+            })
+    }
+
+    fn insert(&mut self, node_id: NodeId, v: &[f32]) {
+        self.ensure_planes(v.len());
+        if self.dim() != Some(v.len()) {
+            return;
+        }
+        for table in 0..self.config.tables {
+            let sig = self.signature(table, v);
+            self.buckets[table].entry(sig).or_default().insert(node_id);
+        }
+    }
+
+    fn remove(&mut self, node_id: NodeId, v: &[f32]) {
+        if self.dim() != Some(v.len()) {
+            return;
+        }
+        for table in 0..self.config.tables {
+            let sig = self.signature(table, v);
+            if let Some(set) = self.buckets[table].get_mut(&sig) {
+                set.remove(&node_id);
+                if set.is_empty() {
+                    self.buckets[table].remove(&sig);
+                }
+            }
+        }
+    }
+
+    /// Nodes sharing a bucket with the query, or one bit away, in any table.
+    fn candidates(&self, query: &[f32]) -> Option<HashSet<NodeId>> {
+        if self.dim() != Some(query.len()) {
+            return None;
+        }
+        let mut result = HashSet::new();
+        for table in 0..self.config.tables {
+            let sig = self.signature(table, query);
+            let probes = std::iter::once(sig).chain((0..self.config.bits).map(|b| sig ^ (1 << b)));
+            for probe in probes {
+                if let Some(set) = self.buckets[table].get(&probe) {
+                    result.extend(set.iter().copied());
+                }
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Spatial index: (TypeId, attr_name) -> R-tree over point attributes.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    trees: HashMap<(TypeId, String), RTree>,
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, type_id: TypeId, attr_name: &str, value: &Value, node_id: NodeId) {
+        if let Value::Point(p) = value {
+            self.trees
+                .entry((type_id, attr_name.to_string()))
+                .or_default()
+                .insert(node_id, *p);
+        }
+    }
+
+    pub fn remove(&mut self, type_id: TypeId, attr_name: &str, value: &Value, node_id: NodeId) {
+        if let Value::Point(_) = value {
+            let key = (type_id, attr_name.to_string());
+            if let Some(tree) = self.trees.get_mut(&key) {
+                tree.remove(node_id);
+                if tree.points.is_empty() {
+                    self.trees.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Nodes whose point lies in the box spanned by two opposite corners,
+    /// in node ID order. Points of another dimensionality never match.
+    pub fn within_box(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        corner_a: &Point,
+        corner_b: &Point,
+    ) -> Vec<NodeId> {
+        self.search(
+            type_id,
+            attr_name,
+            BoundingBox::spanning(corner_a, corner_b),
+            |p| p.within_box(corner_a, corner_b) == Some(true),
+        )
+    }
+
+    /// Nodes whose point lies within `radius` of `center`, in node ID order.
+    /// Points of another dimensionality never match.
+    pub fn within_radius(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        center: &Point,
+        radius: f64,
+    ) -> Vec<NodeId> {
+        if radius.is_nan() || radius < 0.0 {
+            return Vec::new();
+        }
+        let c = center.coords();
+        let r = [
+            radius,
+            radius,
+            if center.z.is_some() { radius } else { 0.0 },
+        ];
+        let query = BoundingBox {
+            min: [c[0] - r[0], c[1] - r[1], c[2] - r[2]],
+            max: [c[0] + r[0], c[1] + r[1], c[2] + r[2]],
+        };
+        self.search(type_id, attr_name, query, |p| {
+            p.within_radius(center, radius) == Some(true)
+        })
+    }
+
+    /// Collect candidates from the tree, then keep those passing the exact test.
+    fn search(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        query: BoundingBox,
+        exact: impl Fn(&Point) -> bool,
+    ) -> Vec<NodeId> {
+        let Some(tree) = self.trees.get(&(type_id, attr_name.to_string())) else {
+            return Vec::new();
+        };
+        let mut candidates = Vec::new();
+        tree.root.search(&query, &mut candidates);
+        let mut result: Vec<NodeId> = candidates
+            .into_iter()
+            .filter(|id| tree.points.get(id).is_some_and(&exact))
+            .collect();
+        result.sort();
+        result
+    }
+}
+
+/// Text index: (TypeId, attr_name) -> inverted index over the words of a
+/// string attribute. Only attributes enabled with [`TextIndex::enable`] are
+/// indexed; others are found by scanning.
+#[derive(Debug, Clone, Default)]
+pub struct TextIndex {
+    fields: HashMap<(TypeId, String), TextField>,
+}
+
+/// The inverted index of one (type, attribute) pair.
+#[derive(Debug, Clone, Default)]
+struct TextField {
+    /// Word -> node -> occurrences of the word in the node's text
+    postings: HashMap<String, HashMap<NodeId, usize>>,
+    /// Node -> number of words in its text
+    lengths: HashMap<NodeId, usize>,
+}
+
+impl TextIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start indexing an attribute. Values already stored must be inserted
+    /// by the caller.
+    pub fn enable(&mut self, type_id: TypeId, attr_name: &str) {
+        self.fields
+            .entry((type_id, attr_name.to_string()))
+            .or_default();
+    }
+
+    pub fn is_enabled(&self, type_id: TypeId, attr_name: &str) -> bool {
+        self.fields.contains_key(&(type_id, attr_name.to_string()))
+    }
+
+    pub fn insert(&mut self, type_id: TypeId, attr_name: &str, value: &Value, node_id: NodeId) {
+        let Value::String(text) = value else {
+            return;
+        };
+        let Some(field) = self.fields.get_mut(&(type_id, attr_name.to_string())) else {
+            return;
+        };
+        let words = text::tokenize(text);
+        field.lengths.insert(node_id, words.len());
+        for word in words {
+            *field
+                .postings
+                .entry(word)
+                .or_default()
+                .entry(node_id)
+                .or_default() += 1;
+        }
+    }
+
+    pub fn remove(&mut self, type_id: TypeId, attr_name: &str, value: &Value, node_id: NodeId) {
+        let Value::String(text) = value else {
+            return;
+        };
+        let Some(field) = self.fields.get_mut(&(type_id, attr_name.to_string())) else {
+            return;
+        };
+        field.lengths.remove(&node_id);
+        for word in text::tokenize(text) {
+            if let Some(nodes) = field.postings.get_mut(&word) {
+                nodes.remove(&node_id);
+                if nodes.is_empty() {
+                    field.postings.remove(&word);
+                }
+            }
+        }
+    }
+
+    /// Nodes whose text matches `query`, with their relevance, in node ID
+    /// order. None when the attribute is not indexed.
+    pub fn search(
+        &self,
+        type_id: TypeId,
+        attr_name: &str,
+        query: &str,
+    ) -> Option<Vec<(NodeId, f64)>> {
+        let field = self.fields.get(&(type_id, attr_name.to_string()))?;
+        let terms = text::query_terms(query);
+
+        // Every match contains the rarest term: score only its nodes
+        let Some(rarest) = terms
+            .iter()
+            .map(|term| field.postings.get(term))
+            .min_by_key(|nodes| nodes.map_or(0, HashMap::len))
+        else {
+            return Some(Vec::new());
+        };
+        let mut result: Vec<(NodeId, f64)> = rarest
+            .into_iter()
+            .flat_map(HashMap::keys)
+            .filter_map(|&node_id| {
+                let len = field.lengths.get(&node_id).copied().unwrap_or(0);
+                let count = |term: &str| {
+                    field
+                        .postings
+                        .get(term)
+                        .and_then(|nodes| nodes.get(&node_id))
+                        .copied()
+                        .unwrap_or(0)
+                };
+                text::relevance(&terms, len, count).map(|score| (node_id, score))
+            })
+            .collect();
+        result.sort_by_key(|(node_id, _)| *node_id);
+        Some(result)
+    }
+}
+
+/// Maximum entries per R-tree node before it splits.
+const RTREE_MAX_ENTRIES: usize = 16;
+
+/// Axis-aligned bounding box. 2D points lie on the z = 0 plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BoundingBox {
+    min: [f64; 3],
+    max: [f64; 3],
+}
+
+impl BoundingBox {
+    fn point(p: &Point) -> Self {
+        let c = p.coords();
+        Self { min: c, max: c }
+    }
+
+    fn spanning(a: &Point, b: &Point) -> Self {
+        Self::point(a).union(&Self::point(b))
+    }
+
+    fn union(&self, other: &BoundingBox) -> Self {
+        let mut result = *self;
+        for i in 0..3 {
+            result.min[i] = result.min[i].min(other.min[i]);
+            result.max[i] = result.max[i].max(other.max[i]);
+        }
+        result
+    }
+
+    fn intersects(&self, other: &BoundingBox) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    /// Sum of edge lengths. Used instead of volume so that flat 2D boxes
+    /// still have a meaningful size.
+    fn margin(&self) -> f64 {
+        (0..3).map(|i| self.max[i] - self.min[i]).sum()
+    }
+
+    fn center(&self, axis: usize) -> f64 {
+        (self.min[axis] + self.max[axis]) / 2.0
+    }
+}
+
+/// An R-tree over the points of one (type, attribute) pair.
+#[derive(Debug, Clone)]
+struct RTree {
+    root: RNode,
+    points: HashMap<NodeId, Point>,
+}
+
+impl Default for RTree {
+    fn default() -> Self {
+        Self {
+            root: RNode::Leaf(Vec::new()),
+            points: HashMap::new(),
+        }
+    }
+}
+
+impl RTree {
+    fn insert(&mut self, node_id: NodeId, point: Point) {
+        if self.points.contains_key(&node_id) {
+            self.remove(node_id);
+        }
+        self.points.insert(node_id, point);
+        if let Some(sibling) = self.root.insert(BoundingBox::point(&point), node_id) {
+            // The root split: grow the tree by one level
+            let old_root = std::mem::replace(&mut self.root, RNode::Leaf(Vec::new()));
+            let old_bounds = old_root.bounds().expect("split node is not empty");
+            self.root = RNode::Inner(vec![(old_bounds, Arc::new(old_root)), sibling]);
+        }
+    }
+
+    fn remove(&mut self, node_id: NodeId) {
+        let Some(point) = self.points.remove(&node_id) else {
+            return;
+        };
+        self.root.remove(&BoundingBox::point(&point), node_id);
+        // Shrink the tree while the root has a single child
+        while let RNode::Inner(children) = &mut self.root {
+            match children.len() {
+                0 => self.root = RNode::Leaf(Vec::new()),
+                1 => self.root = Arc::unwrap_or_clone(children.pop().expect("one child").1),
+                _ => break,
+            }
+        }
+    }
+}
+
+/// An R-tree node. Leaves hold points; inner nodes hold child subtrees,
+/// each with the box covering everything beneath it. Children are shared
+/// between graph versions and copied only along the path a write touches.
+#[derive(Debug, Clone)]
+enum RNode {
+    Leaf(Vec<(BoundingBox, NodeId)>),
+    Inner(Vec<(BoundingBox, Arc<RNode>)>),
+}
+
+impl RNode {
+    fn bounds(&self) -> Option<BoundingBox> {
+        match self {
+            RNode::Leaf(entries) => entries.iter().map(|(b, _)| *b).reduce(|a, b| a.union(&b)),
+            RNode::Inner(entries) => entries.iter().map(|(b, _)| *b).reduce(|a, b| a.union(&b)),
+        }
+    }
+
+    /// Insert an entry. Returns a new sibling (with its bounds) if this node split.
+    fn insert(
+        &mut self,
+        bounds: BoundingBox,
+        node_id: NodeId,
+    ) -> Option<(BoundingBox, Arc<RNode>)> {
+        match self {
+            RNode::Leaf(entries) => {
+                entries.push((bounds, node_id));
+                if entries.len() <= RTREE_MAX_ENTRIES {
+                    return None;
+                }
+                let sibling = RNode::Leaf(split_entries(entries));
+                Some((
+                    sibling.bounds().expect("split half is not empty"),
+                    Arc::new(sibling),
+                ))
+            }
+            RNode::Inner(children) => {
+                // Descend into the child that grows least, preferring smaller children
+                let best = children
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, (a, _)), (_, (b, _))| {
+                        let grow_a = a.union(&bounds).margin() - a.margin();
+                        let grow_b = b.union(&bounds).margin() - b.margin();
+                        grow_a
+                            .total_cmp(&grow_b)
+                            .then(a.margin().total_cmp(&b.margin()))
+                    })
+                    .map(|(i, _)| i)
+                    .expect("inner node has children");
+
+                let (child_bounds, child) = &mut children[best];
+                let child = Arc::make_mut(child);
+                *child_bounds = child_bounds.union(&bounds);
+                if let Some((sibling_bounds, sibling)) = child.insert(bounds, node_id) {
+                    *child_bounds = child.bounds().expect("child is not empty");
+                    children.push((sibling_bounds, sibling));
+                }
+
+                if children.len() <= RTREE_MAX_ENTRIES {
+                    return None;
+                }
+                let sibling = RNode::Inner(split_entries(children));
+                Some((
+                    sibling.bounds().expect("split half is not empty"),
+                    Arc::new(sibling),
+                ))
+            }
+        }
+    }
+
+    /// Remove an entry located at `bounds`. Returns true if it was found.
+    /// Emptied children are dropped and the bounds on the path are tightened.
+    fn remove(&mut self, bounds: &BoundingBox, node_id: NodeId) -> bool {
+        match self {
+            RNode::Leaf(entries) => match entries.iter().position(|(_, id)| *id == node_id) {
+                Some(pos) => {
+                    entries.swap_remove(pos);
+                    true
+                }
+                None => false,
+            },
+            RNode::Inner(children) => {
+                for i in 0..children.len() {
+                    if !children[i].0.intersects(bounds) {
+                        continue;
+                    }
+                    if Arc::make_mut(&mut children[i].1).remove(bounds, node_id) {
+                        match children[i].1.bounds() {
+                            Some(b) => children[i].0 = b,
+                            None => {
+                                children.swap_remove(i);
+                            }
+                        }
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    fn search(&self, query: &BoundingBox, out: &mut Vec<NodeId>) {
+        match self {
+            RNode::Leaf(entries) => out.extend(
+                entries
+                    .iter()
+                    .filter(|(b, _)| b.intersects(query))
+                    .map(|(_, id)| *id),
+            ),
+            RNode::Inner(children) => {
+                for (b, child) in children {
+                    if b.intersects(query) {
+                        child.search(query, out);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Split an overfull node: sort entries along the axis where their centers
+/// spread most and move the upper half into a new node.
+fn split_entries<T>(entries: &mut Vec<(BoundingBox, T)>) -> Vec<(BoundingBox, T)> {
+    let spread = |axis: usize| {
+        let (lo, hi) = entries
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), (b, _)| {
+                (lo.min(b.center(axis)), hi.max(b.center(axis)))
+            });
+        hi - lo
+    };
+    let axis = (0..3)
+        .max_by(|a, b| spread(*a).total_cmp(&spread(*b)))
+        .unwrap_or(0);
+    entries.sort_by(|(a, _), (b, _)| a.center(axis).total_cmp(&b.center(axis)));
+    entries.split_off(entries.len() / 2)
+}
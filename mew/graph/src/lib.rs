@@ -4,6 +4,9 @@
 //! - Node and edge storage
 //! - Type index: Find nodes by type
 //! - Attribute index: Find nodes by attribute value or range
+//! - Vector index: Find nodes by nearest embedding (exact or approximate)
+//! - Spatial index: Find nodes by point attribute within a box or radius (R-tree)
+//! - Text index: Find nodes by the words of a string attribute (inverted index)
 //! - Adjacency index: Find edges from/to a node
 //! - Higher-order index: Find edges about an edge
 
@@ -11,3 +14,4 @@ mod graph;
 mod index;
 
 pub use graph::*;
+pub use index::ApproximateConfig;
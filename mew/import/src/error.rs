@@ -0,0 +1,74 @@
+//! Import error types.
+//!
+//! These errors abort a load. Problems with single rows are not errors:
+//! they are reported as [`RejectedRow`](crate::RejectedRow)s.
+
+use thiserror::Error;
+
+/// Import errors.
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// IO error while reading the source.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The source cannot be read as the given format.
+    #[error("invalid {format} input: {message}")]
+    InvalidInput {
+        format: &'static str,
+        message: String,
+    },
+
+    /// The file format could not be determined.
+    #[error("unknown file format for '{path}': use [format: csv] or [format: jsonl]")]
+    UnknownFormat { path: String },
+
+    /// Neither a node type nor an edge type.
+    #[error("unknown import target '{name}'")]
+    UnknownTarget { name: String },
+
+    /// The column mapping does not fit the target.
+    #[error("invalid import mapping: {message}")]
+    InvalidMapping { message: String },
+
+    /// The statement's source or options did not evaluate.
+    #[error("cannot evaluate import {what}: {message}")]
+    Eval { what: &'static str, message: String },
+
+    /// Constraint checking failed (not a violation: the check itself).
+    #[error("constraint check failed: {0}")]
+    Constraint(String),
+}
+
+impl ImportError {
+    pub fn invalid_input(format: &'static str, message: impl Into<String>) -> Self {
+        Self::InvalidInput {
+            format,
+            message: message.into(),
+        }
+    }
+
+    pub fn unknown_format(path: impl Into<String>) -> Self {
+        Self::UnknownFormat { path: path.into() }
+    }
+
+    pub fn unknown_target(name: impl Into<String>) -> Self {
+        Self::UnknownTarget { name: name.into() }
+    }
+
+    pub fn invalid_mapping(message: impl Into<String>) -> Self {
+        Self::InvalidMapping {
+            message: message.into(),
+        }
+    }
+
+    pub fn eval(what: &'static str, message: impl Into<String>) -> Self {
+        Self::Eval {
+            what,
+            message: message.into(),
+        }
+    }
+}
+
+/// Result type for import operations.
+pub type ImportResult<T> = Result<T, ImportError>;
@@ -0,0 +1,26 @@
+//! MEW Import
+//!
+//! Bulk loading of CSV and JSON Lines files.
+//!
+//! Responsibilities:
+//! - Read records from CSV (with a header row) and JSON Lines files
+//! - Map columns to node or edge attributes, converting cells to the
+//!   declared attribute types
+//! - Resolve edge endpoints through unique key attributes
+//! - Validate rows against the registry and insert them in batches, with
+//!   constraint checks deferred to the end of each batch
+//! - Report rejected rows with their reasons instead of failing the load
+
+mod error;
+mod loader;
+mod reader;
+mod report;
+mod spec;
+
+pub use error::{ImportError, ImportResult};
+pub use loader::BulkLoader;
+pub use reader::format_for_path;
+pub use report::{ImportReport, RejectedRow};
+pub use spec::{ColumnMap, EndpointKey, ImportSpec, DEFAULT_BATCH_SIZE};
+
+pub use mew_parser::ImportFormat;
@@ -0,0 +1,579 @@
+//! The bulk loader.
+//!
+//! Rows are validated and inserted one by one with the checks of SPAWN and
+//! LINK, except for registry constraints: those run once per batch over the
+//! entities the batch created, and the rows whose entities violate them
+//! are taken back out and rejected.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use mew_constraint::ConstraintChecker;
+use mew_core::{EdgeTypeId, EntityId, NodeId, TypeId, Value};
+use mew_graph::Graph;
+use mew_mutation::MutationExecutor;
+use mew_parser::{ImportFormat, ImportStmt};
+use mew_pattern::{Bindings, Evaluator};
+use mew_registry::Registry;
+
+use crate::error::{ImportError, ImportResult};
+use crate::reader::{cell_value, format_for_path, read_entries, Cell, Entry, Record};
+use crate::report::{ImportReport, RejectedRow};
+use crate::spec::{ColumnMap, EndpointKey, ImportSpec};
+
+/// Loads CSV and JSON Lines sources into a graph.
+pub struct BulkLoader<'r, 'g> {
+    registry: &'r Registry,
+    graph: &'g mut Graph,
+}
+
+/// What the rows of a load create.
+#[derive(Clone, Copy)]
+enum Target {
+    Node(TypeId),
+    Edge(EdgeTypeId),
+}
+
+/// An edge endpoint resolved against the registry.
+struct Endpoint<'s> {
+    spec: &'s EndpointKey,
+    /// The endpoint type and its subtypes.
+    type_ids: Vec<TypeId>,
+    /// Declared type of the key attribute.
+    key_type: String,
+}
+
+/// An import spec resolved against the registry.
+struct Plan<'s> {
+    target: Target,
+    /// Explicit column mappings; `None` maps columns by name.
+    columns: Option<&'s [ColumnMap]>,
+    endpoints: Vec<Endpoint<'s>>,
+}
+
+impl Plan<'_> {
+    fn is_endpoint(&self, column: &str) -> bool {
+        self.endpoints.iter().any(|e| e.spec.column == column)
+    }
+}
+
+impl<'r, 'g> BulkLoader<'r, 'g> {
+    /// Create a loader that inserts into `graph`.
+    pub fn new(registry: &'r Registry, graph: &'g mut Graph) -> Self {
+        Self { registry, graph }
+    }
+
+    /// Execute an IMPORT statement. Its source path and batch option are
+    /// evaluated with `bindings`.
+    pub fn execute(
+        &mut self,
+        stmt: &ImportStmt,
+        bindings: &Bindings,
+    ) -> ImportResult<ImportReport> {
+        let evaluator = Evaluator::new(self.registry);
+        let mut spec = ImportSpec::from_stmt(stmt);
+
+        let source = match evaluator.eval(&stmt.source, bindings, self.graph) {
+            Ok(Value::String(path)) => path,
+            Ok(other) => {
+                return Err(ImportError::eval(
+                    "source",
+                    format!("expected a file path, got {}", other.type_name()),
+                ))
+            }
+            Err(e) => return Err(ImportError::eval("source", e.to_string())),
+        };
+        if let Some(batch) = &stmt.options.batch {
+            match evaluator.eval(batch, bindings, self.graph) {
+                Ok(Value::Int(n)) if n > 0 => spec = spec.batch_size(n as usize),
+                Ok(other) => {
+                    return Err(ImportError::eval(
+                        "batch",
+                        format!("expected a positive Int, got {}", other),
+                    ))
+                }
+                Err(e) => return Err(ImportError::eval("batch", e.to_string())),
+            }
+        }
+
+        self.load_file(&spec, source)
+    }
+
+    /// Load a file. The format is the spec's, or else implied by the file
+    /// extension.
+    pub fn load_file(
+        &mut self,
+        spec: &ImportSpec,
+        path: impl AsRef<Path>,
+    ) -> ImportResult<ImportReport> {
+        let path = path.as_ref();
+        let format = spec
+            .format
+            .or_else(|| format_for_path(path))
+            .ok_or_else(|| ImportError::unknown_format(path.display().to_string()))?;
+        let file = File::open(path)?;
+        self.load(spec, format, BufReader::new(file))
+    }
+
+    /// Load the records of `input`.
+    pub fn load<R: Read>(
+        &mut self,
+        spec: &ImportSpec,
+        format: ImportFormat,
+        input: R,
+    ) -> ImportResult<ImportReport> {
+        let plan = self.plan(spec)?;
+        let mut report = ImportReport::default();
+        let mut batch = Vec::new();
+
+        read_entries(format, input, |entry| {
+            report.rows += 1;
+            match entry {
+                Entry::Malformed(row) => report.rejected.push(row),
+                Entry::Record(record) => match self.insert(&plan, &record) {
+                    Ok(Some(id)) => batch.push((record.line, id)),
+                    Ok(None) => {}
+                    Err(reason) => report.rejected.push(RejectedRow::new(record.line, reason)),
+                },
+            }
+            if batch.len() >= spec.batch_size {
+                self.finish_batch(&mut batch, &mut report)?;
+            }
+            Ok(())
+        })?;
+        self.finish_batch(&mut batch, &mut report)?;
+
+        // Constraint rejections are found after the rows around them
+        report.rejected.sort_by_key(|row| row.line);
+        Ok(report)
+    }
+
+    /// Resolve a spec against the registry.
+    fn plan<'s>(&self, spec: &'s ImportSpec) -> ImportResult<Plan<'s>> {
+        let registry = self.registry;
+        let target = if let Some(type_id) = registry.get_type_id(&spec.target) {
+            if registry.get_type(type_id).is_some_and(|t| t.is_abstract) {
+                return Err(ImportError::invalid_mapping(format!(
+                    "cannot import abstract type '{}'",
+                    spec.target
+                )));
+            }
+            if !spec.endpoints.is_empty() {
+                return Err(ImportError::invalid_mapping(format!(
+                    "node type '{}' has no endpoints",
+                    spec.target
+                )));
+            }
+            Target::Node(type_id)
+        } else if let Some(edge_type) = registry.get_edge_type_by_name(&spec.target) {
+            if spec.endpoints.len() != edge_type.params.len() {
+                return Err(ImportError::invalid_mapping(format!(
+                    "edge type '{}' has {} endpoints, {} given",
+                    spec.target,
+                    edge_type.params.len(),
+                    spec.endpoints.len()
+                )));
+            }
+            Target::Edge(edge_type.id)
+        } else {
+            return Err(ImportError::unknown_target(&spec.target));
+        };
+
+        let mut endpoints = Vec::with_capacity(spec.endpoints.len());
+        for endpoint in &spec.endpoints {
+            let type_id = registry.get_type_id(&endpoint.type_name).ok_or_else(|| {
+                ImportError::invalid_mapping(format!(
+                    "unknown endpoint type '{}'",
+                    endpoint.type_name
+                ))
+            })?;
+            let key = registry
+                .get_type_attr(type_id, &endpoint.key)
+                .ok_or_else(|| {
+                    ImportError::invalid_mapping(format!(
+                        "unknown key attribute '{}.{}'",
+                        endpoint.type_name, endpoint.key
+                    ))
+                })?;
+            if !key.unique {
+                return Err(ImportError::invalid_mapping(format!(
+                    "key attribute '{}.{}' is not unique",
+                    endpoint.type_name, endpoint.key
+                )));
+            }
+            let mut type_ids = vec![type_id];
+            type_ids.extend(registry.get_subtypes(type_id));
+            endpoints.push(Endpoint {
+                spec: endpoint,
+                type_ids,
+                key_type: key.type_name.clone(),
+            });
+        }
+
+        let plan = Plan {
+            target,
+            columns: (!spec.columns.is_empty()).then_some(spec.columns.as_slice()),
+            endpoints,
+        };
+        for column in &spec.columns {
+            if self.attr_type(&plan, &column.attr).is_none() {
+                return Err(ImportError::invalid_mapping(format!(
+                    "unknown attribute '{}' on '{}'",
+                    column.attr, spec.target
+                )));
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Declared type of an attribute of the plan's target.
+    fn attr_type(&self, plan: &Plan, attr: &str) -> Option<&'r str> {
+        let registry = self.registry;
+        match plan.target {
+            Target::Node(type_id) => registry.get_type_attr(type_id, attr),
+            Target::Edge(edge_type_id) => registry
+                .get_edge_type(edge_type_id)
+                .and_then(|e| e.attributes.get(attr)),
+        }
+        .map(|a| a.type_name.as_str())
+    }
+
+    /// Insert one record. Returns the created entity, `None` if an equal
+    /// symmetric edge already exists, or the reason the row is rejected.
+    fn insert(&mut self, plan: &Plan, record: &Record) -> Result<Option<EntityId>, String> {
+        let values = self.values(plan, record)?;
+        match plan.target {
+            Target::Node(type_id) => {
+                let type_name = &self.type_name(type_id);
+                let mut executor = MutationExecutor::new(self.registry, self.graph);
+                let node_id = executor
+                    .create_node(type_name, values)
+                    .map_err(|e| e.to_string())?;
+                Ok(Some(node_id.into()))
+            }
+            Target::Edge(edge_type_id) => {
+                let targets = plan
+                    .endpoints
+                    .iter()
+                    .map(|endpoint| self.resolve_endpoint(endpoint, record))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let edge_type = self
+                    .registry
+                    .get_edge_type(edge_type_id)
+                    .map(|e| e.name.as_str())
+                    .unwrap_or_default();
+                let mut executor = MutationExecutor::new(self.registry, self.graph);
+                let edge_id = executor
+                    .create_edge(edge_type, targets, values, false)
+                    .map_err(|e| e.to_string())?;
+                Ok(edge_id.map(EntityId::from))
+            }
+        }
+    }
+
+    /// The attribute values of a record.
+    fn values(&self, plan: &Plan, record: &Record) -> Result<Vec<(String, Value)>, String> {
+        let mut values = Vec::new();
+        let mut push = |column: &str, attr: &str, cell: &Cell| -> Result<(), String> {
+            let value = cell_value(cell, self.attr_type(plan, attr))
+                .map_err(|e| format!("column '{}': {}", column, e))?;
+            if let Some(value) = value {
+                values.push((attr.to_string(), value));
+            }
+            Ok(())
+        };
+        match plan.columns {
+            Some(columns) => {
+                for map in columns {
+                    if let Some(cell) = record.get(&map.column) {
+                        push(&map.column, &map.attr, cell)?;
+                    }
+                }
+            }
+            None => {
+                for (column, cell) in &record.fields {
+                    if !plan.is_endpoint(column) {
+                        push(column, column, cell)?;
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Find the node an endpoint column refers to.
+    fn resolve_endpoint(&self, endpoint: &Endpoint, record: &Record) -> Result<EntityId, String> {
+        let column = &endpoint.spec.column;
+        let key = match record.get(column) {
+            Some(cell) => cell_value(cell, Some(&endpoint.key_type))
+                .map_err(|e| format!("column '{}': {}", column, e))?,
+            None => None,
+        };
+        let key = key
+            .filter(|v| !v.is_null())
+            .ok_or_else(|| format!("missing endpoint column '{}'", column))?;
+
+        endpoint
+            .type_ids
+            .iter()
+            .find_map(|&type_id| self.find_node(type_id, &endpoint.spec.key, &key))
+            .map(EntityId::Node)
+            .ok_or_else(|| {
+                format!(
+                    "no {} with {} = {}",
+                    endpoint.spec.type_name, endpoint.spec.key, key
+                )
+            })
+    }
+
+    /// Find the node of a type whose attribute has the given value.
+    fn find_node(&self, type_id: TypeId, attr: &str, value: &Value) -> Option<NodeId> {
+        if Graph::is_attr_indexable(value) {
+            return self.graph.nodes_by_attr(type_id, attr, value).next();
+        }
+        self.graph.nodes_by_type(type_id).find(|&id| {
+            self.graph
+                .get_node(id)
+                .and_then(|n| n.get_attr(attr))
+                .is_some_and(|v| v == value)
+        })
+    }
+
+    fn type_name(&self, type_id: TypeId) -> String {
+        self.registry
+            .get_type(type_id)
+            .map(|t| t.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Check registry constraints on the entities of a batch. Rows whose
+    /// entity violates a hard constraint are removed and rejected.
+    fn finish_batch(
+        &mut self,
+        batch: &mut Vec<(u64, EntityId)>,
+        report: &mut ImportReport,
+    ) -> ImportResult<()> {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        for (_, id) in batch.iter() {
+            match id {
+                EntityId::Node(node_id) => nodes.push(*node_id),
+                EntityId::Edge(edge_id) => edges.push(*edge_id),
+            }
+        }
+
+        let violations = {
+            let checker = ConstraintChecker::new(self.registry, self.graph);
+            let constraint_error =
+                |e: mew_constraint::ConstraintError| ImportError::Constraint(e.to_string());
+            let mut violations = checker
+                .check_deferred(&nodes, &edges)
+                .map_err(constraint_error)?;
+            for &node_id in &nodes {
+                violations.merge(
+                    checker
+                        .check_node_immediate(node_id)
+                        .map_err(constraint_error)?,
+                );
+            }
+            for &edge_id in &edges {
+                violations.merge(
+                    checker
+                        .check_edge_immediate(edge_id)
+                        .map_err(constraint_error)?,
+                );
+            }
+            violations
+        };
+
+        for (line, id) in batch.drain(..) {
+            let violation = violations.errors().find(|v| match id {
+                EntityId::Node(node_id) => v.node_id == Some(node_id),
+                EntityId::Edge(edge_id) => v.edge_id == Some(edge_id),
+            });
+            if let Some(violation) = violation {
+                let reason = format!("{}: {}", violation.constraint_name, violation.message);
+                report.rejected.push(RejectedRow::new(line, reason));
+                let removed = match id {
+                    EntityId::Node(node_id) => self.graph.delete_node(node_id),
+                    EntityId::Edge(edge_id) => self.graph.delete_edge(edge_id),
+                };
+                removed.map_err(|e| ImportError::Constraint(e.to_string()))?;
+                continue;
+            }
+            match id {
+                EntityId::Node(_) => report.nodes_created += 1,
+                EntityId::Edge(_) => report.edges_created += 1,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mew_registry::{AttrDef, RegistryBuilder};
+
+    fn test_registry() -> Registry {
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Person")
+            .attr(AttrDef::new("name", "String").required())
+            .attr(AttrDef::new("email", "String").unique())
+            .attr(AttrDef::new("age", "Int"))
+            .attr(AttrDef::new("joined", "Timestamp"))
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("knows")
+            .param("a", "Person")
+            .param("b", "Person")
+            .attr(AttrDef::new("since", "Int"))
+            .done()
+            .unwrap();
+        builder
+            .add_constraint("no_self_knows", "no_self")
+            .for_edge_type("knows")
+            .deferred()
+            .done()
+            .unwrap();
+        builder.build().unwrap()
+    }
+
+    fn load(
+        registry: &Registry,
+        graph: &mut Graph,
+        spec: &ImportSpec,
+        format: ImportFormat,
+        input: &str,
+    ) -> ImportReport {
+        BulkLoader::new(registry, graph)
+            .load(spec, format, input.as_bytes())
+            .unwrap()
+    }
+
+    fn person(graph: &Graph, registry: &Registry, email: &str) -> Option<NodeId> {
+        let type_id = registry.get_type_id("Person").unwrap();
+        graph
+            .nodes_by_attr(type_id, "email", &Value::String(email.into()))
+            .next()
+    }
+
+    #[test]
+    fn test_load_nodes_rejects_invalid_rows() {
+        // GIVEN
+        let registry = test_registry();
+        let mut graph = Graph::new();
+        let spec = ImportSpec::new("Person")
+            .column("full_name", "name")
+            .column("email", "email")
+            .column("age", "age")
+            .column("joined", "joined");
+        let input = "full_name,email,age,joined,ignored\n\
+                     Ada,ada@x.io,36,2024-01-15,?\n\
+                     Bob,bob@x.io,old,,?\n\
+                     ,cy@x.io,7,,?\n\
+                     Ada Again,ada@x.io,,,?\n\
+                     Dee,dee@x.io,,,?\n";
+
+        // WHEN
+        let report = load(&registry, &mut graph, &spec, ImportFormat::Csv, input);
+
+        // THEN
+        assert_eq!(report.rows, 5);
+        assert_eq!(report.nodes_created, 2);
+        let lines: Vec<u64> = report.rejected.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+        assert!(report.rejected[0].reason.contains("column 'age'"));
+        assert_eq!(graph.node_count(), 2);
+        let ada = graph
+            .get_node(person(&graph, &registry, "ada@x.io").unwrap())
+            .unwrap();
+        assert_eq!(ada.get_attr("name"), Some(&Value::String("Ada".into())));
+        assert_eq!(
+            ada.get_attr("joined"),
+            Some(&Value::Timestamp(1_705_276_800_000))
+        );
+    }
+
+    #[test]
+    fn test_load_edges_by_unique_key_with_deferred_constraints() {
+        // GIVEN
+        let registry = test_registry();
+        let mut graph = Graph::new();
+        let people = "{\"name\": \"Ada\", \"email\": \"ada@x.io\"}\n\
+                      {\"name\": \"Bob\", \"email\": \"bob@x.io\"}\n";
+        load(
+            &registry,
+            &mut graph,
+            &ImportSpec::new("Person"),
+            ImportFormat::JsonLines,
+            people,
+        );
+        let spec = ImportSpec::new("knows")
+            .endpoint("from", "Person", "email")
+            .endpoint("to", "Person", "email")
+            .batch_size(2);
+        let input = "from,to,since\n\
+                     ada@x.io,bob@x.io,2020\n\
+                     ada@x.io,ada@x.io,2021\n\
+                     ada@x.io,zed@x.io,2022\n\
+                     bob@x.io,,2023\n";
+
+        // WHEN
+        let report = load(&registry, &mut graph, &spec, ImportFormat::Csv, input);
+
+        // THEN
+        assert_eq!(report.edges_created, 1);
+        assert_eq!(graph.edge_count(), 1);
+        let reasons: Vec<_> = report
+            .rejected
+            .iter()
+            .map(|r| (r.line, r.reason.as_str()))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (3, "no_self_knows: Self-referential edge not allowed"),
+                (4, "no Person with email = \"zed@x.io\""),
+                (5, "missing endpoint column 'to'"),
+            ]
+        );
+        let ada = person(&graph, &registry, "ada@x.io").unwrap();
+        let edge_id = graph.edges_from(ada, None).next().unwrap();
+        assert_eq!(
+            graph.get_edge(edge_id).unwrap().attributes.get("since"),
+            Some(&Value::Int(2020))
+        );
+    }
+
+    #[test]
+    fn test_invalid_mappings_abort_the_load() {
+        // GIVEN
+        let registry = test_registry();
+        let mut graph = Graph::new();
+        let mut loader = BulkLoader::new(&registry, &mut graph);
+        let mut load = |spec: ImportSpec| {
+            loader
+                .load(&spec, ImportFormat::Csv, "a\n1\n".as_bytes())
+                .unwrap_err()
+                .to_string()
+        };
+
+        // WHEN / THEN
+        assert!(load(ImportSpec::new("Robot")).contains("unknown import target"));
+        assert!(load(ImportSpec::new("Person").column("a", "nickname")).contains("nickname"));
+        assert!(load(
+            ImportSpec::new("knows")
+                .endpoint("a", "Person", "name")
+                .endpoint("b", "Person", "email")
+        )
+        .contains("'Person.name' is not unique"));
+        assert!(
+            load(ImportSpec::new("knows").endpoint("a", "Person", "email"))
+                .contains("has 2 endpoints, 1 given")
+        );
+    }
+}
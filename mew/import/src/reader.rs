@@ -0,0 +1,365 @@
+//! Reading records from CSV and JSON Lines sources.
+//!
+//! Cells are converted to values only once the attribute they feed is
+//! known: CSV cells are text and get parsed by the declared type, JSON
+//! values keep their own type unless they are strings.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use mew_core::Value;
+use mew_parser::{parse_literal, ImportFormat, LiteralKind};
+use serde_json::Value as Json;
+
+use crate::error::{ImportError, ImportResult};
+use crate::report::RejectedRow;
+
+/// Attribute types parsed from text; values of other types (Json, Vector,
+/// Point, ...) are read from CSV cells as JSON.
+const TEXT_TYPES: &[&str] = &[
+    "String",
+    "Int",
+    "Float",
+    "Bool",
+    "Timestamp",
+    "Duration",
+    "Bytes",
+    "Hash",
+];
+
+/// One cell of a record.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Cell {
+    /// A CSV field.
+    Text(String),
+    /// A JSON Lines field.
+    Json(Json),
+}
+
+/// A record read from the source.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Record {
+    pub line: u64,
+    pub fields: Vec<(String, Cell)>,
+}
+
+impl Record {
+    /// The cell of a column, if the record has it.
+    pub fn get(&self, column: &str) -> Option<&Cell> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == column)
+            .map(|(_, cell)| cell)
+    }
+}
+
+/// What the reader produces for each line: a record, or a row that cannot
+/// be read and is rejected as it stands.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Entry {
+    Record(Record),
+    Malformed(RejectedRow),
+}
+
+/// The format implied by a file extension: `.csv`, or `.jsonl`/`.ndjson`.
+pub fn format_for_path(path: &Path) -> Option<ImportFormat> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "csv" => Some(ImportFormat::Csv),
+        "jsonl" | "ndjson" => Some(ImportFormat::JsonLines),
+        _ => None,
+    }
+}
+
+/// Read every entry of `input`, in order.
+pub(crate) fn read_entries<R: Read>(
+    format: ImportFormat,
+    input: R,
+    f: impl FnMut(Entry) -> ImportResult<()>,
+) -> ImportResult<()> {
+    match format {
+        ImportFormat::Csv => read_csv(input, f),
+        ImportFormat::JsonLines => read_json_lines(input, f),
+    }
+}
+
+fn read_csv<R: Read>(input: R, mut f: impl FnMut(Entry) -> ImportResult<()>) -> ImportResult<()> {
+    let mut reader = csv::Reader::from_reader(input);
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| ImportError::invalid_input("CSV", e.to_string()))?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    for result in reader.records() {
+        let entry = match result {
+            Ok(record) => Entry::Record(Record {
+                line: record.position().map_or(0, |p| p.line()),
+                fields: headers
+                    .iter()
+                    .zip(record.iter())
+                    .map(|(h, v)| (h.clone(), Cell::Text(v.to_string())))
+                    .collect(),
+            }),
+            Err(e) if e.is_io_error() => {
+                return Err(ImportError::invalid_input("CSV", e.to_string()))
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                Entry::Malformed(RejectedRow::new(line, csv_error_reason(&e)))
+            }
+        };
+        f(entry)?;
+    }
+    Ok(())
+}
+
+fn csv_error_reason(error: &csv::Error) -> String {
+    match error.kind() {
+        csv::ErrorKind::UnequalLengths {
+            expected_len, len, ..
+        } => format!("expected {} fields, found {}", expected_len, len),
+        _ => error.to_string(),
+    }
+}
+
+fn read_json_lines<R: Read>(
+    input: R,
+    mut f: impl FnMut(Entry) -> ImportResult<()>,
+) -> ImportResult<()> {
+    for (index, line) in BufReader::new(input).lines().enumerate() {
+        let line = line?;
+        let number = index as u64 + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = match serde_json::from_str::<Json>(&line) {
+            Ok(Json::Object(fields)) => Entry::Record(Record {
+                line: number,
+                fields: fields
+                    .into_iter()
+                    .map(|(k, v)| (k, Cell::Json(v)))
+                    .collect(),
+            }),
+            Ok(_) => Entry::Malformed(RejectedRow::new(number, "expected a JSON object")),
+            Err(e) => Entry::Malformed(RejectedRow::new(number, format!("invalid JSON: {}", e))),
+        };
+        f(entry)?;
+    }
+    Ok(())
+}
+
+/// Convert a cell to a value for an attribute declared as `type_name`
+/// (`None` for an undeclared attribute).
+///
+/// An empty CSV cell gives `None` and leaves the attribute unset, so that
+/// defaults apply; JSON `null` is an explicit null.
+pub(crate) fn cell_value(cell: &Cell, type_name: Option<&str>) -> Result<Option<Value>, String> {
+    let type_name = type_name.unwrap_or("String");
+    match cell {
+        Cell::Text(text) if text.is_empty() => Ok(None),
+        Cell::Text(text) if !TEXT_TYPES.contains(&type_name) => {
+            // Structured values are written as JSON; anything else is text
+            match serde_json::from_str::<Json>(text) {
+                Ok(json) => Ok(Some(json_value(&json))),
+                Err(_) => Ok(Some(Value::String(text.clone()))),
+            }
+        }
+        Cell::Text(text) => text_value(text, type_name).map(Some),
+        Cell::Json(Json::String(text)) if TEXT_TYPES.contains(&type_name) => {
+            text_value(text, type_name).map(Some)
+        }
+        Cell::Json(json) => Ok(Some(json_value(json))),
+    }
+}
+
+/// Parse text as a value of a scalar type.
+fn text_value(text: &str, type_name: &str) -> Result<Value, String> {
+    let trimmed = text.trim();
+    let value = match type_name {
+        "String" => Some(Value::String(text.to_string())),
+        "Int" => trimmed.parse().ok().map(Value::Int),
+        "Float" => trimmed.parse().ok().map(Value::Float),
+        "Bool" => match trimmed.to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        // Milliseconds since the epoch, or an ISO 8601 date or time
+        "Timestamp" => trimmed.parse().ok().map(Value::Timestamp).or_else(|| {
+            let literal = if trimmed.starts_with('@') {
+                trimmed.to_string()
+            } else {
+                format!("@{}", trimmed)
+            };
+            literal_value(&literal)
+        }),
+        // Milliseconds, or a duration literal such as `30.seconds`
+        "Duration" => trimmed
+            .parse()
+            .ok()
+            .map(Value::Duration)
+            .or_else(|| literal_value(trimmed)),
+        _ => literal_value(trimmed),
+    };
+    value
+        .filter(|v| v.type_name() == type_name)
+        .ok_or_else(|| format!("expected {}, found '{}'", type_name, text))
+}
+
+/// Parse a literal such as `@2024-01-15`, `1.hour`, `0xff` or a hash.
+fn literal_value(text: &str) -> Option<Value> {
+    Some(match parse_literal(text).ok()? {
+        LiteralKind::Null => Value::Null,
+        LiteralKind::Bool(b) => Value::Bool(b),
+        LiteralKind::Int(n) => Value::Int(n),
+        LiteralKind::Float(f) => Value::Float(f),
+        LiteralKind::String(s) => Value::String(s),
+        LiteralKind::Duration(ms) => Value::Duration(ms),
+        LiteralKind::Timestamp(ms) => Value::Timestamp(ms),
+        LiteralKind::Bytes(bytes) => Value::Bytes(bytes),
+        LiteralKind::Hash(hash) => Value::Hash(hash),
+    })
+}
+
+/// Convert a JSON value as it stands. Lists of numbers become vectors or
+/// points when the attribute is declared so.
+fn json_value(json: &Json) -> Value {
+    match json {
+        Json::Null => Value::Null,
+        Json::Bool(b) => Value::Bool(*b),
+        Json::Number(n) => n
+            .as_i64()
+            .map(Value::Int)
+            .unwrap_or_else(|| Value::Float(n.as_f64().unwrap_or(f64::NAN))),
+        Json::String(s) => Value::String(s.clone()),
+        Json::Array(items) => Value::List(items.iter().map(json_value).collect()),
+        Json::Object(fields) => Value::Map(
+            fields
+                .iter()
+                .map(|(k, v)| (k.clone(), json_value(v)))
+                .collect::<BTreeMap<_, _>>(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entries(format: ImportFormat, input: &str) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        read_entries(format, input.as_bytes(), |e| {
+            entries.push(e);
+            Ok(())
+        })
+        .unwrap();
+        entries
+    }
+
+    #[test]
+    fn test_csv_records_and_malformed_rows() {
+        // GIVEN
+        let input = "name, age\nAda,36\nBob\n\"Cy, Jr\",7\n";
+
+        // WHEN
+        let entries = entries(ImportFormat::Csv, input);
+
+        // THEN
+        assert_eq!(entries.len(), 3);
+        match &entries[0] {
+            Entry::Record(r) => {
+                assert_eq!(r.line, 2);
+                assert_eq!(r.get("age"), Some(&Cell::Text("36".into())));
+            }
+            other => panic!("expected a record, got {:?}", other),
+        }
+        assert_eq!(
+            entries[1],
+            Entry::Malformed(RejectedRow::new(3, "expected 2 fields, found 1"))
+        );
+        assert!(
+            matches!(&entries[2], Entry::Record(r) if r.get("name") == Some(&Cell::Text("Cy, Jr".into())))
+        );
+    }
+
+    #[test]
+    fn test_json_lines_records_and_malformed_rows() {
+        // GIVEN
+        let input = "{\"name\": \"Ada\"}\n\n[1]\n{oops\n";
+
+        // WHEN
+        let entries = entries(ImportFormat::JsonLines, input);
+
+        // THEN
+        assert_eq!(entries.len(), 3);
+        assert!(matches!(&entries[0], Entry::Record(r) if r.line == 1));
+        assert_eq!(
+            entries[1],
+            Entry::Malformed(RejectedRow::new(3, "expected a JSON object"))
+        );
+        assert!(matches!(&entries[2], Entry::Malformed(r) if r.line == 4));
+    }
+
+    #[test]
+    fn test_cells_convert_to_declared_types() {
+        let text = |s: &str| Cell::Text(s.to_string());
+
+        assert_eq!(cell_value(&text(""), Some("Int")), Ok(None));
+        assert_eq!(
+            cell_value(&text("42"), Some("Int")),
+            Ok(Some(Value::Int(42)))
+        );
+        assert_eq!(
+            cell_value(&text("4"), Some("Float")),
+            Ok(Some(Value::Float(4.0)))
+        );
+        assert_eq!(
+            cell_value(&text("TRUE"), Some("Bool")),
+            Ok(Some(Value::Bool(true)))
+        );
+        assert_eq!(
+            cell_value(&text("1970-01-02"), Some("Timestamp")),
+            Ok(Some(Value::Timestamp(86_400_000)))
+        );
+        assert_eq!(
+            cell_value(&text("2.minutes"), Some("Duration")),
+            Ok(Some(Value::Duration(120_000)))
+        );
+        assert_eq!(
+            cell_value(&text("[1, 2]"), Some("Vector[2]")),
+            Ok(Some(Value::List(vec![Value::Int(1), Value::Int(2)])))
+        );
+        assert_eq!(
+            cell_value(
+                &Cell::Json(json!("1970-01-01T00:00:01Z")),
+                Some("Timestamp")
+            ),
+            Ok(Some(Value::Timestamp(1000)))
+        );
+        assert_eq!(
+            cell_value(&Cell::Json(json!(null)), Some("Int")),
+            Ok(Some(Value::Null))
+        );
+        assert_eq!(
+            cell_value(&text("abc"), Some("Int")),
+            Err("expected Int, found 'abc'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_for_path() {
+        assert_eq!(
+            format_for_path(Path::new("people.CSV")),
+            Some(ImportFormat::Csv)
+        );
+        assert_eq!(
+            format_for_path(Path::new("a/b.ndjson")),
+            Some(ImportFormat::JsonLines)
+        );
+        assert_eq!(format_for_path(Path::new("people.txt")), None);
+    }
+}
@@ -0,0 +1,33 @@
+//! Import reports.
+
+/// Outcome of a load.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    /// Data rows read (a CSV header row is not counted).
+    pub rows: usize,
+    /// Nodes created.
+    pub nodes_created: usize,
+    /// Edges created. Symmetric edges that already existed are skipped,
+    /// as by LINK, and are neither created nor rejected.
+    pub edges_created: usize,
+    /// Rows that were not loaded, in file order.
+    pub rejected: Vec<RejectedRow>,
+}
+
+/// A row that was not loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedRow {
+    /// Line of the row in the source file, starting at 1.
+    pub line: u64,
+    /// Why the row was rejected.
+    pub reason: String,
+}
+
+impl RejectedRow {
+    pub fn new(line: u64, reason: impl Into<String>) -> Self {
+        Self {
+            line,
+            reason: reason.into(),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Import specifications: what the columns of a source mean.
+
+use mew_parser::{ImportColumn, ImportFormat, ImportStmt};
+
+/// Rows inserted between deferred constraint checks by default.
+pub const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+/// Describes how the records of a source become nodes or edges.
+///
+/// Without column mappings every column that is not an endpoint key maps
+/// to the attribute of the same name. With mappings only the mapped
+/// columns are read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSpec {
+    /// Node type or edge type to create.
+    pub target: String,
+    /// Column to attribute mappings.
+    pub columns: Vec<ColumnMap>,
+    /// Edge endpoints, in edge parameter order.
+    pub endpoints: Vec<EndpointKey>,
+    /// Source format; taken from the file extension when not given.
+    pub format: Option<ImportFormat>,
+    /// Rows inserted between deferred constraint checks.
+    pub batch_size: usize,
+}
+
+/// A column holding the value of an attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMap {
+    pub column: String,
+    pub attr: String,
+}
+
+/// A column holding a unique key that identifies an edge endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointKey {
+    pub column: String,
+    /// Type of the endpoint node; its subtypes match too.
+    pub type_name: String,
+    /// Unique attribute of `type_name` the column holds.
+    pub key: String,
+}
+
+impl ImportSpec {
+    /// Create a spec that maps columns to attributes by name.
+    pub fn new(target: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            columns: Vec::new(),
+            endpoints: Vec::new(),
+            format: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Map a column to an attribute.
+    pub fn column(mut self, column: impl Into<String>, attr: impl Into<String>) -> Self {
+        self.columns.push(ColumnMap {
+            column: column.into(),
+            attr: attr.into(),
+        });
+        self
+    }
+
+    /// Add the next edge endpoint, found by its `key` attribute.
+    pub fn endpoint(
+        mut self,
+        column: impl Into<String>,
+        type_name: impl Into<String>,
+        key: impl Into<String>,
+    ) -> Self {
+        self.endpoints.push(EndpointKey {
+            column: column.into(),
+            type_name: type_name.into(),
+            key: key.into(),
+        });
+        self
+    }
+
+    /// Set the source format.
+    pub fn format(mut self, format: ImportFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Set the number of rows between deferred constraint checks.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// The spec given by an IMPORT statement's target, columns and format.
+    /// Its source and batch option are expressions, evaluated by
+    /// [`BulkLoader::execute`](crate::BulkLoader::execute).
+    pub fn from_stmt(stmt: &ImportStmt) -> Self {
+        let mut spec = Self::new(&stmt.target);
+        spec.format = stmt.options.format;
+        for column in &stmt.columns {
+            spec = match column {
+                ImportColumn::Attr { column, attr } => spec.column(column, attr),
+                ImportColumn::Endpoint {
+                    column,
+                    type_name,
+                    key,
+                } => spec.endpoint(column, type_name, key),
+            };
+        }
+        spec
+    }
+}
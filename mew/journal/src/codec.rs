@@ -0,0 +1,335 @@
+//! Text encoding of WAL entry payloads.
+//!
+//! Each journal line is `LSN|TXN_ID|TYPE|DATA`, where DATA is a JSON object
+//! carrying the entry fields. Values are encoded with an explicit type tag so
+//! that Int/Float/Timestamp/Duration and nested List/Map values survive a
+//! round-trip unchanged.
+
+use std::collections::BTreeMap;
+
+use mew_core::{
+    from_hex, to_hex, Attributes, EdgeId, EdgeTypeId, EntityId, NodeId, Point, TypeId, Value,
+};
+use serde_json::{json, Map as JsonMap, Value as Json};
+
+use crate::entry::{TxnId, WalEntry};
+use crate::error::{JournalError, JournalResult};
+
+/// Encode the payload of an entry. Control entries have no payload.
+pub(crate) fn encode_payload(entry: &WalEntry) -> Option<Json> {
+    match entry {
+        WalEntry::Begin { .. } | WalEntry::Commit { .. } | WalEntry::Abort { .. } => None,
+        WalEntry::SpawnNode {
+            node_id,
+            type_id,
+            attrs,
+            ..
+        } => Some(json!({
+            "node": node_id.raw(),
+            "type": type_id.raw(),
+            "attrs": encode_attrs(attrs),
+        })),
+        WalEntry::KillNode { node_id, .. } => Some(json!({ "node": node_id.raw() })),
+        WalEntry::LinkEdge {
+            edge_id,
+            type_id,
+            targets,
+            attrs,
+            ..
+        } => Some(json!({
+            "edge": edge_id.raw(),
+            "type": type_id.raw(),
+            "targets": targets.iter().map(encode_entity).collect::<Vec<_>>(),
+            "attrs": encode_attrs(attrs),
+        })),
+        WalEntry::UnlinkEdge { edge_id, .. } => Some(json!({ "edge": edge_id.raw() })),
+        WalEntry::SetAttr {
+            node_id,
+            attr_name,
+            old_value,
+            new_value,
+            ..
+        } => Some(json!({
+            "node": node_id.raw(),
+            "attr": attr_name,
+            "old": old_value.as_ref().map(encode_value),
+            "new": encode_value(new_value),
+        })),
+        WalEntry::Checkpoint { last_committed_lsn } => Some(json!({ "lsn": last_committed_lsn })),
+    }
+}
+
+/// Decode a data entry from its type tag and payload.
+pub(crate) fn decode_entry(entry_type: &str, txn_id: TxnId, data: &str) -> JournalResult<WalEntry> {
+    let payload: Json = serde_json::from_str(data).map_err(|e| {
+        JournalError::serialization(format!("invalid {} payload: {}", entry_type, e))
+    })?;
+
+    Ok(match entry_type {
+        "SPAWN" => WalEntry::SpawnNode {
+            txn_id,
+            node_id: NodeId::new(field_u64(&payload, "node")?),
+            type_id: TypeId::new(field_u64(&payload, "type")? as u32),
+            attrs: decode_attrs(field(&payload, "attrs")?)?,
+        },
+        "KILL" => WalEntry::KillNode {
+            txn_id,
+            node_id: NodeId::new(field_u64(&payload, "node")?),
+        },
+        "LINK" => {
+            let targets = field(&payload, "targets")?
+                .as_array()
+                .ok_or_else(|| JournalError::serialization("LINK targets must be an array"))?
+                .iter()
+                .map(decode_entity)
+                .collect::<JournalResult<Vec<_>>>()?;
+            WalEntry::LinkEdge {
+                txn_id,
+                edge_id: EdgeId::new(field_u64(&payload, "edge")?),
+                type_id: EdgeTypeId::new(field_u64(&payload, "type")? as u32),
+                targets,
+                attrs: decode_attrs(field(&payload, "attrs")?)?,
+            }
+        }
+        "UNLINK" => WalEntry::UnlinkEdge {
+            txn_id,
+            edge_id: EdgeId::new(field_u64(&payload, "edge")?),
+        },
+        "SET" => WalEntry::SetAttr {
+            txn_id,
+            node_id: NodeId::new(field_u64(&payload, "node")?),
+            attr_name: field(&payload, "attr")?
+                .as_str()
+                .ok_or_else(|| JournalError::serialization("SET attr must be a string"))?
+                .to_string(),
+            old_value: match payload.get("old") {
+                None | Some(Json::Null) => None,
+                Some(v) => Some(decode_value(v)?),
+            },
+            new_value: decode_value(field(&payload, "new")?)?,
+        },
+        "CHECKPOINT" => WalEntry::Checkpoint {
+            last_committed_lsn: field_u64(&payload, "lsn")?,
+        },
+        _ => {
+            return Err(JournalError::invalid_format(format!(
+                "unknown type: {}",
+                entry_type
+            )))
+        }
+    })
+}
+
+/// Encode a value with an explicit type tag.
+pub(crate) fn encode_value(value: &Value) -> Json {
+    match value {
+        Value::Null => Json::Null,
+        Value::Bool(b) => json!({ "bool": b }),
+        Value::Int(i) => json!({ "int": i }),
+        Value::Float(f) => json!({ "float": f }),
+        Value::String(s) => json!({ "str": s }),
+        Value::Timestamp(t) => json!({ "ts": t }),
+        Value::Duration(d) => json!({ "dur": d }),
+        Value::Bytes(b) => json!({ "bytes": to_hex(b) }),
+        Value::Hash(h) => json!({ "hash": to_hex(h) }),
+        Value::Vector(v) => json!({ "vec": v }),
+        Value::Point(p) => match p.z {
+            Some(z) => json!({ "point": [p.x, p.y, z] }),
+            None => json!({ "point": [p.x, p.y] }),
+        },
+        Value::NodeRef(id) => json!({ "node": id.raw() }),
+        Value::EdgeRef(id) => json!({ "edge": id.raw() }),
+        Value::List(items) => json!({ "list": items.iter().map(encode_value).collect::<Vec<_>>() }),
+        Value::Map(entries) => {
+            let obj: JsonMap<String, Json> = entries
+                .iter()
+                .map(|(k, v)| (k.clone(), encode_value(v)))
+                .collect();
+            json!({ "map": obj })
+        }
+    }
+}
+
+/// Decode a value produced by `encode_value`.
+pub(crate) fn decode_value(json: &Json) -> JournalResult<Value> {
+    let obj = match json {
+        Json::Null => return Ok(Value::Null),
+        Json::Object(obj) if obj.len() == 1 => obj,
+        other => {
+            return Err(JournalError::serialization(format!(
+                "invalid value encoding: {}",
+                other
+            )))
+        }
+    };
+    let (tag, inner) = obj.iter().next().expect("object has one entry");
+    let bad = || JournalError::serialization(format!("invalid '{}' value: {}", tag, inner));
+
+    Ok(match tag.as_str() {
+        "bool" => Value::Bool(inner.as_bool().ok_or_else(bad)?),
+        "int" => Value::Int(inner.as_i64().ok_or_else(bad)?),
+        "float" => Value::Float(inner.as_f64().ok_or_else(bad)?),
+        "str" => Value::String(inner.as_str().ok_or_else(bad)?.to_string()),
+        "ts" => Value::Timestamp(inner.as_i64().ok_or_else(bad)?),
+        "dur" => Value::Duration(inner.as_i64().ok_or_else(bad)?),
+        "bytes" => Value::Bytes(inner.as_str().and_then(from_hex).ok_or_else(bad)?),
+        "hash" => Value::Hash(
+            inner
+                .as_str()
+                .and_then(from_hex)
+                .and_then(|b| <[u8; 32]>::try_from(b).ok())
+                .ok_or_else(bad)?,
+        ),
+        "vec" => Value::Vector(
+            inner
+                .as_array()
+                .ok_or_else(bad)?
+                .iter()
+                .map(|x| x.as_f64().map(|f| f as f32).ok_or_else(bad))
+                .collect::<JournalResult<Vec<_>>>()?,
+        ),
+        "point" => {
+            let coords = inner
+                .as_array()
+                .ok_or_else(bad)?
+                .iter()
+                .map(|x| x.as_f64().ok_or_else(bad))
+                .collect::<JournalResult<Vec<_>>>()?;
+            match coords.as_slice() {
+                [x, y] => Value::Point(Point::new_2d(*x, *y)),
+                [x, y, z] => Value::Point(Point::new_3d(*x, *y, *z)),
+                _ => return Err(bad()),
+            }
+        }
+        "node" => Value::NodeRef(NodeId::new(inner.as_u64().ok_or_else(bad)?)),
+        "edge" => Value::EdgeRef(EdgeId::new(inner.as_u64().ok_or_else(bad)?)),
+        "list" => Value::List(
+            inner
+                .as_array()
+                .ok_or_else(bad)?
+                .iter()
+                .map(decode_value)
+                .collect::<JournalResult<Vec<_>>>()?,
+        ),
+        "map" => Value::Map(
+            inner
+                .as_object()
+                .ok_or_else(bad)?
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), decode_value(v)?)))
+                .collect::<JournalResult<BTreeMap<_, _>>>()?,
+        ),
+        _ => return Err(bad()),
+    })
+}
+
+fn encode_attrs(attrs: &Attributes) -> Json {
+    // Sort keys so the journal output is deterministic
+    let sorted: BTreeMap<&String, &Value> = attrs.iter().collect();
+    Json::Object(
+        sorted
+            .into_iter()
+            .map(|(k, v)| (k.clone(), encode_value(v)))
+            .collect(),
+    )
+}
+
+fn decode_attrs(json: &Json) -> JournalResult<Attributes> {
+    json.as_object()
+        .ok_or_else(|| JournalError::serialization("attrs must be an object"))?
+        .iter()
+        .map(|(k, v)| Ok((k.clone(), decode_value(v)?)))
+        .collect()
+}
+
+fn encode_entity(id: &EntityId) -> Json {
+    match id {
+        EntityId::Node(n) => json!({ "node": n.raw() }),
+        EntityId::Edge(e) => json!({ "edge": e.raw() }),
+    }
+}
+
+fn decode_entity(json: &Json) -> JournalResult<EntityId> {
+    match decode_value(json)? {
+        Value::NodeRef(id) => Ok(EntityId::Node(id)),
+        Value::EdgeRef(id) => Ok(EntityId::Edge(id)),
+        other => Err(JournalError::serialization(format!(
+            "invalid edge target: {:?}",
+            other
+        ))),
+    }
+}
+
+fn field<'a>(payload: &'a Json, name: &str) -> JournalResult<&'a Json> {
+    payload
+        .get(name)
+        .ok_or_else(|| JournalError::serialization(format!("missing field '{}'", name)))
+}
+
+fn field_u64(payload: &Json, name: &str) -> JournalResult<u64> {
+    field(payload, name)?
+        .as_u64()
+        .ok_or_else(|| JournalError::serialization(format!("field '{}' must be an integer", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_round_trip() {
+        // GIVEN
+        let mut meta = BTreeMap::new();
+        meta.insert("region".to_string(), Value::from("eu-west"));
+        meta.insert(
+            "tags".to_string(),
+            Value::List(vec![Value::Int(1), Value::Float(2.5)]),
+        );
+        meta.insert("owner".to_string(), Value::NodeRef(NodeId::new(7)));
+        meta.insert("digest".to_string(), Value::Hash([9; 32]));
+        meta.insert("key".to_string(), Value::Bytes(vec![0, 1, 255]));
+        meta.insert("embedding".to_string(), Value::Vector(vec![0.25, -1.5]));
+        meta.insert(
+            "location".to_string(),
+            Value::Point(Point::new_3d(1.0, -2.5, 3.0)),
+        );
+        let value = Value::Map(meta);
+
+        // WHEN
+        let decoded = decode_value(&encode_value(&value)).unwrap();
+
+        // THEN
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_set_entry_round_trip() {
+        // GIVEN
+        let entry = WalEntry::SetAttr {
+            txn_id: 3,
+            node_id: NodeId::new(1),
+            attr_name: "meta".to_string(),
+            old_value: None,
+            new_value: Value::Timestamp(1_700_000_000_000),
+        };
+
+        // WHEN
+        let data = encode_payload(&entry).unwrap().to_string();
+        let decoded = decode_entry("SET", 3, &data).unwrap();
+
+        // THEN
+        match decoded {
+            WalEntry::SetAttr {
+                attr_name,
+                old_value,
+                new_value,
+                ..
+            } => {
+                assert_eq!(attr_name, "meta");
+                assert_eq!(old_value, None);
+                assert_eq!(new_value, Value::Timestamp(1_700_000_000_000));
+            }
+            other => panic!("expected SetAttr, got {:?}", other),
+        }
+    }
+}
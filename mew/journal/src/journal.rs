@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 
 use mew_graph::Graph;
 
+use crate::codec::{decode_entry, encode_payload};
 use crate::entry::{Lsn, TxnId, WalEntry, WalRecord};
 use crate::error::{JournalError, JournalResult};
 
@@ -273,13 +274,12 @@ impl FileJournal {
             "BEGIN" => WalEntry::Begin { txn_id },
             "COMMIT" => WalEntry::Commit { txn_id },
             "ABORT" => WalEntry::Abort { txn_id },
-            // For other entry types, we'd need more complex parsing
-            // Simplified for now - in production we'd use a proper serialization format
+            // Data entries carry a JSON payload in the fourth field
             _ => {
-                return Err(JournalError::invalid_format(format!(
-                    "unknown type: {}",
-                    entry_type
-                )))
+                let data = parts.get(3).ok_or_else(|| {
+                    JournalError::invalid_format(format!("missing payload for {}", entry_type))
+                })?;
+                decode_entry(entry_type, txn_id, data)?
             }
         };
 
@@ -301,7 +301,10 @@ impl FileJournal {
             WalEntry::Checkpoint { .. } => "CHECKPOINT",
         };
 
-        format!("{}|{}|{}", record.lsn, txn_id, entry_type)
+        match encode_payload(&record.entry) {
+            Some(payload) => format!("{}|{}|{}|{}", record.lsn, txn_id, entry_type, payload),
+            None => format!("{}|{}|{}", record.lsn, txn_id, entry_type),
+        }
     }
 
     /// Allocate a new transaction ID.
@@ -483,4 +486,43 @@ mod tests {
         // WHEN/THEN - sync should succeed for memory journal
         assert!(journal.sync().is_ok());
     }
+
+    #[test]
+    fn test_file_journal_reopen_preserves_map_values() {
+        // GIVEN
+        let path = std::env::temp_dir().join(format!("mew-journal-{}.wal", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut meta = std::collections::BTreeMap::new();
+        meta.insert("region".to_string(), mew_core::Value::from("eu-west"));
+        let meta = mew_core::Value::Map(meta);
+        {
+            let mut journal = FileJournal::open(&path).unwrap();
+            let txn = journal.alloc_txn_id();
+            journal.append(WalEntry::Begin { txn_id: txn }).unwrap();
+            journal
+                .append(WalEntry::SpawnNode {
+                    txn_id: txn,
+                    node_id: mew_core::NodeId::new(1),
+                    type_id: TypeId(1),
+                    attrs: attrs! { "meta" => meta.clone() },
+                })
+                .unwrap();
+            journal.append(WalEntry::Commit { txn_id: txn }).unwrap();
+            journal.sync().unwrap();
+        }
+
+        // WHEN
+        let journal = FileJournal::open(&path).unwrap();
+        let mut graph = Graph::new();
+        let stats = journal.recover(&mut graph).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        // THEN
+        assert_eq!(stats.nodes_created, 1);
+        let node_id = graph.all_node_ids().next().unwrap();
+        assert_eq!(
+            graph.get_node(node_id).unwrap().get_attr("meta"),
+            Some(&meta)
+        );
+    }
 }
@@ -8,6 +8,7 @@
 //! - Replay log on startup to recover state
 //! - Manage log segments (rotation, cleanup)
 
+mod codec;
 mod entry;
 mod error;
 mod journal;
@@ -64,6 +64,13 @@ pub enum MutationError {
         max: u32,
     },
 
+    #[error("Invalid ON CONFLICT attribute {attr} on type {type_name}: {reason}")]
+    InvalidConflictTarget {
+        type_name: String,
+        attr: String,
+        reason: String,
+    },
+
     #[error("Deletion restricted by edge type {edge_type}")]
     OnKillRestrict { edge_type: String },
 
@@ -219,6 +226,18 @@ impl MutationError {
         }
     }
 
+    pub fn invalid_conflict_target(
+        type_name: impl Into<String>,
+        attr: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::InvalidConflictTarget {
+            type_name: type_name.into(),
+            attr: attr.into(),
+            reason: reason.into(),
+        }
+    }
+
     pub fn on_kill_restrict(edge_type: impl Into<String>) -> Self {
         Self::OnKillRestrict {
             edge_type: edge_type.into(),
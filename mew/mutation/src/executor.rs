@@ -7,7 +7,7 @@
 //! - `ops/unlink.rs` - UNLINK (edge deletion)
 //! - `ops/set.rs` - SET (attribute updates)
 
-use mew_core::{EdgeId, EntityId, NodeId};
+use mew_core::{EdgeId, EntityId, NodeId, Value};
 use mew_graph::Graph;
 use mew_parser::{KillStmt, LinkStmt, SetStmt, SpawnStmt, UnlinkStmt};
 use mew_pattern::{Bindings, Evaluator};
@@ -43,6 +43,15 @@ impl<'r, 'g> MutationExecutor<'r, 'g> {
         ops::execute_spawn(self.registry, self.graph, &self.evaluator, stmt, bindings)
     }
 
+    /// Create a node from evaluated attribute values, validated as by SPAWN.
+    pub fn create_node(
+        &mut self,
+        type_name: &str,
+        values: Vec<(String, Value)>,
+    ) -> MutationResult<NodeId> {
+        ops::create_node(self.registry, self.graph, type_name, values)
+    }
+
     /// Execute a KILL statement (node deletion).
     pub fn execute_kill(
         &mut self,
@@ -61,6 +70,25 @@ impl<'r, 'g> MutationExecutor<'r, 'g> {
         ops::execute_link(self.registry, self.graph, &self.evaluator, stmt, target_ids)
     }
 
+    /// Create an edge from evaluated attribute values, validated as by LINK.
+    /// Returns `None` if an equal edge exists and none was created.
+    pub fn create_edge(
+        &mut self,
+        edge_type: &str,
+        target_ids: Vec<EntityId>,
+        values: Vec<(String, Value)>,
+        if_not_exists: bool,
+    ) -> MutationResult<Option<EdgeId>> {
+        ops::create_edge(
+            self.registry,
+            self.graph,
+            edge_type,
+            target_ids,
+            values,
+            if_not_exists,
+        )
+    }
+
     /// Execute an UNLINK statement (edge deletion).
     pub fn execute_unlink(
         &mut self,
@@ -123,6 +151,7 @@ mod tests {
         builder
             .add_type("Person")
             .attr(AttrDef::new("name", "String").required())
+            .attr(AttrDef::new("email", "String").unique())
             .done()
             .unwrap();
         builder
@@ -166,6 +195,91 @@ mod tests {
         assert!(output.created_node().is_some());
     }
 
+    #[test]
+    fn test_spawn_on_conflict() {
+        // GIVEN a person with a unique email
+        let registry = test_registry();
+        let mut graph = Graph::new();
+        let person_type_id = registry.get_type_id("Person").unwrap();
+        let alice = graph.create_node(
+            person_type_id,
+            attrs! { "name" => "Alice", "email" => "a@example.com" },
+        );
+        let spawn = |input: &str| match mew_parser::parse_stmt(input).unwrap() {
+            mew_parser::Stmt::Spawn(stmt) => stmt,
+            _ => panic!("Expected SPAWN"),
+        };
+        let mut executor = MutationExecutor::new(&registry, &mut graph);
+        let bindings = Bindings::new();
+
+        // WHEN the same email is spawned with DO NOTHING
+        let result = executor
+            .execute_spawn(
+                &spawn(
+                    r#"SPAWN p: Person { name = "Other", email = "a@example.com" }
+                       ON CONFLICT (email) DO NOTHING"#,
+                ),
+                &bindings,
+            )
+            .unwrap();
+
+        // THEN the existing node is returned untouched
+        let MutationOutcome::Created(created) = result else {
+            panic!("Expected a spawn outcome");
+        };
+        assert_eq!(created.node_ids, vec![alice]);
+        assert_eq!(created.existing, vec![alice]);
+        assert!(created.updated.is_empty());
+
+        // WHEN it is spawned with DO UPDATE
+        let result = executor
+            .execute_spawn(
+                &spawn(
+                    r#"SPAWN p: Person { name = "Alicia", email = "a@example.com" }
+                       ON CONFLICT (email) DO UPDATE SET name = p.name ++ " B.""#,
+                ),
+                &bindings,
+            )
+            .unwrap();
+
+        // THEN the existing node is updated, seen through the spawn variable
+        let MutationOutcome::Created(created) = result else {
+            panic!("Expected a spawn outcome");
+        };
+        assert_eq!(created.updated, vec![alice]);
+
+        // WHEN a new email is spawned
+        let result = executor
+            .execute_spawn(
+                &spawn(
+                    r#"SPAWN p: Person { name = "Bob", email = "b@example.com" }
+                       ON CONFLICT (email) DO NOTHING"#,
+                ),
+                &bindings,
+            )
+            .unwrap();
+
+        // THEN a node is created
+        let MutationOutcome::Created(created) = result else {
+            panic!("Expected a spawn outcome");
+        };
+        assert_ne!(created.node_id, Some(alice));
+        assert!(created.existing.is_empty());
+
+        // AND the conflict target must be unique
+        let err = executor
+            .execute_spawn(
+                &spawn(r#"SPAWN p: Person { name = "Alice" } ON CONFLICT (name) DO NOTHING"#),
+                &bindings,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("not unique"));
+        assert_eq!(
+            graph.get_node(alice).unwrap().get_attr("name"),
+            Some(&Value::String("Alice B.".to_string()))
+        );
+    }
+
     #[test]
     fn test_spawn_unknown_type() {
         // GIVEN
@@ -25,3 +25,4 @@ mod validation;
 pub use error::{MutationError, MutationResult};
 pub use executor::MutationExecutor;
 pub use result::MutationOutcome;
+pub use validation::check_unique_constraints;
@@ -1,6 +1,6 @@
 //! LINK operation - creates edges between entities.
 
-use mew_core::{EdgeId, EdgeTypeId, EntityId, NodeId};
+use mew_core::{EdgeId, EdgeTypeId, EntityId, NodeId, Value};
 use mew_graph::Graph;
 use mew_parser::LinkStmt;
 use mew_pattern::{Bindings, Evaluator};
@@ -18,10 +18,42 @@ pub fn execute_link(
     stmt: &LinkStmt,
     target_ids: Vec<EntityId>,
 ) -> MutationResult<MutationOutcome> {
+    // Evaluate attribute values
+    let bindings = Bindings::new();
+    let mut values = Vec::with_capacity(stmt.attrs.len());
+    for assign in &stmt.attrs {
+        let value = evaluator.eval(&assign.value, &bindings, graph)?;
+        values.push((assign.name.clone(), value));
+    }
+
+    match create_edge(
+        registry,
+        graph,
+        &stmt.edge_type,
+        target_ids,
+        values,
+        stmt.if_not_exists,
+    )? {
+        Some(edge_id) => Ok(MutationOutcome::Created(CreatedEntity::edge(edge_id))),
+        None => Ok(MutationOutcome::Empty),
+    }
+}
+
+/// Create an edge from already evaluated attribute values, with the checks
+/// of LINK. Returns `None` when an equal edge exists and none is created:
+/// always for symmetric edge types, otherwise only with `if_not_exists`.
+pub fn create_edge(
+    registry: &Registry,
+    graph: &mut Graph,
+    edge_type_name: &str,
+    target_ids: Vec<EntityId>,
+    values: Vec<(String, Value)>,
+    if_not_exists: bool,
+) -> MutationResult<Option<EdgeId>> {
     // Look up the edge type
     let edge_type_id = registry
-        .get_edge_type_id(&stmt.edge_type)
-        .ok_or_else(|| MutationError::unknown_edge_type(&stmt.edge_type))?;
+        .get_edge_type_id(edge_type_name)
+        .ok_or_else(|| MutationError::unknown_edge_type(edge_type_name))?;
 
     // Check if this is a symmetric edge type
     let is_symmetric = registry
@@ -34,11 +66,11 @@ pub fn execute_link(
     if is_symmetric {
         if find_existing_edge_symmetric(graph, edge_type_id, &target_ids).is_some() {
             // Edge already exists - no new edge created (symmetric deduplication)
-            return Ok(MutationOutcome::Empty);
+            return Ok(None);
         }
-    } else if stmt.if_not_exists && find_existing_edge(graph, edge_type_id, &target_ids).is_some() {
+    } else if if_not_exists && find_existing_edge(graph, edge_type_id, &target_ids).is_some() {
         // Edge already exists - no new edge created
-        return Ok(MutationOutcome::Empty);
+        return Ok(None);
     }
 
     // Validate arity and target types
@@ -47,7 +79,7 @@ pub fn execute_link(
         let actual = target_ids.len();
         if expected != actual {
             return Err(MutationError::invalid_arity(
-                &stmt.edge_type,
+                edge_type_name,
                 expected,
                 actual,
             ));
@@ -80,37 +112,34 @@ pub fn execute_link(
         }
 
         if edge_type.acyclic {
-            ensure_acyclic(graph, edge_type_id, &stmt.edge_type, &target_ids)?;
+            ensure_acyclic(graph, edge_type_id, edge_type_name, &target_ids)?;
         }
 
         // Check cardinality constraints (maximum)
-        ensure_cardinality(graph, edge_type, &stmt.edge_type, &target_ids)?;
+        ensure_cardinality(graph, edge_type, edge_type_name, &target_ids)?;
     }
 
     // Build attributes
     let mut attrs = mew_core::Attributes::new();
-    let bindings = Bindings::new();
-
-    for assign in &stmt.attrs {
-        let value = evaluator.eval(&assign.value, &bindings, graph)?;
 
+    for (name, value) in values {
         // Validate attribute exists and type matches
         crate::validation::validate_edge_attribute(
             registry,
-            &stmt.edge_type,
+            edge_type_name,
             edge_type_id,
-            &assign.name,
+            &name,
             &value,
             false,
         )?;
 
-        attrs.insert(assign.name.clone(), value);
+        attrs.insert(name, value);
     }
 
     // Check that all required edge attributes are present
     crate::validation::check_required_edge_attributes(
         registry,
-        &stmt.edge_type,
+        edge_type_name,
         edge_type_id,
         &attrs,
     )?;
@@ -123,7 +152,7 @@ pub fn execute_link(
         .create_edge(edge_type_id, target_ids, attrs)
         .map_err(|e| MutationError::pattern_error(e.to_string()))?;
 
-    Ok(MutationOutcome::Created(CreatedEntity::edge(edge_id)))
+    Ok(Some(edge_id))
 }
 
 /// Ensure creating this edge wouldn't create a cycle (for acyclic edge types).
@@ -217,7 +246,7 @@ fn find_existing_edge_symmetric(
 
     // For binary symmetric edges, also check reversed order
     if target_ids.len() == 2 {
-        let reversed = vec![target_ids[1].clone(), target_ids[0].clone()];
+        let reversed = vec![target_ids[1], target_ids[0]];
         if let Some(edge_id) = find_existing_edge(graph, edge_type_id, &reversed) {
             return Some(edge_id);
         }
@@ -10,7 +10,7 @@ mod spawn;
 mod unlink;
 
 pub use kill::execute_kill;
-pub use link::execute_link;
+pub use link::{create_edge, execute_link};
 pub use set::{execute_set, execute_set_edge};
-pub use spawn::execute_spawn;
+pub use spawn::{create_node, execute_spawn};
 pub use unlink::execute_unlink;
@@ -2,7 +2,7 @@
 
 use mew_core::{EdgeId, NodeId};
 use mew_graph::Graph;
-use mew_parser::SetStmt;
+use mew_parser::{AttrAssignment, SetStmt};
 use mew_pattern::{Bindings, Evaluator};
 use mew_registry::Registry;
 
@@ -22,59 +22,75 @@ pub fn execute_set(
     let mut updated_ids = Vec::new();
 
     for node_id in node_ids {
-        let node = graph
-            .get_node(node_id)
-            .ok_or(MutationError::NodeNotFound(node_id))?;
+        update_node(
+            registry,
+            graph,
+            evaluator,
+            node_id,
+            &stmt.assignments,
+            bindings,
+        )?;
+        updated_ids.push(node_id);
+    }
 
-        let type_id = node.type_id;
-        let type_name = registry
-            .get_type(type_id)
-            .map(|t| t.name.clone())
-            .unwrap_or_else(|| "unknown".to_string());
+    Ok(MutationOutcome::Updated(UpdatedEntities::nodes(
+        updated_ids,
+    )))
+}
 
-        // Validate and collect new attributes
-        let mut new_attrs = mew_core::Attributes::new();
+/// Evaluate `assignments` and apply them to a node, with the checks of SET:
+/// values are coerced and validated, readonly attributes are refused and
+/// uniqueness is checked against the other nodes.
+pub fn update_node(
+    registry: &Registry,
+    graph: &mut Graph,
+    evaluator: &Evaluator,
+    node_id: NodeId,
+    assignments: &[AttrAssignment],
+    bindings: &Bindings,
+) -> MutationResult<()> {
+    let node = graph
+        .get_node(node_id)
+        .ok_or(MutationError::NodeNotFound(node_id))?;
 
-        for assign in &stmt.assignments {
-            // Evaluate the value
-            let value = evaluator.eval(&assign.value, bindings, graph)?;
+    let type_id = node.type_id;
+    let type_name = registry
+        .get_type(type_id)
+        .map(|t| t.name.clone())
+        .unwrap_or_else(|| "unknown".to_string());
 
-            // Validate attribute (is_update=true since we're modifying an existing node)
-            validation::validate_attribute(
-                registry,
-                &type_name,
-                type_id,
-                &assign.name,
-                &value,
-                true,
-            )?;
+    // Validate and collect new attributes
+    let mut new_attrs = mew_core::Attributes::new();
 
-            new_attrs.insert(assign.name.clone(), value);
-        }
+    for assign in assignments {
+        // Evaluate the value
+        let value = evaluator.eval(&assign.value, bindings, graph)?;
+        let value = validation::coerce_attribute(registry, type_id, &assign.name, value);
 
-        // Check uniqueness constraints, excluding the current node
-        validation::check_unique_constraints(
-            registry,
-            graph,
-            &type_name,
-            type_id,
-            &new_attrs,
-            Some(node_id),
-        )?;
+        // Validate attribute (is_update=true since we're modifying an existing node)
+        validation::validate_attribute(registry, &type_name, type_id, &assign.name, &value, true)?;
 
-        // Apply updates
-        for (name, value) in new_attrs.into_iter() {
-            graph
-                .set_node_attr(node_id, &name, value)
-                .map_err(|e| MutationError::pattern_error(e.to_string()))?;
-        }
+        new_attrs.insert(assign.name.clone(), value);
+    }
 
-        updated_ids.push(node_id);
+    // Check uniqueness constraints, excluding the current node
+    validation::check_unique_constraints(
+        registry,
+        graph,
+        &type_name,
+        type_id,
+        &new_attrs,
+        Some(node_id),
+    )?;
+
+    // Apply updates
+    for (name, value) in new_attrs.into_iter() {
+        graph
+            .set_node_attr(node_id, &name, value)
+            .map_err(|e| MutationError::pattern_error(e.to_string()))?;
     }
 
-    Ok(MutationOutcome::Updated(UpdatedEntities::nodes(
-        updated_ids,
-    )))
+    Ok(())
 }
 
 /// Execute a SET statement to update edge attributes.
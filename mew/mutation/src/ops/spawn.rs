@@ -1,63 +1,121 @@
 //! SPAWN operation - creates new nodes.
 
-use mew_core::NodeId;
+use mew_core::{NodeId, Value};
 use mew_graph::Graph;
-use mew_parser::{SpawnItem, SpawnStmt};
-use mew_pattern::{Bindings, Evaluator};
+use mew_parser::{ConflictAction, SpawnItem, SpawnStmt};
+use mew_pattern::{Binding, Bindings, Evaluator};
 use mew_registry::Registry;
 
 use crate::error::{MutationError, MutationResult};
 use crate::result::{CreatedEntity, MutationOutcome};
 use crate::validation;
 
-/// Execute a single spawn item to create a new node.
+use super::set::update_node;
+
+/// What a spawn item did: created a node, or resolved its ON CONFLICT
+/// to an existing node.
+enum SpawnedNode {
+    Created(NodeId),
+    Existing { node_id: NodeId, updated: bool },
+}
+
+/// Execute a single spawn item to create a new node, or to resolve its
+/// ON CONFLICT clause against an existing one.
 fn execute_spawn_item(
     registry: &Registry,
     graph: &mut Graph,
     evaluator: &Evaluator,
     item: &SpawnItem,
     bindings: &Bindings,
+) -> MutationResult<SpawnedNode> {
+    // Evaluate the value expressions
+    let mut values = Vec::with_capacity(item.attrs.len());
+    for assign in &item.attrs {
+        let value = evaluator.eval(&assign.value, bindings, graph)?;
+        values.push((assign.name.clone(), value));
+    }
+
+    if let Some(on_conflict) = &item.on_conflict {
+        let type_id = registry
+            .get_type_id(&item.type_name)
+            .ok_or_else(|| MutationError::unknown_type(&item.type_name))?;
+        let attrs: mew_core::Attributes = values
+            .iter()
+            .map(|(name, value)| {
+                let value = validation::coerce_attribute(registry, type_id, name, value.clone());
+                (name.clone(), value)
+            })
+            .collect();
+        // Only `graph` is searched: a node committed concurrently to a shared
+        // database is caught by the unique check when this write commits
+        let existing = validation::find_conflicting_node(
+            registry,
+            graph,
+            &item.type_name,
+            type_id,
+            &attrs,
+            &on_conflict.attrs,
+        )?;
+
+        if let Some(node_id) = existing {
+            let updated = match &on_conflict.action {
+                ConflictAction::Nothing => false,
+                ConflictAction::Update(assignments) => {
+                    // The spawn variable refers to the existing node
+                    let mut bindings = bindings.clone();
+                    bindings.insert(item.var.clone(), Binding::Node(node_id));
+                    update_node(registry, graph, evaluator, node_id, assignments, &bindings)?;
+                    true
+                }
+            };
+            return Ok(SpawnedNode::Existing { node_id, updated });
+        }
+    }
+
+    create_node(registry, graph, &item.type_name, values).map(SpawnedNode::Created)
+}
+
+/// Create a node from already evaluated attribute values, with the checks
+/// of SPAWN: values are coerced and validated, required attributes and
+/// uniqueness are checked and defaults are applied.
+pub fn create_node(
+    registry: &Registry,
+    graph: &mut Graph,
+    type_name: &str,
+    values: Vec<(String, Value)>,
 ) -> MutationResult<NodeId> {
     // Look up the type
     let type_id = registry
-        .get_type_id(&item.type_name)
-        .ok_or_else(|| MutationError::unknown_type(&item.type_name))?;
+        .get_type_id(type_name)
+        .ok_or_else(|| MutationError::unknown_type(type_name))?;
 
     // Check that type is not abstract
     if let Some(type_def) = registry.get_type(type_id) {
         if type_def.is_abstract {
-            return Err(MutationError::abstract_type(&item.type_name));
+            return Err(MutationError::abstract_type(type_name));
         }
     }
 
     // Build attributes
     let mut attrs = mew_core::Attributes::new();
 
-    for assign in &item.attrs {
-        // Evaluate the value expression
-        let value = evaluator.eval(&assign.value, bindings, graph)?;
+    for (name, value) in values {
+        let value = validation::coerce_attribute(registry, type_id, &name, value);
 
         // Validate attribute exists and type matches (is_update=false since this is a new node)
-        validation::validate_attribute(
-            registry,
-            &item.type_name,
-            type_id,
-            &assign.name,
-            &value,
-            false,
-        )?;
+        validation::validate_attribute(registry, type_name, type_id, &name, &value, false)?;
 
-        attrs.insert(assign.name.clone(), value);
+        attrs.insert(name, value);
     }
 
     // Check required attributes
-    validation::check_required_attributes(registry, &item.type_name, type_id, &attrs)?;
+    validation::check_required_attributes(registry, type_name, type_id, &attrs)?;
 
     // Apply default values
     validation::apply_defaults(registry, type_id, &mut attrs)?;
 
     // Check uniqueness constraints
-    validation::check_unique_constraints(registry, graph, &item.type_name, type_id, &attrs, None)?;
+    validation::check_unique_constraints(registry, graph, type_name, type_id, &attrs, None)?;
 
     // Create the node
     let node_id = graph.create_node(type_id, attrs);
@@ -74,21 +132,33 @@ pub fn execute_spawn(
     stmt: &SpawnStmt,
     bindings: &Bindings,
 ) -> MutationResult<MutationOutcome> {
-    let mut created_nodes = Vec::new();
+    let mut node_ids = Vec::new();
+    let mut existing = Vec::new();
+    let mut updated = Vec::new();
 
     for item in &stmt.items {
-        let node_id = execute_spawn_item(registry, graph, evaluator, item, bindings)?;
-        created_nodes.push(node_id);
+        match execute_spawn_item(registry, graph, evaluator, item, bindings)? {
+            SpawnedNode::Created(node_id) => node_ids.push(node_id),
+            SpawnedNode::Existing {
+                node_id,
+                updated: was_updated,
+            } => {
+                node_ids.push(node_id);
+                existing.push(node_id);
+                if was_updated {
+                    updated.push(node_id);
+                }
+            }
+        }
     }
 
     // For single spawns, return just the node; for multiple, return all
-    if created_nodes.len() == 1 {
-        Ok(MutationOutcome::Created(CreatedEntity::node(
-            created_nodes[0],
-        )))
+    let created = if node_ids.len() == 1 {
+        CreatedEntity::node(node_ids[0])
     } else {
-        Ok(MutationOutcome::Created(CreatedEntity::nodes(
-            created_nodes,
-        )))
-    }
+        CreatedEntity::nodes(node_ids)
+    };
+    Ok(MutationOutcome::Created(
+        created.with_existing(existing, updated),
+    ))
 }
@@ -61,6 +61,10 @@ pub struct CreatedEntity {
     pub edge_id: Option<EdgeId>,
     /// Returned attributes.
     pub attributes: HashMap<String, Value>,
+    /// Nodes of `node_ids` that already existed (SPAWN ... ON CONFLICT).
+    pub existing: Vec<NodeId>,
+    /// Existing nodes updated by ON CONFLICT DO UPDATE.
+    pub updated: Vec<NodeId>,
 }
 
 impl CreatedEntity {
@@ -70,6 +74,8 @@ impl CreatedEntity {
             node_ids: vec![id],
             edge_id: None,
             attributes: HashMap::new(),
+            existing: Vec::new(),
+            updated: Vec::new(),
         }
     }
 
@@ -79,6 +85,8 @@ impl CreatedEntity {
             node_ids: ids,
             edge_id: None,
             attributes: HashMap::new(),
+            existing: Vec::new(),
+            updated: Vec::new(),
         }
     }
 
@@ -88,9 +96,18 @@ impl CreatedEntity {
             node_ids: Vec::new(),
             edge_id: Some(id),
             attributes: HashMap::new(),
+            existing: Vec::new(),
+            updated: Vec::new(),
         }
     }
 
+    /// Mark the nodes that already existed and the ones of them updated.
+    pub fn with_existing(mut self, existing: Vec<NodeId>, updated: Vec<NodeId>) -> Self {
+        self.existing = existing;
+        self.updated = updated;
+        self
+    }
+
     pub fn with_attrs(mut self, attrs: HashMap<String, Value>) -> Self {
         self.attributes = attrs;
         self
@@ -20,11 +20,40 @@ fn format_value(value: &Value) -> String {
             let formatted: Vec<String> = items.iter().map(format_value).collect();
             format!("[{}]", formatted.join(", "))
         }
+        Value::Map(_) | Value::Bytes(_) | Value::Hash(_) | Value::Vector(_) | Value::Point(_) => {
+            value.to_string()
+        }
         Value::NodeRef(id) => format!("node:{:?}", id),
         Value::EdgeRef(id) => format!("edge:{:?}", id),
     }
 }
 
+/// Convert a value to the representation declared for the attribute.
+/// A list of numbers assigned to a `Vector[N]` attribute becomes a Vector, and a
+/// list of 2 or 3 numbers assigned to a `Point` attribute becomes a Point;
+/// everything else is returned unchanged.
+pub fn coerce_attribute(
+    registry: &Registry,
+    type_id: TypeId,
+    attr_name: &str,
+    value: Value,
+) -> Value {
+    if !matches!(value, Value::List(_)) {
+        return value;
+    }
+    let Some(attr_def) = registry.get_type_attr(type_id, attr_name) else {
+        return value;
+    };
+    let coerced = if attr_def.type_name.starts_with("Vector") {
+        value.to_vector().map(Value::Vector)
+    } else if attr_def.type_name == "Point" {
+        value.to_point().map(Value::Point)
+    } else {
+        None
+    };
+    coerced.unwrap_or(value)
+}
+
 /// Validate an attribute assignment against the registry.
 /// Use `is_update` = true for SET operations to enforce readonly constraints.
 pub fn validate_attribute(
@@ -51,9 +80,7 @@ pub fn validate_attribute(
 
         // Check type compatibility
         let expected_type = &attr_def.type_name;
-        let actual_type = value_type_name(value);
-
-        if !types_compatible(expected_type, &actual_type) {
+        if let Err(actual_type) = check_value_type(expected_type, value) {
             return Err(MutationError::invalid_attr_type(
                 attr_name,
                 expected_type,
@@ -125,9 +152,7 @@ pub fn validate_edge_attribute(
 
         // Check type compatibility
         let expected_type = &attr_def.type_name;
-        let actual_type = value_type_name(value);
-
-        if !types_compatible(expected_type, &actual_type) {
+        if let Err(actual_type) = check_value_type(expected_type, value) {
             return Err(MutationError::invalid_attr_type(
                 attr_name,
                 expected_type,
@@ -259,7 +284,38 @@ pub fn apply_edge_defaults(
 
 /// Get the type name of a value.
 pub fn value_type_name(value: &Value) -> String {
-    value.type_name().to_string()
+    match value {
+        // The dimension is part of a vector's type
+        Value::Vector(v) => format!("Vector[{}]", v.len()),
+        _ => value.type_name().to_string(),
+    }
+}
+
+/// Check a value against a declared attribute type, returning the value's
+/// type name on mismatch. A list type `[T]` accepts a list whose elements
+/// are all compatible with `T`.
+pub fn check_value_type(expected: &str, value: &Value) -> Result<(), String> {
+    let Some(elem_type) = list_element_type(expected) else {
+        let actual = value_type_name(value);
+        return if types_compatible(expected, &actual) {
+            Ok(())
+        } else {
+            Err(actual)
+        };
+    };
+    match value {
+        Value::Null => Ok(()),
+        Value::List(items) => items
+            .iter()
+            .try_for_each(|item| check_value_type(elem_type, item))
+            .map_err(|actual| format!("[{}]", actual)),
+        other => Err(value_type_name(other)),
+    }
+}
+
+/// The element type of a list type name such as `[String]`.
+pub fn list_element_type(type_name: &str) -> Option<&str> {
+    type_name.strip_prefix('[')?.strip_suffix(']')
 }
 
 /// Check if types are compatible.
@@ -279,6 +335,14 @@ pub fn types_compatible(expected: &str, actual: &str) -> bool {
     if (expected == "Duration" || expected == "Timestamp") && actual == "Int" {
         return true;
     }
+    // A bare Vector attribute accepts any dimension
+    if expected == "Vector" && actual.starts_with("Vector[") {
+        return true;
+    }
+    // Json holds any JSON-representable value
+    if expected == "Json" {
+        return matches!(actual, "Map" | "List" | "String" | "Int" | "Float" | "Bool");
+    }
     false
 }
 
@@ -357,28 +421,98 @@ fn value_exists_in_nodes(
     value: &Value,
     exclude_node: Option<NodeId>,
 ) -> bool {
+    nodes_with_value(registry, graph, type_id, attr_name, value)
+        .into_iter()
+        .any(|node_id| Some(node_id) != exclude_node)
+}
+
+/// Nodes of the given type or its subtypes whose attribute holds `value`.
+fn nodes_with_value(
+    registry: &Registry,
+    graph: &Graph,
+    type_id: TypeId,
+    attr_name: &str,
+    value: &Value,
+) -> Vec<NodeId> {
     // Collect all type IDs to check (the type itself plus all subtypes)
     let mut types_to_check = vec![type_id];
     types_to_check.extend(registry.get_subtypes(type_id));
 
-    // Check all nodes of these types
-    for check_type_id in types_to_check {
-        for node_id in graph.nodes_by_type(check_type_id) {
-            // Skip the excluded node
-            if Some(node_id) == exclude_node {
-                continue;
-            }
+    // Indexed values (String, Int, Hash, ...) are looked up directly
+    if Graph::is_attr_indexable(value) {
+        return types_to_check
+            .into_iter()
+            .flat_map(|check_type_id| graph.nodes_by_attr(check_type_id, attr_name, value))
+            .collect();
+    }
+
+    // Otherwise check all nodes of these types
+    types_to_check
+        .into_iter()
+        .flat_map(|check_type_id| graph.nodes_by_type(check_type_id))
+        .filter(|&node_id| {
+            graph
+                .get_node(node_id)
+                .and_then(|node| node.attributes.get(attr_name))
+                .is_some_and(|node_value| node_value == value)
+        })
+        .collect()
+}
+
+/// Find the node a SPAWN with ON CONFLICT on `conflict_attrs` collides
+/// with: the node that already holds the spawned value of one of them.
+///
+/// Each attribute must be unique and set by the SPAWN; null values never
+/// conflict. Values that match different nodes are an error, since the
+/// SPAWN could only resolve to one of them.
+pub fn find_conflicting_node(
+    registry: &Registry,
+    graph: &Graph,
+    type_name: &str,
+    type_id: TypeId,
+    attrs: &mew_core::Attributes,
+    conflict_attrs: &[String],
+) -> MutationResult<Option<NodeId>> {
+    let mut found = None;
+    for attr_name in conflict_attrs {
+        let attr_def = registry
+            .get_all_type_attrs(type_id)
+            .into_iter()
+            .find(|attr_def| &attr_def.name == attr_name)
+            .ok_or_else(|| MutationError::unknown_attribute(type_name, attr_name))?;
+        if !attr_def.unique {
+            return Err(MutationError::invalid_conflict_target(
+                type_name,
+                attr_name,
+                "the attribute is not unique",
+            ));
+        }
+        let value = attrs.get(attr_name).ok_or_else(|| {
+            MutationError::invalid_conflict_target(
+                type_name,
+                attr_name,
+                "the SPAWN does not set it",
+            )
+        })?;
+        if matches!(value, Value::Null) {
+            continue;
+        }
 
-            if let Some(node) = graph.get_node(node_id) {
-                if let Some(node_value) = node.attributes.get(attr_name) {
-                    if node_value == value {
-                        return true;
-                    }
+        let declaring_type_id = find_declaring_type(registry, type_id, attr_name);
+        for node_id in nodes_with_value(registry, graph, declaring_type_id, attr_name, value) {
+            match found {
+                Some(existing) if existing != node_id => {
+                    return Err(MutationError::invalid_conflict_target(
+                        type_name,
+                        attr_name,
+                        "the values conflict with different nodes",
+                    ));
                 }
+                _ => found = Some(node_id),
             }
         }
     }
-    false
+    Ok(found)
 }
 
 /// Validate a value against a format constraint.
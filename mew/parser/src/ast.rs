@@ -26,6 +26,7 @@ impl Span {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
     Match(MatchStmt),
+    Union(UnionStmt),
     MatchMutate(MatchMutateStmt),
     MatchWalk(MatchWalkStmt),
     Spawn(SpawnStmt),
@@ -38,6 +39,13 @@ pub enum Stmt {
     Txn(TxnStmt),
     Explain(ExplainStmt),
     Profile(ProfileStmt),
+    Watch(WatchStmt),
+    Ack(AckStmt),
+    Nack(NackStmt),
+    Import(ImportStmt),
+    Dump(DumpStmt),
+    Call(CallStmt),
+    Unwind(UnwindStmt),
 }
 
 // ==================== EXPLAIN / PROFILE ====================
@@ -65,13 +73,81 @@ pub struct MatchStmt {
     pub where_clause: Option<Expr>,
     /// OPTIONAL MATCH clauses (left outer joins)
     pub optional_matches: Vec<OptionalMatch>,
+    /// NEAREST k TO expr ON var.attr - k-nearest-neighbour selection
+    pub nearest: Option<NearestClause>,
+    /// WITH stages between the pattern and RETURN, in order
+    pub stages: Vec<WithStage>,
     pub return_clause: ReturnClause,
+    /// GROUP BY keys; without them, the non-aggregate projections group
+    pub group_by: Option<Vec<Expr>>,
+    /// HAVING: filter on the grouped rows
+    pub having: Option<Expr>,
     pub order_by: Option<Vec<OrderTerm>>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub span: Span,
 }
 
+/// Two or more MATCH queries combined with UNION or UNION ALL.
+///
+/// ORDER BY, LIMIT and OFFSET written after the last branch apply to the
+/// combined rows; the branches themselves carry none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionStmt {
+    pub branches: Vec<MatchStmt>,
+    /// UNION ALL keeps duplicate rows; UNION removes them
+    pub all: bool,
+    pub order_by: Option<Vec<OrderTerm>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub span: Span,
+}
+
+/// A WITH stage of a MATCH query:
+/// `WITH [DISTINCT] projections [GROUP BY ...] [HAVING ...] [ORDER BY ...]
+/// [LIMIT n] [OFFSET n] [MATCH pattern] [WHERE ...]`.
+///
+/// The stage projects (and may aggregate) the rows so far. Only the names it
+/// projects stay in scope for the pattern and WHERE that follow it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithStage {
+    pub distinct: bool,
+    pub projections: Vec<Projection>,
+    pub group_by: Option<Vec<Expr>>,
+    pub having: Option<Expr>,
+    pub order_by: Option<Vec<OrderTerm>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Pattern of a following MATCH, joined with each row of the stage
+    pub pattern: Vec<PatternElem>,
+    pub where_clause: Option<Expr>,
+    pub span: Span,
+}
+
+/// A NEAREST clause: `NEAREST k TO <vector> ON var.attr [USING metric]`.
+///
+/// Keeps the k rows whose vector attribute is closest to the query vector,
+/// ordered closest first unless the query has its own ORDER BY.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearestClause {
+    pub k: i64,
+    pub query: Expr,
+    pub var: String,
+    pub attr: String,
+    pub metric: NearestMetric,
+    pub span: Span,
+}
+
+/// Similarity metric for NEAREST, named after the matching expression function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NearestMetric {
+    /// cosine_similarity (the default)
+    #[default]
+    Cosine,
+    /// l2_distance
+    L2,
+}
+
 /// An OPTIONAL MATCH clause (left outer join).
 #[derive(Debug, Clone, PartialEq)]
 pub struct OptionalMatch {
@@ -94,6 +170,18 @@ pub struct MatchMutateStmt {
     pub span: Span,
 }
 
+/// UNWIND over a list, running mutations once per element.
+/// E.g., UNWIND $rows AS r SPAWN t: Task { title = r.title }
+///
+/// The whole batch is one statement: it succeeds or fails as a unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnwindStmt {
+    pub list: Expr,
+    pub var: String,
+    pub mutations: Vec<MutationAction>,
+    pub span: Span,
+}
+
 /// MATCH followed by WALK (compound statement).
 /// E.g., MATCH e: Employee WHERE ... WALK FROM e FOLLOW ...
 #[derive(Debug, Clone, PartialEq)]
@@ -138,6 +226,10 @@ pub struct EdgePattern {
     pub alias: Option<String>,
     /// Transitive modifier: None, Plus (+, one or more), Star (*, zero or more)
     pub transitive: Option<TransitiveKind>,
+    /// Lower hop bound from `[depth: min..max]`
+    pub min_depth: Option<i64>,
+    /// Upper hop bound from `[depth: max]` or `[depth: min..max]`
+    pub max_depth: Option<i64>,
     pub span: Span,
 }
 
@@ -197,9 +289,32 @@ pub struct SpawnItem {
     pub var: String,
     pub type_name: String,
     pub attrs: Vec<AttrAssignment>,
+    /// ON CONFLICT: what to do when the node already exists
+    pub on_conflict: Option<OnConflict>,
     pub span: Span,
 }
 
+/// ON CONFLICT clause of a SPAWN: `ON CONFLICT (attr, ...) DO NOTHING` or
+/// `ON CONFLICT (attr, ...) DO UPDATE SET attr = expr, ...`.
+///
+/// A node conflicts when it already holds the spawned value of one of the
+/// unique `attrs`. The SPAWN then binds that node instead of creating one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnConflict {
+    pub attrs: Vec<String>,
+    pub action: ConflictAction,
+    pub span: Span,
+}
+
+/// What an ON CONFLICT clause does with the existing node.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictAction {
+    /// Leave the node as it is
+    Nothing,
+    /// Update the node; the SPAWN variable refers to it in the values
+    Update(Vec<AttrAssignment>),
+}
+
 /// SPAWN statement for creating nodes.
 /// Supports both single and chained spawns:
 /// - Single: SPAWN t: Task { ... } [RETURNING ...]
@@ -225,6 +340,7 @@ impl SpawnStmt {
                 var,
                 type_name,
                 attrs,
+                on_conflict: None,
                 span,
             }],
             returning,
@@ -366,6 +482,7 @@ pub struct SetStmt {
 pub struct WalkStmt {
     pub from: Expr,
     pub follow: Vec<FollowClause>,
+    pub shortest: Option<ShortestClause>,
     pub until: Option<Expr>,
     pub return_type: WalkReturnType,
     pub span: Span,
@@ -381,6 +498,24 @@ pub struct FollowClause {
     pub span: Span,
 }
 
+/// SHORTEST clause in WALK: the cheapest paths to a target node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestClause {
+    pub paths: ShortestPaths,
+    pub to: Expr,
+    /// Edge attribute giving the cost of each edge; every edge costs 1 without it
+    pub weight: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortestPaths {
+    /// SHORTEST PATH, or SHORTEST k PATHS: the k cheapest paths
+    First(i64),
+    /// ALL SHORTEST PATHS: every path of the lowest cost
+    All,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum WalkDirection {
     #[default]
@@ -420,9 +555,17 @@ pub struct InspectStmt {
 /// Transaction statement.
 #[derive(Debug, Clone, PartialEq)]
 pub enum TxnStmt {
-    Begin { isolation: Option<IsolationLevel> },
+    Begin {
+        isolation: Option<IsolationLevel>,
+    },
     Commit,
     Rollback,
+    /// SAVEPOINT name
+    Savepoint(String),
+    /// ROLLBACK TO [SAVEPOINT] name
+    RollbackTo(String),
+    /// RELEASE [SAVEPOINT] name
+    Release(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -431,6 +574,175 @@ pub enum IsolationLevel {
     Serializable,
 }
 
+// ==================== WATCH ====================
+
+/// WATCH statement: a persistent query that delivers matches to the session.
+/// E.g., WATCH j: Job WHERE j.status = "pending" [mode: consume, ack_timeout: 30s] RETURN j
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchStmt {
+    pub pattern: Vec<PatternElem>,
+    pub where_clause: Option<Expr>,
+    pub options: WatchOptions,
+    pub return_clause: ReturnClause,
+    pub span: Span,
+}
+
+impl WatchStmt {
+    /// The MATCH query whose rows the watch delivers.
+    pub fn to_match(&self) -> MatchStmt {
+        MatchStmt {
+            pattern: self.pattern.clone(),
+            where_clause: self.where_clause.clone(),
+            optional_matches: Vec::new(),
+            nearest: None,
+            stages: Vec::new(),
+            return_clause: self.return_clause.clone(),
+            group_by: None,
+            having: None,
+            order_by: None,
+            limit: None,
+            offset: None,
+            span: self.span,
+        }
+    }
+}
+
+/// Options given in `[name: value, ...]` blocks of a WATCH statement.
+/// Values are expressions so that they may use parameters.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WatchOptions {
+    pub mode: WatchMode,
+    /// Consumer group name
+    pub group: Option<Expr>,
+    /// Time a delivery may stay unacknowledged before it is redelivered
+    pub ack_timeout: Option<Expr>,
+    /// Failed deliveries allowed before a message is dead-lettered
+    pub max_redeliveries: Option<Expr>,
+    /// Dead letter destination (a name or node reference)
+    pub dead_letter: Option<Expr>,
+}
+
+/// Watch delivery mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WatchMode {
+    /// Non-destructive observation (the default)
+    #[default]
+    Watch,
+    /// Destructive receipt with ACK/NACK
+    Consume,
+}
+
+/// ACK statement: confirms a consume-mode delivery.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AckStmt {
+    pub delivery_id: Expr,
+    pub span: Span,
+}
+
+/// NACK statement: rejects a consume-mode delivery.
+/// E.g., NACK "delivery_1_4" [no_retry]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NackStmt {
+    pub delivery_id: Expr,
+    /// Skip redelivery and dead-letter the message at once
+    pub no_retry: bool,
+    pub span: Span,
+}
+
+// ==================== IMPORT ====================
+
+/// IMPORT statement: bulk-loads a CSV or JSON Lines file.
+/// E.g., IMPORT Person(full_name AS name, email) FROM "people.csv"
+///       IMPORT knows(src: Person.email, dst: Person.email) FROM "knows.jsonl"
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportStmt {
+    /// Node type or edge type to create
+    pub target: String,
+    /// Column mappings; without attribute mappings every remaining column
+    /// maps to the attribute of the same name
+    pub columns: Vec<ImportColumn>,
+    /// Path of the file to read
+    pub source: Expr,
+    pub options: ImportOptions,
+    pub span: Span,
+}
+
+/// How one column of an imported file is used.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportColumn {
+    /// `column [AS attr]`: the column holds an attribute value
+    Attr { column: String, attr: String },
+    /// `column: Type.key`: the column holds the unique key of an edge
+    /// endpoint, in edge parameter order
+    Endpoint {
+        column: String,
+        type_name: String,
+        key: String,
+    },
+}
+
+/// Options given in `[name: value, ...]` blocks of an IMPORT statement.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportOptions {
+    /// File format; taken from the file extension when not given
+    pub format: Option<ImportFormat>,
+    /// Rows inserted between deferred constraint checks
+    pub batch: Option<Expr>,
+}
+
+/// File format of an IMPORT source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+// ==================== DUMP ====================
+
+/// DUMP statement: writes the ontology and the data as a MEW script.
+/// E.g., DUMP TO "backup.mew"
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpStmt {
+    pub path: Expr,
+    pub span: Span,
+}
+
+// ==================== CALL ====================
+
+/// CALL statement: runs a graph algorithm over edges of the given types.
+/// E.g., CALL algo.pagerank(follows, damping = 0.9) YIELD node, score WRITE rank
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallStmt {
+    /// Qualified procedure name, e.g. `algo.pagerank`
+    pub procedure: String,
+    /// Edge types to run over; empty for all
+    pub edge_types: Vec<String>,
+    pub options: Vec<CallOption>,
+    /// Columns to return; empty for all, unless the result is written
+    pub yields: Vec<YieldItem>,
+    /// Node attribute to store the result in
+    pub write: Option<String>,
+    pub span: Span,
+}
+
+/// A named procedure argument: `damping = 0.9`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallOption {
+    pub name: String,
+    pub value: Expr,
+    pub span: Span,
+}
+
+/// A YIELD column, optionally renamed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YieldItem {
+    pub column: String,
+    pub alias: Option<String>,
+    pub span: Span,
+}
+
 // ==================== EXPRESSIONS ====================
 
 /// Expression types.
@@ -456,8 +768,16 @@ pub enum Expr {
     Exists(Vec<PatternElem>, Option<Box<Expr>>, Span),
     /// NOT EXISTS subpattern
     NotExists(Vec<PatternElem>, Option<Box<Expr>>, Span),
+    /// Pattern subquery: COUNT { }, COLLECT { } or a scalar { MATCH ... RETURN ... }
+    Subquery(Box<Subquery>),
+    /// List quantifier: ANY(x IN list WHERE ...) or ALL(x IN list WHERE ...)
+    Quantifier(Box<Quantifier>),
+    /// Subscript: list[i] (negative counts from the end) or map["key"]
+    Index(Box<Expr>, Box<Expr>, Span),
     /// List literal: [a, b, c]
     List(Vec<Expr>, Span),
+    /// Map literal: {key: value, ...}
+    Map(Vec<(String, Expr)>, Span),
     /// Type check: expr:Type (checks if expr is of type Type)
     TypeCheck(Box<Expr>, String, Span),
 }
@@ -475,7 +795,11 @@ impl Expr {
             Expr::Param(_, span) => *span,
             Expr::Exists(_, _, span) => *span,
             Expr::NotExists(_, _, span) => *span,
+            Expr::Subquery(sq) => sq.span,
+            Expr::Quantifier(q) => q.span,
+            Expr::Index(_, _, span) => *span,
             Expr::List(_, span) => *span,
+            Expr::Map(_, span) => *span,
             Expr::TypeCheck(_, _, span) => *span,
         }
     }
@@ -499,6 +823,10 @@ pub enum LiteralKind {
     Duration(i64),
     /// Timestamp as milliseconds since Unix epoch
     Timestamp(i64),
+    /// Raw bytes: 0xdeadbeef
+    Bytes(Vec<u8>),
+    /// 32-byte content hash: 0h<64 hex digits>
+    Hash([u8; 32]),
 }
 
 impl fmt::Display for LiteralKind {
@@ -511,6 +839,14 @@ impl fmt::Display for LiteralKind {
             LiteralKind::String(s) => write!(f, "\"{}\"", s),
             LiteralKind::Duration(ms) => write!(f, "{}ms", ms),
             LiteralKind::Timestamp(ms) => write!(f, "@{}", ms),
+            LiteralKind::Bytes(bytes) => {
+                write!(f, "0x")?;
+                bytes.iter().try_for_each(|b| write!(f, "{:02x}", b))
+            }
+            LiteralKind::Hash(digest) => {
+                write!(f, "0h")?;
+                digest.iter().try_for_each(|b| write!(f, "{:02x}", b))
+            }
         }
     }
 }
@@ -569,6 +905,50 @@ pub enum UnaryOp {
     Neg,
 }
 
+/// Pattern subquery used as a value. Variables bound outside it are shared
+/// with its pattern, so it runs once per outer row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subquery {
+    pub kind: SubqueryKind,
+    pub pattern: Vec<PatternElem>,
+    pub where_clause: Option<Expr>,
+    /// Value produced per match (`RETURN expr`); COUNT has none.
+    pub projection: Option<Expr>,
+    pub span: Span,
+}
+
+/// Predicate over the elements of a list. The variable is scoped to the
+/// predicate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantifier {
+    pub kind: QuantifierKind,
+    pub var: String,
+    pub list: Expr,
+    pub predicate: Expr,
+    pub span: Span,
+}
+
+/// How many list elements must satisfy a quantifier's predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantifierKind {
+    /// `ANY(x IN list WHERE ...)`: at least one.
+    Any,
+    /// `ALL(x IN list WHERE ...)`: every one; true for an empty list.
+    All,
+}
+
+/// What a pattern subquery produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubqueryKind {
+    /// `COUNT { pattern WHERE ... }`: the number of matches.
+    Count,
+    /// `COLLECT { pattern WHERE ... RETURN expr }`: a list with one value per match.
+    Collect,
+    /// `{ MATCH pattern WHERE ... RETURN expr }`: the value for the single match,
+    /// null when there is none.
+    Scalar,
+}
+
 /// Function call.
 #[derive(Debug, Clone, PartialEq)]
 pub struct FnCall {
@@ -582,6 +962,27 @@ pub struct FnCall {
     pub span: Span,
 }
 
+impl FnCall {
+    /// The variable and node type an aggregate over a pattern declares, as
+    /// `c` and `Copy` in `count(c: Copy, copy_of(c, b))`. Such aggregates
+    /// count or collect the pattern's matches for each row.
+    pub fn pattern_var(&self) -> Option<(&str, &str)> {
+        if !matches!(
+            self.name.to_lowercase().as_str(),
+            "count" | "sum" | "avg" | "min" | "max" | "collect"
+        ) {
+            return None;
+        }
+        match self.args.first()? {
+            Expr::TypeCheck(base, type_name, _) => match base.as_ref() {
+                Expr::Var(var, _) => Some((var, type_name)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
 /// Limit specification for COLLECT aggregation.
 #[derive(Debug, Clone, PartialEq)]
 pub enum CollectLimit {
@@ -639,6 +1040,8 @@ pub enum AttrModifier {
     Unique,
     /// readonly - attribute cannot be modified after creation
     Readonly,
+    /// searchable - string attribute kept in a full-text index
+    Searchable,
     Default(Expr),
     Range {
         min: Option<Expr>,
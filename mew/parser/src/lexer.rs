@@ -73,6 +73,10 @@ pub enum TokenKind {
     String(String),
     /// Timestamp literal (milliseconds since epoch)
     Timestamp(i64),
+    /// Bytes literal: 0x48656c6c6f
+    Bytes(Vec<u8>),
+    /// Hash literal: 0h followed by 64 hex digits
+    HashLit([u8; 32]),
 
     // Symbols
     LParen,       // (
@@ -176,6 +180,8 @@ impl TokenKind {
             TokenKind::Float(_) => "float",
             TokenKind::String(_) => "string",
             TokenKind::Timestamp(_) => "timestamp",
+            TokenKind::Bytes(_) => "bytes",
+            TokenKind::HashLit(_) => "hash",
             TokenKind::LParen => "(",
             TokenKind::RParen => ")",
             TokenKind::LBrace => "{",
@@ -630,6 +636,18 @@ impl<'a> Lexer<'a> {
         start_line: usize,
         start_col: usize,
     ) -> ParseResult<TokenKind> {
+        // Bytes literal: 0x followed by hex digit pairs
+        if first == '0' && self.peek_char() == Some('x') {
+            self.next_char();
+            return self.scan_bytes(start, start_line, start_col);
+        }
+
+        // Hash literal: 0h followed by exactly 64 hex digits
+        if first == '0' && self.peek_char() == Some('h') {
+            self.next_char();
+            return self.scan_hash(start, start_line, start_col);
+        }
+
         let mut number = String::new();
         number.push(first);
 
@@ -696,6 +714,76 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// Scan the hex digits of a bytes literal (after the 0x prefix).
+    fn scan_bytes(
+        &mut self,
+        start: usize,
+        start_line: usize,
+        start_col: usize,
+    ) -> ParseResult<TokenKind> {
+        let mut digits = String::new();
+        while let Some(c) = self.peek_char() {
+            if c.is_ascii_hexdigit() {
+                digits.push(c);
+                self.next_char();
+            } else {
+                break;
+            }
+        }
+
+        if !digits.len().is_multiple_of(2) {
+            return Err(ParseError::new(
+                format!(
+                    "bytes literal '0x{}' must have an even number of digits",
+                    digits
+                ),
+                self.span_from(start, start_line, start_col),
+            ));
+        }
+
+        let bytes = (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("validated hex digits"))
+            .collect();
+        Ok(TokenKind::Bytes(bytes))
+    }
+
+    /// Scan the hex digits of a hash literal (after the 0h prefix).
+    fn scan_hash(
+        &mut self,
+        start: usize,
+        start_line: usize,
+        start_col: usize,
+    ) -> ParseResult<TokenKind> {
+        let mut digits = String::with_capacity(64);
+        while let Some(c) = self.peek_char() {
+            if c.is_ascii_hexdigit() {
+                digits.push(c);
+                self.next_char();
+            } else {
+                break;
+            }
+        }
+
+        if digits.len() != 64 {
+            return Err(ParseError::new(
+                format!(
+                    "hash literal '0h{}' must have exactly 64 hex digits, got {}",
+                    digits,
+                    digits.len()
+                ),
+                self.span_from(start, start_line, start_col),
+            ));
+        }
+
+        let mut digest = [0u8; 32];
+        for (i, byte) in digest.iter_mut().enumerate() {
+            *byte =
+                u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).expect("validated hex digits");
+        }
+        Ok(TokenKind::HashLit(digest))
+    }
+
     /// Scan the exponent part of a number (e.g., e10, E-5, e+3)
     fn scan_exponent(&mut self, number: &mut String) -> ParseResult<()> {
         // Consume 'e' or 'E'
@@ -1226,4 +1314,34 @@ mod tests {
         let kinds = tokenize("3.14E10");
         assert_eq!(kinds, vec![TokenKind::Float(3.14e10), TokenKind::Eof]);
     }
+
+    #[test]
+    fn test_binary_literals() {
+        // Bytes literal
+        let kinds = tokenize("0xDEADbeef");
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Bytes(vec![0xde, 0xad, 0xbe, 0xef]),
+                TokenKind::Eof
+            ]
+        );
+
+        // Hash literal: 0h followed by exactly 64 hex digits
+        let digest = "ab".repeat(32);
+        let kinds = tokenize(&format!("0h{}", digest));
+        assert_eq!(kinds, vec![TokenKind::HashLit([0xab; 32]), TokenKind::Eof]);
+
+        // '#' always starts an ID reference, even before 64 hex digits
+        let kinds = tokenize(&format!("#{}", digest));
+        assert_eq!(kinds[0], TokenKind::Hash);
+        assert!(!kinds.iter().any(|k| matches!(k, TokenKind::HashLit(_))));
+
+        // Odd number of hex digits is rejected
+        assert!(Lexer::new("0xabc").tokenize().is_err());
+
+        // A hash with any other number of hex digits is rejected
+        assert!(Lexer::new("0habcd").tokenize().is_err());
+        assert!(Lexer::new(&format!("0h{}ab", digest)).tokenize().is_err());
+    }
 }
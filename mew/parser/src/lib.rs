@@ -1,7 +1,7 @@
 //! MEW Parser
 //!
 //! This crate provides parsing for MEW source text:
-//! - Statement parsing (MATCH, SPAWN, KILL, LINK, UNLINK, SET, BEGIN, COMMIT, ROLLBACK)
+//! - Statement parsing (MATCH, SPAWN, KILL, LINK, UNLINK, SET, BEGIN, COMMIT, ROLLBACK, IMPORT, DUMP, CALL)
 //! - Expression parsing (arithmetic, comparison, function calls)
 //! - Ontology parsing (node, edge, constraint, rule definitions)
 //! - Error handling with location information
@@ -13,4 +13,4 @@ mod parser;
 
 pub use ast::*;
 pub use error::*;
-pub use parser::{parse_ontology, parse_stmt, parse_stmts, Parser};
+pub use parser::{parse_literal, parse_ontology, parse_stmt, parse_stmts, Parser};
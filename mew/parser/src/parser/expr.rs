@@ -6,8 +6,8 @@
 //! - Additive: +, -, ++
 //! - Multiplicative: *, /, %
 //! - Unary: -, NOT
-//! - Postfix: attribute access (.)
-//! - Primary: literals, variables, function calls, EXISTS
+//! - Postfix: attribute access (.), subscripts ([i])
+//! - Primary: literals, variables, function calls, EXISTS, subqueries, quantifiers
 
 use super::Parser;
 use crate::ast::{CollectLimit, *};
@@ -275,10 +275,23 @@ impl Parser {
             return Ok(Expr::TypeCheck(Box::new(expr), type_name, span));
         }
 
-        // Handle attribute access: expr.attr
+        // Handle attribute access: expr.attr, subscripts: expr[i]
         // And duration literals: 30.seconds, 5.minutes, etc.
-        while self.check(&TokenKind::Dot) {
+        loop {
             let start = expr.span();
+            // A subscript bracket follows its operand directly, so `[` after a
+            // space still opens statement options such as WATCH ... [mode: ...]
+            if self.check(&TokenKind::LBracket) && self.peek().span.start == start.end {
+                self.advance();
+                let index = self.parse_expr()?;
+                self.expect(&TokenKind::RBracket)?;
+                let span = self.span_from(start);
+                expr = Expr::Index(Box::new(expr), Box::new(index), span);
+                continue;
+            }
+            if !self.check(&TokenKind::Dot) {
+                break;
+            }
             self.advance();
             let attr = self.expect_ident()?;
 
@@ -396,6 +409,22 @@ impl Parser {
                     span: token.span,
                 }))
             }
+            TokenKind::Bytes(bytes) => {
+                let bytes = bytes.clone();
+                self.advance();
+                Ok(Expr::Literal(Literal {
+                    kind: LiteralKind::Bytes(bytes),
+                    span: token.span,
+                }))
+            }
+            TokenKind::HashLit(digest) => {
+                let digest = *digest;
+                self.advance();
+                Ok(Expr::Literal(Literal {
+                    kind: LiteralKind::Hash(digest),
+                    span: token.span,
+                }))
+            }
             TokenKind::String(s) => {
                 let s = s.clone();
                 self.advance();
@@ -483,6 +512,35 @@ impl Parser {
                 Ok(Expr::List(elements, span))
             }
 
+            // Map literal: {key: value, "other key": value}
+            // or scalar subquery: { MATCH pattern WHERE ... RETURN expr }
+            TokenKind::LBrace => {
+                let start = self.advance().span;
+                if self.check(&TokenKind::Match) {
+                    return self.parse_subquery_body(SubqueryKind::Scalar, start);
+                }
+                let mut entries = Vec::new();
+                while !self.check(&TokenKind::RBrace) {
+                    let key = match &self.peek().kind {
+                        TokenKind::String(s) => {
+                            let s = s.clone();
+                            self.advance();
+                            s
+                        }
+                        _ => self.expect_name()?,
+                    };
+                    self.expect(&TokenKind::Colon)?;
+                    entries.push((key, self.parse_expr()?));
+                    if !self.check(&TokenKind::Comma) {
+                        break;
+                    }
+                    self.advance();
+                }
+                self.expect(&TokenKind::RBrace)?;
+                let span = self.span_from(start);
+                Ok(Expr::Map(entries, span))
+            }
+
             // Handle 'node' and 'edge' keywords as special built-in variables
             // These are used in WALK UNTIL clauses: UNTIL node.status = "done"
             TokenKind::Node => {
@@ -494,11 +552,40 @@ impl Parser {
                 Ok(Expr::Var("edge".to_string(), token.span))
             }
 
+            // ANY(x IN list WHERE ...)
+            TokenKind::Any
+                if matches!(
+                    self.tokens.get(self.pos + 1).map(|t| &t.kind),
+                    Some(TokenKind::LParen)
+                ) =>
+            {
+                self.advance();
+                self.parse_quantifier(QuantifierKind::Any, token.span)
+            }
+
             // Identifier (variable or function call)
             TokenKind::Ident(name) => {
                 let name = name.clone();
                 self.advance();
 
+                // ALL(x IN list WHERE ...)
+                if name.eq_ignore_ascii_case("all") && self.check(&TokenKind::LParen) {
+                    return self.parse_quantifier(QuantifierKind::All, token.span);
+                }
+
+                // COUNT { ... } / COLLECT { ... } subquery
+                if self.check(&TokenKind::LBrace) {
+                    let kind = match name.to_lowercase().as_str() {
+                        "count" => Some(SubqueryKind::Count),
+                        "collect" => Some(SubqueryKind::Collect),
+                        _ => None,
+                    };
+                    if let Some(kind) = kind {
+                        self.advance();
+                        return self.parse_subquery_body(kind, token.span);
+                    }
+                }
+
                 // Check for function call
                 if self.check(&TokenKind::LParen) {
                     self.advance();
@@ -539,19 +626,15 @@ impl Parser {
                     self.expect(&TokenKind::RParen)?;
 
                     // Check for [limit: N] syntax (used with COLLECT)
-                    let limit = if self.check(&TokenKind::LBracket) {
+                    // Any other bracket is a subscript on the call's result
+                    let next = self.tokens.get(self.pos + 1).map(|t| &t.kind);
+                    let limit = if self.check(&TokenKind::LBracket)
+                        && (matches!(next, Some(TokenKind::Limit))
+                            || matches!(next, Some(TokenKind::Ident(n)) if n.eq_ignore_ascii_case("limit")))
+                    {
                         self.advance();
                         // "limit" can be either the Limit keyword or an identifier
-                        if self.check(&TokenKind::Limit) || self.check_ident("limit") {
-                            self.advance();
-                        } else {
-                            let token = self.peek();
-                            return Err(crate::ParseError::unexpected_token(
-                                token.span,
-                                "limit",
-                                token.kind.name(),
-                            ));
-                        }
+                        self.advance();
                         self.expect(&TokenKind::Colon)?;
                         let limit_value = if self.check_ident("none") {
                             self.advance();
@@ -586,4 +669,52 @@ impl Parser {
             )),
         }
     }
+
+    /// Parse a quantifier after its keyword: `(var IN list WHERE predicate)`.
+    fn parse_quantifier(&mut self, kind: QuantifierKind, start: Span) -> ParseResult<Expr> {
+        self.expect(&TokenKind::LParen)?;
+        let var = self.expect_ident()?;
+        self.expect(&TokenKind::In)?;
+        let list = self.parse_expr()?;
+        self.expect(&TokenKind::Where)?;
+        let predicate = self.parse_expr()?;
+        self.expect(&TokenKind::RParen)?;
+        Ok(Expr::Quantifier(Box::new(Quantifier {
+            kind,
+            var,
+            list,
+            predicate,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// Parse a subquery after its opening brace: an optional MATCH, the
+    /// pattern, an optional WHERE, the RETURN (except for COUNT) and `}`.
+    fn parse_subquery_body(&mut self, kind: SubqueryKind, start: Span) -> ParseResult<Expr> {
+        if self.check(&TokenKind::Match) {
+            self.advance();
+        }
+        let pattern = self.parse_pattern()?;
+        let where_clause = if self.check(&TokenKind::Where) {
+            self.advance();
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        let projection = if kind == SubqueryKind::Count {
+            None
+        } else {
+            self.expect(&TokenKind::Return)?;
+            Some(self.parse_expr()?)
+        };
+        self.expect(&TokenKind::RBrace)?;
+        let span = self.span_from(start);
+        Ok(Expr::Subquery(Box::new(Subquery {
+            kind,
+            pattern,
+            where_clause,
+            projection,
+            span,
+        })))
+    }
 }
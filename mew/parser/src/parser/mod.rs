@@ -174,6 +174,35 @@ pub fn parse_match(input: &str) -> ParseResult<MatchStmt> {
     }
 }
 
+/// Parse a single literal such as `42`, `-1.5`, `30.seconds`, `@2024-01-15` or
+/// `0xff`. A leading minus is folded into numbers and durations.
+pub fn parse_literal(input: &str) -> ParseResult<LiteralKind> {
+    let mut parser = Parser::new(input)?;
+    let expr = parser.parse_expr()?;
+    parser.expect(&TokenKind::Eof)?;
+    let span = expr.span();
+    let kind = match expr {
+        Expr::Literal(lit) => Some(lit.kind),
+        Expr::UnaryOp(UnaryOp::Neg, inner, _) => match *inner {
+            Expr::Literal(Literal {
+                kind: LiteralKind::Int(n),
+                ..
+            }) => Some(LiteralKind::Int(-n)),
+            Expr::Literal(Literal {
+                kind: LiteralKind::Float(f),
+                ..
+            }) => Some(LiteralKind::Float(-f)),
+            Expr::Literal(Literal {
+                kind: LiteralKind::Duration(ms),
+                ..
+            }) => Some(LiteralKind::Duration(-ms)),
+            _ => None,
+        },
+        _ => None,
+    };
+    kind.ok_or_else(|| ParseError::new("expected a literal", span))
+}
+
 /// Parse ontology definitions from source text.
 pub fn parse_ontology(input: &str) -> ParseResult<Vec<OntologyDef>> {
     Parser::new(input)?.parse_ontology_defs()
@@ -218,6 +247,101 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_match_transitive_with_depth() {
+        // GIVEN transitive edge patterns with and without depth bounds
+        let stmt = parse_match(
+            "MATCH a: Task, b: Task, depends_on+(a, b) [depth: 2..4], parent_of*(a, c) [depth: 3] RETURN b",
+        )
+        .unwrap();
+
+        // THEN the modifier and the bounds are kept on the edge patterns
+        match (&stmt.pattern[2], &stmt.pattern[3]) {
+            (PatternElem::Edge(plus), PatternElem::Edge(star)) => {
+                assert_eq!(plus.transitive, Some(TransitiveKind::Plus));
+                assert_eq!((plus.min_depth, plus.max_depth), (Some(2), Some(4)));
+                assert_eq!(star.transitive, Some(TransitiveKind::Star));
+                assert_eq!((star.min_depth, star.max_depth), (None, Some(3)));
+            }
+            _ => panic!("Expected edge patterns"),
+        }
+
+        // AND a depth bound needs a transitive pattern
+        assert!(
+            parse_match("MATCH a: Task, b: Task, depends_on(a, b) [depth: 2] RETURN b").is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_match_with_stages_and_grouping() {
+        // GIVEN a query with a WITH stage, GROUP BY and HAVING
+        let stmt = parse_match(
+            "MATCH p: Project, t: Task, belongs_to(t, p) \
+             WITH DISTINCT p, avg(t.priority) AS avg_priority ORDER BY avg_priority DESC LIMIT 5 \
+             MATCH a: Person, assigned_to(t2, a), belongs_to(t2, p) \
+             WHERE avg_priority > 3 \
+             RETURN p.name, a.name, count(t2) AS n GROUP BY p.name, a.name HAVING n > 1",
+        )
+        .unwrap();
+
+        // THEN the stage keeps its projection, ordering, pattern and filter
+        assert_eq!(stmt.stages.len(), 1);
+        let stage = &stmt.stages[0];
+        assert!(stage.distinct);
+        assert_eq!(stage.projections.len(), 2);
+        assert_eq!(stage.projections[1].alias.as_deref(), Some("avg_priority"));
+        assert_eq!(stage.order_by.as_ref().map(Vec::len), Some(1));
+        assert_eq!(stage.limit, Some(5));
+        assert_eq!(stage.pattern.len(), 3);
+        assert!(stage.where_clause.is_some());
+
+        // AND the RETURN has explicit grouping
+        assert_eq!(stmt.group_by.as_ref().map(Vec::len), Some(2));
+        assert!(stmt.having.is_some());
+
+        // AND HAVING needs a RETURN or WITH before it
+        assert!(parse_match("MATCH t: Task HAVING count(t) > 1 RETURN t").is_err());
+    }
+
+    #[test]
+    fn test_parse_union() {
+        // GIVEN two MATCH queries joined by UNION ALL with a trailing ORDER BY
+        let stmt = parse_stmt(
+            "MATCH t: Task RETURN t.title AS title \
+             UNION ALL MATCH p: Project RETURN p.name AS title \
+             ORDER BY title LIMIT 3",
+        )
+        .unwrap();
+
+        // THEN both branches are kept and the ordering belongs to the union
+        let Stmt::Union(union) = stmt else {
+            panic!("Expected UNION statement");
+        };
+        assert!(union.all);
+        assert_eq!(union.branches.len(), 2);
+        assert_eq!(union.order_by.as_ref().map(Vec::len), Some(1));
+        assert_eq!(union.limit, Some(3));
+        assert!(union.branches[1].order_by.is_none());
+        assert!(union.branches[1].limit.is_none());
+
+        // AND plain UNION removes duplicates
+        let Stmt::Union(union) =
+            parse_stmt("MATCH t: Task RETURN t UNION MATCH t: Task RETURN t").unwrap()
+        else {
+            panic!("Expected UNION statement");
+        };
+        assert!(!union.all);
+
+        // AND UNION and UNION ALL cannot be mixed
+        assert!(parse_stmt(
+            "MATCH a: Task RETURN a UNION MATCH b: Task RETURN b UNION ALL MATCH c: Task RETURN c"
+        )
+        .is_err());
+
+        // AND only the last branch may order or limit
+        assert!(parse_stmt("MATCH a: Task RETURN a LIMIT 1 UNION MATCH b: Task RETURN b").is_err());
+    }
+
     #[test]
     fn test_parse_match_with_where() {
         let stmt = parse_match("MATCH t: Task WHERE t.priority > 5 RETURN t.title").unwrap();
@@ -247,6 +371,304 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_match_nearest() {
+        // GIVEN
+        let input = "MATCH d: Doc NEAREST 3 TO $q ON d.embedding USING l2_distance RETURN d";
+
+        // WHEN
+        let stmt = parse_match(input).unwrap();
+
+        // THEN
+        let nearest = stmt.nearest.expect("nearest clause");
+        assert_eq!(nearest.k, 3);
+        assert_eq!(nearest.var, "d");
+        assert_eq!(nearest.attr, "embedding");
+        assert_eq!(nearest.metric, NearestMetric::L2);
+        assert!(matches!(nearest.query, Expr::Param(ref name, _) if name == "q"));
+    }
+
+    #[test]
+    fn test_parse_watch_consume_options() {
+        // GIVEN
+        let input = r#"WATCH j: Job WHERE j.status = "pending"
+            [mode: consume]
+            [ack_timeout: 30.seconds, max_redeliveries: 3]
+            [dead_letter: #failed_jobs]
+            RETURN j"#;
+
+        // WHEN
+        let stmt = parse_stmt(input).unwrap();
+
+        // THEN
+        match stmt {
+            Stmt::Watch(w) => {
+                assert_eq!(w.options.mode, WatchMode::Consume);
+                assert!(w.where_clause.is_some());
+                assert!(matches!(
+                    w.options.ack_timeout,
+                    Some(Expr::Literal(Literal {
+                        kind: LiteralKind::Duration(30_000),
+                        ..
+                    }))
+                ));
+                assert!(matches!(
+                    w.options.max_redeliveries,
+                    Some(Expr::Literal(Literal {
+                        kind: LiteralKind::Int(3),
+                        ..
+                    }))
+                ));
+                assert!(
+                    matches!(w.options.dead_letter, Some(Expr::IdRef(ref n, _)) if n == "failed_jobs")
+                );
+                assert_eq!(w.return_clause.projections.len(), 1);
+            }
+            _ => panic!("Expected WATCH statement"),
+        }
+        assert!(parse_stmt("WATCH j: Job [color: red] RETURN j").is_err());
+    }
+
+    #[test]
+    fn test_parse_ack_nack() {
+        // GIVEN
+        let ack = r#"ACK "delivery_1_1""#;
+        let nack = r#"NACK $id [no_retry]"#;
+
+        // WHEN
+        let ack = parse_stmt(ack).unwrap();
+        let nack = parse_stmt(nack).unwrap();
+
+        // THEN
+        assert!(matches!(ack, Stmt::Ack(_)));
+        match nack {
+            Stmt::Nack(n) => {
+                assert!(n.no_retry);
+                assert!(matches!(n.delivery_id, Expr::Param(ref name, _) if name == "id"));
+            }
+            _ => panic!("Expected NACK statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_import() {
+        // GIVEN
+        let nodes = r#"IMPORT Person(full_name AS name, email) FROM "people.csv""#;
+        let edges = r#"IMPORT knows(src: Person.email, dst: Person.email, since) FROM $file [format: jsonl, batch: 500]"#;
+
+        // WHEN
+        let nodes = parse_stmt(nodes).unwrap();
+        let edges = parse_stmt(edges).unwrap();
+
+        // THEN
+        match nodes {
+            Stmt::Import(i) => {
+                assert_eq!(i.target, "Person");
+                assert_eq!(
+                    i.columns,
+                    vec![
+                        ImportColumn::Attr {
+                            column: "full_name".into(),
+                            attr: "name".into()
+                        },
+                        ImportColumn::Attr {
+                            column: "email".into(),
+                            attr: "email".into()
+                        },
+                    ]
+                );
+                assert_eq!(i.options, ImportOptions::default());
+            }
+            _ => panic!("Expected IMPORT statement"),
+        }
+        match edges {
+            Stmt::Import(i) => {
+                assert_eq!(
+                    i.columns[1],
+                    ImportColumn::Endpoint {
+                        column: "dst".into(),
+                        type_name: "Person".into(),
+                        key: "email".into()
+                    }
+                );
+                assert_eq!(i.options.format, Some(ImportFormat::JsonLines));
+                assert!(i.options.batch.is_some());
+                assert!(matches!(i.source, Expr::Param(ref name, _) if name == "file"));
+            }
+            _ => panic!("Expected IMPORT statement"),
+        }
+        assert!(parse_stmt(r#"IMPORT Person FROM "p.csv" [format: xml]"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_dump() {
+        // GIVEN
+        let input = r#"DUMP TO "backup.mew""#;
+
+        // WHEN
+        let stmt = parse_stmt(input).unwrap();
+
+        // THEN
+        match stmt {
+            Stmt::Dump(d) => assert!(matches!(
+                d.path,
+                Expr::Literal(Literal { kind: LiteralKind::String(ref s), .. }) if s == "backup.mew"
+            )),
+            _ => panic!("Expected DUMP statement"),
+        }
+        assert!(parse_stmt("DUMP \"backup.mew\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_call() {
+        // GIVEN
+        let input =
+            "CALL algo.pagerank(follows, likes, damping = 0.9) YIELD node AS n, score WRITE rank";
+
+        // WHEN
+        let stmt = parse_stmt(input).unwrap();
+
+        // THEN
+        let Stmt::Call(call) = stmt else {
+            panic!("Expected CALL statement");
+        };
+        assert_eq!(call.procedure, "algo.pagerank");
+        assert_eq!(call.edge_types, vec!["follows", "likes"]);
+        assert_eq!(call.options.len(), 1);
+        assert_eq!(call.options[0].name, "damping");
+        assert_eq!(call.yields[0].column, "node");
+        assert_eq!(call.yields[0].alias.as_deref(), Some("n"));
+        assert_eq!(call.yields[1].column, "score");
+        assert_eq!(call.write.as_deref(), Some("rank"));
+
+        // AND no arguments means every edge type
+        let Stmt::Call(call) = parse_stmt("CALL algo.wcc()").unwrap() else {
+            panic!("Expected CALL statement");
+        };
+        assert!(call.edge_types.is_empty() && call.yields.is_empty());
+        assert!(parse_stmt("CALL algo.pagerank(damping = 0.9, follows)").is_err());
+    }
+
+    #[test]
+    fn test_parse_walk_shortest() {
+        // GIVEN
+        let input = "WALK FROM a FOLLOW road SHORTEST 3 PATHS TO b WEIGHT km RETURN PATH AS route";
+
+        // WHEN
+        let stmt = parse_stmt(input).unwrap();
+
+        // THEN
+        match stmt {
+            Stmt::Walk(w) => {
+                let shortest = w.shortest.unwrap();
+                assert_eq!(shortest.paths, ShortestPaths::First(3));
+                assert!(matches!(shortest.to, Expr::Var(ref name, _) if name == "b"));
+                assert_eq!(shortest.weight.as_deref(), Some("km"));
+                assert_eq!(
+                    w.return_type,
+                    WalkReturnType::Path {
+                        alias: Some("route".to_string())
+                    }
+                );
+            }
+            _ => panic!("Expected WALK statement"),
+        }
+
+        // AND the other forms parse too
+        let all = parse_stmt("WALK FROM a FOLLOW road ALL SHORTEST PATHS TO b RETURN PATH");
+        match all.unwrap() {
+            Stmt::Walk(w) => assert_eq!(w.shortest.unwrap().paths, ShortestPaths::All),
+            _ => panic!("Expected WALK statement"),
+        }
+        let one = parse_stmt("WALK FROM a FOLLOW road SHORTEST PATH TO b RETURN PATH");
+        match one.unwrap() {
+            Stmt::Walk(w) => assert_eq!(w.shortest.unwrap().paths, ShortestPaths::First(1)),
+            _ => panic!("Expected WALK statement"),
+        }
+        assert!(parse_stmt("WALK FROM a FOLLOW road SHORTEST 0 PATHS TO b RETURN PATH").is_err());
+    }
+
+    #[test]
+    fn test_parse_literal() {
+        assert_eq!(parse_literal("-42").unwrap(), LiteralKind::Int(-42));
+        assert_eq!(parse_literal("1.5").unwrap(), LiteralKind::Float(1.5));
+        assert_eq!(
+            parse_literal("30.seconds").unwrap(),
+            LiteralKind::Duration(30_000)
+        );
+        assert_eq!(
+            parse_literal("@1970-01-02").unwrap(),
+            LiteralKind::Timestamp(86_400_000)
+        );
+        assert!(parse_literal("1 + 2").is_err());
+        assert!(parse_literal("name").is_err());
+    }
+
+    #[test]
+    fn test_parse_vector_attr_type() {
+        // GIVEN
+        let input = "node Doc { embedding: Vector[3] [required] }";
+
+        // WHEN
+        let defs = parse_ontology(input).unwrap();
+
+        // THEN
+        match &defs[0] {
+            OntologyDef::Node(n) => {
+                assert_eq!(n.attrs[0].type_name, "Vector[3]");
+                assert_eq!(n.attrs[0].modifiers, vec![AttrModifier::Required]);
+            }
+            _ => panic!("Expected node type"),
+        }
+    }
+
+    #[test]
+    fn test_parse_list_attr_type() {
+        // GIVEN
+        let input = "node Bookmark { tags: [String]? [required] = [] }";
+
+        // WHEN
+        let defs = parse_ontology(input).unwrap();
+
+        // THEN
+        match &defs[0] {
+            OntologyDef::Node(n) => {
+                assert_eq!(n.attrs[0].type_name, "[String]");
+                assert!(n.attrs[0].nullable);
+                assert_eq!(n.attrs[0].modifiers, vec![AttrModifier::Required]);
+            }
+            _ => panic!("Expected node type"),
+        }
+    }
+
+    #[test]
+    fn test_parse_subscripts_and_quantifiers() {
+        // GIVEN subscripts on attributes and calls, and a quantifier
+        let stmt = parse_match(
+            r#"MATCH b: Bookmark WHERE ANY(t IN b.tags WHERE t = "rust")
+               RETURN b.tags[0], split(b.url, "/")[-1], collect(b)[limit: 5]"#,
+        )
+        .unwrap();
+
+        // THEN
+        match stmt.where_clause.unwrap() {
+            Expr::Quantifier(q) => {
+                assert_eq!(q.kind, QuantifierKind::Any);
+                assert_eq!(q.var, "t");
+                assert!(matches!(q.list, Expr::AttrAccess(_, ref attr, _) if attr == "tags"));
+            }
+            other => panic!("Expected quantifier, got {:?}", other),
+        }
+        let projections = &stmt.return_clause.projections;
+        assert!(matches!(projections[0].expr, Expr::Index(..)));
+        assert!(matches!(projections[1].expr, Expr::Index(..)));
+        assert!(matches!(&projections[2].expr, Expr::FnCall(fc) if fc.limit.is_some()));
+
+        // AND a bracket after a space still starts statement options
+        assert!(parse_stmt("NACK $delivery [no_retry]").is_ok());
+        assert!(parse_stmt("WATCH t: Task WHERE t.done = false [mode: consume] RETURN t").is_ok());
+    }
+
     // ==================== SPAWN TESTS ====================
 
     #[test]
@@ -265,6 +687,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_spawn_on_conflict() {
+        // GIVEN an upsert that updates two attributes and returns the id
+        let stmt = parse_stmt(
+            r#"SPAWN p: Person { email = "a@b", name = "A" }
+               ON CONFLICT (email) DO UPDATE SET name = "A", visits = p.visits + 1
+               RETURNING id"#,
+        )
+        .unwrap();
+
+        // THEN the clause belongs to the spawn item
+        let Stmt::Spawn(s) = stmt else {
+            panic!("Expected SPAWN");
+        };
+        let on_conflict = s.items[0].on_conflict.as_ref().unwrap();
+        assert_eq!(on_conflict.attrs, vec!["email".to_string()]);
+        match &on_conflict.action {
+            ConflictAction::Update(assignments) => {
+                let names: Vec<_> = assignments.iter().map(|a| a.name.as_str()).collect();
+                assert_eq!(names, vec!["name", "visits"]);
+            }
+            other => panic!("Expected DO UPDATE, got {:?}", other),
+        }
+        assert_eq!(s.returning, Some(ReturningClause::Id));
+
+        // AND DO NOTHING works in chained spawns
+        let Stmt::Spawn(s) = parse_stmt(
+            r#"SPAWN a: Person { email = "a" } ON CONFLICT (email) DO NOTHING,
+               SPAWN b: Person { email = "b" }"#,
+        )
+        .unwrap() else {
+            panic!("Expected SPAWN");
+        };
+        assert_eq!(s.items.len(), 2);
+        assert_eq!(
+            s.items[0].on_conflict.as_ref().map(|c| &c.action),
+            Some(&ConflictAction::Nothing)
+        );
+        assert!(s.items[1].on_conflict.is_none());
+
+        // AND the action is required
+        assert!(parse_stmt(r#"SPAWN p: Person { email = "a" } ON CONFLICT (email)"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_unwind() {
+        // GIVEN an UNWIND over a parameter with two mutations
+        let stmt = parse_stmt(
+            r#"UNWIND $rows AS r
+               SPAWN t: Task { title = r.title }
+               LINK tagged(t, tag)"#,
+        )
+        .unwrap();
+
+        // THEN the list, variable and mutations are parsed in order
+        let Stmt::Unwind(u) = stmt else {
+            panic!("Expected UNWIND");
+        };
+        assert!(matches!(u.list, Expr::Param(ref name, _) if name == "rows"));
+        assert_eq!(u.var, "r");
+        assert!(matches!(
+            u.mutations.as_slice(),
+            [MutationAction::Spawn(_), MutationAction::Link(_)]
+        ));
+
+        // AND at least one mutation is required
+        assert!(parse_stmt("UNWIND [1, 2] AS x").is_err());
+    }
+
     #[test]
     fn test_parse_spawn_with_duration() {
         let stmt = parse_stmt(r#"SPAWN t: Timer { timeout = 30.seconds }"#).unwrap();
@@ -373,6 +864,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_savepoint_statements() {
+        assert_eq!(
+            parse_stmt("SAVEPOINT batch_1").unwrap(),
+            Stmt::Txn(TxnStmt::Savepoint("batch_1".to_string()))
+        );
+        assert_eq!(
+            parse_stmt("ROLLBACK TO batch_1").unwrap(),
+            Stmt::Txn(TxnStmt::RollbackTo("batch_1".to_string()))
+        );
+        assert_eq!(
+            parse_stmt("ROLLBACK TO SAVEPOINT batch_1").unwrap(),
+            Stmt::Txn(TxnStmt::RollbackTo("batch_1".to_string()))
+        );
+        assert_eq!(
+            parse_stmt("RELEASE SAVEPOINT batch_1").unwrap(),
+            Stmt::Txn(TxnStmt::Release("batch_1".to_string()))
+        );
+        assert_eq!(
+            parse_stmt("release batch_1").unwrap(),
+            Stmt::Txn(TxnStmt::Release("batch_1".to_string()))
+        );
+    }
+
     // ==================== EXPRESSION TESTS ====================
 
     #[test]
@@ -442,6 +957,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_subqueries() {
+        // GIVEN COUNT, COLLECT and scalar subqueries next to a map literal
+        let stmt = parse_match(
+            "MATCH p: Project WHERE COUNT { t: Task, belongs_to(t, p) WHERE t.open } > 5 \
+             RETURN COLLECT { MATCH t: Task, belongs_to(t, p) RETURN t.title }, \
+                    { MATCH o: Person, owns(o, p) RETURN o.name }, {name: p.name}",
+        )
+        .unwrap();
+
+        // THEN each subquery keeps its pattern, filter and projection
+        match stmt.where_clause.unwrap() {
+            Expr::BinaryOp(BinaryOp::Gt, left, _, _) => match *left {
+                Expr::Subquery(sq) => {
+                    assert_eq!(sq.kind, SubqueryKind::Count);
+                    assert_eq!(sq.pattern.len(), 2);
+                    assert!(sq.where_clause.is_some());
+                    assert!(sq.projection.is_none());
+                }
+                other => panic!("Expected subquery, got {:?}", other),
+            },
+            _ => panic!("Expected Gt"),
+        }
+        let projections = &stmt.return_clause.projections;
+        let kinds: Vec<_> = projections
+            .iter()
+            .map(|p| match &p.expr {
+                Expr::Subquery(sq) => Some((sq.kind, sq.projection.is_some())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Some((SubqueryKind::Collect, true)),
+                Some((SubqueryKind::Scalar, true)),
+                None
+            ]
+        );
+        assert!(matches!(projections[2].expr, Expr::Map(..)));
+
+        // AND COLLECT needs a RETURN
+        assert!(parse_match("MATCH p: Project RETURN COLLECT { t: Task }").is_err());
+    }
+
     // ==================== ERROR TESTS ====================
 
     #[test]
@@ -146,7 +146,36 @@ impl Parser {
         // Use expect_name to allow keywords like 'order' as attribute names
         let name = self.expect_name()?;
         self.expect(&TokenKind::Colon)?;
-        let type_name = self.expect_ident()?;
+
+        // [T]: a list whose elements have type T
+        let mut type_name = if self.check(&TokenKind::LBracket) {
+            self.advance();
+            let element_type = self.expect_ident()?;
+            self.expect(&TokenKind::RBracket)?;
+            format!("[{}]", element_type)
+        } else {
+            self.expect_ident()?
+        };
+
+        // Vector[N]: the dimension is part of the type name.
+        // Only an integer directly followed by ']' is a dimension; [N..M] stays a range modifier.
+        let next_pos = self.pos + 1;
+        if type_name == "Vector"
+            && self.check(&TokenKind::LBracket)
+            && matches!(
+                self.tokens.get(next_pos).map(|t| &t.kind),
+                Some(TokenKind::Int(_))
+            )
+            && matches!(
+                self.tokens.get(next_pos + 1).map(|t| &t.kind),
+                Some(TokenKind::RBracket)
+            )
+        {
+            self.advance();
+            let dim = self.expect_int()?;
+            self.expect(&TokenKind::RBracket)?;
+            type_name = format!("Vector[{}]", dim);
+        }
 
         // Parse optional nullable marker (?)
         let nullable = if self.check(&TokenKind::Question) {
@@ -209,6 +238,9 @@ impl Parser {
         } else if self.check_ident("readonly") {
             self.advance();
             Ok(AttrModifier::Readonly)
+        } else if self.check_ident("searchable") {
+            self.advance();
+            Ok(AttrModifier::Searchable)
         } else if self.check_ident("default") {
             self.advance();
             if self.check(&TokenKind::Eq) || self.check(&TokenKind::Colon) {
@@ -56,7 +56,8 @@ impl Parser {
     }
 
     /// Parse the body of an edge pattern after the edge type name.
-    /// Handles transitive modifiers (+, *), targets, and optional alias.
+    /// Handles transitive modifiers (+, *), targets, optional alias and,
+    /// for transitive patterns, an optional `[depth: max]` or `[depth: min..max]`.
     fn parse_edge_pattern_body(
         &mut self,
         edge_type: String,
@@ -93,12 +94,31 @@ impl Parser {
             None
         };
 
+        let (min_depth, max_depth) = if transitive.is_some() && self.check(&TokenKind::LBracket) {
+            self.advance();
+            self.expect_keyword("depth")?;
+            self.expect(&TokenKind::Colon)?;
+            let first = self.expect_int()?;
+            let bounds = if self.check(&TokenKind::Range) {
+                self.advance();
+                (Some(first), Some(self.expect_int()?))
+            } else {
+                (None, Some(first))
+            };
+            self.expect(&TokenKind::RBracket)?;
+            bounds
+        } else {
+            (None, None)
+        };
+
         let span = self.span_from(start);
         Ok(PatternElem::Edge(EdgePattern {
             edge_type,
             targets,
             alias,
             transitive,
+            min_depth,
+            max_depth,
             span,
         }))
     }
@@ -8,11 +8,15 @@
 //! - UNLINK: edge deletion
 //! - SET: attribute updates
 //! - WALK: graph traversal
-//! - Transactions: BEGIN, COMMIT, ROLLBACK
+//! - Transactions: BEGIN, COMMIT, ROLLBACK, SAVEPOINT, ROLLBACK TO, RELEASE
+//! - WATCH, ACK, NACK: consume-mode watches
+//! - IMPORT: bulk loading from files
+//! - DUMP: writing the database out as a script
+//! - CALL: running graph algorithms
 
 use super::Parser;
 use crate::ast::*;
-use crate::error::ParseResult;
+use crate::error::{ParseError, ParseResult};
 use crate::lexer::TokenKind;
 
 impl Parser {
@@ -48,10 +52,47 @@ impl Parser {
             }
             TokenKind::Rollback => {
                 self.advance();
-                Ok(Stmt::Txn(TxnStmt::Rollback))
+                if self.check_ident("to") {
+                    self.advance();
+                    let name = self.parse_savepoint_name()?;
+                    Ok(Stmt::Txn(TxnStmt::RollbackTo(name)))
+                } else {
+                    Ok(Stmt::Txn(TxnStmt::Rollback))
+                }
+            }
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("savepoint") => {
+                self.advance();
+                let name = self.expect_ident()?;
+                Ok(Stmt::Txn(TxnStmt::Savepoint(name)))
+            }
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("release") => {
+                self.advance();
+                let name = self.parse_savepoint_name()?;
+                Ok(Stmt::Txn(TxnStmt::Release(name)))
             }
             TokenKind::Explain => self.parse_explain().map(Stmt::Explain),
             TokenKind::Profile => self.parse_profile().map(Stmt::Profile),
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("watch") => {
+                self.parse_watch().map(Stmt::Watch)
+            }
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("ack") => {
+                self.parse_ack().map(Stmt::Ack)
+            }
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("nack") => {
+                self.parse_nack().map(Stmt::Nack)
+            }
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("import") => {
+                self.parse_import().map(Stmt::Import)
+            }
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("dump") => {
+                self.parse_dump().map(Stmt::Dump)
+            }
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("call") => {
+                self.parse_call().map(Stmt::Call)
+            }
+            TokenKind::Ident(name) if name.eq_ignore_ascii_case("unwind") => {
+                self.parse_unwind().map(Stmt::Unwind)
+            }
             _ => Err(crate::ParseError::unexpected_token(
                 token.span,
                 "statement",
@@ -60,6 +101,17 @@ impl Parser {
         }
     }
 
+    /// Parse the savepoint name of ROLLBACK TO / RELEASE, with an optional
+    /// SAVEPOINT keyword in front.
+    fn parse_savepoint_name(&mut self) -> ParseResult<String> {
+        let name = self.expect_ident()?;
+        if name.eq_ignore_ascii_case("savepoint") && matches!(self.peek().kind, TokenKind::Ident(_))
+        {
+            return self.expect_ident();
+        }
+        Ok(name)
+    }
+
     /// Parse EXPLAIN statement.
     fn parse_explain(&mut self) -> ParseResult<ExplainStmt> {
         let start = self.expect(&TokenKind::Explain)?.span;
@@ -111,48 +163,17 @@ impl Parser {
         // Parse OPTIONAL MATCH clauses
         let optional_matches = self.parse_optional_matches()?;
 
-        // Check what comes next: RETURN, mutation keyword, or WALK
-        if self.check(&TokenKind::Return) {
-            // Parse as normal MATCH query
-            let return_clause = self.parse_return_clause()?;
-
-            // Parse optional ORDER BY
-            let order_by = if self.check(&TokenKind::Order) {
-                self.advance();
-                self.expect(&TokenKind::By)?;
-                Some(self.parse_order_terms()?)
-            } else {
-                None
-            };
-
-            // Parse optional LIMIT
-            let limit = if self.check(&TokenKind::Limit) {
-                self.advance();
-                Some(self.expect_int()?)
-            } else {
-                None
-            };
+        // Parse optional NEAREST
+        let nearest = self.parse_optional_nearest()?;
 
-            // Parse optional OFFSET
-            let offset = if self.check(&TokenKind::Offset) {
-                self.advance();
-                Some(self.expect_int()?)
-            } else {
-                None
-            };
-
-            let span = self.span_from(start);
-
-            Ok(Stmt::Match(MatchStmt {
-                pattern,
-                where_clause,
-                optional_matches,
-                return_clause,
-                order_by,
-                limit,
-                offset,
-                span,
-            }))
+        // Check what comes next: WITH/RETURN, mutation keyword, or WALK
+        if nearest.is_some() || self.check(&TokenKind::With) || self.check(&TokenKind::Return) {
+            let stmt =
+                self.parse_match_tail(start, pattern, where_clause, optional_matches, nearest)?;
+            if self.check_ident("union") {
+                return self.parse_union(start, stmt).map(Stmt::Union);
+            }
+            Ok(Stmt::Match(stmt))
         } else if self.is_mutation_keyword() {
             // Parse as compound mutation
             // Per spec (4_QUERIES.md §2.11.3): "No RETURN clause is allowed
@@ -262,9 +283,170 @@ impl Parser {
         // Parse OPTIONAL MATCH clauses
         let optional_matches = self.parse_optional_matches()?;
 
+        // Parse optional NEAREST
+        let nearest = self.parse_optional_nearest()?;
+
+        self.parse_match_tail(start, pattern, where_clause, optional_matches, nearest)
+    }
+
+    /// Parse the rest of a MATCH query after its pattern, WHERE, OPTIONAL MATCH
+    /// and NEAREST: WITH stages, then RETURN [GROUP BY] [HAVING] [ORDER BY]
+    /// [LIMIT] [OFFSET].
+    fn parse_match_tail(
+        &mut self,
+        start: Span,
+        pattern: Vec<PatternElem>,
+        where_clause: Option<Expr>,
+        optional_matches: Vec<OptionalMatch>,
+        nearest: Option<NearestClause>,
+    ) -> ParseResult<MatchStmt> {
+        let mut stages = Vec::new();
+        while self.check(&TokenKind::With) {
+            stages.push(self.parse_with_stage()?);
+        }
+
         // Parse RETURN (required for query)
         let return_clause = self.parse_return_clause()?;
+        let (group_by, having) = self.parse_grouping()?;
+        let (order_by, limit, offset) = self.parse_order_limit_offset()?;
+
+        let span = self.span_from(start);
 
+        Ok(MatchStmt {
+            pattern,
+            where_clause,
+            optional_matches,
+            nearest,
+            stages,
+            return_clause,
+            group_by,
+            having,
+            order_by,
+            limit,
+            offset,
+            span,
+        })
+    }
+
+    /// Parse the UNION [ALL] branches that follow a first MATCH query.
+    ///
+    /// ORDER BY, LIMIT and OFFSET parsed with the last branch belong to the
+    /// whole union; earlier branches may not have them.
+    fn parse_union(&mut self, start: Span, first: MatchStmt) -> ParseResult<UnionStmt> {
+        let mut branches = vec![first];
+        let mut all = None;
+        while self.check_ident("union") {
+            let union_span = self.advance().span;
+            let branch_all = if self.check_ident("all") {
+                self.advance();
+                true
+            } else {
+                false
+            };
+            if *all.get_or_insert(branch_all) != branch_all {
+                return Err(ParseError::new(
+                    "Cannot mix UNION and UNION ALL in one query",
+                    union_span,
+                ));
+            }
+            let previous = branches.last().expect("union has a first branch");
+            if previous.order_by.is_some() || previous.limit.is_some() || previous.offset.is_some()
+            {
+                return Err(ParseError::new(
+                    "ORDER BY, LIMIT and OFFSET must follow the last UNION branch",
+                    previous.span,
+                ));
+            }
+            branches.push(self.parse_match()?);
+        }
+
+        let last = branches.last_mut().expect("union has a last branch");
+        let order_by = last.order_by.take();
+        let limit = last.limit.take();
+        let offset = last.offset.take();
+
+        let span = self.span_from(start);
+        Ok(UnionStmt {
+            branches,
+            all: all.unwrap_or(false),
+            order_by,
+            limit,
+            offset,
+            span,
+        })
+    }
+
+    /// Parse a WITH stage, including the MATCH and WHERE that follow it.
+    fn parse_with_stage(&mut self) -> ParseResult<WithStage> {
+        let start = self.expect(&TokenKind::With)?.span;
+
+        let distinct = if self.check(&TokenKind::Distinct) {
+            self.advance();
+            true
+        } else {
+            false
+        };
+        let projections = self.parse_projections()?;
+        let (group_by, having) = self.parse_grouping()?;
+        let (order_by, limit, offset) = self.parse_order_limit_offset()?;
+
+        let pattern = if self.check(&TokenKind::Match) {
+            self.advance();
+            self.parse_chained_patterns()?
+        } else {
+            Vec::new()
+        };
+        let where_clause = if self.check(&TokenKind::Where) {
+            self.advance();
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok(WithStage {
+            distinct,
+            projections,
+            group_by,
+            having,
+            order_by,
+            limit,
+            offset,
+            pattern,
+            where_clause,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse optional `GROUP BY expr, ...` and `HAVING expr`.
+    fn parse_grouping(&mut self) -> ParseResult<(Option<Vec<Expr>>, Option<Expr>)> {
+        let group_by = if self.check_ident("group") {
+            self.advance();
+            self.expect(&TokenKind::By)?;
+            let mut keys = vec![self.parse_expr()?];
+            while self.check(&TokenKind::Comma) {
+                self.advance();
+                keys.push(self.parse_expr()?);
+            }
+            Some(keys)
+        } else {
+            None
+        };
+
+        let having = if self.check_ident("having") {
+            self.advance();
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok((group_by, having))
+    }
+
+    /// Parse optional `ORDER BY`, `LIMIT` and `OFFSET`.
+    #[allow(clippy::type_complexity)]
+    fn parse_order_limit_offset(
+        &mut self,
+    ) -> ParseResult<(Option<Vec<OrderTerm>>, Option<i64>, Option<i64>)> {
         // Parse optional ORDER BY
         let order_by = if self.check(&TokenKind::Order) {
             self.advance();
@@ -290,20 +472,374 @@ impl Parser {
             None
         };
 
-        let span = self.span_from(start);
+        Ok((order_by, limit, offset))
+    }
 
-        Ok(MatchStmt {
+    /// Parse an optional NEAREST clause: NEAREST k TO expr ON var.attr [USING metric]
+    fn parse_optional_nearest(&mut self) -> ParseResult<Option<NearestClause>> {
+        if !self.check_ident("nearest") {
+            return Ok(None);
+        }
+        let start = self.advance().span;
+        let k = self.expect_int()?;
+        self.expect_keyword("to")?;
+        let query = self.parse_expr()?;
+        self.expect(&TokenKind::On)?;
+        let var = self.expect_ident()?;
+        self.expect(&TokenKind::Dot)?;
+        let attr = self.expect_name()?;
+
+        let metric = if self.check_ident("using") {
+            self.advance();
+            let token = self.peek().clone();
+            let name = self.expect_ident()?;
+            match name.to_lowercase().as_str() {
+                "cosine" | "cosine_similarity" => NearestMetric::Cosine,
+                "l2" | "l2_distance" => NearestMetric::L2,
+                _ => {
+                    return Err(ParseError::unexpected_token(
+                        token.span,
+                        "cosine_similarity or l2_distance",
+                        &name,
+                    ))
+                }
+            }
+        } else {
+            NearestMetric::default()
+        };
+
+        Ok(Some(NearestClause {
+            k,
+            query,
+            var,
+            attr,
+            metric,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parse WATCH statement: WATCH pattern [WHERE expr] [options]* RETURN ...
+    fn parse_watch(&mut self) -> ParseResult<WatchStmt> {
+        let start = self.expect_keyword("watch")?.span;
+        let pattern = self.parse_chained_patterns()?;
+
+        let where_clause = if self.check(&TokenKind::Where) {
+            self.advance();
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        // Options may be split over several bracket blocks
+        let mut options = WatchOptions::default();
+        while self.check(&TokenKind::LBracket) {
+            self.advance();
+            loop {
+                self.parse_watch_option(&mut options)?;
+                if self.check(&TokenKind::Comma) {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            self.expect(&TokenKind::RBracket)?;
+        }
+
+        let return_clause = self.parse_return_clause()?;
+
+        Ok(WatchStmt {
             pattern,
             where_clause,
-            optional_matches,
+            options,
             return_clause,
-            order_by,
-            limit,
-            offset,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse one `name: value` watch option into `options`.
+    fn parse_watch_option(&mut self, options: &mut WatchOptions) -> ParseResult<()> {
+        let token = self.peek().clone();
+        let name = self.expect_ident()?;
+        self.expect(&TokenKind::Colon)?;
+        match name.to_lowercase().as_str() {
+            "mode" => {
+                let token = self.peek().clone();
+                let mode = self.expect_ident()?;
+                options.mode = match mode.to_lowercase().as_str() {
+                    "watch" => WatchMode::Watch,
+                    "consume" => WatchMode::Consume,
+                    _ => {
+                        return Err(ParseError::unexpected_token(
+                            token.span,
+                            "watch or consume",
+                            &mode,
+                        ))
+                    }
+                };
+            }
+            "group" => options.group = Some(self.parse_expr()?),
+            "ack_timeout" => options.ack_timeout = Some(self.parse_expr()?),
+            "max_redeliveries" => options.max_redeliveries = Some(self.parse_expr()?),
+            "dead_letter" => options.dead_letter = Some(self.parse_expr()?),
+            _ => {
+                return Err(ParseError::unexpected_token(
+                    token.span,
+                    "watch option",
+                    &name,
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse ACK statement: ACK delivery_id
+    fn parse_ack(&mut self) -> ParseResult<AckStmt> {
+        let start = self.expect_keyword("ack")?.span;
+        let delivery_id = self.parse_expr()?;
+        Ok(AckStmt {
+            delivery_id,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse NACK statement: NACK delivery_id [no_retry]
+    fn parse_nack(&mut self) -> ParseResult<NackStmt> {
+        let start = self.expect_keyword("nack")?.span;
+        let delivery_id = self.parse_expr()?;
+        let no_retry = if self.check(&TokenKind::LBracket) {
+            self.advance();
+            self.expect_keyword("no_retry")?;
+            self.expect(&TokenKind::RBracket)?;
+            true
+        } else {
+            false
+        };
+        Ok(NackStmt {
+            delivery_id,
+            no_retry,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse DUMP statement: DUMP TO path
+    fn parse_dump(&mut self) -> ParseResult<DumpStmt> {
+        let start = self.expect_keyword("dump")?.span;
+        self.expect_keyword("to")?;
+        let path = self.parse_expr()?;
+        let span = self.span_from(start);
+        Ok(DumpStmt { path, span })
+    }
+
+    /// Parse UNWIND statement:
+    /// UNWIND expr AS var mutation+
+    fn parse_unwind(&mut self) -> ParseResult<UnwindStmt> {
+        let start = self.expect_keyword("unwind")?.span;
+        let list = self.parse_expr()?;
+        self.expect(&TokenKind::As)?;
+        let var = self.expect_ident()?;
+
+        if !self.is_mutation_keyword() {
+            return Err(ParseError::unexpected_token(
+                self.peek().span,
+                "mutation (SPAWN, LINK, SET, KILL, UNLINK)",
+                self.peek().kind.name(),
+            ));
+        }
+        let mut mutations = Vec::new();
+        while self.is_mutation_keyword() {
+            mutations.push(self.parse_mutation_action()?);
+        }
+
+        let span = self.span_from(start);
+        Ok(UnwindStmt {
+            list,
+            var,
+            mutations,
             span,
         })
     }
 
+    /// Parse CALL statement:
+    /// CALL name.name([edge_type | option = expr], ...) [YIELD column [AS alias], ...] [WRITE attr]
+    fn parse_call(&mut self) -> ParseResult<CallStmt> {
+        let start = self.expect_keyword("call")?.span;
+        let mut procedure = self.expect_ident()?;
+        while self.check(&TokenKind::Dot) {
+            self.advance();
+            procedure.push('.');
+            procedure.push_str(&self.expect_name()?);
+        }
+
+        let mut edge_types = Vec::new();
+        let mut options = Vec::new();
+        self.expect(&TokenKind::LParen)?;
+        while !self.check(&TokenKind::RParen) {
+            let arg_start = self.peek().span;
+            let name = self.expect_ident()?;
+            if self.check(&TokenKind::Eq) {
+                self.advance();
+                let value = self.parse_expr()?;
+                options.push(CallOption {
+                    name,
+                    value,
+                    span: self.span_from(arg_start),
+                });
+            } else if options.is_empty() {
+                edge_types.push(name);
+            } else {
+                return Err(ParseError::new(
+                    "edge types must come before options",
+                    arg_start,
+                ));
+            }
+            if !self.check(&TokenKind::RParen) {
+                self.expect(&TokenKind::Comma)?;
+            }
+        }
+        self.expect(&TokenKind::RParen)?;
+
+        let mut yields = Vec::new();
+        if self.check_ident("yield") {
+            self.advance();
+            loop {
+                let item_start = self.peek().span;
+                let column = self.expect_name()?;
+                let alias = if self.check(&TokenKind::As) {
+                    self.advance();
+                    Some(self.expect_name()?)
+                } else {
+                    None
+                };
+                yields.push(YieldItem {
+                    column,
+                    alias,
+                    span: self.span_from(item_start),
+                });
+                if !self.check(&TokenKind::Comma) {
+                    break;
+                }
+                self.advance();
+            }
+        }
+
+        let write = if self.check_ident("write") {
+            self.advance();
+            Some(self.expect_name()?)
+        } else {
+            None
+        };
+
+        Ok(CallStmt {
+            procedure,
+            edge_types,
+            options,
+            yields,
+            write,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse IMPORT statement: IMPORT Target [(columns)] FROM source [options]*
+    fn parse_import(&mut self) -> ParseResult<ImportStmt> {
+        let start = self.expect_keyword("import")?.span;
+        let target = self.expect_ident()?;
+
+        let mut columns = Vec::new();
+        if self.check(&TokenKind::LParen) {
+            self.advance();
+            loop {
+                columns.push(self.parse_import_column()?);
+                if self.check(&TokenKind::Comma) {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            self.expect(&TokenKind::RParen)?;
+        }
+
+        self.expect(&TokenKind::From)?;
+        let source = self.parse_expr()?;
+
+        let mut options = ImportOptions::default();
+        while self.check(&TokenKind::LBracket) {
+            self.advance();
+            loop {
+                self.parse_import_option(&mut options)?;
+                if self.check(&TokenKind::Comma) {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            self.expect(&TokenKind::RBracket)?;
+        }
+
+        Ok(ImportStmt {
+            target,
+            columns,
+            source,
+            options,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse one IMPORT column: `column [AS attr]` or `column: Type.key`.
+    fn parse_import_column(&mut self) -> ParseResult<ImportColumn> {
+        let column = self.expect_name()?;
+        if self.check(&TokenKind::Colon) {
+            self.advance();
+            let type_name = self.expect_ident()?;
+            self.expect(&TokenKind::Dot)?;
+            let key = self.expect_name()?;
+            return Ok(ImportColumn::Endpoint {
+                column,
+                type_name,
+                key,
+            });
+        }
+        let attr = if self.check(&TokenKind::As) {
+            self.advance();
+            self.expect_name()?
+        } else {
+            column.clone()
+        };
+        Ok(ImportColumn::Attr { column, attr })
+    }
+
+    /// Parse one `name: value` import option into `options`.
+    fn parse_import_option(&mut self, options: &mut ImportOptions) -> ParseResult<()> {
+        let token = self.peek().clone();
+        let name = self.expect_ident()?;
+        self.expect(&TokenKind::Colon)?;
+        match name.to_lowercase().as_str() {
+            "format" => {
+                let token = self.peek().clone();
+                let format = self.expect_ident()?;
+                options.format = Some(match format.to_lowercase().as_str() {
+                    "csv" => ImportFormat::Csv,
+                    "jsonl" | "ndjson" => ImportFormat::JsonLines,
+                    _ => {
+                        return Err(ParseError::unexpected_token(
+                            token.span,
+                            "csv or jsonl",
+                            &format,
+                        ))
+                    }
+                });
+            }
+            "batch" => options.batch = Some(self.parse_expr()?),
+            _ => {
+                return Err(ParseError::unexpected_token(
+                    token.span,
+                    "import option",
+                    &name,
+                ))
+            }
+        }
+        Ok(())
+    }
+
     /// Check if current token is a mutation keyword.
     fn is_mutation_keyword(&self) -> bool {
         matches!(
@@ -513,16 +1049,85 @@ impl Parser {
             var
         };
 
+        let on_conflict = self.parse_optional_on_conflict()?;
+
         let span = self.span_from(start);
 
         Ok(SpawnItem {
             var,
             type_name,
             attrs,
+            on_conflict,
             span,
         })
     }
 
+    /// Parse an optional `ON CONFLICT (attr, ...) DO NOTHING | DO UPDATE SET ...`.
+    fn parse_optional_on_conflict(&mut self) -> ParseResult<Option<OnConflict>> {
+        if !self.check(&TokenKind::On) {
+            return Ok(None);
+        }
+        let start = self.advance().span;
+        if !self.check_ident("conflict") {
+            return Err(ParseError::unexpected_token(
+                self.peek().span,
+                "CONFLICT",
+                self.peek().kind.name(),
+            ));
+        }
+        self.advance();
+
+        self.expect(&TokenKind::LParen)?;
+        let mut attrs = vec![self.expect_name()?];
+        while self.check(&TokenKind::Comma) {
+            self.advance();
+            attrs.push(self.expect_name()?);
+        }
+        self.expect(&TokenKind::RParen)?;
+
+        if !self.check_ident("do") {
+            return Err(ParseError::unexpected_token(
+                self.peek().span,
+                "DO",
+                self.peek().kind.name(),
+            ));
+        }
+        self.advance();
+
+        let action = if self.check_ident("nothing") {
+            self.advance();
+            ConflictAction::Nothing
+        } else if self.check_ident("update") {
+            self.advance();
+            self.expect(&TokenKind::Set)?;
+            let mut assignments = vec![self.parse_attr_assignment()?];
+            // A comma not followed by `name =` separates LINK targets instead
+            while self.check(&TokenKind::Comma)
+                && matches!(
+                    self.tokens.get(self.pos + 2).map(|t| &t.kind),
+                    Some(TokenKind::Eq)
+                )
+            {
+                self.advance();
+                assignments.push(self.parse_attr_assignment()?);
+            }
+            ConflictAction::Update(assignments)
+        } else {
+            return Err(ParseError::unexpected_token(
+                self.peek().span,
+                "NOTHING or UPDATE",
+                self.peek().kind.name(),
+            ));
+        };
+
+        let span = self.span_from(start);
+        Ok(Some(OnConflict {
+            attrs,
+            action,
+            span,
+        }))
+    }
+
     pub(crate) fn parse_attr_block(&mut self) -> ParseResult<Vec<AttrAssignment>> {
         self.expect(&TokenKind::LBrace)?;
 
@@ -817,6 +1422,12 @@ impl Parser {
             follow.push(self.parse_follow_clause()?);
         }
 
+        let shortest = if self.check_ident("shortest") || self.check_ident("all") {
+            Some(self.parse_shortest_clause()?)
+        } else {
+            None
+        };
+
         let until = if self.check(&TokenKind::Until) {
             self.advance();
             Some(self.parse_expr()?)
@@ -830,6 +1441,7 @@ impl Parser {
         Ok(WalkStmt {
             from,
             follow,
+            shortest,
             until,
             return_type,
             span,
@@ -892,6 +1504,52 @@ impl Parser {
         })
     }
 
+    /// Parse `SHORTEST PATH`, `SHORTEST k PATHS` or `ALL SHORTEST PATHS`,
+    /// then `TO expr [WEIGHT attr]`.
+    fn parse_shortest_clause(&mut self) -> ParseResult<ShortestClause> {
+        let start = self.peek().span;
+        let paths = if self.check_ident("all") {
+            self.advance();
+            self.expect_keyword("shortest")?;
+            ShortestPaths::All
+        } else {
+            self.expect_keyword("shortest")?;
+            if matches!(self.peek().kind, TokenKind::Int(_)) {
+                let k = self.expect_int()?;
+                if k < 1 {
+                    return Err(ParseError::new(
+                        "SHORTEST needs at least 1 path",
+                        self.span_from(start),
+                    ));
+                }
+                ShortestPaths::First(k)
+            } else {
+                ShortestPaths::First(1)
+            }
+        };
+        if self.check(&TokenKind::Path) {
+            self.advance();
+        } else {
+            self.expect_keyword("paths")?;
+        }
+
+        self.expect_keyword("to")?;
+        let to = self.parse_expr()?;
+        let weight = if self.check_ident("weight") {
+            self.advance();
+            Some(self.expect_ident()?)
+        } else {
+            None
+        };
+
+        Ok(ShortestClause {
+            paths,
+            to,
+            weight,
+            span: self.span_from(start),
+        })
+    }
+
     fn parse_walk_return(&mut self) -> ParseResult<WalkReturnType> {
         self.expect(&TokenKind::Return)?;
 
@@ -99,6 +99,32 @@ impl Bindings {
         self.map.get(name)
     }
 
+    /// Bind a statement parameter (`$name`).
+    ///
+    /// Parameters share the binding map under a `$`-prefixed key, which can
+    /// never collide with a pattern variable.
+    pub fn insert_param(&mut self, name: &str, value: Value) {
+        self.map.insert(format!("${}", name), Binding::Value(value));
+    }
+
+    /// Get a statement parameter by name (without the `$`).
+    pub fn param(&self, name: &str) -> Option<&Binding> {
+        self.map.get(&format!("${}", name))
+    }
+
+    /// Bind an ID reference (`#name`) to an entity of the session, such as
+    /// the node of an earlier `SPAWN name: ...`.
+    ///
+    /// Like parameters, ID references use a prefixed key of their own.
+    pub fn insert_id_ref(&mut self, name: &str, binding: impl Into<Binding>) {
+        self.map.insert(format!("#{}", name), binding.into());
+    }
+
+    /// Get the entity an ID reference (`#name`) was bound to.
+    pub fn id_ref(&self, name: &str) -> Option<&Binding> {
+        self.map.get(&format!("#{}", name))
+    }
+
     /// Check if a variable is bound.
     pub fn contains(&self, name: &str) -> bool {
         self.map.contains_key(name)
@@ -165,6 +191,19 @@ mod tests {
         assert_eq!(bindings.get("y").unwrap().as_value(), Some(&Value::Int(42)));
     }
 
+    #[test]
+    fn test_bindings_params() {
+        let mut bindings = Bindings::with("x", NodeId::new(1));
+        bindings.insert_param("x", Value::Int(7));
+
+        assert_eq!(bindings.get("x").unwrap().as_node(), Some(NodeId::new(1)));
+        assert_eq!(
+            bindings.param("x").unwrap().as_value(),
+            Some(&Value::Int(7))
+        );
+        assert!(bindings.param("y").is_none());
+    }
+
     #[test]
     fn test_bindings_extend_with() {
         let bindings = Bindings::with("x", NodeId::new(1));
@@ -1,10 +1,31 @@
 //! Expression evaluation.
 
 use crate::{Bindings, CompiledPattern, Matcher, PatternError, PatternResult};
-use mew_core::Value;
+use mew_core::{Point, Value, VectorMetric};
 use mew_graph::Graph;
-use mew_parser::{BinaryOp, Expr, LiteralKind, PatternElem, UnaryOp};
+use mew_parser::{
+    BinaryOp, EdgePattern, Expr, FnCall, LiteralKind, NodePattern, PatternElem, Quantifier,
+    QuantifierKind, Span, SubqueryKind, UnaryOp,
+};
 use mew_registry::Registry;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Current wall-clock time in milliseconds since the Unix epoch.
+pub fn current_time_millis() -> i64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as i64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let duration = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        duration.as_millis() as i64
+    }
+}
 
 /// Expression evaluator.
 ///
@@ -31,16 +52,26 @@ impl<'r> Evaluator<'r> {
                 self.eval_binary_op(*op, left, right, bindings, graph)
             }
             Expr::UnaryOp(op, operand, _) => self.eval_unary_op(*op, operand, bindings, graph),
-            Expr::FnCall(fc) => self.eval_fn_call(&fc.name, &fc.args, bindings, graph),
+            Expr::FnCall(fc) => match fc.pattern_var() {
+                Some((var, type_name)) => {
+                    self.eval_pattern_aggregate(fc, var, type_name, bindings, graph)
+                }
+                None => self.eval_fn_call(&fc.name, &fc.args, bindings, graph),
+            },
             Expr::IdRef(id, _) => {
-                // Look up the ID in bindings - ID refs use session-stored names from SPAWN
+                // Look up the ID in bindings - ID refs use session-stored names from SPAWN,
+                // then pattern variables of the same name
                 // For ID refs, we return NodeNotFound instead of UnboundVariable when not found
                 bindings
-                    .get(id)
+                    .id_ref(id)
+                    .or_else(|| bindings.get(id))
                     .map(|b| b.to_value())
                     .ok_or_else(|| PatternError::node_not_found(id))
             }
-            Expr::Param(name, _) => Err(PatternError::missing_parameter(name)),
+            Expr::Param(name, _) => bindings
+                .param(name)
+                .map(|b| b.to_value())
+                .ok_or_else(|| PatternError::missing_parameter(name)),
             Expr::Exists(pattern_elems, where_clause, _) => {
                 // Compile the subpattern and check if any matches exist
                 let exists =
@@ -53,6 +84,13 @@ impl<'r> Evaluator<'r> {
                     self.eval_exists(pattern_elems, where_clause.as_deref(), bindings, graph)?;
                 Ok(Value::Bool(!exists))
             }
+            Expr::Subquery(sq) => self.eval_subquery(sq, bindings, graph),
+            Expr::Quantifier(q) => self.eval_quantifier(q, bindings, graph),
+            Expr::Index(base, index, _) => {
+                let base = self.eval(base, bindings, graph)?;
+                let index = self.eval(index, bindings, graph)?;
+                Self::eval_index(base, index)
+            }
             Expr::List(elements, _) => {
                 // Evaluate each element and collect into a list
                 let values: PatternResult<Vec<Value>> = elements
@@ -61,6 +99,14 @@ impl<'r> Evaluator<'r> {
                     .collect();
                 Ok(Value::List(values?))
             }
+            Expr::Map(entries, _) => {
+                // Evaluate each entry value; later duplicate keys win
+                let mut map = BTreeMap::new();
+                for (key, value_expr) in entries {
+                    map.insert(key.clone(), self.eval(value_expr, bindings, graph)?);
+                }
+                Ok(Value::Map(map))
+            }
             Expr::TypeCheck(base, type_name, _) => {
                 // Type check: expr:Type - checks if the value is of the given type
                 let base_val = self.eval(base, bindings, graph)?;
@@ -98,6 +144,186 @@ impl<'r> Evaluator<'r> {
         bindings: &Bindings,
         graph: &Graph,
     ) -> PatternResult<bool> {
+        let pattern = self.compile_subpattern(pattern_elems, where_clause, bindings)?;
+
+        // Use short-circuit exists check instead of finding all matches
+        let matcher = Matcher::new(self.registry, graph);
+        matcher.exists(&pattern, bindings.clone())
+    }
+
+    /// Evaluate a COUNT, COLLECT or scalar subquery.
+    fn eval_subquery(
+        &self,
+        sq: &mew_parser::Subquery,
+        bindings: &Bindings,
+        graph: &Graph,
+    ) -> PatternResult<Value> {
+        let pattern = self.compile_subpattern(&sq.pattern, sq.where_clause.as_ref(), bindings)?;
+        let matches =
+            Matcher::new(self.registry, graph).find_all_with_initial(&pattern, bindings.clone())?;
+
+        let project = |row: &Bindings| match &sq.projection {
+            Some(expr) => self.eval(expr, row, graph),
+            None => Ok(Value::Null),
+        };
+        match sq.kind {
+            SubqueryKind::Count => Ok(Value::Int(matches.len() as i64)),
+            SubqueryKind::Collect => Ok(Value::List(
+                matches.iter().map(project).collect::<PatternResult<_>>()?,
+            )),
+            SubqueryKind::Scalar => match matches.as_slice() {
+                [] => Ok(Value::Null),
+                [row] => project(row),
+                _ => Err(PatternError::invalid_operation(format!(
+                    "Scalar subquery returned {} rows; expected at most one",
+                    matches.len()
+                ))),
+            },
+        }
+    }
+
+    /// Evaluate an aggregate over a pattern, such as
+    /// `count(c: Copy, copy_of(c, b))`, for the current row. The declared
+    /// node and the edge predicates among the other arguments form the
+    /// pattern; the remaining arguments and the WHERE clause filter it.
+    fn eval_pattern_aggregate(
+        &self,
+        fc: &FnCall,
+        var: &str,
+        type_name: &str,
+        bindings: &Bindings,
+        graph: &Graph,
+    ) -> PatternResult<Value> {
+        let mut elems = vec![PatternElem::Node(NodePattern {
+            var: var.to_string(),
+            type_name: type_name.to_string(),
+            span: fc.span,
+        })];
+        let mut conditions = Vec::new();
+        for arg in &fc.args[1..] {
+            let targets: Option<Vec<String>> = match arg {
+                Expr::FnCall(edge) if self.registry.get_edge_type_id(&edge.name).is_some() => edge
+                    .args
+                    .iter()
+                    .map(|target| match target {
+                        Expr::Var(name, _) => Some(name.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => None,
+            };
+            match (arg, targets) {
+                (Expr::FnCall(edge), Some(targets)) => elems.push(PatternElem::Edge(EdgePattern {
+                    edge_type: edge.name.clone(),
+                    targets,
+                    alias: None,
+                    transitive: None,
+                    min_depth: None,
+                    max_depth: None,
+                    span: edge.span,
+                })),
+                _ => conditions.push(arg.clone()),
+            }
+        }
+        conditions.extend(fc.filter.as_deref().cloned());
+        let filter = conditions.into_iter().reduce(|left, right| {
+            let span = left.span();
+            Expr::BinaryOp(BinaryOp::And, Box::new(left), Box::new(right), span)
+        });
+
+        let pattern = self.compile_subpattern(&elems, filter.as_ref(), bindings)?;
+        let matches =
+            Matcher::new(self.registry, graph).find_all_with_initial(&pattern, bindings.clone())?;
+        let mut nodes = Vec::with_capacity(matches.len());
+        for row in &matches {
+            let node = self.eval_var(var, row)?;
+            if !fc.distinct || !nodes.contains(&node) {
+                nodes.push(node);
+            }
+        }
+        match fc.name.to_lowercase().as_str() {
+            "count" => Ok(Value::Int(nodes.len() as i64)),
+            "collect" => Ok(Value::List(nodes)),
+            other => Err(PatternError::invalid_operation(format!(
+                "{} over a pattern is not supported; use COUNT or COLLECT",
+                other.to_uppercase()
+            ))),
+        }
+    }
+
+    /// Evaluate ANY/ALL over a list. As with AND and OR, a null predicate
+    /// result makes the answer null unless another element decides it.
+    fn eval_quantifier(
+        &self,
+        q: &Quantifier,
+        bindings: &Bindings,
+        graph: &Graph,
+    ) -> PatternResult<Value> {
+        let items = match self.eval(&q.list, bindings, graph)? {
+            Value::List(items) => items,
+            Value::Null => return Ok(Value::Null),
+            other => {
+                return Err(PatternError::type_error(format!(
+                    "quantifier expects a list, got {}",
+                    other.type_name()
+                )))
+            }
+        };
+        // ANY stops at the first true element, ALL at the first false one
+        let decisive = q.kind == QuantifierKind::Any;
+        let mut saw_null = false;
+        let mut row = bindings.clone();
+        for item in items {
+            row.insert(&q.var, item);
+            match self.eval(&q.predicate, &row, graph)? {
+                Value::Bool(b) if b == decisive => return Ok(Value::Bool(decisive)),
+                Value::Bool(_) => {}
+                Value::Null => saw_null = true,
+                other => {
+                    return Err(PatternError::type_error(format!(
+                        "quantifier predicate must be Bool, got {}",
+                        other.type_name()
+                    )))
+                }
+            }
+        }
+        Ok(if saw_null {
+            Value::Null
+        } else {
+            Value::Bool(!decisive)
+        })
+    }
+
+    /// Subscript a list by position (negative counts from the end) or a map by
+    /// key. Missing elements are null.
+    fn eval_index(base: Value, index: Value) -> PatternResult<Value> {
+        match (base, index) {
+            (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+            (Value::List(items), Value::Int(i)) => {
+                let pos = if i < 0 { items.len() as i64 + i } else { i };
+                Ok(usize::try_from(pos)
+                    .ok()
+                    .and_then(|pos| items.get(pos).cloned())
+                    .unwrap_or(Value::Null))
+            }
+            (Value::Map(entries), Value::String(key)) => {
+                Ok(entries.get(&key).cloned().unwrap_or(Value::Null))
+            }
+            (base, index) => Err(PatternError::type_error(format!(
+                "cannot index {} by {}",
+                base.type_name(),
+                index.type_name()
+            ))),
+        }
+    }
+
+    /// Compile a nested pattern that shares the variables already bound.
+    fn compile_subpattern(
+        &self,
+        pattern_elems: &[PatternElem],
+        where_clause: Option<&Expr>,
+        bindings: &Bindings,
+    ) -> PatternResult<CompiledPattern> {
         // Get the names of already-bound variables
         let prebound: Vec<String> = bindings.names().map(|s| s.to_string()).collect();
 
@@ -109,10 +335,7 @@ impl<'r> Evaluator<'r> {
         if let Some(where_expr) = where_clause {
             pattern = pattern.with_filter(where_expr.clone());
         }
-
-        // Use short-circuit exists check instead of finding all matches
-        let matcher = Matcher::new(self.registry, graph);
-        matcher.exists(&pattern, bindings.clone())
+        Ok(pattern)
     }
 
     /// Evaluate a literal.
@@ -125,6 +348,8 @@ impl<'r> Evaluator<'r> {
             LiteralKind::String(s) => Value::String(s.clone()),
             LiteralKind::Duration(ms) => Value::Duration(*ms),
             LiteralKind::Timestamp(ms) => Value::Timestamp(*ms),
+            LiteralKind::Bytes(bytes) => Value::Bytes(bytes.clone()),
+            LiteralKind::Hash(digest) => Value::Hash(*digest),
         })
     }
 
@@ -176,6 +401,8 @@ impl<'r> Evaluator<'r> {
                     Ok(Value::Null)
                 }
             }
+            // Path access into structured values: n.meta.region
+            Value::Map(entries) => Ok(entries.get(attr).cloned().unwrap_or(Value::Null)),
             // Null propagation for OPTIONAL MATCH: accessing attr on null returns null
             Value::Null => Ok(Value::Null),
             _ => Err(PatternError::type_error(format!(
@@ -245,6 +472,7 @@ impl<'r> Evaluator<'r> {
             UnaryOp::Neg => match val {
                 Value::Int(i) => Ok(Value::Int(-i)),
                 Value::Float(f) => Ok(Value::Float(-f)),
+                Value::Duration(d) => Ok(Value::Duration(Self::checked_time(d.checked_neg())?)),
                 _ => Err(PatternError::type_error(format!("cannot negate {:?}", val))),
             },
             UnaryOp::Not => match val {
@@ -269,97 +497,126 @@ impl<'r> Evaluator<'r> {
         let name_lower = name.to_lowercase();
 
         match name_lower.as_str() {
-            "now" => {
-                // Return current timestamp in milliseconds since epoch
-                #[cfg(target_arch = "wasm32")]
-                {
-                    let ms = js_sys::Date::now() as i64;
-                    Ok(Value::Timestamp(ms))
-                }
-                #[cfg(not(target_arch = "wasm32"))]
-                {
-                    use std::time::{SystemTime, UNIX_EPOCH};
-                    let duration = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or_default();
-                    Ok(Value::Timestamp(duration.as_millis() as i64))
-                }
-            }
-            "year" => {
-                if let Some(arg) = args.first() {
-                    let val = self.eval(arg, bindings, graph)?;
-                    if let Value::Timestamp(ms) = val {
-                        let (year, _, _) = Self::timestamp_to_date(ms);
-                        return Ok(Value::Int(year as i64));
-                    }
-                    return Err(PatternError::type_error(
-                        "YEAR expects a timestamp argument",
-                    ));
-                }
-                Err(PatternError::type_error("YEAR expects one argument"))
-            }
-            "month" => {
-                if let Some(arg) = args.first() {
-                    let val = self.eval(arg, bindings, graph)?;
-                    if let Value::Timestamp(ms) = val {
-                        let (_, month, _) = Self::timestamp_to_date(ms);
-                        return Ok(Value::Int(month as i64));
-                    }
-                    return Err(PatternError::type_error(
-                        "MONTH expects a timestamp argument",
-                    ));
+            // Return current timestamp in milliseconds since epoch
+            "now" | "wall_time" => Ok(Value::Timestamp(current_time_millis())),
+            "year" | "month" | "day" | "hour" | "minute" | "second" | "millisecond"
+            | "day_of_week" => {
+                if args.len() == 1 {
+                    let val = self.eval(&args[0], bindings, graph)?;
+                    let ms = match val {
+                        Value::Timestamp(ms) => ms,
+                        Value::Null => return Ok(Value::Null),
+                        _ => {
+                            return Err(PatternError::type_error(format!(
+                                "{} expects a timestamp argument",
+                                name_lower.to_uppercase()
+                            )))
+                        }
+                    };
+                    let (year, month, day, hour, minute, second, millis) =
+                        mew_core::time::components(ms);
+                    return Ok(Value::Int(match name_lower.as_str() {
+                        "year" => year as i64,
+                        "month" => month as i64,
+                        "day" => day as i64,
+                        "hour" => hour as i64,
+                        "minute" => minute as i64,
+                        "second" => second as i64,
+                        "millisecond" => millis as i64,
+                        _ => mew_core::time::day_of_week(ms) as i64,
+                    }));
                 }
-                Err(PatternError::type_error("MONTH expects one argument"))
+                Err(PatternError::type_error(format!(
+                    "{} expects one argument",
+                    name_lower.to_uppercase()
+                )))
             }
-            "day" => {
-                if let Some(arg) = args.first() {
-                    let val = self.eval(arg, bindings, graph)?;
-                    if let Value::Timestamp(ms) = val {
-                        let (_, _, day) = Self::timestamp_to_date(ms);
-                        return Ok(Value::Int(day as i64));
-                    }
-                    return Err(PatternError::type_error("DAY expects a timestamp argument"));
+            "timestamp" => {
+                // Parse an ISO 8601 string
+                if args.len() == 1 {
+                    return match self.eval(&args[0], bindings, graph)? {
+                        Value::String(s) => mew_core::time::parse_timestamp(&s)
+                            .map(Value::Timestamp)
+                            .ok_or_else(|| {
+                                PatternError::invalid_operation(format!(
+                                    "cannot parse \"{}\" as timestamp. Expected ISO 8601 format",
+                                    s
+                                ))
+                            }),
+                        Value::Null => Ok(Value::Null),
+                        _ => Err(PatternError::type_error(
+                            "TIMESTAMP expects a string argument",
+                        )),
+                    };
                 }
-                Err(PatternError::type_error("DAY expects one argument"))
+                Err(PatternError::type_error("TIMESTAMP expects one argument"))
             }
-            "hour" => {
-                if let Some(arg) = args.first() {
-                    let val = self.eval(arg, bindings, graph)?;
-                    if let Value::Timestamp(ms) = val {
-                        let (hour, _, _) = Self::timestamp_to_time(ms);
-                        return Ok(Value::Int(hour as i64));
-                    }
-                    return Err(PatternError::type_error(
-                        "HOUR expects a timestamp argument",
-                    ));
+            "format_timestamp" => {
+                if args.len() == 1 {
+                    return match self.eval(&args[0], bindings, graph)? {
+                        Value::Timestamp(ms) => {
+                            Ok(Value::String(mew_core::time::format_timestamp(ms)))
+                        }
+                        Value::Null => Ok(Value::Null),
+                        _ => Err(PatternError::type_error(
+                            "FORMAT_TIMESTAMP expects a timestamp argument",
+                        )),
+                    };
                 }
-                Err(PatternError::type_error("HOUR expects one argument"))
+                Err(PatternError::type_error(
+                    "FORMAT_TIMESTAMP expects one argument",
+                ))
             }
-            "minute" => {
-                if let Some(arg) = args.first() {
-                    let val = self.eval(arg, bindings, graph)?;
-                    if let Value::Timestamp(ms) = val {
-                        let (_, minute, _) = Self::timestamp_to_time(ms);
-                        return Ok(Value::Int(minute as i64));
-                    }
-                    return Err(PatternError::type_error(
-                        "MINUTE expects a timestamp argument",
-                    ));
+            "date_trunc" => {
+                // date_trunc(unit, t): start of the unit containing t
+                if args.len() == 2 {
+                    let unit = self.eval(&args[0], bindings, graph)?;
+                    let val = self.eval(&args[1], bindings, graph)?;
+                    return match (unit, val) {
+                        (_, Value::Null) => Ok(Value::Null),
+                        (Value::String(unit), Value::Timestamp(ms)) => {
+                            mew_core::time::truncate(ms, &unit)
+                                .map(Value::Timestamp)
+                                .ok_or_else(|| {
+                                    PatternError::invalid_operation(format!(
+                                        "unknown DATE_TRUNC unit '{}'",
+                                        unit
+                                    ))
+                                })
+                        }
+                        _ => Err(PatternError::type_error(
+                            "DATE_TRUNC expects (unit string, timestamp)",
+                        )),
+                    };
                 }
-                Err(PatternError::type_error("MINUTE expects one argument"))
+                Err(PatternError::type_error("DATE_TRUNC expects two arguments"))
             }
-            "second" => {
-                if let Some(arg) = args.first() {
-                    let val = self.eval(arg, bindings, graph)?;
-                    if let Value::Timestamp(ms) = val {
-                        let (_, _, second) = Self::timestamp_to_time(ms);
-                        return Ok(Value::Int(second as i64));
-                    }
-                    return Err(PatternError::type_error(
-                        "SECOND expects a timestamp argument",
-                    ));
+            "to_milliseconds" | "to_seconds" | "to_minutes" | "to_hours" | "to_days" => {
+                if args.len() == 1 {
+                    let val = self.eval(&args[0], bindings, graph)?;
+                    let ms = match val {
+                        Value::Duration(ms) => ms,
+                        Value::Null => return Ok(Value::Null),
+                        _ => {
+                            return Err(PatternError::type_error(format!(
+                                "{} expects a duration argument",
+                                name_lower.to_uppercase()
+                            )))
+                        }
+                    };
+                    // Truncated toward zero, like integer division
+                    return Ok(Value::Int(match name_lower.as_str() {
+                        "to_seconds" => ms / 1000,
+                        "to_minutes" => ms / 60_000,
+                        "to_hours" => ms / 3_600_000,
+                        "to_days" => ms / 86_400_000,
+                        _ => ms,
+                    }));
                 }
-                Err(PatternError::type_error("SECOND expects one argument"))
+                Err(PatternError::type_error(format!(
+                    "{} expects one argument",
+                    name_lower.to_uppercase()
+                )))
             }
             "count" => {
                 // COUNT expects 0 or 1 arguments
@@ -430,6 +687,7 @@ impl<'r> Evaluator<'r> {
                     return match val {
                         Value::Int(i) => Ok(Value::Int(i.abs())),
                         Value::Float(f) => Ok(Value::Float(f.abs())),
+                        Value::Duration(d) => Ok(Value::Duration(d.abs())),
                         _ => Err(PatternError::type_error("ABS expects a numeric argument")),
                     };
                 }
@@ -545,24 +803,219 @@ impl<'r> Evaluator<'r> {
                 if args.len() >= 2 {
                     let s = self.eval(&args[0], bindings, graph)?;
                     let pattern = self.eval(&args[1], bindings, graph)?;
-                    if let (Value::String(s), Value::String(pattern)) = (s, pattern) {
-                        return Ok(Value::Bool(s.contains(&pattern)));
+                    match (&s, &pattern) {
+                        (Value::String(s), Value::String(pattern)) => {
+                            return Ok(Value::Bool(s.contains(pattern.as_str())));
+                        }
+                        // Map CONTAINS "key" checks for the key
+                        (Value::Map(entries), Value::String(key)) => {
+                            return Ok(Value::Bool(entries.contains_key(key)));
+                        }
+                        // Map CONTAINS {k: v} checks that every entry is present
+                        (Value::Map(_), Value::Map(_)) => {
+                            return Ok(Value::Bool(s.contains_map(&pattern)));
+                        }
+                        (Value::Null, _) | (_, Value::Null) => return Ok(Value::Null),
+                        _ => {}
                     }
                 }
                 Err(PatternError::type_error(
                     "CONTAINS expects (string, pattern)",
                 ))
             }
+            "search" | "search_score" => {
+                // Full-text match: every query word occurs in the text
+                if args.len() == 2 {
+                    let text = self.eval(&args[0], bindings, graph)?;
+                    let query = self.eval(&args[1], bindings, graph)?;
+                    match (text, query) {
+                        (Value::String(text), Value::String(query)) => {
+                            let score = mew_core::text::search(&text, &query);
+                            return Ok(if name_lower == "search" {
+                                Value::Bool(score.is_some())
+                            } else {
+                                Value::Float(score.unwrap_or(0.0))
+                            });
+                        }
+                        (Value::Null, _) | (_, Value::Null) => return Ok(Value::Null),
+                        _ => {}
+                    }
+                }
+                Err(PatternError::type_error(format!(
+                    "{} expects (string, query)",
+                    name_lower.to_uppercase()
+                )))
+            }
             "in" => {
                 // x IN [a, b, c] - check if x is in the list
                 if args.len() >= 2 {
                     let needle = self.eval(&args[0], bindings, graph)?;
                     let haystack = self.eval(&args[1], bindings, graph)?;
-                    if let Value::List(list) = haystack {
-                        return Ok(Value::Bool(list.contains(&needle)));
+                    match (&needle, haystack) {
+                        (_, Value::Null) => return Ok(Value::Null),
+                        (_, Value::List(list)) => {
+                            for item in &list {
+                                if matches!(self.eval_eq(&needle, item), Ok(Value::Bool(true))) {
+                                    return Ok(Value::Bool(true));
+                                }
+                            }
+                            return Ok(Value::Bool(false));
+                        }
+                        // "key" IN map checks for the key
+                        (Value::String(key), Value::Map(entries)) => {
+                            return Ok(Value::Bool(entries.contains_key(key)))
+                        }
+                        _ => {}
                     }
                 }
-                Err(PatternError::type_error("IN expects (value, list)"))
+                Err(PatternError::type_error(
+                    "IN expects (value, list) or (key, map)",
+                ))
+            }
+            "size" => {
+                // Number of list elements, map entries or string characters
+                if args.len() == 1 {
+                    return match self.eval(&args[0], bindings, graph)? {
+                        Value::List(items) => Ok(Value::Int(items.len() as i64)),
+                        Value::Map(entries) => Ok(Value::Int(entries.len() as i64)),
+                        Value::String(s) => Ok(Value::Int(s.chars().count() as i64)),
+                        Value::Null => Ok(Value::Null),
+                        _ => Err(PatternError::type_error(
+                            "SIZE expects a list, map or string argument",
+                        )),
+                    };
+                }
+                Err(PatternError::type_error("SIZE expects one argument"))
+            }
+            "head" | "last" => {
+                // First or last element; null for an empty list
+                if args.len() == 1 {
+                    return match self.eval(&args[0], bindings, graph)? {
+                        Value::List(items) => {
+                            let item = if name_lower == "head" {
+                                items.into_iter().next()
+                            } else {
+                                items.into_iter().next_back()
+                            };
+                            Ok(item.unwrap_or(Value::Null))
+                        }
+                        Value::Null => Ok(Value::Null),
+                        _ => Err(PatternError::type_error(format!(
+                            "{} expects a list argument",
+                            name_lower.to_uppercase()
+                        ))),
+                    };
+                }
+                Err(PatternError::type_error(format!(
+                    "{} expects one argument",
+                    name_lower.to_uppercase()
+                )))
+            }
+            "slice" => {
+                // slice(list, start[, end]): elements from start up to, not including,
+                // end; negative positions count from the end
+                if args.len() == 2 || args.len() == 3 {
+                    let list = self.eval(&args[0], bindings, graph)?;
+                    let start = self.eval(&args[1], bindings, graph)?;
+                    let end = match args.get(2) {
+                        Some(arg) => self.eval(arg, bindings, graph)?,
+                        None => Value::Null,
+                    };
+                    return match (list, start, end) {
+                        (Value::Null, _, _) => Ok(Value::Null),
+                        (
+                            Value::List(items),
+                            Value::Int(start),
+                            end @ (Value::Int(_) | Value::Null),
+                        ) => {
+                            let len = items.len() as i64;
+                            let clamp =
+                                |i: i64| (if i < 0 { len + i } else { i }).clamp(0, len) as usize;
+                            let start = clamp(start);
+                            let end = match end {
+                                Value::Int(end) => clamp(end),
+                                _ => items.len(),
+                            };
+                            Ok(Value::List(
+                                items
+                                    .get(start..end.max(start))
+                                    .unwrap_or_default()
+                                    .to_vec(),
+                            ))
+                        }
+                        _ => Err(PatternError::type_error(
+                            "SLICE expects (list, start int[, end int])",
+                        )),
+                    };
+                }
+                Err(PatternError::type_error(
+                    "SLICE expects two or three arguments",
+                ))
+            }
+            "split" => {
+                if args.len() == 2 {
+                    let s = self.eval(&args[0], bindings, graph)?;
+                    let delim = self.eval(&args[1], bindings, graph)?;
+                    return match (s, delim) {
+                        (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+                        // An empty delimiter splits into characters
+                        (Value::String(s), Value::String(delim)) if delim.is_empty() => Ok(
+                            Value::List(s.chars().map(|c| Value::String(c.to_string())).collect()),
+                        ),
+                        (Value::String(s), Value::String(delim)) => Ok(Value::List(
+                            s.split(delim.as_str())
+                                .map(|part| Value::String(part.to_string()))
+                                .collect(),
+                        )),
+                        _ => Err(PatternError::type_error(
+                            "SPLIT expects (string, delimiter)",
+                        )),
+                    };
+                }
+                Err(PatternError::type_error("SPLIT expects two arguments"))
+            }
+            "join" => {
+                // join(list, delimiter): null elements are skipped
+                if args.len() == 2 {
+                    let list = self.eval(&args[0], bindings, graph)?;
+                    let delim = self.eval(&args[1], bindings, graph)?;
+                    return match (list, delim) {
+                        (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+                        (Value::List(items), Value::String(delim)) => {
+                            let mut parts = Vec::with_capacity(items.len());
+                            for item in items {
+                                match item {
+                                    Value::String(s) => parts.push(s),
+                                    Value::Null => {}
+                                    other => {
+                                        return Err(PatternError::type_error(format!(
+                                            "JOIN expects a list of strings, got {}",
+                                            other.type_name()
+                                        )))
+                                    }
+                                }
+                            }
+                            Ok(Value::String(parts.join(&delim)))
+                        }
+                        _ => Err(PatternError::type_error("JOIN expects (list, delimiter)")),
+                    };
+                }
+                Err(PatternError::type_error("JOIN expects two arguments"))
+            }
+            "hash" => {
+                // Content hash: hash(bytes) = sha256(bytes); strings hash their UTF-8 encoding
+                if let Some(arg) = args.first() {
+                    let val = self.eval(arg, bindings, graph)?;
+                    return match val {
+                        Value::Bytes(b) => Ok(Value::Hash(Sha256::digest(&b).into())),
+                        Value::String(s) => Ok(Value::Hash(Sha256::digest(s.as_bytes()).into())),
+                        Value::Null => Ok(Value::Null),
+                        _ => Err(PatternError::type_error(
+                            "HASH expects a bytes or string argument",
+                        )),
+                    };
+                }
+                Err(PatternError::type_error("HASH expects one argument"))
             }
             "replace" => {
                 if args.len() >= 3 {
@@ -579,6 +1032,124 @@ impl<'r> Evaluator<'r> {
                     "REPLACE expects (string, from, to)",
                 ))
             }
+            "vector" => {
+                if let Some(arg) = args.first() {
+                    let val = self.eval(arg, bindings, graph)?;
+                    if val.is_null() {
+                        return Ok(Value::Null);
+                    }
+                    return val.to_vector().map(Value::Vector).ok_or_else(|| {
+                        PatternError::type_error("VECTOR expects a list of numbers")
+                    });
+                }
+                Err(PatternError::type_error("VECTOR expects one argument"))
+            }
+            "cosine_similarity" | "l2_distance" => {
+                if args.len() >= 2 {
+                    let a = self.eval(&args[0], bindings, graph)?;
+                    let b = self.eval(&args[1], bindings, graph)?;
+                    if a.is_null() || b.is_null() {
+                        return Ok(Value::Null);
+                    }
+                    let metric = if name_lower == "l2_distance" {
+                        VectorMetric::L2
+                    } else {
+                        VectorMetric::Cosine
+                    };
+                    if let (Some(a), Some(b)) = (a.to_vector(), b.to_vector()) {
+                        if a.len() != b.len() {
+                            return Err(PatternError::type_error(format!(
+                                "{} expects vectors of equal dimension, got {} and {}",
+                                name_lower.to_uppercase(),
+                                a.len(),
+                                b.len()
+                            )));
+                        }
+                        // Undefined scores (cosine of a zero vector) are null
+                        return Ok(metric
+                            .score(&a, &b)
+                            .map(Value::Float)
+                            .unwrap_or(Value::Null));
+                    }
+                }
+                Err(PatternError::type_error(format!(
+                    "{} expects (vector, vector)",
+                    name_lower.to_uppercase()
+                )))
+            }
+            "point" => {
+                if args.len() == 2 || args.len() == 3 {
+                    let coords: Vec<Value> = args
+                        .iter()
+                        .map(|arg| self.eval(arg, bindings, graph))
+                        .collect::<PatternResult<_>>()?;
+                    if coords.iter().any(Value::is_null) {
+                        return Ok(Value::Null);
+                    }
+                    if let Some(p) = Value::List(coords).to_point() {
+                        return Ok(Value::Point(p));
+                    }
+                }
+                Err(PatternError::type_error(
+                    "POINT expects 2 or 3 numeric coordinates",
+                ))
+            }
+            // Spatial functions: points may also be given as lists of coordinates.
+            // Points of different dimensionality are incomparable and yield null.
+            "distance" => {
+                if args.len() == 2 {
+                    let points = self.eval_points(&args[..2], bindings, graph, "DISTANCE")?;
+                    return Ok(match points {
+                        Some(p) => p[0]
+                            .distance(&p[1])
+                            .map(Value::Float)
+                            .unwrap_or(Value::Null),
+                        None => Value::Null,
+                    });
+                }
+                Err(PatternError::type_error("DISTANCE expects (point, point)"))
+            }
+            "within_box" => {
+                if args.len() == 3 {
+                    let points = self.eval_points(args, bindings, graph, "WITHIN_BOX")?;
+                    return Ok(match points {
+                        Some(p) => p[0]
+                            .within_box(&p[1], &p[2])
+                            .map(Value::Bool)
+                            .unwrap_or(Value::Null),
+                        None => Value::Null,
+                    });
+                }
+                Err(PatternError::type_error(
+                    "WITHIN_BOX expects (point, corner, corner)",
+                ))
+            }
+            "within_radius" => {
+                if args.len() == 3 {
+                    let points = self.eval_points(&args[..2], bindings, graph, "WITHIN_RADIUS")?;
+                    let radius = self.eval(&args[2], bindings, graph)?;
+                    let radius = match radius {
+                        Value::Null => return Ok(Value::Null),
+                        Value::Int(i) => i as f64,
+                        Value::Float(f) => f,
+                        _ => {
+                            return Err(PatternError::type_error(
+                                "WITHIN_RADIUS expects a numeric radius",
+                            ))
+                        }
+                    };
+                    return Ok(match points {
+                        Some(p) => p[0]
+                            .within_radius(&p[1], radius)
+                            .map(Value::Bool)
+                            .unwrap_or(Value::Null),
+                        None => Value::Null,
+                    });
+                }
+                Err(PatternError::type_error(
+                    "WITHIN_RADIUS expects (point, center, radius)",
+                ))
+            }
             "floor" => {
                 if let Some(arg) = args.first() {
                     let val = self.eval(arg, bindings, graph)?;
@@ -612,6 +1183,11 @@ impl<'r> Evaluator<'r> {
                 }
                 Err(PatternError::type_error("ROUND expects one argument"))
             }
+            // An edge type used as a predicate, as in constraint patterns:
+            // `friend_of(a, b)` holds when such an edge links the targets
+            _ if self.registry.get_edge_type_id(name).is_some() => {
+                self.eval_edge_predicate(name, args, bindings, graph)
+            }
             _ => Err(PatternError::invalid_operation(format!(
                 "unknown function '{}'",
                 name
@@ -619,6 +1195,64 @@ impl<'r> Evaluator<'r> {
         }
     }
 
+    /// Evaluate `edge_type(a, b, ...)` over variables as an EXISTS of that
+    /// one edge.
+    fn eval_edge_predicate(
+        &self,
+        edge_type: &str,
+        args: &[Expr],
+        bindings: &Bindings,
+        graph: &Graph,
+    ) -> PatternResult<Value> {
+        let targets = args
+            .iter()
+            .map(|arg| match arg {
+                Expr::Var(name, _) => Ok(name.clone()),
+                _ => Err(PatternError::type_error(format!(
+                    "arguments of edge predicate '{}' must be variables",
+                    edge_type
+                ))),
+            })
+            .collect::<PatternResult<Vec<_>>>()?;
+        let edge = PatternElem::Edge(EdgePattern {
+            edge_type: edge_type.to_string(),
+            targets,
+            alias: None,
+            transitive: None,
+            min_depth: None,
+            max_depth: None,
+            span: Span::default(),
+        });
+        let exists = self.eval_exists(&[edge], None, bindings, graph)?;
+        Ok(Value::Bool(exists))
+    }
+
+    /// Evaluate spatial function arguments as points. Returns None if any is null.
+    fn eval_points(
+        &self,
+        args: &[Expr],
+        bindings: &Bindings,
+        graph: &Graph,
+        fn_name: &str,
+    ) -> PatternResult<Option<Vec<Point>>> {
+        let mut points = Vec::with_capacity(args.len());
+        for arg in args {
+            let val = self.eval(arg, bindings, graph)?;
+            if val.is_null() {
+                return Ok(None);
+            }
+            let point = val.to_point().ok_or_else(|| {
+                PatternError::type_error(format!(
+                    "{} expects points, got {}",
+                    fn_name,
+                    val.type_name()
+                ))
+            })?;
+            points.push(point);
+        }
+        Ok(Some(points))
+    }
+
     // ========== Arithmetic helpers ==========
 
     /// Compare two numeric values and return the one matching the predicate.
@@ -671,10 +1305,14 @@ impl<'r> Evaluator<'r> {
             (Value::Int(a), Value::Float(b)) => Ok(Value::Float(*a as f64 + b)),
             (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a + *b as f64)),
             // Timestamp + Duration = Timestamp
-            (Value::Timestamp(ts), Value::Duration(dur)) => Ok(Value::Timestamp(ts + dur)),
-            (Value::Duration(dur), Value::Timestamp(ts)) => Ok(Value::Timestamp(ts + dur)),
+            (Value::Timestamp(ts), Value::Duration(dur))
+            | (Value::Duration(dur), Value::Timestamp(ts)) => {
+                Ok(Value::Timestamp(Self::checked_time(ts.checked_add(*dur))?))
+            }
             // Duration + Duration = Duration
-            (Value::Duration(a), Value::Duration(b)) => Ok(Value::Duration(a + b)),
+            (Value::Duration(a), Value::Duration(b)) => {
+                Ok(Value::Duration(Self::checked_time(a.checked_add(*b))?))
+            }
             _ => Err(PatternError::type_error(format!(
                 "cannot add {:?} and {:?}",
                 left, right
@@ -691,11 +1329,14 @@ impl<'r> Evaluator<'r> {
             (Value::Int(a), Value::Float(b)) => Ok(Value::Float(*a as f64 - b)),
             (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a - *b as f64)),
             // Timestamp - Duration = Timestamp
-            (Value::Timestamp(ts), Value::Duration(dur)) => Ok(Value::Timestamp(ts - dur)),
-            // Timestamp - Timestamp = Duration
-            (Value::Timestamp(a), Value::Timestamp(b)) => Ok(Value::Duration(a - b)),
-            // Duration - Duration = Duration
-            (Value::Duration(a), Value::Duration(b)) => Ok(Value::Duration(a - b)),
+            (Value::Timestamp(ts), Value::Duration(dur)) => {
+                Ok(Value::Timestamp(Self::checked_time(ts.checked_sub(*dur))?))
+            }
+            // Timestamp - Timestamp = Duration, Duration - Duration = Duration
+            (Value::Timestamp(a), Value::Timestamp(b))
+            | (Value::Duration(a), Value::Duration(b)) => {
+                Ok(Value::Duration(Self::checked_time(a.checked_sub(*b))?))
+            }
             _ => Err(PatternError::type_error(format!(
                 "cannot subtract {:?} and {:?}",
                 left, right
@@ -711,6 +1352,10 @@ impl<'r> Evaluator<'r> {
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
             (Value::Int(a), Value::Float(b)) => Ok(Value::Float(*a as f64 * b)),
             (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a * *b as f64)),
+            // Duration * Int = Duration
+            (Value::Duration(d), Value::Int(n)) | (Value::Int(n), Value::Duration(d)) => {
+                Ok(Value::Duration(Self::checked_time(d.checked_mul(*n))?))
+            }
             _ => Err(PatternError::type_error(format!(
                 "cannot multiply {:?} and {:?}",
                 left, right
@@ -750,6 +1395,21 @@ impl<'r> Evaluator<'r> {
                     Ok(Value::Float(a / *b as f64))
                 }
             }
+            // Duration / Int = Duration, Duration / Duration = Int
+            (Value::Duration(a), Value::Int(b)) => {
+                if *b == 0 {
+                    Err(PatternError::DivisionByZero)
+                } else {
+                    Ok(Value::Duration(Self::checked_time(a.checked_div(*b))?))
+                }
+            }
+            (Value::Duration(a), Value::Duration(b)) => {
+                if *b == 0 {
+                    Err(PatternError::DivisionByZero)
+                } else {
+                    Ok(Value::Int(Self::checked_time(a.checked_div(*b))?))
+                }
+            }
             _ => Err(PatternError::type_error(format!(
                 "cannot divide {:?} by {:?}",
                 left, right
@@ -797,7 +1457,12 @@ impl<'r> Evaluator<'r> {
             (Value::EdgeRef(a), Value::EdgeRef(b)) => Ok(Value::Bool(a == b)),
             (Value::Timestamp(a), Value::Timestamp(b)) => Ok(Value::Bool(a == b)),
             (Value::Duration(a), Value::Duration(b)) => Ok(Value::Bool(a == b)),
-            (Value::List(a), Value::List(b)) => Ok(Value::Bool(a == b)),
+            (Value::List(a), Value::List(b)) => Ok(Value::Bool(self.lists_equal(a, b)?)),
+            (Value::Map(a), Value::Map(b)) => Ok(Value::Bool(a == b)),
+            (Value::Bytes(a), Value::Bytes(b)) => Ok(Value::Bool(a == b)),
+            (Value::Hash(a), Value::Hash(b)) => Ok(Value::Bool(a == b)),
+            (Value::Vector(a), Value::Vector(b)) => Ok(Value::Bool(a == b)),
+            (Value::Point(a), Value::Point(b)) => Ok(Value::Bool(a == b)),
             _ => Err(PatternError::type_error(format!(
                 "cannot compare {:?} = {:?}",
                 left, right
@@ -825,7 +1490,12 @@ impl<'r> Evaluator<'r> {
             (Value::EdgeRef(a), Value::EdgeRef(b)) => Ok(Value::Bool(a != b)),
             (Value::Timestamp(a), Value::Timestamp(b)) => Ok(Value::Bool(a != b)),
             (Value::Duration(a), Value::Duration(b)) => Ok(Value::Bool(a != b)),
-            (Value::List(a), Value::List(b)) => Ok(Value::Bool(a != b)),
+            (Value::List(a), Value::List(b)) => Ok(Value::Bool(!self.lists_equal(a, b)?)),
+            (Value::Map(a), Value::Map(b)) => Ok(Value::Bool(a != b)),
+            (Value::Bytes(a), Value::Bytes(b)) => Ok(Value::Bool(a != b)),
+            (Value::Hash(a), Value::Hash(b)) => Ok(Value::Bool(a != b)),
+            (Value::Vector(a), Value::Vector(b)) => Ok(Value::Bool(a != b)),
+            (Value::Point(a), Value::Point(b)) => Ok(Value::Bool(a != b)),
             _ => Err(PatternError::type_error(format!(
                 "cannot compare {:?} != {:?}",
                 left, right
@@ -949,6 +1619,25 @@ impl<'r> Evaluator<'r> {
 
     // ========== String helpers ==========
 
+    /// Unwrap timestamp or duration arithmetic, which must not wrap around.
+    fn checked_time(result: Option<i64>) -> PatternResult<i64> {
+        result.ok_or_else(|| PatternError::invalid_operation("Duration overflow"))
+    }
+
+    /// Compare lists element by element with `=`, so `[1, 2] = [1.0, 2.0]`.
+    fn lists_equal(&self, a: &[Value], b: &[Value]) -> PatternResult<bool> {
+        if a.len() != b.len() {
+            return Ok(false);
+        }
+        for (x, y) in a.iter().zip(b) {
+            // Elements of different types are unequal rather than an error
+            if !matches!(self.eval_eq(x, y), Ok(Value::Bool(true))) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     fn eval_concat(&self, left: &Value, right: &Value) -> PatternResult<Value> {
         match (left, right) {
             (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{}{}", a, b))),
@@ -959,41 +1648,6 @@ impl<'r> Evaluator<'r> {
         }
     }
 
-    // ========== Timestamp helpers ==========
-
-    /// Convert milliseconds since epoch to (year, month, day).
-    fn timestamp_to_date(ms: i64) -> (i32, u32, u32) {
-        // Convert ms to days since epoch
-        let days = (ms / 86_400_000) as i32;
-        Self::days_to_date(days)
-    }
-
-    /// Convert milliseconds since epoch to (hour, minute, second).
-    fn timestamp_to_time(ms: i64) -> (u32, u32, u32) {
-        let ms_in_day = ms.rem_euclid(86_400_000) as u64;
-        let total_seconds = ms_in_day / 1000;
-        let hour = (total_seconds / 3600) as u32;
-        let minute = ((total_seconds % 3600) / 60) as u32;
-        let second = (total_seconds % 60) as u32;
-        (hour, minute, second)
-    }
-
-    /// Convert days since Unix epoch (1970-01-01) to (year, month, day).
-    fn days_to_date(days: i32) -> (i32, u32, u32) {
-        // Algorithm from https://howardhinnant.github.io/date_algorithms.html
-        let z = days + 719468;
-        let era = if z >= 0 { z } else { z - 146096 } / 146097;
-        let doe = (z - era * 146097) as u32; // day of era [0, 146096]
-        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // year of era [0, 399]
-        let y = yoe as i32 + era * 400;
-        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // day of year [0, 365]
-        let mp = (5 * doy + 2) / 153; // month offset [0, 11]
-        let d = doy - (153 * mp + 2) / 5 + 1; // day [1, 31]
-        let m = if mp < 10 { mp + 3 } else { mp - 9 }; // month [1, 12]
-        let y = if m <= 2 { y + 1 } else { y };
-        (y, m, d)
-    }
-
     /// Evaluate an expression and convert to bool.
     pub fn eval_bool(
         &self,
@@ -1191,4 +1845,48 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), PatternError::DivisionByZero));
     }
+
+    #[test]
+    fn test_eval_list_quantifiers() {
+        // GIVEN
+        let registry = test_registry();
+        let graph = test_graph();
+        let evaluator = Evaluator::new(&registry);
+        let mut bindings = Bindings::new();
+        bindings.insert(
+            "scores",
+            Value::List(vec![Value::Int(1), Value::Int(4), Value::Null]),
+        );
+
+        // ANY/ALL(s IN scores WHERE s > threshold)
+        let quantifier = |kind: QuantifierKind, threshold: i64| {
+            Expr::Quantifier(Box::new(Quantifier {
+                kind,
+                var: "s".to_string(),
+                list: Expr::Var("scores".to_string(), Span::default()),
+                predicate: Expr::BinaryOp(
+                    BinaryOp::Gt,
+                    Box::new(Expr::Var("s".to_string(), Span::default())),
+                    Box::new(Expr::Literal(Literal {
+                        kind: LiteralKind::Int(threshold),
+                        span: Span::default(),
+                    })),
+                    Span::default(),
+                ),
+                span: Span::default(),
+            }))
+        };
+        let eval = |expr: &Expr| evaluator.eval(expr, &bindings, &graph).unwrap();
+
+        // WHEN / THEN a match decides ANY, a failure decides ALL
+        assert_eq!(eval(&quantifier(QuantifierKind::Any, 3)), Value::Bool(true));
+        assert_eq!(
+            eval(&quantifier(QuantifierKind::All, 3)),
+            Value::Bool(false)
+        );
+
+        // WHEN / THEN otherwise the null element leaves the result unknown
+        assert_eq!(eval(&quantifier(QuantifierKind::Any, 5)), Value::Null);
+        assert_eq!(eval(&quantifier(QuantifierKind::All, 0)), Value::Null);
+    }
 }
@@ -16,10 +16,12 @@ mod eval;
 mod matcher;
 mod pattern;
 pub mod target;
+mod transitive;
 
 pub use binding::{Binding, Bindings};
 pub use error::{PatternError, PatternResult};
-pub use eval::Evaluator;
+pub use eval::{current_time_millis, Evaluator};
 pub use matcher::Matcher;
 pub use pattern::{CompiledPattern, PatternOp};
 pub use target::{resolve_target, resolve_target_ref, resolve_var_target, TargetError};
+pub use transitive::{Closure, ClosureEnd, DEFAULT_MAX_DEPTH};
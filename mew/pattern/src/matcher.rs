@@ -1,6 +1,6 @@
 //! Pattern matching against the graph.
 
-use crate::{Binding, Bindings, CompiledPattern, Evaluator, PatternOp, PatternResult};
+use crate::{Binding, Bindings, ClosureEnd, CompiledPattern, Evaluator, PatternOp, PatternResult};
 use mew_core::NodeId;
 use mew_graph::Graph;
 use mew_registry::Registry;
@@ -61,15 +61,13 @@ impl<'r, 'g> Matcher<'r, 'g> {
             // Track edge variables from symmetric edge FollowEdge operations
             if let PatternOp::FollowEdge {
                 edge_type_id,
-                edge_var,
+                edge_var: Some(var),
                 ..
             } = op
             {
-                if let Some(ref var) = edge_var {
-                    if let Some(edge_type) = self.registry.get_edge_type(*edge_type_id) {
-                        if edge_type.symmetric {
-                            edge_vars_for_dedup.push(var.clone());
-                        }
+                if let Some(edge_type) = self.registry.get_edge_type(*edge_type_id) {
+                    if edge_type.symmetric {
+                        edge_vars_for_dedup.push(var.clone());
                     }
                 }
             }
@@ -261,7 +259,7 @@ impl<'r, 'g> Matcher<'r, 'g> {
                                     new_bindings.insert(alias, Binding::Edge(edge_id));
                                     // Mark as reverse match for deduplication preference
                                     new_bindings.insert(
-                                        &format!("_reverse_{}", alias),
+                                        format!("_reverse_{}", alias),
                                         Binding::Value(mew_core::Value::Bool(true)),
                                     );
                                 }
@@ -340,6 +338,27 @@ impl<'r, 'g> Matcher<'r, 'g> {
                 Ok(vec![])
             }
 
+            PatternOp::Transitive { closure, from, to } => {
+                let from_end = ClosureEnd::of(bindings, from)?;
+                let to_end = ClosureEnd::of(bindings, to)?;
+                let pairs = closure.pairs(self.registry, self.graph, from_end, to_end, from == to);
+
+                let matches = pairs
+                    .into_iter()
+                    .map(|(from_id, to_id)| {
+                        let mut new_bindings = bindings.clone();
+                        if from_end == ClosureEnd::Free {
+                            new_bindings.insert(from, Binding::Node(from_id));
+                        }
+                        if to_end == ClosureEnd::Free {
+                            new_bindings.insert(to, Binding::Node(to_id));
+                        }
+                        new_bindings
+                    })
+                    .collect();
+                Ok(matches)
+            }
+
             PatternOp::Filter { condition } => {
                 // Evaluate the filter condition
                 let result = self.evaluator.eval_bool(condition, bindings, self.graph)?;
@@ -456,6 +475,8 @@ mod tests {
                 targets: vec!["p".to_string(), "t".to_string()],
                 alias: None,
                 transitive: None,
+                min_depth: None,
+                max_depth: None,
                 span: Default::default(),
             }),
         ];
@@ -570,6 +591,8 @@ mod tests {
                 targets: vec!["a".to_string(), "b".to_string()],
                 alias: None,
                 transitive: None,
+                min_depth: None,
+                max_depth: None,
                 span: Default::default(),
             }),
         ];
@@ -592,4 +615,69 @@ mod tests {
         assert_eq!(binding.get("a").unwrap().as_node(), Some(alice));
         assert_eq!(binding.get("b").unwrap().as_node(), Some(bob));
     }
+
+    #[test]
+    fn test_match_transitive_binds_undeclared_targets() {
+        // GIVEN owners a→b→c→a linked by manages
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Person")
+            .attr(AttrDef::new("name", "String"))
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("manages")
+            .param("boss", "Person")
+            .param("report", "Person")
+            .done()
+            .unwrap();
+        let registry = builder.build().unwrap();
+        let mut graph = Graph::new();
+        let person = registry.get_type_id("Person").unwrap();
+        let manages = registry.get_edge_type_id("manages").unwrap();
+        let a = graph.create_node(person, attrs! { "name" => "A" });
+        let b = graph.create_node(person, attrs! { "name" => "B" });
+        let c = graph.create_node(person, attrs! { "name" => "C" });
+        for (from, to) in [(a, b), (b, c)] {
+            graph
+                .create_edge(manages, vec![from.into(), to.into()], attrs! {})
+                .unwrap();
+        }
+        let transitive = |targets: [&str; 2]| {
+            PatternElem::Edge(EdgePattern {
+                edge_type: "manages".to_string(),
+                targets: targets.iter().map(|t| t.to_string()).collect(),
+                alias: None,
+                transitive: Some(mew_parser::TransitiveKind::Plus),
+                min_depth: None,
+                max_depth: None,
+                span: Default::default(),
+            })
+        };
+        let matcher = Matcher::new(&registry, &graph);
+
+        // WHEN matching manages+(x, y) with neither end declared
+        let pattern = CompiledPattern::compile(&[transitive(["x", "y"])], &registry).unwrap();
+        let matches = matcher.find_all(&pattern).unwrap();
+
+        // THEN every reachable pair is bound: (a,b), (a,c), (b,c)
+        let mut pairs: Vec<_> = matches
+            .iter()
+            .map(|m| (m.get("x").unwrap().as_node(), m.get("y").unwrap().as_node()))
+            .collect();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![(Some(a), Some(b)), (Some(a), Some(c)), (Some(b), Some(c))]
+        );
+
+        // AND manages+(u, u) finds no cycle until c manages a
+        let cycle = CompiledPattern::compile(&[transitive(["u", "u"])], &registry).unwrap();
+        assert!(matcher.find_all(&cycle).unwrap().is_empty());
+        graph
+            .create_edge(manages, vec![c.into(), a.into()], attrs! {})
+            .unwrap();
+        let matcher = Matcher::new(&registry, &graph);
+        assert_eq!(matcher.find_all(&cycle).unwrap().len(), 3);
+    }
 }
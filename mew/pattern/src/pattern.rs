@@ -4,7 +4,7 @@ use mew_core::{EdgeTypeId, TypeId};
 use mew_parser::{EdgePattern, Expr, NodePattern, PatternElem};
 use mew_registry::Registry;
 
-use crate::{PatternError, PatternResult};
+use crate::{Closure, PatternError, PatternResult};
 
 /// A compiled pattern operation.
 #[derive(Debug, Clone)]
@@ -22,6 +22,13 @@ pub enum PatternOp {
         edge_type_id: EdgeTypeId,
         target_vars: Vec<String>,
     },
+    /// Join on a transitive edge pattern (`edge+` or `edge*`), binding
+    /// whichever of `from` and `to` is not bound yet.
+    Transitive {
+        closure: Closure,
+        from: String,
+        to: String,
+    },
     /// Filter by expression.
     Filter { condition: Expr },
     /// Check NOT EXISTS subpattern.
@@ -70,6 +77,21 @@ impl CompiledPattern {
                     bound_vars.push(node.var.clone());
                     new_vars.push(node.var.clone());
                 }
+                PatternElem::Edge(edge) if edge.transitive.is_some() => {
+                    // Transitive patterns bind their undeclared targets
+                    let closure = Closure::new(edge, registry)?;
+                    for target in &edge.targets {
+                        if target != "_" && !bound_vars.contains(target) {
+                            bound_vars.push(target.clone());
+                            new_vars.push(target.clone());
+                        }
+                    }
+                    ops.push(PatternOp::Transitive {
+                        closure,
+                        from: edge.targets[0].clone(),
+                        to: edge.targets[1].clone(),
+                    });
+                }
                 PatternElem::Edge(edge) => {
                     let op = compile_edge_pattern(edge, registry, &bound_vars)?;
                     ops.push(op);
@@ -234,6 +256,8 @@ mod tests {
                 targets: vec!["p".to_string(), "t".to_string()],
                 alias: None,
                 transitive: None,
+                min_depth: None,
+                max_depth: None,
                 span: Default::default(),
             }),
         ];
@@ -0,0 +1,444 @@
+//! Transitive closure over one edge type, for `edge+` and `edge*` patterns.
+
+use std::collections::HashSet;
+
+use mew_core::{EdgeTypeId, NodeId, TypeId};
+use mew_graph::Graph;
+use mew_parser::{EdgePattern, TransitiveKind};
+use mew_registry::Registry;
+
+use crate::{Bindings, PatternError, PatternResult};
+
+/// Hop limit of a transitive pattern without `[depth: ...]`.
+pub const DEFAULT_MAX_DEPTH: i64 = 100;
+
+/// One end of a transitive pattern, as seen by a row being matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClosureEnd {
+    /// The variable is bound to this node.
+    Bound(NodeId),
+    /// The variable is not bound yet; the pattern binds it.
+    Free,
+    /// `_`: any node, not bound.
+    Wildcard,
+}
+
+impl ClosureEnd {
+    /// How `var` stands in `bindings`.
+    pub fn of(bindings: &Bindings, var: &str) -> PatternResult<Self> {
+        if var == "_" {
+            return Ok(ClosureEnd::Wildcard);
+        }
+        match bindings.get(var) {
+            None => Ok(ClosureEnd::Free),
+            Some(binding) => binding
+                .as_node()
+                .map(ClosureEnd::Bound)
+                .ok_or_else(|| PatternError::type_error("expected node binding")),
+        }
+    }
+
+    fn node(self) -> Option<NodeId> {
+        match self {
+            ClosureEnd::Bound(id) => Some(id),
+            _ => None,
+        }
+    }
+}
+
+/// A compiled transitive edge pattern: which edges to follow and how far.
+///
+/// A node matches when some path from the start has a length within the
+/// depth bounds, even if a shorter path falls below the minimum. The search
+/// is breadth first over (node, hops); once a node has been expanded at a
+/// depth within the bounds it is never expanded again, which ends cycles.
+#[derive(Debug, Clone)]
+pub struct Closure {
+    pub edge_type_id: EdgeTypeId,
+    /// Fewest hops: 0 for `*`, 1 for `+`, unless `[depth: min..max]` says more.
+    pub min_depth: i64,
+    /// Most hops followed.
+    pub max_depth: i64,
+    /// Edges of a symmetric type are followed both ways.
+    pub symmetric: bool,
+    /// Edges of an acyclic type never lead back to where a path started.
+    pub acyclic: bool,
+    /// Node type of the first edge parameter, scanned when neither end is bound.
+    /// None when the parameter is `any`.
+    pub start_type: Option<TypeId>,
+}
+
+impl Closure {
+    /// Compile a transitive edge pattern.
+    pub fn new(edge: &EdgePattern, registry: &Registry) -> PatternResult<Self> {
+        let edge_type_id = registry
+            .get_edge_type_id(&edge.edge_type)
+            .ok_or_else(|| PatternError::unknown_edge_type(&edge.edge_type))?;
+        let edge_type = registry
+            .get_edge_type(edge_type_id)
+            .ok_or_else(|| PatternError::unknown_edge_type(&edge.edge_type))?;
+        if edge_type.params.len() != 2 || edge.targets.len() != 2 {
+            return Err(PatternError::invalid_operation(
+                "Transitive patterns require binary edges",
+            ));
+        }
+
+        let max_depth = edge.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+        if max_depth <= 0 {
+            return Err(PatternError::invalid_operation(
+                "Depth limit must be positive",
+            ));
+        }
+        let min_depth = match edge.transitive {
+            Some(TransitiveKind::Plus) => edge.min_depth.unwrap_or(1).max(1),
+            _ => edge.min_depth.unwrap_or(0),
+        };
+
+        Ok(Self {
+            edge_type_id,
+            min_depth,
+            max_depth,
+            symmetric: edge_type.symmetric,
+            acyclic: edge_type.acyclic,
+            start_type: registry.get_type_id(&edge_type.params[0].type_constraint),
+        })
+    }
+
+    /// Nodes a path may start from when neither end is bound.
+    pub fn starts(&self, registry: &Registry, graph: &Graph) -> Vec<NodeId> {
+        match self.start_type {
+            Some(type_id) => {
+                let mut ids: Vec<NodeId> = graph.nodes_by_type(type_id).collect();
+                for subtype_id in registry.get_subtypes(type_id) {
+                    ids.extend(graph.nodes_by_type(subtype_id));
+                }
+                ids
+            }
+            None => graph.all_node_ids().collect(),
+        }
+    }
+
+    /// Nodes with a path from `start` whose length is within the depth
+    /// bounds, each with the fewest hops in bounds, nearest first. `forward`
+    /// follows edges from their first target to their second; otherwise
+    /// edges are followed backwards.
+    ///
+    /// `start` itself is included at depth 0 when the bounds allow zero hops,
+    /// or at the length of a cycle back to it otherwise.
+    pub fn reach(&self, graph: &Graph, start: NodeId, forward: bool) -> Vec<(NodeId, i64)> {
+        let mut reached = Vec::new();
+        let mut found = HashSet::new();
+        // Nodes already expanded within the bounds. Every later depth is
+        // within the bounds too, so expanding them again finds nothing new.
+        let mut settled = HashSet::new();
+        // Nodes at exactly `depth` hops; below the minimum a node may recur
+        // at several depths, each of which can lead into the bounds.
+        let mut frontier = vec![start];
+        let mut depth = 0;
+        loop {
+            if depth >= self.min_depth {
+                for node in &frontier {
+                    if found.insert(*node) {
+                        reached.push((*node, depth));
+                    }
+                }
+                frontier.retain(|node| settled.insert(*node));
+            }
+            if frontier.is_empty() || depth >= self.max_depth {
+                break;
+            }
+            depth += 1;
+            let mut level = HashSet::new();
+            frontier = frontier
+                .iter()
+                .flat_map(|node| self.neighbors(graph, *node, forward))
+                .filter(|neighbor| level.insert(*neighbor))
+                .collect();
+        }
+        reached
+    }
+
+    /// Whether a path within the depth bounds leads from `from` to `to`.
+    pub fn connects(&self, graph: &Graph, from: NodeId, to: NodeId) -> bool {
+        if from == to && self.acyclic && self.min_depth > 0 {
+            return false;
+        }
+        self.reach(graph, from, true)
+            .iter()
+            .any(|(node, _)| *node == to)
+    }
+
+    /// The (from, to) node pairs the pattern matches for a row. `same_var`
+    /// says both ends are one variable, as in `follows+(u, u)`. A wildcard
+    /// end is only checked for existence, so each pair of the other end is
+    /// returned once.
+    pub fn pairs(
+        &self,
+        registry: &Registry,
+        graph: &Graph,
+        from: ClosureEnd,
+        to: ClosureEnd,
+        same_var: bool,
+    ) -> Vec<(NodeId, NodeId)> {
+        let mut pairs: Vec<(NodeId, NodeId)> = match (from.node(), to.node()) {
+            (Some(f), Some(t)) => {
+                if self.connects(graph, f, t) {
+                    vec![(f, t)]
+                } else {
+                    Vec::new()
+                }
+            }
+            (Some(f), None) => self
+                .reach(graph, f, true)
+                .into_iter()
+                .map(|(n, _)| (f, n))
+                .collect(),
+            (None, Some(t)) => self
+                .reach(graph, t, false)
+                .into_iter()
+                .map(|(n, _)| (n, t))
+                .collect(),
+            (None, None) if same_var => self
+                .starts(registry, graph)
+                .into_iter()
+                .filter(|s| self.connects(graph, *s, *s))
+                .map(|s| (s, s))
+                .collect(),
+            (None, None) => self
+                .starts(registry, graph)
+                .into_iter()
+                .flat_map(|s| {
+                    self.reach(graph, s, true)
+                        .into_iter()
+                        .map(move |(n, _)| (s, n))
+                })
+                .collect(),
+        };
+
+        let from_wild = from == ClosureEnd::Wildcard;
+        let to_wild = to == ClosureEnd::Wildcard;
+        if from_wild || to_wild {
+            let mut seen = HashSet::new();
+            pairs.retain(|(f, t)| {
+                seen.insert(((!from_wild).then_some(*f), (!to_wild).then_some(*t)))
+            });
+        }
+        pairs
+    }
+
+    /// Nodes one edge away from `node`.
+    fn neighbors(&self, graph: &Graph, node: NodeId, forward: bool) -> Vec<NodeId> {
+        let mut neighbors = Vec::new();
+        if forward || self.symmetric {
+            for edge_id in graph.edges_from(node, Some(self.edge_type_id)) {
+                if let Some(target) = graph
+                    .get_edge(edge_id)
+                    .and_then(|edge| edge.targets.get(1).and_then(|t| t.as_node()))
+                {
+                    neighbors.push(target);
+                }
+            }
+        }
+        if !forward || self.symmetric {
+            for edge_id in graph.edges_to(node, Some(self.edge_type_id)) {
+                if let Some(source) = graph
+                    .get_edge(edge_id)
+                    .and_then(|edge| edge.targets.first().and_then(|t| t.as_node()))
+                {
+                    neighbors.push(source);
+                }
+            }
+        }
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mew_core::attrs;
+    use mew_parser::Span;
+    use mew_registry::{AttrDef, RegistryBuilder};
+
+    fn test_registry() -> Registry {
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Task")
+            .attr(AttrDef::new("title", "String"))
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("depends_on")
+            .param("from", "Task")
+            .param("to", "Task")
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("related")
+            .param("a", "Task")
+            .param("b", "Task")
+            .symmetric()
+            .done()
+            .unwrap();
+        builder.build().unwrap()
+    }
+
+    fn edge(
+        edge_type: &str,
+        kind: TransitiveKind,
+        min_depth: Option<i64>,
+        max_depth: Option<i64>,
+    ) -> EdgePattern {
+        EdgePattern {
+            edge_type: edge_type.to_string(),
+            targets: vec!["a".to_string(), "b".to_string()],
+            alias: None,
+            transitive: Some(kind),
+            min_depth,
+            max_depth,
+            span: Span::default(),
+        }
+    }
+
+    /// Tasks t0..t3 with edges of `edge_type` t0→t1→t2→t3→t1.
+    fn cycle_graph(registry: &Registry, edge_type: &str) -> (Graph, Vec<NodeId>) {
+        let mut graph = Graph::new();
+        let task = registry.get_type_id("Task").unwrap();
+        let edge_type_id = registry.get_edge_type_id(edge_type).unwrap();
+        let tasks: Vec<NodeId> = (0..4)
+            .map(|i| graph.create_node(task, attrs! { "title" => format!("t{i}") }))
+            .collect();
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (3, 1)] {
+            graph
+                .create_edge(
+                    edge_type_id,
+                    vec![tasks[from].into(), tasks[to].into()],
+                    attrs! {},
+                )
+                .unwrap();
+        }
+        (graph, tasks)
+    }
+
+    #[test]
+    fn test_reach_stops_at_cycles_with_shortest_depths() {
+        // GIVEN t0→t1→t2→t3→t1
+        let registry = test_registry();
+        let (graph, t) = cycle_graph(&registry, "depends_on");
+
+        // WHEN following depends_on+ from t0 and from t1
+        let closure = Closure::new(
+            &edge("depends_on", TransitiveKind::Plus, None, None),
+            &registry,
+        )
+        .unwrap();
+
+        // THEN every node is reached once at its shortest depth
+        assert_eq!(
+            closure.reach(&graph, t[0], true),
+            vec![(t[1], 1), (t[2], 2), (t[3], 3)]
+        );
+        // AND a node on a cycle reaches itself
+        assert_eq!(
+            closure.reach(&graph, t[1], true),
+            vec![(t[2], 1), (t[3], 2), (t[1], 3)]
+        );
+        // AND backwards follows edges against their direction
+        assert_eq!(closure.reach(&graph, t[0], false), vec![]);
+    }
+
+    #[test]
+    fn test_reach_honours_depth_bounds() {
+        // GIVEN t0→t1→t2→t3→t1
+        let registry = test_registry();
+        let (graph, t) = cycle_graph(&registry, "depends_on");
+
+        // WHEN following depends_on* and depends_on+ [depth: 2..3] from t0
+        let star = Closure::new(
+            &edge("depends_on", TransitiveKind::Star, None, None),
+            &registry,
+        )
+        .unwrap();
+        let ranged = Closure::new(
+            &edge("depends_on", TransitiveKind::Plus, Some(2), Some(3)),
+            &registry,
+        )
+        .unwrap();
+
+        // THEN zero hops include the start, and the range cuts both ends
+        assert_eq!(star.reach(&graph, t[0], true)[0], (t[0], 0));
+        assert_eq!(ranged.reach(&graph, t[0], true), vec![(t[2], 2), (t[3], 3)]);
+        assert!(!ranged.connects(&graph, t[0], t[1]));
+    }
+
+    #[test]
+    fn test_reach_matches_longer_paths_within_bounds() {
+        // GIVEN t0→t1→t2→t3→t1, so t1 is 1 hop from t0 and again 4 hops
+        let registry = test_registry();
+        let (graph, t) = cycle_graph(&registry, "depends_on");
+
+        // WHEN following depends_on+ [depth: 2..4] and [depth: 5..6] from t0
+        let ranged = |min, max| {
+            Closure::new(
+                &edge("depends_on", TransitiveKind::Plus, Some(min), Some(max)),
+                &registry,
+            )
+            .unwrap()
+        };
+
+        // THEN t1 matches through the longer path around the cycle
+        assert_eq!(
+            ranged(2, 4).reach(&graph, t[0], true),
+            vec![(t[2], 2), (t[3], 3), (t[1], 4)]
+        );
+        assert!(ranged(2, 4).connects(&graph, t[0], t[1]));
+        // AND every node on the cycle has a path of 5 or 6 hops
+        assert_eq!(
+            ranged(5, 6).reach(&graph, t[0], true),
+            vec![(t[2], 5), (t[3], 6)]
+        );
+    }
+
+    #[test]
+    fn test_reach_follows_symmetric_edges_both_ways() {
+        // GIVEN related edges stored t0→t1→t2→t3→t1
+        let registry = test_registry();
+        let (graph, t) = cycle_graph(&registry, "related");
+
+        // WHEN following related+ from t3 with a depth limit of 1
+        let closure = Closure::new(
+            &edge("related", TransitiveKind::Plus, None, Some(1)),
+            &registry,
+        )
+        .unwrap();
+
+        // THEN neighbours on either side are reached
+        let mut reached: Vec<NodeId> = closure
+            .reach(&graph, t[3], true)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        reached.sort();
+        assert_eq!(reached, vec![t[1], t[2]]);
+    }
+
+    #[test]
+    fn test_closure_rejects_bad_depth_and_non_binary_edges() {
+        // GIVEN a zero depth limit and a ternary edge type
+        let registry = test_registry();
+        let mut ternary = edge("depends_on", TransitiveKind::Plus, None, None);
+        ternary.targets.push("c".to_string());
+
+        // THEN both are rejected
+        let zero = Closure::new(
+            &edge("depends_on", TransitiveKind::Plus, None, Some(0)),
+            &registry,
+        );
+        assert!(zero
+            .unwrap_err()
+            .to_string()
+            .contains("Depth limit must be positive"));
+        let err = Closure::new(&ternary, &registry).unwrap_err();
+        assert!(err.to_string().contains("require binary edges"));
+    }
+}
@@ -2,6 +2,7 @@ use crate::completions::{get_ontology_completions, get_statement_completions, Co
 use crate::graph_export::{export_full_graph, export_nodes_by_ids, get_neighbors, GraphData};
 use crate::session::SessionManager;
 use mew_parser::{parse_ontology, parse_stmt};
+use mew_session::{WireError, WireMessage};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
@@ -23,21 +24,6 @@ pub struct ParseError {
     pub column: u32,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct ExecuteResult {
-    pub success: bool,
-    pub result_type: String,
-    pub columns: Option<Vec<String>>,
-    pub rows: Option<Vec<Vec<serde_json::Value>>>,
-    pub nodes_created: Option<usize>,
-    pub nodes_modified: Option<usize>,
-    pub nodes_deleted: Option<usize>,
-    pub edges_created: Option<usize>,
-    pub edges_modified: Option<usize>,
-    pub edges_deleted: Option<usize>,
-    pub error: Option<String>,
-}
-
 #[derive(Serialize, Deserialize)]
 pub struct SchemaData {
     pub types: Vec<TypeInfo>,
@@ -121,7 +107,7 @@ impl Playground {
                     }],
                 },
             },
-            "statement" | _ => match parse_stmt(source) {
+            _ => match parse_stmt(source) {
                 Ok(_) => ParseResult {
                     success: true,
                     errors: vec![],
@@ -162,125 +148,21 @@ impl Playground {
         self.manager.delete_session(session_id)
     }
 
+    /// Execute statements; the result is a wire message (see `mew_session::wire`).
     #[wasm_bindgen]
     pub fn execute(&mut self, session_id: u32, statement: &str) -> JsValue {
-        use mew_session::StatementResult;
         // Use execute_all to support multi-statement input
         let result = self
             .manager
             .with_session(session_id, |session| session.execute_all(statement));
-        let execute_result = match result {
-            None => ExecuteResult {
-                success: false,
-                result_type: "error".to_string(),
-                columns: None,
-                rows: None,
-                nodes_created: None,
-                nodes_modified: None,
-                nodes_deleted: None,
-                edges_created: None,
-                edges_modified: None,
-                edges_deleted: None,
-                error: Some("Session not found".to_string()),
-            },
-            Some(Err(e)) => ExecuteResult {
-                success: false,
-                result_type: "error".to_string(),
-                columns: None,
-                rows: None,
-                nodes_created: None,
-                nodes_modified: None,
-                nodes_deleted: None,
-                edges_created: None,
-                edges_modified: None,
-                edges_deleted: None,
-                error: Some(e.to_string()),
-            },
-            Some(Ok(stmt_result)) => match stmt_result {
-                StatementResult::Query(qr) => {
-                    let rows: Vec<Vec<serde_json::Value>> = qr
-                        .rows
-                        .iter()
-                        .map(|row| row.iter().map(|v| value_to_json(v)).collect())
-                        .collect();
-                    ExecuteResult {
-                        success: true,
-                        result_type: "query".to_string(),
-                        columns: Some(qr.columns),
-                        rows: Some(rows),
-                        nodes_created: None,
-                        nodes_modified: None,
-                        nodes_deleted: None,
-                        edges_created: None,
-                        edges_modified: None,
-                        edges_deleted: None,
-                        error: None,
-                    }
-                }
-                StatementResult::Mutation(mr) => ExecuteResult {
-                    success: true,
-                    result_type: "mutation".to_string(),
-                    columns: None,
-                    rows: None,
-                    nodes_created: Some(mr.nodes_created),
-                    nodes_modified: Some(mr.nodes_modified),
-                    nodes_deleted: Some(mr.nodes_deleted),
-                    edges_created: Some(mr.edges_created),
-                    edges_modified: Some(mr.edges_modified),
-                    edges_deleted: Some(mr.edges_deleted),
-                    error: None,
-                },
-                StatementResult::Transaction(_) => ExecuteResult {
-                    success: true,
-                    result_type: "transaction".to_string(),
-                    columns: None,
-                    rows: None,
-                    nodes_created: None,
-                    nodes_modified: None,
-                    nodes_deleted: None,
-                    edges_created: None,
-                    edges_modified: None,
-                    edges_deleted: None,
-                    error: None,
-                },
-                StatementResult::Mixed { mutations, queries } => {
-                    let rows: Vec<Vec<serde_json::Value>> = queries
-                        .rows
-                        .iter()
-                        .map(|row| row.iter().map(|v| value_to_json(v)).collect())
-                        .collect();
-                    ExecuteResult {
-                        success: true,
-                        result_type: "mixed".to_string(),
-                        columns: Some(queries.columns),
-                        rows: Some(rows),
-                        nodes_created: Some(mutations.nodes_created),
-                        nodes_modified: Some(mutations.nodes_modified),
-                        nodes_deleted: Some(mutations.nodes_deleted),
-                        edges_created: Some(mutations.edges_created),
-                        edges_modified: Some(mutations.edges_modified),
-                        edges_deleted: Some(mutations.edges_deleted),
-                        error: None,
-                    }
-                }
-                StatementResult::Empty => ExecuteResult {
-                    success: true,
-                    result_type: "empty".to_string(),
-                    columns: None,
-                    rows: None,
-                    nodes_created: None,
-                    nodes_modified: None,
-                    nodes_deleted: None,
-                    edges_created: None,
-                    edges_modified: None,
-                    edges_deleted: None,
-                    error: None,
-                },
-            },
+        let message = match result {
+            None => WireMessage::from(WireError::new("session_not_found", "Session not found")),
+            Some(Err(e)) => WireMessage::from(&e),
+            Some(Ok(stmt_result)) => WireMessage::from(stmt_result),
         };
         // Use custom serializer to properly handle nested objects
         let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
-        execute_result.serialize(&serializer).unwrap()
+        message.serialize(&serializer).unwrap()
     }
 
     #[wasm_bindgen]
@@ -437,23 +319,6 @@ impl Default for Playground {
     }
 }
 
-fn value_to_json(v: &mew_core::Value) -> serde_json::Value {
-    match v {
-        mew_core::Value::Null => serde_json::Value::Null,
-        mew_core::Value::Bool(b) => serde_json::Value::Bool(*b),
-        mew_core::Value::Int(i) => serde_json::json!(*i),
-        mew_core::Value::Float(f) => serde_json::json!(*f),
-        mew_core::Value::String(s) => serde_json::Value::String(s.clone()),
-        mew_core::Value::List(items) => {
-            serde_json::Value::Array(items.iter().map(value_to_json).collect())
-        }
-        mew_core::Value::NodeRef(id) => serde_json::json!({ "_type": "node", "_id": id.raw() }),
-        mew_core::Value::EdgeRef(id) => serde_json::json!({ "_type": "edge", "_id": id.raw() }),
-        mew_core::Value::Timestamp(ts) => serde_json::json!({ "_type": "timestamp", "value": *ts }),
-        mew_core::Value::Duration(d) => serde_json::json!({ "_type": "duration", "value": *d }),
-    }
-}
-
 fn build_schema_data(registry: &mew_registry::Registry) -> SchemaData {
     let types: Vec<TypeInfo> = registry
         .all_types()
@@ -93,6 +93,9 @@ pub fn get_statement_completions(prefix: &str) -> Vec<CompletionItem> {
         ("BEGIN", "Start transaction"),
         ("COMMIT", "Commit transaction"),
         ("ROLLBACK", "Rollback transaction"),
+        ("SAVEPOINT", "Mark a point to roll back to"),
+        ("ROLLBACK TO", "Undo changes since a savepoint"),
+        ("RELEASE", "Forget a savepoint"),
     ];
     for (stmt, detail) in statements {
         if stmt.starts_with(&prefix_upper)
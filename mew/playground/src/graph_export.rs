@@ -1,7 +1,9 @@
-use mew_core::{EntityId, Value};
+use mew_core::EntityId;
 use mew_graph::Graph;
 use mew_registry::Registry;
+use mew_session::value_to_json;
 use serde::Serialize;
+use serde_json::Value as Json;
 use std::collections::HashMap;
 
 fn entity_id_to_raw(id: &EntityId) -> u64 {
@@ -22,7 +24,7 @@ pub struct NodeData {
     pub id: u64,
     #[serde(rename = "type")]
     pub type_name: String,
-    pub attrs: HashMap<String, JsonValue>,
+    pub attrs: HashMap<String, Json>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -31,35 +33,7 @@ pub struct EdgeData {
     #[serde(rename = "type")]
     pub type_name: String,
     pub targets: Vec<u64>,
-    pub attrs: HashMap<String, JsonValue>,
-}
-
-#[derive(Debug, Clone, Serialize)]
-#[serde(untagged)]
-pub enum JsonValue {
-    Null,
-    Bool(bool),
-    Int(i64),
-    Float(f64),
-    String(String),
-    List(Vec<JsonValue>),
-}
-
-impl From<&Value> for JsonValue {
-    fn from(v: &Value) -> Self {
-        match v {
-            Value::Null => JsonValue::Null,
-            Value::Bool(b) => JsonValue::Bool(*b),
-            Value::Int(i) => JsonValue::Int(*i),
-            Value::Float(f) => JsonValue::Float(*f),
-            Value::String(s) => JsonValue::String(s.clone()),
-            Value::List(items) => JsonValue::List(items.iter().map(JsonValue::from).collect()),
-            Value::NodeRef(id) => JsonValue::Int(id.raw() as i64),
-            Value::EdgeRef(id) => JsonValue::Int(id.raw() as i64),
-            Value::Timestamp(ts) => JsonValue::Int(*ts),
-            Value::Duration(d) => JsonValue::Int(*d),
-        }
-    }
+    pub attrs: HashMap<String, Json>,
 }
 
 pub fn export_full_graph(graph: &Graph, registry: &Registry) -> GraphData {
@@ -71,10 +45,10 @@ pub fn export_full_graph(graph: &Graph, registry: &Registry) -> GraphData {
                 .get_type(node.type_id)
                 .map(|t| t.name.clone())
                 .unwrap_or_else(|| format!("Unknown<{}>", node.type_id));
-            let attrs: HashMap<String, JsonValue> = node
+            let attrs: HashMap<String, Json> = node
                 .attributes
                 .iter()
-                .map(|(k, v)| (k.clone(), JsonValue::from(v)))
+                .map(|(k, v)| (k.clone(), value_to_json(v)))
                 .collect();
             nodes.push(NodeData {
                 id: id.raw(),
@@ -90,10 +64,10 @@ pub fn export_full_graph(graph: &Graph, registry: &Registry) -> GraphData {
                 .map(|e| e.name.clone())
                 .unwrap_or_else(|| format!("Unknown<{}>", edge.type_id));
             let targets: Vec<u64> = edge.targets.iter().map(entity_id_to_raw).collect();
-            let attrs: HashMap<String, JsonValue> = edge
+            let attrs: HashMap<String, Json> = edge
                 .attributes
                 .iter()
-                .map(|(k, v)| (k.clone(), JsonValue::from(v)))
+                .map(|(k, v)| (k.clone(), value_to_json(v)))
                 .collect();
             edges.push(EdgeData {
                 id: id.raw(),
@@ -118,10 +92,10 @@ pub fn export_nodes_by_ids(graph: &Graph, registry: &Registry, node_ids: &[u64])
                 .get_type(node.type_id)
                 .map(|t| t.name.clone())
                 .unwrap_or_else(|| format!("Unknown<{}>", node.type_id));
-            let attrs: HashMap<String, JsonValue> = node
+            let attrs: HashMap<String, Json> = node
                 .attributes
                 .iter()
-                .map(|(k, v)| (k.clone(), JsonValue::from(v)))
+                .map(|(k, v)| (k.clone(), value_to_json(v)))
                 .collect();
             nodes.push(NodeData {
                 id: raw_id,
@@ -144,10 +118,10 @@ pub fn export_nodes_by_ids(graph: &Graph, registry: &Registry, node_ids: &[u64])
                     .map(|e| e.name.clone())
                     .unwrap_or_else(|| format!("Unknown<{}>", edge.type_id));
                 let targets: Vec<u64> = edge.targets.iter().map(entity_id_to_raw).collect();
-                let attrs: HashMap<String, JsonValue> = edge
+                let attrs: HashMap<String, Json> = edge
                     .attributes
                     .iter()
-                    .map(|(k, v)| (k.clone(), JsonValue::from(v)))
+                    .map(|(k, v)| (k.clone(), value_to_json(v)))
                     .collect();
                 edges.push(EdgeData {
                     id: edge_id.raw(),
@@ -180,10 +154,10 @@ pub fn get_neighbors(graph: &Graph, registry: &Registry, node_ids: &[u64]) -> Gr
                     .get_edge_type(edge.type_id)
                     .map(|e| e.name.clone())
                     .unwrap_or_else(|| format!("Unknown<{}>", edge.type_id));
-                let attrs: HashMap<String, JsonValue> = edge
+                let attrs: HashMap<String, Json> = edge
                     .attributes
                     .iter()
-                    .map(|(k, v)| (k.clone(), JsonValue::from(v)))
+                    .map(|(k, v)| (k.clone(), value_to_json(v)))
                     .collect();
                 relevant_edges.push(EdgeData {
                     id: edge_id.raw(),
@@ -202,10 +176,10 @@ pub fn get_neighbors(graph: &Graph, registry: &Registry, node_ids: &[u64]) -> Gr
                 .get_type(node.type_id)
                 .map(|t| t.name.clone())
                 .unwrap_or_else(|| format!("Unknown<{}>", node.type_id));
-            let attrs: HashMap<String, JsonValue> = node
+            let attrs: HashMap<String, Json> = node
                 .attributes
                 .iter()
-                .map(|(k, v)| (k.clone(), JsonValue::from(v)))
+                .map(|(k, v)| (k.clone(), value_to_json(v)))
                 .collect();
             nodes.push(NodeData {
                 id: raw_id,
@@ -6,6 +6,8 @@ import type {
   CompletionItem,
   Stats,
   CreateSessionResult,
+  WireMessage,
+  WireMutation,
 } from './types';
 import init, { Playground } from '../pkg/mew_playground';
 
@@ -36,8 +38,65 @@ export function deleteSession(sessionId: number): boolean {
   return getPlayground().delete_session(sessionId);
 }
 
+/** Wire encoding version this client understands. */
+const WIRE_VERSION = 1;
+
 export function execute(sessionId: number, statement: string): ExecuteResult {
-  return getPlayground().execute(sessionId, statement) as ExecuteResult;
+  return fromWire(getPlayground().execute(sessionId, statement) as WireMessage);
+}
+
+function mutationCounts(m: WireMutation) {
+  return {
+    nodes_created: m.nodes_created,
+    nodes_modified: m.nodes_modified,
+    nodes_deleted: m.nodes_deleted,
+    edges_created: m.edges_created,
+    edges_modified: m.edges_modified,
+    edges_deleted: m.edges_deleted,
+  };
+}
+
+/** Flatten a wire message into the shape the UI renders. */
+export function fromWire(message: WireMessage): ExecuteResult {
+  if (message.version !== WIRE_VERSION) {
+    return {
+      success: false,
+      result_type: 'error',
+      error: `Unsupported wire version ${message.version}`,
+    };
+  }
+  if (message.error || !message.result) {
+    return {
+      success: false,
+      result_type: 'error',
+      error: message.error?.message ?? 'Unknown error',
+      error_kind: message.error?.kind,
+    };
+  }
+  const result = message.result;
+  switch (result.type) {
+    case 'query':
+      return {
+        success: true,
+        result_type: 'query',
+        columns: result.columns,
+        types: result.types,
+        rows: result.rows,
+      };
+    case 'mutation':
+      return { success: true, result_type: 'mutation', ...mutationCounts(result) };
+    case 'mixed':
+      return {
+        success: true,
+        result_type: 'mixed',
+        columns: result.query.columns,
+        types: result.query.types,
+        rows: result.query.rows,
+        ...mutationCounts(result.mutation),
+      };
+    default:
+      return { success: true, result_type: result.type };
+  }
 }
 
 export function getGraph(sessionId: number): GraphData {
@@ -7,7 +7,7 @@ export const mewLanguage: Monaco.languages.IMonarchLanguage = {
     'ontology', 'node', 'edge', 'constraint', 'rule', 'type',
     'MATCH', 'SPAWN', 'KILL', 'LINK', 'UNLINK', 'SET', 'RETURN',
     'WHERE', 'ORDER', 'BY', 'ASC', 'DESC', 'LIMIT', 'OFFSET',
-    'GROUP', 'HAVING', 'BEGIN', 'COMMIT', 'ROLLBACK', 'INSPECT',
+    'GROUP', 'HAVING', 'BEGIN', 'COMMIT', 'ROLLBACK', 'SAVEPOINT', 'RELEASE', 'INSPECT',
     'WALK', 'FROM', 'TO', 'VIA', 'EXPLAIN', 'PROFILE', 'AS',
     'AND', 'OR', 'NOT', 'IN', 'true', 'false', 'null',
     'on_kill_source', 'on_kill_target', 'cascade', 'restrict', 'delete',
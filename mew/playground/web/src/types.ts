@@ -13,6 +13,7 @@ export interface ExecuteResult {
   success: boolean;
   result_type: 'query' | 'mutation' | 'transaction' | 'mixed' | 'empty' | 'error';
   columns?: string[];
+  types?: string[];
   rows?: unknown[][];
   nodes_created?: number;
   nodes_modified?: number;
@@ -21,6 +22,40 @@ export interface ExecuteResult {
   edges_modified?: number;
   edges_deleted?: number;
   error?: string;
+  error_kind?: string;
+}
+
+/** Mutation counts as encoded on the wire. */
+export interface WireMutation {
+  nodes_created: number;
+  nodes_modified: number;
+  nodes_deleted: number;
+  edges_created: number;
+  edges_modified: number;
+  edges_deleted: number;
+  returned: unknown[];
+}
+
+/** Query result as encoded on the wire. */
+export interface WireQuery {
+  columns: string[];
+  types: string[];
+  rows: unknown[][];
+}
+
+/** Statement result as encoded on the wire (see `mew_session::wire`). */
+export type WireResult =
+  | ({ type: 'query' } & WireQuery)
+  | ({ type: 'mutation' } & WireMutation)
+  | { type: 'mixed'; mutation: WireMutation; query: WireQuery }
+  | { type: 'transaction'; status: string; savepoint?: string }
+  | { type: 'empty' };
+
+/** Versioned wire message returned by `Playground.execute`. */
+export interface WireMessage {
+  version: number;
+  result?: WireResult;
+  error?: { kind: string; message: string };
 }
 
 export interface GraphData {
@@ -1,14 +1,18 @@
 //! Aggregate computation for query execution.
 //!
 //! This module contains the logic for computing aggregate functions
-//! (COUNT, SUM, AVG, MIN, MAX) over grouped result sets.
+//! (COUNT, SUM, AVG, MIN, MAX, COLLECT and the statistical aggregates)
+//! over grouped result sets. Null values are ignored by every aggregate,
+//! and DISTINCT keeps only the first occurrence of each value. Over no
+//! values, whether the group has no rows or only nulls, COUNT is 0, SUM is
+//! 0, COLLECT is an empty list and the other aggregates are null.
 
 use mew_core::Value;
 use mew_graph::Graph;
 use mew_pattern::{Bindings, Evaluator};
 
 use crate::plan::{AggregateKind, AggregateSpec};
-use crate::QueryResult;
+use crate::{QueryError, QueryResult};
 
 /// Compute an aggregate over a group of rows.
 pub fn compute_aggregate(
@@ -17,163 +21,245 @@ pub fn compute_aggregate(
     evaluator: &Evaluator<'_>,
     graph: &Graph,
 ) -> QueryResult<Value> {
+    let values = group_values(agg, group, evaluator, graph)?;
     match agg.kind {
-        AggregateKind::Count => compute_count(agg, group, evaluator, graph),
-        AggregateKind::Sum => compute_sum(agg, group, evaluator, graph),
-        AggregateKind::Avg => compute_avg(agg, group, evaluator, graph),
-        AggregateKind::Min => {
-            compute_min_max(agg, group, evaluator, graph, std::cmp::Ordering::Less)
+        AggregateKind::Count => Ok(Value::Int(values.len() as i64)),
+        AggregateKind::Sum => Ok(compute_sum(values)),
+        AggregateKind::Avg => Ok(compute_avg(values)),
+        AggregateKind::Min => Ok(compute_min_max(values, std::cmp::Ordering::Less)),
+        AggregateKind::Max => Ok(compute_min_max(values, std::cmp::Ordering::Greater)),
+        AggregateKind::Collect => Ok(Value::List(values)),
+        AggregateKind::StdDev => Ok(sample_variance(&numbers("STDDEV", values)?)
+            .map_or(Value::Null, |v| Value::Float(v.sqrt()))),
+        AggregateKind::Variance => {
+            Ok(sample_variance(&numbers("VARIANCE", values)?).map_or(Value::Null, Value::Float))
         }
-        AggregateKind::Max => {
-            compute_min_max(agg, group, evaluator, graph, std::cmp::Ordering::Greater)
+        AggregateKind::Median => Ok(percentile_cont(numbers("MEDIAN", values)?, 0.5)),
+        AggregateKind::PercentileCont => {
+            let fraction = fraction("PERCENTILE_CONT", agg, group, evaluator, graph)?;
+            Ok(percentile_cont(
+                numbers("PERCENTILE_CONT", values)?,
+                fraction,
+            ))
+        }
+        AggregateKind::PercentileDisc => {
+            let fraction = fraction("PERCENTILE_DISC", agg, group, evaluator, graph)?;
+            Ok(percentile_disc(values, fraction))
+        }
+        AggregateKind::StringAgg => {
+            let separator = match param(agg, group, evaluator, graph)? {
+                Value::String(s) => s,
+                Value::Null => String::new(),
+                other => {
+                    return Err(QueryError::aggregate_error(format!(
+                        "STRING_AGG separator must be a string, got {}",
+                        other.type_name()
+                    )))
+                }
+            };
+            compute_string_agg(values, &separator)
         }
-        AggregateKind::Collect => compute_collect(agg, group, evaluator, graph),
     }
 }
 
-/// Compute COUNT aggregate.
-fn compute_count(
+/// Evaluate the aggregated expression over a group, skipping nulls. With
+/// DISTINCT only the first occurrence of each value is kept. Evaluation
+/// errors are reported rather than skipped.
+fn group_values(
     agg: &AggregateSpec,
     group: &[(Bindings, Vec<Value>)],
     evaluator: &Evaluator<'_>,
     graph: &Graph,
-) -> QueryResult<Value> {
-    if agg.distinct {
-        // Count distinct values
-        let mut seen = std::collections::HashSet::new();
-        for (bindings, _) in group {
-            if let Ok(val) = evaluator.eval(&agg.expr, bindings, graph) {
-                seen.insert(format!("{:?}", val));
-            }
+) -> QueryResult<Vec<Value>> {
+    let mut seen = std::collections::HashSet::new();
+    let mut values = Vec::new();
+    for (bindings, _) in group {
+        let val = evaluator.eval(&agg.expr, bindings, graph)?;
+        if matches!(val, Value::Null) {
+            continue;
+        }
+        if !agg.distinct || seen.insert(format!("{:?}", val)) {
+            values.push(val);
         }
-        Ok(Value::Int(seen.len() as i64))
-    } else {
-        Ok(Value::Int(group.len() as i64))
     }
+    Ok(values)
 }
 
-/// Compute SUM aggregate.
-fn compute_sum(
+/// Evaluate the aggregate's second argument against the group's first row.
+fn param(
     agg: &AggregateSpec,
     group: &[(Bindings, Vec<Value>)],
     evaluator: &Evaluator<'_>,
     graph: &Graph,
 ) -> QueryResult<Value> {
+    let Some(expr) = &agg.param else {
+        return Ok(Value::Null);
+    };
+    let bindings = group.first().map(|(b, _)| b.clone()).unwrap_or_default();
+    Ok(evaluator.eval(expr, &bindings, graph)?)
+}
+
+/// The fraction of a percentile aggregate, which must lie in [0, 1].
+fn fraction(
+    name: &str,
+    agg: &AggregateSpec,
+    group: &[(Bindings, Vec<Value>)],
+    evaluator: &Evaluator<'_>,
+    graph: &Graph,
+) -> QueryResult<f64> {
+    let fraction = match param(agg, group, evaluator, graph)? {
+        Value::Int(i) => i as f64,
+        Value::Float(f) => f,
+        _ => -1.0,
+    };
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(fraction)
+    } else {
+        Err(QueryError::aggregate_error(format!(
+            "{} fraction must be a number between 0 and 1",
+            name
+        )))
+    }
+}
+
+/// Convert the values of a numeric aggregate to floats.
+fn numbers(name: &str, values: Vec<Value>) -> QueryResult<Vec<f64>> {
+    values
+        .into_iter()
+        .map(|val| match val {
+            Value::Int(i) => Ok(i as f64),
+            Value::Float(f) => Ok(f),
+            other => Err(QueryError::aggregate_error(format!(
+                "{} requires numeric values, got {}",
+                name,
+                other.type_name()
+            ))),
+        })
+        .collect()
+}
+
+/// Compute SUM aggregate.
+fn compute_sum(values: Vec<Value>) -> Value {
     let mut int_sum = 0i64;
     let mut float_sum = 0.0f64;
     let mut has_float = false;
 
-    for (bindings, _) in group {
-        if let Ok(val) = evaluator.eval(&agg.expr, bindings, graph) {
-            match val {
-                Value::Int(i) => {
-                    if has_float {
-                        float_sum += i as f64;
-                    } else {
-                        int_sum += i;
-                    }
+    for val in values {
+        match val {
+            Value::Int(i) => {
+                if has_float {
+                    float_sum += i as f64;
+                } else {
+                    int_sum += i;
                 }
-                Value::Float(f) => {
-                    if !has_float {
-                        float_sum = int_sum as f64;
-                        has_float = true;
-                    }
-                    float_sum += f;
+            }
+            Value::Float(f) => {
+                if !has_float {
+                    float_sum = int_sum as f64;
+                    has_float = true;
                 }
-                _ => {}
+                float_sum += f;
             }
+            _ => {}
         }
     }
 
     if has_float {
-        Ok(Value::Float(float_sum))
+        Value::Float(float_sum)
     } else {
-        Ok(Value::Int(int_sum))
+        Value::Int(int_sum)
     }
 }
 
 /// Compute AVG aggregate.
-fn compute_avg(
-    agg: &AggregateSpec,
-    group: &[(Bindings, Vec<Value>)],
-    evaluator: &Evaluator<'_>,
-    graph: &Graph,
-) -> QueryResult<Value> {
+fn compute_avg(values: Vec<Value>) -> Value {
     let mut sum = 0.0f64;
     let mut count = 0;
 
-    for (bindings, _) in group {
-        if let Ok(val) = evaluator.eval(&agg.expr, bindings, graph) {
-            match val {
-                Value::Int(i) => {
-                    sum += i as f64;
-                    count += 1;
-                }
-                Value::Float(f) => {
-                    sum += f;
-                    count += 1;
-                }
-                _ => {}
+    for val in values {
+        match val {
+            Value::Int(i) => {
+                sum += i as f64;
+                count += 1;
+            }
+            Value::Float(f) => {
+                sum += f;
+                count += 1;
             }
+            _ => {}
         }
     }
 
     if count == 0 {
-        Ok(Value::Null)
+        Value::Null
     } else {
-        Ok(Value::Float(sum / count as f64))
+        Value::Float(sum / count as f64)
     }
 }
 
 /// Compute MIN or MAX aggregate.
-fn compute_min_max(
-    agg: &AggregateSpec,
-    group: &[(Bindings, Vec<Value>)],
-    evaluator: &Evaluator<'_>,
-    graph: &Graph,
-    target_order: std::cmp::Ordering,
-) -> QueryResult<Value> {
-    let mut result: Option<Value> = None;
-
-    for (bindings, _) in group {
-        if let Ok(val) = evaluator.eval(&agg.expr, bindings, graph) {
-            if !matches!(val, Value::Null) {
-                result = Some(match result {
-                    None => val,
-                    Some(current) => {
-                        if val.cmp_sortable(&current) == target_order {
-                            val
-                        } else {
-                            current
-                        }
-                    }
-                });
+fn compute_min_max(values: Vec<Value>, target_order: std::cmp::Ordering) -> Value {
+    values
+        .into_iter()
+        .reduce(|current, val| {
+            if val.cmp_sortable(&current) == target_order {
+                val
+            } else {
+                current
             }
-        }
-    }
+        })
+        .unwrap_or(Value::Null)
+}
 
-    Ok(result.unwrap_or(Value::Null))
+/// Sample variance; undefined for fewer than two values.
+fn sample_variance(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    Some(values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0))
 }
 
-/// Compute COLLECT aggregate - collects all values into a list.
-fn compute_collect(
-    agg: &AggregateSpec,
-    group: &[(Bindings, Vec<Value>)],
-    evaluator: &Evaluator<'_>,
-    graph: &Graph,
-) -> QueryResult<Value> {
-    let mut result = Vec::new();
+/// Continuous percentile: linear interpolation between the two closest ranks.
+fn percentile_cont(mut values: Vec<f64>, fraction: f64) -> Value {
+    if values.is_empty() {
+        return Value::Null;
+    }
+    values.sort_by(f64::total_cmp);
+    let rank = fraction * (values.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    let weight = rank - lower as f64;
+    Value::Float(values[lower] + (values[upper] - values[lower]) * weight)
+}
 
-    for (bindings, _) in group {
-        if let Ok(val) = evaluator.eval(&agg.expr, bindings, graph) {
-            // Skip null values
-            if !matches!(val, Value::Null) {
-                result.push(val);
-            }
-        }
+/// Discrete percentile: the first value whose cumulative distribution
+/// reaches the fraction.
+fn percentile_disc(mut values: Vec<Value>, fraction: f64) -> Value {
+    if values.is_empty() {
+        return Value::Null;
     }
+    values.sort_by(Value::cmp_sortable);
+    let index = ((fraction * values.len() as f64).ceil() as usize).saturating_sub(1);
+    values.swap_remove(index)
+}
 
-    Ok(Value::List(result))
+/// Compute STRING_AGG aggregate.
+fn compute_string_agg(values: Vec<Value>, separator: &str) -> QueryResult<Value> {
+    if values.is_empty() {
+        return Ok(Value::Null);
+    }
+    let parts = values
+        .into_iter()
+        .map(|val| match val {
+            Value::String(s) => Ok(s),
+            other => Err(QueryError::aggregate_error(format!(
+                "STRING_AGG requires string values, got {}",
+                other.type_name()
+            ))),
+        })
+        .collect::<QueryResult<Vec<_>>>()?;
+    Ok(Value::String(parts.join(separator)))
 }
 
 #[cfg(test)]
@@ -195,6 +281,32 @@ mod tests {
         })
     }
 
+    fn var_expr(name: &str) -> Expr {
+        Expr::Var(name.to_string(), Span::default())
+    }
+
+    /// One row per value, each binding `x`.
+    fn group_of(values: Vec<Value>) -> Vec<(Bindings, Vec<Value>)> {
+        values
+            .into_iter()
+            .map(|value| {
+                let mut bindings = Bindings::new();
+                bindings.insert("x", value);
+                (bindings, vec![])
+            })
+            .collect()
+    }
+
+    fn aggregate_of_x(kind: AggregateKind, distinct: bool, param: Option<Expr>) -> AggregateSpec {
+        AggregateSpec {
+            name: "agg".to_string(),
+            kind,
+            expr: var_expr("x"),
+            distinct,
+            param,
+        }
+    }
+
     #[test]
     fn test_count_empty() {
         // GIVEN
@@ -205,6 +317,7 @@ mod tests {
             kind: AggregateKind::Count,
             expr: make_literal_expr(1),
             distinct: false,
+            param: None,
         };
 
         // WHEN
@@ -224,6 +337,7 @@ mod tests {
             kind: AggregateKind::Count,
             expr: make_literal_expr(1),
             distinct: false,
+            param: None,
         };
 
         let group = vec![
@@ -251,6 +365,7 @@ mod tests {
             kind: AggregateKind::Sum,
             expr: make_literal_expr(5),
             distinct: false,
+            param: None,
         };
 
         let group = vec![
@@ -278,6 +393,7 @@ mod tests {
             kind: AggregateKind::Avg,
             expr: make_literal_expr(6),
             distinct: false,
+            param: None,
         };
 
         let group = vec![
@@ -305,6 +421,7 @@ mod tests {
             kind: AggregateKind::Collect,
             expr: make_literal_expr(7),
             distinct: false,
+            param: None,
         };
 
         let group = vec![
@@ -334,6 +451,7 @@ mod tests {
             kind: AggregateKind::Collect,
             expr: make_literal_expr(1),
             distinct: false,
+            param: None,
         };
 
         // WHEN
@@ -342,4 +460,185 @@ mod tests {
         // THEN
         assert_eq!(result, Value::List(vec![]));
     }
+
+    #[test]
+    fn test_count_skips_nulls_and_duplicates() {
+        // GIVEN x = 1, 1, null, 2
+        let evaluator = test_evaluator();
+        let graph = Graph::new();
+        let group = group_of(vec![
+            Value::Int(1),
+            Value::Int(1),
+            Value::Null,
+            Value::Int(2),
+        ]);
+
+        // WHEN
+        let count = |distinct| {
+            let agg = aggregate_of_x(AggregateKind::Count, distinct, None);
+            compute_aggregate(&agg, &group, &evaluator, &graph).unwrap()
+        };
+
+        // THEN COUNT(x) = 3 and COUNT(DISTINCT x) = 2
+        assert_eq!(count(false), Value::Int(3));
+        assert_eq!(count(true), Value::Int(2));
+    }
+
+    #[test]
+    fn test_statistical_aggregates() {
+        // GIVEN x = 2, 4, 4, 4, 5, 5, 7, 9 and a null
+        let evaluator = test_evaluator();
+        let graph = Graph::new();
+        let mut values: Vec<Value> = [2, 4, 4, 4, 5, 5, 7, 9]
+            .into_iter()
+            .map(Value::Int)
+            .collect();
+        values.push(Value::Null);
+        let group = group_of(values);
+        let fraction = |f: f64| {
+            Some(Expr::Literal(Literal {
+                kind: LiteralKind::Float(f),
+                span: Span::default(),
+            }))
+        };
+
+        // WHEN
+        let compute = |kind, param| {
+            let agg = aggregate_of_x(kind, false, param);
+            compute_aggregate(&agg, &group, &evaluator, &graph).unwrap()
+        };
+
+        // THEN the null is ignored; variance and stddev are sample statistics
+        assert_eq!(
+            compute(AggregateKind::Variance, None),
+            Value::Float(32.0 / 7.0)
+        );
+        assert_eq!(
+            compute(AggregateKind::StdDev, None),
+            Value::Float((32.0f64 / 7.0).sqrt())
+        );
+        assert_eq!(compute(AggregateKind::Median, None), Value::Float(4.5));
+        assert_eq!(
+            compute(AggregateKind::PercentileCont, fraction(0.25)),
+            Value::Float(4.0)
+        );
+        assert_eq!(
+            compute(AggregateKind::PercentileDisc, fraction(0.75)),
+            Value::Int(5)
+        );
+        assert_eq!(
+            compute(AggregateKind::PercentileDisc, fraction(1.0)),
+            Value::Int(9)
+        );
+    }
+
+    #[test]
+    fn test_stddev_of_single_value_is_null() {
+        // GIVEN
+        let evaluator = test_evaluator();
+        let graph = Graph::new();
+        let agg = aggregate_of_x(AggregateKind::StdDev, false, None);
+
+        // WHEN
+        let result = compute_aggregate(&agg, &group_of(vec![Value::Int(3)]), &evaluator, &graph);
+
+        // THEN
+        assert_eq!(result.unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_string_agg_distinct() {
+        // GIVEN x = "a", null, "b", "a"
+        let evaluator = test_evaluator();
+        let graph = Graph::new();
+        let group = group_of(vec![
+            Value::from("a"),
+            Value::Null,
+            Value::from("b"),
+            Value::from("a"),
+        ]);
+        let separator = Some(Expr::Literal(Literal {
+            kind: LiteralKind::String(", ".to_string()),
+            span: Span::default(),
+        }));
+        let agg = aggregate_of_x(AggregateKind::StringAgg, true, separator);
+
+        // WHEN
+        let result = compute_aggregate(&agg, &group, &evaluator, &graph).unwrap();
+
+        // THEN values keep their first-seen order
+        assert_eq!(result, Value::from("a, b"));
+    }
+
+    #[test]
+    fn test_percentile_fraction_out_of_range() {
+        // GIVEN
+        let evaluator = test_evaluator();
+        let graph = Graph::new();
+        let agg = aggregate_of_x(
+            AggregateKind::PercentileCont,
+            false,
+            Some(make_literal_expr(2)),
+        );
+
+        // WHEN
+        let result = compute_aggregate(&agg, &group_of(vec![Value::Int(1)]), &evaluator, &graph);
+
+        // THEN
+        assert!(matches!(result, Err(QueryError::AggregateError { .. })));
+    }
+
+    #[test]
+    fn test_no_rows_and_only_nulls_aggregate_alike() {
+        // GIVEN an empty group and a group of nulls
+        let evaluator = test_evaluator();
+        let graph = Graph::new();
+        let nulls = group_of(vec![Value::Null, Value::Null]);
+        let half = Expr::Literal(Literal {
+            kind: LiteralKind::Float(0.5),
+            span: Span::default(),
+        });
+        let separator = Expr::Literal(Literal {
+            kind: LiteralKind::String(", ".to_string()),
+            span: Span::default(),
+        });
+
+        for (kind, param, expected) in [
+            (AggregateKind::Count, None, Value::Int(0)),
+            (AggregateKind::Sum, None, Value::Int(0)),
+            (AggregateKind::Collect, None, Value::List(Vec::new())),
+            (AggregateKind::Avg, None, Value::Null),
+            (AggregateKind::Min, None, Value::Null),
+            (AggregateKind::Max, None, Value::Null),
+            (AggregateKind::StdDev, None, Value::Null),
+            (AggregateKind::Median, None, Value::Null),
+            (AggregateKind::PercentileDisc, Some(half), Value::Null),
+            (AggregateKind::StringAgg, Some(separator), Value::Null),
+        ] {
+            let agg = aggregate_of_x(kind, false, param);
+
+            // WHEN
+            let empty = compute_aggregate(&agg, &[], &evaluator, &graph).unwrap();
+            let all_null = compute_aggregate(&agg, &nulls, &evaluator, &graph).unwrap();
+
+            // THEN both give the aggregate's value over no values
+            assert_eq!(empty, expected, "{:?} over no rows", kind);
+            assert_eq!(all_null, expected, "{:?} over nulls", kind);
+        }
+    }
+
+    #[test]
+    fn test_evaluation_errors_are_reported() {
+        // GIVEN an aggregate over an unbound variable
+        let evaluator = test_evaluator();
+        let graph = Graph::new();
+        let mut agg = aggregate_of_x(AggregateKind::Sum, false, None);
+        agg.expr = var_expr("y");
+
+        // WHEN
+        let result = compute_aggregate(&agg, &group_of(vec![Value::Int(1)]), &evaluator, &graph);
+
+        // THEN
+        assert!(matches!(result, Err(QueryError::PatternError { .. })));
+    }
 }
@@ -2,7 +2,7 @@
 
 use mew_core::Value;
 use mew_graph::Graph;
-use mew_parser::{MatchStmt, WalkStmt};
+use mew_parser::{MatchStmt, UnionStmt, WalkStmt};
 use mew_pattern::{Bindings, Evaluator, Matcher};
 use mew_registry::Registry;
 
@@ -117,6 +117,23 @@ impl<'r, 'g> QueryExecutor<'r, 'g> {
         self.execute_plan(&plan, Some(initial_bindings))
     }
 
+    /// Execute a UNION of MATCH statements.
+    pub fn execute_union(&self, stmt: &UnionStmt) -> QueryResult<QueryResults> {
+        self.execute_union_with_bindings(stmt, None)
+    }
+
+    /// Execute a UNION of MATCH statements with initial bindings (parameters).
+    pub fn execute_union_with_bindings(
+        &self,
+        stmt: &UnionStmt,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<QueryResults> {
+        let planner = QueryPlanner::new(self.registry);
+        let plan = planner.plan_union(stmt)?;
+
+        self.execute_plan(&plan, initial_bindings)
+    }
+
     /// Execute a query plan.
     pub fn execute_plan(
         &self,
@@ -221,6 +238,8 @@ mod tests {
             })],
             where_clause: None,
             optional_matches: vec![],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![Projection {
@@ -230,6 +249,8 @@ mod tests {
                 }],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: None,
             limit: None,
             offset: None,
@@ -265,6 +286,8 @@ mod tests {
             })],
             where_clause: None,
             optional_matches: vec![],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![Projection {
@@ -274,6 +297,8 @@ mod tests {
                 }],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: None,
             limit: Some(5),
             offset: None,
@@ -324,6 +349,8 @@ mod tests {
                 Span::default(),
             )),
             optional_matches: vec![],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![Projection {
@@ -333,6 +360,8 @@ mod tests {
                 }],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: None,
             limit: None,
             offset: None,
@@ -368,6 +397,8 @@ mod tests {
             })],
             where_clause: None,
             optional_matches: vec![],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![Projection {
@@ -381,6 +412,8 @@ mod tests {
                 }],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: Some(vec![mew_parser::OrderTerm {
                 expr: mew_parser::Expr::AttrAccess(
                     Box::new(mew_parser::Expr::Var("t".to_string(), Span::default())),
@@ -427,6 +460,178 @@ mod tests {
         builder.build().unwrap()
     }
 
+    #[test]
+    fn test_execute_grouping_keeps_projection_order() {
+        // GIVEN tasks with priorities 1, 2, 2 and 3
+        let registry = test_registry();
+        let mut graph = Graph::new();
+        let task_type_id = registry.get_type_id("Task").unwrap();
+        for (title, priority) in [("A", 1), ("B", 2), ("C", 2), ("D", 3)] {
+            graph.create_node(
+                task_type_id,
+                attrs! { "title" => title, "priority" => priority },
+            );
+        }
+        let executor = QueryExecutor::new(&registry, &graph);
+        let query = |input: &str| match mew_parser::parse_stmt(input).unwrap() {
+            mew_parser::Stmt::Match(stmt) => executor.execute_match(&stmt).unwrap(),
+            _ => panic!("Expected MATCH"),
+        };
+
+        // WHEN the aggregate comes before the group key, with HAVING and ORDER BY on it
+        let results = query(
+            "MATCH t: Task RETURN count(t) AS n, t.priority GROUP BY t.priority \
+             HAVING n < 2 ORDER BY t.priority DESC",
+        );
+
+        // THEN each column holds its own projection, in sorted order
+        let rows: Vec<_> = results.iter().map(|r| r.values().to_vec()).collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::Int(1), Value::Int(3)],
+                vec![Value::Int(1), Value::Int(1)]
+            ]
+        );
+
+        // WHEN a WITH stage aggregates and the next stage filters on it
+        let results =
+            query("MATCH t: Task WITH t.priority AS p, count(t) AS n WHERE n > 1 RETURN p, n");
+
+        // THEN only the priority shared by two tasks remains
+        assert_eq!(results.len(), 1);
+        assert_eq!(results.rows()[0].get_by_name("p"), Some(&Value::Int(2)));
+        assert_eq!(results.rows()[0].get_by_name("n"), Some(&Value::Int(2)));
+    }
+
+    #[test]
+    fn test_execute_aggregates_over_no_rows_match_all_null_rows() {
+        // GIVEN tasks without a priority
+        let registry = test_registry();
+        let mut graph = Graph::new();
+        let task_type_id = registry.get_type_id("Task").unwrap();
+        graph.create_node(task_type_id, attrs! { "title" => "A" });
+        let executor = QueryExecutor::new(&registry, &graph);
+        let query = |input: &str| match mew_parser::parse_stmt(input).unwrap() {
+            mew_parser::Stmt::Match(stmt) => executor.execute_match(&stmt).unwrap(),
+            _ => panic!("Expected MATCH"),
+        };
+        let aggregates = "RETURN count(t.priority), sum(t.priority), avg(t.priority), \
+                          collect(t.priority)";
+
+        // WHEN aggregating over no rows and over rows whose values are all null
+        let empty = query(&format!(
+            "MATCH t: Task WHERE t.title = \"none\" {}",
+            aggregates
+        ));
+        let all_null = query(&format!("MATCH t: Task {}", aggregates));
+
+        // THEN both give the same single row
+        let expected = vec![
+            Value::Int(0),
+            Value::Int(0),
+            Value::Null,
+            Value::List(Vec::new()),
+        ];
+        assert_eq!(empty.rows()[0].values(), expected.as_slice());
+        assert_eq!(all_null.rows()[0].values(), expected.as_slice());
+    }
+
+    #[test]
+    fn test_execute_union() {
+        // GIVEN tasks with priorities 1, 2, 2 and 3
+        let registry = test_registry();
+        let mut graph = Graph::new();
+        let task_type_id = registry.get_type_id("Task").unwrap();
+        for (title, priority) in [("A", 1), ("B", 2), ("C", 2), ("D", 3)] {
+            graph.create_node(
+                task_type_id,
+                attrs! { "title" => title, "priority" => priority },
+            );
+        }
+        let executor = QueryExecutor::new(&registry, &graph);
+        let query = |input: &str| match mew_parser::parse_stmt(input).unwrap() {
+            mew_parser::Stmt::Union(stmt) => executor.execute_union(&stmt).unwrap(),
+            _ => panic!("Expected UNION"),
+        };
+        let column = |results: &QueryResults| -> Vec<Value> {
+            results.iter().map(|r| r.values()[0].clone()).collect()
+        };
+
+        // WHEN overlapping branches are combined with UNION ALL
+        let results = query(
+            "MATCH t: Task WHERE t.priority = 2 RETURN t.priority \
+             UNION ALL MATCH t: Task WHERE t.priority >= 2 RETURN t.priority \
+             ORDER BY t.priority",
+        );
+
+        // THEN every row of both branches is kept, in the union's order
+        assert_eq!(results.column_names(), &["t.priority".to_string()]);
+        assert_eq!(column(&results), [2, 2, 2, 2, 3].map(Value::Int).to_vec());
+
+        // WHEN the same branches are combined with UNION, ordered and limited
+        let results = query(
+            "MATCH t: Task WHERE t.priority = 2 RETURN t.priority AS p \
+             UNION MATCH t: Task WHERE t.priority >= 2 RETURN t.priority \
+             ORDER BY p DESC LIMIT 1",
+        );
+
+        // THEN duplicates are removed before ORDER BY and LIMIT apply
+        assert_eq!(column(&results), vec![Value::Int(3)]);
+
+        // AND ORDER BY cannot reach past the result columns
+        let stmt = mew_parser::parse_stmt(
+            "MATCH t: Task RETURN t.title UNION MATCH t: Task RETURN t.title ORDER BY t.priority",
+        )
+        .unwrap();
+        let mew_parser::Stmt::Union(stmt) = stmt else {
+            panic!("Expected UNION");
+        };
+        assert!(executor
+            .execute_union(&stmt)
+            .unwrap_err()
+            .to_string()
+            .contains("only use its result columns"));
+    }
+
+    #[test]
+    fn test_execute_pattern_count_per_row() {
+        // GIVEN Alice knows Bob and Carol, and Bob knows Carol
+        let registry = walk_test_registry();
+        let mut graph = Graph::new();
+        let person_type_id = registry.get_type_id("Person").unwrap();
+        let knows_type_id = registry.get_edge_type_id("knows").unwrap();
+        let alice = graph.create_node(person_type_id, attrs! { "name" => "Alice" });
+        let bob = graph.create_node(person_type_id, attrs! { "name" => "Bob" });
+        let carol = graph.create_node(person_type_id, attrs! { "name" => "Carol" });
+        for (from, to) in [(alice, bob), (alice, carol), (bob, carol)] {
+            let _ = graph.create_edge(knows_type_id, vec![from.into(), to.into()], attrs! {});
+        }
+        let executor = QueryExecutor::new(&registry, &graph);
+        let query = |input: &str| match mew_parser::parse_stmt(input).unwrap() {
+            mew_parser::Stmt::Match(stmt) => executor.execute_match(&stmt).unwrap(),
+            _ => panic!("Expected MATCH"),
+        };
+
+        // WHEN counting each person's acquaintances in RETURN and in WHERE
+        let counts = query(
+            "MATCH p: Person RETURN p.name, count(f: Person, knows(p, f)) AS n ORDER BY p.name",
+        );
+        let busy = query("MATCH p: Person WHERE count(f: Person, knows(p, f)) > 1 RETURN p.name");
+
+        // THEN each row counts the matches for its own person
+        let counts: Vec<_> = counts
+            .iter()
+            .map(|r| r.get_by_name("n").cloned().unwrap())
+            .collect();
+        assert_eq!(counts, vec![Value::Int(2), Value::Int(1), Value::Int(0)]);
+        assert_eq!(busy.len(), 1);
+        assert_eq!(
+            busy.rows()[0].get_by_name("p.name"),
+            Some(&Value::String("Alice".to_string()))
+        );
+    }
+
     #[test]
     fn test_execute_walk_simple_chain() {
         // GIVEN - A -> B -> C chain
@@ -454,6 +659,7 @@ mod tests {
                 max_depth: Some(3),
                 span: Span::default(),
             }],
+            shortest: None,
             until: None,
             return_type: mew_parser::WalkReturnType::Path { alias: None },
             span: Span::default(),
@@ -498,6 +704,7 @@ mod tests {
                 max_depth: Some(3),
                 span: Span::default(),
             }],
+            shortest: None,
             until: None,
             return_type: mew_parser::WalkReturnType::Path { alias: None },
             span: Span::default(),
@@ -542,6 +749,7 @@ mod tests {
                 max_depth: Some(10), // Should not infinite loop
                 span: Span::default(),
             }],
+            shortest: None,
             until: None,
             return_type: mew_parser::WalkReturnType::Path { alias: None },
             span: Span::default(),
@@ -631,12 +839,16 @@ mod tests {
                         targets: vec!["p".to_string(), "pet".to_string()],
                         alias: None,
                         transitive: None,
+                        min_depth: None,
+                        max_depth: None,
                         span: Span::default(),
                     }),
                 ],
                 where_clause: None,
                 span: Span::default(),
             }],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![
@@ -661,6 +873,8 @@ mod tests {
                 ],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: None,
             limit: None,
             offset: None,
@@ -713,12 +927,16 @@ mod tests {
                         targets: vec!["p".to_string(), "pet".to_string()],
                         alias: None,
                         transitive: None,
+                        min_depth: None,
+                        max_depth: None,
                         span: Span::default(),
                     }),
                 ],
                 where_clause: None,
                 span: Span::default(),
             }],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![
@@ -743,6 +961,8 @@ mod tests {
                 ],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: None,
             limit: None,
             offset: None,
@@ -815,6 +1035,7 @@ mod tests {
                     max_depth: Some(3),
                     span: Span::default(),
                 }],
+                shortest: None,
                 until: None,
                 return_type: mew_parser::WalkReturnType::Nodes { alias: None },
                 span: Span::default(),
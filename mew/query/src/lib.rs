@@ -12,6 +12,7 @@ mod aggregates;
 mod error;
 mod executor;
 mod operators;
+mod paths;
 mod plan;
 mod result;
 
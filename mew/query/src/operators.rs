@@ -3,15 +3,19 @@
 //! This module contains the execution logic for each query plan operator.
 //! Operators transform and filter binding sets during query execution.
 
-use mew_core::{EdgeTypeId, Value};
+use mew_core::{EdgeTypeId, Point, Value, VectorMetric};
 use mew_graph::Graph;
-use mew_parser::Expr;
-use mew_pattern::{Bindings, Evaluator};
+use mew_parser::{Expr, ShortestPaths};
+use mew_pattern::{Bindings, Closure, ClosureEnd, Evaluator};
 use mew_registry::Registry;
 
 use crate::aggregates::compute_aggregate;
-use crate::plan::{PlanOp, WalkDirection};
-use crate::QueryResult;
+use crate::paths::PathSearch;
+use crate::plan::{PlanOp, SpatialRegion, WalkDirection};
+use crate::{QueryError, QueryResult};
+
+/// Depth limit of a WALK without one.
+const DEFAULT_MAX_DEPTH: i64 = 100;
 
 /// Operator executor context.
 ///
@@ -50,6 +54,40 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
                 value,
             } => self.execute_index_scan(var, *type_id, attr, value, initial_bindings),
 
+            PlanOp::VectorScan {
+                var,
+                type_id,
+                attr,
+                query,
+                k,
+                metric,
+            } => {
+                self.execute_vector_scan(var, *type_id, attr, query, *k, *metric, initial_bindings)
+            }
+
+            PlanOp::SpatialScan {
+                var,
+                type_id,
+                attr,
+                region,
+            } => self.execute_spatial_scan(var, *type_id, attr, region, initial_bindings),
+
+            PlanOp::TextScan {
+                var,
+                type_id,
+                attr,
+                query,
+            } => self.execute_text_scan(var, *type_id, attr, query, initial_bindings),
+
+            PlanOp::Nearest {
+                input,
+                var,
+                attr,
+                query,
+                k,
+                metric,
+            } => self.execute_nearest(input, var, attr, query, *k, *metric, initial_bindings),
+
             PlanOp::EdgeJoin {
                 input,
                 edge_type_id,
@@ -59,6 +97,15 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
                 self.execute_edge_join(input, *edge_type_id, from_vars, edge_var, initial_bindings)
             }
 
+            PlanOp::TransitiveJoin {
+                input,
+                closure,
+                from,
+                to,
+            } => {
+                self.execute_transitive_join(input.as_deref(), closure, from, to, initial_bindings)
+            }
+
             PlanOp::Filter { input, condition } => {
                 self.execute_filter(input, condition, initial_bindings)
             }
@@ -114,8 +161,50 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
                 initial_bindings,
             ),
 
+            PlanOp::ShortestPaths {
+                start_expr,
+                target_expr,
+                edge_types,
+                min_depth,
+                max_depth,
+                direction,
+                weight,
+                paths,
+            } => {
+                let search = PathSearch::new(
+                    self.graph,
+                    edge_types,
+                    *direction,
+                    weight.as_deref(),
+                    *min_depth,
+                    max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+                );
+                self.execute_shortest_paths(
+                    &search,
+                    start_expr,
+                    target_expr,
+                    *paths,
+                    weight.is_some(),
+                    initial_bindings,
+                )
+            }
+
             PlanOp::Distinct { input } => self.execute_distinct(input, initial_bindings),
 
+            PlanOp::Union { inputs } => {
+                let mut results = Vec::new();
+                for input in inputs {
+                    results.extend(self.execute_op(input, initial_bindings)?);
+                }
+                Ok(results)
+            }
+
+            PlanOp::Rebind { input, columns } => {
+                self.execute_rebind(input, columns, initial_bindings)
+            }
+
+            PlanOp::Apply { input, inner } => self.execute_apply(input, inner, initial_bindings),
+
             PlanOp::EdgeDedup { input, edge_var } => {
                 self.execute_edge_dedup(input, edge_var, initial_bindings)
             }
@@ -216,6 +305,255 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
         Ok(results)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn execute_vector_scan(
+        &self,
+        var: &str,
+        type_id: mew_core::TypeId,
+        attr: &str,
+        query: &Expr,
+        k: usize,
+        metric: VectorMetric,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Vec<(Bindings, Vec<Value>)>> {
+        let query_vec = self.eval_query_vector(query, initial_bindings)?;
+        let Some(query_vec) = query_vec else {
+            return Ok(Vec::new());
+        };
+        self.check_query_dimension(type_id, attr, &query_vec)?;
+
+        // Collect the k nearest of each type (including subtypes), then re-rank together
+        let mut type_ids = vec![type_id];
+        type_ids.extend(self.registry.get_subtypes(type_id));
+
+        let mut nearest: Vec<(mew_core::NodeId, f64)> = type_ids
+            .iter()
+            .flat_map(|tid| self.graph.nearest_nodes(*tid, attr, &query_vec, k, metric))
+            .collect();
+        nearest.sort_by(|(a_id, a), (b_id, b)| metric.cmp_scores(*a, *b).then(a_id.cmp(b_id)));
+        nearest.truncate(k);
+
+        let mut results = Vec::new();
+        for (node_id, _) in nearest {
+            let mut bindings = initial_bindings.cloned().unwrap_or_default();
+            if let Some(existing) = bindings.get(var) {
+                if existing.as_node() != Some(node_id) {
+                    continue;
+                }
+            }
+            bindings.insert(var, mew_pattern::Binding::Node(node_id));
+            results.push((bindings, Vec::new()));
+        }
+
+        Ok(results)
+    }
+
+    fn execute_spatial_scan(
+        &self,
+        var: &str,
+        type_id: mew_core::TypeId,
+        attr: &str,
+        region: &SpatialRegion,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Vec<(Bindings, Vec<Value>)>> {
+        let bindings = initial_bindings.cloned().unwrap_or_default();
+        let eval_point = |expr: &Expr| -> QueryResult<Option<Point>> {
+            let value = self.evaluator.eval(expr, &bindings, self.graph)?;
+            if value.is_null() {
+                return Ok(None);
+            }
+            value.to_point().map(Some).ok_or_else(|| {
+                QueryError::type_error(format!("expected a point, got {}", value.type_name()))
+            })
+        };
+
+        // Null region bounds match nothing, exactly as the WHERE predicate would
+        let mut type_ids = vec![type_id];
+        type_ids.extend(self.registry.get_subtypes(type_id));
+        let node_ids: Vec<mew_core::NodeId> = match region {
+            SpatialRegion::Box { corner_a, corner_b } => {
+                let (Some(a), Some(b)) = (eval_point(corner_a)?, eval_point(corner_b)?) else {
+                    return Ok(Vec::new());
+                };
+                type_ids
+                    .iter()
+                    .flat_map(|tid| self.graph.nodes_within_box(*tid, attr, &a, &b))
+                    .collect()
+            }
+            SpatialRegion::Radius { center, radius } => {
+                let Some(center) = eval_point(center)? else {
+                    return Ok(Vec::new());
+                };
+                let radius = match self.evaluator.eval(radius, &bindings, self.graph)? {
+                    Value::Null => return Ok(Vec::new()),
+                    Value::Int(i) => i as f64,
+                    Value::Float(f) => f,
+                    other => {
+                        return Err(QueryError::type_error(format!(
+                            "expected a numeric radius, got {}",
+                            other.type_name()
+                        )))
+                    }
+                };
+                type_ids
+                    .iter()
+                    .flat_map(|tid| self.graph.nodes_within_radius(*tid, attr, &center, radius))
+                    .collect()
+            }
+        };
+
+        let mut results = Vec::new();
+        for node_id in node_ids {
+            let mut row = bindings.clone();
+            if let Some(existing) = row.get(var) {
+                if existing.as_node() != Some(node_id) {
+                    continue;
+                }
+            }
+            row.insert(var, mew_pattern::Binding::Node(node_id));
+            results.push((row, Vec::new()));
+        }
+
+        Ok(results)
+    }
+
+    fn execute_text_scan(
+        &self,
+        var: &str,
+        type_id: mew_core::TypeId,
+        attr: &str,
+        query: &Expr,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Vec<(Bindings, Vec<Value>)>> {
+        let bindings = initial_bindings.cloned().unwrap_or_default();
+        let query = match self.evaluator.eval(query, &bindings, self.graph)? {
+            // A null query matches nothing, exactly as the WHERE predicate would
+            Value::Null => return Ok(Vec::new()),
+            Value::String(query) => query,
+            other => {
+                return Err(QueryError::type_error(format!(
+                    "expected a search query string, got {}",
+                    other.type_name()
+                )))
+            }
+        };
+
+        // Types without a text index are scanned; the WHERE filter re-checks them
+        let mut type_ids = vec![type_id];
+        type_ids.extend(self.registry.get_subtypes(type_id));
+        let mut node_ids = Vec::new();
+        for tid in type_ids {
+            match self.graph.search_nodes(tid, attr, &query) {
+                Some(hits) => node_ids.extend(hits.into_iter().map(|(node_id, _)| node_id)),
+                None => node_ids.extend(self.graph.nodes_by_type(tid)),
+            }
+        }
+
+        let mut results = Vec::new();
+        for node_id in node_ids {
+            let mut row = bindings.clone();
+            if let Some(existing) = row.get(var) {
+                if existing.as_node() != Some(node_id) {
+                    continue;
+                }
+            }
+            row.insert(var, mew_pattern::Binding::Node(node_id));
+            results.push((row, Vec::new()));
+        }
+
+        Ok(results)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn execute_nearest(
+        &self,
+        input: &PlanOp,
+        var: &str,
+        attr: &str,
+        query: &Expr,
+        k: usize,
+        metric: VectorMetric,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Vec<(Bindings, Vec<Value>)>> {
+        let query_vec = self.eval_query_vector(query, initial_bindings)?;
+        let Some(query_vec) = query_vec else {
+            return Ok(Vec::new());
+        };
+
+        let input_results = self.execute_op(input, initial_bindings)?;
+        let mut scored: Vec<(f64, (Bindings, Vec<Value>))> = Vec::new();
+        for row in input_results {
+            let Some(node) = row.0.get(var).and_then(|b| b.as_node()) else {
+                continue;
+            };
+            let Some(node) = self.graph.get_node(node) else {
+                continue;
+            };
+            self.check_query_dimension(node.type_id, attr, &query_vec)?;
+            let score = node
+                .get_attr(attr)
+                .and_then(|v| v.as_vector())
+                .and_then(|vector| metric.score(vector, &query_vec));
+            if let Some(score) = score {
+                scored.push((score, row));
+            }
+        }
+
+        // Stable sort keeps the input order among equal scores
+        scored.sort_by(|(a, _), (b, _)| metric.cmp_scores(*a, *b));
+        Ok(scored.into_iter().take(k).map(|(_, row)| row).collect())
+    }
+
+    /// Check that a query vector has the dimension `attr` declares on the
+    /// type. A bare `Vector` attribute accepts any dimension.
+    fn check_query_dimension(
+        &self,
+        type_id: mew_core::TypeId,
+        attr: &str,
+        query_vec: &[f32],
+    ) -> QueryResult<()> {
+        let Some(attr_def) = self.registry.get_type_attr(type_id, attr) else {
+            return Ok(());
+        };
+        let declared = attr_def
+            .type_name
+            .strip_prefix("Vector[")
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|dim| dim.parse::<usize>().ok());
+        match declared {
+            Some(dim) if dim != query_vec.len() => {
+                let type_name = self.registry.get_type(type_id).map_or("", |t| &t.name);
+                Err(QueryError::type_error(format!(
+                    "NEAREST expects a vector of dimension {} for {}.{}, got dimension {}",
+                    dim,
+                    type_name,
+                    attr,
+                    query_vec.len()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Evaluate the query vector of a nearest-neighbour search. Null yields None.
+    fn eval_query_vector(
+        &self,
+        query: &Expr,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Option<Vec<f32>>> {
+        let bindings = initial_bindings.cloned().unwrap_or_default();
+        let value = self.evaluator.eval(query, &bindings, self.graph)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+        value.to_vector().map(Some).ok_or_else(|| {
+            QueryError::type_error(format!(
+                "NEAREST expects a vector, got {}",
+                value.type_name()
+            ))
+        })
+    }
+
     fn execute_edge_join(
         &self,
         input: &PlanOp,
@@ -350,6 +688,60 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
         Ok(results)
     }
 
+    fn execute_transitive_join(
+        &self,
+        input: Option<&PlanOp>,
+        closure: &Closure,
+        from: &str,
+        to: &str,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Vec<(Bindings, Vec<Value>)>> {
+        let input_results = match input {
+            Some(input) => self.execute_op(input, initial_bindings)?,
+            None => vec![(initial_bindings.cloned().unwrap_or_default(), Vec::new())],
+        };
+        let mut results = Vec::new();
+        // Rows from a cross join share their start nodes; search each once
+        let mut reached_from: std::collections::HashMap<
+            mew_core::NodeId,
+            std::collections::HashSet<mew_core::NodeId>,
+        > = std::collections::HashMap::new();
+
+        for (bindings, _) in input_results {
+            let from_end = ClosureEnd::of(&bindings, from)?;
+            let to_end = ClosureEnd::of(&bindings, to)?;
+            let pairs = match (from_end, to_end) {
+                (ClosureEnd::Bound(from_id), ClosureEnd::Bound(to_id)) => {
+                    let reached = reached_from.entry(from_id).or_insert_with(|| {
+                        closure
+                            .reach(self.graph, from_id, true)
+                            .into_iter()
+                            .map(|(node, _)| node)
+                            .collect()
+                    });
+                    if reached.contains(&to_id) {
+                        vec![(from_id, to_id)]
+                    } else {
+                        Vec::new()
+                    }
+                }
+                _ => closure.pairs(self.registry, self.graph, from_end, to_end, from == to),
+            };
+            for (from_id, to_id) in pairs {
+                let mut new_bindings = bindings.clone();
+                if from_end == ClosureEnd::Free {
+                    new_bindings.insert(from, mew_pattern::Binding::Node(from_id));
+                }
+                if to_end == ClosureEnd::Free {
+                    new_bindings.insert(to, mew_pattern::Binding::Node(to_id));
+                }
+                results.push((new_bindings, Vec::new()));
+            }
+        }
+
+        Ok(results)
+    }
+
     fn execute_filter(
         &self,
         input: &PlanOp,
@@ -443,52 +835,88 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
         let results = self.execute_op(input, initial_bindings)?;
 
         if group_by.is_empty() && results.is_empty() {
-            // Empty input with no grouping returns single row with defaults
-            let values: Vec<Value> = aggregates
-                .iter()
-                .map(|agg| match agg.kind {
-                    crate::plan::AggregateKind::Count => Value::Int(0),
-                    _ => Value::Null,
-                })
-                .collect();
-            return Ok(vec![(Bindings::new(), values)]);
+            // Empty input with no grouping returns a single row, aggregated
+            // over no values exactly like a group of nulls
+            let mut bindings = initial_bindings.cloned().unwrap_or_default();
+            for agg in aggregates {
+                let value = compute_aggregate(agg, &[], self.evaluator, self.graph)?;
+                bindings.insert(&agg.name, mew_pattern::Binding::from(value));
+            }
+            return Ok(vec![(bindings, Vec::new())]);
         }
 
-        // Group rows by key (using string serialization since Value doesn't impl Hash)
-        let mut groups: std::collections::HashMap<String, Vec<(Bindings, Vec<Value>)>> =
+        // Group rows by key (using string serialization since Value doesn't impl Hash),
+        // keeping groups in the order their first row arrives
+        let mut groups: Vec<Vec<(Bindings, Vec<Value>)>> = Vec::new();
+        let mut group_index: std::collections::HashMap<String, usize> =
             std::collections::HashMap::new();
 
         for (bindings, values) in results {
             let key = compute_group_key(group_by, &bindings, self.evaluator, self.graph);
-            groups.entry(key).or_default().push((bindings, values));
+            let idx = *group_index.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[idx].push((bindings, values));
         }
 
-        // Compute output for each group
+        // Each group's row keeps its first row's bindings, which agree on the
+        // group keys, and binds every aggregate by name
         let mut output = Vec::new();
-        for group in groups.into_values() {
-            let first_bindings = group.first().map(|(b, _)| b.clone()).unwrap_or_default();
-
-            // Collect group_by values then aggregate values
-            let mut row_values: Vec<Value> = group_by
-                .iter()
-                .map(|expr| {
-                    self.evaluator
-                        .eval(expr, &first_bindings, self.graph)
-                        .unwrap_or(Value::Null)
-                })
-                .collect();
-
+        for group in groups {
+            let mut bindings = group.first().map(|(b, _)| b.clone()).unwrap_or_default();
             for agg in aggregates {
                 let val = compute_aggregate(agg, &group, self.evaluator, self.graph)?;
-                row_values.push(val);
+                bindings.insert(&agg.name, mew_pattern::Binding::from(val));
             }
-
-            output.push((first_bindings, row_values));
+            output.push((bindings, Vec::new()));
         }
 
         Ok(output)
     }
 
+    fn execute_rebind(
+        &self,
+        input: &PlanOp,
+        columns: &[String],
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Vec<(Bindings, Vec<Value>)>> {
+        let input_results = self.execute_op(input, initial_bindings)?;
+        let mut results = Vec::new();
+
+        for (_, values) in input_results {
+            let mut bindings = initial_bindings.cloned().unwrap_or_default();
+            for (name, value) in columns.iter().zip(values) {
+                let binding = match value {
+                    Value::NodeRef(id) => mew_pattern::Binding::Node(id),
+                    Value::EdgeRef(id) => mew_pattern::Binding::Edge(id),
+                    Value::Null => mew_pattern::Binding::Null,
+                    other => mew_pattern::Binding::from(other),
+                };
+                bindings.insert(name, binding);
+            }
+            results.push((bindings, Vec::new()));
+        }
+
+        Ok(results)
+    }
+
+    fn execute_apply(
+        &self,
+        input: &PlanOp,
+        inner: &PlanOp,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Vec<(Bindings, Vec<Value>)>> {
+        let input_results = self.execute_op(input, initial_bindings)?;
+        let mut results = Vec::new();
+
+        for (bindings, _) in &input_results {
+            results.extend(self.execute_op(inner, Some(bindings))?);
+        }
+
+        Ok(results)
+    }
+
     fn execute_cross_join(
         &self,
         left: &PlanOp,
@@ -572,6 +1000,7 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
         Ok(results)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn execute_transitive_closure(
         &self,
         start_expr: &Expr,
@@ -611,7 +1040,7 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
         // Start with (node_id, depth, path)
         frontier.push_back((start_id, 0, vec![start_id.into()]));
 
-        let max_d = max_depth.unwrap_or(100); // Default max depth
+        let max_d = max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
 
         while let Some((current_id, depth, path)) = frontier.pop_front() {
             // Skip if already visited (cycle prevention) - check BEFORE yielding
@@ -650,23 +1079,9 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
                 };
 
                 if should_yield {
-                    // Add path as a value (list of node refs)
-                    let path_values: Vec<Value> = path
-                        .iter()
-                        .map(|id| match id {
-                            mew_core::EntityId::Node(n) => Value::NodeRef(*n),
-                            mew_core::EntityId::Edge(e) => Value::EdgeRef(*e),
-                        })
-                        .collect();
-
-                    // Output: node (the current node), path (the path taken)
-                    results.push((
-                        bindings.clone(),
-                        vec![
-                            Value::NodeRef(current_id),
-                            Value::String(format!("{:?}", path_values)),
-                        ],
-                    ));
+                    let mut row = bindings.clone();
+                    bind_path(&mut row, &path);
+                    results.push((row, Vec::new()));
                 }
             }
 
@@ -712,6 +1127,44 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
         Ok(results)
     }
 
+    /// Execute WALK ... SHORTEST: one row per path found.
+    fn execute_shortest_paths(
+        &self,
+        search: &PathSearch,
+        start_expr: &Expr,
+        target_expr: &Expr,
+        paths: ShortestPaths,
+        weighted: bool,
+        initial_bindings: Option<&Bindings>,
+    ) -> QueryResult<Vec<(Bindings, Vec<Value>)>> {
+        let init_bindings = initial_bindings.cloned().unwrap_or_default();
+        let start = self
+            .evaluator
+            .eval(start_expr, &init_bindings, self.graph)?;
+        let target = self
+            .evaluator
+            .eval(target_expr, &init_bindings, self.graph)?;
+        let (Value::NodeRef(start), Value::NodeRef(target)) = (start, target) else {
+            return Ok(Vec::new());
+        };
+
+        let mut results = Vec::new();
+        for path in search.shortest(start, target, paths)? {
+            let mut row = init_bindings.clone();
+            row.insert("node", mew_pattern::Binding::Node(target));
+            bind_path(&mut row, &path.entities);
+            // Unweighted costs count edges, so they stay integers
+            let cost = if weighted {
+                Value::Float(path.cost)
+            } else {
+                Value::Int(path.depth())
+            };
+            row.insert("cost", mew_pattern::Binding::Value(cost));
+            results.push((row, Vec::new()));
+        }
+        Ok(results)
+    }
+
     fn expand_outbound(
         &self,
         current_id: mew_core::NodeId,
@@ -820,6 +1273,30 @@ impl<'r, 'g> OperatorContext<'r, 'g> {
     }
 }
 
+/// Bind the walk variables of a path of alternating nodes and edges:
+/// `path` to it as a list, `edge` to its last edge (null when it has none)
+/// and `depth` to its number of edges.
+fn bind_path(bindings: &mut Bindings, path: &[mew_core::EntityId]) {
+    let values: Vec<Value> = path
+        .iter()
+        .map(|id| match id {
+            mew_core::EntityId::Node(n) => Value::NodeRef(*n),
+            mew_core::EntityId::Edge(e) => Value::EdgeRef(*e),
+        })
+        .collect();
+    let edge = path
+        .iter()
+        .rev()
+        .find_map(|id| id.as_edge())
+        .map_or(mew_pattern::Binding::Null, mew_pattern::Binding::Edge);
+    bindings.insert("edge", edge);
+    bindings.insert(
+        "depth",
+        mew_pattern::Binding::Value(Value::Int((path.len() / 2) as i64)),
+    );
+    bindings.insert("path", mew_pattern::Binding::Value(Value::List(values)));
+}
+
 /// Compare two optional values for sorting.
 pub fn compare_values(a: &Option<Value>, b: &Option<Value>) -> std::cmp::Ordering {
     match (a, b) {
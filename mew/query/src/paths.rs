@@ -0,0 +1,481 @@
+//! Shortest path search for WALK.
+//!
+//! Without a weight every edge costs 1 and the search is breadth-first;
+//! with one, each edge costs the value of its weight attribute and the
+//! search is Dijkstra's. `SHORTEST k PATHS` enumerates simple paths
+//! cheapest first until k of them reach the target.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use mew_core::{EdgeId, EdgeTypeId, EntityId, NodeId, Value};
+use mew_graph::Graph;
+use mew_parser::ShortestPaths;
+
+use crate::plan::WalkDirection;
+use crate::{QueryError, QueryResult};
+
+/// A path found by the search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundPath {
+    /// Alternating node and edge IDs, from the start node to the target.
+    pub entities: Vec<EntityId>,
+    /// Sum of the edge costs.
+    pub cost: f64,
+}
+
+impl FoundPath {
+    /// Number of edges on the path.
+    pub fn depth(&self) -> i64 {
+        (self.entities.len() / 2) as i64
+    }
+}
+
+/// One edge leading away from a node.
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    edge: EdgeId,
+    next: NodeId,
+    cost: f64,
+}
+
+/// Searches the paths that follow a set of edge types.
+pub struct PathSearch<'g, 'a> {
+    graph: &'g Graph,
+    edge_types: &'a [EdgeTypeId],
+    direction: WalkDirection,
+    weight: Option<&'a str>,
+    min_depth: i64,
+    max_depth: i64,
+}
+
+impl<'g, 'a> PathSearch<'g, 'a> {
+    pub fn new(
+        graph: &'g Graph,
+        edge_types: &'a [EdgeTypeId],
+        direction: WalkDirection,
+        weight: Option<&'a str>,
+        min_depth: i64,
+        max_depth: i64,
+    ) -> Self {
+        Self {
+            graph,
+            edge_types,
+            direction,
+            weight,
+            min_depth,
+            max_depth,
+        }
+    }
+
+    /// The cheapest paths from `start` to `target`, cheapest first.
+    /// Paths whose number of edges is outside the depth range are left out.
+    pub fn shortest(
+        &self,
+        start: NodeId,
+        target: NodeId,
+        paths: ShortestPaths,
+    ) -> QueryResult<Vec<FoundPath>> {
+        match paths {
+            ShortestPaths::First(k) if k > 1 => self.k_shortest(start, target, k as usize),
+            ShortestPaths::First(_) => {
+                let tree = self.search(start, target)?;
+                Ok(tree.paths(start, target, 1, self))
+            }
+            ShortestPaths::All => {
+                let tree = self.search(start, target)?;
+                Ok(tree.paths(start, target, usize::MAX, self))
+            }
+        }
+    }
+
+    /// Breadth-first search, or Dijkstra's algorithm with a weight, from
+    /// `start` until `target` is settled. Records every predecessor on a
+    /// cheapest path, so that all shortest paths can be read back.
+    fn search(&self, start: NodeId, target: NodeId) -> QueryResult<SearchTree> {
+        let mut tree = SearchTree::default();
+        tree.reach(start, 0.0, 0, None);
+
+        if self.weight.is_none() {
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                let (cost, depth) = tree.best[&node];
+                if node == target || depth >= self.max_depth {
+                    continue;
+                }
+                for step in self.steps(node)? {
+                    if tree.reach(step.next, cost + 1.0, depth + 1, Some((node, step.edge))) {
+                        queue.push_back(step.next);
+                    }
+                }
+            }
+            return Ok(tree);
+        }
+
+        let mut heap = BinaryHeap::from([Candidate::new(0.0, 0, start)]);
+        let mut pushed = 1;
+        let mut settled = HashSet::new();
+        while let Some(Candidate { cost, node, .. }) = heap.pop() {
+            if tree.best.get(&target).is_some_and(|(best, _)| cost > *best) {
+                break;
+            }
+            if !settled.insert(node) {
+                continue;
+            }
+            let (_, depth) = tree.best[&node];
+            if node == target || depth >= self.max_depth {
+                continue;
+            }
+            for step in self.steps(node)? {
+                let next_cost = cost + step.cost;
+                if tree.reach(step.next, next_cost, depth + 1, Some((node, step.edge))) {
+                    heap.push(Candidate::new(next_cost, pushed, step.next));
+                    pushed += 1;
+                }
+            }
+        }
+        Ok(tree)
+    }
+
+    /// The k cheapest simple paths, found by extending partial paths
+    /// cheapest first.
+    fn k_shortest(&self, start: NodeId, target: NodeId, k: usize) -> QueryResult<Vec<FoundPath>> {
+        let mut partial = vec![FoundPath {
+            entities: vec![start.into()],
+            cost: 0.0,
+        }];
+        let mut heap = BinaryHeap::from([Candidate::new(0.0, 0, start).with_index(0)]);
+        let mut found = Vec::new();
+
+        while let Some(candidate) = heap.pop() {
+            let path = partial[candidate.index].clone();
+            if candidate.node == target && path.depth() >= self.min_depth {
+                found.push(path.clone());
+                if found.len() == k {
+                    break;
+                }
+            }
+            if path.depth() >= self.max_depth {
+                continue;
+            }
+            for step in self.steps(candidate.node)? {
+                if path.entities.contains(&step.next.into()) {
+                    continue;
+                }
+                let mut entities = path.entities.clone();
+                entities.push(step.edge.into());
+                entities.push(step.next.into());
+                let cost = path.cost + step.cost;
+                partial.push(FoundPath { entities, cost });
+                heap.push(
+                    Candidate::new(cost, partial.len(), step.next).with_index(partial.len() - 1),
+                );
+            }
+        }
+        Ok(found)
+    }
+
+    /// The edges leading away from a node, in edge ID order.
+    fn steps(&self, node: NodeId) -> QueryResult<Vec<Step>> {
+        let mut steps = Vec::new();
+        for edge_type in self.edge_types {
+            if matches!(
+                self.direction,
+                WalkDirection::Outbound | WalkDirection::Both
+            ) {
+                for edge_id in self.graph.edges_from(node, Some(*edge_type)) {
+                    self.push_step(&mut steps, edge_id, 1)?;
+                }
+            }
+            if matches!(self.direction, WalkDirection::Inbound | WalkDirection::Both) {
+                for edge_id in self.graph.edges_to(node, Some(*edge_type)) {
+                    self.push_step(&mut steps, edge_id, 0)?;
+                }
+            }
+        }
+        steps.sort_by_key(|step| (step.edge, step.next));
+        Ok(steps)
+    }
+
+    /// Add the step along an edge to its target at `position`.
+    fn push_step(
+        &self,
+        steps: &mut Vec<Step>,
+        edge_id: EdgeId,
+        position: usize,
+    ) -> QueryResult<()> {
+        let Some(edge) = self.graph.get_edge(edge_id) else {
+            return Ok(());
+        };
+        let Some(next) = edge.targets.get(position).and_then(|t| t.as_node()) else {
+            return Ok(());
+        };
+        let cost = match self.weight {
+            None => 1.0,
+            Some(attr) => match edge.attributes.get(attr) {
+                Some(Value::Int(n)) | Some(Value::Duration(n)) if *n >= 0 => *n as f64,
+                Some(Value::Float(f)) if *f >= 0.0 => *f,
+                other => {
+                    return Err(QueryError::type_error(format!(
+                        "edge {} has no non-negative weight '{}' (got {})",
+                        edge_id.raw(),
+                        attr,
+                        other.unwrap_or(&Value::Null)
+                    )))
+                }
+            },
+        };
+        steps.push(Step {
+            edge: edge_id,
+            next,
+            cost,
+        });
+        Ok(())
+    }
+}
+
+/// Cheapest known cost of every reached node, with the steps that reach it
+/// at that cost.
+#[derive(Default)]
+struct SearchTree {
+    best: HashMap<NodeId, (f64, i64)>,
+    predecessors: HashMap<NodeId, Vec<(NodeId, EdgeId)>>,
+}
+
+impl SearchTree {
+    /// Record reaching `node` at `cost`. Returns true when the cost is
+    /// new or lower, so the node must be (re)visited.
+    fn reach(
+        &mut self,
+        node: NodeId,
+        cost: f64,
+        depth: i64,
+        via: Option<(NodeId, EdgeId)>,
+    ) -> bool {
+        match self.best.get(&node) {
+            Some((best, _)) if cost > *best => false,
+            Some((best, _)) if cost == *best => {
+                if let Some(via) = via {
+                    self.predecessors.entry(node).or_default().push(via);
+                }
+                false
+            }
+            _ => {
+                self.best.insert(node, (cost, depth));
+                self.predecessors.insert(node, via.into_iter().collect());
+                true
+            }
+        }
+    }
+
+    /// Read back up to `limit` cheapest simple paths to `target`.
+    fn paths(
+        &self,
+        start: NodeId,
+        target: NodeId,
+        limit: usize,
+        search: &PathSearch,
+    ) -> Vec<FoundPath> {
+        let Some((cost, _)) = self.best.get(&target) else {
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        let mut suffix = vec![EntityId::from(target)];
+        self.walk_back(start, target, &mut suffix, limit, &mut found);
+        found
+            .into_iter()
+            .map(|entities| FoundPath {
+                entities,
+                cost: *cost,
+            })
+            .filter(|path| (search.min_depth..=search.max_depth).contains(&path.depth()))
+            .collect()
+    }
+
+    fn walk_back(
+        &self,
+        start: NodeId,
+        node: NodeId,
+        suffix: &mut Vec<EntityId>,
+        limit: usize,
+        found: &mut Vec<Vec<EntityId>>,
+    ) {
+        if found.len() >= limit {
+            return;
+        }
+        if node == start {
+            found.push(suffix.iter().rev().copied().collect());
+            return;
+        }
+        for (previous, edge) in self.predecessors.get(&node).into_iter().flatten() {
+            // Zero-cost cycles would otherwise be walked forever
+            if suffix.contains(&EntityId::from(*previous)) {
+                continue;
+            }
+            suffix.push((*edge).into());
+            suffix.push((*previous).into());
+            self.walk_back(start, *previous, suffix, limit, found);
+            suffix.truncate(suffix.len() - 2);
+        }
+    }
+}
+
+/// An entry of the search frontier, ordered cheapest first and then in
+/// insertion order.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    cost: f64,
+    order: usize,
+    node: NodeId,
+    index: usize,
+}
+
+impl Candidate {
+    fn new(cost: f64, order: usize, node: NodeId) -> Self {
+        Self {
+            cost,
+            order,
+            node,
+            index: 0,
+        }
+    }
+
+    fn with_index(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap: reverse so the cheapest comes out first
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mew_core::attrs;
+
+    /// A diamond a -> b -> d, a -> c -> d with edges of 1 km, and a direct
+    /// road a -> d of 5 km.
+    fn diamond() -> (Graph, Vec<NodeId>, [EdgeTypeId; 1]) {
+        let mut graph = Graph::new();
+        let city = mew_core::TypeId(1);
+        let road = EdgeTypeId(1);
+        let cities: Vec<NodeId> = (0..4).map(|_| graph.create_node(city, attrs! {})).collect();
+        let (a, b, c, d) = (cities[0], cities[1], cities[2], cities[3]);
+        for (from, to, km) in [
+            (a, b, 1.0),
+            (b, d, 1.0),
+            (a, c, 1.0),
+            (c, d, 1.0),
+            (a, d, 5.0),
+        ] {
+            graph
+                .create_edge(road, vec![from.into(), to.into()], attrs! { "km" => km })
+                .unwrap();
+        }
+        (graph, cities, [road])
+    }
+
+    fn nodes(path: &FoundPath) -> Vec<NodeId> {
+        path.entities.iter().filter_map(|id| id.as_node()).collect()
+    }
+
+    #[test]
+    fn test_unweighted_shortest_path_counts_edges() {
+        // GIVEN
+        let (graph, cities, roads) = diamond();
+        let search = PathSearch::new(&graph, &roads, WalkDirection::Outbound, None, 1, 10);
+
+        // WHEN
+        let paths = search
+            .shortest(cities[0], cities[3], ShortestPaths::All)
+            .unwrap();
+
+        // THEN only the direct road has one edge
+        assert_eq!(paths.len(), 1);
+        assert_eq!(nodes(&paths[0]), vec![cities[0], cities[3]]);
+        assert_eq!(paths[0].cost, 1.0);
+    }
+
+    #[test]
+    fn test_weighted_shortest_paths() {
+        // GIVEN
+        let (graph, cities, roads) = diamond();
+        let search = PathSearch::new(&graph, &roads, WalkDirection::Outbound, Some("km"), 1, 10);
+        let (a, b, c, d) = (cities[0], cities[1], cities[2], cities[3]);
+
+        // WHEN
+        let one = search.shortest(a, d, ShortestPaths::First(1)).unwrap();
+        let all = search.shortest(a, d, ShortestPaths::All).unwrap();
+        let three = search.shortest(a, d, ShortestPaths::First(3)).unwrap();
+
+        // THEN the two 2 km routes tie, and the direct road comes third
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].cost, 2.0);
+        let mut routes: Vec<_> = all.iter().map(nodes).collect();
+        routes.sort();
+        assert_eq!(routes, vec![vec![a, b, d], vec![a, c, d]]);
+        assert_eq!(
+            three.iter().map(|p| p.cost).collect::<Vec<_>>(),
+            vec![2.0, 2.0, 5.0]
+        );
+        assert_eq!(nodes(&three[2]), vec![a, d]);
+    }
+
+    #[test]
+    fn test_shortest_path_respects_direction_and_depth() {
+        // GIVEN
+        let (graph, cities, roads) = diamond();
+
+        // WHEN searching against the edges, or within too few hops
+        let inbound = PathSearch::new(&graph, &roads, WalkDirection::Inbound, None, 1, 10)
+            .shortest(cities[0], cities[3], ShortestPaths::All)
+            .unwrap();
+        let backwards = PathSearch::new(&graph, &roads, WalkDirection::Inbound, None, 1, 10)
+            .shortest(cities[3], cities[1], ShortestPaths::All)
+            .unwrap();
+        let shallow = PathSearch::new(&graph, &roads, WalkDirection::Outbound, Some("km"), 1, 1)
+            .shortest(cities[0], cities[3], ShortestPaths::First(2))
+            .unwrap();
+
+        // THEN
+        assert!(inbound.is_empty());
+        assert_eq!(nodes(&backwards[0]), vec![cities[3], cities[1]]);
+        assert_eq!(shallow.len(), 1);
+        assert_eq!(shallow[0].cost, 5.0);
+    }
+
+    #[test]
+    fn test_missing_weight_is_an_error() {
+        // GIVEN
+        let (graph, cities, roads) = diamond();
+        let search = PathSearch::new(&graph, &roads, WalkDirection::Outbound, Some("toll"), 1, 10);
+
+        // WHEN
+        let result = search.shortest(cities[0], cities[3], ShortestPaths::First(1));
+
+        // THEN
+        assert!(result.unwrap_err().to_string().contains("weight 'toll'"));
+    }
+}
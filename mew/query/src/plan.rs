@@ -1,7 +1,13 @@
 //! Query planning.
 
-use mew_core::{EdgeTypeId, TypeId};
-use mew_parser::{Expr, MatchStmt, Projection, WalkStmt};
+use std::collections::HashMap;
+
+use mew_core::{EdgeTypeId, TypeId, VectorMetric};
+use mew_parser::{
+    Expr, MatchStmt, NearestClause, NearestMetric, Projection, ShortestPaths, Span, UnionStmt,
+    WalkStmt, WithStage,
+};
+use mew_pattern::Closure;
 use mew_registry::Registry;
 
 use crate::{QueryError, QueryResult};
@@ -29,6 +35,45 @@ pub enum PlanOp {
         value: Expr,
     },
 
+    /// Scan the k nodes nearest to a query vector using the vector index.
+    /// Rows come out closest first.
+    VectorScan {
+        var: String,
+        type_id: TypeId,
+        attr: String,
+        query: Expr,
+        k: usize,
+        metric: VectorMetric,
+    },
+
+    /// Scan nodes whose point attribute lies in a region, using the spatial index.
+    SpatialScan {
+        var: String,
+        type_id: TypeId,
+        attr: String,
+        region: SpatialRegion,
+    },
+
+    /// Scan nodes whose `[searchable]` attribute matches a text query, using
+    /// the text index when the graph has one for the attribute.
+    TextScan {
+        var: String,
+        type_id: TypeId,
+        attr: String,
+        query: Expr,
+    },
+
+    /// Keep the k input rows whose vector attribute is nearest to a query vector.
+    /// Rows come out closest first; rows without a comparable vector are dropped.
+    Nearest {
+        input: Box<PlanOp>,
+        var: String,
+        attr: String,
+        query: Expr,
+        k: usize,
+        metric: VectorMetric,
+    },
+
     /// Follow edges from bound nodes.
     EdgeJoin {
         input: Box<PlanOp>,
@@ -37,6 +82,16 @@ pub enum PlanOp {
         edge_var: Option<String>,
     },
 
+    /// Join on a transitive edge pattern (`edge+` or `edge*`): keeps rows whose
+    /// bound ends are connected and binds the ends that are not bound yet.
+    /// Without an input it starts from a single empty row.
+    TransitiveJoin {
+        input: Option<Box<PlanOp>>,
+        closure: Closure,
+        from: String,
+        to: String,
+    },
+
     /// Filter rows by a condition.
     Filter { input: Box<PlanOp>, condition: Expr },
 
@@ -84,6 +139,10 @@ pub enum PlanOp {
     },
 
     /// Transitive closure for WALK.
+    ///
+    /// Binds `node` to each node reached, `edge` to the last edge followed,
+    /// `path` to the list of nodes and edges from the start and `depth` to
+    /// the number of edges.
     TransitiveClosure {
         start_var: String,
         start_expr: Expr,
@@ -97,9 +156,39 @@ pub enum PlanOp {
         return_terminal: bool,
     },
 
+    /// Cheapest paths between two nodes for WALK ... SHORTEST. Binds the
+    /// same variables as `TransitiveClosure`, plus `cost`.
+    ShortestPaths {
+        start_expr: Expr,
+        target_expr: Expr,
+        edge_types: Vec<EdgeTypeId>,
+        min_depth: i64,
+        max_depth: Option<i64>,
+        direction: WalkDirection,
+        /// Edge attribute giving each edge's cost
+        weight: Option<String>,
+        paths: ShortestPaths,
+    },
+
     /// Remove duplicate rows.
     Distinct { input: Box<PlanOp> },
 
+    /// Rows of each input in turn (UNION ALL).
+    Union { inputs: Vec<PlanOp> },
+
+    /// Turn projected rows back into bindings for the next WITH stage: each
+    /// row binds `columns` to its values, on top of the initial bindings.
+    Rebind {
+        input: Box<PlanOp>,
+        columns: Vec<String>,
+    },
+
+    /// Run `inner` once per input row, starting from that row's bindings.
+    Apply {
+        input: Box<PlanOp>,
+        inner: Box<PlanOp>,
+    },
+
     /// Deduplicate by edge binding variable (for symmetric edges).
     /// Keeps only one row per unique edge_id.
     EdgeDedup {
@@ -111,6 +200,16 @@ pub enum PlanOp {
     Empty,
 }
 
+/// A region served by the spatial index. The expressions must not depend on
+/// pattern variables; they are evaluated once per scan.
+#[derive(Debug, Clone)]
+pub enum SpatialRegion {
+    /// `within_box(v.attr, corner_a, corner_b)`
+    Box { corner_a: Expr, corner_b: Expr },
+    /// `within_radius(v.attr, center, radius)`
+    Radius { center: Expr, radius: Expr },
+}
+
 /// Aggregate function kinds.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AggregateKind {
@@ -120,6 +219,18 @@ pub enum AggregateKind {
     Min,
     Max,
     Collect,
+    /// Sample standard deviation.
+    StdDev,
+    /// Sample variance.
+    Variance,
+    /// Percentile interpolated between input values.
+    PercentileCont,
+    /// Percentile picked from the input values.
+    PercentileDisc,
+    /// Interpolated 50th percentile.
+    Median,
+    /// Strings concatenated with a separator.
+    StringAgg,
 }
 
 /// Specification for an aggregate computation.
@@ -133,6 +244,9 @@ pub struct AggregateSpec {
     pub expr: Expr,
     /// Whether to only consider distinct values (e.g., COUNT(DISTINCT x)).
     pub distinct: bool,
+    /// Second argument, constant per group: the fraction of a percentile or
+    /// the separator of STRING_AGG.
+    pub param: Option<Expr>,
 }
 
 /// Walk direction.
@@ -156,9 +270,122 @@ impl<'r> QueryPlanner<'r> {
 
     /// Plan a MATCH statement.
     pub fn plan_match(&self, stmt: &MatchStmt) -> QueryResult<QueryPlan> {
+        // A lone node pattern ranked by similarity is served by the vector index
+        if let Some(scan) = self.plan_vector_scan(stmt) {
+            return self.plan_match_from(stmt, scan, true);
+        }
+
         // Build the pattern matching plan
         let mut plan = self.plan_pattern(&stmt.pattern)?;
+        if let Some(cond) = &stmt.where_clause {
+            plan = Self::apply_spatial_scans(plan, cond);
+            plan = self.apply_text_scans(plan, cond);
+        }
+        self.plan_match_from(stmt, plan, false)
+    }
+
+    /// Plan a UNION of MATCH queries.
+    ///
+    /// Each branch is planned on its own; their rows are concatenated, made
+    /// distinct unless UNION ALL, and bound to the first branch's column
+    /// names so the union's ORDER BY can refer to them.
+    pub fn plan_union(&self, stmt: &UnionStmt) -> QueryResult<QueryPlan> {
+        let mut inputs = Vec::with_capacity(stmt.branches.len());
+        let mut columns: Option<Vec<String>> = None;
+        for branch in &stmt.branches {
+            let plan = self.plan_match(branch)?;
+            match &columns {
+                Some(first) if first.len() != plan.columns.len() => {
+                    return Err(QueryError::pattern_error(format!(
+                        "UNION branches return different numbers of columns: {} and {}",
+                        first.len(),
+                        plan.columns.len()
+                    )));
+                }
+                Some(_) => {}
+                None => columns = Some(plan.columns),
+            }
+            inputs.push(plan.root);
+        }
+        let columns = columns.unwrap_or_default();
+
+        let mut plan = PlanOp::Union { inputs };
+        if !stmt.all {
+            plan = PlanOp::Distinct {
+                input: Box::new(plan),
+            };
+        }
+        plan = PlanOp::Rebind {
+            input: Box::new(plan),
+            columns: columns.clone(),
+        };
+
+        if let Some(order_by) = &stmt.order_by {
+            let order_by = Self::order_terms(order_by)
+                .into_iter()
+                .map(|(expr, asc)| Ok((self.union_order_expr(&expr, &columns)?, asc)))
+                .collect::<QueryResult<Vec<_>>>()?;
+            plan = PlanOp::Sort {
+                input: Box::new(plan),
+                order_by,
+            };
+        }
+        if stmt.limit.is_some() || stmt.offset.is_some() {
+            plan = PlanOp::LimitOffset {
+                input: Box::new(plan),
+                limit: stmt.limit,
+                offset: stmt.offset,
+            };
+        }
+
+        plan = PlanOp::Project {
+            input: Box::new(plan),
+            projections: columns
+                .iter()
+                .map(|name| (name.clone(), Expr::Var(name.clone(), Span::default())))
+                .collect(),
+        };
+
+        Ok(QueryPlan {
+            root: plan,
+            columns,
+        })
+    }
+
+    /// Rewrite an ORDER BY term of a UNION over its result columns: every
+    /// part of the term that names a column, such as `title` or `t.title`,
+    /// becomes that column. Anything else is not available after the union.
+    fn union_order_expr(&self, expr: &Expr, columns: &[String]) -> QueryResult<Expr> {
+        let mut missing = None;
+        let rewritten = map_expr(expr, &mut |e| {
+            if matches!(e, Expr::Var(..) | Expr::AttrAccess(..) | Expr::FnCall(_)) {
+                let name = self.expr_to_name(e);
+                if columns.contains(&name) {
+                    return Some(Expr::Var(name, e.span()));
+                }
+            }
+            if let Expr::Var(var, _) = e {
+                missing.get_or_insert_with(|| var.clone());
+            }
+            None
+        });
+        match missing {
+            Some(var) => Err(QueryError::pattern_error(format!(
+                "ORDER BY of a UNION can only use its result columns, not '{}'",
+                var
+            ))),
+            None => Ok(rewritten),
+        }
+    }
 
+    /// Plan the rest of a MATCH on top of its pattern plan.
+    /// `nearest_applied` is set when the pattern plan already selects the NEAREST rows.
+    fn plan_match_from(
+        &self,
+        stmt: &MatchStmt,
+        mut plan: PlanOp,
+        nearest_applied: bool,
+    ) -> QueryResult<QueryPlan> {
         // Add WHERE filter
         if let Some(ref cond) = stmt.where_clause {
             plan = PlanOp::Filter {
@@ -171,6 +398,10 @@ impl<'r> QueryPlanner<'r> {
         // This deduplicates by edge_id after WHERE filtering, so each physical edge appears once
         for elem in &stmt.pattern {
             if let mew_parser::PatternElem::Edge(ep) = elem {
+                if ep.transitive.is_some() {
+                    // Transitive joins bind no edge
+                    continue;
+                }
                 // Check if this edge type is symmetric
                 if let Some(edge_type_id) = self.registry.get_edge_type_id(&ep.edge_type) {
                     if let Some(edge_type) = self.registry.get_edge_type(edge_type_id) {
@@ -202,41 +433,43 @@ impl<'r> QueryPlanner<'r> {
             };
         }
 
-        // Check if projections contain aggregates
-        let aggregates = self.extract_aggregates(&stmt.return_clause.projections);
-        let has_aggregates = !aggregates.is_empty();
-
-        // Add aggregate operator if needed (before ORDER BY)
-        if has_aggregates {
-            // Extract non-aggregate expressions as implicit GROUP BY
-            let group_by: Vec<Expr> = stmt
-                .return_clause
-                .projections
-                .iter()
-                .filter(|p| self.get_aggregate(&p.expr).is_none())
-                .map(|p| p.expr.clone())
-                .collect();
-
-            plan = PlanOp::Aggregate {
+        // Add NEAREST selection over the filtered rows
+        if let Some(nearest) = stmt.nearest.as_ref().filter(|_| !nearest_applied) {
+            plan = PlanOp::Nearest {
                 input: Box::new(plan),
-                group_by,
-                aggregates,
+                var: nearest.var.clone(),
+                attr: nearest.attr.clone(),
+                query: nearest.query.clone(),
+                k: nearest.k.max(0) as usize,
+                metric: Self::vector_metric(nearest.metric),
             };
         }
 
-        // Add ORDER BY
-        if let Some(ref order_by) = stmt.order_by {
-            let order: Vec<(Expr, bool)> = order_by
-                .iter()
-                .map(|term| {
-                    let asc = matches!(term.direction, mew_parser::OrderDirection::Asc);
-                    (term.expr.clone(), asc)
-                })
-                .collect();
+        // WITH stages
+        for stage in &stmt.stages {
+            plan = self.plan_with_stage(plan, stage)?;
+        }
 
+        // Group and aggregate (before ORDER BY)
+        let (projections, columns) = self.plan_projections(&stmt.return_clause.projections)?;
+        let order_by = stmt
+            .order_by
+            .as_deref()
+            .map(Self::order_terms)
+            .unwrap_or_default();
+        let (mut plan, projections, order_by) = self.plan_grouping(
+            plan,
+            projections,
+            stmt.group_by.as_deref(),
+            stmt.having.as_ref(),
+            order_by,
+        );
+
+        // Add ORDER BY
+        if !order_by.is_empty() {
             plan = PlanOp::Sort {
                 input: Box::new(plan),
-                order_by: order,
+                order_by,
             };
         }
 
@@ -249,17 +482,11 @@ impl<'r> QueryPlanner<'r> {
             };
         }
 
-        // Add projections (for non-aggregate queries, or for the column naming)
-        let (projections, columns) = self.plan_projections(&stmt.return_clause.projections)?;
-
-        // If we have aggregates, the projection just names the output columns
-        // The actual values are computed by the Aggregate operator
-        if !has_aggregates {
-            plan = PlanOp::Project {
-                input: Box::new(plan),
-                projections,
-            };
-        }
+        // Add projections
+        plan = PlanOp::Project {
+            input: Box::new(plan),
+            projections,
+        };
 
         // Add DISTINCT if requested
         if stmt.return_clause.distinct {
@@ -274,29 +501,430 @@ impl<'r> QueryPlanner<'r> {
         })
     }
 
-    /// Extract aggregate functions from projections.
-    fn extract_aggregates(&self, projections: &[Projection]) -> Vec<AggregateSpec> {
-        projections
+    /// Plan a WITH stage on top of the rows so far. The stage's projected
+    /// values become the only bindings of its rows; its ORDER BY, LIMIT,
+    /// MATCH and WHERE see just those names.
+    fn plan_with_stage(&self, plan: PlanOp, stage: &WithStage) -> QueryResult<PlanOp> {
+        let (projections, columns) = self.plan_projections(&stage.projections)?;
+        let (plan, projections, _) = self.plan_grouping(
+            plan,
+            projections,
+            stage.group_by.as_deref(),
+            stage.having.as_ref(),
+            Vec::new(),
+        );
+
+        let mut plan = PlanOp::Project {
+            input: Box::new(plan),
+            projections,
+        };
+        if stage.distinct {
+            plan = PlanOp::Distinct {
+                input: Box::new(plan),
+            };
+        }
+        plan = PlanOp::Rebind {
+            input: Box::new(plan),
+            columns,
+        };
+
+        if let Some(order_by) = &stage.order_by {
+            plan = PlanOp::Sort {
+                input: Box::new(plan),
+                order_by: Self::order_terms(order_by),
+            };
+        }
+        if stage.limit.is_some() || stage.offset.is_some() {
+            plan = PlanOp::LimitOffset {
+                input: Box::new(plan),
+                limit: stage.limit,
+                offset: stage.offset,
+            };
+        }
+
+        if !stage.pattern.is_empty() {
+            plan = PlanOp::Apply {
+                input: Box::new(plan),
+                inner: Box::new(self.plan_pattern(&stage.pattern)?),
+            };
+        }
+        if let Some(cond) = &stage.where_clause {
+            plan = PlanOp::Filter {
+                input: Box::new(plan),
+                condition: cond.clone(),
+            };
+        }
+
+        Ok(plan)
+    }
+
+    /// Plan the grouping of a RETURN or WITH: an Aggregate operator when it
+    /// aggregates or has GROUP BY, then the HAVING filter.
+    ///
+    /// Aggregate calls in the projections, HAVING and ORDER BY become variables
+    /// bound by the Aggregate operator, and aliases in HAVING and ORDER BY
+    /// become the expressions they name, so everything after the Aggregate
+    /// evaluates as usual. Returns the plan with the rewritten projections and
+    /// ORDER BY terms.
+    #[allow(clippy::type_complexity)]
+    fn plan_grouping(
+        &self,
+        mut plan: PlanOp,
+        projections: Vec<(String, Expr)>,
+        group_by: Option<&[Expr]>,
+        having: Option<&Expr>,
+        order_by: Vec<(Expr, bool)>,
+    ) -> (PlanOp, Vec<(String, Expr)>, Vec<(Expr, bool)>) {
+        let mut aggregates = Vec::new();
+        let mut implicit_keys = Vec::new();
+        let mut rewritten = Vec::with_capacity(projections.len());
+        for (name, expr) in &projections {
+            let before = aggregates.len();
+            let expr_rw = self.extract_aggregates(expr, Some(name), &mut aggregates);
+            if aggregates.len() == before {
+                implicit_keys.push(expr.clone());
+            }
+            rewritten.push((name.clone(), expr_rw));
+        }
+
+        // Aliases stand for what they name, unless they name themselves
+        let aliases: HashMap<String, Expr> = rewritten
             .iter()
-            .filter_map(|proj| {
-                self.get_aggregate(&proj.expr)
-                    .map(|(kind, expr, distinct)| AggregateSpec {
-                        name: proj
-                            .alias
-                            .clone()
-                            .unwrap_or_else(|| self.expr_to_name(&proj.expr)),
-                        kind,
-                        expr,
-                        distinct,
-                    })
+            .filter(|(name, expr)| !matches!(expr, Expr::Var(v, _) if v == name))
+            .cloned()
+            .collect();
+        let resolve = |expr: &Expr| {
+            map_expr(expr, &mut |e| match e {
+                Expr::Var(name, _) => aliases.get(name).cloned(),
+                _ => None,
+            })
+        };
+
+        let having =
+            having.map(|cond| resolve(&self.extract_aggregates(cond, None, &mut aggregates)));
+        let order_by = order_by
+            .into_iter()
+            .map(|(expr, asc)| {
+                (
+                    resolve(&self.extract_aggregates(&expr, None, &mut aggregates)),
+                    asc,
+                )
+            })
+            .collect();
+
+        if !aggregates.is_empty() || group_by.is_some() {
+            let group_by = match group_by {
+                Some(keys) => keys.iter().map(&resolve).collect(),
+                None => implicit_keys,
+            };
+            plan = PlanOp::Aggregate {
+                input: Box::new(plan),
+                group_by,
+                aggregates,
+            };
+        }
+        if let Some(condition) = having {
+            plan = PlanOp::Filter {
+                input: Box::new(plan),
+                condition,
+            };
+        }
+
+        (plan, rewritten, order_by)
+    }
+
+    /// ORDER BY terms as (expression, ascending) pairs.
+    fn order_terms(terms: &[mew_parser::OrderTerm]) -> Vec<(Expr, bool)> {
+        terms
+            .iter()
+            .map(|term| {
+                let asc = matches!(term.direction, mew_parser::OrderDirection::Asc);
+                (term.expr.clone(), asc)
             })
             .collect()
     }
 
-    /// Check if an expression is an aggregate function and return its kind, argument, and distinct flag.
+    /// Plan a vector index scan when the whole pattern is one node ranked by similarity.
+    ///
+    /// Applies to `NEAREST k TO q ON v.attr`, and to `ORDER BY cosine_similarity(v.attr, q)
+    /// DESC LIMIT k`, when there is no WHERE clause, OPTIONAL MATCH or aggregate that
+    /// could change which rows rank first. Only cosine ordering is rewritten: nodes
+    /// without a vector sort last there, exactly as they are absent from the index.
+    fn plan_vector_scan(&self, stmt: &MatchStmt) -> Option<PlanOp> {
+        let [mew_parser::PatternElem::Node(np)] = stmt.pattern.as_slice() else {
+            return None;
+        };
+        if stmt.where_clause.is_some()
+            || !stmt.optional_matches.is_empty()
+            || !stmt.stages.is_empty()
+            || stmt.group_by.is_some()
+            || stmt.having.is_some()
+            || self.has_aggregates(&stmt.return_clause.projections)
+        {
+            return None;
+        }
+
+        let (attr, query, k, metric) = match &stmt.nearest {
+            Some(NearestClause {
+                k,
+                query,
+                var,
+                attr,
+                metric,
+                ..
+            }) if *var == np.var => (
+                attr.clone(),
+                query.clone(),
+                (*k).max(0) as usize,
+                Self::vector_metric(*metric),
+            ),
+            Some(_) => return None,
+            None => {
+                let limit = stmt.limit?;
+                let [term] = stmt.order_by.as_deref()? else {
+                    return None;
+                };
+                if !matches!(term.direction, mew_parser::OrderDirection::Desc) {
+                    return None;
+                }
+                let Expr::FnCall(fc) = &term.expr else {
+                    return None;
+                };
+                if !fc.name.eq_ignore_ascii_case("cosine_similarity") || fc.args.len() != 2 {
+                    return None;
+                }
+                // The similarity is symmetric: accept the attribute on either side
+                let (attr, query) = match (&fc.args[0], &fc.args[1]) {
+                    (Expr::AttrAccess(base, attr, _), query)
+                    | (query, Expr::AttrAccess(base, attr, _))
+                        if matches!(base.as_ref(), Expr::Var(v, _) if *v == np.var) =>
+                    {
+                        (attr.clone(), query.clone())
+                    }
+                    _ => return None,
+                };
+                let k = (limit + stmt.offset.unwrap_or(0)).max(0) as usize;
+                (attr, query, k, VectorMetric::Cosine)
+            }
+        };
+
+        let type_id = self.registry.get_type_id(&np.type_name)?;
+        Some(PlanOp::VectorScan {
+            var: np.var.clone(),
+            type_id,
+            attr,
+            query,
+            k,
+            metric,
+        })
+    }
+
+    /// Replace node scans with spatial index scans where the WHERE clause requires
+    /// `within_box(v.attr, ...)` or `within_radius(v.attr, ...)` on the scanned variable.
+    /// The WHERE filter stays in place and re-checks every row, so only predicates
+    /// that must hold (top-level conjuncts) are used.
+    fn apply_spatial_scans(plan: PlanOp, cond: &Expr) -> PlanOp {
+        match plan {
+            PlanOp::NodeScan { var, type_id } => match Self::spatial_predicate(cond, &var) {
+                Some((attr, region)) => PlanOp::SpatialScan {
+                    var,
+                    type_id,
+                    attr,
+                    region,
+                },
+                None => PlanOp::NodeScan { var, type_id },
+            },
+            PlanOp::CrossJoin { left, right } => PlanOp::CrossJoin {
+                left: Box::new(Self::apply_spatial_scans(*left, cond)),
+                right: Box::new(Self::apply_spatial_scans(*right, cond)),
+            },
+            PlanOp::EdgeJoin {
+                input,
+                edge_type_id,
+                from_vars,
+                edge_var,
+            } => PlanOp::EdgeJoin {
+                input: Box::new(Self::apply_spatial_scans(*input, cond)),
+                edge_type_id,
+                from_vars,
+                edge_var,
+            },
+            other => other,
+        }
+    }
+
+    /// Find a spatial predicate on `var` among the top-level conjuncts of `cond`.
+    fn spatial_predicate(cond: &Expr, var: &str) -> Option<(String, SpatialRegion)> {
+        match cond {
+            Expr::BinaryOp(mew_parser::BinaryOp::And, left, right, _) => {
+                Self::spatial_predicate(left, var).or_else(|| Self::spatial_predicate(right, var))
+            }
+            Expr::FnCall(fc) if fc.args.len() == 3 => {
+                let Expr::AttrAccess(base, attr, _) = &fc.args[0] else {
+                    return None;
+                };
+                if !matches!(base.as_ref(), Expr::Var(v, _) if v == var)
+                    || !fc.args[1..].iter().all(Self::is_row_independent)
+                {
+                    return None;
+                }
+                let region = match fc.name.to_lowercase().as_str() {
+                    "within_box" => SpatialRegion::Box {
+                        corner_a: fc.args[1].clone(),
+                        corner_b: fc.args[2].clone(),
+                    },
+                    "within_radius" => SpatialRegion::Radius {
+                        center: fc.args[1].clone(),
+                        radius: fc.args[2].clone(),
+                    },
+                    _ => return None,
+                };
+                Some((attr.clone(), region))
+            }
+            _ => None,
+        }
+    }
+
+    /// Replace node scans with text index scans where the WHERE clause requires
+    /// `search(v.attr, query)` on a `[searchable]` attribute of the scanned
+    /// variable. As for spatial scans, the WHERE filter stays in place.
+    fn apply_text_scans(&self, plan: PlanOp, cond: &Expr) -> PlanOp {
+        match plan {
+            PlanOp::NodeScan { var, type_id } => {
+                let searchable = Self::text_predicate(cond, &var).filter(|(attr, _)| {
+                    self.registry
+                        .get_type_attr(type_id, attr)
+                        .is_some_and(|a| a.searchable)
+                });
+                match searchable {
+                    Some((attr, query)) => PlanOp::TextScan {
+                        var,
+                        type_id,
+                        attr,
+                        query,
+                    },
+                    None => PlanOp::NodeScan { var, type_id },
+                }
+            }
+            PlanOp::CrossJoin { left, right } => PlanOp::CrossJoin {
+                left: Box::new(self.apply_text_scans(*left, cond)),
+                right: Box::new(self.apply_text_scans(*right, cond)),
+            },
+            PlanOp::EdgeJoin {
+                input,
+                edge_type_id,
+                from_vars,
+                edge_var,
+            } => PlanOp::EdgeJoin {
+                input: Box::new(self.apply_text_scans(*input, cond)),
+                edge_type_id,
+                from_vars,
+                edge_var,
+            },
+            other => other,
+        }
+    }
+
+    /// Find a `search(var.attr, query)` predicate among the top-level conjuncts
+    /// of `cond`.
+    fn text_predicate(cond: &Expr, var: &str) -> Option<(String, Expr)> {
+        match cond {
+            Expr::BinaryOp(mew_parser::BinaryOp::And, left, right, _) => {
+                Self::text_predicate(left, var).or_else(|| Self::text_predicate(right, var))
+            }
+            Expr::FnCall(fc) if fc.name.eq_ignore_ascii_case("search") && fc.args.len() == 2 => {
+                let Expr::AttrAccess(base, attr, _) = &fc.args[0] else {
+                    return None;
+                };
+                if !matches!(base.as_ref(), Expr::Var(v, _) if v == var)
+                    || !Self::is_row_independent(&fc.args[1])
+                {
+                    return None;
+                }
+                Some((attr.clone(), fc.args[1].clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check that an expression can be evaluated before any pattern variable is bound.
+    fn is_row_independent(expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(_) | Expr::Param(_, _) => true,
+            Expr::BinaryOp(_, left, right, _) => {
+                Self::is_row_independent(left) && Self::is_row_independent(right)
+            }
+            Expr::UnaryOp(_, operand, _) => Self::is_row_independent(operand),
+            Expr::FnCall(fc) => fc.args.iter().all(Self::is_row_independent),
+            Expr::List(items, _) => items.iter().all(Self::is_row_independent),
+            Expr::Map(entries, _) => entries.iter().all(|(_, e)| Self::is_row_independent(e)),
+            Expr::Index(base, index, _) => {
+                Self::is_row_independent(base) && Self::is_row_independent(index)
+            }
+            Expr::Var(_, _)
+            | Expr::AttrAccess(_, _, _)
+            | Expr::IdRef(_, _)
+            | Expr::Exists(_, _, _)
+            | Expr::NotExists(_, _, _)
+            | Expr::Subquery(_)
+            | Expr::Quantifier(_)
+            | Expr::TypeCheck(_, _, _) => false,
+        }
+    }
+
+    /// Map a NEAREST metric to the vector index metric.
+    fn vector_metric(metric: NearestMetric) -> VectorMetric {
+        match metric {
+            NearestMetric::Cosine => VectorMetric::Cosine,
+            NearestMetric::L2 => VectorMetric::L2,
+        }
+    }
+
+    /// Replace the aggregate calls in `expr` by variables, adding an aggregate
+    /// for each. An aggregate that is the whole of a projection takes the
+    /// projection's `name`; others get internal names.
+    fn extract_aggregates(
+        &self,
+        expr: &Expr,
+        name: Option<&str>,
+        aggregates: &mut Vec<AggregateSpec>,
+    ) -> Expr {
+        let mut name = name.filter(|n| {
+            self.get_aggregate(expr).is_some() && !aggregates.iter().any(|a| a.name == *n)
+        });
+        map_expr(expr, &mut |e| {
+            let (kind, arg, param, distinct) = self.get_aggregate(e)?;
+            let var = name
+                .take()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("_agg{}", aggregates.len()));
+            aggregates.push(AggregateSpec {
+                name: var.clone(),
+                kind,
+                expr: arg,
+                distinct,
+                param,
+            });
+            Some(Expr::Var(var, e.span()))
+        })
+    }
+
+    /// Whether any projection is an aggregate or contains one.
+    fn has_aggregates(&self, projections: &[Projection]) -> bool {
+        let mut aggregates = Vec::new();
+        for proj in projections {
+            self.extract_aggregates(&proj.expr, None, &mut aggregates);
+        }
+        !aggregates.is_empty()
+    }
+
+    /// Check if an expression is an aggregate function and return its kind, argument,
+    /// second argument and distinct flag.
     /// Note: min/max with 2 arguments are binary functions, not aggregates.
-    fn get_aggregate(&self, expr: &Expr) -> Option<(AggregateKind, Expr, bool)> {
+    fn get_aggregate(&self, expr: &Expr) -> Option<(AggregateKind, Expr, Option<Expr>, bool)> {
         match expr {
+            // Aggregates over a pattern are evaluated for each row
+            Expr::FnCall(fc) if fc.pattern_var().is_some() => None,
             Expr::FnCall(fc) => {
                 let kind = match fc.name.to_lowercase().as_str() {
                     "count" => Some(AggregateKind::Count),
@@ -306,6 +934,12 @@ impl<'r> QueryPlanner<'r> {
                     "min" if fc.args.len() == 1 => Some(AggregateKind::Min),
                     "max" if fc.args.len() == 1 => Some(AggregateKind::Max),
                     "collect" => Some(AggregateKind::Collect),
+                    "stddev" => Some(AggregateKind::StdDev),
+                    "variance" => Some(AggregateKind::Variance),
+                    "percentile_cont" => Some(AggregateKind::PercentileCont),
+                    "percentile_disc" => Some(AggregateKind::PercentileDisc),
+                    "median" => Some(AggregateKind::Median),
+                    "string_agg" => Some(AggregateKind::StringAgg),
                     _ => None,
                 };
                 kind.map(|k| {
@@ -316,7 +950,7 @@ impl<'r> QueryPlanner<'r> {
                             span: fc.span,
                         })
                     });
-                    (k, arg, fc.distinct)
+                    (k, arg, fc.args.get(1).cloned(), fc.distinct)
                 })
             }
             _ => None,
@@ -352,6 +986,14 @@ impl<'r> QueryPlanner<'r> {
                         },
                     });
                 }
+                mew_parser::PatternElem::Edge(ep) if ep.transitive.is_some() => {
+                    plan = Some(PlanOp::TransitiveJoin {
+                        input: plan.map(Box::new),
+                        closure: Closure::new(ep, self.registry)?,
+                        from: ep.targets[0].clone(),
+                        to: ep.targets[1].clone(),
+                    });
+                }
                 mew_parser::PatternElem::Edge(ep) => {
                     let edge_type_id = self
                         .registry
@@ -372,14 +1014,13 @@ impl<'r> QueryPlanner<'r> {
                         None
                     };
 
-                    if let Some(p) = plan {
-                        plan = Some(PlanOp::EdgeJoin {
-                            input: Box::new(p),
-                            edge_type_id,
-                            from_vars: ep.targets.clone(),
-                            edge_var,
-                        });
-                    }
+                    // An edge alone joins on the variables bound before it
+                    plan = Some(PlanOp::EdgeJoin {
+                        input: Box::new(plan.unwrap_or(PlanOp::Empty)),
+                        edge_type_id,
+                        from_vars: ep.targets.clone(),
+                        edge_var,
+                    });
                 }
             }
         }
@@ -482,46 +1123,77 @@ impl<'r> QueryPlanner<'r> {
             };
         }
 
-        // Check if we're returning TERMINAL (need to stop at first node matching UNTIL)
-        let return_terminal = matches!(
-            stmt.return_type,
-            mew_parser::WalkReturnType::Terminal { .. }
-        );
-
-        let plan = PlanOp::TransitiveClosure {
-            start_var: "start".to_string(),
-            start_expr: stmt.from.clone(),
-            edge_types,
-            min_depth,
-            max_depth,
-            direction,
-            until_condition: stmt.until.clone(),
-            return_terminal,
+        let walk = match &stmt.shortest {
+            Some(shortest) => PlanOp::ShortestPaths {
+                start_expr: stmt.from.clone(),
+                target_expr: shortest.to.clone(),
+                edge_types,
+                min_depth,
+                max_depth,
+                direction,
+                weight: shortest.weight.clone(),
+                paths: shortest.paths,
+            },
+            None => PlanOp::TransitiveClosure {
+                start_var: "start".to_string(),
+                start_expr: stmt.from.clone(),
+                edge_types,
+                min_depth,
+                max_depth,
+                direction,
+                until_condition: stmt.until.clone(),
+                // TERMINAL stops at the first node matching UNTIL
+                return_terminal: matches!(
+                    stmt.return_type,
+                    mew_parser::WalkReturnType::Terminal { .. }
+                ),
+            },
         };
 
-        // Determine column names based on return type and alias
-        let columns = match &stmt.return_type {
+        // Project the walk variables the return type names
+        let var = |name: &str| Expr::Var(name.to_string(), Span::default());
+        let projections: Vec<(String, Expr)> = match &stmt.return_type {
             mew_parser::WalkReturnType::Path { alias } => {
-                let col = alias.clone().unwrap_or_else(|| "path".to_string());
-                vec![col]
-            }
-            mew_parser::WalkReturnType::Nodes { alias } => {
-                let col = alias.clone().unwrap_or_else(|| "node".to_string());
-                vec![col, "path".to_string()]
-            }
-            mew_parser::WalkReturnType::Edges { alias } => {
-                let col = alias.clone().unwrap_or_else(|| "edge".to_string());
-                vec![col, "path".to_string()]
+                vec![(
+                    alias.clone().unwrap_or_else(|| "path".to_string()),
+                    var("path"),
+                )]
             }
+            mew_parser::WalkReturnType::Nodes { alias } => vec![
+                (
+                    alias.clone().unwrap_or_else(|| "node".to_string()),
+                    var("node"),
+                ),
+                ("path".to_string(), var("path")),
+            ],
+            mew_parser::WalkReturnType::Edges { alias } => vec![
+                (
+                    alias.clone().unwrap_or_else(|| "edge".to_string()),
+                    var("edge"),
+                ),
+                ("path".to_string(), var("path")),
+            ],
             mew_parser::WalkReturnType::Terminal { alias } => {
-                let col = alias.clone().unwrap_or_else(|| "terminal".to_string());
-                vec![col]
+                vec![(
+                    alias.clone().unwrap_or_else(|| "terminal".to_string()),
+                    var("node"),
+                )]
             }
             mew_parser::WalkReturnType::Projections(projs) => projs
                 .iter()
-                .map(|p| p.alias.clone().unwrap_or_else(|| "expr".to_string()))
+                .map(|p| {
+                    (
+                        p.alias.clone().unwrap_or_else(|| "expr".to_string()),
+                        p.expr.clone(),
+                    )
+                })
                 .collect(),
         };
+        let columns = projections.iter().map(|(name, _)| name.clone()).collect();
+        let plan = PlanOp::Project {
+            input: Box::new(walk),
+            projections,
+        };
 
         Ok(QueryPlan {
             root: plan,
@@ -530,6 +1202,58 @@ impl<'r> QueryPlanner<'r> {
     }
 }
 
+/// Rebuild `expr`, trying `f` on each subexpression outermost first: wherever
+/// it returns a replacement, that is used instead of the subexpression.
+fn map_expr(expr: &Expr, f: &mut impl FnMut(&Expr) -> Option<Expr>) -> Expr {
+    if let Some(replaced) = f(expr) {
+        return replaced;
+    }
+    match expr {
+        Expr::AttrAccess(base, attr, span) => {
+            Expr::AttrAccess(Box::new(map_expr(base, f)), attr.clone(), *span)
+        }
+        Expr::BinaryOp(op, left, right, span) => Expr::BinaryOp(
+            *op,
+            Box::new(map_expr(left, f)),
+            Box::new(map_expr(right, f)),
+            *span,
+        ),
+        Expr::UnaryOp(op, operand, span) => {
+            Expr::UnaryOp(*op, Box::new(map_expr(operand, f)), *span)
+        }
+        Expr::FnCall(fc) => {
+            let mut fc = fc.clone();
+            fc.args = fc.args.iter().map(|arg| map_expr(arg, f)).collect();
+            Expr::FnCall(fc)
+        }
+        Expr::List(items, span) => {
+            Expr::List(items.iter().map(|item| map_expr(item, f)).collect(), *span)
+        }
+        Expr::Map(entries, span) => Expr::Map(
+            entries
+                .iter()
+                .map(|(key, value)| (key.clone(), map_expr(value, f)))
+                .collect(),
+            *span,
+        ),
+        Expr::TypeCheck(inner, type_name, span) => {
+            Expr::TypeCheck(Box::new(map_expr(inner, f)), type_name.clone(), *span)
+        }
+        Expr::Index(base, index, span) => Expr::Index(
+            Box::new(map_expr(base, f)),
+            Box::new(map_expr(index, f)),
+            *span,
+        ),
+        Expr::Quantifier(q) => {
+            let mut q = q.clone();
+            q.list = map_expr(&q.list, f);
+            q.predicate = map_expr(&q.predicate, f);
+            Expr::Quantifier(q)
+        }
+        other => other.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -546,6 +1270,8 @@ mod tests {
         builder
             .add_type("Person")
             .attr(AttrDef::new("name", "String"))
+            .attr(AttrDef::new("home", "Point"))
+            .attr(AttrDef::new("bio", "String").searchable())
             .done()
             .unwrap();
         builder
@@ -571,6 +1297,8 @@ mod tests {
             })],
             where_clause: None,
             optional_matches: vec![],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![mew_parser::Projection {
@@ -580,6 +1308,8 @@ mod tests {
                 }],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: None,
             limit: None,
             offset: None,
@@ -609,6 +1339,8 @@ mod tests {
             })],
             where_clause: None,
             optional_matches: vec![],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![mew_parser::Projection {
@@ -618,6 +1350,8 @@ mod tests {
                 }],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: None,
             limit: Some(10),
             offset: Some(5),
@@ -646,11 +1380,15 @@ mod tests {
             })],
             where_clause: None,
             optional_matches: vec![],
+            nearest: None,
+            stages: Vec::new(),
             return_clause: ReturnClause {
                 distinct: false,
                 projections: vec![],
                 span: Span::default(),
             },
+            group_by: None,
+            having: None,
             order_by: None,
             limit: None,
             offset: None,
@@ -667,4 +1405,73 @@ mod tests {
             QueryError::UnknownType { .. }
         ));
     }
+
+    #[test]
+    fn test_plan_spatial_predicate_uses_index() {
+        // GIVEN a radius predicate on a scanned variable, and a correlated one
+        let registry = test_registry();
+        let planner = QueryPlanner::new(&registry);
+        let parse = |src: &str| match mew_parser::parse_stmt(src).unwrap() {
+            mew_parser::Stmt::Match(m) => m,
+            _ => panic!("Expected MATCH"),
+        };
+        let indexed = parse(
+            "MATCH p: Person, t: Task, owns(p, t) \
+             WHERE t.title = \"x\" AND within_radius(p.home, point(0, 0), $r) RETURN p",
+        );
+        let correlated =
+            parse("MATCH p: Person, q: Person WHERE within_radius(p.home, q.home, 1.0) RETURN p");
+
+        // WHEN
+        let indexed = planner.plan_match(&indexed).unwrap();
+        let correlated = planner.plan_match(&correlated).unwrap();
+
+        // THEN the first scan uses the spatial index and the filter is kept
+        let PlanOp::Project { input, .. } = indexed.root else {
+            panic!("Expected Project");
+        };
+        let PlanOp::Filter { input, .. } = *input else {
+            panic!("Expected Filter");
+        };
+        let PlanOp::EdgeJoin { input, .. } = *input else {
+            panic!("Expected EdgeJoin");
+        };
+        let PlanOp::CrossJoin { left, right } = *input else {
+            panic!("Expected CrossJoin");
+        };
+        assert!(matches!(
+            *left,
+            PlanOp::SpatialScan { ref var, ref attr, region: SpatialRegion::Radius { .. }, .. }
+                if var == "p" && attr == "home"
+        ));
+        assert!(matches!(*right, PlanOp::NodeScan { .. }));
+
+        // THEN a center that depends on another row is not served by the index
+        let plan_text = format!("{:?}", correlated.root);
+        assert!(!plan_text.contains("SpatialScan"));
+    }
+
+    #[test]
+    fn test_plan_search_predicate_uses_text_index() {
+        // GIVEN search predicates on a searchable and a plain string attribute
+        let registry = test_registry();
+        let planner = QueryPlanner::new(&registry);
+        let parse = |src: &str| match mew_parser::parse_stmt(src).unwrap() {
+            mew_parser::Stmt::Match(m) => m,
+            _ => panic!("Expected MATCH"),
+        };
+        let indexed =
+            parse("MATCH p: Person WHERE p.name = $n AND search(p.bio, \"rust\") RETURN p");
+        let plain = parse("MATCH p: Person WHERE search(p.name, \"rust\") RETURN p");
+
+        // WHEN
+        let indexed = planner.plan_match(&indexed).unwrap();
+        let plain = planner.plan_match(&plain).unwrap();
+
+        // THEN only the searchable attribute is served by the text index
+        let indexed_text = format!("{:?}", indexed.root);
+        assert!(indexed_text.contains("TextScan"));
+        assert!(indexed_text.contains("Filter"));
+        assert!(!format!("{:?}", plain.root).contains("TextScan"));
+    }
 }
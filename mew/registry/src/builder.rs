@@ -2,7 +2,7 @@
 
 use crate::{
     AttrDef, Cardinality, ConstraintDef, EdgeParam, EdgeTypeDef, OnKillAction, Registry, RuleDef,
-    SubtypeIndex, TypeDef,
+    SubtypeIndex, TypeDef, DEAD_LETTER_TYPE,
 };
 use mew_core::{EdgeTypeId, TypeId};
 use std::collections::HashMap;
@@ -120,6 +120,7 @@ impl RegistryBuilder {
             hard: true,
             deferred: false,
             condition: condition.into(),
+            source: None,
         }
     }
 
@@ -145,8 +146,26 @@ impl RegistryBuilder {
         }
     }
 
+    /// Declare the Layer 0 types the runtime relies on, unless the ontology
+    /// already declares them. They are added last so user type IDs are unaffected.
+    fn add_layer0_types(&mut self) -> Result<(), RegistryError> {
+        if !self.type_names.contains_key(DEAD_LETTER_TYPE) {
+            self.add_type(DEAD_LETTER_TYPE)
+                .attr(AttrDef::new("original_match", "String").required())
+                .attr(AttrDef::new("failure_reason", "String").required())
+                .attr(AttrDef::new("delivery_attempts", "Int").required())
+                .attr(AttrDef::new("created_at", "Timestamp").required())
+                .attr(AttrDef::new("destination", "String").nullable())
+                .sealed()
+                .done()?;
+        }
+        Ok(())
+    }
+
     /// Build the immutable Registry.
-    pub fn build(self) -> Result<Registry, RegistryError> {
+    pub fn build(mut self) -> Result<Registry, RegistryError> {
+        self.add_layer0_types()?;
+
         // Validate and resolve parent types
         let mut resolved_types = HashMap::new();
         for (id, type_def) in &self.types {
@@ -174,7 +193,7 @@ impl RegistryBuilder {
 
         // Sort and index rules by type (sorted by priority descending)
         let mut rules = self.rules;
-        rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        rules.sort_by_key(|r| std::cmp::Reverse(r.priority));
 
         let mut rules_by_type: HashMap<TypeId, Vec<usize>> = HashMap::new();
         let mut rules_by_edge_type: HashMap<EdgeTypeId, Vec<usize>> = HashMap::new();
@@ -391,6 +410,7 @@ pub struct ConstraintBuilder<'a> {
     hard: bool,
     deferred: bool,
     condition: String,
+    source: Option<String>,
 }
 
 impl<'a> ConstraintBuilder<'a> {
@@ -418,6 +438,12 @@ impl<'a> ConstraintBuilder<'a> {
         self
     }
 
+    /// Keep the ontology source of a declared constraint.
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
     /// Finish building this constraint.
     pub fn done(self) -> Result<u32, RegistryError> {
         // Resolve type ID if specified
@@ -448,6 +474,7 @@ impl<'a> ConstraintBuilder<'a> {
             hard: self.hard,
             deferred: self.deferred,
             condition: self.condition,
+            source: self.source,
         };
 
         self.builder.constraints.push(constraint);
@@ -1,12 +1,15 @@
 //! MEW Registry
 //!
 //! Runtime schema lookup. Single source of truth for types, edges, constraints, rules.
-//! The registry is immutable after construction via RegistryBuilder.
+//! The registry is immutable after construction via RegistryBuilder. It also
+//! holds the signatures of the procedures CALL can run.
 
 mod builder;
+mod procedure;
 mod registry;
 mod types;
 
 pub use builder::{RegistryBuilder, RegistryError};
+pub use procedure::{Procedure, NODE_COLUMN};
 pub use registry::Registry;
 pub use types::*;
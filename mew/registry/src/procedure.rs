@@ -0,0 +1,104 @@
+//! Signatures of the procedures CALL can run.
+
+/// Name of the column holding the node of each row.
+pub const NODE_COLUMN: &str = "node";
+
+/// The signature of a graph algorithm callable from a statement: its
+/// name, options and output columns. The algorithms themselves live in
+/// `mew-algo`.
+///
+/// Every procedure yields one row per node of the graph it runs on: the
+/// node and one value column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Procedure {
+    PageRank,
+    ConnectedComponents,
+    StronglyConnectedComponents,
+    Degree,
+    Betweenness,
+    TriangleCount,
+    LabelPropagation,
+}
+
+impl Procedure {
+    /// Every procedure.
+    pub const ALL: [Procedure; 7] = [
+        Procedure::PageRank,
+        Procedure::ConnectedComponents,
+        Procedure::StronglyConnectedComponents,
+        Procedure::Degree,
+        Procedure::Betweenness,
+        Procedure::TriangleCount,
+        Procedure::LabelPropagation,
+    ];
+
+    /// The procedure with the given qualified name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "algo.pagerank" => Some(Self::PageRank),
+            "algo.connected_components" | "algo.wcc" => Some(Self::ConnectedComponents),
+            "algo.strongly_connected_components" | "algo.scc" => {
+                Some(Self::StronglyConnectedComponents)
+            }
+            "algo.degree" => Some(Self::Degree),
+            "algo.betweenness" => Some(Self::Betweenness),
+            "algo.triangle_count" | "algo.triangles" => Some(Self::TriangleCount),
+            "algo.label_propagation" => Some(Self::LabelPropagation),
+            _ => None,
+        }
+    }
+
+    /// The qualified name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PageRank => "algo.pagerank",
+            Self::ConnectedComponents => "algo.connected_components",
+            Self::StronglyConnectedComponents => "algo.strongly_connected_components",
+            Self::Degree => "algo.degree",
+            Self::Betweenness => "algo.betweenness",
+            Self::TriangleCount => "algo.triangle_count",
+            Self::LabelPropagation => "algo.label_propagation",
+        }
+    }
+
+    /// The name and value type of the value column.
+    pub fn value_column(self) -> (&'static str, &'static str) {
+        match self {
+            Self::PageRank | Self::Betweenness => ("score", "Float"),
+            Self::ConnectedComponents | Self::StronglyConnectedComponents => ("component", "Int"),
+            Self::Degree => ("degree", "Int"),
+            Self::TriangleCount => ("triangles", "Int"),
+            Self::LabelPropagation => ("community", "Int"),
+        }
+    }
+
+    /// The names and value types of the options.
+    pub fn options(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::PageRank => &[("damping", "Float"), ("iterations", "Int")],
+            Self::Degree => &[("direction", "String")],
+            Self::LabelPropagation => &[("iterations", "Int")],
+            Self::ConnectedComponents
+            | Self::StronglyConnectedComponents
+            | Self::Betweenness
+            | Self::TriangleCount => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_procedure_names_round_trip() {
+        for procedure in Procedure::ALL {
+            assert_eq!(Procedure::from_name(procedure.name()), Some(procedure));
+        }
+        assert_eq!(
+            Procedure::from_name("ALGO.SCC"),
+            Some(Procedure::StronglyConnectedComponents)
+        );
+        assert_eq!(Procedure::from_name("algo.nope"), None);
+    }
+}
@@ -34,6 +34,9 @@ pub struct Registry {
 
     /// Precomputed subtype relationships.
     subtype_index: SubtypeIndex,
+
+    /// Ontology source the registry was compiled from, if any.
+    source: Option<String>,
 }
 
 impl Registry {
@@ -64,9 +67,22 @@ impl Registry {
             rules_by_type,
             rules_by_edge_type,
             subtype_index,
+            source: None,
         }
     }
 
+    /// Record the ontology source the registry was compiled from.
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// The ontology source the registry was compiled from; `None` for a
+    /// registry built with [`RegistryBuilder`](crate::RegistryBuilder).
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
     // ==================== Type Lookups ====================
 
     /// Get a type definition by name.
@@ -284,6 +300,7 @@ impl Default for Registry {
             rules_by_type: HashMap::new(),
             rules_by_edge_type: HashMap::new(),
             subtype_index: SubtypeIndex::new(),
+            source: None,
         }
     }
 }
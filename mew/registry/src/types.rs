@@ -3,6 +3,10 @@
 use mew_core::{EdgeTypeId, TypeId, Value};
 use std::collections::{HashMap, HashSet};
 
+/// Layer 0 type recording a consume-mode message that failed permanently.
+/// Every registry declares it; see `RegistryBuilder::build`.
+pub const DEAD_LETTER_TYPE: &str = "_DeadLetter";
+
 /// Attribute definition within a type.
 #[derive(Debug, Clone)]
 pub struct AttrDef {
@@ -18,6 +22,8 @@ pub struct AttrDef {
     pub unique: bool,
     /// Whether this attribute is readonly (cannot be modified after creation).
     pub readonly: bool,
+    /// Whether this attribute is kept in a full-text index.
+    pub searchable: bool,
     /// Default value if not provided.
     pub default: Option<Value>,
     /// Minimum value constraint (for Int, Float).
@@ -45,6 +51,7 @@ impl AttrDef {
             nullable: false,
             unique: false,
             readonly: false,
+            searchable: false,
             default: None,
             min: None,
             max: None,
@@ -76,6 +83,11 @@ impl AttrDef {
         self
     }
 
+    pub fn searchable(mut self) -> Self {
+        self.searchable = true;
+        self
+    }
+
     pub fn with_default(mut self, value: Value) -> Self {
         self.default = Some(value);
         self
@@ -160,7 +172,8 @@ impl TypeDef {
 }
 
 /// Cardinality constraint for an edge parameter.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The default is 0..* (no constraint).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct Cardinality {
     /// Minimum count (default 0).
     pub min: u32,
@@ -168,13 +181,6 @@ pub struct Cardinality {
     pub max: Option<u32>,
 }
 
-impl Default for Cardinality {
-    fn default() -> Self {
-        // Default is 0..* (no constraint)
-        Self { min: 0, max: None }
-    }
-}
-
 impl Cardinality {
     /// Create a cardinality constraint.
     pub fn new(min: u32, max: Option<u32>) -> Self {
@@ -280,6 +286,9 @@ pub struct ConstraintDef {
     /// Condition expression (serialized or stored somehow).
     /// For now, we store a string representation.
     pub condition: String,
+    /// Ontology source of a declared `constraint`, parsed again to
+    /// evaluate its pattern and condition.
+    pub source: Option<String>,
 }
 
 impl ConstraintDef {
@@ -292,6 +301,7 @@ impl ConstraintDef {
             hard: true,
             deferred: false,
             condition: condition.into(),
+            source: None,
         }
     }
 
@@ -314,6 +324,11 @@ impl ConstraintDef {
         self.deferred = true;
         self
     }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
 }
 
 /// Rule definition.
@@ -2,6 +2,8 @@
 
 use std::io::{self, BufRead, Write};
 
+use mew_parser::Stmt;
+
 /// Collect a block (e.g., ontology definition) from an iterator of lines.
 ///
 /// This reads lines until the braces are balanced.
@@ -141,3 +143,18 @@ pub fn extract_ontology_source(block: &str) -> Result<String, String> {
 pub fn should_continue_parse(err: &str) -> bool {
     err.contains("unexpected end of input") || err.contains("found end of input")
 }
+
+/// Check if a parsed query may go on in the next line: a MATCH or UNION
+/// without ORDER BY can still take an ORDER BY or another UNION branch.
+pub fn awaits_more_lines(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Match(match_stmt) => match_stmt.order_by.is_none(),
+        Stmt::Union(union_stmt) => union_stmt.order_by.is_none(),
+        _ => false,
+    }
+}
+
+/// Check if a line adds a UNION branch to the query before it.
+pub fn continues_union(line: &str) -> bool {
+    line.to_uppercase().starts_with("UNION")
+}
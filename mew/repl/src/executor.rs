@@ -1,22 +1,45 @@
 //! Statement execution for the REPL.
 
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 
-use mew_core::{messages, EntityId};
+use mew_analyzer::{Analyzer, Type};
+use mew_core::{messages, EntityId, Value};
 use mew_graph::Graph;
+use mew_import::BulkLoader;
 use mew_mutation::MutationExecutor;
 use mew_parser::{
-    ExplainStmt, InspectStmt, MatchMutateStmt, MatchStmt, MatchWalkStmt, MutationAction,
-    ProfileStmt, Stmt, Target, TargetRef, TxnStmt, WalkStmt,
+    parse_stmt, CallStmt, DumpStmt, ExplainStmt, ImportStmt, InspectStmt, MatchMutateStmt,
+    MatchStmt, MatchWalkStmt, MutationAction, ProfileStmt, Stmt, Target, TargetRef, TxnStmt,
+    UnionStmt, UnwindStmt, WalkStmt,
 };
-use mew_pattern::{target, Binding, Bindings};
-use mew_query::{QueryExecutor, QueryResults};
+use mew_pattern::{target, Binding, Bindings, Evaluator};
+use mew_query::{QueryExecutor, QueryResults, QueryRow};
 use mew_registry::Registry;
+use mew_session::dump::write_dump;
+use mew_session::export::{export, ExportFormat, Subgraph};
+use mew_session::{convert_query_result, StatementResult, WireMessage};
+use mew_transaction::UndoLog;
 
-use crate::format::format_value;
+use crate::format::{format_value, OutputFormat};
 
-/// Format query results as a table string.
-fn format_results(results: &QueryResults, empty_msg: &str, count_label: &str) -> String {
+/// Format query results in the given output format.
+///
+/// `types` are the analyzer's column types, if known; JSON output reports
+/// them (or types inferred from the values) alongside the rows.
+fn format_results(
+    results: &QueryResults,
+    format: OutputFormat,
+    types: &[String],
+    empty_msg: &str,
+    count_label: &str,
+) -> String {
+    if format == OutputFormat::Json {
+        let result = StatementResult::Query(convert_query_result(results, types));
+        return WireMessage::from(result).to_json().to_string();
+    }
     if results.is_empty() {
         return format!("({})", empty_msg);
     }
@@ -49,20 +72,82 @@ fn format_results(results: &QueryResults, empty_msg: &str, count_label: &str) ->
     output
 }
 
+/// Analyze a query with the REPL's bindings in scope, returning its
+/// column types.
+fn analyze_query(
+    registry: &Registry,
+    graph: &Graph,
+    bindings: &HashMap<String, EntityId>,
+    stmt: &Stmt,
+) -> Result<Vec<String>, String> {
+    let mut analyzer = Analyzer::new(registry);
+    for (name, entity) in bindings {
+        let ty = match entity {
+            EntityId::Node(id) => graph
+                .get_node(*id)
+                .map_or(Type::AnyNodeRef, |node| Type::NodeRef(node.type_id)),
+            EntityId::Edge(id) => graph
+                .get_edge(*id)
+                .map_or(Type::AnyEdgeRef, |edge| Type::EdgeRef(edge.type_id)),
+        };
+        analyzer.bind(name.clone(), ty);
+    }
+    analyzer
+        .analyze_stmt(stmt)
+        .map_err(|e| format!("Analysis error: {}", e))?;
+    Ok(analyzer
+        .column_types()
+        .iter()
+        .map(|t| t.describe(registry))
+        .collect())
+}
+
 /// Execute a MATCH statement and return formatted results.
 pub fn execute_match(
     registry: &Registry,
     graph: &Graph,
     bindings: &HashMap<String, EntityId>,
     stmt: &MatchStmt,
+    format: OutputFormat,
 ) -> Result<String, String> {
+    let types = analyze_query(registry, graph, bindings, &Stmt::Match(stmt.clone()))?;
     let executor = QueryExecutor::new(registry, graph);
     let initial_bindings = to_pattern_bindings(bindings);
     let results = executor
         .execute_match_with_bindings(stmt, &initial_bindings)
         .map_err(|e| format!("Query error: {}", e))?;
 
-    Ok(format_results(&results, "no results", "rows"))
+    Ok(format_results(
+        &results,
+        format,
+        &types,
+        "no results",
+        "rows",
+    ))
+}
+
+/// Execute a UNION of MATCH statements and return formatted results.
+pub fn execute_union(
+    registry: &Registry,
+    graph: &Graph,
+    bindings: &HashMap<String, EntityId>,
+    stmt: &UnionStmt,
+    format: OutputFormat,
+) -> Result<String, String> {
+    let types = analyze_query(registry, graph, bindings, &Stmt::Union(stmt.clone()))?;
+    let executor = QueryExecutor::new(registry, graph);
+    let initial_bindings = to_pattern_bindings(bindings);
+    let results = executor
+        .execute_union_with_bindings(stmt, Some(&initial_bindings))
+        .map_err(|e| format!("Query error: {}", e))?;
+
+    Ok(format_results(
+        &results,
+        format,
+        &types,
+        "no results",
+        "rows",
+    ))
 }
 
 /// Execute a WALK statement and return formatted results.
@@ -71,13 +156,20 @@ pub fn execute_walk(
     graph: &Graph,
     _bindings: &HashMap<String, EntityId>,
     stmt: &WalkStmt,
+    format: OutputFormat,
 ) -> Result<String, String> {
     let executor = QueryExecutor::new(registry, graph);
     let results = executor
         .execute_walk(stmt)
         .map_err(|e| format!("Walk error: {}", e))?;
 
-    Ok(format_results(&results, "no paths found", "paths"))
+    Ok(format_results(
+        &results,
+        format,
+        &[],
+        "no paths found",
+        "paths",
+    ))
 }
 
 /// Execute a MATCH...WALK compound statement and return formatted results.
@@ -86,13 +178,20 @@ pub fn execute_match_walk(
     graph: &Graph,
     _bindings: &HashMap<String, EntityId>,
     stmt: &MatchWalkStmt,
+    format: OutputFormat,
 ) -> Result<String, String> {
     let executor = QueryExecutor::new(registry, graph);
     let results = executor
         .execute_match_walk(stmt)
         .map_err(|e| format!("Walk error: {}", e))?;
 
-    Ok(format_results(&results, "no paths found", "paths"))
+    Ok(format_results(
+        &results,
+        format,
+        &[],
+        "no paths found",
+        "paths",
+    ))
 }
 
 /// Execute an INSPECT statement and return formatted results.
@@ -193,7 +292,14 @@ pub fn execute_spawn(
         for (i, item) in stmt.items.iter().enumerate() {
             if let Some(&node_id) = created.node_ids.get(i) {
                 bindings.insert(item.var.clone(), node_id.into());
-                messages.push(format!("Created {} with id {}", item.var, node_id.raw()));
+                let action = if created.updated.contains(&node_id) {
+                    "Updated"
+                } else if created.existing.contains(&node_id) {
+                    "Found"
+                } else {
+                    "Created"
+                };
+                messages.push(format!("{} {} with id {}", action, item.var, node_id.raw()));
             }
         }
         if !messages.is_empty() {
@@ -312,30 +418,198 @@ pub fn execute_set(
 }
 
 /// Execute a transaction statement.
-pub fn execute_txn(in_transaction: &mut bool, stmt: &TxnStmt) -> Result<String, String> {
+///
+/// An open transaction is an undo log over the graph: ROLLBACK and
+/// ROLLBACK TO replay it backwards.
+pub fn execute_txn(
+    graph: &mut Graph,
+    txn: &mut Option<UndoLog>,
+    stmt: &TxnStmt,
+) -> Result<String, String> {
+    let no_transaction = || "No transaction active".to_string();
     match stmt {
         TxnStmt::Begin { .. } => {
-            if *in_transaction {
+            if txn.is_some() {
                 return Err("Transaction already active".to_string());
             }
-            *in_transaction = true;
+            *txn = Some(UndoLog::begin(graph));
             Ok("BEGIN".to_string())
         }
         TxnStmt::Commit => {
-            if !*in_transaction {
-                return Err("No transaction active".to_string());
-            }
-            *in_transaction = false;
+            txn.take().ok_or_else(no_transaction)?.commit(graph);
             Ok("COMMIT".to_string())
         }
         TxnStmt::Rollback => {
-            if !*in_transaction {
-                return Err("No transaction active".to_string());
-            }
-            *in_transaction = false;
+            txn.take()
+                .ok_or_else(no_transaction)?
+                .rollback(graph)
+                .map_err(|e| format!("Rollback error: {}", e))?;
             Ok("ROLLBACK".to_string())
         }
+        TxnStmt::Savepoint(name) => {
+            txn.as_mut()
+                .ok_or_else(no_transaction)?
+                .savepoint(graph, name);
+            Ok(format!("SAVEPOINT {}", name))
+        }
+        TxnStmt::RollbackTo(name) => {
+            txn.as_mut()
+                .ok_or_else(no_transaction)?
+                .rollback_to(graph, name)
+                .map_err(|e| e.to_string())?;
+            Ok(format!("ROLLBACK TO {}", name))
+        }
+        TxnStmt::Release(name) => {
+            txn.as_mut()
+                .ok_or_else(no_transaction)?
+                .release(name)
+                .map_err(|e| e.to_string())?;
+            Ok(format!("RELEASE {}", name))
+        }
+    }
+}
+
+/// Execute an IMPORT statement, listing the rows that were not loaded.
+pub fn execute_import(
+    registry: &Registry,
+    graph: &mut Graph,
+    stmt: &ImportStmt,
+) -> Result<String, String> {
+    Analyzer::new(registry)
+        .analyze_stmt(&Stmt::Import(stmt.clone()))
+        .map_err(|e| format!("Analysis error: {}", e))?;
+
+    let report = BulkLoader::new(registry, graph)
+        .execute(stmt, &Bindings::new())
+        .map_err(|e| format!("Import error: {}", e))?;
+
+    let mut lines = vec![format!(
+        "Imported {} nodes, {} edges from {} rows ({} rejected)",
+        report.nodes_created,
+        report.edges_created,
+        report.rows,
+        report.rejected.len()
+    )];
+    for row in &report.rejected {
+        lines.push(format!("  line {}: {}", row.line, row.reason));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Execute a DUMP statement.
+pub fn execute_dump(
+    registry: &Registry,
+    graph: &Graph,
+    bindings: &HashMap<String, EntityId>,
+    stmt: &DumpStmt,
+) -> Result<String, String> {
+    Analyzer::new(registry)
+        .analyze_stmt(&Stmt::Dump(stmt.clone()))
+        .map_err(|e| format!("Analysis error: {}", e))?;
+
+    let path = match Evaluator::new(registry)
+        .eval(&stmt.path, &to_pattern_bindings(bindings), graph)
+        .map_err(|e| format!("Evaluation error: {}", e))?
+    {
+        Value::String(path) => path,
+        other => return Err(format!("DUMP TO needs a file path, got {}", other)),
+    };
+
+    dump_to_file(registry, graph, Path::new(&path))
+}
+
+/// Execute a CALL statement, writing the results back if it asks to.
+pub fn execute_call(
+    registry: &Registry,
+    graph: &mut Graph,
+    bindings: &HashMap<String, EntityId>,
+    stmt: &CallStmt,
+    format: OutputFormat,
+) -> Result<String, String> {
+    let mut analyzer = Analyzer::new(registry);
+    analyzer
+        .analyze_stmt(&Stmt::Call(stmt.clone()))
+        .map_err(|e| format!("Analysis error: {}", e))?;
+    let types: Vec<String> = analyzer
+        .column_types()
+        .iter()
+        .map(|t| t.describe(registry))
+        .collect();
+
+    let output = mew_algo::call(registry, graph, stmt, &to_pattern_bindings(bindings))
+        .map_err(|e| format!("Call error: {}", e))?;
+    let mut lines = Vec::new();
+    if let Some(attr) = &stmt.write {
+        let written = mew_algo::write_results(registry, graph, attr, &output.results)
+            .map_err(|e| format!("Call error: {}", e))?;
+        lines.push(format!("Updated {} nodes", written));
+    }
+    if !output.columns.is_empty() {
+        let mut results = QueryResults::with_columns(output.columns.clone());
+        for row in output.rows {
+            results.push(QueryRow::from_columns(output.columns.clone(), row));
+        }
+        lines.push(format_results(&results, format, &types, "no nodes", "rows"));
     }
+    Ok(lines.join("\n"))
+}
+
+/// Write the ontology and the graph to a file as a replayable script.
+pub fn dump_to_file(registry: &Registry, graph: &Graph, path: &Path) -> Result<String, String> {
+    let mut file =
+        BufWriter::new(File::create(path).map_err(|e| format!("Failed to create file: {}", e))?);
+    write_dump(registry, graph, &mut file)
+        .and_then(|_| Ok(file.flush()?))
+        .map_err(|e| e.to_string())?;
+    Ok(format!(
+        "Dumped {} nodes, {} edges to {}",
+        graph.node_count(),
+        graph.edge_count(),
+        path.display()
+    ))
+}
+
+/// Export the graph, or the entities a MATCH returns, to a file in the
+/// format its extension names.
+pub fn execute_export(
+    registry: &Registry,
+    graph: &Graph,
+    bindings: &HashMap<String, EntityId>,
+    path: &Path,
+    query: Option<&str>,
+) -> Result<String, String> {
+    let format = ExportFormat::for_path(path).ok_or_else(|| {
+        format!(
+            "Unknown export format for '{}': use .json, .graphml or .dot",
+            path.display()
+        )
+    })?;
+
+    let subgraph = match query {
+        None => Subgraph::full(graph),
+        Some(query) => {
+            let stmt = match parse_stmt(query).map_err(|e| format!("Parse error: {}", e))? {
+                Stmt::Match(stmt) => stmt,
+                _ => return Err("Only a MATCH can select what to export".to_string()),
+            };
+            let results = QueryExecutor::new(registry, graph)
+                .execute_match_with_bindings(&stmt, &to_pattern_bindings(bindings))
+                .map_err(|e| format!("Query error: {}", e))?;
+            Subgraph::from_values(graph, results.iter().flat_map(|row| row.values()))
+        }
+    };
+
+    let mut file =
+        BufWriter::new(File::create(path).map_err(|e| format!("Failed to create file: {}", e))?);
+    export(registry, graph, &subgraph, format, &mut file)
+        .and_then(|_| file.flush())
+        .map_err(|e| format!("Export error: {}", e))?;
+    Ok(format!(
+        "Exported {} nodes, {} edges to {}",
+        subgraph.nodes.len(),
+        subgraph.edges.len(),
+        path.display()
+    ))
 }
 
 /// Resolve a target to an entity ID.
@@ -452,113 +726,191 @@ pub fn execute_match_mutate(
             }
         }
 
-        // Execute each mutation with the current bindings
-        for mutation in &stmt.mutations {
-            match mutation {
-                MutationAction::Spawn(spawn_stmt) => {
-                    use mew_mutation::MutationOutcome;
-                    let pattern_bindings = to_pattern_bindings(&local_bindings);
-                    let mut executor = MutationExecutor::new(registry, graph);
-                    let result = executor
-                        .execute_spawn(spawn_stmt, &pattern_bindings)
-                        .map_err(|e| format!("Spawn error: {}", e))?;
-
-                    // Handle created nodes - for chained spawns, bind each variable
-                    if let MutationOutcome::Created(ref created) = result {
-                        for (i, item) in spawn_stmt.items.iter().enumerate() {
-                            if let Some(&node_id) = created.node_ids.get(i) {
-                                local_bindings.insert(item.var.clone(), node_id.into());
-                                total_nodes += 1;
-                            }
-                        }
-                    }
-                }
-                MutationAction::Link(link_stmt) => {
-                    let mut targets = Vec::new();
-                    for target_ref in &link_stmt.targets {
-                        let entity_id = resolve_or_spawn_target_ref(
-                            registry,
-                            graph,
-                            &mut local_bindings,
-                            target_ref,
-                        )?;
-                        targets.push(entity_id);
-                    }
+        execute_mutation_actions(
+            registry,
+            graph,
+            &mut local_bindings,
+            &Bindings::new(),
+            &stmt.mutations,
+            &mut total_nodes,
+            &mut total_edges,
+        )?;
+    }
 
-                    let mut executor = MutationExecutor::new(registry, graph);
-                    let result = executor
-                        .execute_link(link_stmt, targets)
-                        .map_err(|e| format!("Link error: {}", e))?;
+    Ok(format!(
+        "Affected {} nodes and {} edges",
+        total_nodes, total_edges
+    ))
+}
 
-                    if let Some(ref var) = link_stmt.var {
-                        if let Some(edge_id) = result.created_edge() {
-                            local_bindings.insert(var.clone(), edge_id.into());
-                        }
-                    }
+/// Execute an UNWIND statement: the mutations run once per list element.
+pub fn execute_unwind(
+    registry: &Registry,
+    graph: &mut Graph,
+    bindings: &HashMap<String, EntityId>,
+    stmt: &UnwindStmt,
+) -> Result<String, String> {
+    let evaluator = Evaluator::new(registry);
+    let elements = match evaluator
+        .eval(&stmt.list, &to_pattern_bindings(bindings), graph)
+        .map_err(|e| format!("Eval error: {}", e))?
+    {
+        Value::List(elements) => elements,
+        Value::Null => Vec::new(),
+        other => {
+            return Err(format!(
+                "{}, got {}",
+                messages::ERR_UNWIND_REQUIRES_LIST,
+                other.type_name()
+            ))
+        }
+    };
 
-                    if result.created_edge().is_some() {
-                        total_edges += 1;
-                    }
-                }
-                MutationAction::Set(set_stmt) => {
-                    let target_id = resolve_target(&set_stmt.target, &local_bindings)?;
-                    let pb = Bindings::new();
-                    let mut executor = MutationExecutor::new(registry, graph);
-
-                    use mew_mutation::MutationOutcome;
-                    if let Some(node_id) = target_id.as_node() {
-                        let result = executor
-                            .execute_set(set_stmt, vec![node_id], &pb)
-                            .map_err(|e| format!("Set error: {}", e))?;
-                        if let MutationOutcome::Updated(ref u) = result {
-                            total_nodes += u.node_ids.len();
-                        }
-                    } else if let Some(edge_id) = target_id.as_edge() {
-                        let result = executor
-                            .execute_set_edge(set_stmt, vec![edge_id], &pb)
-                            .map_err(|e| format!("Set error: {}", e))?;
-                        if let MutationOutcome::Updated(ref u) = result {
-                            total_edges += u.edge_ids.len();
+    let mut total_nodes = 0usize;
+    let mut total_edges = 0usize;
+    for element in elements {
+        let mut local_bindings = bindings.clone();
+        let mut values = Bindings::new();
+        match element {
+            Value::NodeRef(node_id) => {
+                local_bindings.insert(stmt.var.clone(), node_id.into());
+            }
+            Value::EdgeRef(edge_id) => {
+                local_bindings.insert(stmt.var.clone(), edge_id.into());
+            }
+            value => {
+                local_bindings.remove(&stmt.var);
+                values.insert(stmt.var.clone(), Binding::Value(value));
+            }
+        }
+
+        execute_mutation_actions(
+            registry,
+            graph,
+            &mut local_bindings,
+            &values,
+            &stmt.mutations,
+            &mut total_nodes,
+            &mut total_edges,
+        )?;
+    }
+
+    Ok(format!(
+        "Affected {} nodes and {} edges",
+        total_nodes, total_edges
+    ))
+}
+
+/// Execute the mutations of a compound statement for one row, adding to the
+/// affected node and edge totals.
+fn execute_mutation_actions(
+    registry: &Registry,
+    graph: &mut Graph,
+    local_bindings: &mut HashMap<String, EntityId>,
+    values: &Bindings,
+    mutations: &[MutationAction],
+    total_nodes: &mut usize,
+    total_edges: &mut usize,
+) -> Result<(), String> {
+    for mutation in mutations {
+        match mutation {
+            MutationAction::Spawn(spawn_stmt) => {
+                use mew_mutation::MutationOutcome;
+                let mut pattern_bindings = to_pattern_bindings(local_bindings);
+                pattern_bindings.merge(values);
+                let mut executor = MutationExecutor::new(registry, graph);
+                let result = executor
+                    .execute_spawn(spawn_stmt, &pattern_bindings)
+                    .map_err(|e| format!("Spawn error: {}", e))?;
+
+                // Handle created nodes - for chained spawns, bind each variable
+                if let MutationOutcome::Created(ref created) = result {
+                    for (i, item) in spawn_stmt.items.iter().enumerate() {
+                        if let Some(&node_id) = created.node_ids.get(i) {
+                            local_bindings.insert(item.var.clone(), node_id.into());
+                            *total_nodes += 1;
                         }
-                    } else {
-                        return Err(messages::ERR_SET_REQUIRES_NODE.to_string());
                     }
                 }
-                MutationAction::Kill(kill_stmt) => {
-                    let target_id = resolve_target(&kill_stmt.target, &local_bindings)?;
-                    let node_id = target_id
-                        .as_node()
-                        .ok_or_else(|| messages::ERR_KILL_REQUIRES_NODE.to_string())?;
+            }
+            MutationAction::Link(link_stmt) => {
+                let mut targets = Vec::new();
+                for target_ref in &link_stmt.targets {
+                    let entity_id =
+                        resolve_or_spawn_target_ref(registry, graph, local_bindings, target_ref)?;
+                    targets.push(entity_id);
+                }
 
-                    let mut executor = MutationExecutor::new(registry, graph);
-                    let result = executor
-                        .execute_kill(kill_stmt, node_id)
-                        .map_err(|e| format!("Kill error: {}", e))?;
+                let mut executor = MutationExecutor::new(registry, graph);
+                let result = executor
+                    .execute_link(link_stmt, targets)
+                    .map_err(|e| format!("Link error: {}", e))?;
 
-                    total_nodes += result.deleted_nodes();
-                    total_edges += result.deleted_edges();
+                if let Some(ref var) = link_stmt.var {
+                    if let Some(edge_id) = result.created_edge() {
+                        local_bindings.insert(var.clone(), edge_id.into());
+                    }
                 }
-                MutationAction::Unlink(unlink_stmt) => {
-                    let target_id = resolve_target(&unlink_stmt.target, &local_bindings)?;
-                    let edge_id = target_id
-                        .as_edge()
-                        .ok_or_else(|| messages::ERR_UNLINK_REQUIRES_EDGE.to_string())?;
 
-                    let mut executor = MutationExecutor::new(registry, graph);
+                if result.created_edge().is_some() {
+                    *total_edges += 1;
+                }
+            }
+            MutationAction::Set(set_stmt) => {
+                let target_id = resolve_target(&set_stmt.target, local_bindings)?;
+                let mut pb = to_pattern_bindings(local_bindings);
+                pb.merge(values);
+                let mut executor = MutationExecutor::new(registry, graph);
+
+                use mew_mutation::MutationOutcome;
+                if let Some(node_id) = target_id.as_node() {
                     let result = executor
-                        .execute_unlink(unlink_stmt, edge_id)
-                        .map_err(|e| format!("Unlink error: {}", e))?;
-
-                    total_edges += result.deleted_edges();
+                        .execute_set(set_stmt, vec![node_id], &pb)
+                        .map_err(|e| format!("Set error: {}", e))?;
+                    if let MutationOutcome::Updated(ref u) = result {
+                        *total_nodes += u.node_ids.len();
+                    }
+                } else if let Some(edge_id) = target_id.as_edge() {
+                    let result = executor
+                        .execute_set_edge(set_stmt, vec![edge_id], &pb)
+                        .map_err(|e| format!("Set error: {}", e))?;
+                    if let MutationOutcome::Updated(ref u) = result {
+                        *total_edges += u.edge_ids.len();
+                    }
+                } else {
+                    return Err(messages::ERR_SET_REQUIRES_NODE.to_string());
                 }
             }
+            MutationAction::Kill(kill_stmt) => {
+                let target_id = resolve_target(&kill_stmt.target, local_bindings)?;
+                let node_id = target_id
+                    .as_node()
+                    .ok_or_else(|| messages::ERR_KILL_REQUIRES_NODE.to_string())?;
+
+                let mut executor = MutationExecutor::new(registry, graph);
+                let result = executor
+                    .execute_kill(kill_stmt, node_id)
+                    .map_err(|e| format!("Kill error: {}", e))?;
+
+                *total_nodes += result.deleted_nodes();
+                *total_edges += result.deleted_edges();
+            }
+            MutationAction::Unlink(unlink_stmt) => {
+                let target_id = resolve_target(&unlink_stmt.target, local_bindings)?;
+                let edge_id = target_id
+                    .as_edge()
+                    .ok_or_else(|| messages::ERR_UNLINK_REQUIRES_EDGE.to_string())?;
+
+                let mut executor = MutationExecutor::new(registry, graph);
+                let result = executor
+                    .execute_unlink(unlink_stmt, edge_id)
+                    .map_err(|e| format!("Unlink error: {}", e))?;
+
+                *total_edges += result.deleted_edges();
+            }
         }
     }
-
-    Ok(format!(
-        "Affected {} nodes and {} edges",
-        total_nodes, total_edges
-    ))
+    Ok(())
 }
 
 /// Execute an EXPLAIN statement - returns the query plan without executing.
@@ -578,6 +930,13 @@ pub fn execute_explain(
                 Err(e) => format!("Plan error: {}", e),
             }
         }
+        Stmt::Union(u) => {
+            let planner = QueryPlanner::new(registry);
+            match planner.plan_union(u) {
+                Ok(plan) => format!("{:#?}", plan),
+                Err(e) => format!("Plan error: {}", e),
+            }
+        }
         Stmt::Walk(w) => {
             let planner = QueryPlanner::new(registry);
             match planner.plan_walk(w) {
@@ -599,22 +958,36 @@ pub fn execute_profile(
     registry: &Registry,
     graph: &mut Graph,
     stmt: &ProfileStmt,
+    format: OutputFormat,
 ) -> Result<String, String> {
     // Execute the inner statement and return its results
     match stmt.statement.as_ref() {
         Stmt::Match(m) => {
+            let types = analyze_query(registry, graph, &HashMap::new(), stmt.statement.as_ref())?;
             let executor = QueryExecutor::new(registry, graph);
             let results = executor
                 .execute_match(m)
                 .map_err(|e| format!("Query error: {}", e))?;
-            Ok(format_results(&results, "no results", "rows"))
+            Ok(format_results(
+                &results,
+                format,
+                &types,
+                "no results",
+                "rows",
+            ))
         }
         Stmt::Walk(w) => {
             let executor = QueryExecutor::new(registry, graph);
             let results = executor
                 .execute_walk(w)
                 .map_err(|e| format!("Walk error: {}", e))?;
-            Ok(format_results(&results, "no paths found", "paths"))
+            Ok(format_results(
+                &results,
+                format,
+                &[],
+                "no paths found",
+                "paths",
+            ))
         }
         other => Ok(format!(
             "PROFILE not supported for {:?}",
@@ -2,6 +2,16 @@
 
 use mew_core::Value;
 
+/// How query results are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned text table.
+    #[default]
+    Table,
+    /// One wire-encoded JSON message per result (see `mew_session::wire`).
+    Json,
+}
+
 /// Format a value for display.
 pub fn format_value(v: &Value) -> String {
     match v {
@@ -12,12 +22,22 @@ pub fn format_value(v: &Value) -> String {
         Value::String(s) => format!("\"{}\"", s),
         Value::Timestamp(t) => format!("@{}", t),
         Value::Duration(d) => format!("{}ms", d),
+        Value::Bytes(b) => format!("0x{}", mew_core::to_hex(b)),
+        Value::Hash(h) => format!("0h{}", mew_core::to_hex(h)),
+        Value::Vector(_) | Value::Point(_) => v.to_string(),
         Value::NodeRef(id) => format!("node#{}", id.raw()),
         Value::EdgeRef(id) => format!("edge#{}", id.raw()),
         Value::List(items) => {
             let formatted: Vec<String> = items.iter().map(format_value).collect();
             format!("[{}]", formatted.join(", "))
         }
+        Value::Map(entries) => {
+            let formatted: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| format!("{}: {}", k, format_value(v)))
+                .collect();
+            format!("{{{}}}", formatted.join(", "))
+        }
     }
 }
 
@@ -25,9 +45,12 @@ pub fn format_value(v: &Value) -> String {
 pub fn print_help() {
     println!("MEW REPL Commands:");
     println!("  \\i <file>      Load and execute a file");
+    println!("  \\import <Type> <file>  Bulk load a CSV or JSON Lines file");
+    println!("  \\export <file> [MATCH ...]  Export to .json, .graphml or .dot");
     println!("  \\dt            Show types");
     println!("  \\dg            Show graph stats");
     println!("  verbose        Toggle verbose mode");
+    println!("  json           Toggle JSON output of query results");
     println!("  help, \\h       Show this help");
     println!("  quit, \\q       Exit");
     println!();
@@ -38,7 +61,13 @@ pub fn print_help() {
     println!("  KILL ...       Delete a node");
     println!("  UNLINK ...     Delete an edge");
     println!("  SET ...        Update attributes");
+    println!("  IMPORT ...     Bulk load nodes or edges from a file");
+    println!("  DUMP TO \"f\"   Write the database to f as a script");
+    println!("  CALL algo.x(e)  Run a graph algorithm over edges of type e");
     println!("  BEGIN          Start transaction");
     println!("  COMMIT         Commit transaction");
     println!("  ROLLBACK       Rollback transaction");
+    println!("  SAVEPOINT n    Mark a point to roll back to");
+    println!("  ROLLBACK TO n  Undo changes since savepoint n");
+    println!("  RELEASE n      Forget savepoint n");
 }
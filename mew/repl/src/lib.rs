@@ -13,5 +13,5 @@ mod executor;
 mod format;
 mod repl;
 
-pub use format::{format_value, print_help};
+pub use format::{format_value, print_help, OutputFormat};
 pub use repl::Repl;
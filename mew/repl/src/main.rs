@@ -1,43 +1,104 @@
 //! MEW REPL - Read-Eval-Print-Loop for MEW hypergraph database.
 //!
 //! This is the entry point for the MEW REPL binary.
+//!
+//! `mew dump <out> [files...]` runs the files (or stdin) and writes the
+//! resulting database to `<out>` as a script that restores it.
 
 use std::env;
 use std::io::{self, IsTerminal, Read};
 use std::path::Path;
 
-use mew_repl::Repl;
+use mew_repl::{OutputFormat, Repl};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
     let mut repl = Repl::new();
+    let mut loaded_files = false;
+    let mut export_path = None;
+    let mut dump_path = None;
 
     // Load any files passed as arguments
-    for arg in &args[1..] {
+    let mut args = args[1..].iter().peekable();
+    if args.peek().is_some_and(|arg| *arg == "dump") {
+        args.next();
+        match args.next() {
+            Some(path) => dump_path = Some(path),
+            None => {
+                eprintln!("Usage: mew dump <out> [files...]");
+                std::process::exit(1);
+            }
+        }
+    }
+    while let Some(arg) = args.next() {
         if arg == "-v" || arg == "--verbose" {
             repl.set_verbose(true);
             continue;
         }
+        if arg == "--json" {
+            repl.set_format(OutputFormat::Json);
+            continue;
+        }
+        if arg == "--export" {
+            match args.next() {
+                Some(path) => export_path = Some(path),
+                None => {
+                    eprintln!("Error: --export needs a file");
+                    std::process::exit(1);
+                }
+            }
+            continue;
+        }
 
         if let Err(e) = repl.run_file(Path::new(arg)) {
             eprintln!("Error loading {}: {}", arg, e);
             std::process::exit(1);
         }
+        loaded_files = true;
+    }
+
+    // Export or dump the loaded graph instead of starting the REPL
+    if let Some(path) = dump_path {
+        if !loaded_files {
+            run_stdin(&mut repl);
+        }
+        finish(repl.dump(path));
+        return;
+    }
+    if let Some(path) = export_path {
+        finish(repl.export(path));
+        return;
     }
 
     // Enter interactive mode if stdin is a terminal
     let stdin = io::stdin();
     if stdin.is_terminal() {
         repl.interactive();
-    } else if args.len() == 1 {
+    } else if !loaded_files {
         // Only read from stdin pipe if no files were passed
-        let mut input = String::new();
-        if let Err(e) = stdin.lock().read_to_string(&mut input) {
-            eprintln!("Error reading stdin: {}", e);
-            std::process::exit(1);
-        }
-        if let Err(e) = repl.run_script(&input) {
+        run_stdin(&mut repl);
+    }
+}
+
+/// Run the script piped to stdin.
+fn run_stdin(repl: &mut Repl) {
+    let mut input = String::new();
+    if let Err(e) = io::stdin().lock().read_to_string(&mut input) {
+        eprintln!("Error reading stdin: {}", e);
+        std::process::exit(1);
+    }
+    if let Err(e) = repl.run_script(&input) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Print the outcome of a one-shot command, exiting on error.
+fn finish(result: Result<String, String>) {
+    match result {
+        Ok(msg) => println!("{}", msg),
+        Err(e) => {
             eprintln!("Error: {}", e);
             std::process::exit(1);
         }
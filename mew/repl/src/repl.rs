@@ -6,28 +6,36 @@ use std::io::{self, BufRead, Write};
 use std::path::Path;
 
 use mew_compiler::compile;
+use mew_constraint::{ConstraintChecker, Touched};
 use mew_core::EntityId;
 use mew_graph::Graph;
-use mew_parser::{parse_stmt, Stmt};
+use mew_parser::{parse_stmt, Stmt, TxnStmt};
 use mew_registry::{Registry, RegistryBuilder};
+use mew_transaction::UndoLog;
 
 use crate::block::{
-    collect_block_from_lines, collect_block_from_stdin, extract_ontology_source,
-    should_continue_parse,
+    awaits_more_lines, collect_block_from_lines, collect_block_from_stdin, continues_union,
+    extract_ontology_source, should_continue_parse,
 };
 use crate::executor::{
-    execute_explain, execute_inspect, execute_kill, execute_link, execute_match,
-    execute_match_mutate, execute_match_walk, execute_profile, execute_set, execute_spawn,
-    execute_txn, execute_unlink, execute_walk,
+    dump_to_file, execute_call, execute_dump, execute_explain, execute_export, execute_import,
+    execute_inspect, execute_kill, execute_link, execute_match, execute_match_mutate,
+    execute_match_walk, execute_profile, execute_set, execute_spawn, execute_txn, execute_union,
+    execute_unlink, execute_unwind, execute_walk,
 };
-use crate::format::print_help;
+use crate::format::{print_help, OutputFormat};
 
 /// REPL state.
 pub struct Repl {
     registry: Registry,
     graph: Graph,
-    in_transaction: bool,
+    /// Undo log of the open transaction.
+    txn: Option<UndoLog>,
+    /// What the open transaction wrote, for the declared constraints
+    /// checked at COMMIT.
+    touched: Touched,
     verbose: bool,
+    format: OutputFormat,
     bindings: HashMap<String, EntityId>,
 }
 
@@ -37,8 +45,10 @@ impl Repl {
         Self {
             registry: RegistryBuilder::new().build().unwrap(),
             graph: Graph::new(),
-            in_transaction: false,
+            txn: None,
+            touched: Touched::default(),
             verbose: false,
+            format: OutputFormat::Table,
             bindings: HashMap::new(),
         }
     }
@@ -48,15 +58,25 @@ impl Repl {
         self.verbose = verbose;
     }
 
+    /// Set how query results are printed.
+    pub fn set_format(&mut self, format: OutputFormat) {
+        self.format = format;
+    }
+
     /// Load an ontology from source.
     pub fn load_ontology(&mut self, source: &str) -> Result<String, String> {
         let registry = compile(source).map_err(|e| format!("Compile error: {}", e))?;
 
-        let type_count = registry.type_count();
+        // Built-in Layer 0 types (`_DeadLetter`, ...) are not counted
+        let type_count = registry
+            .all_types()
+            .filter(|t| !t.name.starts_with('_'))
+            .count();
         let edge_type_count = registry.edge_type_count();
 
         self.registry = registry;
         self.graph = Graph::new();
+        self.txn = None;
         self.bindings.clear();
 
         if self.verbose {
@@ -71,7 +91,12 @@ impl Repl {
 
     /// Print a summary of the registry.
     pub fn print_registry_summary(&self) {
-        let type_names: Vec<&str> = self.registry.all_types().map(|t| t.name.as_str()).collect();
+        let type_names: Vec<&str> = self
+            .registry
+            .all_types()
+            .map(|t| t.name.as_str())
+            .filter(|name| !name.starts_with('_'))
+            .collect();
         let edge_type_names: Vec<&str> = self
             .registry
             .all_edge_types()
@@ -94,6 +119,41 @@ impl Repl {
         println!("Verbose mode: {}", self.verbose);
     }
 
+    /// Toggle JSON output of query results.
+    pub fn toggle_json(&mut self) {
+        self.format = match self.format {
+            OutputFormat::Table => OutputFormat::Json,
+            OutputFormat::Json => OutputFormat::Table,
+        };
+        println!("JSON output: {}", self.format == OutputFormat::Json);
+    }
+
+    /// Export the graph to a file: `<file>`, or `<file> MATCH ...` to
+    /// export only the entities the query returns.
+    pub fn export(&self, args: &str) -> Result<String, String> {
+        let args = args.trim();
+        let (path, query) = match args.split_once(char::is_whitespace) {
+            Some((path, query)) => (path, Some(query.trim())),
+            None => (args, None),
+        };
+        if path.is_empty() {
+            return Err("Usage: \\export <file> [MATCH ...]".to_string());
+        }
+        execute_export(
+            &self.registry,
+            &self.graph,
+            &self.bindings,
+            Path::new(path),
+            query,
+        )
+    }
+
+    /// Write the ontology and the graph to a file as a script that
+    /// restores them.
+    pub fn dump(&self, path: &str) -> Result<String, String> {
+        dump_to_file(&self.registry, &self.graph, Path::new(path))
+    }
+
     /// Execute a statement or command.
     pub fn execute(&mut self, input: &str) -> Result<String, String> {
         let trimmed = input.trim();
@@ -114,11 +174,106 @@ impl Repl {
 
         // Parse and execute statement
         let stmt = parse_stmt(trimmed).map_err(|e| format!("Parse error: {}", e))?;
+        if let Stmt::Txn(ref txn_stmt) = stmt {
+            return self.run_txn(txn_stmt);
+        }
+        self.run_atomic(&stmt)
+    }
 
+    /// Execute a transaction statement. COMMIT first checks the declared
+    /// constraints the transaction's writes can break; a hard violation
+    /// rolls the whole transaction back.
+    fn run_txn(&mut self, stmt: &TxnStmt) -> Result<String, String> {
         match stmt {
-            Stmt::Match(ref match_stmt) => {
-                execute_match(&self.registry, &self.graph, &self.bindings, match_stmt)
+            TxnStmt::Begin { .. } => self.touched = Touched::default(),
+            TxnStmt::Commit if self.txn.is_some() => {
+                let touched = std::mem::take(&mut self.touched);
+                if let Err(e) = self.check_constraints(&touched) {
+                    if let Some(undo) = self.txn.take() {
+                        undo.rollback(&mut self.graph)
+                            .map_err(|e| format!("Rollback error: {}", e))?;
+                    }
+                    return Err(e);
+                }
+            }
+            _ => {}
+        }
+        execute_txn(&mut self.graph, &mut self.txn, stmt)
+    }
+
+    /// Check the declared constraints whose types, edges or attributes were
+    /// written.
+    fn check_constraints(&self, touched: &Touched) -> Result<(), String> {
+        let checker = ConstraintChecker::new(&self.registry, &self.graph);
+        let violations = checker
+            .check_touched(touched)
+            .map_err(|e| format!("Constraint error: {}", e))?;
+        if let Some(first) = violations.errors().next() {
+            return Err(format!(
+                "Constraint violation: {}: {}",
+                first.constraint_name, first.message
+            ));
+        }
+        Ok(())
+    }
+
+    /// Execute a statement all or nothing.
+    ///
+    /// Outside a transaction the statement gets its own undo log and the
+    /// declared constraints its writes can break are checked; inside one, a
+    /// failed statement is undone back to where it started and the
+    /// transaction stays open.
+    fn run_atomic(&mut self, stmt: &Stmt) -> Result<String, String> {
+        let implicit = self.txn.is_none();
+        if implicit {
+            self.txn = Some(UndoLog::begin(&mut self.graph));
+        }
+        let mark = self.graph.undo_mark();
+        let bindings = self.bindings.clone();
+
+        let mut result = self.run_statement(stmt).and_then(|output| {
+            // Inside a transaction, constraints are checked at COMMIT
+            let touched = Touched::of(stmt);
+            if implicit {
+                self.check_constraints(&touched)?;
+            } else {
+                self.touched.merge(touched);
             }
+            Ok(output)
+        });
+        if result.is_err() {
+            self.bindings = bindings;
+            if let Some(undo) = &mut self.txn {
+                if let Err(e) = undo.undo_to(&mut self.graph, mark) {
+                    result = Err(format!("Rollback error: {}", e));
+                }
+            }
+        }
+        if implicit {
+            if let Some(undo) = self.txn.take() {
+                undo.commit(&mut self.graph);
+            }
+        }
+        result
+    }
+
+    /// Execute a parsed statement against the graph.
+    fn run_statement(&mut self, stmt: &Stmt) -> Result<String, String> {
+        match *stmt {
+            Stmt::Match(ref match_stmt) => execute_match(
+                &self.registry,
+                &self.graph,
+                &self.bindings,
+                match_stmt,
+                self.format,
+            ),
+            Stmt::Union(ref union_stmt) => execute_union(
+                &self.registry,
+                &self.graph,
+                &self.bindings,
+                union_stmt,
+                self.format,
+            ),
             Stmt::Spawn(ref spawn_stmt) => execute_spawn(
                 &self.registry,
                 &mut self.graph,
@@ -146,10 +301,14 @@ impl Repl {
             Stmt::Set(ref set_stmt) => {
                 execute_set(&self.registry, &mut self.graph, &self.bindings, set_stmt)
             }
-            Stmt::Txn(ref txn_stmt) => execute_txn(&mut self.in_transaction, txn_stmt),
-            Stmt::Walk(ref walk_stmt) => {
-                execute_walk(&self.registry, &self.graph, &self.bindings, walk_stmt)
-            }
+            Stmt::Txn(ref txn_stmt) => execute_txn(&mut self.graph, &mut self.txn, txn_stmt),
+            Stmt::Walk(ref walk_stmt) => execute_walk(
+                &self.registry,
+                &self.graph,
+                &self.bindings,
+                walk_stmt,
+                self.format,
+            ),
             Stmt::Inspect(ref inspect_stmt) => {
                 execute_inspect(&self.registry, &self.graph, inspect_stmt)
             }
@@ -159,14 +318,37 @@ impl Repl {
                 &mut self.bindings,
                 match_mutate_stmt,
             ),
-            Stmt::MatchWalk(ref match_walk_stmt) => {
-                execute_match_walk(&self.registry, &self.graph, &self.bindings, match_walk_stmt)
+            Stmt::Unwind(ref unwind_stmt) => {
+                execute_unwind(&self.registry, &mut self.graph, &self.bindings, unwind_stmt)
             }
+            Stmt::MatchWalk(ref match_walk_stmt) => execute_match_walk(
+                &self.registry,
+                &self.graph,
+                &self.bindings,
+                match_walk_stmt,
+                self.format,
+            ),
             Stmt::Explain(ref explain_stmt) => {
                 execute_explain(&self.registry, &self.graph, explain_stmt)
             }
             Stmt::Profile(ref profile_stmt) => {
-                execute_profile(&self.registry, &mut self.graph, profile_stmt)
+                execute_profile(&self.registry, &mut self.graph, profile_stmt, self.format)
+            }
+            Stmt::Import(ref import_stmt) => {
+                execute_import(&self.registry, &mut self.graph, import_stmt)
+            }
+            Stmt::Dump(ref dump_stmt) => {
+                execute_dump(&self.registry, &self.graph, &self.bindings, dump_stmt)
+            }
+            Stmt::Call(ref call_stmt) => execute_call(
+                &self.registry,
+                &mut self.graph,
+                &self.bindings,
+                call_stmt,
+                self.format,
+            ),
+            Stmt::Watch(_) | Stmt::Ack(_) | Stmt::Nack(_) => {
+                Err("WATCH, ACK and NACK are not supported in the REPL".to_string())
             }
         }
     }
@@ -213,6 +395,8 @@ impl Repl {
                     || upper.starts_with("BEGIN")
                     || upper.starts_with("COMMIT")
                     || upper.starts_with("ROLLBACK")
+                    || upper.starts_with("SAVEPOINT ")
+                    || upper.starts_with("RELEASE ")
                     || upper.starts_with("WALK ")
             })
             .unwrap_or(false);
@@ -266,6 +450,10 @@ impl Repl {
                 continue;
             }
 
+            if pending_match && continues_union(trimmed) {
+                pending_match = false;
+            }
+
             if pending_match {
                 let stmt_text = buffer.trim().to_string();
                 buffer.clear();
@@ -308,11 +496,9 @@ impl Repl {
 
             match parse_stmt(&buffer) {
                 Ok(stmt) => {
-                    if let Stmt::Match(match_stmt) = stmt {
-                        if match_stmt.order_by.is_none() {
-                            pending_match = true;
-                            continue;
-                        }
+                    if awaits_more_lines(&stmt) {
+                        pending_match = true;
+                        continue;
                     }
                     let stmt_text = buffer.trim().to_string();
                     buffer.clear();
@@ -373,7 +559,7 @@ impl Repl {
 
         loop {
             // Prompt
-            let prompt = if self.in_transaction {
+            let prompt = if self.txn.is_some() {
                 "mew*> "
             } else {
                 "mew> "
@@ -408,9 +594,38 @@ impl Repl {
                     self.toggle_verbose();
                     continue;
                 }
+                "json" => {
+                    self.toggle_json();
+                    continue;
+                }
                 _ => {}
             }
 
+            // Handle export: \export <file> [MATCH ...]
+            if let Some(args) = trimmed.strip_prefix("\\export ") {
+                match self.export(args) {
+                    Ok(msg) => println!("{}", msg),
+                    Err(e) => eprintln!("Error: {}", e),
+                }
+                continue;
+            }
+
+            // Handle bulk import: \import <Type> <file>
+            if let Some(args) = trimmed.strip_prefix("\\import ") {
+                let mut parts = args.split_whitespace();
+                let result = match (parts.next(), parts.next()) {
+                    (Some(target), Some(path)) => {
+                        self.execute(&format!("IMPORT {} FROM {:?}", target, path))
+                    }
+                    _ => Err("Usage: \\import <Type> <file>".to_string()),
+                };
+                match result {
+                    Ok(msg) => println!("{}", msg),
+                    Err(e) => eprintln!("Error: {}", e),
+                }
+                continue;
+            }
+
             // Handle file loading
             if trimmed.starts_with("\\i ") || trimmed.starts_with("load ") {
                 let path = trimmed.split_whitespace().nth(1).unwrap_or("");
@@ -476,6 +691,10 @@ impl Repl {
                 continue;
             }
 
+            if pending_match && continues_union(trimmed) {
+                pending_match = false;
+            }
+
             if pending_match {
                 let stmt_text = buffer.trim().to_string();
                 buffer.clear();
@@ -499,11 +718,9 @@ impl Repl {
             loop {
                 match parse_stmt(&buffer) {
                     Ok(stmt) => {
-                        if let Stmt::Match(match_stmt) = stmt {
-                            if match_stmt.order_by.is_none() {
-                                pending_match = true;
-                                break;
-                            }
+                        if awaits_more_lines(&stmt) {
+                            pending_match = true;
+                            break;
                         }
                         let stmt_text = buffer.trim().to_string();
                         buffer.clear();
@@ -574,6 +791,24 @@ mod tests {
         assert_eq!(output, "Ontology loaded: 2 types, 1 edge types");
     }
 
+    #[test]
+    fn prints_query_results_as_wire_json() {
+        let mut repl = Repl::new();
+        repl.load_ontology(SIMPLE_ONTOLOGY).unwrap();
+        repl.execute("SPAWN t: Task { title = \"Write\" }").unwrap();
+        repl.set_format(OutputFormat::Json);
+
+        let output = repl.execute("MATCH t: Task RETURN t, t.title").unwrap();
+        let message = mew_session::WireMessage::from_json(output.parse().unwrap()).unwrap();
+        let json = message.to_json();
+        assert_eq!(
+            json["result"]["types"],
+            serde_json::json!(["Task", "String"])
+        );
+        assert_eq!(json["result"]["rows"][0][0]["_type"], "node");
+        assert_eq!(json["result"]["rows"][0][1], "Write");
+    }
+
     #[test]
     fn reports_empty_match_results() {
         let mut repl = Repl::new();
@@ -592,6 +827,45 @@ mod tests {
         assert!(err.starts_with("Parse error:"));
     }
 
+    #[test]
+    fn rejects_ungrouped_columns() {
+        let mut repl = Repl::new();
+        repl.load_ontology(SIMPLE_ONTOLOGY).unwrap();
+        repl.execute("SPAWN t: Task { title = \"Write\" }").unwrap();
+        repl.execute("SPAWN p: Person { name = \"Ada\" }").unwrap();
+        repl.execute("LINK assigned(t, p)").unwrap();
+
+        let err = repl
+            .execute(
+                "MATCH t: Task, p: Person, assigned(t, p) \
+                 RETURN p.name, t.title, count(t) GROUP BY p.name",
+            )
+            .unwrap_err();
+        assert!(err.starts_with("Analysis error:"));
+        assert!(err.contains("'t' is neither grouped nor aggregated"));
+
+        let grouped = repl
+            .execute(
+                "MATCH t: Task, p: Person, assigned(t, p) \
+                 RETURN p.name, count(t) AS n GROUP BY p.name HAVING n > 0",
+            )
+            .unwrap();
+        assert!(grouped.contains("(1 rows)"));
+    }
+
+    #[test]
+    fn rejects_unions_of_incompatible_columns() {
+        let mut repl = Repl::new();
+        repl.load_ontology(SIMPLE_ONTOLOGY).unwrap();
+        repl.execute("SPAWN t: Task { title = \"Write\" }").unwrap();
+
+        let err = repl
+            .execute("MATCH t: Task RETURN t.title AS v UNION MATCH t: Task RETURN 1 AS v")
+            .unwrap_err();
+        assert!(err.starts_with("Analysis error:"));
+        assert!(err.contains("incompatible types String and Int"));
+    }
+
     #[test]
     fn supports_query_and_mutation_feedback() {
         let mut repl = Repl::new();
@@ -633,6 +907,78 @@ mod tests {
         assert_eq!(commit_output, "COMMIT");
     }
 
+    #[test]
+    fn supports_savepoints() {
+        let mut repl = Repl::new();
+        repl.load_ontology(SIMPLE_ONTOLOGY).unwrap();
+        repl.execute("BEGIN").unwrap();
+        repl.execute("SPAWN t: Task { title = \"kept\" }").unwrap();
+
+        let savepoint_output = repl.execute("SAVEPOINT batch").unwrap();
+        assert_eq!(savepoint_output, "SAVEPOINT batch");
+        repl.execute("SPAWN u: Task { title = \"dropped\" }")
+            .unwrap();
+        repl.execute("KILL t").unwrap();
+
+        let rollback_output = repl.execute("ROLLBACK TO batch").unwrap();
+        assert_eq!(rollback_output, "ROLLBACK TO batch");
+        repl.execute("RELEASE batch").unwrap();
+        assert!(repl.execute("ROLLBACK TO batch").is_err());
+        repl.execute("COMMIT").unwrap();
+
+        let match_output = repl.execute("MATCH t: Task RETURN t.title").unwrap();
+        assert!(match_output.contains("kept"));
+        assert!(!match_output.contains("dropped"));
+    }
+
+    #[test]
+    fn undoes_an_unwind_batch_when_an_element_fails() {
+        let mut repl = Repl::new();
+        repl.load_ontology("node Tag { name: String [unique] }")
+            .unwrap();
+
+        let err = repl
+            .execute("UNWIND [\"a\", \"a\", \"b\"] AS n SPAWN t: Tag { name = n }")
+            .unwrap_err();
+
+        assert!(err.contains("unique"));
+        let count = repl.execute("MATCH t: Tag RETURN count(t)").unwrap();
+        assert_eq!(count.lines().nth(2).map(str::trim), Some("0"));
+    }
+
+    #[test]
+    fn enforces_subquery_constraints_on_link_and_set() {
+        let mut repl = Repl::new();
+        repl.load_ontology(
+            "node Project { name: String }
+             node Task { open: Bool }
+             edge belongs_to(task: Task, project: Project)
+             constraint max_open: p: Project =>
+               COUNT { t: Task, belongs_to(t, p) WHERE t.open = true } <= 2",
+        )
+        .unwrap();
+        repl.execute("SPAWN p: Project { name = \"p\" }").unwrap();
+        for task in ["a", "b", "c"] {
+            repl.execute(&format!("SPAWN {}: Task {{ open = true }}", task))
+                .unwrap();
+        }
+        repl.execute("SPAWN d: Task { open = false }").unwrap();
+        for task in ["a", "b", "d"] {
+            repl.execute(&format!("LINK belongs_to({}, p)", task))
+                .unwrap();
+        }
+
+        let linked = repl.execute("LINK belongs_to(c, p)").unwrap_err();
+        let reopened = repl.execute("SET d.open = true").unwrap_err();
+
+        assert!(linked.contains("max_open"));
+        assert!(reopened.contains("max_open"));
+        let open = repl
+            .execute("MATCH t: Task, belongs_to(t, p) WHERE t.open = true RETURN t")
+            .unwrap();
+        assert!(open.contains("(2 rows)"));
+    }
+
     #[test]
     fn rejects_transaction_commands_outside_txn() {
         let mut repl = Repl::new();
@@ -641,4 +987,115 @@ mod tests {
         let err = repl.execute("COMMIT").unwrap_err();
         assert_eq!(err, "No transaction active");
     }
+
+    #[test]
+    fn imports_json_lines_and_lists_rejected_rows() {
+        let mut repl = Repl::new();
+        repl.load_ontology(SIMPLE_ONTOLOGY).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("mew_repl_import_{}.jsonl", std::process::id()));
+        fs::write(
+            &path,
+            "{\"title\": \"Write\"}\nnot json\n{\"title\": \"Ship\"}\n",
+        )
+        .unwrap();
+
+        let output = repl.execute(&format!("IMPORT Task FROM {:?}", path.to_string_lossy()));
+        fs::remove_file(&path).ok();
+
+        let output = output.unwrap();
+        assert!(output.starts_with("Imported 2 nodes, 0 edges from 3 rows (1 rejected)"));
+        assert!(output.contains("\n  line 2: invalid JSON"));
+        let match_output = repl.execute("MATCH t: Task RETURN t.title").unwrap();
+        assert!(match_output.contains("(2 rows)"));
+    }
+
+    #[test]
+    fn exports_match_results_and_whole_graph() {
+        let mut repl = Repl::new();
+        repl.load_ontology(SIMPLE_ONTOLOGY).unwrap();
+        repl.execute("SPAWN t: Task { title = \"Write\" }").unwrap();
+        repl.execute("SPAWN p: Person { name = \"Ada\" }").unwrap();
+        repl.execute("SPAWN q: Person { name = \"Bob\" }").unwrap();
+        repl.execute("LINK assigned(t, p)").unwrap();
+        let dir = std::env::temp_dir();
+        let dot = dir.join(format!("mew_repl_export_{}.dot", std::process::id()));
+        let json = dir.join(format!("mew_repl_export_{}.json", std::process::id()));
+
+        let dot_output = repl.export(&format!(
+            "{} MATCH t: Task RETURN t, t.title",
+            dot.display()
+        ));
+        let json_output = repl.export(&json.display().to_string());
+        let dot_text = fs::read_to_string(&dot);
+        let json_text = fs::read_to_string(&json);
+        fs::remove_file(&dot).ok();
+        fs::remove_file(&json).ok();
+
+        assert!(dot_output
+            .unwrap()
+            .starts_with("Exported 1 nodes, 0 edges to "));
+        assert!(dot_text
+            .unwrap()
+            .contains("[label=\"Task\\ntitle: \\\"Write\\\"\"]"));
+        assert!(json_output
+            .unwrap()
+            .starts_with("Exported 3 nodes, 1 edges to "));
+        let document: serde_json::Value = serde_json::from_str(&json_text.unwrap()).unwrap();
+        assert_eq!(document["edges"][0]["type"], "assigned");
+        assert_eq!(document["nodes"].as_array().unwrap().len(), 3);
+
+        let err = repl.export("graph.txt").unwrap_err();
+        assert!(err.starts_with("Unknown export format"));
+    }
+
+    #[test]
+    fn dumps_a_script_that_restores_the_graph() {
+        let mut repl = Repl::new();
+        repl.load_ontology(SIMPLE_ONTOLOGY).unwrap();
+        repl.execute("SPAWN t: Task { title = \"Write\" }").unwrap();
+        repl.execute("SPAWN p: Person { name = \"Ada\" }").unwrap();
+        repl.execute("LINK assigned(t, p)").unwrap();
+        let path = std::env::temp_dir().join(format!("mew_repl_dump_{}.mew", std::process::id()));
+
+        let output = repl.execute(&format!("DUMP TO {:?}", path.to_string_lossy()));
+        let mut restored = Repl::new();
+        let restore_result = restored.run_file(&path);
+        fs::remove_file(&path).ok();
+
+        assert!(output.unwrap().starts_with("Dumped 2 nodes, 1 edges to "));
+        restore_result.unwrap();
+        let match_output = restored
+            .execute("MATCH t: Task, p: Person, assigned(t, p) RETURN p.name")
+            .unwrap();
+        assert!(match_output.contains("Ada"));
+        assert!(match_output.contains("(1 rows)"));
+    }
+
+    #[test]
+    fn calls_an_algorithm_and_writes_back() {
+        let mut repl = Repl::new();
+        repl.load_ontology(
+            "node Page { url: String, rank: Float? } edge links(from: Page, to: Page)",
+        )
+        .unwrap();
+        repl.execute("SPAWN a: Page { url = \"a\" }").unwrap();
+        repl.execute("SPAWN b: Page { url = \"b\" }").unwrap();
+        repl.execute("LINK links(a, b)").unwrap();
+
+        let yielded = repl
+            .execute("CALL algo.wcc(links) YIELD node, component")
+            .unwrap();
+        let written = repl
+            .execute("CALL algo.pagerank(links) WRITE rank")
+            .unwrap();
+
+        assert!(yielded.contains("node | component"));
+        assert!(yielded.contains("(2 rows)"));
+        assert_eq!(written, "Updated 2 nodes");
+        let ranked = repl
+            .execute("MATCH p: Page WHERE p.rank > 0.5 RETURN p.url")
+            .unwrap();
+        assert!(ranked.contains("(1 rows)"));
+    }
 }
@@ -74,7 +74,7 @@ impl<'r, 'g> RuleEngine<'r, 'g> {
         }
 
         // Sort by priority (highest first)
-        triggered.sort_by(|a, b| b.priority.cmp(&a.priority));
+        triggered.sort_by_key(|r| std::cmp::Reverse(r.priority));
 
         triggered
     }
@@ -96,7 +96,7 @@ impl<'r, 'g> RuleEngine<'r, 'g> {
         }
 
         // Sort by priority (highest first)
-        triggered.sort_by(|a, b| b.priority.cmp(&a.priority));
+        triggered.sort_by_key(|r| std::cmp::Reverse(r.priority));
 
         triggered
     }
@@ -0,0 +1,81 @@
+//! Server error types.
+
+use mew_session::SessionError;
+use mew_transaction::TransactionError;
+use thiserror::Error;
+
+/// Errors reported to HTTP clients.
+#[derive(Debug, Error)]
+pub enum ServerError {
+    /// Malformed request (bad JSON, missing fields, ...).
+    #[error("bad request: {message}")]
+    BadRequest { message: String },
+
+    /// Request body over the server's limit.
+    #[error("request body exceeds {limit} bytes")]
+    PayloadTooLarge { limit: usize },
+
+    /// No route for the method and path.
+    #[error("not found: {path}")]
+    RouteNotFound { path: String },
+
+    /// Unknown or closed session token.
+    #[error("session not found: {token}")]
+    SessionNotFound { token: String },
+
+    /// Statement execution failed.
+    #[error("{0}")]
+    Session(#[from] SessionError),
+}
+
+impl ServerError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::BadRequest {
+            message: message.into(),
+        }
+    }
+
+    pub fn payload_too_large(limit: usize) -> Self {
+        Self::PayloadTooLarge { limit }
+    }
+
+    pub fn route_not_found(path: impl Into<String>) -> Self {
+        Self::RouteNotFound { path: path.into() }
+    }
+
+    pub fn session_not_found(token: impl Into<String>) -> Self {
+        Self::SessionNotFound {
+            token: token.into(),
+        }
+    }
+
+    /// HTTP status code for this error.
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::BadRequest { .. } => 400,
+            Self::PayloadTooLarge { .. } => 413,
+            Self::RouteNotFound { .. } | Self::SessionNotFound { .. } => 404,
+            Self::Session(SessionError::TransactionError(
+                TransactionError::SerializationConflict { .. },
+            )) => 409,
+            Self::Session(SessionError::FileAccessDisabled { .. }) => 403,
+            Self::Session(SessionError::WatchNotFound { .. })
+            | Self::Session(SessionError::InvalidDeliveryId { .. }) => 404,
+            Self::Session(_) => 400,
+        }
+    }
+
+    /// Machine-readable error kind.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::BadRequest { .. } => "bad_request",
+            Self::PayloadTooLarge { .. } => "payload_too_large",
+            Self::RouteNotFound { .. } => "route_not_found",
+            Self::SessionNotFound { .. } => "session_not_found",
+            Self::Session(e) => e.kind(),
+        }
+    }
+}
+
+/// Result type for request handling.
+pub type ServerResult<T> = Result<T, ServerError>;
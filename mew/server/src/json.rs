@@ -0,0 +1,117 @@
+//! Request decoding and the JSON the wire encoding does not cover.
+//!
+//! Results, watch events and errors use the canonical encoding of
+//! [`mew_session::wire`]. Parameters are decoded with the same value rules,
+//! so any value the server returns can be sent back.
+
+use mew_core::Value;
+use mew_registry::{AttrDef, Registry};
+use mew_session::{json_to_value, WireError, WireMessage};
+use serde_json::{json, Value as Json};
+use std::collections::HashMap;
+
+use crate::error::{ServerError, ServerResult};
+
+/// Decode a `params` object into statement parameters.
+pub fn json_to_params(json: Option<&Json>) -> ServerResult<HashMap<String, Value>> {
+    match json {
+        None | Some(Json::Null) => Ok(HashMap::new()),
+        Some(Json::Object(fields)) => fields
+            .iter()
+            .map(|(name, v)| {
+                let value = json_to_value(v).map_err(|e| ServerError::bad_request(e.message))?;
+                Ok((name.trim_start_matches('$').to_string(), value))
+            })
+            .collect(),
+        Some(_) => Err(ServerError::bad_request("'params' must be an object")),
+    }
+}
+
+/// Encode an error.
+pub fn error_to_json(error: &ServerError) -> Json {
+    WireMessage::from(WireError::new(error.kind(), error.to_string())).to_json()
+}
+
+/// Describe the registry's user-visible types and edge types.
+///
+/// Built-in Layer 0 types (`_DeadLetter`, ...) are left out. Lists are
+/// sorted by name so the output is stable.
+pub fn schema_to_json(registry: &Registry) -> Json {
+    let mut types: Vec<_> = registry
+        .all_types()
+        .filter(|t| !t.name.starts_with('_'))
+        .collect();
+    types.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut edge_types: Vec<_> = registry.all_edge_types().collect();
+    edge_types.sort_by(|a, b| a.name.cmp(&b.name));
+
+    json!({
+        "types": types
+            .iter()
+            .map(|t| json!({
+                "name": t.name,
+                "abstract": t.is_abstract,
+                "parents": t
+                    .parent_ids
+                    .iter()
+                    .filter_map(|id| registry.get_type(*id))
+                    .map(|p| p.name.clone())
+                    .collect::<Vec<_>>(),
+                "attributes": attrs_json(t.attributes.values()),
+            }))
+            .collect::<Vec<_>>(),
+        "edge_types": edge_types
+            .iter()
+            .map(|e| json!({
+                "name": e.name,
+                "symmetric": e.symmetric,
+                "params": e
+                    .params
+                    .iter()
+                    .map(|p| json!({ "name": p.name, "type": p.type_constraint }))
+                    .collect::<Vec<_>>(),
+                "attributes": attrs_json(e.attributes.values()),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn attrs_json<'a>(attrs: impl Iterator<Item = &'a AttrDef>) -> Json {
+    let mut attrs: Vec<_> = attrs.collect();
+    attrs.sort_by(|a, b| a.name.cmp(&b.name));
+    Json::Array(
+        attrs
+            .iter()
+            .map(|a| {
+                json!({
+                    "name": a.name,
+                    "type": a.type_name,
+                    "required": a.required,
+                    "nullable": a.nullable,
+                    "unique": a.unique,
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_accept_dollar_prefix() {
+        // GIVEN
+        let json = json!({ "$name": "a", "limit": 2, "at": { "_type": "timestamp", "value": 5 } });
+
+        // WHEN
+        let params = json_to_params(Some(&json)).unwrap();
+
+        // THEN
+        assert_eq!(params["name"], Value::String("a".into()));
+        assert_eq!(params["limit"], Value::Int(2));
+        assert_eq!(params["at"], Value::Timestamp(5));
+        assert!(json_to_params(Some(&json!([1]))).is_err());
+        assert!(json_to_params(Some(&json!({ "at": { "_type": "timestamp" } }))).is_err());
+    }
+}
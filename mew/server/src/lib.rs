@@ -0,0 +1,23 @@
+//! MEW Server
+//!
+//! HTTP/JSON access to a shared [`mew_session::Database`]:
+//! - statement execution with parameters, results in the wire encoding of
+//!   [`mew_session::wire`]
+//! - transactions and watches pinned to a session token
+//! - schema introspection
+//! - watch deliveries streamed as server-sent events
+//!
+//! The server is synchronous: one thread per request, scoped to
+//! [`Server::run`] so it can borrow the database it serves, with a bound on
+//! how many run at once.
+
+mod error;
+mod json;
+mod server;
+mod sessions;
+
+pub use error::{ServerError, ServerResult};
+pub use server::{
+    Server, DEFAULT_MAX_BODY, DEFAULT_MAX_CONNECTIONS, DEFAULT_POLL_INTERVAL,
+    DEFAULT_SESSION_TIMEOUT, SESSION_HEADER,
+};
@@ -0,0 +1,59 @@
+//! MEW Server - HTTP/JSON access to a MEW database.
+//!
+//! Usage: `mew-server [--bind ADDR] ONTOLOGY.mew [DATA.mew ...]`
+//!
+//! The ontology file defines the schema; data files are executed in order
+//! before the server starts listening (default `127.0.0.1:7878`).
+
+use std::env;
+use std::fs;
+
+use mew_compiler::compile;
+use mew_server::Server;
+use mew_session::Database;
+
+const DEFAULT_BIND: &str = "127.0.0.1:7878";
+
+fn main() {
+    let mut bind = DEFAULT_BIND.to_string();
+    let mut files = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--bind" || arg == "-b" {
+            bind = args
+                .next()
+                .unwrap_or_else(|| fail("--bind needs an address"));
+        } else {
+            files.push(arg);
+        }
+    }
+    let Some((ontology, data)) = files.split_first() else {
+        fail("usage: mew-server [--bind ADDR] ONTOLOGY.mew [DATA.mew ...]");
+    };
+
+    let source = read(ontology);
+    let registry = compile(&source).unwrap_or_else(|e| fail(&format!("{}: {}", ontology, e)));
+    let database = Database::new(registry);
+
+    let mut session = database.session();
+    for path in data {
+        if let Err(e) = session.execute_all(&read(path)) {
+            fail(&format!("{}: {}", path, e));
+        }
+    }
+    drop(session);
+
+    let server = Server::bind(&bind, &database)
+        .unwrap_or_else(|e| fail(&format!("cannot bind {}: {}", bind, e)));
+    eprintln!("mew-server listening on http://{}", server.local_addr());
+    server.run();
+}
+
+fn read(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| fail(&format!("cannot read {}: {}", path, e)))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,805 @@
+//! HTTP server.
+//!
+//! Routes:
+//!
+//! | Method | Path                                        | Purpose                      |
+//! |--------|---------------------------------------------|------------------------------|
+//! | GET    | `/health`                                   | Liveness and database version|
+//! | GET    | `/schema`                                   | Types and edge types         |
+//! | POST   | `/sessions`                                 | Open a session, get a token  |
+//! | DELETE | `/sessions/{token}`                         | Close it (rolls back)        |
+//! | POST   | `/execute`                                  | Run one statement            |
+//! | GET    | `/sessions/{token}/watches/{id}/events`     | Stream watch deliveries (SSE)|
+//!
+//! `/execute` takes `{"statement": "...", "params": {...}}`. With an
+//! `X-Mew-Session: <token>` header it runs in that session, so transactions
+//! and watches span requests; without one it runs in a fresh auto-commit
+//! session. Results, deliveries and errors use the versioned encoding of
+//! [`mew_session::wire`]: `{"version": 1, "result": {...}}`,
+//! `{"version": 1, "event": {...}}` or
+//! `{"version": 1, "error": {"kind": ..., "message": ...}}`.
+//!
+//! The event stream is `text/event-stream`: one `delivery` event per message,
+//! settled with `ACK $id` / `NACK $id` through `/execute` in the same
+//! session. `?limit=N` ends the stream after N deliveries; HTTP/1.1 streams
+//! are chunked, so the connection stays open for the next request.
+//!
+//! Request bodies over [`DEFAULT_MAX_BODY`] bytes are refused with 413, at
+//! most [`DEFAULT_MAX_CONNECTIONS`] requests (event streams included) are
+//! handled at once, and sessions idle for [`DEFAULT_SESSION_TIMEOUT`] are
+//! closed. Each limit can be changed on the [`Server`].
+//!
+//! Sessions opened by the server never have file access, so IMPORT and DUMP,
+//! which read and write files on the server host, are refused with 403.
+
+use mew_session::{Database, WireMessage};
+use serde_json::{json, Value as Json};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::error::{ServerError, ServerResult};
+use crate::json::{error_to_json, json_to_params, schema_to_json};
+use crate::sessions::{lock_session, SessionPool};
+
+/// Header carrying the session token.
+pub const SESSION_HEADER: &str = "X-Mew-Session";
+
+/// Default time between watch polls while streaming events.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Default limit on the size of a request body.
+pub const DEFAULT_MAX_BODY: usize = 1024 * 1024;
+
+/// Default limit on the number of requests handled at once.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+
+/// Default idle time after which a session is closed.
+pub const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Idle time after which an event stream sends a keep-alive comment.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Deliveries pulled per poll when the stream has no limit.
+const POLL_BATCH: usize = 64;
+
+/// An HTTP/JSON front end for a shared [`Database`].
+pub struct Server<'db> {
+    http: tiny_http::Server,
+    database: &'db Database,
+    sessions: SessionPool<'db>,
+    poll_interval: Duration,
+    max_body: usize,
+    permits: Permits,
+    stopping: AtomicBool,
+}
+
+impl<'db> Server<'db> {
+    /// Bind to `addr`. Use port 0 to let the OS pick one.
+    pub fn bind(addr: impl ToSocketAddrs, database: &'db Database) -> io::Result<Self> {
+        let http = tiny_http::Server::http(addr).map_err(io::Error::other)?;
+        Ok(Self {
+            http,
+            database,
+            sessions: SessionPool::new(database, DEFAULT_SESSION_TIMEOUT),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            max_body: DEFAULT_MAX_BODY,
+            permits: Permits::new(DEFAULT_MAX_CONNECTIONS),
+            stopping: AtomicBool::new(false),
+        })
+    }
+
+    /// Set the time between watch polls while streaming events.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the largest request body accepted, in bytes.
+    pub fn with_max_body(mut self, bytes: usize) -> Self {
+        self.max_body = bytes;
+        self
+    }
+
+    /// Set how many requests are handled at once. Further connections wait
+    /// until a running request finishes.
+    pub fn with_max_connections(mut self, max: usize) -> Self {
+        self.permits = Permits::new(max.max(1));
+        self
+    }
+
+    /// Set the idle time after which a session is closed.
+    pub fn with_session_timeout(mut self, timeout: Duration) -> Self {
+        self.sessions.set_idle_timeout(timeout);
+        self
+    }
+
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.http
+            .server_addr()
+            .to_ip()
+            .expect("server listens on a TCP socket")
+    }
+
+    /// Number of open sessions.
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Serve requests until [`shutdown`](Self::shutdown), one thread each,
+    /// with at most the configured number running at once.
+    pub fn run(&self) {
+        std::thread::scope(|scope| {
+            for request in self.http.incoming_requests() {
+                let permit = self.permits.acquire();
+                if self.stopping.load(Ordering::Relaxed) {
+                    break;
+                }
+                scope.spawn(move || {
+                    let _permit = permit;
+                    self.handle(request)
+                });
+            }
+        });
+    }
+
+    /// Stop accepting requests and end open event streams.
+    pub fn shutdown(&self) {
+        self.stopping.store(true, Ordering::Relaxed);
+        self.http.unblock();
+    }
+
+    fn handle(&self, mut request: Request) {
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        if let (Method::Get, ["sessions", token, "watches", id, "events"]) =
+            (request.method(), &segments[..])
+        {
+            let limit = query_param(query, "limit").and_then(|v| v.parse().ok());
+            match self.open_stream(token, id) {
+                Ok(watch_id) => self.stream_events(request, token, watch_id, limit),
+                Err(e) => respond(request, e.status(), &error_to_json(&e)),
+            }
+            return;
+        }
+
+        let result = match (request.method(), &segments[..]) {
+            (Method::Get, ["health"]) => Ok((
+                200,
+                json!({ "status": "ok", "version": self.database.version() }),
+            )),
+            (Method::Get, ["schema"]) => Ok((200, schema_to_json(self.database.registry()))),
+            (Method::Post, ["sessions"]) => Ok((201, json!({ "session": self.sessions.open() }))),
+            (Method::Delete, ["sessions", token]) => self
+                .sessions
+                .close(token)
+                .map(|()| (200, json!({ "closed": true }))),
+            (Method::Post, ["execute"]) => self.execute(&mut request).map(|json| (200, json)),
+            _ => Err(ServerError::route_not_found(path)),
+        };
+
+        match result {
+            Ok((status, json)) => respond(request, status, &json),
+            Err(e) => respond(request, e.status(), &error_to_json(&e)),
+        }
+    }
+
+    /// Run the statement of an `/execute` request.
+    fn execute(&self, request: &mut Request) -> ServerResult<Json> {
+        if request.body_length().is_some_and(|len| len > self.max_body) {
+            return Err(ServerError::payload_too_large(self.max_body));
+        }
+        let mut body = String::new();
+        request
+            .as_reader()
+            .take(self.max_body as u64 + 1)
+            .read_to_string(&mut body)
+            .map_err(|e| ServerError::bad_request(e.to_string()))?;
+        if body.len() > self.max_body {
+            return Err(ServerError::payload_too_large(self.max_body));
+        }
+        let body: Json = serde_json::from_str(&body)
+            .map_err(|e| ServerError::bad_request(format!("invalid JSON: {}", e)))?;
+        let statement = body
+            .get("statement")
+            .and_then(Json::as_str)
+            .ok_or_else(|| ServerError::bad_request("missing 'statement'"))?;
+        let params = json_to_params(body.get("params"))?;
+
+        match header(request, SESSION_HEADER) {
+            Some(token) => {
+                let session = self.sessions.get(&token)?;
+                let result = lock_session(&session).execute_with_params(statement, &params)?;
+                Ok(WireMessage::from(result).to_json())
+            }
+            None => {
+                let mut session = self.database.session();
+                let result = session.execute_with_params(statement, &params)?;
+                if session.in_transaction() {
+                    session.reset_transaction();
+                    return Err(ServerError::bad_request(format!(
+                        "transactions need a session: open one with POST /sessions and pass it in {}",
+                        SESSION_HEADER
+                    )));
+                }
+                Ok(WireMessage::from(result).to_json())
+            }
+        }
+    }
+
+    /// Check the session and watch of an event stream request.
+    fn open_stream(&self, token: &str, id: &str) -> ServerResult<u64> {
+        let watch_id = id
+            .parse()
+            .map_err(|_| ServerError::bad_request(format!("invalid watch ID '{}'", id)))?;
+        let session = self.sessions.get(token)?;
+        lock_session(&session).poll_watch(watch_id, 0)?;
+        Ok(watch_id)
+    }
+
+    /// Stream a watch's deliveries as server-sent events.
+    ///
+    /// The stream ends when `limit` deliveries were sent, the session is
+    /// closed, the watch is cancelled, the client goes away or the server
+    /// shuts down. Deliveries a client never received time out and are
+    /// handed out again like any unacknowledged message.
+    fn stream_events(&self, request: Request, token: &str, watch_id: u64, limit: Option<usize>) {
+        let chunked = *request.http_version() >= (1, 1);
+        let mut writer = EventWriter {
+            inner: request.into_writer(),
+            chunked,
+        };
+        let framing = if chunked {
+            "Transfer-Encoding: chunked"
+        } else {
+            "Connection: close"
+        };
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+             Cache-Control: no-cache\r\n{}\r\n\r\n",
+            framing
+        );
+        if writer
+            .inner
+            .write_all(head.as_bytes())
+            .and_then(|()| writer.inner.flush())
+            .is_err()
+        {
+            return;
+        }
+
+        let mut sent = 0;
+        let mut last_write = Instant::now();
+        while limit.is_none_or(|limit| sent < limit) && !self.stopping.load(Ordering::Relaxed) {
+            // Looking the session up each time keeps it from idling out and
+            // ends the stream once it is closed
+            let Ok(session) = self.sessions.get(token) else {
+                break;
+            };
+            let max = limit.map_or(POLL_BATCH, |limit| limit - sent);
+            let polled = lock_session(&session).poll_watch(watch_id, max);
+            drop(session);
+
+            let (mut frame, done, idle) = match polled {
+                Ok(events) => {
+                    let mut frame = String::new();
+                    let idle = events.is_empty();
+                    sent += events.len();
+                    for event in events {
+                        let id = event.delivery_id.clone();
+                        let data = WireMessage::from(event).to_json();
+                        frame.push_str(&format!("id: {}\nevent: delivery\ndata: {}\n\n", id, data));
+                    }
+                    (frame, false, idle)
+                }
+                Err(e) => {
+                    let e = ServerError::from(e);
+                    let frame = format!("event: error\ndata: {}\n\n", error_to_json(&e));
+                    (frame, true, false)
+                }
+            };
+            if idle && last_write.elapsed() >= HEARTBEAT_INTERVAL {
+                frame.push_str(": keep-alive\n\n");
+            }
+            if !frame.is_empty() {
+                if writer.send(&frame).is_err() {
+                    return;
+                }
+                last_write = Instant::now();
+            }
+            if done {
+                break;
+            }
+            if idle {
+                std::thread::sleep(self.poll_interval);
+            }
+        }
+        let _ = writer.finish();
+    }
+}
+
+/// Body of an event stream. HTTP/1.1 streams are chunked, so the client sees
+/// where a finished stream ends and can reuse the connection; HTTP/1.0 has no
+/// chunking, so the connection is closed instead.
+struct EventWriter {
+    inner: Box<dyn Write + Send>,
+    chunked: bool,
+}
+
+impl EventWriter {
+    /// Send a frame right away.
+    fn send(&mut self, frame: &str) -> io::Result<()> {
+        if self.chunked {
+            write!(self.inner, "{:x}\r\n{}\r\n", frame.len(), frame)?;
+        } else {
+            self.inner.write_all(frame.as_bytes())?;
+        }
+        self.inner.flush()
+    }
+
+    /// End the body.
+    fn finish(mut self) -> io::Result<()> {
+        if self.chunked {
+            self.inner.write_all(b"0\r\n\r\n")?;
+        }
+        self.inner.flush()
+    }
+}
+
+/// Counting semaphore bounding the requests handled at once.
+struct Permits {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+/// A held permit, returned when dropped.
+struct Permit<'p>(&'p Permits);
+
+impl Permits {
+    fn new(count: usize) -> Self {
+        Self {
+            available: Mutex::new(count),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Wait for a free permit.
+    fn acquire(&self) -> Permit<'_> {
+        let mut available = self.available.lock().unwrap_or_else(|e| e.into_inner());
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *available -= 1;
+        Permit(self)
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        *self.0.available.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+        self.0.released.notify_one();
+    }
+}
+
+fn header(request: &Request, name: &'static str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str().to_string())
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn respond(request: Request, status: u16, json: &Json) {
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let response = Response::from_string(json.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    // The client may already be gone; nothing to do about it
+    let _ = request.respond(response);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mew_registry::{AttrDef, RegistryBuilder};
+    use std::io::Read;
+    use std::net::TcpStream;
+
+    fn test_database() -> Database {
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Job")
+            .attr(AttrDef::new("name", "String"))
+            .attr(AttrDef::new("status", "String"))
+            .attr(AttrDef::new("priority", "Int"))
+            .attr(AttrDef::new("runtime", "Float"))
+            .done()
+            .unwrap();
+        Database::new(builder.build().unwrap())
+    }
+
+    /// Stops the server even when a test assertion panics.
+    struct Running<'s, 'db>(&'s Server<'db>);
+
+    impl Drop for Running<'_, '_> {
+        fn drop(&mut self) {
+            self.0.shutdown();
+        }
+    }
+
+    fn with_server(test: impl FnOnce(SocketAddr)) {
+        with_configured_server(|server| server, test);
+    }
+
+    fn with_configured_server(
+        configure: impl for<'db> FnOnce(Server<'db>) -> Server<'db>,
+        test: impl FnOnce(SocketAddr),
+    ) {
+        let db = test_database();
+        let server = configure(
+            Server::bind("127.0.0.1:0", &db)
+                .unwrap()
+                .with_poll_interval(Duration::from_millis(10)),
+        );
+        std::thread::scope(|scope| {
+            let running = Running(&server);
+            scope.spawn(|| server.run());
+            test(server.local_addr());
+            drop(running);
+        });
+    }
+
+    /// Minimal HTTP/1.1 client: returns the status and the raw body.
+    fn send(
+        addr: SocketAddr,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: Option<Json>,
+    ) -> (u16, String) {
+        let body = body.map(|b| b.to_string()).unwrap_or_default();
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            path,
+            body.len()
+        );
+        if let Some(token) = token {
+            head.push_str(&format!("{}: {}\r\n", SESSION_HEADER, token));
+        }
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(format!("{}\r\n{}", head, body).as_bytes())
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    fn execute(
+        addr: SocketAddr,
+        token: Option<&str>,
+        statement: &str,
+        params: Json,
+    ) -> (u16, Json) {
+        let body = json!({ "statement": statement, "params": params });
+        let (status, body) = send(addr, "POST", "/execute", token, Some(body));
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    fn open_session(addr: SocketAddr) -> String {
+        let (status, body) = send(addr, "POST", "/sessions", None, None);
+        assert_eq!(status, 201);
+        let body: Json = serde_json::from_str(&body).unwrap();
+        body["session"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_execute_with_params_and_schema() {
+        with_server(|addr| {
+            // GIVEN a job spawned with bound parameters
+            let (status, spawned) = execute(
+                addr,
+                None,
+                "SPAWN j: Job { name = $name, status = \"pending\" }",
+                json!({ "name": "build" }),
+            );
+            assert_eq!(status, 200);
+            assert_eq!(spawned["result"]["type"], "mutation");
+            assert_eq!(spawned["result"]["nodes_created"], 1);
+
+            // WHEN querying it back and asking for the schema
+            let (_, rows) = execute(
+                addr,
+                None,
+                "MATCH j: Job WHERE j.name = $name RETURN j.name, j.status",
+                json!({ "$name": "build" }),
+            );
+            let (_, schema) = send(addr, "GET", "/schema", None, None);
+            let schema: Json = serde_json::from_str(&schema).unwrap();
+
+            // THEN
+            assert_eq!(rows["result"]["columns"], json!(["j.name", "j.status"]));
+            assert_eq!(rows["result"]["types"], json!(["String", "String"]));
+            assert_eq!(rows["result"]["rows"], json!([["build", "pending"]]));
+            assert_eq!(rows["version"], mew_session::WIRE_VERSION);
+            assert_eq!(schema["types"][0]["name"], "Job");
+            assert_eq!(schema["types"][0]["attributes"][0]["name"], "name");
+
+            // AND errors carry a kind and a status
+            let (status, error) = execute(addr, None, "MATCH j: Nope RETURN j", json!({}));
+            assert_eq!(status, 400);
+            assert_eq!(error["error"]["kind"], "analysis");
+            let (status, _) = send(addr, "GET", "/nowhere", None, None);
+            assert_eq!(status, 404);
+
+            // AND statements touching server files are refused
+            let (status, error) = execute(addr, None, "IMPORT Job FROM \"/etc/passwd\"", json!({}));
+            assert_eq!(status, 403);
+            assert_eq!(error["error"]["kind"], "file_access_disabled");
+            let (status, _) = execute(addr, None, "DUMP TO \"/tmp/mew.dump\"", json!({}));
+            assert_eq!(status, 403);
+        });
+    }
+
+    #[test]
+    fn test_aggregate_column_types_follow_their_argument() {
+        with_server(|addr| {
+            // GIVEN two jobs
+            for (name, priority, runtime) in [("a", 1, 1.5), ("b", 2, 2.0)] {
+                execute(
+                    addr,
+                    None,
+                    "SPAWN j: Job { name = $name, priority = $priority, runtime = $runtime }",
+                    json!({ "name": name, "priority": priority, "runtime": runtime }),
+                );
+            }
+
+            // WHEN aggregating Int, Float and String attributes
+            let (status, body) = execute(
+                addr,
+                None,
+                "MATCH j: Job RETURN sum(j.priority) AS si, sum(j.runtime) AS sf, \
+                 avg(j.priority) AS a, min(j.name) AS mn, max(j.priority) AS mx, \
+                 collect(j.name) AS names",
+                json!({}),
+            );
+
+            // THEN each column's type follows the aggregated values
+            assert_eq!(status, 200);
+            assert_eq!(
+                body["result"]["types"],
+                json!(["Int", "Float", "Float", "String", "Int", "[String]"])
+            );
+            assert_eq!(body["result"]["rows"][0][0], 3);
+            assert_eq!(body["result"]["rows"][0][4], 2);
+
+            // AND list literals and COLLECT subqueries name their element type
+            let (_, body) = execute(
+                addr,
+                None,
+                "MATCH j: Job WHERE j.name = \"a\" \
+                 RETURN [j.priority, 2] AS l, COLLECT { k: Job RETURN k.runtime } AS runtimes",
+                json!({}),
+            );
+            assert_eq!(body["result"]["types"], json!(["[Int]", "[Float]"]));
+        });
+    }
+    #[test]
+    fn test_transaction_pinned_to_session() {
+        with_server(|addr| {
+            // GIVEN a transaction opened in a session
+            let token = open_session(addr);
+            let (_, begun) = execute(addr, Some(&token), "BEGIN", json!({}));
+            assert_eq!(begun["result"]["status"], "begun");
+            execute(
+                addr,
+                Some(&token),
+                "SPAWN j: Job { name = \"tx\" }",
+                json!({}),
+            );
+
+            // WHEN another client reads before and after COMMIT
+            let count = |addr| {
+                let (_, rows) = execute(addr, None, "MATCH j: Job RETURN count(j)", json!({}));
+                rows["result"]["rows"][0][0].clone()
+            };
+            let before = count(addr);
+            execute(addr, Some(&token), "COMMIT", json!({}));
+            let after = count(addr);
+
+            // THEN the write becomes visible only at COMMIT
+            assert_eq!(before, json!(0));
+            assert_eq!(after, json!(1));
+
+            // AND transactions need a session, and closed sessions are gone
+            let (status, error) = execute(addr, None, "BEGIN", json!({}));
+            assert_eq!(status, 400);
+            assert!(error["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("POST /sessions"));
+            let (status, _) = send(addr, "DELETE", &format!("/sessions/{}", token), None, None);
+            assert_eq!(status, 200);
+            let (status, error) = execute(addr, Some(&token), "BEGIN", json!({}));
+            assert_eq!(status, 404);
+            assert_eq!(error["error"]["kind"], "session_not_found");
+        });
+    }
+
+    #[test]
+    fn test_watch_events_stream() {
+        with_server(|addr| {
+            // GIVEN a consume watch over two pending jobs
+            let token = open_session(addr);
+            for name in ["a", "b"] {
+                execute(
+                    addr,
+                    None,
+                    "SPAWN j: Job { name = $name, status = \"pending\" }",
+                    json!({ "name": name }),
+                );
+            }
+            let (_, watch) = execute(
+                addr,
+                Some(&token),
+                "WATCH j: Job WHERE j.status = \"pending\" [mode: consume] RETURN j.name",
+                json!({}),
+            );
+            let watch_id = watch["result"]["rows"][0][0].as_i64().unwrap();
+
+            // WHEN streaming its events
+            let path = format!("/sessions/{}/watches/{}/events?limit=2", token, watch_id);
+            let (status, stream) = send(addr, "GET", &path, None, None);
+
+            // THEN both jobs arrive as server-sent events
+            assert_eq!(status, 200);
+            let events: Vec<Json> = stream
+                .lines()
+                .filter_map(|line| line.strip_prefix("data: "))
+                .map(|data| serde_json::from_str(data).unwrap())
+                .collect();
+            assert_eq!(events.len(), 2);
+            assert_eq!(events[0]["event"]["columns"], json!(["j.name"]));
+
+            // AND a delivery is settled with ACK in the same session
+            let delivery = events[0]["event"]["delivery_id"].clone();
+            let (status, acked) = execute(addr, Some(&token), "ACK $id", json!({ "id": delivery }));
+            assert_eq!(status, 200);
+            assert_eq!(acked["result"]["nodes_deleted"], 1);
+            let (status, error) = execute(addr, Some(&token), "ACK $id", json!({ "id": delivery }));
+            assert_eq!(status, 404);
+            assert_eq!(error["error"]["kind"], "invalid_delivery_id");
+
+            // AND unknown watches are reported before streaming starts
+            let path = format!("/sessions/{}/watches/99/events", token);
+            let (status, _) = send(addr, "GET", &path, None, None);
+            assert_eq!(status, 404);
+        });
+    }
+
+    #[test]
+    fn test_finished_stream_keeps_the_connection() {
+        with_server(|addr| {
+            // GIVEN a consume watch over one pending job
+            let token = open_session(addr);
+            execute(addr, None, "SPAWN j: Job { name = \"a\" }", json!({}));
+            let (_, watch) = execute(
+                addr,
+                Some(&token),
+                "WATCH j: Job [mode: consume] RETURN j.name",
+                json!({}),
+            );
+            let watch_id = watch["result"]["rows"][0][0].as_i64().unwrap();
+
+            // WHEN a keep-alive client streams one event
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            let request = format!(
+                "GET /sessions/{}/watches/{}/events?limit=1 HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                token, watch_id
+            );
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = Vec::new();
+            let mut buf = [0; 1024];
+            while !response.ends_with(b"\r\n0\r\n\r\n") {
+                let n = stream.read(&mut buf).expect("stream did not end");
+                assert!(n > 0, "connection closed");
+                response.extend_from_slice(&buf[..n]);
+            }
+
+            // THEN the stream is chunked and ends after the event
+            let response = String::from_utf8(response).unwrap();
+            assert!(response.contains("Transfer-Encoding: chunked"));
+            assert!(response.contains("event: delivery"));
+
+            // AND the connection serves the next request
+            stream
+                .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            let mut next = String::new();
+            stream.read_to_string(&mut next).unwrap();
+            assert!(next.starts_with("HTTP/1.1 200"));
+        });
+    }
+
+    #[test]
+    fn test_request_limits() {
+        with_configured_server(
+            |server| server.with_max_body(128).with_max_connections(1),
+            |addr| {
+                // GIVEN a server taking one request at a time and small bodies
+                let small = json!({ "name": "ok" });
+                let large = json!({ "name": "x".repeat(256) });
+
+                // WHEN sending a body under and one over the limit
+                let (ok, _) = execute(addr, None, "SPAWN j: Job { name = $name }", small);
+                let (status, error) = execute(addr, None, "SPAWN j: Job { name = $name }", large);
+
+                // THEN the large one is refused and later requests still run
+                assert_eq!(ok, 200);
+                assert_eq!(status, 413);
+                assert_eq!(error["error"]["kind"], "payload_too_large");
+                let (_, rows) = execute(addr, None, "MATCH j: Job RETURN count(j)", json!({}));
+                assert_eq!(rows["result"]["rows"][0][0], json!(1));
+
+                // AND session tokens are 128 random bits
+                let (a, b) = (open_session(addr), open_session(addr));
+                assert_eq!(a.len(), 32);
+                assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+                assert_ne!(a, b);
+            },
+        );
+    }
+
+    #[test]
+    fn test_idle_session_expires() {
+        with_configured_server(
+            |server| server.with_session_timeout(Duration::from_millis(50)),
+            |addr| {
+                // GIVEN a session left idle inside a transaction
+                let token = open_session(addr);
+                execute(addr, Some(&token), "BEGIN", json!({}));
+                execute(
+                    addr,
+                    Some(&token),
+                    "SPAWN j: Job { name = \"tx\" }",
+                    json!({}),
+                );
+
+                // WHEN it stays idle past the timeout
+                std::thread::sleep(Duration::from_millis(100));
+                let (status, error) = execute(addr, Some(&token), "COMMIT", json!({}));
+
+                // THEN it is gone and its transaction never committed
+                assert_eq!(status, 404);
+                assert_eq!(error["error"]["kind"], "session_not_found");
+                let (_, rows) = execute(addr, None, "MATCH j: Job RETURN count(j)", json!({}));
+                assert_eq!(rows["result"]["rows"][0][0], json!(0));
+            },
+        );
+    }
+}
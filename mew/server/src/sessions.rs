@@ -0,0 +1,125 @@
+//! Server-side sessions addressed by token.
+//!
+//! A token pins one [`Session`] across requests, so BEGIN, the statements of
+//! a transaction and COMMIT can arrive as separate requests, and watches
+//! registered by a client stay alive until it closes its session.
+//!
+//! Sessions left idle for longer than the pool's timeout are evicted: their
+//! transaction is rolled back and dropping the session releases its pinned
+//! snapshot and private graph.
+
+use mew_session::{Database, Session};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::error::{ServerError, ServerResult};
+
+/// A session shared between the requests that use its token.
+pub(crate) type SharedSession<'db> = Arc<Mutex<Session<'db>>>;
+
+/// Random bytes in a session token.
+const TOKEN_BYTES: usize = 16;
+
+/// An open session and when a request last used it.
+struct Entry<'db> {
+    session: SharedSession<'db>,
+    last_used: Instant,
+}
+
+/// Open sessions by token.
+pub(crate) struct SessionPool<'db> {
+    database: &'db Database,
+    sessions: Mutex<HashMap<String, Entry<'db>>>,
+    idle_timeout: Duration,
+}
+
+impl<'db> SessionPool<'db> {
+    pub fn new(database: &'db Database, idle_timeout: Duration) -> Self {
+        Self {
+            database,
+            sessions: Mutex::new(HashMap::new()),
+            idle_timeout,
+        }
+    }
+
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.idle_timeout = idle_timeout;
+    }
+
+    /// Open a session and return its token.
+    pub fn open(&self) -> String {
+        self.evict_idle();
+        let token = new_token();
+        let entry = Entry {
+            session: Arc::new(Mutex::new(self.database.session())),
+            last_used: Instant::now(),
+        };
+        self.lock().insert(token.clone(), entry);
+        token
+    }
+
+    /// Look up a session by token and mark it as used.
+    pub fn get(&self, token: &str) -> ServerResult<SharedSession<'db>> {
+        self.evict_idle();
+        let mut sessions = self.lock();
+        let entry = sessions
+            .get_mut(token)
+            .ok_or_else(|| ServerError::session_not_found(token))?;
+        entry.last_used = Instant::now();
+        Ok(entry.session.clone())
+    }
+
+    /// Close a session. An open transaction is rolled back.
+    pub fn close(&self, token: &str) -> ServerResult<()> {
+        let entry = self
+            .lock()
+            .remove(token)
+            .ok_or_else(|| ServerError::session_not_found(token))?;
+        lock_session(&entry.session).reset_transaction();
+        Ok(())
+    }
+
+    /// Number of open sessions.
+    pub fn len(&self) -> usize {
+        self.evict_idle();
+        self.lock().len()
+    }
+
+    /// Close every session idle for longer than the timeout.
+    fn evict_idle(&self) {
+        let expired: Vec<Entry<'db>> = {
+            let mut sessions = self.lock();
+            let idle: Vec<String> = sessions
+                .iter()
+                .filter(|(_, entry)| entry.last_used.elapsed() > self.idle_timeout)
+                .map(|(token, _)| token.clone())
+                .collect();
+            idle.iter().filter_map(|t| sessions.remove(t)).collect()
+        };
+        // Roll back outside the pool lock; a request still holding the
+        // session keeps it alive until it finishes
+        for entry in expired {
+            lock_session(&entry.session).reset_transaction();
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Entry<'db>>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A fresh token: 128 bits from the operating system's random source.
+fn new_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::getrandom(&mut bytes).expect("operating system random source is available");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Lock a shared session, ignoring poisoning: a panicking request must not
+/// take the session down with it.
+pub(crate) fn lock_session<'a, 'db>(
+    session: &'a SharedSession<'db>,
+) -> MutexGuard<'a, Session<'db>> {
+    session.lock().unwrap_or_else(|e| e.into_inner())
+}
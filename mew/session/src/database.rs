@@ -0,0 +1,608 @@
+//! Shared multi-session database.
+//!
+//! A [`Database`] owns the committed graph and hands out sessions that work
+//! on snapshots of it (MVCC):
+//! - every committed version is an immutable `Arc<Graph>`, so taking a
+//!   snapshot is a pointer copy and readers never block writers
+//! - a session writes to its own copy of its snapshot, and the graph records
+//!   which entities were written. Graph storage is persistent, so the copy
+//!   shares everything it does not write with the snapshot
+//! - at commit the write set is checked against everything committed since
+//!   the snapshot was taken: the first committer wins, later ones fail with a
+//!   serialization conflict. A commit onto a newer version replays only the
+//!   written entities onto it, so its cost follows the write set, not the
+//!   size of the graph
+//! - written nodes are also checked against the unique attributes of the
+//!   latest version, so two transactions cannot both create the same key
+//!
+//! Isolation levels:
+//! - READ COMMITTED (the default): every statement sees the latest committed
+//!   data plus the transaction's own writes
+//! - SERIALIZABLE: the whole transaction reads the snapshot taken at BEGIN.
+//!   Conflicts are detected on written entities only (snapshot isolation), so
+//!   two transactions with disjoint write sets may still both commit.
+
+use mew_graph::{ChangeSet, Graph};
+use mew_mutation::check_unique_constraints;
+use mew_registry::Registry;
+use mew_transaction::TransactionError;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::error::{SessionError, SessionResult};
+use crate::session::{enable_text_indexes, Session, SessionId};
+use crate::watch::Leases;
+
+/// A database shared by many sessions, possibly on different threads.
+pub struct Database {
+    registry: Registry,
+    state: RwLock<Committed>,
+    next_session_id: AtomicU64,
+    /// Consume-watch messages handed out by any session.
+    leases: Arc<Mutex<Leases>>,
+}
+
+/// The committed state.
+struct Committed {
+    version: u64,
+    graph: Arc<Graph>,
+    /// Write sets of recent commits (oldest first), for conflict checks.
+    log: VecDeque<(u64, ChangeSet)>,
+    /// Snapshot versions held by sessions, with reference counts.
+    pins: BTreeMap<u64, usize>,
+}
+
+impl Committed {
+    /// The first entity in `changes` that was also written after `base`.
+    fn conflict(&self, base: u64, changes: &ChangeSet) -> Option<String> {
+        self.log
+            .iter()
+            .filter(|(version, _)| *version > base)
+            .find_map(|(_, committed)| {
+                let node = committed.nodes.intersection(&changes.nodes).next();
+                let edge = committed.edges.intersection(&changes.edges).next();
+                node.map(|id| format!("node {} was modified", id))
+                    .or_else(|| edge.map(|id| format!("edge {} was modified", id)))
+            })
+    }
+
+    /// Drop log entries that no pinned snapshot can conflict with.
+    fn prune(&mut self) {
+        match self.pins.keys().next().copied() {
+            Some(oldest) => {
+                while self.log.front().is_some_and(|(v, _)| *v <= oldest) {
+                    self.log.pop_front();
+                }
+            }
+            None => self.log.clear(),
+        }
+    }
+}
+
+impl Database {
+    /// Create an empty database.
+    pub fn new(registry: Registry) -> Self {
+        Self::with_graph(registry, Graph::new())
+    }
+
+    /// Create a database whose first committed version is `graph`.
+    pub fn with_graph(registry: Registry, mut graph: Graph) -> Self {
+        enable_text_indexes(&registry, &mut graph);
+        graph.share_ids();
+        graph.track_changes();
+        graph.take_changes();
+        Self {
+            registry,
+            state: RwLock::new(Committed {
+                version: 0,
+                graph: Arc::new(graph),
+                log: VecDeque::new(),
+                pins: BTreeMap::new(),
+            }),
+            next_session_id: AtomicU64::new(1),
+            leases: Arc::default(),
+        }
+    }
+
+    /// Get the registry.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Open a new session on this database.
+    pub fn session(&self) -> Session<'_> {
+        let id: SessionId = self.next_session_id.fetch_add(1, Ordering::Relaxed);
+        Session::attach(id, self)
+    }
+
+    /// Consume-watch deliveries, shared by every session so that two
+    /// consumers never hold the same message.
+    pub(crate) fn leases(&self) -> Arc<Mutex<Leases>> {
+        Arc::clone(&self.leases)
+    }
+
+    /// The latest committed version number.
+    pub fn version(&self) -> u64 {
+        self.read().version
+    }
+
+    /// The latest committed graph.
+    pub fn snapshot(&self) -> Arc<Graph> {
+        Arc::clone(&self.read().graph)
+    }
+
+    /// Take the latest committed version and hold it until `unpin`.
+    pub(crate) fn pin_latest(&self) -> (u64, Arc<Graph>) {
+        let mut state = self.write();
+        let version = state.version;
+        *state.pins.entry(version).or_insert(0) += 1;
+        (version, Arc::clone(&state.graph))
+    }
+
+    /// Take another hold on a version the caller already has pinned.
+    pub(crate) fn repin(&self, version: u64) {
+        *self.write().pins.entry(version).or_insert(0) += 1;
+    }
+
+    /// Release a version taken with `pin_latest`, `repin` or `commit`.
+    pub(crate) fn unpin(&self, version: u64) {
+        let mut state = self.write();
+        if let Some(count) = state.pins.get_mut(&version) {
+            *count -= 1;
+            if *count == 0 {
+                state.pins.remove(&version);
+            }
+        }
+        state.prune();
+    }
+
+    /// Replay a transaction's pending writes onto the latest version.
+    ///
+    /// Returns the new (pinned) version and working graph, or None if the
+    /// working graph is already current or the writes conflict with a
+    /// later commit (COMMIT will report the conflict).
+    pub(crate) fn rebase(&self, base: u64, working: &Graph) -> Option<(u64, Graph)> {
+        let changes = working.changes().cloned().unwrap_or_default();
+        let (version, latest) = self.pin_latest();
+        let rebased = if version == base || self.read().conflict(base, &changes).is_some() {
+            None
+        } else {
+            let mut graph = (*latest).clone();
+            graph
+                .apply_changes(working, &changes)
+                .ok()
+                .filter(|()| self.unique_conflict(&graph, &changes).is_none())
+                .map(|()| graph)
+        };
+        match rebased {
+            Some(graph) => Some((version, graph)),
+            None => {
+                self.unpin(version);
+                None
+            }
+        }
+    }
+
+    /// Commit `changes`, written by `working` on top of version `base`.
+    ///
+    /// On success the session's pin moves from `base` to the new version,
+    /// which is returned together with its graph.
+    pub(crate) fn commit(
+        &self,
+        base: u64,
+        working: &Arc<Graph>,
+        changes: ChangeSet,
+    ) -> SessionResult<(u64, Arc<Graph>)> {
+        loop {
+            let (latest_version, latest) = {
+                let state = self.read();
+                if let Some(message) = state.conflict(base, &changes) {
+                    return Err(conflict_error(message));
+                }
+                (state.version, Arc::clone(&state.graph))
+            };
+
+            // Merge outside the lock; retry if someone committed meanwhile
+            let graph = if latest_version == base {
+                Arc::clone(working)
+            } else {
+                let mut merged = (*latest).clone();
+                merged
+                    .apply_changes(working, &changes)
+                    .map_err(|e| conflict_error(e.to_string()))?;
+                if let Some(message) = self.unique_conflict(&merged, &changes) {
+                    return Err(conflict_error(message));
+                }
+                merged.take_changes();
+                Arc::new(merged)
+            };
+
+            let mut state = self.write();
+            if state.version != latest_version {
+                continue;
+            }
+            state.version += 1;
+            let version = state.version;
+            state.graph = Arc::clone(&graph);
+            state.log.push_back((version, changes));
+            *state.pins.entry(version).or_insert(0) += 1;
+            if let Some(count) = state.pins.get_mut(&base) {
+                *count -= 1;
+                if *count == 0 {
+                    state.pins.remove(&base);
+                }
+            }
+            state.prune();
+            return Ok((version, graph));
+        }
+    }
+
+    /// The first unique attribute value of a node in `changes` that another
+    /// node of `graph` holds too. Writes made on an older version are
+    /// validated only against that version, so uniqueness is checked again
+    /// once they are merged onto a newer one.
+    fn unique_conflict(&self, graph: &Graph, changes: &ChangeSet) -> Option<String> {
+        changes.nodes.iter().find_map(|&id| {
+            let node = graph.get_node(id)?;
+            let type_name = &self.registry.get_type(node.type_id)?.name;
+            check_unique_constraints(
+                &self.registry,
+                graph,
+                type_name,
+                node.type_id,
+                &node.attributes,
+                Some(id),
+            )
+            .err()
+            .map(|e| e.to_string())
+        })
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Committed> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Committed> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn conflict_error(message: String) -> SessionError {
+    SessionError::TransactionError(TransactionError::serialization_conflict(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::StatementResult;
+    use mew_core::Value;
+    use mew_registry::{AttrDef, RegistryBuilder};
+
+    fn test_database() -> Database {
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Task")
+            .attr(AttrDef::new("title", "String"))
+            .attr(AttrDef::new("points", "Int"))
+            .attr(AttrDef::new("code", "String").unique())
+            .done()
+            .unwrap();
+        Database::new(builder.build().unwrap())
+    }
+
+    fn titles(session: &mut Session<'_>) -> Vec<Value> {
+        match session
+            .execute("MATCH t: Task RETURN t.title ORDER BY t.title")
+            .unwrap()
+        {
+            StatementResult::Query(q) => q.rows.into_iter().map(|r| r[0].clone()).collect(),
+            _ => panic!("Expected query result"),
+        }
+    }
+
+    #[test]
+    fn test_database_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Database>();
+    }
+
+    #[test]
+    fn test_sessions_share_committed_data() {
+        // GIVEN two sessions on one database
+        let db = test_database();
+        let mut a = db.session();
+        let mut b = db.session();
+
+        // WHEN A writes in auto-commit mode
+        a.execute(r#"SPAWN t: Task { title = "shared" }"#).unwrap();
+
+        // THEN B sees it on its next statement
+        assert_eq!(titles(&mut b), vec![Value::String("shared".into())]);
+        assert_eq!(db.version(), 1);
+        assert_ne!(a.id(), b.id());
+    }
+
+    #[test]
+    fn test_failed_statement_in_transaction_is_undone() {
+        // GIVEN a transaction on a database with two tasks
+        let db = test_database();
+        let mut setup = db.session();
+        for title in ["a", "b"] {
+            setup
+                .execute(&format!(r#"SPAWN t: Task {{ title = "{}" }}"#, title))
+                .unwrap();
+        }
+        let mut session = db.session();
+        session.execute("BEGIN").unwrap();
+        session
+            .execute(r#"SPAWN t: Task { title = "kept" }"#)
+            .unwrap();
+
+        // WHEN a statement fails after writing: the second unique code clashes
+        let result =
+            session.execute(r#"MATCH t: Task SPAWN d: Task { title = "dup", code = "same" }"#);
+        session.execute("COMMIT").unwrap();
+
+        // THEN only the writes of the successful statements are committed
+        assert!(result.is_err());
+        assert_eq!(
+            titles(&mut setup),
+            vec![
+                Value::String("a".into()),
+                Value::String("b".into()),
+                Value::String("kept".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unique_key_conflict_at_commit() {
+        // GIVEN two transactions each creating a task with the same code
+        let db = test_database();
+        let mut a = db.session();
+        let mut b = db.session();
+        a.execute("BEGIN").unwrap();
+        b.execute("BEGIN").unwrap();
+        a.execute(r#"SPAWN t: Task { title = "a", code = "x" }"#)
+            .unwrap();
+        b.execute(r#"SPAWN t: Task { title = "b", code = "x" }"#)
+            .unwrap();
+
+        // WHEN both commit
+        let first = a.execute("COMMIT");
+        let second = b.execute("COMMIT");
+
+        // THEN the second fails with a conflict and only the first task exists
+        assert!(first.is_ok());
+        let err = second.unwrap_err();
+        assert_eq!(err.kind(), "conflict");
+        assert!(err.to_string().contains("code"));
+        assert_eq!(titles(&mut a), vec![Value::String("a".into())]);
+    }
+
+    #[test]
+    fn test_concurrent_on_conflict_spawn() {
+        // GIVEN two transactions spawning the same code with ON CONFLICT
+        let db = test_database();
+        let upsert = r#"SPAWN t: Task { title = "t", code = "x" } ON CONFLICT (code) DO NOTHING"#;
+        let mut a = db.session();
+        let mut b = db.session();
+        a.execute("BEGIN").unwrap();
+        b.execute("BEGIN").unwrap();
+        a.execute(upsert).unwrap();
+        b.execute(upsert).unwrap();
+
+        // WHEN both commit
+        a.execute("COMMIT").unwrap();
+        let second = b.execute("COMMIT");
+
+        // THEN the second fails with a conflict
+        assert_eq!(second.unwrap_err().kind(), "conflict");
+
+        // AND retrying it finds the committed node instead of creating one
+        let StatementResult::Mutation(retry) = b.execute(upsert).unwrap() else {
+            panic!("Expected mutation result");
+        };
+        assert_eq!(retry.nodes_created, 0);
+        assert_eq!(titles(&mut b), vec![Value::String("t".into())]);
+    }
+
+    #[test]
+    fn test_consumers_share_in_flight_messages() {
+        // GIVEN two pending tasks and a consume watch in each of two sessions
+        let db = test_database();
+        let mut a = db.session();
+        let mut b = db.session();
+        a.execute(r#"SPAWN t: Task { title = "one" }"#).unwrap();
+        a.execute(r#"SPAWN t: Task { title = "two" }"#).unwrap();
+        let watch = |session: &mut Session<'_>| match session
+            .execute("WATCH t: Task [mode: consume] RETURN t.title")
+            .unwrap()
+        {
+            StatementResult::Query(q) => q.rows[0][0].as_int().unwrap() as u64,
+            _ => panic!("Expected query result"),
+        };
+        let (watch_a, watch_b) = (watch(&mut a), watch(&mut b));
+
+        // WHEN both poll
+        let first = a.poll_watch(watch_a, 1).unwrap();
+        let second = b.poll_watch(watch_b, 10).unwrap();
+
+        // THEN each task goes to exactly one consumer
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_ne!(first[0].node, second[0].node);
+        assert!(b.poll_watch(watch_b, 10).unwrap().is_empty());
+
+        // WHEN the first consumer's session ends
+        drop(a);
+
+        // THEN its unsettled task is handed to the other
+        let redelivered = b.poll_watch(watch_b, 10).unwrap();
+        assert_eq!(redelivered.len(), 1);
+        assert_eq!(redelivered[0].node, first[0].node);
+    }
+
+    #[test]
+    fn test_timed_out_message_is_reclaimed_by_another_consumer() {
+        // GIVEN one task handed to consumer A, which never acks or polls again
+        let db = test_database();
+        let mut a = db.session();
+        let mut b = db.session();
+        a.execute(r#"SPAWN t: Task { title = "one" }"#).unwrap();
+        let watch = |session: &mut Session<'_>| match session
+            .execute(
+                "WATCH t: Task [mode: consume, ack_timeout: 200, max_redeliveries: 2] RETURN t",
+            )
+            .unwrap()
+        {
+            StatementResult::Query(q) => q.rows[0][0].as_int().unwrap() as u64,
+            _ => panic!("Expected query result"),
+        };
+        let (watch_a, watch_b) = (watch(&mut a), watch(&mut b));
+        let held = a.poll_watch_at(watch_a, 1, 0).unwrap();
+        assert_eq!(held.len(), 1);
+
+        // WHEN B polls before and after A's ack_timeout
+        let early = b.poll_watch_at(watch_b, 1, 100).unwrap();
+        let late = b.poll_watch_at(watch_b, 1, 200).unwrap();
+
+        // THEN B gets the message once it times out, as a second attempt
+        assert!(early.is_empty());
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].node, held[0].node);
+        assert_eq!(late[0].attempt, 2);
+
+        // WHEN B times out as well and A polls
+        let after = a.poll_watch_at(watch_a, 1, 400).unwrap();
+
+        // THEN the redelivery budget is spent and the message is gone
+        assert!(after.is_empty());
+        assert!(titles(&mut a).is_empty());
+    }
+
+    #[test]
+    fn test_isolation_levels() {
+        // GIVEN one READ COMMITTED and one SERIALIZABLE transaction
+        let db = test_database();
+        let mut writer = db.session();
+        let mut rc = db.session();
+        let mut ser = db.session();
+        rc.execute("BEGIN READ COMMITTED").unwrap();
+        ser.execute("BEGIN SERIALIZABLE").unwrap();
+        rc.execute(r#"SPAWN t: Task { title = "rc" }"#).unwrap();
+
+        // WHEN another session commits while both are open
+        writer
+            .execute(r#"SPAWN t: Task { title = "committed" }"#)
+            .unwrap();
+
+        // THEN READ COMMITTED sees it next to its own write, SERIALIZABLE does not
+        assert_eq!(
+            titles(&mut rc),
+            vec![
+                Value::String("committed".into()),
+                Value::String("rc".into())
+            ]
+        );
+        assert!(titles(&mut ser).is_empty());
+
+        // AND uncommitted writes stay invisible to others until COMMIT
+        assert_eq!(titles(&mut writer), vec![Value::String("committed".into())]);
+        rc.execute("COMMIT").unwrap();
+        ser.execute("COMMIT").unwrap();
+        assert_eq!(titles(&mut writer).len(), 2);
+    }
+
+    #[test]
+    fn test_write_write_conflict_at_commit() {
+        // GIVEN a task and two transactions updating it
+        let db = test_database();
+        let mut setup = db.session();
+        setup
+            .execute(r#"SPAWN t: Task { title = "t", points = 1 }"#)
+            .unwrap();
+        let mut a = db.session();
+        let mut b = db.session();
+        a.execute("BEGIN SERIALIZABLE").unwrap();
+        b.execute("BEGIN SERIALIZABLE").unwrap();
+        a.execute("MATCH t: Task SET t.points = 2").unwrap();
+        b.execute("MATCH t: Task SET t.points = 3").unwrap();
+
+        // WHEN both commit
+        let first = a.execute("COMMIT");
+        let second = b.execute("COMMIT");
+
+        // THEN the first committer wins and the loser is rolled back
+        assert!(first.is_ok());
+        assert!(matches!(
+            second,
+            Err(SessionError::TransactionError(
+                TransactionError::SerializationConflict { .. }
+            ))
+        ));
+        assert!(!b.in_transaction());
+        let points = match b.execute("MATCH t: Task RETURN t.points").unwrap() {
+            StatementResult::Query(q) => q.rows[0][0].clone(),
+            _ => panic!("Expected query result"),
+        };
+        assert_eq!(points, Value::Int(2));
+    }
+
+    #[test]
+    fn test_rollback_to_savepoint_on_shared_database() {
+        // GIVEN a transaction with a savepoint, and a commit from elsewhere
+        let db = test_database();
+        let mut a = db.session();
+        let mut other = db.session();
+        a.execute("BEGIN").unwrap();
+        a.execute(r#"SPAWN t: Task { title = "kept" }"#).unwrap();
+        a.execute("SAVEPOINT sp").unwrap();
+        a.execute(r#"SPAWN t: Task { title = "dropped" }"#).unwrap();
+        other
+            .execute(r#"SPAWN t: Task { title = "other" }"#)
+            .unwrap();
+
+        // WHEN rolling back to the savepoint and committing
+        a.execute("ROLLBACK TO sp").unwrap();
+        a.execute("COMMIT").unwrap();
+
+        // THEN the writes before the savepoint and the other commit survive
+        assert_eq!(
+            titles(&mut other),
+            vec![Value::String("kept".into()), Value::String("other".into())]
+        );
+        drop(a);
+        drop(other);
+        assert!(db.read().pins.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_writers_on_threads() {
+        // GIVEN a database shared by four threads
+        let db = test_database();
+
+        // WHEN each thread spawns tasks through its own session
+        std::thread::scope(|scope| {
+            for worker in 0..4 {
+                let db = &db;
+                scope.spawn(move || {
+                    let mut session = db.session();
+                    for i in 0..5 {
+                        session
+                            .execute(&format!(
+                                r#"SPAWN t: Task {{ title = "w{}-{}" }}"#,
+                                worker, i
+                            ))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        // THEN no write is lost
+        assert_eq!(db.snapshot().node_count(), 20);
+        assert_eq!(db.version(), 20);
+    }
+}
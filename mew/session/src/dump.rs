@@ -0,0 +1,513 @@
+//! Logical dumps: a database written out as a MEW script.
+//!
+//! A dump holds the ontology source in a `load ontology { ... }` block,
+//! then one transaction that rebuilds the data:
+//!
+//! ```text
+//! -- MEW dump
+//! load ontology {
+//! node Person { name: String }
+//! edge knows(a: Person, b: Person) { since: Int }
+//! }
+//!
+//! BEGIN
+//! SPAWN n1: Person { name = "Ada" }
+//! SPAWN n2: Person { name = "Bob" }
+//! LINK e1: knows(n1, n2) { since = 2020 }
+//! COMMIT
+//! ```
+//!
+//! Nodes and edges are named `n1`, `n2`, ... and `e1`, `e2`, ... in ID
+//! order, so dumping a restored database gives the same script. Edges come
+//! after their targets, which may be edges themselves. Attribute values that
+//! refer to entities are written as ID references to these names (`#n2`) and
+//! set once every entity exists, so restoring maps them to the new IDs:
+//!
+//! ```text
+//! SET n1 { related = [#n2] }
+//! ```
+//!
+//! Floats without a literal (NaN and the infinities) cannot be dumped; the
+//! dump fails before anything is written. The script runs as it stands in
+//! the REPL, and [`restore`] rebuilds the registry and graph from it.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write;
+
+use mew_core::time::format_timestamp;
+use mew_core::{Attributes, EntityId, Value};
+use mew_graph::Graph;
+use mew_registry::Registry;
+
+use crate::error::{SessionError, SessionResult};
+use crate::session::Session;
+
+/// First line of every dump.
+pub const DUMP_HEADER: &str = "-- MEW dump";
+
+/// Write the ontology and the data of a database as a MEW script.
+///
+/// The registry must have been compiled from source, which the dump
+/// repeats; a registry built in code has no source to write.
+pub fn write_dump(registry: &Registry, graph: &Graph, out: &mut dyn Write) -> SessionResult<()> {
+    let source = registry.source().ok_or_else(|| {
+        SessionError::dump_error("DUMP needs a registry compiled from ontology source")
+    })?;
+
+    let mut nodes: Vec<_> = graph.all_node_ids().collect();
+    let mut edges: Vec<_> = graph.all_edge_ids().collect();
+    nodes.sort();
+    edges.sort();
+    let mut names: Names = HashMap::new();
+    for (i, id) in nodes.iter().enumerate() {
+        names.insert((*id).into(), format!("n{}", i + 1));
+    }
+    for (i, id) in edges.iter().enumerate() {
+        names.insert((*id).into(), format!("e{}", i + 1));
+    }
+
+    // Render everything first, so a value that cannot be dumped leaves
+    // nothing half-written
+    let mut text = String::new();
+    writeln!(text, "{}", DUMP_HEADER).unwrap();
+    writeln!(text, "load ontology {{").unwrap();
+    writeln!(text, "{}", source.trim()).unwrap();
+    writeln!(text, "}}").unwrap();
+    writeln!(text).unwrap();
+    writeln!(text, "BEGIN").unwrap();
+
+    // Attributes referring to entities, set once all of them exist
+    let mut deferred = Vec::new();
+
+    for id in &nodes {
+        let Some(node) = graph.get_node(*id) else {
+            continue;
+        };
+        let name = &names[&(*id).into()];
+        let type_name = registry
+            .get_type(node.type_id)
+            .map(|t| t.name.as_str())
+            .unwrap_or("?");
+        let (attrs, refs) = attr_blocks(name, &node.attributes, &names)?;
+        writeln!(text, "SPAWN {}: {}{}", name, type_name, attrs).unwrap();
+        if !refs.is_empty() {
+            deferred.push(format!("SET {}{}", name, refs));
+        }
+    }
+
+    for id in &edges {
+        let Some(edge) = graph.get_edge(*id) else {
+            continue;
+        };
+        let name = &names[&(*id).into()];
+        let type_name = registry
+            .get_edge_type(edge.type_id)
+            .map(|e| e.name.as_str())
+            .unwrap_or("?");
+        let targets: Vec<&str> = edge.targets.iter().map(|t| names[t].as_str()).collect();
+        let (attrs, refs) = attr_blocks(name, &edge.attributes, &names)?;
+        writeln!(
+            text,
+            "LINK {}: {}({}){}",
+            name,
+            type_name,
+            targets.join(", "),
+            attrs
+        )
+        .unwrap();
+        if !refs.is_empty() {
+            deferred.push(format!("SET {}{}", name, refs));
+        }
+    }
+
+    for set in deferred {
+        writeln!(text, "{}", set).unwrap();
+    }
+    writeln!(text, "COMMIT").unwrap();
+
+    out.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+/// Rebuild the registry and graph of a dump.
+pub fn restore(script: &str) -> SessionResult<(Registry, Graph)> {
+    let (source, data) = split_dump(script)?;
+    let registry = mew_compiler::compile(source)?;
+    let graph = {
+        let mut session = Session::new(0, &registry);
+        session.execute_all(data)?;
+        session.graph().clone()
+    };
+    Ok((registry, graph))
+}
+
+/// Split a dump into its ontology source and its statements.
+fn split_dump(script: &str) -> SessionResult<(&str, &str)> {
+    let invalid = |message: &str| SessionError::dump_error(message);
+    let start = script
+        .to_ascii_lowercase()
+        .find("load ontology")
+        .ok_or_else(|| invalid("not a dump: no 'load ontology' block"))?;
+    let open = start
+        + script[start..]
+            .find('{')
+            .ok_or_else(|| invalid("not a dump: 'load ontology' without a block"))?;
+
+    // Find the matching brace, skipping strings and comments
+    let mut depth = 0usize;
+    let mut chars = script[open..].char_indices();
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    let close = open + offset;
+                    return Ok((&script[open + 1..close], &script[close + 1..]));
+                }
+            }
+            '"' => {
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '-' if script[open + offset..].starts_with("--") => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(invalid("not a dump: unterminated 'load ontology' block"))
+}
+
+/// Dump names of the nodes and edges.
+type Names = HashMap<EntityId, String>;
+
+/// The attribute blocks of the entity dumped as `entity`: the one of its
+/// SPAWN or LINK, and the one of a later SET for the attributes that refer
+/// to entities. Each is empty when there is nothing to assign. Null
+/// attributes are left out.
+fn attr_blocks(
+    entity: &str,
+    attributes: &Attributes,
+    names: &Names,
+) -> SessionResult<(String, String)> {
+    let mut attrs: Vec<_> = attributes.iter().filter(|(_, v)| !v.is_null()).collect();
+    attrs.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut items = Vec::new();
+    let mut refs = Vec::new();
+    for (name, value) in attrs {
+        let mut text = format!("{} = ", name);
+        write_literal(&mut text, value, names).map_err(|reason| {
+            SessionError::dump_error(format!("cannot dump {}.{}: {}", entity, name, reason))
+        })?;
+        if has_refs(value) {
+            refs.push(text);
+        } else {
+            items.push(text);
+        }
+    }
+    Ok((block(&items), block(&refs)))
+}
+
+fn block(items: &[String]) -> String {
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!(" {{ {} }}", items.join(", "))
+    }
+}
+
+/// Check whether a value is or holds a node or edge reference.
+fn has_refs(value: &Value) -> bool {
+    match value {
+        Value::NodeRef(_) | Value::EdgeRef(_) => true,
+        Value::List(items) => items.iter().any(has_refs),
+        Value::Map(entries) => entries.values().any(has_refs),
+        _ => false,
+    }
+}
+
+/// Write a float, which must have a literal.
+fn write_float<F: Copy + Into<f64> + std::fmt::Debug>(
+    out: &mut String,
+    f: F,
+) -> Result<(), String> {
+    if !f.into().is_finite() {
+        return Err(format!("{} has no literal", f.into()));
+    }
+    // Debug keeps a fraction or an exponent, so the value reads back as a Float
+    write!(out, "{:?}", f).unwrap();
+    Ok(())
+}
+
+/// Write a comma-separated list of floats.
+fn write_floats<F: Copy + Into<f64> + std::fmt::Debug>(
+    out: &mut String,
+    floats: &[F],
+) -> Result<(), String> {
+    for (i, f) in floats.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_float(out, *f)?;
+    }
+    Ok(())
+}
+
+/// Write a value as an expression that evaluates back to it, references
+/// as ID references to the dump names of their entities. Fails with the
+/// reason when the value has no such expression.
+fn write_literal(out: &mut String, value: &Value, names: &Names) -> Result<(), String> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => write!(out, "{}", b).unwrap(),
+        Value::Int(i) => write!(out, "{}", i).unwrap(),
+        Value::Float(f) => write_float(out, *f)?,
+        Value::String(s) => write_string(out, s),
+        Value::Timestamp(ms) => write!(out, "@{}", format_timestamp(*ms)).unwrap(),
+        Value::Duration(ms) => write!(out, "{}.ms", ms).unwrap(),
+        Value::Bytes(_) | Value::Hash(_) => write!(out, "{}", value).unwrap(),
+        Value::Vector(v) => {
+            out.push_str("vector([");
+            write_floats(out, v)?;
+            out.push_str("])");
+        }
+        Value::Point(p) => {
+            out.push_str("point(");
+            write_floats(out, &p.coords()[..p.dims()])?;
+            out.push(')');
+        }
+        Value::List(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_literal(out, item, names)?;
+            }
+            out.push(']');
+        }
+        Value::Map(entries) => {
+            out.push('{');
+            for (i, (key, item)) in entries.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_string(out, key);
+                out.push_str(": ");
+                write_literal(out, item, names)?;
+            }
+            out.push('}');
+        }
+        Value::NodeRef(id) => write_ref(out, (*id).into(), names)?,
+        Value::EdgeRef(id) => write_ref(out, (*id).into(), names)?,
+    }
+    Ok(())
+}
+
+/// Write a reference as an ID reference to its entity's dump name.
+fn write_ref(out: &mut String, id: EntityId, names: &Names) -> Result<(), String> {
+    let name = names
+        .get(&id)
+        .ok_or_else(|| format!("it refers to {:?}, which no longer exists", id))?;
+    write!(out, "#{}", name).unwrap();
+    Ok(())
+}
+
+/// Write a string literal, escaping what the lexer unescapes.
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONTOLOGY: &str = r#"
+        node Person { name: String, born: Timestamp?, score: Float?, tags: Json? }
+        edge knows(a: Person, b: Person) { since: Int? }
+        edge meeting(host: Person, guest: Person, witness: Person)
+        edge confirms(who: Person, fact: any) { note: String? }
+    "#;
+
+    fn dump_string(registry: &Registry, graph: &Graph) -> String {
+        let mut out = Vec::new();
+        write_dump(registry, graph, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn test_database() -> (Registry, Graph) {
+        let registry = mew_compiler::compile(ONTOLOGY).unwrap();
+        let graph = {
+            let mut session = Session::new(1, &registry);
+            session
+                .execute_all(
+                    r#"
+                    SPAWN ada: Person { name = "Ada \"the\" first", born = @1815-12-10T00:00:00Z, score = 2.0 }
+                    SPAWN bob: Person { name = "Bob\nSmith", score = 0.1 }
+                    SPAWN cy: Person { name = "Cy" }
+                    SET cy { tags = {"role": "witness", "ids": [1, 2]} }
+                    LINK k: knows(ada, bob) { since = 1833 }
+                    LINK meeting(ada, bob, cy)
+                    LINK confirms(cy, k) { note = "seen" }
+                    SET ada { tags = {"friend": #bob, "seen": [#k]} }
+                    "#,
+                )
+                .unwrap();
+            session.graph().clone()
+        };
+        (registry, graph)
+    }
+
+    #[test]
+    fn test_dump_names_entities_in_id_order() {
+        // GIVEN
+        let (registry, graph) = test_database();
+
+        // WHEN
+        let dump = dump_string(&registry, &graph);
+
+        // THEN the ontology comes first, then the data in one transaction
+        assert!(dump.starts_with("-- MEW dump\nload ontology {\nnode Person"));
+        assert!(dump.contains(
+            "SPAWN n1: Person { born = @1815-12-10T00:00:00.000Z, name = \"Ada \\\"the\\\" first\", score = 2.0 }"
+        ));
+        assert!(dump.contains("SPAWN n2: Person { name = \"Bob\\nSmith\", score = 0.1 }\n"));
+        assert!(dump.contains("LINK e1: knows(n1, n2) { since = 1833 }\n"));
+        assert!(dump.contains("LINK e2: meeting(n1, n2, n3)\n"));
+
+        // AND the higher-order edge targets the edge by its symbolic name
+        assert!(dump.contains("LINK e3: confirms(n3, e1) { note = \"seen\" }\n"));
+
+        // AND structured values are written as literals
+        assert!(dump.contains("SPAWN n3: Person { name = \"Cy\", tags = {"));
+
+        // AND references are set by dump name once every entity exists
+        assert!(dump.ends_with(
+            "LINK e3: confirms(n3, e1) { note = \"seen\" }\n\
+             SET n1 { tags = {\"friend\": #n2, \"seen\": [#e1]} }\n\
+             COMMIT\n"
+        ));
+    }
+
+    #[test]
+    fn test_restore_reproduces_the_database() {
+        // GIVEN a dump
+        let (registry, graph) = test_database();
+        let dump = dump_string(&registry, &graph);
+
+        // WHEN it is restored
+        let (restored_registry, restored) = restore(&dump).unwrap();
+
+        // THEN the graph is the same and dumps to the same script
+        assert_eq!(restored.node_count(), 3);
+        assert_eq!(restored.edge_count(), 3);
+        assert_eq!(dump_string(&restored_registry, &restored), dump);
+    }
+
+    #[test]
+    fn test_restore_remaps_references() {
+        // GIVEN a dump of a database whose IDs do not start at 1
+        let registry = mew_compiler::compile(ONTOLOGY).unwrap();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute_all(
+                r#"
+                SPAWN gone: Person { name = "Gone" }
+                KILL gone
+                SPAWN ada: Person { name = "Ada" }
+                SPAWN bob: Person { name = "Bob", tags = [#ada] }
+                "#,
+            )
+            .unwrap();
+        let dump = dump_string(&registry, session.graph());
+
+        // WHEN it is restored under new IDs
+        let (_, restored) = restore(&dump).unwrap();
+
+        // THEN the reference points at the restored Ada
+        let bob = restored
+            .all_node_ids()
+            .find(|id| restored.get_node(*id).unwrap().get_attr("name") == Some(&"Bob".into()))
+            .unwrap();
+        let Some(Value::List(tags)) = restored.get_node(bob).unwrap().get_attr("tags") else {
+            panic!("tags is not a list");
+        };
+        let ada = tags[0].as_node_ref().unwrap();
+        assert_eq!(
+            restored.get_node(ada).unwrap().get_attr("name"),
+            Some(&"Ada".into())
+        );
+    }
+
+    #[test]
+    fn test_dump_rejects_floats_without_literal() {
+        // GIVEN a float attribute holding NaN
+        let (registry, mut graph) = test_database();
+        let ada = graph.all_node_ids().min().unwrap();
+        graph
+            .set_node_attr(ada, "score", Value::Float(f64::NAN))
+            .unwrap();
+
+        // WHEN
+        let mut out = Vec::new();
+        let result = write_dump(&registry, &graph, &mut out);
+
+        // THEN the dump names the value and writes nothing
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), "dump");
+        assert!(err
+            .to_string()
+            .contains("cannot dump n1.score: NaN has no literal"));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_dump_needs_ontology_source() {
+        // GIVEN a registry built in code
+        let registry = mew_registry::RegistryBuilder::new().build().unwrap();
+
+        // WHEN
+        let result = write_dump(&registry, &Graph::new(), &mut Vec::new());
+
+        // THEN
+        assert_eq!(result.unwrap_err().kind(), "dump");
+    }
+
+    #[test]
+    fn test_split_dump_skips_braces_in_strings_and_comments() {
+        // GIVEN
+        let script = "load ontology {\n  node T { s: String = \"}\" } -- }\n}\nSPAWN T {}";
+
+        // WHEN
+        let (source, data) = split_dump(script).unwrap();
+
+        // THEN
+        assert_eq!(source, "\n  node T { s: String = \"}\" } -- }\n");
+        assert_eq!(data, "\nSPAWN T {}");
+    }
+}
@@ -33,6 +33,14 @@ pub enum SessionError {
     #[error("pattern error: {0}")]
     PatternError(#[from] mew_pattern::PatternError),
 
+    /// Import error.
+    #[error("import error: {0}")]
+    ImportError(#[from] mew_import::ImportError),
+
+    /// Graph algorithm error.
+    #[error("algorithm error: {0}")]
+    AlgoError(#[from] mew_algo::AlgoError),
+
     /// Session not found.
     #[error("session not found: {id}")]
     SessionNotFound { id: u64 },
@@ -44,6 +52,31 @@ pub enum SessionError {
     /// Constraint violation.
     #[error("constraint violation: {message}")]
     ConstraintViolation { message: String },
+
+    /// ACK/NACK of an unknown or already settled delivery.
+    #[error("Invalid delivery ID: '{delivery_id}' not found or already processed")]
+    InvalidDeliveryId { delivery_id: String },
+
+    /// Watch not found.
+    #[error("watch not found: {id}")]
+    WatchNotFound { id: u64 },
+
+    /// A database that cannot be dumped, or a dump that cannot be read.
+    #[error("dump error: {message}")]
+    DumpError { message: String },
+
+    /// A statement that reads or writes server files, in a session that
+    /// does not allow it.
+    #[error("file access is disabled: {statement} reads or writes files")]
+    FileAccessDisabled { statement: String },
+
+    /// IO error while writing a file.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// An export document that cannot be loaded.
+    #[error("invalid export: {message}")]
+    InvalidExport { message: String },
 }
 
 impl SessionError {
@@ -62,11 +95,66 @@ impl SessionError {
         Self::SessionNotFound { id }
     }
 
+    pub fn invalid_delivery_id(delivery_id: impl Into<String>) -> Self {
+        Self::InvalidDeliveryId {
+            delivery_id: delivery_id.into(),
+        }
+    }
+
+    pub fn watch_not_found(id: u64) -> Self {
+        Self::WatchNotFound { id }
+    }
+
+    pub fn dump_error(message: impl Into<String>) -> Self {
+        Self::DumpError {
+            message: message.into(),
+        }
+    }
+
+    pub fn file_access_disabled(statement: impl Into<String>) -> Self {
+        Self::FileAccessDisabled {
+            statement: statement.into(),
+        }
+    }
+
+    pub fn invalid_export(message: impl Into<String>) -> Self {
+        Self::InvalidExport {
+            message: message.into(),
+        }
+    }
+
     pub fn invalid_statement_type(message: impl Into<String>) -> Self {
         Self::InvalidStatementType {
             message: message.into(),
         }
     }
+
+    /// Machine-readable error kind, as carried by the wire encoding.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ParseError(_) => "parse",
+            Self::AnalysisError { .. } => "analysis",
+            Self::QueryError(_) | Self::PatternError(_) => "query",
+            Self::MutationError(_) => "mutation",
+            Self::TransactionError(mew_transaction::TransactionError::SerializationConflict {
+                ..
+            }) => "conflict",
+            Self::TransactionError(_) => "transaction",
+            Self::CompileError(_) => "compile",
+            Self::ImportError(_) => "import",
+            Self::AlgoError(mew_algo::AlgoError::Write(_)) => "mutation",
+            Self::AlgoError(_) => "algo",
+            Self::SessionNotFound { .. } => "session_not_found",
+            Self::InvalidStatementType { .. } => "invalid_statement",
+            Self::ConstraintViolation { .. } => "constraint",
+            Self::InvalidDeliveryId { .. } => "invalid_delivery_id",
+            Self::WatchNotFound { .. } => "watch_not_found",
+            Self::DumpError { .. } => "dump",
+            Self::FileAccessDisabled { .. } => "file_access_disabled",
+            Self::Io(_) => "io",
+            Self::InvalidExport { .. } => "invalid_export",
+        }
+    }
 }
 
 impl From<mew_pattern::TargetError> for SessionError {
@@ -0,0 +1,122 @@
+//! Graphviz DOT export.
+//!
+//! Vertices are labeled with their type and attributes, binary edges with
+//! their type and attributes. Reified edges are drawn as diamonds, with
+//! dashed lines to their targets labeled by parameter name.
+
+use std::io::{self, Write};
+
+use mew_core::Attributes;
+use mew_graph::Graph;
+use mew_registry::Registry;
+
+use super::{
+    edge_type_name, is_symmetric, node_type_name, sorted_attrs, target_roles, vertex_id, Subgraph,
+};
+
+/// Write a subgraph as a DOT digraph.
+pub fn write_dot(
+    registry: &Registry,
+    graph: &Graph,
+    subgraph: &Subgraph,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let reified = subgraph.reified(graph);
+
+    writeln!(out, "digraph mew {{")?;
+    for node in subgraph.node_entries(graph) {
+        writeln!(
+            out,
+            "  {} [label=\"{}\"];",
+            vertex_id(node.id.into()),
+            label(&node_type_name(registry, node), &node.attributes)
+        )?;
+    }
+
+    for edge in subgraph.edge_entries(graph) {
+        let label = label(&edge_type_name(registry, edge), &edge.attributes);
+        if !reified.contains(&edge.id) {
+            let dir = if is_symmetric(registry, edge) {
+                ", dir=none"
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                "  {} -> {} [label=\"{}\"{}];",
+                vertex_id(edge.targets[0]),
+                vertex_id(edge.targets[1]),
+                label,
+                dir
+            )?;
+            continue;
+        }
+
+        let id = vertex_id(edge.id.into());
+        writeln!(out, "  {} [shape=diamond, label=\"{}\"];", id, label)?;
+        for (target, role) in edge.targets.iter().zip(target_roles(registry, edge)) {
+            writeln!(
+                out,
+                "  {} -> {} [label=\"{}\", style=dashed];",
+                id,
+                vertex_id(*target),
+                escape(&role)
+            )?;
+        }
+    }
+    writeln!(out, "}}")
+}
+
+/// A label of the type name followed by one `name: value` line per
+/// attribute, escaped for a quoted DOT string.
+fn label(type_name: &str, attributes: &Attributes) -> String {
+    let mut lines = vec![escape(type_name)];
+    for (name, value) in sorted_attrs(attributes) {
+        lines.push(escape(&format!("{}: {}", name, value)));
+    }
+    lines.join("\\n")
+}
+
+/// Escape text for a quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{test_graph, test_registry};
+    use super::*;
+
+    #[test]
+    fn test_dot_reifies_higher_order_edges() {
+        // GIVEN
+        let registry = test_registry();
+        let (graph, people, edges) = test_graph(&registry);
+
+        // WHEN
+        let mut out = Vec::new();
+        write_dot(&registry, &graph, &Subgraph::full(&graph), &mut out).unwrap();
+        let dot = String::from_utf8(out).unwrap();
+
+        // THEN nodes are labeled with their type and attributes
+        assert!(dot.starts_with("digraph mew {\n"));
+        assert!(dot.contains(&format!(
+            "  n{} [label=\"Person\\nname: \\\"Ada\\\"\"];",
+            people[0].raw()
+        )));
+
+        // AND the edge a higher-order edge targets is a vertex too
+        let (knows, confirms) = (edges[0].raw(), edges[2].raw());
+        assert!(dot.contains(&format!(
+            "  e{} [shape=diamond, label=\"knows\\nsince: 2020\"];",
+            knows
+        )));
+        assert!(dot.contains(&format!(
+            "  e{} -> e{} [label=\"fact\", style=dashed];",
+            confirms, knows
+        )));
+        assert!(dot.ends_with("}\n"));
+    }
+}
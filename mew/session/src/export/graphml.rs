@@ -0,0 +1,277 @@
+//! GraphML export.
+//!
+//! Every vertex carries its type in `_type` and whether it is a node or a
+//! reified edge in `_kind`; the edges linking a reified edge to its targets
+//! carry the parameter name in `_role`. Attributes become GraphML keys typed
+//! from their values: booleans, integers and floats keep their type, and
+//! everything else is written as a string (structured values in the wire
+//! JSON encoding).
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use mew_core::{EntityId, Value};
+use mew_graph::Graph;
+use mew_registry::Registry;
+
+use super::{
+    edge_type_name, is_symmetric, node_type_name, sorted_attrs, target_roles, vertex_id, Subgraph,
+};
+use crate::wire::value_to_json;
+
+/// Write a subgraph as a GraphML document.
+pub fn write_graphml(
+    registry: &Registry,
+    graph: &Graph,
+    subgraph: &Subgraph,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let reified = subgraph.reified(graph);
+    let binary = |id| !reified.contains(&id);
+
+    // Attribute keys of vertices (nodes and reified edges) and of edges
+    let mut vertex_keys = Keys::default();
+    let mut edge_keys = Keys::default();
+    for node in subgraph.node_entries(graph) {
+        vertex_keys.add_all(&node.attributes);
+    }
+    for edge in subgraph.edge_entries(graph) {
+        if binary(edge.id) {
+            edge_keys.add_all(&edge.attributes);
+        } else {
+            vertex_keys.add_all(&edge.attributes);
+        }
+    }
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="type" for="all" attr.name="_type" attr.type="string"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="kind" for="node" attr.name="_kind" attr.type="string"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="role" for="edge" attr.name="_role" attr.type="string"/>"#
+    )?;
+    vertex_keys.write(out, "node", "v")?;
+    edge_keys.write(out, "edge", "e")?;
+    writeln!(out, r#"  <graph id="mew" edgedefault="directed">"#)?;
+
+    for node in subgraph.node_entries(graph) {
+        let id = vertex_id(node.id.into());
+        writeln!(out, r#"    <node id="{}">"#, id)?;
+        write_data(out, "type", &node_type_name(registry, node))?;
+        write_data(out, "kind", "node")?;
+        vertex_keys.write_values(out, "v", &node.attributes)?;
+        writeln!(out, "    </node>")?;
+    }
+
+    for edge in subgraph.edge_entries(graph) {
+        let id = vertex_id(edge.id.into());
+        let type_name = edge_type_name(registry, edge);
+        if binary(edge.id) {
+            let directed = if is_symmetric(registry, edge) {
+                r#" directed="false""#
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                r#"    <edge id="{}" source="{}" target="{}"{}>"#,
+                id,
+                vertex_id(edge.targets[0]),
+                vertex_id(edge.targets[1]),
+                directed
+            )?;
+            write_data(out, "type", &type_name)?;
+            edge_keys.write_values(out, "e", &edge.attributes)?;
+            writeln!(out, "    </edge>")?;
+            continue;
+        }
+
+        writeln!(out, r#"    <node id="{}">"#, id)?;
+        write_data(out, "type", &type_name)?;
+        write_data(out, "kind", "edge")?;
+        vertex_keys.write_values(out, "v", &edge.attributes)?;
+        writeln!(out, "    </node>")?;
+        for (i, (target, role)) in edge
+            .targets
+            .iter()
+            .zip(target_roles(registry, edge))
+            .enumerate()
+        {
+            write_role_edge(out, &id, i, *target, &role)?;
+        }
+    }
+
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")
+}
+
+fn write_role_edge(
+    out: &mut dyn Write,
+    source: &str,
+    position: usize,
+    target: EntityId,
+    role: &str,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        r#"    <edge id="{}.{}" source="{}" target="{}">"#,
+        source,
+        position,
+        source,
+        vertex_id(target)
+    )?;
+    write_data(out, "role", role)?;
+    writeln!(out, "    </edge>")
+}
+
+fn write_data(out: &mut dyn Write, key: &str, text: &str) -> io::Result<()> {
+    writeln!(out, r#"      <data key="{}">{}</data>"#, key, escape(text))
+}
+
+/// Attribute names and the GraphML type of their values.
+#[derive(Default)]
+struct Keys(BTreeMap<String, &'static str>);
+
+impl Keys {
+    fn add_all(&mut self, attributes: &mew_core::Attributes) {
+        for (name, value) in attributes {
+            let Some(new) = graphml_type(value) else {
+                continue;
+            };
+            self.0
+                .entry(name.clone())
+                .and_modify(|old| {
+                    *old = match (*old, new) {
+                        (a, b) if a == b => a,
+                        ("long" | "double", "long" | "double") => "double",
+                        _ => "string",
+                    }
+                })
+                .or_insert(new);
+        }
+    }
+
+    fn write(&self, out: &mut dyn Write, domain: &str, prefix: &str) -> io::Result<()> {
+        for (name, attr_type) in &self.0 {
+            writeln!(
+                out,
+                r#"  <key id="{}.{}" for="{}" attr.name="{}" attr.type="{}"/>"#,
+                prefix,
+                escape(name),
+                domain,
+                escape(name),
+                attr_type
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_values(
+        &self,
+        out: &mut dyn Write,
+        prefix: &str,
+        attributes: &mew_core::Attributes,
+    ) -> io::Result<()> {
+        for (name, value) in sorted_attrs(attributes) {
+            let text = match (self.0.get(name), value) {
+                (_, Value::Null) | (None, _) => continue,
+                (Some(&"string"), Value::String(s)) => s.clone(),
+                (Some(&"string"), Value::Bool(_) | Value::Int(_) | Value::Float(_)) => {
+                    value.to_string()
+                }
+                (Some(&"string"), _) => value_to_json(value).to_string(),
+                _ => value.to_string(),
+            };
+            write_data(out, &format!("{}.{}", prefix, name), &text)?;
+        }
+        Ok(())
+    }
+}
+
+/// The GraphML type a value is written as; `None` for null.
+fn graphml_type(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some("boolean"),
+        Value::Int(_) => Some("long"),
+        Value::Float(f) if f.is_finite() => Some("double"),
+        _ => Some("string"),
+    }
+}
+
+/// Escape text for XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{test_graph, test_registry};
+    use super::*;
+
+    #[test]
+    fn test_graphml_reifies_hyperedges() {
+        // GIVEN
+        let registry = test_registry();
+        let (graph, people, edges) = test_graph(&registry);
+        let mut subgraph = Subgraph::full(&graph);
+        subgraph.edges.remove(&edges[2]);
+
+        // WHEN
+        let mut out = Vec::new();
+        write_graphml(&registry, &graph, &subgraph, &mut out).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+
+        // THEN the binary edge is an edge with a typed attribute
+        let (ada, bob) = (people[0].raw(), people[1].raw());
+        assert!(
+            xml.contains(r#"<key id="e.since" for="edge" attr.name="since" attr.type="long"/>"#)
+        );
+        assert!(xml.contains(&format!(
+            r#"<edge id="e{}" source="n{}" target="n{}">"#,
+            edges[0].raw(),
+            ada,
+            bob
+        )));
+        assert!(xml.contains(r#"<data key="e.since">2020</data>"#));
+
+        // AND the meeting is a vertex linked to its three targets by role
+        let meeting = edges[1].raw();
+        assert!(xml.contains(&format!(
+            "<node id=\"e{}\">\n      <data key=\"type\">meeting</data>\n      <data key=\"kind\">edge</data>",
+            meeting
+        )));
+        assert!(xml.contains(&format!(
+            "<edge id=\"e{m}.0\" source=\"e{m}\" target=\"n{ada}\">\n      <data key=\"role\">host</data>",
+            m = meeting,
+            ada = ada
+        )));
+        assert_eq!(xml.matches(&format!("source=\"e{}\"", meeting)).count(), 3);
+    }
+
+    #[test]
+    fn test_graphml_escapes_text() {
+        assert_eq!(escape(r#"<a & "b">"#), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+}
@@ -0,0 +1,309 @@
+//! Lossless JSON export and its loader.
+//!
+//! ```text
+//! {"version": 1,
+//!  "nodes": [{"id": 1, "type": "Person", "attrs": {"name": "Ada"}}],
+//!  "edges": [{"id": 3, "type": "knows",
+//!             "targets": [{"_type": "node", "_id": 1}, {"_type": "node", "_id": 2}],
+//!             "attrs": {"since": 2020}}]}
+//! ```
+//!
+//! Targets are node or edge references, so nodes and edges are told apart
+//! even where their IDs coincide. Edges are listed in ID order, which puts
+//! the targets of a higher-order edge before it.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use mew_core::{EdgeId, EntityId, NodeId, Value};
+use mew_graph::Graph;
+use mew_mutation::MutationExecutor;
+use mew_registry::Registry;
+use serde_json::{json, Map, Value as Json};
+
+use super::{edge_type_name, node_type_name, sorted_attrs, Subgraph};
+use crate::error::{SessionError, SessionResult};
+use crate::wire::{json_to_value, value_to_json};
+
+/// Version of the JSON export document.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Encode a subgraph as a JSON document.
+pub fn graph_to_json(registry: &Registry, graph: &Graph, subgraph: &Subgraph) -> Json {
+    let attrs_json = |attributes| {
+        Json::Object(
+            sorted_attrs(attributes)
+                .into_iter()
+                .map(|(k, v)| (k.clone(), value_to_json(v)))
+                .collect::<Map<_, _>>(),
+        )
+    };
+    let nodes: Vec<Json> = subgraph
+        .node_entries(graph)
+        .map(|node| {
+            json!({
+                "id": node.id.raw(),
+                "type": node_type_name(registry, node),
+                "attrs": attrs_json(&node.attributes),
+            })
+        })
+        .collect();
+    let edges: Vec<Json> = subgraph
+        .edge_entries(graph)
+        .map(|edge| {
+            json!({
+                "id": edge.id.raw(),
+                "type": edge_type_name(registry, edge),
+                "targets": edge.targets.iter().map(|t| value_to_json(&entity_value(*t))).collect::<Vec<_>>(),
+                "attrs": attrs_json(&edge.attributes),
+            })
+        })
+        .collect();
+    json!({ "version": EXPORT_VERSION, "nodes": nodes, "edges": edges })
+}
+
+/// Write a subgraph as a JSON document.
+pub fn write_json(
+    registry: &Registry,
+    graph: &Graph,
+    subgraph: &Subgraph,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, &graph_to_json(registry, graph, subgraph))?;
+    writeln!(out)
+}
+
+/// Load a document written by [`graph_to_json`] into a graph.
+///
+/// Entities get new IDs; references to exported entities, in edge targets
+/// and in attribute values, are rewritten to them. Nodes and edges are
+/// created as by SPAWN and LINK, so the registry must define their types.
+/// Returns the new ID of every exported entity.
+pub fn load_json(
+    registry: &Registry,
+    graph: &mut Graph,
+    document: &Json,
+) -> SessionResult<HashMap<EntityId, EntityId>> {
+    let version = document.get("version").and_then(Json::as_u64);
+    if version != Some(EXPORT_VERSION as u64) {
+        return Err(SessionError::invalid_export(format!(
+            "unsupported version {}",
+            document.get("version").unwrap_or(&Json::Null)
+        )));
+    }
+
+    let mut executor = MutationExecutor::new(registry, graph);
+    let mut ids = HashMap::new();
+    for entry in entries(document, "nodes")? {
+        let entry = Entry::parse(entry, "node")?;
+        let values = entry.attrs(&ids)?;
+        let id = executor.create_node(&entry.type_name, values)?;
+        ids.insert(NodeId::new(entry.id).into(), id.into());
+    }
+    for entry in entries(document, "edges")? {
+        let entry = Entry::parse(entry, "edge")?;
+        let targets = entry
+            .targets
+            .iter()
+            .map(|t| match remap(&decode(t)?, &ids)? {
+                Value::NodeRef(id) => Ok(id.into()),
+                Value::EdgeRef(id) => Ok(id.into()),
+                _ => Err(SessionError::invalid_export(format!(
+                    "edge {}: target {} is not a node or edge reference",
+                    entry.id, t
+                ))),
+            })
+            .collect::<SessionResult<Vec<EntityId>>>()?;
+        let values = entry.attrs(&ids)?;
+        let id = executor
+            .create_edge(&entry.type_name, targets, values, false)?
+            .ok_or_else(|| {
+                SessionError::invalid_export(format!("edge {} is a duplicate", entry.id))
+            })?;
+        ids.insert(EdgeId::new(entry.id).into(), id.into());
+    }
+    Ok(ids)
+}
+
+fn decode(json: &Json) -> SessionResult<Value> {
+    json_to_value(json).map_err(|e| SessionError::invalid_export(e.message))
+}
+
+fn entity_value(id: EntityId) -> Value {
+    match id {
+        EntityId::Node(id) => Value::NodeRef(id),
+        EntityId::Edge(id) => Value::EdgeRef(id),
+    }
+}
+
+fn entries<'a>(document: &'a Json, key: &str) -> SessionResult<&'a [Json]> {
+    match document.get(key) {
+        None => Ok(&[]),
+        Some(Json::Array(items)) => Ok(items),
+        Some(_) => Err(SessionError::invalid_export(format!(
+            "'{}' is not an array",
+            key
+        ))),
+    }
+}
+
+/// A node or edge of the document.
+struct Entry<'a> {
+    id: u64,
+    type_name: String,
+    targets: &'a [Json],
+    attrs: Option<&'a Map<String, Json>>,
+}
+
+impl<'a> Entry<'a> {
+    fn parse(json: &'a Json, kind: &str) -> SessionResult<Self> {
+        let invalid =
+            |what: &str| SessionError::invalid_export(format!("{} {}: {}", kind, json, what));
+        let id = json
+            .get("id")
+            .and_then(Json::as_u64)
+            .ok_or_else(|| invalid("missing id"))?;
+        let type_name = json
+            .get("type")
+            .and_then(Json::as_str)
+            .ok_or_else(|| invalid("missing type"))?
+            .to_string();
+        let targets = match json.get("targets") {
+            Some(Json::Array(targets)) => targets.as_slice(),
+            None if kind == "node" => &[],
+            _ => return Err(invalid("missing targets")),
+        };
+        let attrs = match json.get("attrs") {
+            Some(Json::Object(attrs)) => Some(attrs),
+            None => None,
+            Some(_) => return Err(invalid("attrs is not an object")),
+        };
+        Ok(Self {
+            id,
+            type_name,
+            targets,
+            attrs,
+        })
+    }
+
+    fn attrs(&self, ids: &HashMap<EntityId, EntityId>) -> SessionResult<Vec<(String, Value)>> {
+        self.attrs
+            .into_iter()
+            .flatten()
+            .map(|(name, json)| Ok((name.clone(), remap(&decode(json)?, ids)?)))
+            .collect()
+    }
+}
+
+/// Rewrite the references in a value to the loaded entities.
+fn remap(value: &Value, ids: &HashMap<EntityId, EntityId>) -> SessionResult<Value> {
+    let lookup = |id: EntityId| {
+        ids.get(&id).copied().ok_or_else(|| {
+            SessionError::invalid_export(format!(
+                "reference to {} that is not in the document",
+                value
+            ))
+        })
+    };
+    Ok(match value {
+        Value::NodeRef(id) => entity_value(lookup((*id).into())?),
+        Value::EdgeRef(id) => entity_value(lookup((*id).into())?),
+        Value::List(items) => Value::List(
+            items
+                .iter()
+                .map(|v| remap(v, ids))
+                .collect::<SessionResult<_>>()?,
+        ),
+        Value::Map(entries) => Value::Map(
+            entries
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), remap(v, ids)?)))
+                .collect::<SessionResult<_>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{test_graph, test_registry};
+    use super::*;
+
+    #[test]
+    fn test_json_export_tells_nodes_from_edges() {
+        // GIVEN
+        let registry = test_registry();
+        let (graph, people, edges) = test_graph(&registry);
+
+        // WHEN
+        let json = graph_to_json(&registry, &graph, &Subgraph::full(&graph));
+
+        // THEN
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["nodes"][0]["type"], "Person");
+        assert_eq!(json["nodes"][0]["attrs"]["name"], "Ada");
+        let confirms = &json["edges"][2];
+        assert_eq!(confirms["type"], "confirms");
+        assert_eq!(
+            confirms["targets"],
+            json!([
+                {"_type": "node", "_id": people[2].raw()},
+                {"_type": "edge", "_id": edges[0].raw()}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        // GIVEN an export of the graph
+        let registry = test_registry();
+        let (graph, _, edges) = test_graph(&registry);
+        let json = graph_to_json(&registry, &graph, &Subgraph::full(&graph));
+
+        // WHEN it is loaded into an empty graph
+        let mut loaded = Graph::new();
+        let ids = load_json(&registry, &mut loaded, &json).unwrap();
+
+        // THEN the loaded graph exports the same, up to IDs
+        assert_eq!(ids.len(), 6);
+        let reloaded = graph_to_json(&registry, &loaded, &Subgraph::full(&loaded));
+        let strip = |json: &Json| {
+            let mut json = json.clone();
+            for kind in ["nodes", "edges"] {
+                for entry in json[kind].as_array_mut().unwrap() {
+                    entry.as_object_mut().unwrap().remove("id");
+                    entry.as_object_mut().unwrap().remove("targets");
+                }
+            }
+            json
+        };
+        assert_eq!(strip(&reloaded), strip(&json));
+        let new_edge = |id: EdgeId| ids[&EntityId::from(id)];
+        let confirms = loaded
+            .get_edge(new_edge(edges[2]).as_edge().unwrap())
+            .unwrap();
+        let knows = new_edge(edges[0]);
+        assert_eq!(confirms.targets[1], knows);
+    }
+
+    #[test]
+    fn test_load_rejects_dangling_targets() {
+        // GIVEN an edge whose target is missing
+        let registry = test_registry();
+        let json = json!({
+            "version": 1,
+            "nodes": [{"id": 1, "type": "Person", "attrs": {}}],
+            "edges": [{"id": 5, "type": "knows", "targets": [
+                {"_type": "node", "_id": 1}, {"_type": "node", "_id": 2}
+            ]}]
+        });
+
+        // WHEN
+        let result = load_json(&registry, &mut Graph::new(), &json);
+
+        // THEN
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), "invalid_export");
+        assert!(err.to_string().contains("not in the document"));
+    }
+}
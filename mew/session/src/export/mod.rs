@@ -0,0 +1,365 @@
+//! Graph export to JSON, GraphML and Graphviz DOT.
+//!
+//! The whole graph or a [`Subgraph`] of it (such as the entities a MATCH
+//! returned) can be written as:
+//!
+//! - JSON: lossless, with values in the [`wire`](crate::wire) encoding;
+//!   [`load_json`] reads it back into a graph
+//! - GraphML and DOT: for graph tools, which only know binary edges
+//!
+//! In the binary formats an edge with more or fewer than two targets, an
+//! edge targeting an edge, and an edge targeted by another exported edge
+//! cannot be drawn as a line. Such edges are reified: they become a vertex
+//! of their own, linked to each of their targets by an edge labeled with
+//! the target's parameter name.
+
+mod dot;
+mod graphml;
+mod json;
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::Path;
+
+use mew_core::{Edge, EdgeId, EntityId, Node, NodeId, Value};
+use mew_graph::Graph;
+use mew_registry::Registry;
+
+pub use dot::write_dot;
+pub use graphml::write_graphml;
+pub use json::{graph_to_json, load_json, write_json, EXPORT_VERSION};
+
+/// An export format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    GraphMl,
+    Dot,
+}
+
+impl ExportFormat {
+    /// The format with the given name: `json`, `graphml` or `dot`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "graphml" => Some(Self::GraphMl),
+            "dot" | "gv" => Some(Self::Dot),
+            _ => None,
+        }
+    }
+
+    /// The format implied by a file extension.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        Self::from_name(path.extension()?.to_str()?)
+    }
+}
+
+/// The nodes and edges to export.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Subgraph {
+    pub nodes: BTreeSet<NodeId>,
+    pub edges: BTreeSet<EdgeId>,
+}
+
+impl Subgraph {
+    /// Every node and edge of the graph.
+    pub fn full(graph: &Graph) -> Self {
+        Self {
+            nodes: graph.all_node_ids().collect(),
+            edges: graph.all_edge_ids().collect(),
+        }
+    }
+
+    /// The entities referenced by `values` (such as the rows of a query
+    /// result), searching lists and maps.
+    ///
+    /// The subgraph is closed: the targets of every edge are added, and so
+    /// is every edge whose targets are all in it.
+    pub fn from_values<'a>(graph: &Graph, values: impl IntoIterator<Item = &'a Value>) -> Self {
+        let mut subgraph = Self::default();
+        for value in values {
+            subgraph.add_value(graph, value);
+        }
+        subgraph.close(graph);
+        subgraph
+    }
+
+    fn add_value(&mut self, graph: &Graph, value: &Value) {
+        match value {
+            Value::NodeRef(id) if graph.get_node(*id).is_some() => {
+                self.nodes.insert(*id);
+            }
+            Value::EdgeRef(id) => self.add_edge(graph, *id),
+            Value::List(items) => items.iter().for_each(|v| self.add_value(graph, v)),
+            Value::Map(entries) => entries.values().for_each(|v| self.add_value(graph, v)),
+            _ => {}
+        }
+    }
+
+    /// Add an edge and, recursively, its targets.
+    fn add_edge(&mut self, graph: &Graph, id: EdgeId) {
+        let Some(edge) = graph.get_edge(id) else {
+            return;
+        };
+        if !self.edges.insert(id) {
+            return;
+        }
+        for target in &edge.targets {
+            match target {
+                EntityId::Node(node_id) => {
+                    self.nodes.insert(*node_id);
+                }
+                EntityId::Edge(edge_id) => self.add_edge(graph, *edge_id),
+            }
+        }
+    }
+
+    /// Add the edges between entities already in the subgraph, until no
+    /// more are found (an added edge may be the target of another).
+    fn close(&mut self, graph: &Graph) {
+        loop {
+            let found: Vec<EdgeId> = graph
+                .all_edge_ids()
+                .filter(|id| !self.edges.contains(id))
+                .filter(|id| {
+                    graph
+                        .get_edge(*id)
+                        .is_some_and(|edge| edge.targets.iter().all(|t| self.contains(*t)))
+                })
+                .collect();
+            if found.is_empty() {
+                return;
+            }
+            self.edges.extend(found);
+        }
+    }
+
+    /// Whether the subgraph contains an entity.
+    pub fn contains(&self, id: EntityId) -> bool {
+        match id {
+            EntityId::Node(id) => self.nodes.contains(&id),
+            EntityId::Edge(id) => self.edges.contains(&id),
+        }
+    }
+
+    /// The nodes of the subgraph, in ID order.
+    fn node_entries<'a>(&'a self, graph: &'a Graph) -> impl Iterator<Item = &'a Node> + 'a {
+        self.nodes.iter().filter_map(move |id| graph.get_node(*id))
+    }
+
+    /// The edges of the subgraph, in ID order (so targets come first).
+    fn edge_entries<'a>(&'a self, graph: &'a Graph) -> impl Iterator<Item = &'a Edge> + 'a {
+        self.edges.iter().filter_map(move |id| graph.get_edge(*id))
+    }
+
+    /// The edges that must be reified in a binary-only format.
+    fn reified(&self, graph: &Graph) -> BTreeSet<EdgeId> {
+        let mut reified = BTreeSet::new();
+        for edge in self.edge_entries(graph) {
+            if edge.targets.len() != 2 {
+                reified.insert(edge.id);
+            }
+            for target in &edge.targets {
+                if let EntityId::Edge(target_id) = target {
+                    reified.insert(edge.id);
+                    reified.insert(*target_id);
+                }
+            }
+        }
+        reified
+    }
+}
+
+/// Write a subgraph in the given format.
+pub fn export(
+    registry: &Registry,
+    graph: &Graph,
+    subgraph: &Subgraph,
+    format: ExportFormat,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    match format {
+        ExportFormat::Json => write_json(registry, graph, subgraph, out),
+        ExportFormat::GraphMl => write_graphml(registry, graph, subgraph, out),
+        ExportFormat::Dot => write_dot(registry, graph, subgraph, out),
+    }
+}
+
+/// The type name of a node.
+fn node_type_name(registry: &Registry, node: &Node) -> String {
+    registry
+        .get_type(node.type_id)
+        .map(|t| t.name.clone())
+        .unwrap_or_else(|| format!("Unknown<{}>", node.type_id))
+}
+
+/// The type name of an edge.
+fn edge_type_name(registry: &Registry, edge: &Edge) -> String {
+    registry
+        .get_edge_type(edge.type_id)
+        .map(|e| e.name.clone())
+        .unwrap_or_else(|| format!("Unknown<{}>", edge.type_id))
+}
+
+/// The parameter name of each target of an edge, or its position when the
+/// edge type is unknown.
+fn target_roles(registry: &Registry, edge: &Edge) -> Vec<String> {
+    let params = registry
+        .get_edge_type(edge.type_id)
+        .map(|e| e.params.as_slice())
+        .unwrap_or_default();
+    (0..edge.targets.len())
+        .map(|i| {
+            params
+                .get(i)
+                .map_or_else(|| i.to_string(), |p| p.name.clone())
+        })
+        .collect()
+}
+
+/// Whether an edge's type is symmetric.
+fn is_symmetric(registry: &Registry, edge: &Edge) -> bool {
+    registry
+        .get_edge_type(edge.type_id)
+        .is_some_and(|e| e.symmetric)
+}
+
+/// Attributes in name order.
+fn sorted_attrs(attributes: &mew_core::Attributes) -> Vec<(&String, &Value)> {
+    let mut attrs: Vec<_> = attributes.iter().collect();
+    attrs.sort_by(|a, b| a.0.cmp(b.0));
+    attrs
+}
+
+/// The vertex ID of an entity in the binary formats.
+fn vertex_id(id: EntityId) -> String {
+    match id {
+        EntityId::Node(id) => format!("n{}", id.raw()),
+        EntityId::Edge(id) => format!("e{}", id.raw()),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use mew_mutation::MutationExecutor;
+    use mew_registry::{AttrDef, RegistryBuilder};
+
+    pub(crate) fn test_registry() -> Registry {
+        let mut builder = RegistryBuilder::new();
+        builder
+            .add_type("Person")
+            .attr(AttrDef::new("name", "String"))
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("knows")
+            .param("a", "Person")
+            .param("b", "Person")
+            .attr(AttrDef::new("since", "Int"))
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("meeting")
+            .param("host", "Person")
+            .param("guest", "Person")
+            .param("witness", "Person")
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("confirms")
+            .param("who", "Person")
+            .param("fact", "any")
+            .done()
+            .unwrap();
+        builder.build().unwrap()
+    }
+
+    /// Ada knows Bob (since 2020), a three-way meeting and Cy confirming
+    /// the knows edge.
+    pub(crate) fn test_graph(registry: &Registry) -> (Graph, Vec<NodeId>, Vec<EdgeId>) {
+        let mut graph = Graph::new();
+        let people: Vec<NodeId> = ["Ada", "Bob", "Cy"]
+            .iter()
+            .map(|name| {
+                MutationExecutor::new(registry, &mut graph)
+                    .create_node(
+                        "Person",
+                        vec![("name".into(), Value::String(name.to_string()))],
+                    )
+                    .unwrap()
+            })
+            .collect();
+        let mut link = |edge_type: &str, targets: Vec<EntityId>, values| {
+            MutationExecutor::new(registry, &mut graph)
+                .create_edge(edge_type, targets, values, false)
+                .unwrap()
+                .unwrap()
+        };
+        let knows = link(
+            "knows",
+            vec![people[0].into(), people[1].into()],
+            vec![("since".into(), Value::Int(2020))],
+        );
+        let meeting = link(
+            "meeting",
+            people.iter().map(|p| (*p).into()).collect(),
+            vec![],
+        );
+        let confirms = link("confirms", vec![people[2].into(), knows.into()], vec![]);
+        (graph, people, vec![knows, meeting, confirms])
+    }
+
+    #[test]
+    fn test_subgraph_from_values_is_closed() {
+        // GIVEN
+        let registry = test_registry();
+        let (graph, people, edges) = test_graph(&registry);
+
+        // WHEN the values name only Ada and Bob
+        let subgraph = Subgraph::from_values(
+            &graph,
+            &[
+                Value::NodeRef(people[0]),
+                Value::List(vec![Value::NodeRef(people[1])]),
+            ],
+        );
+
+        // THEN the edge between them is included, the others are not
+        assert_eq!(subgraph.nodes, [people[0], people[1]].into());
+        assert_eq!(subgraph.edges, [edges[0]].into());
+
+        // WHEN the values name the confirming edge
+        let subgraph = Subgraph::from_values(&graph, &[Value::EdgeRef(edges[2])]);
+
+        // THEN its targets come with it, recursively
+        assert_eq!(subgraph.nodes, people.iter().copied().collect());
+        assert_eq!(subgraph, Subgraph::full(&graph));
+    }
+
+    #[test]
+    fn test_reified_edges() {
+        // GIVEN
+        let registry = test_registry();
+        let (graph, _, edges) = test_graph(&registry);
+
+        // WHEN
+        let reified = Subgraph::full(&graph).reified(&graph);
+
+        // THEN the ternary edge, the higher-order edge and its target are
+        assert_eq!(reified, edges.iter().copied().collect());
+    }
+
+    #[test]
+    fn test_format_for_path() {
+        assert_eq!(
+            ExportFormat::for_path(Path::new("g.GraphML")),
+            Some(ExportFormat::GraphMl)
+        );
+        assert_eq!(
+            ExportFormat::for_path(Path::new("g.gv")),
+            Some(ExportFormat::Dot)
+        );
+        assert_eq!(ExportFormat::for_path(Path::new("g.txt")), None);
+    }
+}
@@ -6,15 +6,26 @@
 //! - Accept statements (REPL, HTTP, embedded)
 //! - Route statements to appropriate executor
 //! - Track session state (current transaction)
-//! - Format and return results
+//! - Format and return results (see [`wire`] for the JSON encoding)
+//! - Export graphs to JSON, GraphML and DOT (see [`export`])
+//! - Dump and restore databases as MEW scripts (see [`dump`])
 //! - Handle errors gracefully
 
+mod database;
+pub mod dump;
 mod error;
+pub mod export;
 mod query;
 mod result;
 mod session;
 mod transaction;
+mod watch;
+pub mod wire;
 
+pub use database::Database;
 pub use error::{SessionError, SessionResult};
+pub use query::convert_query_result;
 pub use result::{MutationSummary, QueryResult, StatementResult, TransactionResult};
 pub use session::{Session, SessionId, SessionManager};
+pub use watch::{WatchEvent, WatchId};
+pub use wire::{json_to_value, value_to_json, WireBody, WireError, WireMessage, WIRE_VERSION};
@@ -8,13 +8,19 @@ use mew_query::QueryResults;
 
 use crate::result::QueryResult;
 
+/// Type name reported for a column whose type is unknown or mixed.
+pub(crate) const ANY_TYPE: &str = "Any";
+
+/// Type name reported for a list whose element type is unknown or mixed.
+const ANY_LIST_TYPE: &str = "[Any]";
+
 /// Convert an internal query result to a session QueryResult.
 ///
 /// This extracts column names and row data into the format expected
-/// by session clients.
-pub fn convert_query_result(result: &QueryResults) -> QueryResult {
+/// by session clients. `types` are the analyzer's column types; see
+/// [`column_types`] for columns it could not type.
+pub fn convert_query_result(result: &QueryResults, types: &[String]) -> QueryResult {
     let columns: Vec<String> = result.column_names().to_vec();
-    let types = vec!["any".to_string(); columns.len()]; // Simplified type info
 
     let mut rows = Vec::new();
     for row in result.rows() {
@@ -25,9 +31,45 @@ pub fn convert_query_result(result: &QueryResults) -> QueryResult {
         rows.push(values);
     }
 
+    let types = column_types(types, &rows, columns.len());
     QueryResult::new(columns, types, rows)
 }
 
+/// Resolve the reported type of each column.
+///
+/// A declared type is used as is unless it is `Any` or `[Any]`; such
+/// columns (and columns without a declared type) take the type shared by
+/// all their non-null values, or keep the declared type (`Any` if none)
+/// when the values disagree.
+pub(crate) fn column_types(declared: &[String], rows: &[Vec<Value>], width: usize) -> Vec<String> {
+    (0..width)
+        .map(|i| match declared.get(i) {
+            Some(ty) if ty != ANY_TYPE && ty != ANY_LIST_TYPE => ty.clone(),
+            ty => shared_type(rows.iter().filter_map(|row| row.get(i)))
+                .or_else(|| ty.cloned())
+                .unwrap_or_else(|| ANY_TYPE.to_string()),
+        })
+        .collect()
+}
+
+/// The type shared by all non-null `values`, if there is one.
+fn shared_type<'a>(values: impl Iterator<Item = &'a Value>) -> Option<String> {
+    let mut kinds = values.filter(|v| !v.is_null()).map(value_type);
+    let first = kinds.next()?;
+    kinds.all(|k| k == first).then_some(first)
+}
+
+/// The type name of a value; a list names its elements' shared type.
+fn value_type(value: &Value) -> String {
+    match value {
+        Value::List(items) => format!(
+            "[{}]",
+            shared_type(items.iter()).unwrap_or_else(|| ANY_TYPE.to_string())
+        ),
+        other => other.type_name().to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,10 +81,11 @@ mod tests {
         let result = QueryResults::with_columns(vec!["a".to_string(), "b".to_string()]);
 
         // WHEN
-        let converted = convert_query_result(&result);
+        let converted = convert_query_result(&result, &[]);
 
         // THEN
         assert_eq!(converted.columns, vec!["a", "b"]);
+        assert_eq!(converted.types, vec!["Any", "Any"]);
         assert!(converted.rows.is_empty());
     }
 
@@ -55,11 +98,43 @@ mod tests {
         result.push(row);
 
         // WHEN
-        let converted = convert_query_result(&result);
+        let converted = convert_query_result(&result, &[]);
 
         // THEN
         assert_eq!(converted.columns, vec!["x"]);
+        assert_eq!(converted.types, vec!["Int"]);
         assert_eq!(converted.rows.len(), 1);
         assert_eq!(converted.rows[0][0], Value::Int(42));
     }
+
+    #[test]
+    fn test_declared_types_win_over_values() {
+        // GIVEN a declared column and an untyped column with mixed values
+        let rows = vec![
+            vec![Value::Null, Value::Int(1)],
+            vec![Value::String("a".into()), Value::Float(1.5)],
+        ];
+        let declared = vec!["Person".to_string(), ANY_TYPE.to_string()];
+
+        // WHEN
+        let types = column_types(&declared, &rows, 3);
+
+        // THEN
+        assert_eq!(types, vec!["Person", "Any", "Any"]);
+    }
+
+    #[test]
+    fn test_list_columns_name_their_element_type() {
+        // GIVEN lists of strings, an untyped list of mixed values and an empty list
+        let strings = Value::List(vec![Value::String("a".into()), Value::Null]);
+        let mixed = Value::List(vec![Value::Int(1), Value::String("b".into())]);
+        let rows = vec![vec![strings, mixed, Value::List(Vec::new())]];
+        let declared = vec![ANY_LIST_TYPE.to_string(), ANY_TYPE.to_string()];
+
+        // WHEN
+        let types = column_types(&declared, &rows, 3);
+
+        // THEN the element type is inferred where the values share one
+        assert_eq!(types, vec!["[String]", "[Any]", "[Any]"]);
+    }
 }
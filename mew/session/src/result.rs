@@ -1,9 +1,16 @@
 //! Session result types.
+//!
+//! The types serialize to the wire encoding described in [`crate::wire`].
 
 use mew_core::Value;
+use mew_import::ImportReport;
+use serde::{Deserialize, Serialize};
+
+use crate::wire::serde_values;
 
 /// Result of executing a statement.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum StatementResult {
     /// Query result with rows.
     Query(QueryResult),
@@ -11,7 +18,9 @@ pub enum StatementResult {
     Mutation(MutationSummary),
     /// Mixed result containing both mutations and queries.
     Mixed {
+        #[serde(rename = "mutation")]
         mutations: MutationSummary,
+        #[serde(rename = "query")]
         queries: QueryResult,
     },
     /// Transaction control result.
@@ -20,14 +29,40 @@ pub enum StatementResult {
     Empty,
 }
 
+impl From<ImportReport> for StatementResult {
+    /// The created nodes and edges, with the rejected rows as `line` and
+    /// `reason` columns.
+    fn from(report: ImportReport) -> Self {
+        let rows = report
+            .rejected
+            .into_iter()
+            .map(|row| vec![Value::Int(row.line as i64), Value::String(row.reason)])
+            .collect();
+        StatementResult::Mixed {
+            mutations: MutationSummary {
+                nodes_created: report.nodes_created,
+                edges_created: report.edges_created,
+                ..Default::default()
+            },
+            queries: QueryResult::new(
+                vec!["line".to_string(), "reason".to_string()],
+                vec!["Int".to_string(), "String".to_string()],
+                rows,
+            ),
+        }
+    }
+}
+
 /// Result of a query execution.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResult {
     /// Column names.
     pub columns: Vec<String>,
-    /// Column types.
+    /// Column types: a scalar type name (`Int`, `Timestamp`, ...), the
+    /// node or edge type of a reference column, or `Any`.
     pub types: Vec<String>,
     /// Data rows.
+    #[serde(with = "serde_values::rows")]
     pub rows: Vec<Vec<Value>>,
 }
 
@@ -62,7 +97,7 @@ impl QueryResult {
 }
 
 /// Summary of a mutation execution.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MutationSummary {
     /// Number of nodes created.
     pub nodes_created: usize,
@@ -77,6 +112,7 @@ pub struct MutationSummary {
     /// Number of edges deleted.
     pub edges_deleted: usize,
     /// Any returned values.
+    #[serde(with = "serde_values::list", default)]
     pub returned: Vec<Value>,
 }
 
@@ -163,11 +199,13 @@ impl MutationSummary {
         self.edges_created += other.edges_created;
         self.edges_modified += other.edges_modified;
         self.edges_deleted += other.edges_deleted;
+        self.returned.extend(other.returned.iter().cloned());
     }
 }
 
 /// Result of a transaction control statement.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
 pub enum TransactionResult {
     /// Transaction began.
     Begun,
@@ -176,11 +214,20 @@ pub enum TransactionResult {
     /// Transaction rolled back.
     RolledBack,
     /// Savepoint created.
-    SavepointCreated { name: String },
+    SavepointCreated {
+        #[serde(rename = "savepoint")]
+        name: String,
+    },
     /// Rolled back to savepoint.
-    RolledBackTo { name: String },
+    RolledBackTo {
+        #[serde(rename = "savepoint")]
+        name: String,
+    },
     /// Savepoint released.
-    SavepointReleased { name: String },
+    SavepointReleased {
+        #[serde(rename = "savepoint")]
+        name: String,
+    },
 }
 
 #[cfg(test)]
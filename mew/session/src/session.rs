@@ -1,23 +1,35 @@
 //! Session manager.
 
 use mew_analyzer::Analyzer;
-use mew_constraint::ConstraintChecker;
-use mew_core::{messages, EntityId, Value};
+use mew_constraint::{ConstraintChecker, Touched};
+use mew_core::{messages, Attributes, EntityId, NodeId, Value};
 use mew_graph::Graph;
+use mew_import::{BulkLoader, ImportReport};
 use mew_mutation::{MutationExecutor, MutationOutcome};
 use mew_parser::{
-    parse_stmt, parse_stmts, InspectStmt, MatchMutateStmt, MatchStmt, MutationAction, Stmt,
-    TargetRef, WalkStmt,
+    parse_stmt, parse_stmts, CallStmt, DumpStmt, Expr, ImportStmt, InspectStmt, IsolationLevel,
+    MatchMutateStmt, MatchStmt, MutationAction, PatternElem, Projection, Stmt, TargetRef, TxnStmt,
+    UnionStmt, UnwindStmt, WalkStmt, WatchMode, WatchStmt,
 };
-use mew_pattern::{target, Binding, Bindings};
+use mew_pattern::{current_time_millis, target, Binding, Bindings, Evaluator};
 use mew_query::QueryExecutor;
-use mew_registry::Registry;
-use std::collections::HashMap;
+use mew_registry::{Registry, DEAD_LETTER_TYPE};
+use mew_transaction::{TransactionError, UndoLog};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
+use crate::database::Database;
 use crate::error::{SessionError, SessionResult};
-use crate::query::convert_query_result;
+use crate::query::{column_types, convert_query_result};
 use crate::result::{MutationSummary, QueryResult, StatementResult, TransactionResult};
-use crate::transaction::{self, TransactionState};
+use crate::transaction::{self, Snapshot, TransactionState};
+use crate::watch::{
+    ConsumeWatch, Settlement, WatchEvent, WatchId, WatchState, DEFAULT_ACK_TIMEOUT_MS,
+    DEFAULT_MAX_REDELIVERIES,
+};
+
+/// Column under which a consume watch query projects its message node.
+const WATCH_MESSAGE_COLUMN: &str = "_message";
 
 /// Session ID type.
 pub type SessionId = u64;
@@ -28,38 +40,85 @@ pub struct Session<'r> {
     id: SessionId,
     /// The registry (shared).
     registry: &'r Registry,
-    /// Session-specific graph.
-    graph: Graph,
+    /// Working graph: private to the session, or a copy-on-write snapshot of
+    /// the shared database.
+    graph: Arc<Graph>,
+    /// Shared database this session is attached to.
+    database: Option<&'r Database>,
+    /// Database version the working graph is based on.
+    base_version: u64,
+    /// Isolation level of the current transaction.
+    isolation: IsolationLevel,
     /// Auto-commit mode.
     auto_commit: bool,
     /// Transaction state.
     txn_state: TransactionState,
     /// Variable bindings (var_name -> EntityId) for mutation targets.
     bindings: HashMap<String, EntityId>,
+    /// Parameter values (`$name`) for the statement being executed.
+    params: HashMap<String, Value>,
+    /// Consume-mode watches and their in-flight deliveries.
+    watches: WatchState,
+    /// Whether IMPORT and DUMP may touch the filesystem.
+    file_access: bool,
+}
+
+/// Keep a full-text index for every `[searchable]` attribute, inherited
+/// ones included, so text searches on them need not scan.
+pub(crate) fn enable_text_indexes(registry: &Registry, graph: &mut Graph) {
+    for type_def in registry.all_types() {
+        for attr in registry.get_all_type_attrs(type_def.id) {
+            if attr.searchable {
+                graph.enable_text_index(type_def.id, &attr.name);
+            }
+        }
+    }
 }
 
 impl<'r> Session<'r> {
     /// Create a new session.
     pub fn new(id: SessionId, registry: &'r Registry) -> Self {
+        Self::with_graph(id, registry, Graph::new())
+    }
+
+    /// Create a session with an existing graph.
+    pub fn with_graph(id: SessionId, registry: &'r Registry, mut graph: Graph) -> Self {
+        enable_text_indexes(registry, &mut graph);
         Self {
             id,
             registry,
-            graph: Graph::new(),
+            graph: Arc::new(graph),
+            database: None,
+            base_version: 0,
+            isolation: IsolationLevel::ReadCommitted,
             auto_commit: true,
             txn_state: TransactionState::new(),
             bindings: HashMap::new(),
+            params: HashMap::new(),
+            watches: WatchState::new(),
+            file_access: false,
         }
     }
 
-    /// Create a session with an existing graph.
-    pub fn with_graph(id: SessionId, registry: &'r Registry, graph: Graph) -> Self {
+    /// Create a session attached to a shared database.
+    ///
+    /// Statements outside a transaction commit on success; see
+    /// [`Database`] for the isolation guarantees inside one.
+    pub fn attach(id: SessionId, database: &'r Database) -> Self {
+        let (base_version, graph) = database.pin_latest();
         Self {
             id,
-            registry,
+            registry: database.registry(),
             graph,
+            database: Some(database),
+            base_version,
+            isolation: IsolationLevel::ReadCommitted,
             auto_commit: true,
             txn_state: TransactionState::new(),
             bindings: HashMap::new(),
+            params: HashMap::new(),
+            watches: WatchState::shared(database.leases()),
+            file_access: false,
         }
     }
 
@@ -79,8 +138,11 @@ impl<'r> Session<'r> {
     }
 
     /// Get a mutable reference to the graph.
+    ///
+    /// For an attached session this is the working copy; changes made here
+    /// are committed with the next statement outside a transaction.
     pub fn graph_mut(&mut self) -> &mut Graph {
-        &mut self.graph
+        Arc::make_mut(&mut self.graph)
     }
 
     /// Check if auto-commit is enabled.
@@ -93,6 +155,18 @@ impl<'r> Session<'r> {
         self.auto_commit = enabled;
     }
 
+    /// Check if IMPORT and DUMP may read and write files.
+    pub fn has_file_access(&self) -> bool {
+        self.file_access
+    }
+
+    /// Allow IMPORT and DUMP to read and write files with the permissions
+    /// of this process. Off by default, since statement text may come from
+    /// untrusted clients.
+    pub fn set_file_access(&mut self, enabled: bool) {
+        self.file_access = enabled;
+    }
+
     /// Check if a transaction is active.
     pub fn in_transaction(&self) -> bool {
         self.txn_state.in_transaction
@@ -100,19 +174,21 @@ impl<'r> Session<'r> {
 
     /// Reset transaction state (used after errors to clean up).
     ///
-    /// This rolls back any pending changes and resets the transaction flag.
-    /// Note: This doesn't undo changes already applied to the graph - those
-    /// would need proper rollback support at the graph level.
+    /// This rolls back every change of the open transaction, if any, and
+    /// resets the transaction flag.
     pub fn reset_transaction(&mut self) {
-        if self.txn_state.in_transaction {
-            // Roll back created entities
-            for edge_id in self.txn_state.created_edges.drain(..) {
-                let _ = self.graph.delete_edge(edge_id);
+        if !self.txn_state.in_transaction {
+            return;
+        }
+        self.txn_state.in_transaction = false;
+        match self.database {
+            Some(db) => {
+                self.drop_snapshots(db, 0);
+                self.discard_changes(db);
             }
-            for node_id in self.txn_state.created_nodes.drain(..) {
-                let _ = self.graph.delete_node(node_id);
+            None => {
+                let _ = self.rollback_changes();
             }
-            self.txn_state.in_transaction = false;
         }
     }
 
@@ -124,6 +200,22 @@ impl<'r> Session<'r> {
         self.execute_statement(&stmt)
     }
 
+    /// Execute a statement string with values for its `$name` parameters.
+    ///
+    /// Parameters are only visible to this statement.
+    pub fn execute_with_params(
+        &mut self,
+        input: &str,
+        params: &HashMap<String, Value>,
+    ) -> SessionResult<StatementResult> {
+        let stmt = parse_stmt(input)?;
+
+        let previous = std::mem::replace(&mut self.params, params.clone());
+        let result = self.execute_statement(&stmt);
+        self.params = previous;
+        result
+    }
+
     /// Execute multiple statements from a string.
     ///
     /// Aggregation behavior:
@@ -156,20 +248,11 @@ impl<'r> Session<'r> {
                     total_mutations = MutationSummary::default();
                 }
                 StatementResult::Query(q) => {
-                    // Combine query results: append rows if columns match, or replace if different
-                    combined_query = Some(match combined_query {
-                        None => q,
-                        Some(mut existing) => {
-                            if existing.columns == q.columns {
-                                // Same columns - append rows
-                                existing.rows.extend(q.rows);
-                                existing
-                            } else {
-                                // Different columns - use the new result
-                                q
-                            }
-                        }
-                    });
+                    combined_query = Some(combine_queries(combined_query, q));
+                }
+                StatementResult::Mixed { mutations, queries } => {
+                    total_mutations.merge(&mutations);
+                    combined_query = Some(combine_queries(combined_query, queries));
                 }
                 _ => {}
             }
@@ -201,12 +284,96 @@ impl<'r> Session<'r> {
 
     /// Execute a parsed statement.
     fn execute_statement(&mut self, stmt: &Stmt) -> SessionResult<StatementResult> {
+        let Some(db) = self.database else {
+            return self.run_atomic(stmt);
+        };
+        if let Stmt::Txn(txn_stmt) = stmt {
+            return self.execute_shared_txn(db, txn_stmt);
+        }
+
+        self.refresh_snapshot(db);
+        let result = self.run_shared(stmt);
+        self.finish_statement(db, result.is_ok())?;
+        result
+    }
+
+    /// Execute a statement on the working copy of a shared database, all or
+    /// nothing.
+    ///
+    /// A working graph this session owns alone journals the statement's
+    /// writes, like [`run_atomic`](Self::run_atomic). One still shared with
+    /// a committed version or a savepoint is copied on its first write, so
+    /// keeping the shared graph is enough to undo the statement.
+    fn run_shared(&mut self, stmt: &Stmt) -> SessionResult<StatementResult> {
+        if Arc::get_mut(&mut self.graph).is_some() {
+            return self.run_atomic(stmt);
+        }
+        let before = Arc::clone(&self.graph);
+        let result = self.run_statement(stmt);
+        if result.is_err() {
+            self.graph = before;
+        }
+        result
+    }
+
+    /// Execute a statement on the private graph, all or nothing.
+    ///
+    /// Outside a transaction the statement gets its own undo log; inside
+    /// one, a failed statement is undone back to where it started and the
+    /// transaction stays open.
+    fn run_atomic(&mut self, stmt: &Stmt) -> SessionResult<StatementResult> {
+        if let Stmt::Txn(txn_stmt) = stmt {
+            return self.execute_txn(txn_stmt);
+        }
+
+        let implicit = self.txn_state.undo.is_none();
+        if implicit {
+            self.txn_state.undo = Some(UndoLog::begin(Arc::make_mut(&mut self.graph)));
+        }
+        let mark = self.graph.undo_mark();
+
+        let result = self.run_statement(stmt);
+        if result.is_err() {
+            if let Some(undo) = &mut self.txn_state.undo {
+                undo.undo_to(Arc::make_mut(&mut self.graph), mark)?;
+            }
+        }
+        if implicit {
+            if let Some(undo) = self.txn_state.undo.take() {
+                undo.commit(Arc::make_mut(&mut self.graph));
+            }
+        }
+        result
+    }
+
+    /// Execute a parsed statement against the working graph, then check the
+    /// declared constraints its writes can break: right away, or at COMMIT
+    /// inside a transaction.
+    fn run_statement(&mut self, stmt: &Stmt) -> SessionResult<StatementResult> {
+        let result = self.dispatch_statement(stmt)?;
+        let touched = Touched::of(stmt);
+        if self.txn_state.in_transaction {
+            // Checked at COMMIT, once all the transaction's writes are in
+            self.txn_state.touched.merge(touched);
+        } else {
+            self.check_declared_constraints(&touched)?;
+        }
+        Ok(result)
+    }
+
+    /// Execute a parsed statement against the working graph.
+    fn dispatch_statement(&mut self, stmt: &Stmt) -> SessionResult<StatementResult> {
         match stmt {
             Stmt::Match(match_stmt) => {
                 let result = self.execute_match(match_stmt)?;
                 Ok(StatementResult::Query(result))
             }
 
+            Stmt::Union(union_stmt) => {
+                let result = self.execute_union(union_stmt)?;
+                Ok(StatementResult::Query(result))
+            }
+
             Stmt::MatchMutate(match_mutate_stmt) => {
                 let result = self.execute_match_mutate(match_mutate_stmt)?;
                 Ok(StatementResult::Mutation(result))
@@ -263,7 +430,123 @@ impl<'r> Session<'r> {
                 let result = self.execute_profile(profile_stmt)?;
                 Ok(StatementResult::Query(result))
             }
+
+            Stmt::Watch(watch_stmt) => {
+                let result = self.execute_watch(watch_stmt)?;
+                Ok(StatementResult::Query(result))
+            }
+
+            Stmt::Ack(ack_stmt) => {
+                let result = self.execute_ack(ack_stmt)?;
+                Ok(StatementResult::Mutation(result))
+            }
+
+            Stmt::Nack(nack_stmt) => {
+                let result = self.execute_nack(nack_stmt)?;
+                Ok(StatementResult::Mutation(result))
+            }
+
+            Stmt::Import(import_stmt) => {
+                let report = self.execute_import(import_stmt)?;
+                Ok(StatementResult::from(report))
+            }
+
+            Stmt::Dump(dump_stmt) => {
+                let result = self.execute_dump(dump_stmt)?;
+                Ok(StatementResult::Query(result))
+            }
+
+            Stmt::Call(call_stmt) => self.execute_call(call_stmt),
+
+            Stmt::Unwind(unwind_stmt) => {
+                let result = self.execute_unwind(unwind_stmt)?;
+                Ok(StatementResult::Mutation(result))
+            }
+        }
+    }
+
+    /// Execute a CALL statement. Results written back are SET on the
+    /// nodes within the statement's transaction.
+    fn execute_call(&mut self, stmt: &CallStmt) -> SessionResult<StatementResult> {
+        let mut analyzer = Analyzer::new(self.registry);
+        analyzer.analyze_stmt(&Stmt::Call(stmt.clone()))?;
+        let types: Vec<String> = analyzer
+            .column_types()
+            .iter()
+            .map(|t| t.describe(self.registry))
+            .collect();
+
+        let output = mew_algo::call(self.registry, &self.graph, stmt, &self.param_bindings())?;
+        let queries = QueryResult::new(output.columns, types, output.rows);
+        let Some(attr) = &stmt.write else {
+            return Ok(StatementResult::Query(queries));
+        };
+
+        let nodes_modified = mew_algo::write_results(
+            self.registry,
+            Arc::make_mut(&mut self.graph),
+            attr,
+            &output.results,
+        )?;
+        let mutations = MutationSummary {
+            nodes_modified,
+            ..Default::default()
+        };
+        if stmt.yields.is_empty() {
+            Ok(StatementResult::Mutation(mutations))
+        } else {
+            Ok(StatementResult::Mixed { mutations, queries })
+        }
+    }
+
+    /// Execute a DUMP statement, writing the database to a file. Returns
+    /// the path and the number of nodes and edges written.
+    fn execute_dump(&self, stmt: &DumpStmt) -> SessionResult<QueryResult> {
+        if !self.file_access {
+            return Err(SessionError::file_access_disabled("DUMP"));
+        }
+        let mut analyzer = Analyzer::new(self.registry);
+        analyzer.analyze_stmt(&Stmt::Dump(stmt.clone()))?;
+
+        let evaluator = Evaluator::new(self.registry);
+        let path = match evaluator.eval(&stmt.path, &self.param_bindings(), &self.graph)? {
+            Value::String(path) => path,
+            other => {
+                return Err(SessionError::invalid_statement_type(format!(
+                    "DUMP TO needs a file path, got {}",
+                    other
+                )))
+            }
+        };
+
+        // Only create the file once the dump is complete
+        let mut dump = Vec::new();
+        crate::dump::write_dump(self.registry, &self.graph, &mut dump)?;
+        std::fs::write(&path, dump)?;
+
+        Ok(QueryResult::new(
+            vec!["path".to_string(), "nodes".to_string(), "edges".to_string()],
+            vec!["String".to_string(), "Int".to_string(), "Int".to_string()],
+            vec![vec![
+                Value::String(path),
+                Value::Int(self.graph.node_count() as i64),
+                Value::Int(self.graph.edge_count() as i64),
+            ]],
+        ))
+    }
+
+    /// Execute an IMPORT statement. Rows the loader rejects do not fail
+    /// the statement; they are part of the report.
+    fn execute_import(&mut self, stmt: &ImportStmt) -> SessionResult<ImportReport> {
+        if !self.file_access {
+            return Err(SessionError::file_access_disabled("IMPORT"));
         }
+        let mut analyzer = Analyzer::new(self.registry);
+        analyzer.analyze_stmt(&Stmt::Import(stmt.clone()))?;
+
+        let bindings = self.param_bindings();
+        let mut loader = BulkLoader::new(self.registry, Arc::make_mut(&mut self.graph));
+        Ok(loader.execute(stmt, &bindings)?)
     }
 
     /// Execute a MATCH statement.
@@ -271,10 +554,38 @@ impl<'r> Session<'r> {
         // Run analyzer for type checking before execution
         let mut analyzer = Analyzer::new(self.registry);
         analyzer.analyze_stmt(&Stmt::Match(stmt.clone()))?;
+        let types: Vec<String> = analyzer
+            .column_types()
+            .iter()
+            .map(|t| t.describe(self.registry))
+            .collect();
+
+        let executor = QueryExecutor::new(self.registry, &self.graph);
+        let result = if self.params.is_empty() {
+            executor.execute_match(stmt)?
+        } else {
+            executor.execute_match_with_bindings(stmt, &self.param_bindings())?
+        };
+        Ok(convert_query_result(&result, &types))
+    }
+
+    /// Execute a UNION of MATCH statements.
+    fn execute_union(&self, stmt: &UnionStmt) -> SessionResult<QueryResult> {
+        let mut analyzer = Analyzer::new(self.registry);
+        analyzer.analyze_stmt(&Stmt::Union(stmt.clone()))?;
+        let types: Vec<String> = analyzer
+            .column_types()
+            .iter()
+            .map(|t| t.describe(self.registry))
+            .collect();
 
         let executor = QueryExecutor::new(self.registry, &self.graph);
-        let result = executor.execute_match(stmt)?;
-        Ok(convert_query_result(&result))
+        let result = if self.params.is_empty() {
+            executor.execute_union(stmt)?
+        } else {
+            executor.execute_union_with_bindings(stmt, Some(&self.param_bindings()))?
+        };
+        Ok(convert_query_result(&result, &types))
     }
 
     /// Execute a MATCH...mutation compound statement.
@@ -292,14 +603,9 @@ impl<'r> Session<'r> {
 
         // Execute the pattern match to get all bindings
         let matcher = Matcher::new(self.registry, &self.graph);
-        let bindings_list = matcher.find_all(&pattern)?;
+        let bindings_list = matcher.find_all_with_initial(&pattern, self.param_bindings())?;
 
-        let mut nodes_created = 0usize;
-        let mut nodes_modified = 0usize;
-        let mut edges_modified = 0usize;
-        let mut edges_created = 0usize;
-        let mut nodes_deleted = 0usize;
-        let mut edges_deleted = 0usize;
+        let mut summary = MutationSummary::default();
 
         // For each set of bindings from the match, execute the mutations
         for pattern_bindings in bindings_list {
@@ -320,112 +626,175 @@ impl<'r> Session<'r> {
                 }
             }
 
-            // Execute each mutation with the current bindings
-            for mutation in &stmt.mutations {
-                match mutation {
-                    MutationAction::Spawn(spawn_stmt) => {
-                        let summary = self.execute_spawn(spawn_stmt)?;
-                        nodes_created += summary.nodes_created;
+            self.execute_mutation_actions(
+                &stmt.mutations,
+                &mut local_bindings,
+                &Bindings::new(),
+                &mut summary,
+            )?;
+        }
 
-                        // Add spawned nodes to local bindings (handles both single and chained)
-                        for item in &spawn_stmt.items {
-                            if let Some(entity_id) = self.bindings.get(&item.var) {
-                                local_bindings.insert(item.var.clone(), *entity_id);
-                            }
-                        }
-                    }
-                    MutationAction::Link(link_stmt) => {
-                        let mut targets = Vec::new();
-                        for target_ref in &link_stmt.targets {
-                            let entity_id = self.resolve_or_spawn_target_ref_with_bindings(
-                                target_ref,
-                                &mut local_bindings,
-                                &mut nodes_created,
-                            )?;
-                            targets.push(entity_id);
-                        }
+        Ok(summary)
+    }
 
-                        let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
-                        let result = executor.execute_link(link_stmt, targets)?;
+    /// Execute an UNWIND statement: the mutations run once per list
+    /// element, with the element bound to the UNWIND variable. A null list
+    /// runs them zero times.
+    fn execute_unwind(&mut self, stmt: &UnwindStmt) -> SessionResult<MutationSummary> {
+        let evaluator = Evaluator::new(self.registry);
+        let elements = match evaluator.eval(&stmt.list, &self.param_bindings(), &self.graph)? {
+            Value::List(elements) => elements,
+            Value::Null => Vec::new(),
+            other => {
+                return Err(SessionError::invalid_statement_type(format!(
+                    "{}, got {}",
+                    messages::ERR_UNWIND_REQUIRES_LIST,
+                    other.type_name()
+                )))
+            }
+        };
 
-                        if let Some(edge_id) = result.created_edge() {
-                            self.check_edge_constraints(edge_id)?;
-                        }
+        let mut summary = MutationSummary::default();
+        for element in elements {
+            let mut local_bindings = self.bindings.clone();
+            let mut values = Bindings::new();
+            match element {
+                Value::NodeRef(node_id) => {
+                    local_bindings.insert(stmt.var.clone(), node_id.into());
+                }
+                Value::EdgeRef(edge_id) => {
+                    local_bindings.insert(stmt.var.clone(), edge_id.into());
+                }
+                value => {
+                    local_bindings.remove(&stmt.var);
+                    values.insert(stmt.var.clone(), Binding::Value(value));
+                }
+            }
 
-                        // Store edge binding if variable present
-                        if let Some(ref var) = link_stmt.var {
-                            if let Some(edge_id) = result.created_edge() {
-                                local_bindings.insert(var.clone(), edge_id.into());
-                            }
-                        }
+            self.execute_mutation_actions(
+                &stmt.mutations,
+                &mut local_bindings,
+                &values,
+                &mut summary,
+            )?;
+        }
+
+        Ok(summary)
+    }
 
-                        if result.created_edge().is_some() {
-                            edges_created += 1;
+    /// Execute the mutations of a compound statement for one row.
+    ///
+    /// `local_bindings` holds the row's nodes and edges and collects the
+    /// variables the mutations bind; `values` holds its scalar variables.
+    fn execute_mutation_actions(
+        &mut self,
+        mutations: &[MutationAction],
+        local_bindings: &mut HashMap<String, EntityId>,
+        values: &Bindings,
+        summary: &mut MutationSummary,
+    ) -> SessionResult<()> {
+        for mutation in mutations {
+            // Bindings for expression evaluation: entities, then scalars and parameters
+            let mut pb = to_pattern_bindings(local_bindings);
+            pb.merge(values);
+            pb.merge(&self.param_bindings());
+
+            match mutation {
+                MutationAction::Spawn(spawn_stmt) => {
+                    let spawned = self.execute_spawn_with_bindings(spawn_stmt, &pb)?;
+                    summary.merge(&spawned);
+
+                    // Add spawned nodes to local bindings (handles both single and chained)
+                    for item in &spawn_stmt.items {
+                        if let Some(entity_id) = self.bindings.get(&item.var) {
+                            local_bindings.insert(item.var.clone(), *entity_id);
                         }
                     }
-                    MutationAction::Set(set_stmt) => {
-                        let target_id =
-                            self.resolve_target_with_bindings(&set_stmt.target, &local_bindings)?;
-
-                        // Convert local_bindings to pattern Bindings for expression evaluation
-                        let pb = to_pattern_bindings(&local_bindings);
-                        let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
-
-                        use mew_mutation::MutationOutcome;
-                        if let Some(node_id) = target_id.as_node() {
-                            let result = executor.execute_set(set_stmt, vec![node_id], &pb)?;
-                            if let MutationOutcome::Updated(ref u) = result {
-                                nodes_modified += u.node_ids.len();
-                            }
-                        } else if let Some(edge_id) = target_id.as_edge() {
-                            let result = executor.execute_set_edge(set_stmt, vec![edge_id], &pb)?;
-                            if let MutationOutcome::Updated(ref u) = result {
-                                edges_modified += u.edge_ids.len();
-                            }
-                        } else {
-                            return Err(SessionError::invalid_statement_type(
-                                messages::ERR_SET_REQUIRES_NODE,
-                            ));
+                }
+                MutationAction::Link(link_stmt) => {
+                    let mut targets = Vec::new();
+                    for target_ref in &link_stmt.targets {
+                        let entity_id = self.resolve_or_spawn_target_ref_with_bindings(
+                            target_ref,
+                            local_bindings,
+                            &pb,
+                            &mut summary.nodes_created,
+                        )?;
+                        targets.push(entity_id);
+                    }
+
+                    let mut executor =
+                        MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
+                    let result = executor.execute_link(link_stmt, targets)?;
+
+                    if let Some(edge_id) = result.created_edge() {
+                        self.check_edge_constraints(edge_id)?;
+                    }
+
+                    // Store edge binding if variable present
+                    if let Some(ref var) = link_stmt.var {
+                        if let Some(edge_id) = result.created_edge() {
+                            local_bindings.insert(var.clone(), edge_id.into());
                         }
                     }
-                    MutationAction::Kill(kill_stmt) => {
-                        let target_id =
-                            self.resolve_target_with_bindings(&kill_stmt.target, &local_bindings)?;
-                        let node_id = target_id.as_node().ok_or_else(|| {
-                            SessionError::invalid_statement_type(messages::ERR_KILL_REQUIRES_NODE)
-                        })?;
-
-                        let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
-                        let result = executor.execute_kill(kill_stmt, node_id)?;
-
-                        nodes_deleted += result.deleted_nodes();
-                        edges_deleted += result.deleted_edges();
+
+                    if result.created_edge().is_some() {
+                        summary.edges_created += 1;
                     }
-                    MutationAction::Unlink(unlink_stmt) => {
-                        let target_id = self
-                            .resolve_target_with_bindings(&unlink_stmt.target, &local_bindings)?;
-                        let edge_id = target_id.as_edge().ok_or_else(|| {
-                            SessionError::invalid_statement_type(messages::ERR_UNLINK_REQUIRES_EDGE)
-                        })?;
+                }
+                MutationAction::Set(set_stmt) => {
+                    let target_id =
+                        self.resolve_target_with_bindings(&set_stmt.target, local_bindings)?;
 
-                        let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
-                        let result = executor.execute_unlink(unlink_stmt, edge_id)?;
+                    let mut executor =
+                        MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
 
-                        edges_deleted += result.deleted_edges();
+                    if let Some(node_id) = target_id.as_node() {
+                        let result = executor.execute_set(set_stmt, vec![node_id], &pb)?;
+                        if let MutationOutcome::Updated(ref u) = result {
+                            summary.nodes_modified += u.node_ids.len();
+                        }
+                    } else if let Some(edge_id) = target_id.as_edge() {
+                        let result = executor.execute_set_edge(set_stmt, vec![edge_id], &pb)?;
+                        if let MutationOutcome::Updated(ref u) = result {
+                            summary.edges_modified += u.edge_ids.len();
+                        }
+                    } else {
+                        return Err(SessionError::invalid_statement_type(
+                            messages::ERR_SET_REQUIRES_NODE,
+                        ));
                     }
                 }
+                MutationAction::Kill(kill_stmt) => {
+                    let target_id =
+                        self.resolve_target_with_bindings(&kill_stmt.target, local_bindings)?;
+                    let node_id = target_id.as_node().ok_or_else(|| {
+                        SessionError::invalid_statement_type(messages::ERR_KILL_REQUIRES_NODE)
+                    })?;
+
+                    let mut executor =
+                        MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
+                    let result = executor.execute_kill(kill_stmt, node_id)?;
+
+                    summary.nodes_deleted += result.deleted_nodes();
+                    summary.edges_deleted += result.deleted_edges();
+                }
+                MutationAction::Unlink(unlink_stmt) => {
+                    let target_id =
+                        self.resolve_target_with_bindings(&unlink_stmt.target, local_bindings)?;
+                    let edge_id = target_id.as_edge().ok_or_else(|| {
+                        SessionError::invalid_statement_type(messages::ERR_UNLINK_REQUIRES_EDGE)
+                    })?;
+
+                    let mut executor =
+                        MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
+                    let result = executor.execute_unlink(unlink_stmt, edge_id)?;
+
+                    summary.edges_deleted += result.deleted_edges();
+                }
             }
         }
-
-        Ok(MutationSummary {
-            nodes_created,
-            nodes_modified,
-            edges_modified,
-            edges_created,
-            nodes_deleted,
-            edges_deleted,
-            ..Default::default()
-        })
+        Ok(())
     }
 
     /// Resolve a target using provided bindings.
@@ -456,12 +825,13 @@ impl<'r> Session<'r> {
         &mut self,
         target_ref: &TargetRef,
         bindings: &mut HashMap<String, EntityId>,
+        expr_bindings: &Bindings,
         nodes_created: &mut usize,
     ) -> SessionResult<EntityId> {
         match target_ref {
             TargetRef::InlineSpawn(spawn_stmt) => {
                 // Execute the spawn and return the created node ID
-                let summary = self.execute_spawn(spawn_stmt)?;
+                let summary = self.execute_spawn_with_bindings(spawn_stmt, expr_bindings)?;
                 *nodes_created += summary.nodes_created;
 
                 // Get the variable name for inline spawn (first item)
@@ -489,14 +859,14 @@ impl<'r> Session<'r> {
         let pattern_bindings = to_pattern_bindings(&self.bindings);
         let executor = QueryExecutor::new(self.registry, &self.graph);
         let result = executor.execute_walk_with_bindings(stmt, Some(&pattern_bindings))?;
-        Ok(convert_query_result(&result))
+        Ok(convert_query_result(&result, &[]))
     }
 
     /// Execute a MATCH...WALK compound statement.
     fn execute_match_walk(&self, stmt: &mew_parser::MatchWalkStmt) -> SessionResult<QueryResult> {
         let executor = QueryExecutor::new(self.registry, &self.graph);
         let result = executor.execute_match_walk(stmt)?;
-        Ok(convert_query_result(&result))
+        Ok(convert_query_result(&result, &[]))
     }
 
     /// Execute an INSPECT statement.
@@ -570,41 +940,51 @@ impl<'r> Session<'r> {
                     (cols, vals)
                 };
 
-                let types = vec!["any".to_string(); columns.len()];
-                return Ok(QueryResult::new(columns, types, vec![values]));
+                let rows = vec![values];
+                let types = column_types(&[], &rows, columns.len());
+                return Ok(QueryResult::new(columns, types, rows));
             }
         }
 
         // Entity not found - return empty result with found=false
         let columns = vec!["found".to_string()];
-        let types = vec!["bool".to_string()];
+        let types = vec!["Bool".to_string()];
         let values = vec![Value::Bool(false)];
         Ok(QueryResult::new(columns, types, vec![values]))
     }
 
     /// Execute a SPAWN statement.
     fn execute_spawn(&mut self, stmt: &mew_parser::SpawnStmt) -> SessionResult<MutationSummary> {
-        let pattern_bindings = Bindings::new();
-        let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
-        let result = executor.execute_spawn(stmt, &pattern_bindings)?;
+        let pattern_bindings =
+            self.assignment_bindings(stmt.items.iter().flat_map(|item| &item.attrs));
+        self.execute_spawn_with_bindings(stmt, &pattern_bindings)
+    }
+
+    /// Execute a SPAWN whose attribute expressions see `bindings`.
+    fn execute_spawn_with_bindings(
+        &mut self,
+        stmt: &mew_parser::SpawnStmt,
+        bindings: &Bindings,
+    ) -> SessionResult<MutationSummary> {
+        let mut executor = MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
+        let result = executor.execute_spawn(stmt, bindings)?;
 
-        // Handle created nodes - for chained spawns, bind each variable
+        // Bind each spawn item's variable to its node, created or existing
         let mut nodes_created = 0;
+        let mut nodes_modified = 0;
+        let mut returned = Vec::new();
         if let MutationOutcome::Created(ref created) = result {
-            // Bind each spawn item's variable to its created node
-            for (i, item) in stmt.items.iter().enumerate() {
-                if let Some(&node_id) = created.node_ids.get(i) {
-                    self.bindings.insert(item.var.clone(), node_id.into());
-                    // Track for transaction rollback
-                    self.txn_state.track_created_node(node_id);
-                    nodes_created += 1;
+            for (item, &node_id) in stmt.items.iter().zip(&created.node_ids) {
+                self.bindings.insert(item.var.clone(), node_id.into());
+                if let Some(returning) = &stmt.returning {
+                    returned.push(self.returning_value(&item.var, node_id, returning));
                 }
             }
+            nodes_created = created.node_ids.len() - created.existing.len();
+            nodes_modified = created.updated.len();
         }
 
-        let edges_created = if let Some(edge_id) = result.created_edge() {
-            // Track for transaction rollback
-            self.txn_state.track_created_edge(edge_id);
+        let edges_created = if result.created_edge().is_some() {
             1
         } else {
             0
@@ -612,11 +992,58 @@ impl<'r> Session<'r> {
 
         Ok(MutationSummary {
             nodes_created,
+            nodes_modified,
             edges_created,
+            returned,
             ..Default::default()
         })
     }
 
+    /// The RETURNING value of a spawned node: its ID for `RETURNING id`,
+    /// otherwise a map of the requested attributes (`id` included when asked
+    /// for, or for `RETURNING *`). Qualified fields of other variables are
+    /// left out.
+    fn returning_value(
+        &self,
+        var: &str,
+        node_id: NodeId,
+        returning: &mew_parser::ReturningClause,
+    ) -> Value {
+        use mew_parser::ReturningClause;
+
+        let Some(node) = self.graph.get_node(node_id) else {
+            return Value::Null;
+        };
+        let field = |name: &str| match name {
+            "id" => Value::NodeRef(node_id),
+            _ => node.attributes.get(name).cloned().unwrap_or(Value::Null),
+        };
+        let fields: Vec<&str> = match returning {
+            ReturningClause::Id => return Value::NodeRef(node_id),
+            ReturningClause::All => {
+                let mut map: std::collections::BTreeMap<String, Value> = node
+                    .attributes
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect();
+                map.insert("id".to_string(), Value::NodeRef(node_id));
+                return Value::Map(map);
+            }
+            ReturningClause::Fields(names) => names.iter().map(String::as_str).collect(),
+            ReturningClause::QualifiedFields(fields) => fields
+                .iter()
+                .filter(|(field_var, _)| field_var == var)
+                .map(|(_, name)| name.as_str())
+                .collect(),
+        };
+        Value::Map(
+            fields
+                .into_iter()
+                .map(|name| (name.to_string(), field(name)))
+                .collect(),
+        )
+    }
+
     /// Execute a KILL statement.
     fn execute_kill(&mut self, stmt: &mew_parser::KillStmt) -> SessionResult<MutationSummary> {
         // Handle pattern-based KILL specially
@@ -630,7 +1057,7 @@ impl<'r> Session<'r> {
             SessionError::invalid_statement_type(messages::ERR_KILL_REQUIRES_NODE)
         })?;
 
-        let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
+        let mut executor = MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
         let result = executor.execute_kill(stmt, node_id)?;
 
         Ok(MutationSummary {
@@ -667,7 +1094,7 @@ impl<'r> Session<'r> {
         let mut total_edges_deleted = 0usize;
 
         for node_id in node_ids {
-            let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
+            let mut executor = MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
             let result = executor.execute_kill(stmt, node_id)?;
             total_nodes_deleted += result.deleted_nodes();
             total_edges_deleted += result.deleted_edges();
@@ -691,7 +1118,7 @@ impl<'r> Session<'r> {
             target_ids.push(entity_id);
         }
 
-        let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
+        let mut executor = MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
         let result = executor.execute_link(stmt, target_ids)?;
 
         // Store the created edge ID with the variable name if present
@@ -701,8 +1128,6 @@ impl<'r> Session<'r> {
             if let Some(ref var) = stmt.var {
                 self.bindings.insert(var.clone(), edge_id.into());
             }
-            // Track for transaction rollback
-            self.txn_state.track_created_edge(edge_id);
             1
         } else {
             0
@@ -751,7 +1176,7 @@ impl<'r> Session<'r> {
             SessionError::invalid_statement_type(messages::ERR_UNLINK_REQUIRES_EDGE)
         })?;
 
-        let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
+        let mut executor = MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
         let result = executor.execute_unlink(stmt, edge_id)?;
 
         Ok(MutationSummary {
@@ -763,11 +1188,11 @@ impl<'r> Session<'r> {
     /// Execute a SET statement.
     fn execute_set(&mut self, stmt: &mew_parser::SetStmt) -> SessionResult<MutationSummary> {
         let target_id = self.resolve_target(&stmt.target)?;
-        let pattern_bindings = Bindings::new();
+        let pattern_bindings = self.assignment_bindings(&stmt.assignments);
 
         // Handle both node and edge targets
         if let Some(node_id) = target_id.as_node() {
-            let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
+            let mut executor = MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
             let result = executor.execute_set(stmt, vec![node_id], &pattern_bindings)?;
 
             let nodes_modified = match result {
@@ -780,7 +1205,7 @@ impl<'r> Session<'r> {
                 ..Default::default()
             })
         } else if let Some(edge_id) = target_id.as_edge() {
-            let mut executor = MutationExecutor::new(self.registry, &mut self.graph);
+            let mut executor = MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
             let result = executor.execute_set_edge(stmt, vec![edge_id], &pattern_bindings)?;
 
             let edges_modified = match result {
@@ -799,6 +1224,36 @@ impl<'r> Session<'r> {
         }
     }
 
+    /// Bindings holding the current statement parameters.
+    fn param_bindings(&self) -> Bindings {
+        let mut bindings = Bindings::new();
+        for (name, value) in &self.params {
+            bindings.insert_param(name, value.clone());
+        }
+        bindings
+    }
+
+    /// Bindings holding the current statement parameters and the session
+    /// entities that `#name` references in the assigned values point to.
+    fn assignment_bindings<'a>(
+        &self,
+        assignments: impl IntoIterator<Item = &'a mew_parser::AttrAssignment>,
+    ) -> Bindings {
+        let mut bindings = self.param_bindings();
+        let mut names = Vec::new();
+        for assignment in assignments {
+            collect_id_refs(&assignment.value, &mut names);
+        }
+        for name in names {
+            match self.bindings.get(name) {
+                Some(EntityId::Node(id)) => bindings.insert_id_ref(name, *id),
+                Some(EntityId::Edge(id)) => bindings.insert_id_ref(name, *id),
+                None => {}
+            }
+        }
+        bindings
+    }
+
     /// Resolve a target to an EntityId.
     fn resolve_target(&self, t: &mew_parser::Target) -> SessionResult<EntityId> {
         Ok(target::resolve_target(
@@ -820,7 +1275,7 @@ impl<'r> Session<'r> {
             .map_err(|e| SessionError::constraint_error(e.to_string()))?;
 
         if !violations.is_empty() {
-            let _ = self.graph.delete_edge(edge_id);
+            let _ = Arc::make_mut(&mut self.graph).delete_edge(edge_id);
             let first = &violations.all()[0];
             return Err(SessionError::constraint_error(format!(
                 "{}: {}",
@@ -830,6 +1285,41 @@ impl<'r> Session<'r> {
         Ok(())
     }
 
+    /// Check the declared constraints whose types, edges or attributes were
+    /// written. A hard violation fails the statement.
+    fn check_declared_constraints(&self, touched: &Touched) -> SessionResult<()> {
+        if touched.is_empty() {
+            return Ok(());
+        }
+
+        let checker = ConstraintChecker::new(self.registry, &self.graph);
+        let violations = checker
+            .check_touched(touched)
+            .map_err(|e| SessionError::constraint_error(e.to_string()))?;
+        if let Some(first) = violations.errors().next() {
+            return Err(SessionError::constraint_error(format!(
+                "{}: {}",
+                first.constraint_name, first.message
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check the declared constraints the open transaction's writes can
+    /// break, before it commits. A hard violation rolls the whole
+    /// transaction back.
+    fn check_transaction_constraints(&mut self) -> SessionResult<()> {
+        if !self.txn_state.in_transaction {
+            return Ok(());
+        }
+        let touched = std::mem::take(&mut self.txn_state.touched);
+        if let Err(e) = self.check_declared_constraints(&touched) {
+            self.reset_transaction();
+            return Err(e);
+        }
+        Ok(())
+    }
+
     /// Resolve a target reference to an EntityId.
     fn resolve_target_ref(&self, target_ref: &TargetRef) -> SessionResult<EntityId> {
         Ok(target::resolve_target_ref(target_ref, &self.bindings)?)
@@ -837,37 +1327,195 @@ impl<'r> Session<'r> {
 
     /// Execute a transaction statement.
     ///
-    /// # ROLLBACK Semantics
+    /// BEGIN starts an undo log on the graph. ROLLBACK replays it backwards,
+    /// which undoes every SPAWN, KILL, LINK, UNLINK and SET of the
+    /// transaction: deleted nodes and edges (including cascaded deletions)
+    /// come back with their original IDs and attributes.
+    fn execute_txn(&mut self, stmt: &TxnStmt) -> SessionResult<StatementResult> {
+        if let TxnStmt::Commit = stmt {
+            self.check_transaction_constraints()?;
+        }
+        let result = transaction::execute_txn(&mut self.txn_state, stmt)?;
+        match stmt {
+            TxnStmt::Begin { .. } => {
+                self.txn_state.undo = Some(UndoLog::begin(Arc::make_mut(&mut self.graph)));
+            }
+            TxnStmt::Commit => {
+                if let Some(undo) = self.txn_state.undo.take() {
+                    undo.commit(Arc::make_mut(&mut self.graph));
+                }
+            }
+            TxnStmt::Rollback => self.rollback_changes()?,
+            TxnStmt::Savepoint(name) => {
+                if let Some(undo) = &mut self.txn_state.undo {
+                    undo.savepoint(&self.graph, name);
+                }
+            }
+            TxnStmt::RollbackTo(name) => {
+                if let Some(undo) = &mut self.txn_state.undo {
+                    undo.rollback_to(Arc::make_mut(&mut self.graph), name)?;
+                }
+            }
+            TxnStmt::Release(name) => {
+                if let Some(undo) = &mut self.txn_state.undo {
+                    undo.release(name)?;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Undo every write of the open transaction.
+    fn rollback_changes(&mut self) -> SessionResult<()> {
+        match self.txn_state.undo.take() {
+            Some(undo) => Ok(undo.rollback(Arc::make_mut(&mut self.graph))?),
+            None => Ok(()),
+        }
+    }
+
+    /// Execute a transaction statement on a shared database.
+    ///
+    /// ROLLBACK and failed commits simply drop the working copy, so every
+    /// kind of change is undone. A savepoint keeps the working copy as it
+    /// was (pinning its base version); ROLLBACK TO switches back to it, and
+    /// the next statement rebases it as usual.
+    fn execute_shared_txn(
+        &mut self,
+        db: &'r Database,
+        stmt: &TxnStmt,
+    ) -> SessionResult<StatementResult> {
+        if let TxnStmt::Commit = stmt {
+            self.check_transaction_constraints()?;
+        }
+        let result = transaction::execute_txn(&mut self.txn_state, stmt)?;
+
+        match stmt {
+            TxnStmt::Begin { isolation } => {
+                self.isolation = isolation.unwrap_or(IsolationLevel::ReadCommitted);
+                self.refresh_snapshot(db);
+            }
+            TxnStmt::Commit => {
+                self.drop_snapshots(db, 0);
+                self.commit_changes(db)?;
+            }
+            TxnStmt::Rollback => {
+                self.drop_snapshots(db, 0);
+                self.discard_changes(db);
+            }
+            TxnStmt::Savepoint(name) => {
+                if let Some(index) = self.snapshot_index(name) {
+                    let old = self.txn_state.snapshots.remove(index);
+                    db.unpin(old.version);
+                }
+                db.repin(self.base_version);
+                self.txn_state.snapshots.push(Snapshot {
+                    name: name.clone(),
+                    version: self.base_version,
+                    graph: Arc::clone(&self.graph),
+                });
+            }
+            TxnStmt::RollbackTo(name) => {
+                let index = self
+                    .snapshot_index(name)
+                    .ok_or_else(|| TransactionError::savepoint_not_found(name))?;
+                self.drop_snapshots(db, index + 1);
+                let snapshot = &self.txn_state.snapshots[index];
+                db.repin(snapshot.version);
+                db.unpin(self.base_version);
+                self.base_version = snapshot.version;
+                self.graph = Arc::clone(&snapshot.graph);
+            }
+            TxnStmt::Release(name) => {
+                let index = self
+                    .snapshot_index(name)
+                    .ok_or_else(|| TransactionError::savepoint_not_found(name))?;
+                self.drop_snapshots(db, index);
+            }
+        }
+        Ok(result)
+    }
+
+    fn snapshot_index(&self, name: &str) -> Option<usize> {
+        self.txn_state
+            .snapshots
+            .iter()
+            .rposition(|snapshot| snapshot.name == name)
+    }
+
+    /// Drop the savepoints from `index` on, releasing their versions.
+    fn drop_snapshots(&mut self, db: &'r Database, index: usize) {
+        for snapshot in self
+            .txn_state
+            .snapshots
+            .drain(index.min(self.txn_state.snapshots.len())..)
+        {
+            db.unpin(snapshot.version);
+        }
+    }
+
+    /// Bring the working graph up to date before a statement.
     ///
-    /// ROLLBACK currently only undoes SPAWN operations (node/edge creation).
-    /// SET mutations and DELETE operations within the transaction are NOT
-    /// automatically reverted. This is a known limitation - full MVCC or
-    /// undo-logging would be required for complete rollback support.
+    /// A SERIALIZABLE transaction keeps the snapshot taken at BEGIN. Otherwise
+    /// the latest committed version is used, with the transaction's own
+    /// pending writes replayed on top of it.
+    fn refresh_snapshot(&mut self, db: &'r Database) {
+        if self.in_transaction() && self.isolation == IsolationLevel::Serializable {
+            return;
+        }
+        if db.version() == self.base_version {
+            return;
+        }
+
+        let pending = self.graph.changes().is_some_and(|c| !c.is_empty());
+        if !pending {
+            self.discard_changes(db);
+        } else if let Some((version, graph)) = db.rebase(self.base_version, &self.graph) {
+            db.unpin(self.base_version);
+            self.base_version = version;
+            self.graph = Arc::new(graph);
+        }
+    }
+
+    /// Commit or discard the writes of a statement run outside a transaction.
+    fn finish_statement(&mut self, db: &'r Database, succeeded: bool) -> SessionResult<()> {
+        if self.in_transaction() {
+            Ok(())
+        } else if succeeded {
+            self.commit_changes(db)
+        } else {
+            self.discard_changes(db);
+            Ok(())
+        }
+    }
+
+    /// Commit the working graph's writes to the database.
     ///
-    /// Errors during rollback cleanup are intentionally ignored because:
-    /// - The entity may have already been deleted by a KILL within the transaction
-    /// - Partial rollback is better than failing and leaving the DB in an
-    ///   inconsistent state
-    fn execute_txn(&mut self, stmt: &mew_parser::TxnStmt) -> SessionResult<StatementResult> {
-        // For ROLLBACK, we need to undo created entities before processing
-        if matches!(stmt, mew_parser::TxnStmt::Rollback) {
-            // Take ownership to avoid clone - clear_tracked() would clear anyway
-            let created_edges = std::mem::take(&mut self.txn_state.created_edges);
-            let created_nodes = std::mem::take(&mut self.txn_state.created_nodes);
+    /// On a conflict the writes are discarded and the error returned.
+    fn commit_changes(&mut self, db: &'r Database) -> SessionResult<()> {
+        if self.graph.changes().is_none_or(|c| c.is_empty()) {
+            return Ok(());
+        }
 
-            // Delete edges first (they may reference nodes)
-            for edge_id in created_edges {
-                // Errors ignored intentionally - see doc comment above
-                let _ = self.graph.delete_edge(edge_id);
+        let changes = Arc::make_mut(&mut self.graph).take_changes();
+        match db.commit(self.base_version, &self.graph, changes) {
+            Ok((version, graph)) => {
+                self.base_version = version;
+                self.graph = graph;
+                Ok(())
             }
-            // Then delete nodes
-            for node_id in created_nodes {
-                // Errors ignored intentionally - see doc comment above
-                let _ = self.graph.delete_node(node_id);
+            Err(e) => {
+                self.discard_changes(db);
+                Err(e)
             }
         }
+    }
 
-        transaction::execute_txn(&mut self.txn_state, stmt)
+    /// Drop the working graph's writes and move to the latest version.
+    fn discard_changes(&mut self, db: &'r Database) {
+        let (version, graph) = db.pin_latest();
+        db.unpin(self.base_version);
+        self.base_version = version;
+        self.graph = graph;
     }
 
     /// Execute an EXPLAIN statement - returns the query plan without executing.
@@ -883,6 +1531,13 @@ impl<'r> Session<'r> {
                     Err(e) => format!("Plan error: {}", e),
                 }
             }
+            Stmt::Union(u) => {
+                let planner = QueryPlanner::new(self.registry);
+                match planner.plan_union(u) {
+                    Ok(plan) => format!("{:#?}", plan),
+                    Err(e) => format!("Plan error: {}", e),
+                }
+            }
             Stmt::Walk(w) => {
                 let planner = QueryPlanner::new(self.registry);
                 match planner.plan_walk(w) {
@@ -931,15 +1586,364 @@ impl<'r> Session<'r> {
             }),
         }
     }
-}
 
-/// Convert a HashMap of entity bindings to pattern Bindings for expression evaluation.
-fn to_pattern_bindings(bindings: &HashMap<String, EntityId>) -> Bindings {
-    let mut pattern_bindings = Bindings::new();
-    for (name, entity) in bindings {
-        match entity {
-            EntityId::Node(node_id) => {
-                pattern_bindings.insert(name.clone(), Binding::Node(*node_id));
+    /// Execute a WATCH statement: register a consume-mode watch.
+    ///
+    /// Returns the watch ID; deliveries are pulled with [`Session::poll_watch`].
+    /// All consumers of a session share one pool, so `group` has no effect here.
+    fn execute_watch(&mut self, stmt: &WatchStmt) -> SessionResult<QueryResult> {
+        if stmt.options.mode != WatchMode::Consume {
+            return Err(SessionError::invalid_statement_type(
+                "only consume-mode watches ([mode: consume]) are supported",
+            ));
+        }
+
+        let mut analyzer = Analyzer::new(self.registry);
+        analyzer.analyze_stmt(&Stmt::Watch(stmt.clone()))?;
+
+        // The first node variable is the message
+        let message_var = stmt
+            .pattern
+            .iter()
+            .find_map(|elem| match elem {
+                PatternElem::Node(node) => Some(node.var.clone()),
+                PatternElem::Edge(_) => None,
+            })
+            .ok_or_else(|| SessionError::invalid_statement_type("WATCH requires a node pattern"))?;
+        let mut query = stmt.to_match();
+        query.return_clause.projections.insert(
+            0,
+            Projection {
+                expr: Expr::Var(message_var, stmt.span),
+                alias: Some(WATCH_MESSAGE_COLUMN.to_string()),
+                span: stmt.span,
+            },
+        );
+
+        let evaluator = Evaluator::new(self.registry);
+        let bindings = self.param_bindings();
+        let options = &stmt.options;
+
+        let ack_timeout_ms = match &options.ack_timeout {
+            None => DEFAULT_ACK_TIMEOUT_MS,
+            Some(expr) => match evaluator.eval(expr, &bindings, &self.graph)? {
+                Value::Duration(ms) | Value::Int(ms) if ms > 0 => ms,
+                other => {
+                    return Err(SessionError::invalid_statement_type(format!(
+                        "ack_timeout must be a positive duration, got {}",
+                        other
+                    )))
+                }
+            },
+        };
+
+        let max_redeliveries = match &options.max_redeliveries {
+            None => DEFAULT_MAX_REDELIVERIES,
+            Some(expr) => match evaluator.eval(expr, &bindings, &self.graph)? {
+                Value::Int(n) if n > 0 => u32::try_from(n).unwrap_or(u32::MAX),
+                other => {
+                    return Err(SessionError::invalid_statement_type(format!(
+                        "max_redeliveries must be a positive integer, got {}",
+                        other
+                    )))
+                }
+            },
+        };
+
+        let dead_letter = match &options.dead_letter {
+            None => None,
+            Some(Expr::IdRef(name, _)) => Some(name.clone()),
+            Some(expr) => match evaluator.eval(expr, &bindings, &self.graph)? {
+                Value::Null => None,
+                Value::String(name) => Some(name),
+                other => Some(other.to_string()),
+            },
+        };
+
+        let watch_id = self.watches.register(ConsumeWatch {
+            query,
+            ack_timeout_ms,
+            max_redeliveries,
+            dead_letter,
+            params: self.params.clone(),
+        });
+
+        Ok(QueryResult::new(
+            vec!["watch_id".to_string()],
+            vec!["Int".to_string()],
+            vec![vec![Value::Int(watch_id as i64)]],
+        ))
+    }
+
+    /// Pull up to `max` new deliveries from a consume-mode watch.
+    pub fn poll_watch(&mut self, watch_id: WatchId, max: usize) -> SessionResult<Vec<WatchEvent>> {
+        self.poll_watch_at(watch_id, max, current_time_millis())
+    }
+
+    /// Pull up to `max` new deliveries as of `now` (milliseconds since epoch).
+    ///
+    /// Deliveries whose `ack_timeout` has passed are treated as NACKed first,
+    /// so their messages can be handed out again.
+    pub fn poll_watch_at(
+        &mut self,
+        watch_id: WatchId,
+        max: usize,
+        now: i64,
+    ) -> SessionResult<Vec<WatchEvent>> {
+        let Some(db) = self.database else {
+            return self.deliver_watch_events(watch_id, max, now);
+        };
+        self.refresh_snapshot(db);
+        let events = self.deliver_watch_events(watch_id, max, now);
+        self.finish_statement(db, events.is_ok())?;
+        events
+    }
+
+    /// Settle expired deliveries, then hand out new ones.
+    fn deliver_watch_events(
+        &mut self,
+        watch_id: WatchId,
+        max: usize,
+        now: i64,
+    ) -> SessionResult<Vec<WatchEvent>> {
+        let watch = self
+            .watches
+            .get(watch_id)
+            .cloned()
+            .ok_or_else(|| SessionError::watch_not_found(watch_id))?;
+
+        self.expire_deliveries(now)?;
+
+        let mut bindings = Bindings::new();
+        for (name, value) in &watch.params {
+            bindings.insert_param(name, value.clone());
+        }
+        let executor = QueryExecutor::new(self.registry, &self.graph);
+        let result = executor.execute_match_with_bindings(&watch.query, &bindings)?;
+        let columns = result.column_names()[1..].to_vec();
+
+        let mut events = Vec::new();
+        let mut seen = HashSet::new();
+        for row in result.rows() {
+            if events.len() >= max {
+                break;
+            }
+            let Some(node) = row.get(0).and_then(|v| v.as_node_ref()) else {
+                continue;
+            };
+            if !seen.insert(node) {
+                continue;
+            }
+            let Some((delivery_id, attempt)) = self.watches.deliver(self.id, watch_id, node, now)
+            else {
+                continue;
+            };
+
+            let values = columns
+                .iter()
+                .map(|col| row.get_by_name(col).cloned().unwrap_or(Value::Null))
+                .collect();
+            events.push(WatchEvent {
+                watch_id,
+                delivery_id,
+                node,
+                columns: columns.clone(),
+                row: values,
+                attempt,
+            });
+        }
+
+        Ok(events)
+    }
+
+    /// Cancel a consume-mode watch. Its unsettled messages return to the pool.
+    pub fn cancel_watch(&mut self, watch_id: WatchId) -> SessionResult<()> {
+        if self.watches.cancel(watch_id) {
+            Ok(())
+        } else {
+            Err(SessionError::watch_not_found(watch_id))
+        }
+    }
+
+    /// Execute an ACK statement: delete the delivered message.
+    fn execute_ack(&mut self, stmt: &mew_parser::AckStmt) -> SessionResult<MutationSummary> {
+        let delivery_id = self.eval_delivery_id(&stmt.delivery_id)?;
+        let now = current_time_millis();
+        self.expire_deliveries(now)?;
+
+        let settlement = self
+            .watches
+            .ack(&delivery_id)
+            .ok_or_else(|| SessionError::invalid_delivery_id(delivery_id))?;
+        self.settle_delivery(settlement, now)
+    }
+
+    /// Execute a NACK statement: redeliver or dead-letter the message.
+    fn execute_nack(&mut self, stmt: &mew_parser::NackStmt) -> SessionResult<MutationSummary> {
+        let delivery_id = self.eval_delivery_id(&stmt.delivery_id)?;
+        let now = current_time_millis();
+        self.expire_deliveries(now)?;
+
+        let settlement = self
+            .watches
+            .nack(&delivery_id, stmt.no_retry)
+            .ok_or_else(|| SessionError::invalid_delivery_id(delivery_id))?;
+        self.settle_delivery(settlement, now)
+    }
+
+    /// Evaluate the delivery ID of an ACK/NACK.
+    fn eval_delivery_id(&self, expr: &Expr) -> SessionResult<String> {
+        let evaluator = Evaluator::new(self.registry);
+        match evaluator.eval(expr, &self.param_bindings(), &self.graph)? {
+            Value::String(id) => Ok(id),
+            other => Err(SessionError::invalid_delivery_id(other.to_string())),
+        }
+    }
+
+    /// Settle every delivery whose `ack_timeout` has passed.
+    fn expire_deliveries(&mut self, now: i64) -> SessionResult<()> {
+        for settlement in self.watches.expire(now) {
+            self.settle_delivery(settlement, now)?;
+        }
+        Ok(())
+    }
+
+    /// Apply a settled delivery to the graph.
+    fn settle_delivery(
+        &mut self,
+        settlement: Settlement,
+        now: i64,
+    ) -> SessionResult<MutationSummary> {
+        match settlement {
+            Settlement::Release(_) => Ok(MutationSummary::default()),
+            Settlement::Delete(node) => self.delete_message(node),
+            Settlement::DeadLetter {
+                node,
+                reason,
+                attempts,
+                destination,
+            } => {
+                let mut summary = MutationSummary::default();
+                if let Some(destination) = destination {
+                    self.create_dead_letter(node, reason, attempts, destination, now)?;
+                    summary.nodes_created += 1;
+                }
+                summary.merge(&self.delete_message(node)?);
+                Ok(summary)
+            }
+        }
+    }
+
+    /// Record a permanently failed message as a `_DeadLetter` node.
+    fn create_dead_letter(
+        &mut self,
+        node: NodeId,
+        reason: &str,
+        attempts: u32,
+        destination: String,
+        now: i64,
+    ) -> SessionResult<()> {
+        let type_id = self.registry.get_type_id(DEAD_LETTER_TYPE).ok_or_else(|| {
+            SessionError::invalid_statement_type(format!(
+                "Dead letter failed: type '{}' is not defined",
+                DEAD_LETTER_TYPE
+            ))
+        })?;
+
+        let mut attributes = Attributes::new();
+        attributes.insert(
+            "original_match".to_string(),
+            Value::String(self.serialize_message(node)),
+        );
+        attributes.insert(
+            "failure_reason".to_string(),
+            Value::String(reason.to_string()),
+        );
+        attributes.insert("delivery_attempts".to_string(), Value::Int(attempts as i64));
+        attributes.insert("created_at".to_string(), Value::Timestamp(now));
+        attributes.insert("destination".to_string(), Value::String(destination));
+
+        Arc::make_mut(&mut self.graph).create_node(type_id, attributes);
+        Ok(())
+    }
+
+    /// Serialize a message node as `Type { attr: value, ... }` (sorted by name).
+    fn serialize_message(&self, node: NodeId) -> String {
+        let Some(message) = self.graph.get_node(node) else {
+            return "null".to_string();
+        };
+        let type_name = self
+            .registry
+            .get_type(message.type_id)
+            .map(|t| t.name.as_str())
+            .unwrap_or("Unknown");
+        let mut attrs: Vec<_> = message.attributes.iter().collect();
+        attrs.sort_by(|a, b| a.0.cmp(b.0));
+        let fields: Vec<String> = attrs
+            .into_iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect();
+        format!("{} {{ {} }}", type_name, fields.join(", "))
+    }
+
+    /// Delete a message node, if it still exists.
+    fn delete_message(&mut self, node: NodeId) -> SessionResult<MutationSummary> {
+        self.watches.forget(node);
+        if self.graph.get_node(node).is_none() {
+            return Ok(MutationSummary::default());
+        }
+
+        let stmt = mew_parser::KillStmt {
+            target: mew_parser::Target::Id(node.to_string()),
+            cascade: None,
+            returning: None,
+            span: Default::default(),
+        };
+        let mut executor = MutationExecutor::new(self.registry, Arc::make_mut(&mut self.graph));
+        let result = executor.execute_kill(&stmt, node)?;
+
+        Ok(MutationSummary {
+            nodes_deleted: result.deleted_nodes(),
+            edges_deleted: result.deleted_edges(),
+            ..Default::default()
+        })
+    }
+}
+
+impl Drop for Session<'_> {
+    fn drop(&mut self) {
+        if let Some(db) = self.database {
+            self.drop_snapshots(db, 0);
+            db.unpin(self.base_version);
+        }
+    }
+}
+
+/// Combine query results of a script: append rows if columns match, or
+/// replace if different.
+fn combine_queries(existing: Option<QueryResult>, q: QueryResult) -> QueryResult {
+    match existing {
+        None => q,
+        Some(mut existing) => {
+            if existing.columns == q.columns {
+                // Same columns - append rows
+                existing.rows.extend(q.rows);
+                existing
+            } else {
+                // Different columns - use the new result
+                q
+            }
+        }
+    }
+}
+
+/// Convert a HashMap of entity bindings to pattern Bindings for expression evaluation.
+fn to_pattern_bindings(bindings: &HashMap<String, EntityId>) -> Bindings {
+    let mut pattern_bindings = Bindings::new();
+    for (name, entity) in bindings {
+        match entity {
+            EntityId::Node(node_id) => {
+                pattern_bindings.insert(name.clone(), Binding::Node(*node_id));
             }
             EntityId::Edge(edge_id) => {
                 pattern_bindings.insert(name.clone(), Binding::Edge(*edge_id));
@@ -949,6 +1953,26 @@ fn to_pattern_bindings(bindings: &HashMap<String, EntityId>) -> Bindings {
     pattern_bindings
 }
 
+/// Collect the names of the `#name` ID references in `expr`.
+fn collect_id_refs<'e>(expr: &'e Expr, names: &mut Vec<&'e str>) {
+    match expr {
+        Expr::IdRef(name, _) => names.push(name),
+        Expr::FnCall(fc) => fc.args.iter().for_each(|arg| collect_id_refs(arg, names)),
+        Expr::AttrAccess(base, _, _) | Expr::UnaryOp(_, base, _) | Expr::TypeCheck(base, _, _) => {
+            collect_id_refs(base, names)
+        }
+        Expr::BinaryOp(_, left, right, _) | Expr::Index(left, right, _) => {
+            collect_id_refs(left, names);
+            collect_id_refs(right, names);
+        }
+        Expr::List(items, _) => items.iter().for_each(|item| collect_id_refs(item, names)),
+        Expr::Map(entries, _) => entries
+            .iter()
+            .for_each(|(_, value)| collect_id_refs(value, names)),
+        _ => {}
+    }
+}
+
 /// Session manager for handling multiple sessions.
 #[derive(Default)]
 pub struct SessionManager {
@@ -986,6 +2010,7 @@ mod tests {
         builder
             .add_type("Task")
             .attr(AttrDef::new("title", "String"))
+            .attr(AttrDef::new("meta", "Map"))
             .done()
             .unwrap();
         builder
@@ -993,6 +2018,37 @@ mod tests {
             .attr(AttrDef::new("name", "String"))
             .done()
             .unwrap();
+        builder
+            .add_type("Blob")
+            .attr(AttrDef::new("hash", "Hash").unique())
+            .attr(AttrDef::new("data", "Bytes"))
+            .done()
+            .unwrap();
+        builder
+            .add_type("Doc")
+            .attr(AttrDef::new("title", "String"))
+            .attr(AttrDef::new("embedding", "Vector[2]"))
+            .attr(AttrDef::new("body", "String").searchable())
+            .done()
+            .unwrap();
+        builder
+            .add_type("Place")
+            .attr(AttrDef::new("name", "String"))
+            .attr(AttrDef::new("loc", "Point"))
+            .done()
+            .unwrap();
+        builder
+            .add_type("Job")
+            .attr(AttrDef::new("name", "String"))
+            .attr(AttrDef::new("status", "String"))
+            .done()
+            .unwrap();
+        builder
+            .add_edge_type("owns")
+            .param("owner", "Person")
+            .param("task", "Task")
+            .done()
+            .unwrap();
         builder.build().unwrap()
     }
 
@@ -1069,6 +2125,231 @@ mod tests {
         assert!(!session.in_transaction());
     }
 
+    #[test]
+    fn test_rollback_undoes_kill_unlink_and_set() {
+        // GIVEN two people owning a task each
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute(r#"SPAWN p: Person { name = "ann" }"#)
+            .unwrap();
+        session
+            .execute(r#"SPAWN q: Person { name = "bob" }"#)
+            .unwrap();
+        session.execute(r#"SPAWN t: Task { title = "a" }"#).unwrap();
+        session.execute(r#"SPAWN u: Task { title = "b" }"#).unwrap();
+        session.execute("LINK e: owns(p, t)").unwrap();
+        session.execute("LINK f: owns(q, u)").unwrap();
+        let before = session.graph().clone();
+
+        // WHEN a transaction kills one owner (cascading its edge), unlinks
+        // the other edge and updates a task, then rolls back
+        session.execute("BEGIN").unwrap();
+        session.execute("KILL p").unwrap();
+        session.execute("UNLINK f").unwrap();
+        session
+            .execute(r#"MATCH t: Task WHERE t.title = "b" SET t.title = "changed""#)
+            .unwrap();
+        session
+            .execute(r#"SPAWN v: Task { title = "new" }"#)
+            .unwrap();
+        session.execute("ROLLBACK").unwrap();
+
+        // THEN every entity is back with its original ID and attributes
+        let graph = session.graph();
+        assert_eq!(graph.node_count(), before.node_count());
+        assert_eq!(graph.edge_count(), before.edge_count());
+        for id in before.all_node_ids() {
+            let (old, new) = (before.get_node(id).unwrap(), graph.get_node(id).unwrap());
+            assert_eq!(old.attributes, new.attributes);
+        }
+        for id in before.all_edge_ids() {
+            let (old, new) = (before.get_edge(id).unwrap(), graph.get_edge(id).unwrap());
+            assert_eq!(old.targets, new.targets);
+        }
+    }
+
+    #[test]
+    fn test_rollback_to_savepoint() {
+        // GIVEN a transaction that writes before and after a savepoint
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session.execute("BEGIN").unwrap();
+        session
+            .execute(r#"SPAWN t: Task { title = "batch 1" }"#)
+            .unwrap();
+        session.execute("SAVEPOINT batch_2").unwrap();
+        session
+            .execute(r#"SPAWN u: Task { title = "batch 2" }"#)
+            .unwrap();
+        session.execute("KILL t").unwrap();
+
+        // WHEN the second batch is rolled back and the transaction committed
+        let result = session.execute("ROLLBACK TO batch_2").unwrap();
+        session.execute("COMMIT").unwrap();
+
+        // THEN only the first batch survives
+        assert!(matches!(
+            result,
+            StatementResult::Transaction(TransactionResult::RolledBackTo { ref name })
+                if name == "batch_2"
+        ));
+        let titles = match session.execute("MATCH t: Task RETURN t.title").unwrap() {
+            StatementResult::Query(q) => q.rows,
+            _ => panic!("Expected query result"),
+        };
+        assert_eq!(titles, vec![vec![Value::String("batch 1".into())]]);
+    }
+
+    #[test]
+    fn test_released_savepoint_is_gone() {
+        // GIVEN
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session.execute("BEGIN").unwrap();
+        session.execute("SAVEPOINT sp").unwrap();
+
+        // WHEN
+        session.execute("RELEASE SAVEPOINT sp").unwrap();
+        let result = session.execute("ROLLBACK TO sp");
+
+        // THEN
+        assert!(matches!(
+            result,
+            Err(SessionError::TransactionError(
+                TransactionError::SavepointNotFound { .. }
+            ))
+        ));
+        assert!(session.in_transaction());
+        assert!(session.execute("SAVEPOINT outside").is_ok());
+        session.execute("COMMIT").unwrap();
+        assert!(session.execute("SAVEPOINT outside").is_err());
+    }
+
+    #[test]
+    fn test_failed_statement_is_undone() {
+        // GIVEN a transaction with two successful writes
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session.execute("BEGIN").unwrap();
+        session.execute(r#"SPAWN t: Task { title = "a" }"#).unwrap();
+        session.execute(r#"SPAWN u: Task { title = "b" }"#).unwrap();
+
+        // WHEN a statement fails after writing: the second unique blob clashes
+        let result = session.execute(r#"MATCH t: Task SPAWN b: Blob { hash = hash("same") }"#);
+
+        // THEN its writes are gone and the transaction stays open
+        assert!(result.is_err());
+        assert!(session.in_transaction());
+        assert_eq!(session.graph().node_count(), 2);
+    }
+
+    #[test]
+    fn test_subquery_constraint_rechecked_on_link_and_set() {
+        // GIVEN a project that may have at most two open tasks, and has two
+        let registry = mew_compiler::compile(
+            "node Project { name: String }
+             node Task { open: Bool }
+             edge belongs_to(task: Task, project: Project)
+             constraint max_open: p: Project =>
+               COUNT { t: Task, belongs_to(t, p) WHERE t.open = true } <= 2",
+        )
+        .unwrap();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute_all(
+                r#"SPAWN p: Project { name = "p" }
+                SPAWN a: Task { open = true }
+                SPAWN b: Task { open = true }
+                SPAWN c: Task { open = true }
+                SPAWN d: Task { open = false }
+                LINK belongs_to(a, p)
+                LINK belongs_to(b, p)
+                LINK belongs_to(d, p)"#,
+            )
+            .unwrap();
+        let open_count = |session: &mut Session| match session
+            .execute(
+                "MATCH p: Project RETURN COUNT { t: Task, belongs_to(t, p) WHERE t.open = true }",
+            )
+            .unwrap()
+        {
+            StatementResult::Query(q) => q.rows[0][0].clone(),
+            _ => panic!("Expected query result"),
+        };
+
+        // WHEN a third open task is linked, or a linked task is reopened
+        let linked = session.execute("LINK belongs_to(c, p)");
+        let reopened = session.execute("SET d.open = true");
+
+        // THEN both are rejected and undone
+        assert!(linked.unwrap_err().to_string().contains("max_open"));
+        assert!(reopened.unwrap_err().to_string().contains("max_open"));
+        assert_eq!(open_count(&mut session), Value::Int(2));
+
+        // WHEN a transaction passes through a violation but ends within it
+        session.execute("BEGIN").unwrap();
+        session.execute("LINK belongs_to(c, p)").unwrap();
+        session.execute("SET a.open = false").unwrap();
+
+        // THEN it commits, since constraints are checked at COMMIT
+        session.execute("COMMIT").unwrap();
+        assert_eq!(open_count(&mut session), Value::Int(2));
+
+        // WHEN a transaction ends in violation
+        session.execute("BEGIN").unwrap();
+        session.execute("SET a.open = true").unwrap();
+        let committed = session.execute("COMMIT");
+
+        // THEN COMMIT fails and the whole transaction is rolled back
+        assert!(committed.unwrap_err().to_string().contains("max_open"));
+        assert!(!session.in_transaction());
+        assert_eq!(open_count(&mut session), Value::Int(2));
+    }
+
+    #[test]
+    fn test_unwind_param_rows_in_one_statement() {
+        // GIVEN client rows passed as a list parameter
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        let row = |title: &str| {
+            Value::Map(
+                [("title".to_string(), Value::String(title.to_string()))]
+                    .into_iter()
+                    .collect(),
+            )
+        };
+        let params = HashMap::from([(
+            "rows".to_string(),
+            Value::List(vec![row("a"), row("b"), row("c")]),
+        )]);
+
+        // WHEN one UNWIND statement spawns a task per row
+        let result = session
+            .execute_with_params(
+                "UNWIND $rows AS r SPAWN t: Task { title = r.title } RETURNING title",
+                &params,
+            )
+            .unwrap();
+
+        // THEN the summary covers the batch, with one RETURNING value per row
+        let StatementResult::Mutation(summary) = result else {
+            panic!("Expected mutation result");
+        };
+        assert_eq!(summary.nodes_created, 3);
+        assert_eq!(summary.returned.len(), 3);
+        assert_eq!(session.graph().node_count(), 3);
+
+        // AND a list that is not a list fails without writing
+        let params = HashMap::from([("rows".to_string(), Value::Int(1))]);
+        let result = session.execute_with_params(
+            "UNWIND $rows AS r SPAWN t: Task { title = r.title }",
+            &params,
+        );
+        assert!(result.is_err());
+        assert_eq!(session.graph().node_count(), 3);
+    }
+
     #[test]
     fn test_commit_without_transaction_fails() {
         // GIVEN
@@ -1121,6 +2402,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_query_reports_column_types() {
+        // GIVEN a task and a query with scalar, node and list columns
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute("SPAWN t: Task { title = \"Test\" }")
+            .unwrap();
+
+        // WHEN
+        let result = session
+            .execute("MATCH t: Task RETURN t, t.title, t.title = \"Test\" AS ok, [t.title] AS l")
+            .unwrap();
+
+        // THEN analyzer types are reported; lists name their element type
+        let StatementResult::Query(query) = result else {
+            panic!("Expected query result");
+        };
+        assert_eq!(query.types, vec!["Task", "String", "Bool", "[String]"]);
+    }
+
     #[test]
     fn test_accept_and_execute_mutation() {
         // TEST: accept_and_execute_mutation
@@ -1236,9 +2538,8 @@ mod tests {
         // Session A's graph has the node
         assert!(session_a.graph().node_count() == 1);
 
-        // Note: In a true shared database, session B would now see the data
-        // Our current implementation uses separate graphs per session
-        // This test verifies the isolation mechanism works
+        // Note: Sessions created with Session::new own separate graphs;
+        // sessions opened on a shared Database see each other's commits
     }
 
     // ========== INSPECT Tests ==========
@@ -1298,4 +2599,582 @@ mod tests {
             _ => panic!("Expected query result"),
         }
     }
+
+    #[test]
+    fn test_map_attribute_path_access() {
+        // GIVEN a task with a structured meta attribute
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute(r#"SPAWN t: Task { title = "A", meta = {region: "eu", limits: {cpu: 2}} }"#)
+            .unwrap();
+        session
+            .execute(r#"SPAWN t: Task { title = "B", meta = {region: "us"} }"#)
+            .unwrap();
+
+        // WHEN filtering on a nested key and checking containment
+        let by_path = session
+            .execute(r#"MATCH t: Task WHERE t.meta.limits.cpu = 2 RETURN t.title, t.meta.region"#)
+            .unwrap();
+        let by_contains = session
+            .execute(r#"MATCH t: Task WHERE t.meta CONTAINS {region: "us"} RETURN t.title"#)
+            .unwrap();
+        let by_key = session
+            .execute(r#"MATCH t: Task WHERE "limits" IN t.meta RETURN t.title"#)
+            .unwrap();
+
+        // THEN
+        match by_path {
+            StatementResult::Query(q) => {
+                assert_eq!(q.rows.len(), 1);
+                assert_eq!(q.rows[0][0], Value::String("A".into()));
+                assert_eq!(q.rows[0][1], Value::String("eu".into()));
+            }
+            _ => panic!("Expected query result"),
+        }
+        match by_contains {
+            StatementResult::Query(q) => assert_eq!(q.rows, vec![vec![Value::from("B")]]),
+            _ => panic!("Expected query result"),
+        }
+        match by_key {
+            StatementResult::Query(q) => assert_eq!(q.rows, vec![vec![Value::from("A")]]),
+            _ => panic!("Expected query result"),
+        }
+    }
+
+    #[test]
+    fn test_content_addressed_dedup() {
+        // GIVEN a blob stored under the hash of its content
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute("SPAWN b: Blob { data = 0x68656c6c6f, hash = hash(0x68656c6c6f) }")
+            .unwrap();
+
+        // WHEN storing the same content again
+        let duplicate =
+            session.execute("SPAWN b: Blob { data = 0x68656c6c6f, hash = hash(0x68656c6c6f) }");
+
+        // THEN the unique hash rejects it, and the blob is addressable by its hash
+        assert!(duplicate.is_err());
+        let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let result = session
+            .execute(&format!(
+                "MATCH b: Blob WHERE b.hash = 0h{} RETURN b.data",
+                digest
+            ))
+            .unwrap();
+        match result {
+            StatementResult::Query(q) => {
+                assert_eq!(q.rows, vec![vec![Value::Bytes(b"hello".to_vec())]]);
+            }
+            _ => panic!("Expected query result"),
+        }
+    }
+
+    #[test]
+    fn test_nearest_vector_search() {
+        // GIVEN docs with 2-dimensional embeddings
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute(r#"SPAWN d: Doc { title = "east", embedding = [1.0, 0.0] }"#)
+            .unwrap();
+        session
+            .execute(r#"SPAWN d: Doc { title = "north", embedding = [0.0, 1.0] }"#)
+            .unwrap();
+        session
+            .execute(r#"SPAWN d: Doc { title = "northeast", embedding = [0.7, 0.7] }"#)
+            .unwrap();
+        let mut params = HashMap::new();
+        params.insert("q".to_string(), Value::Vector(vec![0.9, 0.1]));
+
+        // WHEN searching for the two nearest docs, with and without a filter
+        let nearest = session
+            .execute_with_params(
+                "MATCH d: Doc NEAREST 2 TO $q ON d.embedding RETURN d.title",
+                &params,
+            )
+            .unwrap();
+        let filtered = session
+            .execute_with_params(
+                r#"MATCH d: Doc WHERE d.title != "east" NEAREST 1 TO $q ON d.embedding RETURN d.title"#,
+                &params,
+            )
+            .unwrap();
+        let wrong_dim = session.execute(r#"SPAWN d: Doc { title = "bad", embedding = [1.0] }"#);
+
+        // THEN results come back closest first and dimensions are enforced
+        let titles = |result: StatementResult| -> Vec<Value> {
+            match result {
+                StatementResult::Query(q) => q.rows.into_iter().map(|r| r[0].clone()).collect(),
+                _ => panic!("Expected query result"),
+            }
+        };
+        assert_eq!(
+            titles(nearest),
+            vec![
+                Value::String("east".into()),
+                Value::String("northeast".into())
+            ]
+        );
+        assert_eq!(titles(filtered), vec![Value::String("northeast".into())]);
+        assert!(wrong_dim.is_err());
+
+        // AND a query vector of another dimension is an error, not an empty result
+        params.insert("q".to_string(), Value::Vector(vec![1.0, 0.0, 0.0]));
+        for query in [
+            "MATCH d: Doc NEAREST 2 TO $q ON d.embedding RETURN d.title",
+            r#"MATCH d: Doc WHERE d.title != "east" NEAREST 1 TO $q ON d.embedding RETURN d.title"#,
+        ] {
+            let err = session
+                .execute_with_params(query, &params)
+                .unwrap_err()
+                .to_string();
+            assert!(
+                err.contains("dimension 2 for Doc.embedding, got dimension 3"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_spatial_queries() {
+        // GIVEN places given as point() calls and as coordinate lists
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute(r#"SPAWN p: Place { name = "cafe", loc = point(1.0, 1.0) }"#)
+            .unwrap();
+        session
+            .execute(r#"SPAWN p: Place { name = "park", loc = [4, 5] }"#)
+            .unwrap();
+        session
+            .execute(r#"SPAWN p: Place { name = "tower", loc = point(1.0, 1.0, 300.0) }"#)
+            .unwrap();
+
+        // WHEN querying by radius, by box, and by distance
+        let near = session
+            .execute("MATCH p: Place WHERE within_radius(p.loc, point(0, 0), 2) RETURN p.name")
+            .unwrap();
+        let boxed = session
+            .execute("MATCH p: Place WHERE within_box(p.loc, [0, 0], [10, 10]) RETURN p.name ORDER BY p.name")
+            .unwrap();
+        let dist = session
+            .execute(r#"MATCH p: Place WHERE p.name = "park" RETURN distance(p.loc, point(1, 1))"#)
+            .unwrap();
+
+        // THEN 2D regions never match the 3D point
+        let column = |result: StatementResult| -> Vec<Value> {
+            match result {
+                StatementResult::Query(q) => q.rows.into_iter().map(|r| r[0].clone()).collect(),
+                _ => panic!("Expected query result"),
+            }
+        };
+        assert_eq!(column(near), vec![Value::String("cafe".into())]);
+        assert_eq!(
+            column(boxed),
+            vec![Value::String("cafe".into()), Value::String("park".into())]
+        );
+        assert_eq!(column(dist), vec![Value::Float(5.0)]);
+    }
+
+    #[test]
+    fn test_text_search_ranks_by_score() {
+        // GIVEN documents with searchable bodies
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        for (title, body) in [
+            ("intro", "A graph store written in Rust"),
+            ("exact", "Rust graph"),
+            ("other", "Python graph tools"),
+        ] {
+            session
+                .execute(&format!(
+                    r#"SPAWN d: Doc {{ title = "{}", body = "{}" }}"#,
+                    title, body
+                ))
+                .unwrap();
+        }
+
+        // WHEN searching and ordering by relevance
+        let result = session
+            .execute(
+                r#"MATCH d: Doc WHERE search(d.body, "rust GRAPH")
+                   RETURN d.title, search_score(d.body, "rust graph") AS score
+                   ORDER BY score DESC"#,
+            )
+            .unwrap();
+
+        // THEN only documents with every word match, the densest first
+        let StatementResult::Query(q) = result else {
+            panic!("Expected query result");
+        };
+        assert_eq!(
+            q.rows,
+            vec![
+                vec![Value::String("exact".into()), Value::Float(1.0)],
+                vec![Value::String("intro".into()), Value::Float(1.0 / 3.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_consume_watch_ack_nack() {
+        // GIVEN three pending jobs and a consume watch over them
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        for name in ["a", "b", "c"] {
+            session
+                .execute(&format!(
+                    r#"SPAWN j: Job {{ name = "{}", status = "pending" }}"#,
+                    name
+                ))
+                .unwrap();
+        }
+        let watch_id = match session
+            .execute(
+                r#"WATCH j: Job WHERE j.status = "pending"
+                   [mode: consume] [max_redeliveries: 2] [dead_letter: "failed_jobs"]
+                   RETURN j.name"#,
+            )
+            .unwrap()
+        {
+            StatementResult::Query(q) => q.rows[0][0].as_int().unwrap() as WatchId,
+            _ => panic!("Expected query result"),
+        };
+
+        // WHEN polling twice, the second poll only sees undelivered jobs
+        let now = current_time_millis();
+        let first = session.poll_watch_at(watch_id, 2, now).unwrap();
+        let second = session.poll_watch_at(watch_id, 10, now).unwrap();
+
+        // THEN
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].columns, vec!["j.name"]);
+        assert_eq!(first[0].attempt, 1);
+        assert_eq!(second.len(), 1);
+
+        // WHEN acknowledging a delivery
+        let ack = session
+            .execute(&format!(r#"ACK "{}""#, first[0].delivery_id))
+            .unwrap();
+
+        // THEN the job is deleted and the delivery ID is spent
+        match ack {
+            StatementResult::Mutation(m) => assert_eq!(m.nodes_deleted, 1),
+            _ => panic!("Expected mutation result"),
+        }
+        assert!(session.graph().get_node(first[0].node).is_none());
+        let again = session.execute(&format!(r#"ACK "{}""#, first[0].delivery_id));
+        assert!(matches!(again, Err(SessionError::InvalidDeliveryId { .. })));
+
+        // WHEN a delivery is NACKed, the job is redelivered with a new ID
+        let mut params = HashMap::new();
+        params.insert(
+            "id".to_string(),
+            Value::String(first[1].delivery_id.clone()),
+        );
+        session.execute_with_params("NACK $id", &params).unwrap();
+        let redelivered = session.poll_watch_at(watch_id, 10, now).unwrap();
+
+        // THEN
+        assert_eq!(redelivered.len(), 1);
+        assert_eq!(redelivered[0].node, first[1].node);
+        assert_eq!(redelivered[0].attempt, 2);
+        assert_ne!(redelivered[0].delivery_id, first[1].delivery_id);
+
+        // WHEN one job is rejected for good and another times out twice
+        let rejected = session
+            .execute(&format!(
+                r#"NACK "{}" [no_retry]"#,
+                redelivered[0].delivery_id
+            ))
+            .unwrap();
+        session.poll_watch_at(watch_id, 10, now + 60_000).unwrap();
+        session.poll_watch_at(watch_id, 10, now + 120_000).unwrap();
+
+        // THEN both end up as dead letters and no jobs are left
+        match rejected {
+            StatementResult::Mutation(m) => {
+                assert_eq!(m.nodes_created, 1);
+                assert_eq!(m.nodes_deleted, 1);
+            }
+            _ => panic!("Expected mutation result"),
+        }
+        let letters = match session
+            .execute(
+                "MATCH d: _DeadLetter RETURN d.failure_reason, d.delivery_attempts, d.original_match ORDER BY d.failure_reason DESC",
+            )
+            .unwrap()
+        {
+            StatementResult::Query(q) => q.rows,
+            _ => panic!("Expected query result"),
+        };
+        assert_eq!(letters.len(), 2);
+        assert_eq!(letters[0][0], Value::String("no_retry".into()));
+        assert_eq!(
+            letters[1][0],
+            Value::String("max_redeliveries_exceeded".into())
+        );
+        assert_eq!(letters[1][1], Value::Int(2));
+        assert_eq!(
+            letters[1][2],
+            Value::String(format!(
+                r#"Job {{ name: {}, status: "pending" }}"#,
+                second[0].row[0]
+            ))
+        );
+        assert!(session
+            .poll_watch_at(watch_id, 10, now + 120_000)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_import_reports_rejected_rows() {
+        // GIVEN a CSV file with one row of the wrong width
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        session.set_file_access(true);
+        let path =
+            std::env::temp_dir().join(format!("mew_session_import_{}.csv", std::process::id()));
+        std::fs::write(&path, "title\nWrite docs\nShip,now\nReview\n").unwrap();
+        let mut params = HashMap::new();
+        params.insert(
+            "file".to_string(),
+            Value::String(path.to_string_lossy().into_owned()),
+        );
+
+        // WHEN
+        let result = session
+            .execute_with_params("IMPORT Task FROM $file", &params)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // THEN the good rows are loaded and the bad one is reported
+        match result {
+            StatementResult::Mixed { mutations, queries } => {
+                assert_eq!(mutations.nodes_created, 2);
+                assert_eq!(queries.columns, vec!["line", "reason"]);
+                assert_eq!(
+                    queries.rows,
+                    vec![vec![
+                        Value::Int(3),
+                        Value::String("expected 1 fields, found 2".into())
+                    ]]
+                );
+            }
+            other => panic!("Expected mixed result, got {:?}", other),
+        }
+        match session.execute("MATCH t: Task RETURN count(t)").unwrap() {
+            StatementResult::Query(q) => assert_eq!(q.rows[0][0], Value::Int(2)),
+            _ => panic!("Expected query result"),
+        }
+    }
+
+    #[test]
+    fn test_walk_returns_shortest_paths_as_lists() {
+        // GIVEN two routes from a to c: 2 km through b, and 5 km direct
+        let registry = mew_compiler::compile(
+            "node City { name: String } edge road(from: City, to: City) { km: Float }",
+        )
+        .unwrap();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute_all(
+                r#"
+                SPAWN a: City { name = "a" }
+                SPAWN b: City { name = "b" }
+                SPAWN c: City { name = "c" }
+                LINK ab: road(a, b) { km = 1.0 }
+                LINK bc: road(b, c) { km = 1.0 }
+                LINK ac: road(a, c) { km = 5.0 }
+                "#,
+            )
+            .unwrap();
+        let query = |session: &mut Session, stmt: &str| match session.execute(stmt).unwrap() {
+            StatementResult::Query(q) => q,
+            other => panic!("Expected query result, got {:?}", other),
+        };
+        let id = |session: &Session, name: &str| match session.bindings[name] {
+            EntityId::Node(id) => Value::NodeRef(id),
+            EntityId::Edge(id) => Value::EdgeRef(id),
+        };
+        let (a, b, c) = (id(&session, "a"), id(&session, "b"), id(&session, "c"));
+        let (ab, bc, ac) = (id(&session, "ab"), id(&session, "bc"), id(&session, "ac"));
+
+        // WHEN
+        let fewest_hops = query(
+            &mut session,
+            "WALK FROM a FOLLOW road SHORTEST PATH TO c RETURN PATH AS route",
+        );
+        let cheapest = query(
+            &mut session,
+            "WALK FROM a FOLLOW road SHORTEST 2 PATHS TO c WEIGHT km RETURN PATH",
+        );
+        let costs = query(
+            &mut session,
+            "WALK FROM a FOLLOW road SHORTEST 2 PATHS TO c WEIGHT km RETURN cost AS km, depth",
+        );
+        let reached = query(&mut session, "WALK FROM a FOLLOW road RETURN PATH");
+
+        // THEN paths are lists of alternating nodes and edges
+        assert_eq!(fewest_hops.columns, vec!["route"]);
+        assert_eq!(
+            fewest_hops.rows,
+            vec![vec![Value::List(vec![a.clone(), ac.clone(), c.clone()])]]
+        );
+        assert_eq!(
+            cheapest.rows,
+            vec![
+                vec![Value::List(vec![
+                    a.clone(),
+                    ab.clone(),
+                    b.clone(),
+                    bc,
+                    c.clone()
+                ])],
+                vec![Value::List(vec![a.clone(), ac, c])],
+            ]
+        );
+        assert_eq!(costs.columns, vec!["km", "expr"]);
+        assert_eq!(
+            costs.rows,
+            vec![
+                vec![Value::Float(2.0), Value::Int(2)],
+                vec![Value::Float(5.0), Value::Int(1)]
+            ]
+        );
+
+        // AND a plain walk returns the path to each node it reaches
+        assert_eq!(reached.rows.len(), 2);
+        assert!(reached.rows.contains(&vec![Value::List(vec![a, ab, b])]));
+    }
+
+    #[test]
+    fn test_call_yields_and_writes_back_in_the_transaction() {
+        // GIVEN ada and bob follow cy, who follows ada
+        let registry = mew_compiler::compile(
+            "node Person { name: String, rank: Float? } edge follows(a: Person, b: Person)",
+        )
+        .unwrap();
+        let mut session = Session::new(1, &registry);
+        session
+            .execute_all(
+                r#"
+                SPAWN ada: Person { name = "ada" }
+                SPAWN bob: Person { name = "bob" }
+                SPAWN cy: Person { name = "cy" }
+                LINK follows(ada, cy)
+                LINK follows(bob, cy)
+                LINK follows(cy, ada)
+                "#,
+            )
+            .unwrap();
+        let cy = match session.bindings["cy"] {
+            EntityId::Node(id) => id,
+            EntityId::Edge(_) => panic!("Expected a node"),
+        };
+        let rank = |session: &Session| {
+            session
+                .graph()
+                .get_node(cy)
+                .unwrap()
+                .get_attr("rank")
+                .cloned()
+        };
+
+        // WHEN the scores are yielded
+        let result = session
+            .execute("CALL algo.pagerank(follows) YIELD node, score AS rank")
+            .unwrap();
+
+        // THEN there is a typed row per node and nothing is written
+        let StatementResult::Query(query) = result else {
+            panic!("Expected query result, got {:?}", result);
+        };
+        assert_eq!(query.columns, vec!["node", "rank"]);
+        assert_eq!(query.types[1], "Float");
+        assert_eq!(query.rows.len(), 3);
+        assert_eq!(rank(&session), None);
+
+        // WHEN they are written back in a transaction that rolls back
+        session.execute("BEGIN").unwrap();
+        let result = session
+            .execute("CALL algo.pagerank(follows, damping = 0.5) WRITE rank")
+            .unwrap();
+        let StatementResult::Mutation(summary) = result else {
+            panic!("Expected mutation result, got {:?}", result);
+        };
+        assert_eq!(summary.nodes_modified, 3);
+        assert!(matches!(rank(&session), Some(Value::Float(_))));
+        session.execute("ROLLBACK").unwrap();
+
+        // THEN the attributes are gone again
+        assert_eq!(rank(&session), None);
+
+        // AND writing to a missing attribute fails the analysis
+        let err = session
+            .execute("CALL algo.degree(follows) WRITE degree")
+            .unwrap_err();
+        assert_eq!(err.kind(), "analysis");
+    }
+
+    #[test]
+    fn test_file_statements_need_file_access() {
+        // GIVEN a session with default settings
+        let registry = test_registry();
+        let mut session = Session::new(1, &registry);
+        let path = std::env::temp_dir().join(format!("mew_session_denied_{}", std::process::id()));
+        let mut params = HashMap::new();
+        params.insert(
+            "file".to_string(),
+            Value::String(path.to_string_lossy().into_owned()),
+        );
+
+        // WHEN
+        let import = session.execute_with_params("IMPORT Task FROM $file", &params);
+        let dump = session.execute_with_params("DUMP TO $file", &params);
+
+        // THEN neither touches the filesystem
+        assert_eq!(import.unwrap_err().kind(), "file_access_disabled");
+        assert_eq!(dump.unwrap_err().kind(), "file_access_disabled");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_dump_writes_a_restorable_script() {
+        // GIVEN a database compiled from source
+        let registry = mew_compiler::compile("node Task { title: String }").unwrap();
+        let mut session = Session::new(1, &registry);
+        session.set_file_access(true);
+        session.execute(r#"SPAWN Task { title = "Ship" }"#).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("mew_session_dump_{}.mew", std::process::id()));
+        let mut params = HashMap::new();
+        params.insert(
+            "file".to_string(),
+            Value::String(path.to_string_lossy().into_owned()),
+        );
+
+        // WHEN
+        let result = session
+            .execute_with_params("DUMP TO $file", &params)
+            .unwrap();
+        let script = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // THEN the counts are returned and the file restores the task
+        match result {
+            StatementResult::Query(q) => {
+                assert_eq!(q.columns, vec!["path", "nodes", "edges"]);
+                assert_eq!(q.rows[0][1..], [Value::Int(1), Value::Int(0)]);
+            }
+            other => panic!("Expected query result, got {:?}", other),
+        }
+        let (_, graph) = crate::dump::restore(&script).unwrap();
+        assert_eq!(graph.node_count(), 1);
+    }
 }
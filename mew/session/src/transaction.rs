@@ -1,21 +1,37 @@
 //! Transaction handling for MEW sessions.
 //!
 //! This module contains the logic for handling transaction control statements
-//! (BEGIN, COMMIT, ROLLBACK).
+//! (BEGIN, COMMIT, ROLLBACK, SAVEPOINT, ROLLBACK TO, RELEASE).
 
 use crate::error::{SessionError, SessionResult};
 use crate::result::{StatementResult, TransactionResult};
-use mew_core::{EdgeId, NodeId};
+use mew_constraint::Touched;
+use mew_graph::Graph;
 use mew_parser::TxnStmt;
+use mew_transaction::UndoLog;
+use std::sync::Arc;
 
 /// Transaction state tracker.
 pub struct TransactionState {
     /// Whether a transaction is active.
     pub in_transaction: bool,
-    /// Nodes created during this transaction (for rollback).
-    pub created_nodes: Vec<NodeId>,
-    /// Edges created during this transaction (for rollback).
-    pub created_edges: Vec<EdgeId>,
+    /// Undo log of the open transaction (explicit or per-statement).
+    pub undo: Option<UndoLog>,
+    /// Savepoints of a transaction on a shared database, oldest first.
+    pub snapshots: Vec<Snapshot>,
+    /// What the open transaction wrote, for the declared constraints
+    /// checked at COMMIT.
+    pub touched: Touched,
+}
+
+/// The working graph of a shared-database transaction at a savepoint.
+pub struct Snapshot {
+    /// Savepoint name.
+    pub name: String,
+    /// Database version the graph is based on (pinned by the snapshot).
+    pub version: u64,
+    /// The working graph; copy-on-write, so taking it is cheap.
+    pub graph: Arc<Graph>,
 }
 
 impl TransactionState {
@@ -23,30 +39,11 @@ impl TransactionState {
     pub fn new() -> Self {
         Self {
             in_transaction: false,
-            created_nodes: Vec::new(),
-            created_edges: Vec::new(),
-        }
-    }
-
-    /// Track a created node for potential rollback.
-    pub fn track_created_node(&mut self, id: NodeId) {
-        if self.in_transaction {
-            self.created_nodes.push(id);
-        }
-    }
-
-    /// Track a created edge for potential rollback.
-    pub fn track_created_edge(&mut self, id: EdgeId) {
-        if self.in_transaction {
-            self.created_edges.push(id);
+            undo: None,
+            snapshots: Vec::new(),
+            touched: Touched::default(),
         }
     }
-
-    /// Clear tracked changes (for commit or after rollback).
-    pub fn clear_tracked(&mut self) {
-        self.created_nodes.clear();
-        self.created_edges.clear();
-    }
 }
 
 impl Default for TransactionState {
@@ -65,6 +62,7 @@ pub fn execute_txn(state: &mut TransactionState, stmt: &TxnStmt) -> SessionResul
                 ));
             }
             state.in_transaction = true;
+            state.touched = Touched::default();
             Ok(StatementResult::Transaction(TransactionResult::Begun))
         }
 
@@ -75,7 +73,6 @@ pub fn execute_txn(state: &mut TransactionState, stmt: &TxnStmt) -> SessionResul
                 ));
             }
             state.in_transaction = false;
-            state.clear_tracked(); // Clear tracked entities on commit
             Ok(StatementResult::Transaction(TransactionResult::Committed))
         }
 
@@ -85,10 +82,42 @@ pub fn execute_txn(state: &mut TransactionState, stmt: &TxnStmt) -> SessionResul
                     mew_transaction::TransactionError::NoActiveTransaction,
                 ));
             }
-            // Note: Caller must handle the actual rollback using state.created_nodes/created_edges
+            // Note: Caller must undo the graph writes using state.undo
             state.in_transaction = false;
             Ok(StatementResult::Transaction(TransactionResult::RolledBack))
         }
+
+        // Note: Caller must create, undo to or drop the savepoint itself
+        TxnStmt::Savepoint(name) => {
+            require_transaction(state)?;
+            Ok(StatementResult::Transaction(
+                TransactionResult::SavepointCreated { name: name.clone() },
+            ))
+        }
+
+        TxnStmt::RollbackTo(name) => {
+            require_transaction(state)?;
+            Ok(StatementResult::Transaction(
+                TransactionResult::RolledBackTo { name: name.clone() },
+            ))
+        }
+
+        TxnStmt::Release(name) => {
+            require_transaction(state)?;
+            Ok(StatementResult::Transaction(
+                TransactionResult::SavepointReleased { name: name.clone() },
+            ))
+        }
+    }
+}
+
+fn require_transaction(state: &TransactionState) -> SessionResult<()> {
+    if state.in_transaction {
+        Ok(())
+    } else {
+        Err(SessionError::TransactionError(
+            mew_transaction::TransactionError::NoActiveTransaction,
+        ))
     }
 }
 
@@ -162,6 +191,26 @@ mod tests {
         assert!(!state.in_transaction);
     }
 
+    #[test]
+    fn test_savepoint_requires_transaction() {
+        // GIVEN
+        let mut state = TransactionState::new();
+
+        // WHEN
+        let outside = execute_txn(&mut state, &TxnStmt::Savepoint("sp".to_string()));
+        state.in_transaction = true;
+        let inside = execute_txn(&mut state, &TxnStmt::Savepoint("sp".to_string()));
+
+        // THEN
+        assert!(outside.is_err());
+        assert!(matches!(
+            inside,
+            Ok(StatementResult::Transaction(
+                TransactionResult::SavepointCreated { .. }
+            ))
+        ));
+    }
+
     #[test]
     fn test_rollback_without_transaction() {
         // GIVEN
@@ -0,0 +1,454 @@
+//! Consume-mode watches.
+//!
+//! A consume watch turns the nodes matched by a query into a work queue.
+//! Each poll hands out matches that are not already in flight together with
+//! a delivery ID. The consumer then settles the delivery:
+//! - ACK deletes the message node
+//! - NACK returns it to the pool, or dead-letters it once the redelivery
+//!   budget is spent (or immediately with `[no_retry]`)
+//! - an unsettled delivery past its `ack_timeout` counts as a NACK
+//!
+//! Sessions of one database may consume the same messages; which messages
+//! are handed out is shared between them, so no two consumers hold the same
+//! message at once. A session that ends returns its unsettled messages.
+//!
+//! Everything here is bookkeeping; the messages themselves are ordinary
+//! graph nodes, and dead letters are `_DeadLetter` nodes.
+
+use mew_core::{NodeId, Value};
+use mew_parser::MatchStmt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::wire::serde_values;
+
+/// Watch ID type.
+pub type WatchId = u64;
+
+/// Default time a delivery may stay unacknowledged (30s).
+pub const DEFAULT_ACK_TIMEOUT_MS: i64 = 30_000;
+
+/// Default number of failed deliveries before a message is dead-lettered.
+pub const DEFAULT_MAX_REDELIVERIES: u32 = 3;
+
+/// A message handed to a consumer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchEvent {
+    /// The watch that produced this delivery.
+    pub watch_id: WatchId,
+    /// ID to pass to ACK / NACK.
+    pub delivery_id: String,
+    /// The message node.
+    #[serde(with = "serde_values::node")]
+    pub node: NodeId,
+    /// Column names of the watch's RETURN clause.
+    pub columns: Vec<String>,
+    /// Projected values for the message.
+    #[serde(with = "serde_values::list")]
+    pub row: Vec<Value>,
+    /// Delivery attempt, starting at 1.
+    pub attempt: u32,
+}
+
+/// A registered consume-mode watch.
+#[derive(Debug, Clone)]
+pub(crate) struct ConsumeWatch {
+    /// Query producing messages; its first column is the message node.
+    pub query: MatchStmt,
+    pub ack_timeout_ms: i64,
+    pub max_redeliveries: u32,
+    /// Dead letter destination; without one, failed messages are deleted.
+    pub dead_letter: Option<String>,
+    /// Parameter values captured when the watch was registered.
+    pub params: HashMap<String, Value>,
+}
+
+/// An unsettled delivery.
+#[derive(Debug, Clone)]
+struct Delivery {
+    watch_id: WatchId,
+    node: NodeId,
+    deadline: i64,
+}
+
+/// What to do with a message once its delivery is settled.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Settlement {
+    /// Processing succeeded: delete the message.
+    Delete(NodeId),
+    /// Processing failed: the message goes back to the pool.
+    Release(NodeId),
+    /// Processing failed for good: record a dead letter (if a destination
+    /// is configured) and delete the message.
+    DeadLetter {
+        node: NodeId,
+        reason: &'static str,
+        attempts: u32,
+        destination: Option<String>,
+    },
+}
+
+/// Messages handed out by the consumers of one graph. Sessions attached to
+/// the same database share it, so two consumers never hold the same message,
+/// a message whose `ack_timeout` passed is reclaimed by whichever session
+/// polls next, and its failed attempts count against one redelivery budget.
+#[derive(Debug, Default)]
+pub(crate) struct Leases {
+    /// Message -> delivery holding it.
+    held: HashMap<NodeId, Lease>,
+    /// Failed delivery attempts per message.
+    failures: HashMap<NodeId, u32>,
+}
+
+/// The delivery holding a message, with what any session needs to settle
+/// it once it times out.
+#[derive(Debug, Clone)]
+struct Lease {
+    delivery_id: String,
+    deadline: i64,
+    max_redeliveries: u32,
+    dead_letter: Option<String>,
+}
+
+impl Leases {
+    /// Count a failed delivery of `node` and decide what happens to it.
+    fn fail(&mut self, node: NodeId, lease: &Lease, no_retry: bool) -> Settlement {
+        let failures = self.failures.entry(node).or_insert(0);
+        *failures += 1;
+        let attempts = *failures;
+
+        let reason = if no_retry {
+            "no_retry"
+        } else if attempts >= lease.max_redeliveries {
+            "max_redeliveries_exceeded"
+        } else {
+            return Settlement::Release(node);
+        };
+
+        self.failures.remove(&node);
+        Settlement::DeadLetter {
+            node,
+            reason,
+            attempts,
+            destination: lease.dead_letter.clone(),
+        }
+    }
+}
+
+/// Per-session consume-watch bookkeeping.
+#[derive(Debug)]
+pub(crate) struct WatchState {
+    next_watch_id: WatchId,
+    next_delivery: u64,
+    watches: BTreeMap<WatchId, ConsumeWatch>,
+    in_flight: BTreeMap<String, Delivery>,
+    leases: Arc<Mutex<Leases>>,
+}
+
+impl WatchState {
+    pub fn new() -> Self {
+        Self::shared(Arc::default())
+    }
+
+    /// Bookkeeping for a session whose messages are also consumed by other
+    /// sessions holding the same `leases`.
+    pub fn shared(leases: Arc<Mutex<Leases>>) -> Self {
+        Self {
+            next_watch_id: 1,
+            next_delivery: 1,
+            watches: BTreeMap::new(),
+            in_flight: BTreeMap::new(),
+            leases,
+        }
+    }
+
+    /// Register a watch and return its ID.
+    pub fn register(&mut self, watch: ConsumeWatch) -> WatchId {
+        let id = self.next_watch_id;
+        self.next_watch_id += 1;
+        self.watches.insert(id, watch);
+        id
+    }
+
+    /// Look up a registered watch.
+    pub fn get(&self, id: WatchId) -> Option<&ConsumeWatch> {
+        self.watches.get(&id)
+    }
+
+    /// Remove a watch. Its in-flight deliveries are dropped, which returns
+    /// their messages to the pool.
+    pub fn cancel(&mut self, id: WatchId) -> bool {
+        let dropped: Vec<String> = self
+            .in_flight
+            .iter()
+            .filter(|(_, d)| d.watch_id == id)
+            .map(|(delivery_id, _)| delivery_id.clone())
+            .collect();
+        for delivery_id in dropped {
+            self.settle(&delivery_id);
+        }
+        self.watches.remove(&id).is_some()
+    }
+
+    /// Hand out a message and return its delivery ID and attempt number.
+    /// None if the message is already handed out, by this session or any
+    /// other sharing its leases.
+    pub fn deliver(
+        &mut self,
+        session: u64,
+        watch_id: WatchId,
+        node: NodeId,
+        now: i64,
+    ) -> Option<(String, u32)> {
+        let watch = self.watches.get(&watch_id);
+        let deadline =
+            now.saturating_add(watch.map_or(DEFAULT_ACK_TIMEOUT_MS, |w| w.ack_timeout_ms));
+        let lease = Lease {
+            delivery_id: format!("delivery_{}_{}", session, self.next_delivery),
+            deadline,
+            max_redeliveries: watch.map_or(DEFAULT_MAX_REDELIVERIES, |w| w.max_redeliveries),
+            dead_letter: watch.and_then(|w| w.dead_letter.clone()),
+        };
+        let delivery_id = lease.delivery_id.clone();
+        let attempt = {
+            let mut leases = lock(&self.leases);
+            if leases.held.contains_key(&node) {
+                return None;
+            }
+            leases.held.insert(node, lease);
+            leases.failures.get(&node).copied().unwrap_or(0) + 1
+        };
+        self.next_delivery += 1;
+        self.in_flight.insert(
+            delivery_id.clone(),
+            Delivery {
+                watch_id,
+                node,
+                deadline,
+            },
+        );
+        Some((delivery_id, attempt))
+    }
+
+    /// Settle a delivery as processed. None if the ID is unknown or the
+    /// delivery timed out and was reclaimed.
+    pub fn ack(&mut self, delivery_id: &str) -> Option<Settlement> {
+        let (node, _) = self.settle(delivery_id)?;
+        lock(&self.leases).failures.remove(&node);
+        Some(Settlement::Delete(node))
+    }
+
+    /// Settle a delivery as failed. None if the ID is unknown or the
+    /// delivery timed out and was reclaimed.
+    pub fn nack(&mut self, delivery_id: &str, no_retry: bool) -> Option<Settlement> {
+        let (node, lease) = self.settle(delivery_id)?;
+        Some(lock(&self.leases).fail(node, &lease, no_retry))
+    }
+
+    /// Treat every delivery whose deadline has passed as NACKed, whichever
+    /// session holds it.
+    pub fn expire(&mut self, now: i64) -> Vec<Settlement> {
+        self.in_flight.retain(|_, d| now < d.deadline);
+        let mut leases = lock(&self.leases);
+        let mut expired: Vec<(NodeId, Lease)> = leases
+            .held
+            .iter()
+            .filter(|(_, lease)| now >= lease.deadline)
+            .map(|(node, lease)| (*node, lease.clone()))
+            .collect();
+        expired.sort_by_key(|(node, _)| *node);
+        expired
+            .into_iter()
+            .map(|(node, lease)| {
+                leases.held.remove(&node);
+                leases.fail(node, &lease, false)
+            })
+            .collect()
+    }
+
+    /// Drop all bookkeeping for a message that no longer exists.
+    pub fn forget(&mut self, node: NodeId) {
+        let dropped: Vec<String> = self
+            .in_flight
+            .iter()
+            .filter(|(_, d)| d.node == node)
+            .map(|(delivery_id, _)| delivery_id.clone())
+            .collect();
+        for delivery_id in dropped {
+            self.settle(&delivery_id);
+        }
+        lock(&self.leases).failures.remove(&node);
+    }
+
+    /// Remove an unsettled delivery and give up its lease. None if the
+    /// delivery is unknown or its lease was reclaimed after timing out.
+    fn settle(&mut self, delivery_id: &str) -> Option<(NodeId, Lease)> {
+        let delivery = self.in_flight.remove(delivery_id)?;
+        let mut leases = lock(&self.leases);
+        match leases.held.get(&delivery.node) {
+            Some(lease) if lease.delivery_id == delivery_id => leases
+                .held
+                .remove(&delivery.node)
+                .map(|l| (delivery.node, l)),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for WatchState {
+    /// A session that goes away returns its unsettled messages to the pool.
+    fn drop(&mut self) {
+        let mut leases = lock(&self.leases);
+        for (delivery_id, delivery) in &self.in_flight {
+            if leases
+                .held
+                .get(&delivery.node)
+                .is_some_and(|lease| &lease.delivery_id == delivery_id)
+            {
+                leases.held.remove(&delivery.node);
+            }
+        }
+    }
+}
+
+fn lock(leases: &Mutex<Leases>) -> MutexGuard<'_, Leases> {
+    leases.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mew_parser::parse_stmt;
+
+    fn consume_watch(max_redeliveries: u32, dead_letter: Option<&str>) -> ConsumeWatch {
+        let query = match parse_stmt("MATCH j: Job RETURN j").unwrap() {
+            mew_parser::Stmt::Match(m) => m,
+            _ => unreachable!(),
+        };
+        ConsumeWatch {
+            query,
+            ack_timeout_ms: 1_000,
+            max_redeliveries,
+            dead_letter: dead_letter.map(String::from),
+            params: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_ack_settles_delivery_once() {
+        // GIVEN
+        let mut state = WatchState::new();
+        let watch = state.register(consume_watch(3, None));
+        let node = NodeId::new(7);
+        let (delivery, attempt) = state.deliver(1, watch, node, 0).unwrap();
+
+        // WHEN
+        let first = state.ack(&delivery);
+        let second = state.ack(&delivery);
+
+        // THEN
+        assert_eq!(attempt, 1);
+        assert_eq!(first, Some(Settlement::Delete(node)));
+        assert_eq!(second, None);
+        assert!(state.deliver(1, watch, node, 0).is_some());
+    }
+
+    #[test]
+    fn test_timeout_redelivers_then_dead_letters() {
+        // GIVEN
+        let mut state = WatchState::new();
+        let watch = state.register(consume_watch(2, Some("failed_jobs")));
+        let node = NodeId::new(1);
+        state.deliver(1, watch, node, 0).unwrap();
+
+        // WHEN - the first delivery times out
+        let before_deadline = state.expire(999);
+        let first = state.expire(1_000);
+
+        // THEN
+        assert!(before_deadline.is_empty());
+        assert_eq!(first, vec![Settlement::Release(node)]);
+
+        // WHEN - the redelivery times out as well
+        let (_, attempt) = state.deliver(1, watch, node, 1_000).unwrap();
+        let second = state.expire(2_000);
+
+        // THEN
+        assert_eq!(attempt, 2);
+        assert_eq!(
+            second,
+            vec![Settlement::DeadLetter {
+                node,
+                reason: "max_redeliveries_exceeded",
+                attempts: 2,
+                destination: Some("failed_jobs".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_nack_no_retry_dead_letters_immediately() {
+        // GIVEN
+        let mut state = WatchState::new();
+        let watch = state.register(consume_watch(3, None));
+        let node = NodeId::new(2);
+        let (delivery, _) = state.deliver(1, watch, node, 0).unwrap();
+
+        // WHEN
+        let settlement = state.nack(&delivery, true);
+
+        // THEN
+        assert_eq!(
+            settlement,
+            Some(Settlement::DeadLetter {
+                node,
+                reason: "no_retry",
+                attempts: 1,
+                destination: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_shared_leases_hand_out_each_message_once_and_reclaim_timeouts() {
+        // GIVEN two sessions consuming from the same leases
+        let leases = Arc::new(Mutex::new(Leases::default()));
+        let mut first = WatchState::shared(Arc::clone(&leases));
+        let mut second = WatchState::shared(Arc::clone(&leases));
+        let first_watch = first.register(consume_watch(3, None));
+        let second_watch = second.register(consume_watch(3, None));
+        let node = NodeId::new(4);
+
+        // WHEN both try to take the same message
+        let (delivery, _) = first.deliver(1, first_watch, node, 0).unwrap();
+        let taken = second.deliver(2, second_watch, node, 0);
+
+        // THEN only the first gets it
+        assert_eq!(taken, None);
+
+        // WHEN the first NACKs it
+        first.nack(&delivery, false);
+
+        // THEN the second gets it, as a second attempt
+        let (stale, attempt) = second.deliver(2, second_watch, node, 0).unwrap();
+        assert_eq!(attempt, 2);
+
+        // WHEN the second session never settles it and its timeout passes
+        let reclaimed = first.expire(1_000);
+
+        // THEN the first reclaims it as a failed attempt, and the second
+        // can no longer settle it
+        assert_eq!(reclaimed, vec![Settlement::Release(node)]);
+        let (_, attempt) = first.deliver(1, first_watch, node, 1_000).unwrap();
+        assert_eq!(attempt, 3);
+        assert_eq!(second.ack(&stale), None);
+        assert_eq!(second.deliver(2, second_watch, node, 1_000), None);
+
+        // WHEN the first session goes away
+        drop(first);
+
+        // THEN the message is back in the pool
+        assert!(second.deliver(2, second_watch, node, 1_000).is_some());
+    }
+}
@@ -0,0 +1,436 @@
+//! Canonical JSON wire encoding of results, values and errors.
+//!
+//! Every message is an object carrying the encoding [`WIRE_VERSION`] and
+//! exactly one of `result`, `error` or `event`:
+//!
+//! ```text
+//! {"version": 1, "result": {"type": "query", "columns": ["p"], "types": ["Person"], "rows": [[{"_type": "node", "_id": 3}]]}}
+//! {"version": 1, "result": {"type": "mutation", "nodes_created": 1, ..., "returned": []}}
+//! {"version": 1, "result": {"type": "transaction", "status": "savepoint_created", "savepoint": "a"}}
+//! {"version": 1, "error": {"kind": "parse", "message": "..."}}
+//! ```
+//!
+//! Null, booleans, integers, floats, strings and lists map to their JSON
+//! counterparts; floats always keep a fraction or exponent, so `1.0` stays
+//! distinct from `1`. Maps are objects. Everything else is a tagged object:
+//! `{"_type": "node", "_id": 3}`, `{"_type": "edge", "_id": 4}`,
+//! `{"_type": "timestamp", "value": 1700000000000}`,
+//! `{"_type": "duration", "value": 30000}`, `{"_type": "bytes", "value": "00ff"}`,
+//! `{"_type": "hash", "value": "..."}`, `{"_type": "vector", "value": [0.5]}`,
+//! `{"_type": "point", "value": [1.0, 2.0]}`, non-finite floats as
+//! `{"_type": "float", "value": "NaN"}`, and maps that have a `_type` key
+//! of their own as `{"_type": "map", "value": {...}}`.
+
+use mew_core::{from_hex, to_hex, EdgeId, NodeId, Point, Value};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Map, Value as Json};
+use std::collections::BTreeMap;
+
+use crate::error::SessionError;
+use crate::result::StatementResult;
+use crate::watch::WatchEvent;
+
+/// Version of the wire encoding. Bumped on incompatible changes.
+pub const WIRE_VERSION: u32 = 1;
+
+/// Key marking a tagged value object.
+const TAG: &str = "_type";
+
+/// One encoded message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WireMessage {
+    /// Encoding version; always [`WIRE_VERSION`] for messages built here.
+    pub version: u32,
+    #[serde(flatten)]
+    pub body: WireBody,
+}
+
+/// Payload of a [`WireMessage`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireBody {
+    /// A statement succeeded.
+    Result(StatementResult),
+    /// A statement or request failed.
+    Error(WireError),
+    /// A watch delivery.
+    Event(WatchEvent),
+}
+
+/// An encoded error.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireError {
+    /// Machine-readable kind (`parse`, `analysis`, `conflict`, ...).
+    pub kind: String,
+    /// Human-readable message.
+    pub message: String,
+}
+
+impl WireError {
+    pub fn new(kind: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_value(message: impl Into<String>) -> Self {
+        Self::new("invalid_value", message)
+    }
+}
+
+impl std::fmt::Display for WireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<&SessionError> for WireError {
+    fn from(err: &SessionError) -> Self {
+        Self::new(err.kind(), err.to_string())
+    }
+}
+
+impl WireMessage {
+    fn new(body: WireBody) -> Self {
+        Self {
+            version: WIRE_VERSION,
+            body,
+        }
+    }
+
+    /// Encode as a JSON value.
+    pub fn to_json(&self) -> Json {
+        serde_json::to_value(self).expect("wire messages always encode")
+    }
+
+    /// Decode a message, rejecting other encoding versions.
+    pub fn from_json(json: Json) -> Result<Self, WireError> {
+        let message: Self = serde_json::from_value(json)
+            .map_err(|e| WireError::new("invalid_message", e.to_string()))?;
+        if message.version != WIRE_VERSION {
+            return Err(WireError::new(
+                "unsupported_version",
+                format!(
+                    "wire version {} is not supported (expected {})",
+                    message.version, WIRE_VERSION
+                ),
+            ));
+        }
+        Ok(message)
+    }
+}
+
+impl From<StatementResult> for WireMessage {
+    fn from(result: StatementResult) -> Self {
+        Self::new(WireBody::Result(result))
+    }
+}
+
+impl From<WireError> for WireMessage {
+    fn from(error: WireError) -> Self {
+        Self::new(WireBody::Error(error))
+    }
+}
+
+impl From<&SessionError> for WireMessage {
+    fn from(error: &SessionError) -> Self {
+        Self::new(WireBody::Error(error.into()))
+    }
+}
+
+impl From<WatchEvent> for WireMessage {
+    fn from(event: WatchEvent) -> Self {
+        Self::new(WireBody::Event(event))
+    }
+}
+
+/// Encode a value.
+pub fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::Null => Json::Null,
+        Value::Bool(b) => Json::Bool(*b),
+        Value::Int(i) => json!(*i),
+        Value::Float(f) if f.is_finite() => json!(*f),
+        Value::Float(f) => {
+            let repr = if f.is_nan() {
+                "NaN"
+            } else if *f > 0.0 {
+                "Infinity"
+            } else {
+                "-Infinity"
+            };
+            json!({ TAG: "float", "value": repr })
+        }
+        Value::String(s) => Json::String(s.clone()),
+        Value::List(items) => Json::Array(items.iter().map(value_to_json).collect()),
+        Value::Map(entries) => {
+            let object: Map<String, Json> = entries
+                .iter()
+                .map(|(k, v)| (k.clone(), value_to_json(v)))
+                .collect();
+            if entries.contains_key(TAG) {
+                json!({ TAG: "map", "value": object })
+            } else {
+                Json::Object(object)
+            }
+        }
+        Value::NodeRef(id) => json!({ TAG: "node", "_id": id.raw() }),
+        Value::EdgeRef(id) => json!({ TAG: "edge", "_id": id.raw() }),
+        Value::Timestamp(ts) => json!({ TAG: "timestamp", "value": *ts }),
+        Value::Duration(d) => json!({ TAG: "duration", "value": *d }),
+        Value::Bytes(b) => json!({ TAG: "bytes", "value": to_hex(b) }),
+        Value::Hash(h) => json!({ TAG: "hash", "value": to_hex(h) }),
+        Value::Vector(v) => json!({ TAG: "vector", "value": v }),
+        Value::Point(p) => json!({ TAG: "point", "value": &p.coords()[..p.dims()] }),
+    }
+}
+
+/// Decode a value produced by [`value_to_json`].
+///
+/// JSON written by hand decodes too: integral numbers become `Int`, other
+/// numbers `Float`, and objects without a `_type` tag become maps.
+pub fn json_to_value(json: &Json) -> Result<Value, WireError> {
+    Ok(match json {
+        Json::Null => Value::Null,
+        Json::Bool(b) => Value::Bool(*b),
+        Json::Number(n) => match n.as_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Json::String(s) => Value::String(s.clone()),
+        Json::Array(items) => {
+            Value::List(items.iter().map(json_to_value).collect::<Result<_, _>>()?)
+        }
+        Json::Object(fields) => match fields.get(TAG).and_then(Json::as_str) {
+            Some(tag) => tagged_to_value(tag, fields)?,
+            None => object_to_map(fields)?,
+        },
+    })
+}
+
+fn object_to_map(fields: &Map<String, Json>) -> Result<Value, WireError> {
+    Ok(Value::Map(
+        fields
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), json_to_value(v)?)))
+            .collect::<Result<BTreeMap<_, _>, WireError>>()?,
+    ))
+}
+
+fn tagged_to_value(tag: &str, fields: &Map<String, Json>) -> Result<Value, WireError> {
+    let invalid = || WireError::invalid_value(format!("invalid {} value", tag));
+    let value = || fields.get("value").ok_or_else(invalid);
+    let int = |key: &str| fields.get(key).and_then(Json::as_i64).ok_or_else(invalid);
+    let hex = || value()?.as_str().and_then(from_hex).ok_or_else(invalid);
+    let floats = || -> Result<Vec<f64>, WireError> {
+        value()?
+            .as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|v| v.as_f64().ok_or_else(invalid))
+            .collect()
+    };
+
+    match tag {
+        "node" => Ok(Value::NodeRef(NodeId::new(int("_id")? as u64))),
+        "edge" => Ok(Value::EdgeRef(EdgeId::new(int("_id")? as u64))),
+        "timestamp" => Ok(Value::Timestamp(int("value")?)),
+        "duration" => Ok(Value::Duration(int("value")?)),
+        "bytes" => Ok(Value::Bytes(hex()?)),
+        "hash" => hex()?.try_into().map(Value::Hash).map_err(|_| invalid()),
+        "vector" => Ok(Value::Vector(
+            floats()?.into_iter().map(|f| f as f32).collect(),
+        )),
+        "point" => match floats()?[..] {
+            [x, y] => Ok(Value::Point(Point::new_2d(x, y))),
+            [x, y, z] => Ok(Value::Point(Point::new_3d(x, y, z))),
+            _ => Err(invalid()),
+        },
+        "float" => match value()?.as_str() {
+            Some("NaN") => Ok(Value::Float(f64::NAN)),
+            Some("Infinity") => Ok(Value::Float(f64::INFINITY)),
+            Some("-Infinity") => Ok(Value::Float(f64::NEG_INFINITY)),
+            _ => Err(invalid()),
+        },
+        "map" => object_to_map(value()?.as_object().ok_or_else(invalid)?),
+        _ => Err(WireError::invalid_value(format!(
+            "unknown value type '{}'",
+            tag
+        ))),
+    }
+}
+
+/// Serde adapters for value-carrying fields of the result types.
+pub(crate) mod serde_values {
+    use super::*;
+    use serde::de::Error;
+
+    fn decode<E: Error>(json: &Json) -> Result<Value, E> {
+        json_to_value(json).map_err(E::custom)
+    }
+
+    /// `Vec<Value>`.
+    pub mod list {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(values: &[Value], s: S) -> Result<S::Ok, S::Error> {
+            s.collect_seq(values.iter().map(value_to_json))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Value>, D::Error> {
+            Vec::<Json>::deserialize(d)?.iter().map(decode).collect()
+        }
+    }
+
+    /// `Vec<Vec<Value>>`.
+    pub mod rows {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(rows: &[Vec<Value>], s: S) -> Result<S::Ok, S::Error> {
+            s.collect_seq(
+                rows.iter()
+                    .map(|row| row.iter().map(value_to_json).collect::<Vec<_>>()),
+            )
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Vec<Value>>, D::Error> {
+            Vec::<Vec<Json>>::deserialize(d)?
+                .iter()
+                .map(|row| row.iter().map(decode).collect())
+                .collect()
+        }
+    }
+
+    /// `NodeId`, encoded as a node reference.
+    pub mod node {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(id: &NodeId, s: S) -> Result<S::Ok, S::Error> {
+            value_to_json(&Value::NodeRef(*id)).serialize(s)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<NodeId, D::Error> {
+            match decode(&Json::deserialize(d)?)? {
+                Value::NodeRef(id) => Ok(id),
+                other => Err(D::Error::custom(format!(
+                    "expected a node reference, got {}",
+                    other.type_name()
+                ))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::{MutationSummary, QueryResult, TransactionResult};
+
+    #[test]
+    fn test_values_round_trip() {
+        // GIVEN values of every kind, including look-alikes
+        let values = vec![
+            Value::Null,
+            Value::Bool(true),
+            Value::Int(1),
+            Value::Float(1.0),
+            Value::Float(f64::INFINITY),
+            Value::String("s".into()),
+            Value::Timestamp(1_700_000_000_000),
+            Value::Duration(30_000),
+            Value::Bytes(vec![0, 255]),
+            Value::Hash([7; 32]),
+            Value::Vector(vec![0.5, 1.0]),
+            Value::Point(Point::new_3d(1.0, 2.0, 3.0)),
+            Value::NodeRef(NodeId::new(3)),
+            Value::EdgeRef(EdgeId::new(3)),
+            Value::List(vec![Value::Int(1), Value::Timestamp(1)]),
+            Value::Map(BTreeMap::from([("_type".to_string(), Value::from("node"))])),
+        ];
+
+        // WHEN / THEN every value survives a trip through JSON text
+        for value in values {
+            let text = value_to_json(&value).to_string();
+            let decoded = json_to_value(&serde_json::from_str(&text).unwrap()).unwrap();
+            assert_eq!(decoded, value, "via {}", text);
+        }
+        let nan = json_to_value(&value_to_json(&Value::Float(f64::NAN))).unwrap();
+        assert!(matches!(nan, Value::Float(f) if f.is_nan()));
+    }
+
+    #[test]
+    fn test_query_result_encoding() {
+        // GIVEN a typed query result
+        let result = StatementResult::Query(QueryResult::new(
+            vec!["p".into(), "p.born".into()],
+            vec!["Person".into(), "Timestamp".into()],
+            vec![vec![Value::NodeRef(NodeId::new(3)), Value::Timestamp(5)]],
+        ));
+
+        // WHEN
+        let json = WireMessage::from(result).to_json();
+
+        // THEN
+        assert_eq!(
+            json,
+            json!({
+                "version": WIRE_VERSION,
+                "result": {
+                    "type": "query",
+                    "columns": ["p", "p.born"],
+                    "types": ["Person", "Timestamp"],
+                    "rows": [[{ "_type": "node", "_id": 3 }, { "_type": "timestamp", "value": 5 }]],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+        // GIVEN one message of each shape
+        let messages: Vec<WireMessage> = vec![
+            StatementResult::Mutation(
+                MutationSummary::created_nodes(2).with_returned(vec![Value::Duration(3)]),
+            )
+            .into(),
+            StatementResult::Mixed {
+                mutations: MutationSummary::deleted_edges(1),
+                queries: QueryResult::empty(),
+            }
+            .into(),
+            StatementResult::Transaction(TransactionResult::SavepointCreated { name: "a".into() })
+                .into(),
+            StatementResult::Transaction(TransactionResult::Committed).into(),
+            StatementResult::Empty.into(),
+            WireError::new("parse", "unexpected token").into(),
+        ];
+
+        // WHEN / THEN decoding re-encodes to the same JSON
+        for message in messages {
+            let json = message.to_json();
+            let decoded = WireMessage::from_json(json.clone()).unwrap();
+            assert_eq!(decoded.to_json(), json);
+        }
+        let savepoint = WireMessage::from(StatementResult::Transaction(
+            TransactionResult::RolledBackTo { name: "a".into() },
+        ))
+        .to_json();
+        assert_eq!(
+            savepoint["result"],
+            json!({ "type": "transaction", "status": "rolled_back_to", "savepoint": "a" })
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        // GIVEN a message from a future encoding
+        let json = json!({ "version": WIRE_VERSION + 1, "result": { "type": "empty" } });
+
+        // WHEN
+        let err = WireMessage::from_json(json).unwrap_err();
+
+        // THEN
+        assert_eq!(err.kind, "unsupported_version");
+    }
+}
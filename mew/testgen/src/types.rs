@@ -178,6 +178,12 @@ impl From<&mew_core::Value> for Value {
                 let formatted: Vec<String> = items.iter().map(|v| format!("{}", v)).collect();
                 Value::String(format!("[{}]", formatted.join(", ")))
             }
+            // Structured and binary values use their display representation
+            mew_core::Value::Map(_)
+            | mew_core::Value::Bytes(_)
+            | mew_core::Value::Hash(_)
+            | mew_core::Value::Vector(_)
+            | mew_core::Value::Point(_) => Value::String(v.to_string()),
         }
     }
 }
@@ -37,6 +37,7 @@ pub struct Assertion {
 
     // Query assertions - columns
     pub columns: Option<Vec<String>>,
+    pub types: Option<Vec<String>>,
 
     // Query assertions - rows
     pub rows: Option<usize>,
@@ -69,6 +70,7 @@ impl std::fmt::Debug for Assertion {
             .field("value", &self.value)
             .field("scalar_column", &self.scalar_column)
             .field("columns", &self.columns)
+            .field("types", &self.types)
             .field("rows", &self.rows)
             .field("empty", &self.empty)
             .field("returns", &self.returns)
@@ -298,6 +300,18 @@ impl Assertion {
             }
         }
 
+        if let Some(ref expected_types) = self.types {
+            if result.types != *expected_types {
+                return Err(ExampleError::assertion_failed(
+                    step,
+                    format!(
+                        "column type mismatch:\n  expected: {:?}\n  actual:   {:?}\n  columns:  {:?}",
+                        expected_types, result.types, result.columns
+                    ),
+                ));
+            }
+        }
+
         // Check scalar (column name + value for single-row, single-column result)
         if let Some(ref expected_column) = self.scalar_column {
             if result.rows.len() != 1 {
@@ -730,6 +744,17 @@ impl AssertionBuilder {
         self
     }
 
+    /// Assert the reported column types (in order).
+    ///
+    /// # Example
+    /// ```ignore
+    /// .step("query", |a| a.types(&["Person", "String", "Timestamp"]))
+    /// ```
+    pub fn types(mut self, names: &[&str]) -> Self {
+        self.assertion.types = Some(names.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
     // ========== Query assertions - scalar ==========
 
     /// Assert a single-row, single-column result with column name verification.
@@ -1015,12 +1040,20 @@ fn format_value(v: &Value) -> String {
         Value::Null => "null".to_string(),
         Value::Timestamp(ts) => format!("timestamp({})", ts),
         Value::Duration(d) => format!("duration({})", d),
+        Value::Bytes(_) | Value::Hash(_) | Value::Vector(_) | Value::Point(_) => v.to_string(),
         Value::NodeRef(id) => format!("node({})", id),
         Value::EdgeRef(id) => format!("edge({})", id),
         Value::List(items) => {
             let formatted: Vec<String> = items.iter().map(format_value).collect();
             format!("[{}]", formatted.join(", "))
         }
+        Value::Map(entries) => {
+            let formatted: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| format!("{}: {}", k, format_value(v)))
+                .collect();
+            format!("{{{}}}", formatted.join(", "))
+        }
     }
 }
 
@@ -321,6 +321,57 @@ mod string_functions {
     }
 }
 
+mod list_functions {
+    use super::*;
+
+    pub fn scenario() -> Scenario {
+        Scenario::new("list_functions")
+            .ontology("level-1/bookmarks/ontology.mew")
+            .operations("level-1/bookmarks/operations/list_functions.mew")
+            // Seed data
+            .step("seed_tagged_bookmarks", |a| a.created(4))
+            // Membership and equality
+            .step("query_tag_membership", |a| a.scalar("count", 2i64))
+            .step("query_list_equality", |a| {
+                a.returns(vec![row_str! { "b.title" => "Go" }])
+            })
+            .step("query_empty_default", |a| {
+                a.returns(vec![row_str! { "b.title" => "Untagged" }])
+            })
+            // List functions
+            .step("query_tag_count", |a| a.scalar("count", 3i64))
+            .step("query_first_and_last_tag", |a| {
+                a.first(row_str! {
+                    "first" => "rust",
+                    "final" => "systems",
+                    "second" => "lang"
+                })
+            })
+            .step("query_slice_and_join", |a| {
+                a.first(row_str! { "rest" => "lang, systems" })
+            })
+            .step("query_split_url", |a| {
+                a.first(row_str! { "host" => "docs.rs" })
+            })
+            // Quantifiers
+            .step("query_any_tag_prefix", |a| {
+                a.returns(vec![row_str! { "b.title" => "Rust" }])
+            })
+            .step("query_all_tags_short", |a| a.scalar("count", 2i64))
+            // Updates and errors
+            .step("set_tags", |a| a.modified(1))
+            .step("query_updated_tags", |a| {
+                a.first(row_str! { "b.title" => "Untagged", "count" => 2i64 })
+            })
+            .step("spawn_wrong_element_type", |a| a.error("type"))
+    }
+
+    #[test]
+    fn test_list_attributes_on_bookmarks() {
+        scenario().run().unwrap();
+    }
+}
+
 mod errors_comprehensive {
     use super::*;
 
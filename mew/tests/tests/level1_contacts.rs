@@ -50,7 +50,8 @@ mod edge_attributes {
             .step("spawn_base_entities", |a| a.created(3))
             .step("link_with_edge_attributes", |a| a.linked(1))
             .step("query_edge_attributes", |a| {
-                a.first(row_str! { "work.title" => "Senior Engineer", "work.department" => "Engineering", "work.is_current" => true })
+                a.types(&["String", "String", "Bool"])
+                    .first(row_str! { "work.title" => "Senior Engineer", "work.department" => "Engineering", "work.is_current" => true })
             })
             .step("link_with_partial_edge_attributes", |a| a.linked(1))
             .step("query_partial_edge", |a| {
@@ -266,6 +266,28 @@ mod timestamps {
             .step("query_date_parts_combined", |a| a.rows(1))
             .step("query_timestamp_ordering", |a| a.rows(6))
             .step("query_recent_records", |a| a.scalar("count", 4i64))
+            // Calendar functions, parsing and formatting
+            .step("query_day_of_week", |a| a.scalar("dow", 6i64))
+            .step("query_millisecond_extraction", |a| a.scalar("ms", 250i64))
+            .step("query_parse_timestamp", |a| {
+                a.first(row_str! { "t.label" => "mid_year" })
+            })
+            .step("query_parse_invalid_timestamp", |a| a.error("cannot parse"))
+            .step("query_format_timestamp", |a| {
+                a.scalar("iso", "2024-06-15T12:30:45.000Z")
+            })
+            .step("query_date_trunc", |a| a.scalar("count", 2i64))
+            .step("query_extraction_type_error", |a| a.error("Type mismatch"))
+            // Duration arithmetic and comparison
+            .step("query_duration_comparison", |a| {
+                a.returns(vec![row_str! { "t.label" => "with_end" }])
+            })
+            .step("query_duration_conversion", |a| {
+                a.first(row! { mins: 270i64, slots: 9i64 })
+            })
+            .step("query_due_within_days", |a| {
+                a.returns(vec![row_str! { "t.label" => "year_end" }])
+            })
     }
 
     #[test]
@@ -154,11 +154,11 @@ mod query_complex {
             .step("seed_library_data", |a| a.created(16).linked(15))
             .step("query_books_by_author", |a| a.scalar("count", 2i64))
             .step("query_books_by_nationality", |a| a.scalar("count", 2i64))
-            .step("query_books_with_multiple_authors", |a| a.rows(0)) // Subquery count in WHERE not working
+            .step("query_books_with_multiple_authors", |a| a.rows(1))
             .step("query_available_books", |a| a.rows(3))
             .step("query_unavailable_books", |a| a.rows(2))
             .step("query_books_by_genre", |a| a.scalar("count", 2i64))
-            .step("query_books_in_multiple_genres", |a| a.rows(0)) // Subquery count in WHERE not working
+            .step("query_books_in_multiple_genres", |a| a.rows(1))
             .step("query_borrowed_books", |a| a.rows(2))
             .step("query_members_with_loans", |a| a.rows(2))
             .step("query_copies_per_book", |a| a.rows(3))
@@ -89,10 +89,50 @@ mod aggregations {
             })
             // Products with review_count > 0: laptop, phone = 2
             .step("test_aggregation_with_having_like_filter", |a| a.rows(2))
+            // AVG price: computers=1299.99, electronics=499.99, books=29.99
+            .step("test_having_on_average", |a| {
+                a.rows(2).first(row_str! { "category" => "Computers" })
+            })
+            // p.name is neither a GROUP BY key nor aggregated
+            .step("test_group_by_ungrouped_projection", |a| {
+                a.error("neither grouped nor aggregated")
+            })
+            // Electronics has phone and headphones; only phone is reviewed
+            .step("test_with_stage_then_match", |a| {
+                a.rows(1).first(
+                    row_str! { "category" => "Electronics", "product_count" => 2i64, "reviews" => 1i64 },
+                )
+            })
+            // Laptop at 1299.99 is in Computers
+            .step("test_with_order_limit", |a| a.scalar("category", "Computers"))
             // Distinct customers with reviews: alice, bob = 2
             .step("test_count_distinct_customers_with_reviews", |a| {
                 a.scalar("customers_with_reviews", 2i64)
             })
+            // Statuses: active (4 products), discontinued (1)
+            .step("test_distinct_statuses", |a| {
+                a.first(row_str! {
+                    "status_count" => 2i64,
+                    "statuses" => "active, discontinued",
+                    "collected" => 2i64
+                })
+            })
+            // Active prices 29.99, 199.99, 799.99, 1299.99: median 499.99
+            .step("test_price_distribution", |a| {
+                a.first(row_str! { "median_price" => 500.0, "p50_price" => 199.99 })
+            })
+            // Ratings 5 and 4: sample variance 0.5
+            .step("test_rating_spread", |a| a.scalar("rating_variance", 0.5))
+            .step("test_products_per_category", |a| {
+                a.returns(vec![
+                    row_str! { "category" => "Books", "products" => "Rust Programming Guide" },
+                    row_str! { "category" => "Computers", "products" => "Laptop Pro 15" },
+                    row_str! {
+                        "category" => "Electronics",
+                        "products" => "SmartPhone X | Wireless Headphones"
+                    },
+                ])
+            })
     }
 
     #[test]
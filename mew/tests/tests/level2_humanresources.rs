@@ -12,6 +12,7 @@
 //! - walk_traversal: WALK FOLLOW/UNTIL/DEPTH with RETURN NODES/EDGES/PATH/TERMINAL
 //! - walk_standalone: WALK FROM #id without preceding MATCH (ID reference starting points)
 //! - multiple_inheritance: TeamLead : Employee, Mentorship (node inherits from 2+ types)
+//! - salary_statistics: stddev, variance, median, percentiles, string_agg and DISTINCT aggregates
 
 use mew_tests::prelude::*;
 
@@ -673,3 +674,71 @@ mod walk_standalone {
         scenario().run().unwrap();
     }
 }
+
+mod salary_statistics {
+    use super::*;
+    use mew_core::Value;
+
+    pub fn scenario() -> Scenario {
+        Scenario::new("salary_statistics")
+            .ontology("level-2/humanresources/ontology.mew")
+            .seed("level-2/humanresources/seeds/populated.mew")
+            .operations("level-2/humanresources/operations/salary_statistics.mew")
+            .step("setup_sales_staff", |a| a.created(3))
+            .step("setup_sales_membership", |a| a.linked(3))
+            // Engineering: 100k, 120k, 150k, 250k; Sales: 90k, 110k and a null
+            .step("test_salary_spread_by_department", |a| {
+                a.returns(vec![
+                    row_str! {
+                        "dept" => "Engineering",
+                        "headcount" => 4i64,
+                        "salaried" => 4i64,
+                        "salary_stddev" => 66583.0,
+                        "median_salary" => 135000.0
+                    },
+                    row_str! {
+                        "dept" => "Sales",
+                        "headcount" => 3i64,
+                        "salaried" => 2i64,
+                        "salary_stddev" => 14142.0,
+                        "median_salary" => 100000.0
+                    },
+                ])
+            })
+            // Sample variance: 13.3e9 / 3
+            .step("test_salary_variance", |a| {
+                a.scalar("salary_variance", 4433333333.0)
+            })
+            // The sample standard deviation needs at least two values
+            .step("test_stddev_of_single_value", |a| {
+                a.scalar("salary_stddev", Value::Null)
+            })
+            .step("test_salary_percentiles", |a| {
+                a.first(row_str! { "p25" => 115000.0, "p50" => 120000.0, "p90" => 250000.0 })
+            })
+            .step("test_percentile_fraction_out_of_range", |a| {
+                a.error("between 0 and 1")
+            })
+            .step("test_distinct_employment_types", |a| {
+                a.returns(vec![
+                    row_str! { "dept" => "Engineering", "types" => 1i64 },
+                    row_str! { "dept" => "Sales", "types" => 2i64 },
+                ])
+            })
+            .step("test_department_roster", |a| {
+                a.returns(vec![
+                    row_str! { "dept" => "Engineering", "roster" => "Alice, Bob, Charlie, Diana" },
+                    row_str! { "dept" => "Sales", "roster" => "Frank, Grace, Henry" },
+                ])
+            })
+            .step("test_string_agg_requires_strings", |a| {
+                a.error("Type mismatch")
+            })
+            .step("test_stddev_requires_numbers", |a| a.error("Type mismatch"))
+    }
+
+    #[test]
+    fn test_salary_statistics_operations() {
+        scenario().run().unwrap();
+    }
+}
@@ -10,6 +10,8 @@
 //! - blocking: [no_self] constraint on blocks edge, blocking chains
 //! - anonymous_targets: Use of `_` for anonymous pattern matching
 //! - link_if_not_exists: Idempotent edge creation with LINK IF NOT EXISTS
+//! - upsert: Idempotent node creation with SPAWN ... ON CONFLICT
+//! - unwind: Batch mutations over a list with UNWIND
 
 use mew_tests::prelude::*;
 
@@ -622,3 +624,129 @@ mod policy {
         scenario().run().unwrap();
     }
 }
+
+mod upsert {
+    use super::*;
+    use mew_core::Value;
+    use mew_session::StatementResult;
+
+    pub fn scenario() -> Scenario {
+        Scenario::new("upsert")
+            .ontology("level-2/tasks/ontology.mew")
+            .operations("level-2/tasks/operations/upsert.mew")
+            // DO NOTHING: creates once, then finds the existing node
+            .step("test_upsert_creates_when_absent", |a| a.created(1))
+            .step("test_upsert_do_nothing_existing", |a| {
+                a.created(0).modified(0)
+            })
+            .step("test_verify_do_nothing_kept_color", |a| {
+                a.first(row_str! { "tag_count" => 1, "color" => "#111111" })
+            })
+            // DO UPDATE: updates the existing node in place
+            .step("test_setup_person", |a| a.created(1))
+            .step("test_upsert_do_update_existing", |a| {
+                a.created(0).modified(1)
+            })
+            .step("test_verify_do_update_renamed", |a| {
+                a.first(row_str! { "person_count" => 1, "name" => "Dana Scully" })
+            })
+            // DO UPDATE without a conflict inserts the spawned values
+            .step("test_upsert_do_update_new_email", |a| {
+                a.created(1).modified(0)
+            })
+            .step("test_verify_insert_kept_spawned_name", |a| {
+                a.scalar("name", "Fox")
+            })
+            // RETURNING yields the existing node's id
+            .step("test_upsert_returning_existing_id", |a| {
+                a.created(0).assert_fn(|result| match result {
+                    StatementResult::Mutation(m) => {
+                        matches!(m.returned.as_slice(), [Value::NodeRef(_)])
+                    }
+                    _ => false,
+                })
+            })
+            // Errors
+            .step("test_upsert_non_unique_target", |a| a.error("not unique"))
+            .step("test_upsert_unset_target", |a| a.error("does not set"))
+    }
+
+    #[test]
+    fn test_upsert_operations() {
+        scenario().run().unwrap();
+    }
+}
+
+mod unwind {
+    use super::*;
+    use mew_core::Value;
+    use mew_session::StatementResult;
+
+    pub fn scenario() -> Scenario {
+        Scenario::new("unwind")
+            .ontology("level-2/tasks/ontology.mew")
+            .operations("level-2/tasks/operations/unwind.mew")
+            // One SPAWN per element
+            .step("test_unwind_spawn_rows", |a| a.created(3))
+            .step("test_verify_unwind_spawn_rows", |a| {
+                a.returns(vec![
+                    row_str! { "t.title" => "Batch A", "t.priority" => 1 },
+                    row_str! { "t.title" => "Batch B", "t.priority" => 2 },
+                    row_str! { "t.title" => "Batch C", "t.priority" => 5 },
+                ])
+                .ordered()
+            })
+            .step("test_unwind_empty_list", |a| a.created(0))
+            // SPAWN and LINK in each iteration
+            .step("test_setup_unwind_tag", |a| a.created(1))
+            .step("test_unwind_spawn_and_link", |a| a.created(2).linked(2))
+            .step("test_verify_unwind_links", |a| {
+                a.scalar("tagged_count", 2i64)
+            })
+            // Upsert then SET, with the row's values
+            .step("test_unwind_upsert_and_set", |a| a.created(1).modified(2))
+            .step("test_verify_unwind_set", |a| {
+                a.returns(vec![
+                    row_str! { "g.name" => "batch", "g.color" => "#000000" },
+                    row_str! { "g.name" => "fresh", "g.color" => "#FFFFFF" },
+                ])
+                .ordered()
+            })
+            // RETURNING yields one value per element
+            .step("test_unwind_returning", |a| {
+                a.created(2).assert_fn(|result| match result {
+                    StatementResult::Mutation(m) => {
+                        let titles: Vec<_> = m
+                            .returned
+                            .iter()
+                            .filter_map(|v| match v {
+                                Value::Map(map) => map.get("title").cloned(),
+                                _ => None,
+                            })
+                            .collect();
+                        titles
+                            == vec![
+                                Value::String("Returned 1".into()),
+                                Value::String("Returned 2".into()),
+                            ]
+                    }
+                    _ => false,
+                })
+            })
+            // A failing element undoes the whole batch
+            .step("test_unwind_failing_row_rolls_back", |a| {
+                a.error("priority")
+            })
+            .step("test_verify_nothing_committed", |a| {
+                a.scalar("atomic_count", 0i64)
+            })
+            .step("test_unwind_requires_list", |a| {
+                a.error("UNWIND requires a list")
+            })
+    }
+
+    #[test]
+    fn test_unwind_operations() {
+        scenario().run().unwrap();
+    }
+}
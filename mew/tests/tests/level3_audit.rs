@@ -83,24 +83,33 @@ mod transactions {
             // ROLLBACK works - spawned items are rolled back (0 created)
             .step("test_rollback_transaction", |a| a.created(0))
             .step("test_verify_rollback", |a| a.value(0))
-            // SAVEPOINT/ROLLBACK TO not implemented
-            .step("test_savepoint_basic", |a| a.error("parse"))
-            .step("test_verify_savepoint", |a| a.rows_gte(0))
-            // Multiple savepoints not implemented
-            .step("test_multiple_savepoints", |a| a.error("parse"))
-            .step("test_verify_multiple_savepoints", |a| a.rows_gte(0))
-            // Nested savepoints not implemented
-            .step("test_nested_savepoints", |a| a.error("parse"))
-            .step("test_verify_nested", |a| a.rows_gte(0))
+            // ROLLBACK TO undoes the SET made after the savepoint
+            .step("test_savepoint_basic", |a| a.created(1).linked(1))
+            .step("test_verify_savepoint", |a| a.value("before_savepoint"))
+            // Rolling back to the middle savepoint keeps its value
+            .step("test_multiple_savepoints", |a| a.created(1).linked(1))
+            .step("test_verify_multiple_savepoints", |a| a.value("v2"))
+            // Inner rollback keeps writes made between the savepoints
+            .step("test_nested_savepoints", |a| a.created(2).linked(2))
+            .step("test_verify_nested", |a| {
+                a.returns(vec![
+                    row_str! { "i.key" => "tx.item1", "i.value" => "value1" },
+                    row_str! { "i.key" => "tx.item3", "i.value" => "before_savepoint" },
+                    row_str! { "i.key" => "tx.item4", "i.value" => "v2" },
+                    row_str! { "i.key" => "tx.item5", "i.value" => "outer" },
+                    row_str! { "i.key" => "tx.item6", "i.value" => "inner" },
+                ])
+                .ordered()
+            })
             // Isolation levels work
             .step("test_read_committed", |a| a.created(1).linked(1))
             .step("test_serializable", |a| a.created(1).linked(1))
             // Deferred constraints
             .step("test_deferred_cardinality", |a| a.created(1))
-            // Rules in transaction uses SAVEPOINT which is not implemented
-            .step("test_rules_in_transaction", |a| a.error("parse"))
-            // Cleanup (1 ConfigSet + 4 ConfigItems from working transactions)
-            .step("test_cleanup", |a| a.deleted(5))
+            // Rules fire inside the transaction; SAVEPOINT is just a marker
+            .step("test_rules_in_transaction", |a| a.created(1).linked(1))
+            // Cleanup (1 ConfigSet + 9 ConfigItems from committed transactions)
+            .step("test_cleanup", |a| a.deleted(10))
     }
 
     #[test]
@@ -63,15 +63,15 @@ mod constraint_violations {
             // Temporal violation tests
             .step("test_setup_past_event", |a| a.created(1))
             .step("test_setup_future_event", |a| a.created(1))
-            // Constraint enforcement not yet implemented - LINK succeeds
-            .step("test_temporal_violation", |a| a.linked(1))
+            .step("test_temporal_violation", |a| a.error("temporal_order"))
             .step("test_temporal_same_time_valid", |a| a.created(2).linked(1))
             // Causal loop violations
             // Self-loop constraint is enforced
             .step("test_direct_self_loop", |a| a.error("self"))
             .step("test_setup_loop_chain", |a| a.created(3).linked(2))
-            // Transitive loop constraint not yet enforced
-            .step("test_transitive_loop_violation", |a| a.linked(1))
+            .step("test_transitive_loop_violation", |a| {
+                a.error("no_causal_loop")
+            })
             // Valid operations
             .step("test_valid_chain_no_violation", |a| a.created(3).linked(2))
             .step("test_verify_chain_exists", |a| a.error("parse"))
@@ -103,3 +103,113 @@ mod acyclic_violation {
         scenario().run().unwrap();
     }
 }
+
+mod subqueries {
+    use super::*;
+    use mew_core::Value;
+    use mew_session::StatementResult;
+
+    pub fn scenario() -> Scenario {
+        Scenario::new("subqueries")
+            .ontology("level-3/projectmanagement/ontology.mew")
+            .operations("level-3/projectmanagement/operations/subqueries.mew")
+            // Setup
+            .step("test_setup_projects", |a| a.created(2))
+            .step("test_setup_tasks", |a| a.created(5).linked(7))
+            .step("test_setup_member", |a| a.created(1).linked(1))
+            // MVP has 3 tasks that are not done, Side has 1
+            .step("test_count_subquery_in_return", |a| {
+                a.rows(2)
+                    .first(row_str! { "project" => "MVP", "open_tasks" => 3i64 })
+            })
+            .step("test_count_subquery_in_where", |a| {
+                a.scalar("project", "MVP")
+            })
+            // Test (6) and Docs (3) depend on Build
+            .step("test_collect_subquery", |a| {
+                a.assert_fn(|result| match result {
+                    StatementResult::Query(q) => match q.rows.as_slice() {
+                        [row] => {
+                            let mut priorities = match &row[0] {
+                                Value::List(items) => items.clone(),
+                                _ => return false,
+                            };
+                            priorities.sort_by_key(|v| format!("{v:?}"));
+                            priorities == vec![Value::Int(3), Value::Int(6)]
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                })
+            })
+            .step("test_scalar_subquery", |a| a.scalar("assignee", "Alice"))
+            .step("test_scalar_subquery_no_match", |a| {
+                a.first(row_str! { "assignee" => None::<String> })
+            })
+            .step("test_scalar_subquery_many_rows", |a| {
+                a.error("Scalar subquery returned 4 rows")
+            })
+            // Two dependents: 2 + 5
+            .step("test_set_from_subquery", |a| a.modified(1))
+            .step("test_verify_set_from_subquery", |a| {
+                a.scalar("priority", 7i64)
+            })
+    }
+
+    #[test]
+    fn test_pattern_subqueries() {
+        scenario().run().unwrap();
+    }
+}
+
+mod unions {
+    use super::*;
+
+    pub fn scenario() -> Scenario {
+        Scenario::new("unions")
+            .ontology("level-3/projectmanagement/ontology.mew")
+            .operations("level-3/projectmanagement/operations/unions.mew")
+            // Setup
+            .step("test_setup_projects", |a| a.created(2))
+            .step("test_setup_tasks", |a| a.created(4).linked(4))
+            .step("test_setup_member", |a| a.created(1).linked(2))
+            // Alice has Build and Prototype; MVP has Design, Build and Test
+            .step("test_union_all", |a| a.columns(&["title"]).rows(5))
+            .step("test_union_distinct", |a| {
+                a.returns(vec![
+                    row_str! { "title" => "Design" },
+                    row_str! { "title" => "Build" },
+                    row_str! { "title" => "Test" },
+                    row_str! { "title" => "Prototype" },
+                ])
+            })
+            // Build is in both branches but listed once
+            .step("test_union_order_limit", |a| {
+                a.returns(vec![
+                    row_str! { "t.title" => "Build", "t.priority" => 9i64 },
+                    row_str! { "t.title" => "Design", "t.priority" => 8i64 },
+                    row_str! { "t.title" => "Test", "t.priority" => 6i64 },
+                ])
+                .ordered()
+            })
+            .step("test_union_of_types", |a| {
+                a.columns(&["name", "entity"])
+                    .types(&["String", "NodeRef"])
+                    .rows(3)
+            })
+            .step("test_union_column_count_mismatch", |a| {
+                a.error("different numbers of columns")
+            })
+            .step("test_union_type_mismatch", |a| {
+                a.error("incompatible types String and Int")
+            })
+            .step("test_union_order_by_other_attribute", |a| {
+                a.error("only use its result columns")
+            })
+    }
+
+    #[test]
+    fn test_union_of_match_queries() {
+        scenario().run().unwrap();
+    }
+}
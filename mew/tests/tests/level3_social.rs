@@ -118,30 +118,33 @@ mod transitive {
             .step("test_setup_friendships", |a| a.linked(3))
             // Direct followers (standard query)
             .step("test_direct_followers_of_alice", |a| a.rows(1))
-            // Transitive follows+ - patterns parse but may not traverse correctly
-            .step("test_transitive_followers_plus", |a| a.rows_gte(0))
-            .step("test_transitive_who_alice_reaches", |a| a.rows_gte(0))
+            // Transitive follows+
+            .step("test_transitive_followers_plus", |a| a.rows(5))
+            .step("test_transitive_who_alice_reaches", |a| a.rows(0))
             // Transitive follows*
-            .step("test_transitive_star_includes_self", |a| a.rows_gte(0))
-            .step("test_transitive_star_all", |a| a.rows_gte(0))
-            // Depth limits - modifiers on transitive may not parse
-            .step("test_transitive_depth_2", |a| a.error("parse"))
-            .step("test_transitive_depth_range", |a| a.error("parse"))
-            .step("test_transitive_depth_exact", |a| a.error("parse"))
-            // Symmetric transitive (friend_of)
-            .step("test_friends_of_friends", |a| a.rows_gte(0))
-            .step("test_friends_within_2_hops", |a| a.error("parse"))
-            .step("test_connection_path_exists", |a| a.rows_gte(0))
+            .step("test_transitive_star_includes_self", |a| a.rows(1))
+            .step("test_transitive_star_all", |a| a.rows(6))
+            // Depth limits
+            .step("test_transitive_depth_2", |a| a.rows(2))
+            .step("test_transitive_depth_range", |a| a.rows(3))
+            .step("test_transitive_depth_exact", |a| a.rows(3))
+            // Symmetric transitive (friend_of): alice reaches herself through bob
+            .step("test_friends_of_friends", |a| a.rows(4))
+            .step("test_friends_within_2_hops", |a| a.rows(3))
+            .step("test_connection_path_exists", |a| a.rows(1))
             // Cycle detection
             .step("test_create_follow_cycle", |a| a.linked(1))
-            .step("test_detect_cycle_in_follows", |a| a.rows_gte(0))
+            .step("test_detect_cycle_in_follows", |a| a.rows(6))
             .step("test_remove_cycle", |a| a.unlinked(1))
             // Combined patterns
-            .step("test_find_verified_in_chain", |a| a.rows_gte(0))
+            .step("test_find_verified_in_chain", |a| a.rows(5))
+            // Attribute conditions are not pattern elements
             .step("test_friend_chains_to_verified", |a| a.error("parse"))
             // Aggregation
-            .step("test_count_reachable_users", |a| a.rows_gte(0))
-            .step("test_count_by_depth", |a| a.error("parse"))
+            .step("test_count_reachable_users", |a| {
+                a.scalar("reachable_count", 5i64)
+            })
+            .step("test_count_by_depth", |a| a.scalar("within_3_hops", 3i64))
             // Cleanup
             .step("test_cleanup", |a| a.deleted(6))
     }
@@ -29,6 +29,10 @@ pub enum TransactionError {
     #[error("constraint error: {0}")]
     ConstraintError(#[from] mew_constraint::ConstraintError),
 
+    /// Graph error while applying or undoing a write.
+    #[error("graph error: {0}")]
+    GraphError(#[from] mew_core::GraphError),
+
     /// Savepoint not found.
     #[error("savepoint not found: {name}")]
     SavepointNotFound { name: String },
@@ -36,6 +40,10 @@ pub enum TransactionError {
     /// Transaction rolled back.
     #[error("transaction rolled back")]
     RolledBack,
+
+    /// A concurrent transaction committed a conflicting write first.
+    #[error("Transaction conflicts with concurrent transaction: {message}")]
+    SerializationConflict { message: String },
 }
 
 impl TransactionError {
@@ -48,6 +56,12 @@ impl TransactionError {
     pub fn savepoint_not_found(name: impl Into<String>) -> Self {
         Self::SavepointNotFound { name: name.into() }
     }
+
+    pub fn serialization_conflict(message: impl Into<String>) -> Self {
+        Self::SerializationConflict {
+            message: message.into(),
+        }
+    }
 }
 
 /// Result type for transaction operations.
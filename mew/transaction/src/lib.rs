@@ -12,7 +12,9 @@
 mod buffer;
 mod error;
 mod manager;
+mod undo;
 
 pub use buffer::{PendingEdge, PendingNode, PendingUpdate, TransactionBuffer};
 pub use error::{TransactionError, TransactionResult};
 pub use manager::{TransactionManager, TransactionState};
+pub use undo::UndoLog;
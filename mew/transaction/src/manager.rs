@@ -5,10 +5,10 @@ use mew_core::{Attributes, EdgeId, EdgeTypeId, EntityId, NodeId, TypeId, Value};
 use mew_graph::Graph;
 use mew_registry::Registry;
 use mew_rule::RuleEngine;
-use std::collections::HashMap;
 
 use crate::buffer::TransactionBuffer;
 use crate::error::{TransactionError, TransactionResult};
+use crate::undo::UndoLog;
 
 /// Transaction state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,23 +23,20 @@ pub enum TransactionState {
     RollingBack,
 }
 
-/// A savepoint within a transaction.
-#[derive(Debug, Clone)]
-struct Savepoint {
-    buffer: TransactionBuffer,
-}
-
 /// Transaction manager that orchestrates ACID transactions.
 ///
-/// This implementation applies changes directly to the graph and tracks
-/// them in a buffer for potential rollback. This is appropriate for
-/// Read Committed isolation level (single-writer model).
+/// This implementation applies changes directly to the graph. The graph's
+/// undo log restores them on rollback, and a buffer tracks them for
+/// deferred constraint checks. This is appropriate for Read Committed
+/// isolation level (single-writer model).
 pub struct TransactionManager<'r, 'g> {
     registry: &'r Registry,
     graph: &'g mut Graph,
     state: TransactionState,
     buffer: TransactionBuffer,
-    savepoints: HashMap<String, Savepoint>,
+    undo: UndoLog,
+    /// Buffer snapshots taken at each savepoint.
+    savepoints: Vec<(String, TransactionBuffer)>,
     auto_commit: bool,
 }
 
@@ -51,7 +48,8 @@ impl<'r, 'g> TransactionManager<'r, 'g> {
             graph,
             state: TransactionState::Inactive,
             buffer: TransactionBuffer::new(),
-            savepoints: HashMap::new(),
+            undo: UndoLog::default(),
+            savepoints: Vec::new(),
             auto_commit: false,
         }
     }
@@ -85,6 +83,7 @@ impl<'r, 'g> TransactionManager<'r, 'g> {
         }
 
         self.buffer = TransactionBuffer::new();
+        self.undo = UndoLog::begin(self.graph);
         self.savepoints.clear();
         self.state = TransactionState::Active;
 
@@ -109,6 +108,7 @@ impl<'r, 'g> TransactionManager<'r, 'g> {
         }
 
         // 2. Clear transaction state (changes already applied to graph)
+        std::mem::take(&mut self.undo).commit(self.graph);
         self.buffer.clear();
         self.savepoints.clear();
         self.state = TransactionState::Inactive;
@@ -131,32 +131,14 @@ impl<'r, 'g> TransactionManager<'r, 'g> {
 
     /// Actually perform the rollback.
     fn do_rollback(&mut self) -> TransactionResult<()> {
-        // Undo in reverse order: updates, edge creations, node creations
-        // Delete created edges first
-        for pending in self.buffer.created_edges() {
-            let _ = self.graph.delete_edge(pending.id);
-        }
-
-        // Delete created nodes
-        for pending in self.buffer.created_nodes() {
-            let _ = self.graph.delete_node(pending.id);
-        }
-
-        // Restore deleted edges (would need to store original data - simplified for now)
-        // Restore deleted nodes (would need to store original data - simplified for now)
-        // Restore updated attributes
-        for update in self.buffer.updates() {
-            if let Some(old_value) = &update.old_value {
-                let _ =
-                    self.graph
-                        .set_node_attr(update.node_id, &update.attr_name, old_value.clone());
-            }
-        }
+        // Replay the undo log backwards: creations are removed, deleted and
+        // updated entities get their original state back
+        let result = std::mem::take(&mut self.undo).rollback(self.graph);
 
         self.buffer.clear();
         self.savepoints.clear();
 
-        Ok(())
+        result
     }
 
     // ========== Savepoints ==========
@@ -167,12 +149,10 @@ impl<'r, 'g> TransactionManager<'r, 'g> {
             return Err(TransactionError::NoActiveTransaction);
         }
 
-        self.savepoints.insert(
-            name.to_string(),
-            Savepoint {
-                buffer: self.buffer.savepoint(),
-            },
-        );
+        self.undo.savepoint(self.graph, name);
+        self.savepoints.retain(|(n, _)| n != name);
+        self.savepoints
+            .push((name.to_string(), self.buffer.savepoint()));
 
         Ok(())
     }
@@ -183,37 +163,16 @@ impl<'r, 'g> TransactionManager<'r, 'g> {
             return Err(TransactionError::NoActiveTransaction);
         }
 
-        let savepoint = self
-            .savepoints
-            .get(name)
-            .ok_or_else(|| TransactionError::savepoint_not_found(name))?
-            .clone();
-
         // Undo changes since savepoint
-        // Delete nodes created after savepoint
-        let current_nodes: Vec<_> = self.buffer.created_nodes().map(|p| p.id).collect();
-        let savepoint_nodes: std::collections::HashSet<_> =
-            savepoint.buffer.created_nodes().map(|p| p.id).collect();
-
-        for node_id in current_nodes {
-            if !savepoint_nodes.contains(&node_id) {
-                let _ = self.graph.delete_node(node_id);
-            }
-        }
+        self.undo.rollback_to(self.graph, name)?;
 
-        // Delete edges created after savepoint
-        let current_edges: Vec<_> = self.buffer.created_edges().map(|p| p.id).collect();
-        let savepoint_edges: std::collections::HashSet<_> =
-            savepoint.buffer.created_edges().map(|p| p.id).collect();
-
-        for edge_id in current_edges {
-            if !savepoint_edges.contains(&edge_id) {
-                let _ = self.graph.delete_edge(edge_id);
-            }
-        }
-
-        self.buffer.restore(savepoint.buffer);
-        self.savepoints.remove(name);
+        let index = self
+            .savepoints
+            .iter()
+            .rposition(|(n, _)| n == name)
+            .ok_or_else(|| TransactionError::savepoint_not_found(name))?;
+        self.savepoints.truncate(index + 1);
+        self.buffer.restore(self.savepoints[index].1.clone());
 
         Ok(())
     }
@@ -224,8 +183,9 @@ impl<'r, 'g> TransactionManager<'r, 'g> {
             return Err(TransactionError::NoActiveTransaction);
         }
 
-        if self.savepoints.remove(name).is_none() {
-            return Err(TransactionError::savepoint_not_found(name));
+        self.undo.release(name)?;
+        if let Some(index) = self.savepoints.iter().rposition(|(n, _)| n == name) {
+            self.savepoints.truncate(index);
         }
 
         Ok(())
@@ -594,6 +554,30 @@ mod tests {
         assert!(graph.get_node(node_id).is_none());
     }
 
+    #[test]
+    fn test_rollback_restores_deletes_and_updates() {
+        // GIVEN - a committed node
+        let registry = test_registry();
+        let type_id = registry.get_type_id("Task").unwrap();
+        let mut graph = Graph::new();
+        let node_id = graph.create_node(type_id, attrs! { "title" => "Original" });
+        let mut manager = TransactionManager::new(&registry, &mut graph);
+        manager.begin().unwrap();
+        manager
+            .update_attr(node_id, "title", Value::String("Changed".to_string()))
+            .unwrap();
+        manager.delete_node(node_id).unwrap();
+
+        // WHEN
+        manager.rollback().unwrap();
+
+        // THEN - the node is back with its original ID and title
+        assert_eq!(
+            graph.get_node(node_id).unwrap().get_attr("title"),
+            Some(&Value::String("Original".to_string()))
+        );
+    }
+
     #[test]
     fn test_create_node_in_transaction() {
         // GIVEN
@@ -0,0 +1,158 @@
+//! Undo log for graph transactions.
+//!
+//! The graph journals the before-image of every write while its undo
+//! journal is on. An [`UndoLog`] owns that journal for the duration of a
+//! transaction and maps savepoints to positions in it, so rolling back is a
+//! matter of replaying the journal backwards. Because cascaded deletions are
+//! journaled entity by entity, undoing a KILL brings back the node with its
+//! original ID and attributes together with every edge the cascade removed.
+
+use mew_graph::Graph;
+
+use crate::error::{TransactionError, TransactionResult};
+
+/// Savepoints of one transaction over the graph's undo journal.
+#[derive(Debug, Clone, Default)]
+pub struct UndoLog {
+    /// Savepoints in creation order, with their journal marks.
+    savepoints: Vec<(String, usize)>,
+}
+
+impl UndoLog {
+    /// Start a transaction: turn on the graph's journal, empty.
+    pub fn begin(graph: &mut Graph) -> Self {
+        graph.start_undo_log();
+        graph.truncate_undo(0);
+        Self::default()
+    }
+
+    /// Current journal position, for statement-level rollback.
+    pub fn mark(&self, graph: &Graph) -> usize {
+        graph.undo_mark()
+    }
+
+    /// Undo every write after `mark`.
+    pub fn undo_to(&mut self, graph: &mut Graph, mark: usize) -> TransactionResult<()> {
+        self.savepoints.retain(|(_, m)| *m <= mark);
+        graph.undo_to(mark)?;
+        Ok(())
+    }
+
+    /// Check whether a savepoint exists.
+    pub fn has_savepoint(&self, name: &str) -> bool {
+        self.savepoints.iter().any(|(n, _)| n == name)
+    }
+
+    /// Create a savepoint. A savepoint with the same name is replaced.
+    pub fn savepoint(&mut self, graph: &Graph, name: &str) {
+        self.savepoints.retain(|(n, _)| n != name);
+        self.savepoints.push((name.to_string(), graph.undo_mark()));
+    }
+
+    /// Undo every write made after a savepoint.
+    ///
+    /// The savepoint itself stays; savepoints created after it are dropped.
+    pub fn rollback_to(&mut self, graph: &mut Graph, name: &str) -> TransactionResult<()> {
+        let index = self.position(name)?;
+        let mark = self.savepoints[index].1;
+        self.savepoints.truncate(index + 1);
+        graph.undo_to(mark)?;
+        Ok(())
+    }
+
+    /// Forget a savepoint and every savepoint created after it.
+    pub fn release(&mut self, name: &str) -> TransactionResult<()> {
+        let index = self.position(name)?;
+        self.savepoints.truncate(index);
+        Ok(())
+    }
+
+    /// Undo the whole transaction and turn the journal off.
+    pub fn rollback(self, graph: &mut Graph) -> TransactionResult<()> {
+        let result = graph.undo_to(0);
+        graph.stop_undo_log();
+        result.map_err(TransactionError::from)
+    }
+
+    /// Keep the transaction's writes and turn the journal off.
+    pub fn commit(self, graph: &mut Graph) {
+        graph.stop_undo_log();
+    }
+
+    fn position(&self, name: &str) -> TransactionResult<usize> {
+        self.savepoints
+            .iter()
+            .rposition(|(n, _)| n == name)
+            .ok_or_else(|| TransactionError::savepoint_not_found(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mew_core::{attrs, EdgeTypeId, EntityId, TypeId, Value};
+
+    #[test]
+    fn test_rollback_restores_cascaded_kill() {
+        // GIVEN a committed node with an incident edge
+        let mut graph = Graph::new();
+        let a = graph.create_node(TypeId(1), attrs! { "name" => "a" });
+        let b = graph.create_node(TypeId(1), attrs! { "name" => "b" });
+        let edge = graph
+            .create_edge(
+                EdgeTypeId(1),
+                vec![EntityId::Node(a), EntityId::Node(b)],
+                attrs! {},
+            )
+            .unwrap();
+
+        // WHEN a transaction kills, updates and spawns, then rolls back
+        let log = UndoLog::begin(&mut graph);
+        graph.delete_node(a).unwrap();
+        graph
+            .set_node_attr(b, "name", Value::from("changed"))
+            .unwrap();
+        let c = graph.create_node(TypeId(1), attrs! {});
+        log.rollback(&mut graph).unwrap();
+
+        // THEN the graph is back to where it started, with original IDs
+        assert_eq!(
+            graph.get_node(a).unwrap().get_attr("name"),
+            Some(&Value::from("a"))
+        );
+        assert_eq!(
+            graph.get_node(b).unwrap().get_attr("name"),
+            Some(&Value::from("b"))
+        );
+        assert!(graph.get_node(c).is_none());
+        assert_eq!(graph.get_edge(edge).unwrap().targets.len(), 2);
+        assert_eq!(graph.edges_from(a, None).count(), 1);
+    }
+
+    #[test]
+    fn test_rollback_to_savepoint_keeps_earlier_writes() {
+        // GIVEN a transaction with a savepoint between two spawns
+        let mut graph = Graph::new();
+        let mut log = UndoLog::begin(&mut graph);
+        let first = graph.create_node(TypeId(1), attrs! {});
+        log.savepoint(&graph, "sp");
+        let second = graph.create_node(TypeId(1), attrs! {});
+        log.savepoint(&graph, "later");
+
+        // WHEN rolling back to the savepoint
+        log.rollback_to(&mut graph, "sp").unwrap();
+
+        // THEN only the later write is undone, and later savepoints are gone
+        assert!(graph.get_node(first).is_some());
+        assert!(graph.get_node(second).is_none());
+        assert!(log.has_savepoint("sp"));
+        assert!(!log.has_savepoint("later"));
+
+        // AND released savepoints can no longer be rolled back to
+        log.release("sp").unwrap();
+        assert!(matches!(
+            log.rollback_to(&mut graph, "sp"),
+            Err(TransactionError::SavepointNotFound { .. })
+        ));
+    }
+}